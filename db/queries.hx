@@ -24,6 +24,11 @@ QUERY create_business(
     })
     RETURN business
 
+// Look up a business entity by ID
+QUERY get_business(business_id: String) =>
+    business <- N<Business>::WHERE(_::{business_id}::EQ(business_id))
+    RETURN business
+
 // Create a new customer entity
 QUERY create_customer(
     customer_id: String,
@@ -56,6 +61,8 @@ QUERY add_business_product_memory(
     price: F64,
     currency: String,
     availability: String,
+    stock_quantity: I64,
+    reorder_threshold: I64,
     description: String,
     features: [String],
     specifications: String,
@@ -77,6 +84,8 @@ QUERY add_business_product_memory(
         price: price,
         currency: currency,
         availability: availability,
+        stock_quantity: stock_quantity,
+        reorder_threshold: reorder_threshold,
         description: description,
         features: features,
         specifications: specifications,
@@ -119,8 +128,9 @@ QUERY search_business_products(query_embedding: [F64], limit: I64) =>
 // Semantic search: HelixDB generates embedding from text
 QUERY search_business_products_semantic(query_text: String, k: I64) =>
     results <- SearchV<BusinessProductEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasProductEmbedding>
     products <- results::In<HasProductEmbedding>
-    RETURN products
+    RETURN results, links, products
 
 // Hybrid search: vector + structured filters
 QUERY search_business_products_hybrid(
@@ -131,6 +141,7 @@ QUERY search_business_products_hybrid(
     max_price: F64
 ) =>
     embeddings <- SearchV<BusinessProductEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasProductEmbedding>
     products <- embeddings::In<HasProductEmbedding>
     filtered <- products::WHERE(
         AND(
@@ -139,7 +150,7 @@ QUERY search_business_products_hybrid(
             _::{price}::LTE(max_price)
         )
     )
-    RETURN filtered
+    RETURN embeddings, links, products, filtered
 
 // Get products by business ID (structured query)
 QUERY get_business_products(business_id: String) =>
@@ -465,6 +476,8 @@ QUERY add_business_event_memory(
     location: String,
     capacity: I32,
     registration_required: Boolean,
+    registered_count: I32,
+    status: String,
     tags: [String],
     created_at: I64,
     updated_at: I64,
@@ -483,6 +496,8 @@ QUERY add_business_event_memory(
         location: location,
         capacity: capacity,
         registration_required: registration_required,
+        registered_count: registered_count,
+        status: status,
         tags: tags,
         created_at: created_at,
         updated_at: updated_at,
@@ -577,6 +592,8 @@ QUERY add_customer_preference_memory(
     last_evidence: I64,
     confidence_score: F64,
     source_channels: [String],
+    importance: I32,
+    pinned: Boolean,
     created_at: I64,
     updated_at: I64,
     text_description: String,
@@ -595,6 +612,8 @@ QUERY add_customer_preference_memory(
         last_evidence: last_evidence,
         confidence_score: confidence_score,
         source_channels: source_channels,
+        importance: importance,
+        pinned: pinned,
         created_at: created_at,
         updated_at: updated_at,
         text_description: text_description
@@ -636,9 +655,10 @@ QUERY search_customer_preferences_hybrid(
     customer_id: String
 ) =>
     embeddings <- SearchV<CustomerPreferenceEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasPreferenceEmbedding>
     preferences <- embeddings::In<HasPreferenceEmbedding>
     filtered <- preferences::WHERE(_::{customer_id}::EQ(customer_id))
-    RETURN filtered
+    RETURN embeddings, links, preferences, filtered
 
 // Get customer preferences by ID
 QUERY get_customer_preferences(customer_id: String) =>
@@ -656,6 +676,8 @@ QUERY add_customer_behavior_memory(
     channel: String,
     duration_seconds: I32,
     metadata: String,
+    importance: I32,
+    pinned: Boolean,
     created_at: I64,
     updated_at: I64,
     text_description: String,
@@ -672,6 +694,8 @@ QUERY add_customer_behavior_memory(
         channel: channel,
         duration_seconds: duration_seconds,
         metadata: metadata,
+        importance: importance,
+        pinned: pinned,
         created_at: created_at,
         updated_at: updated_at,
         text_description: text_description
@@ -711,6 +735,8 @@ QUERY add_customer_desire_memory(
     timeframe: String,
     budget_range: String,
     is_active: Boolean,
+    importance: I32,
+    pinned: Boolean,
     created_at: I64,
     updated_at: I64,
     text_description: String,
@@ -727,6 +753,8 @@ QUERY add_customer_desire_memory(
         timeframe: timeframe,
         budget_range: budget_range,
         is_active: is_active,
+        importance: importance,
+        pinned: pinned,
         created_at: created_at,
         updated_at: updated_at,
         text_description: text_description
@@ -765,6 +793,8 @@ QUERY add_customer_rule_memory(
     enforcement: String,
     exceptions: [String],
     is_active: Boolean,
+    importance: I32,
+    pinned: Boolean,
     created_at: I64,
     updated_at: I64,
     text_description: String,
@@ -780,6 +810,8 @@ QUERY add_customer_rule_memory(
         enforcement: enforcement,
         exceptions: exceptions,
         is_active: is_active,
+        importance: importance,
+        pinned: pinned,
         created_at: created_at,
         updated_at: updated_at,
         text_description: text_description
@@ -818,6 +850,8 @@ QUERY add_customer_feedback_memory(
     channel: String,
     response_required: Boolean,
     resolved: Boolean,
+    importance: I32,
+    pinned: Boolean,
     created_at: I64,
     updated_at: I64,
     text_description: String,
@@ -834,6 +868,8 @@ QUERY add_customer_feedback_memory(
         channel: channel,
         response_required: response_required,
         resolved: resolved,
+        importance: importance,
+        pinned: pinned,
         created_at: created_at,
         updated_at: updated_at,
         text_description: text_description
@@ -1002,6 +1038,17 @@ QUERY get_customer_feedback(customer_id: String) =>
     feedback <- N<CustomerFeedbackMemory>::WHERE(_::{customer_id}::EQ(customer_id))
     RETURN feedback
 
+// Get a customer profile with the businesses it is linked to
+QUERY get_customer_profile(customer_id: String) =>
+    customer <- N<Customer>::WHERE(_::{customer_id}::EQ(customer_id))
+    businesses <- customer::Out<CustomerOf>
+    RETURN customer, businesses
+
+// Get customer communications (includes stored conversation chunks)
+QUERY get_customer_communications(customer_id: String) =>
+    communications <- N<CustomerBusinessCommunication>::WHERE(_::{customer_id}::EQ(customer_id))
+    RETURN communications
+
 // Get business services
 QUERY get_business_services(business_id: String) =>
     services <- N<BusinessServiceMemory>::WHERE(_::{business_id}::EQ(business_id))
@@ -1012,6 +1059,11 @@ QUERY get_business_locations(business_id: String) =>
     locations <- N<BusinessLocationMemory>::WHERE(_::{business_id}::EQ(business_id))
     RETURN locations
 
+// Get all business locations (proximity search across businesses)
+QUERY get_all_business_locations() =>
+    locations <- N<BusinessLocationMemory>
+    RETURN locations
+
 // Get business hours
 QUERY get_business_hours(business_id: String) =>
     hours <- N<BusinessHoursMemory>::WHERE(_::{business_id}::EQ(business_id))
@@ -1447,6 +1499,52 @@ QUERY delete_all_business_information(business_id: String) =>
     DROP N<BusinessInformationMemory>({business_id: business_id})
     RETURN "Deleted all information for business"
 
+// ============================================================================
+// BUSINESS MEMORY DELETES BY NODE ID
+// ============================================================================
+// restore_snapshot writes the snapshot copy of a changed memory first, then
+// drops the old node by its node ID (both carry the same entity ID)
+
+QUERY delete_product_node_with_embedding(memory_id: ID) =>
+    DROP N<BusinessProductMemory>(memory_id)::Out<HasProductEmbedding>
+    DROP N<BusinessProductMemory>(memory_id)
+    RETURN "Deleted product node and embedding"
+
+QUERY delete_service_node_with_embedding(memory_id: ID) =>
+    DROP N<BusinessServiceMemory>(memory_id)::Out<HasServiceEmbedding>
+    DROP N<BusinessServiceMemory>(memory_id)
+    RETURN "Deleted service node and embedding"
+
+QUERY delete_location_node_with_embedding(memory_id: ID) =>
+    DROP N<BusinessLocationMemory>(memory_id)::Out<HasLocationEmbedding>
+    DROP N<BusinessLocationMemory>(memory_id)
+    RETURN "Deleted location node and embedding"
+
+QUERY delete_hours_node_with_embedding(memory_id: ID) =>
+    DROP N<BusinessHoursMemory>(memory_id)::Out<HasHoursEmbedding>
+    DROP N<BusinessHoursMemory>(memory_id)
+    RETURN "Deleted hours node and embedding"
+
+QUERY delete_social_node_with_embedding(memory_id: ID) =>
+    DROP N<BusinessSocialMemory>(memory_id)::Out<HasSocialEmbedding>
+    DROP N<BusinessSocialMemory>(memory_id)
+    RETURN "Deleted social node and embedding"
+
+QUERY delete_policy_node_with_embedding(memory_id: ID) =>
+    DROP N<BusinessPolicyMemory>(memory_id)::Out<HasPolicyEmbedding>
+    DROP N<BusinessPolicyMemory>(memory_id)
+    RETURN "Deleted policy node and embedding"
+
+QUERY delete_event_node_with_embedding(memory_id: ID) =>
+    DROP N<BusinessEventMemory>(memory_id)::Out<HasEventEmbedding>
+    DROP N<BusinessEventMemory>(memory_id)
+    RETURN "Deleted event node and embedding"
+
+QUERY delete_information_node_with_embedding(memory_id: ID) =>
+    DROP N<BusinessInformationMemory>(memory_id)::Out<HasInformationEmbedding>
+    DROP N<BusinessInformationMemory>(memory_id)
+    RETURN "Deleted information node and embedding"
+
 // ============================================================================
 // CUSTOMER BEHAVIOR DELETES
 // ============================================================================
@@ -1532,6 +1630,50 @@ QUERY delete_all_customer_feedback(customer_id: String) =>
     DROP N<CustomerFeedbackMemory>::WHERE(_::{customer_id}::EQ(customer_id))
     RETURN "Deleted all feedback for customer"
 
+// ============================================================================
+// INTERACTION AND NAVIGATION DELETES
+// ============================================================================
+
+// Interaction IDs are unique across product and service interactions
+QUERY delete_interaction(interaction_id: String) =>
+    DROP N<CustomerProductInteraction>::WHERE(_::{interaction_id}::EQ(interaction_id))
+    DROP N<CustomerServiceInteraction>::WHERE(_::{interaction_id}::EQ(interaction_id))
+    RETURN "Deleted interaction"
+
+QUERY delete_interaction_with_embedding(interaction_id: String) =>
+    DROP N<CustomerProductInteraction>::WHERE(_::{interaction_id}::EQ(interaction_id))::Out<HasProductInteractionEmbedding>
+    DROP N<CustomerServiceInteraction>::WHERE(_::{interaction_id}::EQ(interaction_id))::Out<HasServiceInteractionEmbedding>
+    DROP N<CustomerProductInteraction>::WHERE(_::{interaction_id}::EQ(interaction_id))
+    DROP N<CustomerServiceInteraction>::WHERE(_::{interaction_id}::EQ(interaction_id))
+    RETURN "Deleted interaction and embedding"
+
+QUERY delete_navigation_hub(navigation_id: String) =>
+    DROP N<BusinessNavigationHub>::WHERE(_::{navigation_id}::EQ(navigation_id))
+    RETURN "Deleted navigation hub"
+
+QUERY delete_navigation_hub_with_embedding(navigation_id: String) =>
+    DROP N<BusinessNavigationHub>::WHERE(_::{navigation_id}::EQ(navigation_id))::Out<HasNavigationEmbedding>
+    DROP N<BusinessNavigationHub>::WHERE(_::{navigation_id}::EQ(navigation_id))
+    RETURN "Deleted navigation hub and embedding"
+
+QUERY delete_waypoint(waypoint_id: String) =>
+    DROP N<NavigationWaypoint>::WHERE(_::{waypoint_id}::EQ(waypoint_id))
+    RETURN "Deleted waypoint"
+
+QUERY delete_waypoint_with_embedding(waypoint_id: String) =>
+    DROP N<NavigationWaypoint>::WHERE(_::{waypoint_id}::EQ(waypoint_id))::Out<HasWaypointEmbedding>
+    DROP N<NavigationWaypoint>::WHERE(_::{waypoint_id}::EQ(waypoint_id))
+    RETURN "Deleted waypoint and embedding"
+
+QUERY delete_path(path_id: String) =>
+    DROP N<DirectionPath>::WHERE(_::{path_id}::EQ(path_id))
+    RETURN "Deleted path"
+
+QUERY delete_path_with_embedding(path_id: String) =>
+    DROP N<DirectionPath>::WHERE(_::{path_id}::EQ(path_id))::Out<HasPathEmbedding>
+    DROP N<DirectionPath>::WHERE(_::{path_id}::EQ(path_id))
+    RETURN "Deleted path and embedding"
+
 // ============================================================================
 // CASCADE DELETES - Delete entire memory hierarchies
 // ============================================================================
@@ -1574,6 +1716,149 @@ QUERY delete_business_complete(business_id: String) =>
     DROP N<Business>::WHERE(_::{business_id}::EQ(business_id))
     RETURN "Deleted business and all memories"
 
+// Everything delete_all_business_memories / delete_business_complete would touch,
+// for preview_delete. Embedding vectors are returned separately: the cascade
+// deletes drop memory nodes (and their edges) but not the vectors.
+QUERY preview_business_delete(business_id: String) =>
+    business <- N<Business>::WHERE(_::{business_id}::EQ(business_id))
+    products <- N<BusinessProductMemory>::WHERE(_::{business_id}::EQ(business_id))
+    product_vectors <- products::Out<HasProductEmbedding>
+    product_owned_by <- products::OutE<OwnedBy>
+    product_shared_with <- products::OutE<SharedWith>
+    product_interaction_about_product <- products::InE<InteractionAboutProduct>
+    product_information_about_product <- products::InE<InformationAboutProduct>
+    services <- N<BusinessServiceMemory>::WHERE(_::{business_id}::EQ(business_id))
+    service_vectors <- services::Out<HasServiceEmbedding>
+    service_owned_by <- services::OutE<OwnedBy>
+    service_shared_with <- services::OutE<SharedWith>
+    service_interaction_about_service <- services::InE<InteractionAboutService>
+    service_information_about_service <- services::InE<InformationAboutService>
+    locations <- N<BusinessLocationMemory>::WHERE(_::{business_id}::EQ(business_id))
+    location_vectors <- locations::Out<HasLocationEmbedding>
+    location_owned_by <- locations::OutE<OwnedBy>
+    location_shared_with <- locations::OutE<SharedWith>
+    location_visit_at_location <- locations::InE<VisitAtLocation>
+    location_information_for_location <- locations::InE<InformationForLocation>
+    hours <- N<BusinessHoursMemory>::WHERE(_::{business_id}::EQ(business_id))
+    hours_vectors <- hours::Out<HasHoursEmbedding>
+    hours_owned_by <- hours::OutE<OwnedBy>
+    hours_shared_with <- hours::OutE<SharedWith>
+    social <- N<BusinessSocialMemory>::WHERE(_::{business_id}::EQ(business_id))
+    social_vectors <- social::Out<HasSocialEmbedding>
+    social_owned_by <- social::OutE<OwnedBy>
+    social_shared_with <- social::OutE<SharedWith>
+    policies <- N<BusinessPolicyMemory>::WHERE(_::{business_id}::EQ(business_id))
+    policy_vectors <- policies::Out<HasPolicyEmbedding>
+    policy_owned_by <- policies::OutE<OwnedBy>
+    policy_shared_with <- policies::OutE<SharedWith>
+    policy_communication_about_policy <- policies::InE<CommunicationAboutPolicy>
+    events <- N<BusinessEventMemory>::WHERE(_::{business_id}::EQ(business_id))
+    event_vectors <- events::Out<HasEventEmbedding>
+    event_owned_by <- events::OutE<OwnedBy>
+    event_shared_with <- events::OutE<SharedWith>
+    event_information_for_event <- events::InE<InformationForEvent>
+    business_owned_by <- business::InE<OwnedBy>
+    business_shared_with <- business::InE<SharedWith>
+    business_customer_of <- business::InE<CustomerOf>
+    business_has_navigation <- business::OutE<HasNavigation>
+    business_collaborates_with <- business::OutE<CollaboratesWith>
+    business_collaborator_of <- business::InE<CollaboratesWith>
+    information <- N<BusinessInformationMemory>::WHERE(_::{business_id}::EQ(business_id))
+    navigation_hubs <- N<BusinessNavigationHub>::WHERE(_::{business_id}::EQ(business_id))
+    RETURN business, products, product_vectors, product_owned_by, product_shared_with, product_interaction_about_product, product_information_about_product, services, service_vectors, service_owned_by, service_shared_with, service_interaction_about_service, service_information_about_service, locations, location_vectors, location_owned_by, location_shared_with, location_visit_at_location, location_information_for_location, hours, hours_vectors, hours_owned_by, hours_shared_with, social, social_vectors, social_owned_by, social_shared_with, policies, policy_vectors, policy_owned_by, policy_shared_with, policy_communication_about_policy, events, event_vectors, event_owned_by, event_shared_with, event_information_for_event, business_owned_by, business_shared_with, business_customer_of, business_has_navigation, business_collaborates_with, business_collaborator_of, information, navigation_hubs
+
+// Everything delete_all_customer_memories / delete_customer_complete would touch, for preview_delete
+QUERY preview_customer_delete(customer_id: String) =>
+    customer <- N<Customer>::WHERE(_::{customer_id}::EQ(customer_id))
+    behaviors <- N<CustomerBehaviorMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    behavior_vectors <- behaviors::Out<HasBehaviorEmbedding>
+    behavior_about_customer <- behaviors::OutE<AboutCustomer>
+    behavior_customer_has_product_interaction <- behaviors::OutE<CustomerHasProductInteraction>
+    behavior_customer_has_communication <- behaviors::OutE<CustomerHasCommunication>
+    preferences <- N<CustomerPreferenceMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    preference_vectors <- preferences::Out<HasPreferenceEmbedding>
+    preference_about_customer <- preferences::OutE<AboutCustomer>
+    desires <- N<CustomerDesireMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    desire_vectors <- desires::Out<HasDesireEmbedding>
+    desire_about_customer <- desires::OutE<AboutCustomer>
+    rules <- N<CustomerRuleMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    rule_vectors <- rules::Out<HasRuleEmbedding>
+    rule_about_customer <- rules::OutE<AboutCustomer>
+    feedback <- N<CustomerFeedbackMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    feedback_vectors <- feedback::Out<HasFeedbackEmbedding>
+    feedback_about_customer <- feedback::OutE<AboutCustomer>
+    customer_about_customer <- customer::InE<AboutCustomer>
+    customer_customer_of <- customer::OutE<CustomerOf>
+    product_interactions <- N<CustomerProductInteraction>::WHERE(_::{customer_id}::EQ(customer_id))
+    service_interactions <- N<CustomerServiceInteraction>::WHERE(_::{customer_id}::EQ(customer_id))
+    location_visits <- N<CustomerLocationVisit>::WHERE(_::{customer_id}::EQ(customer_id))
+    communications <- N<CustomerBusinessCommunication>::WHERE(_::{customer_id}::EQ(customer_id))
+    RETURN customer, behaviors, behavior_vectors, behavior_about_customer, behavior_customer_has_product_interaction, behavior_customer_has_communication, preferences, preference_vectors, preference_about_customer, desires, desire_vectors, desire_about_customer, rules, rule_vectors, rule_about_customer, feedback, feedback_vectors, feedback_about_customer, customer_about_customer, customer_customer_of, product_interactions, service_interactions, location_visits, communications
+
+// Everything forget_customer erases: the customer, every memory, interaction,
+// location visit and communication about them, their loyalty points ledger,
+// the aliases of customer IDs merged into them, and all their vectors
+QUERY preview_forget_customer(customer_id: String) =>
+    customer <- N<Customer>::WHERE(_::{customer_id}::EQ(customer_id))
+    customer_of <- customer::OutE<CustomerOf>
+    about_customer <- customer::InE<AboutCustomer>
+    behaviors <- N<CustomerBehaviorMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    behavior_vectors <- behaviors::Out<HasBehaviorEmbedding>
+    behavior_interaction_links <- behaviors::OutE<CustomerHasProductInteraction>
+    behavior_visit_links <- behaviors::OutE<CustomerHasLocationVisit>
+    behavior_communication_links <- behaviors::OutE<CustomerHasCommunication>
+    preferences <- N<CustomerPreferenceMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    preference_vectors <- preferences::Out<HasPreferenceEmbedding>
+    preference_interaction_links <- preferences::OutE<CustomerHasServiceInteraction>
+    desires <- N<CustomerDesireMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    desire_vectors <- desires::Out<HasDesireEmbedding>
+    rules <- N<CustomerRuleMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    rule_vectors <- rules::Out<HasRuleEmbedding>
+    feedback <- N<CustomerFeedbackMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    feedback_vectors <- feedback::Out<HasFeedbackEmbedding>
+    product_interactions <- N<CustomerProductInteraction>::WHERE(_::{customer_id}::EQ(customer_id))
+    product_interaction_vectors <- product_interactions::Out<HasProductInteractionEmbedding>
+    product_interaction_links <- product_interactions::OutE<InteractionAboutProduct>
+    service_interactions <- N<CustomerServiceInteraction>::WHERE(_::{customer_id}::EQ(customer_id))
+    service_interaction_vectors <- service_interactions::Out<HasServiceInteractionEmbedding>
+    service_interaction_links <- service_interactions::OutE<InteractionAboutService>
+    location_visits <- N<CustomerLocationVisit>::WHERE(_::{customer_id}::EQ(customer_id))
+    location_visit_vectors <- location_visits::Out<HasLocationVisitEmbedding>
+    location_visit_links <- location_visits::OutE<VisitAtLocation>
+    communications <- N<CustomerBusinessCommunication>::WHERE(_::{customer_id}::EQ(customer_id))
+    communication_vectors <- communications::Out<HasCommunicationEmbedding>
+    communication_links <- communications::OutE<CommunicationAboutPolicy>
+    points_transactions <- N<PointsTransaction>::WHERE(_::{customer_id}::EQ(customer_id))
+    points_links <- customer::OutE<HasPointsTransaction>
+    aliases <- N<CustomerAlias>::WHERE(_::{customer_id}::EQ(customer_id))
+    RETURN customer, customer_of, about_customer, behaviors, behavior_vectors, behavior_interaction_links, behavior_visit_links, behavior_communication_links, preferences, preference_vectors, preference_interaction_links, desires, desire_vectors, rules, rule_vectors, feedback, feedback_vectors, product_interactions, product_interaction_vectors, product_interaction_links, service_interactions, service_interaction_vectors, service_interaction_links, location_visits, location_visit_vectors, location_visit_links, communications, communication_vectors, communication_links, points_transactions, points_links, aliases
+
+// Right-to-erasure purge; vectors go first while their nodes still reach them,
+// and the edges go with the nodes
+QUERY forget_customer(customer_id: String) =>
+    DROP N<CustomerBehaviorMemory>::WHERE(_::{customer_id}::EQ(customer_id))::Out<HasBehaviorEmbedding>
+    DROP N<CustomerPreferenceMemory>::WHERE(_::{customer_id}::EQ(customer_id))::Out<HasPreferenceEmbedding>
+    DROP N<CustomerDesireMemory>::WHERE(_::{customer_id}::EQ(customer_id))::Out<HasDesireEmbedding>
+    DROP N<CustomerRuleMemory>::WHERE(_::{customer_id}::EQ(customer_id))::Out<HasRuleEmbedding>
+    DROP N<CustomerFeedbackMemory>::WHERE(_::{customer_id}::EQ(customer_id))::Out<HasFeedbackEmbedding>
+    DROP N<CustomerProductInteraction>::WHERE(_::{customer_id}::EQ(customer_id))::Out<HasProductInteractionEmbedding>
+    DROP N<CustomerServiceInteraction>::WHERE(_::{customer_id}::EQ(customer_id))::Out<HasServiceInteractionEmbedding>
+    DROP N<CustomerLocationVisit>::WHERE(_::{customer_id}::EQ(customer_id))::Out<HasLocationVisitEmbedding>
+    DROP N<CustomerBusinessCommunication>::WHERE(_::{customer_id}::EQ(customer_id))::Out<HasCommunicationEmbedding>
+    DROP N<CustomerBehaviorMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    DROP N<CustomerPreferenceMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    DROP N<CustomerDesireMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    DROP N<CustomerRuleMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    DROP N<CustomerFeedbackMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    DROP N<CustomerProductInteraction>::WHERE(_::{customer_id}::EQ(customer_id))
+    DROP N<CustomerServiceInteraction>::WHERE(_::{customer_id}::EQ(customer_id))
+    DROP N<CustomerLocationVisit>::WHERE(_::{customer_id}::EQ(customer_id))
+    DROP N<CustomerBusinessCommunication>::WHERE(_::{customer_id}::EQ(customer_id))
+    DROP N<PointsTransaction>::WHERE(_::{customer_id}::EQ(customer_id))
+    DROP N<CustomerAlias>::WHERE(_::{customer_id}::EQ(customer_id))
+    DROP N<Customer>::WHERE(_::{customer_id}::EQ(customer_id))
+    RETURN "Erased customer"
+
 // ============================================================================
 // CUSTOMER INTERACTION QUERIES (NEW ARCHITECTURE)
 // ============================================================================
@@ -1718,6 +2003,27 @@ QUERY get_customer_service_interactions(customer_id: String) =>
     interactions <- N<CustomerServiceInteraction>::WHERE(_::{customer_id}::EQ(customer_id))
     RETURN interactions
 
+// Get customer location visits
+QUERY get_customer_location_visits(customer_id: String) =>
+    visits <- N<CustomerLocationVisit>::WHERE(_::{customer_id}::EQ(customer_id))
+    RETURN visits
+
+// Get customers linked to a business (business-scoped analytics)
+QUERY get_business_customers(business_id: String) =>
+    business <- N<Business>::WHERE(_::{business_id}::EQ(business_id))
+    customers <- business::In<CustomerOf>
+    RETURN customers
+
+// Get all interactions with a product (business-scoped analytics)
+QUERY get_product_interactions_by_product(product_id: String) =>
+    interactions <- N<CustomerProductInteraction>::WHERE(_::{product_id}::EQ(product_id))
+    RETURN interactions
+
+// Get all interactions with a service (business-scoped analytics)
+QUERY get_service_interactions_by_service(service_id: String) =>
+    interactions <- N<CustomerServiceInteraction>::WHERE(_::{service_id}::EQ(service_id))
+    RETURN interactions
+
 // ============================================================================
 // NAVIGATION SYSTEM QUERIES (Multi-Tenant Flexible)
 // ============================================================================
@@ -1990,6 +2296,71 @@ QUERY add_direction_path(
     })::From(nav_hub)::To(path)
     RETURN path
 
+// Link a waypoint into a direction path at a sequence position
+QUERY link_path_waypoint(path_id: String, waypoint_id: String, sequence_order: I32, is_optional: Boolean, created_at: I64) =>
+    path <- N<DirectionPath>::WHERE(_::{path_id}::EQ(path_id))
+    waypoint <- N<NavigationWaypoint>::WHERE(_::{waypoint_id}::EQ(waypoint_id))
+    edge <- AddE<PathThroughWaypoint>({
+        sequence_order: sequence_order,
+        is_optional: is_optional,
+        created_at: created_at
+    })::From(path)::To(waypoint)
+    RETURN edge
+
+// Get a direction path with its waypoint edges (sequence order) and waypoints
+QUERY get_path_route(path_id: String) =>
+    path <- N<DirectionPath>::WHERE(_::{path_id}::EQ(path_id))
+    edges <- path::OutE<PathThroughWaypoint>
+    waypoints <- path::Out<PathThroughWaypoint>
+    RETURN path, edges, waypoints
+
+// Add a floor to a navigation hub
+QUERY add_navigation_floor(floor_id: String, navigation_id: String, floor_level: I32, floor_name: String, description: String, created_at: I64) =>
+    floor <- AddN<NavigationFloor>({
+        floor_id: floor_id,
+        navigation_id: navigation_id,
+        floor_level: floor_level,
+        floor_name: floor_name,
+        description: description,
+        created_at: created_at
+    })
+    nav_hub <- N<BusinessNavigationHub>::WHERE(_::{navigation_id}::EQ(navigation_id))
+    floor_edge <- AddE<HasFloor>({
+        created_at: created_at
+    })::From(nav_hub)::To(floor)
+    RETURN floor
+
+// Get the floors of a navigation hub
+QUERY get_navigation_floors(navigation_id: String) =>
+    floors <- N<NavigationFloor>::WHERE(_::{navigation_id}::EQ(navigation_id))
+    RETURN floors
+
+// Put a waypoint on a floor
+QUERY link_floor_waypoint(floor_id: String, waypoint_id: String, created_at: I64) =>
+    floor <- N<NavigationFloor>::WHERE(_::{floor_id}::EQ(floor_id))
+    waypoint <- N<NavigationWaypoint>::WHERE(_::{waypoint_id}::EQ(waypoint_id))
+    edge <- AddE<FloorHasWaypoint>({
+        created_at: created_at
+    })::From(floor)::To(waypoint)
+    RETURN edge
+
+// Connect two waypoints on different floors
+QUERY add_floor_connector(from_waypoint_id: String, to_waypoint_id: String, connector_type: String, is_accessible: Boolean, created_at: I64) =>
+    from_waypoint <- N<NavigationWaypoint>::WHERE(_::{waypoint_id}::EQ(from_waypoint_id))
+    to_waypoint <- N<NavigationWaypoint>::WHERE(_::{waypoint_id}::EQ(to_waypoint_id))
+    edge <- AddE<FloorConnector>({
+        connector_type: connector_type,
+        is_accessible: is_accessible,
+        created_at: created_at
+    })::From(from_waypoint)::To(to_waypoint)
+    RETURN edge
+
+// Get a hub's waypoints with their floor connector edges
+QUERY get_floor_connectors(navigation_id: String) =>
+    waypoints <- N<NavigationWaypoint>::WHERE(_::{navigation_id}::EQ(navigation_id))
+    connectors <- waypoints::OutE<FloorConnector>
+    RETURN waypoints, connectors
+
 // Search navigation hubs by location description
 QUERY search_navigation_hubs(query_embedding: [F64], limit: I64) =>
     embeddings <- SearchV<BusinessNavigationEmbedding>(query_embedding, limit)
@@ -2013,6 +2384,16 @@ QUERY get_business_navigation_hub(business_id: String) =>
     nav_hub <- N<BusinessNavigationHub>::WHERE(_::{business_id}::EQ(business_id))
     RETURN nav_hub
 
+// Get navigation hub by navigation_id
+QUERY get_navigation_hub(navigation_id: String) =>
+    nav_hub <- N<BusinessNavigationHub>::WHERE(_::{navigation_id}::EQ(navigation_id))
+    RETURN nav_hub
+
+// Get all navigation hubs (proximity search across businesses)
+QUERY get_all_navigation_hubs() =>
+    nav_hubs <- N<BusinessNavigationHub>
+    RETURN nav_hubs
+
 // Get navigation waypoints for a business
 QUERY get_navigation_waypoints(navigation_id: String) =>
     waypoints <- N<NavigationWaypoint>::WHERE(_::{navigation_id}::EQ(navigation_id))
@@ -2067,44 +2448,51 @@ QUERY get_accessible_navigation(navigation_id: String) =>
 // Business Services Semantic Search
 QUERY search_business_services_semantic(query_text: String, k: I64) =>
     results <- SearchV<BusinessServiceEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasServiceEmbedding>
     services <- results::In<HasServiceEmbedding>
-    RETURN services
+    RETURN results, links, services
 
 // Business Locations Semantic Search  
 QUERY search_business_locations_semantic(query_text: String, k: I64) =>
     results <- SearchV<BusinessLocationEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasLocationEmbedding>
     locations <- results::In<HasLocationEmbedding>
-    RETURN locations
+    RETURN results, links, locations
 
 // Business Hours Semantic Search
 QUERY search_business_hours_semantic(query_text: String, k: I64) =>
     results <- SearchV<BusinessHoursEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasHoursEmbedding>
     hours <- results::In<HasHoursEmbedding>
-    RETURN hours
+    RETURN results, links, hours
 
 // Business Social Semantic Search
 QUERY search_business_social_semantic(query_text: String, k: I64) =>
     results <- SearchV<BusinessSocialEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasSocialEmbedding>
     social <- results::In<HasSocialEmbedding>
-    RETURN social
+    RETURN results, links, social
 
 // Business Policies Semantic Search
 QUERY search_business_policies_semantic(query_text: String, k: I64) =>
     results <- SearchV<BusinessPolicyEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasPolicyEmbedding>
     policies <- results::In<HasPolicyEmbedding>
-    RETURN policies
+    RETURN results, links, policies
 
 // Business Events Semantic Search
 QUERY search_business_events_semantic(query_text: String, k: I64) =>
     results <- SearchV<BusinessEventEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasEventEmbedding>
     events <- results::In<HasEventEmbedding>
-    RETURN events
+    RETURN results, links, events
 
 // Business Information Semantic Search
 QUERY search_business_information_semantic(query_text: String, k: I64) =>
     results <- SearchV<BusinessInformationEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasInformationEmbedding>
     information <- results::In<HasInformationEmbedding>
-    RETURN information
+    RETURN results, links, information
 
 // Business Information Hybrid Search
 QUERY search_business_information_hybrid(
@@ -2113,69 +2501,99 @@ QUERY search_business_information_hybrid(
     business_id: String
 ) =>
     embeddings <- SearchV<BusinessInformationEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasInformationEmbedding>
     information <- embeddings::In<HasInformationEmbedding>
     filtered <- information::WHERE(_::{business_id}::EQ(business_id))
-    RETURN filtered
+    RETURN embeddings, links, information, filtered
 
 // Customer Behaviors Semantic Search
 QUERY search_customer_behaviors_semantic(query_text: String, k: I64) =>
     results <- SearchV<CustomerBehaviorEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasBehaviorEmbedding>
     behaviors <- results::In<HasBehaviorEmbedding>
-    RETURN behaviors
+    RETURN results, links, behaviors
 
 // Customer Preferences Semantic Search
 QUERY search_customer_preferences_semantic(query_text: String, k: I64) =>
     results <- SearchV<CustomerPreferenceEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasPreferenceEmbedding>
     preferences <- results::In<HasPreferenceEmbedding>
-    RETURN preferences
+    RETURN results, links, preferences
 
 // Customer Desires Semantic Search
 QUERY search_customer_desires_semantic(query_text: String, k: I64) =>
     results <- SearchV<CustomerDesireEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasDesireEmbedding>
     desires <- results::In<HasDesireEmbedding>
-    RETURN desires
+    RETURN results, links, desires
 
 // Customer Rules Semantic Search
 QUERY search_customer_rules_semantic(query_text: String, k: I64) =>
     results <- SearchV<CustomerRuleEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasRuleEmbedding>
     rules <- results::In<HasRuleEmbedding>
-    RETURN rules
+    RETURN results, links, rules
 
 // Customer Feedback Semantic Search
 QUERY search_customer_feedback_semantic(query_text: String, k: I64) =>
     results <- SearchV<CustomerFeedbackEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasFeedbackEmbedding>
     feedback <- results::In<HasFeedbackEmbedding>
-    RETURN feedback
+    RETURN results, links, feedback
+
+// Customer Communications Semantic Search (conversation memory recall)
+QUERY search_customer_communication_semantic(query_text: String, k: I64) =>
+    results <- SearchV<CustomerCommunicationEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasCommunicationEmbedding>
+    communications <- results::In<HasCommunicationEmbedding>
+    RETURN results, links, communications
+
+// Customer Communications Hybrid Search
+QUERY search_customer_communication_hybrid(
+    query_embedding: [F64],
+    limit: I64,
+    customer_id: String
+) =>
+    embeddings <- SearchV<CustomerCommunicationEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasCommunicationEmbedding>
+    communications <- embeddings::In<HasCommunicationEmbedding>
+    filtered <- communications::WHERE(_::{customer_id}::EQ(customer_id))
+    RETURN embeddings, links, communications, filtered
 
 // Customer Product Interactions Semantic Search
 QUERY search_customer_product_interactions_semantic(query_text: String, k: I64) =>
     results <- SearchV<CustomerProductInteractionEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasProductInteractionEmbedding>
     interactions <- results::In<HasProductInteractionEmbedding>
-    RETURN interactions
+    RETURN results, links, interactions
 
 // Customer Service Interactions Semantic Search
 QUERY search_customer_service_interactions_semantic(query_text: String, k: I64) =>
     results <- SearchV<CustomerServiceInteractionEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasServiceInteractionEmbedding>
     interactions <- results::In<HasServiceInteractionEmbedding>
-    RETURN interactions
+    RETURN results, links, interactions
 
 // Navigation Hubs Semantic Search
 QUERY search_navigation_hubs_semantic(query_text: String, k: I64) =>
     results <- SearchV<BusinessNavigationEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasNavigationEmbedding>
     hubs <- results::In<HasNavigationEmbedding>
-    RETURN hubs
+    RETURN results, links, hubs
 
 // Navigation Waypoints Semantic Search
 QUERY search_waypoints_semantic(query_text: String, k: I64) =>
     results <- SearchV<NavigationWaypointEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasWaypointEmbedding>
     waypoints <- results::In<HasWaypointEmbedding>
-    RETURN waypoints
+    RETURN results, links, waypoints
 
 // Direction Paths Semantic Search
 QUERY search_direction_paths_semantic(query_text: String, k: I64) =>
     results <- SearchV<DirectionPathEmbedding>(Embed(query_text), k)
+    links <- results::InE<HasPathEmbedding>
     paths <- results::In<HasPathEmbedding>
-    RETURN paths
+    RETURN results, links, paths
 
 
 // ============================================================================
@@ -2322,6 +2740,95 @@ QUERY update_business_service_memory(
     edge <- AddE<HasServiceEmbedding>({created_at: timestamp})::From(updated)::To(vec)
     RETURN updated
 
+// Partial product/service updates: every field is rewritten from the merged
+// node and the embedding rebuilt from the composite text of those fields
+
+QUERY update_business_product_fields(
+    business_id: String,
+    product_id: String,
+    product_name: String,
+    product_category: String,
+    price: F64,
+    currency: String,
+    availability: String,
+    stock_quantity: I64,
+    reorder_threshold: I64,
+    description: String,
+    features: [String],
+    specifications: String,
+    tags: [String],
+    seo_keywords: [String],
+    competitor_analysis: String,
+    seasonal_trends: String,
+    text_description: String,
+    composite_text: String,
+    new_embedding: [F64],
+    timestamp: I64
+) =>
+    memory <- N<BusinessProductMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(_::{product_id}::EQ(product_id))
+    updated <- memory::UPDATE({
+        product_name: product_name,
+        product_category: product_category,
+        price: price,
+        currency: currency,
+        availability: availability,
+        stock_quantity: stock_quantity,
+        reorder_threshold: reorder_threshold,
+        description: description,
+        features: features,
+        specifications: specifications,
+        tags: tags,
+        seo_keywords: seo_keywords,
+        competitor_analysis: competitor_analysis,
+        seasonal_trends: seasonal_trends,
+        text_description: text_description,
+        updated_at: timestamp
+    })
+    DROP memory::Out<HasProductEmbedding>
+    DROP memory::OutE<HasProductEmbedding>
+    vec <- AddV<BusinessProductEmbedding>(new_embedding, {composite_embedding_text: composite_text, product_name: product_name, category_context: product_category})
+    edge <- AddE<HasProductEmbedding>({created_at: timestamp})::From(updated)::To(vec)
+    RETURN updated
+
+QUERY update_business_service_fields(
+    business_id: String,
+    service_id: String,
+    service_name: String,
+    service_category: String,
+    price: F64,
+    currency: String,
+    duration_minutes: I32,
+    availability: String,
+    description: String,
+    requirements: [String],
+    deliverables: [String],
+    tags: [String],
+    text_description: String,
+    composite_text: String,
+    new_embedding: [F64],
+    timestamp: I64
+) =>
+    memory <- N<BusinessServiceMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(_::{service_id}::EQ(service_id))
+    updated <- memory::UPDATE({
+        service_name: service_name,
+        service_category: service_category,
+        price: price,
+        currency: currency,
+        duration_minutes: duration_minutes,
+        availability: availability,
+        description: description,
+        requirements: requirements,
+        deliverables: deliverables,
+        tags: tags,
+        text_description: text_description,
+        updated_at: timestamp
+    })
+    DROP memory::Out<HasServiceEmbedding>
+    DROP memory::OutE<HasServiceEmbedding>
+    vec <- AddV<BusinessServiceEmbedding>(new_embedding, {composite_embedding_text: composite_text, service_name: service_name, category_context: service_category})
+    edge <- AddE<HasServiceEmbedding>({created_at: timestamp})::From(updated)::To(vec)
+    RETURN updated
+
 // Test File: Update Business Location Memory
 // Status: Ready for validation
 
@@ -2358,6 +2865,12 @@ QUERY update_business_hours_memory(
     edge <- AddE<HasHoursEmbedding>({created_at: timestamp})::From(updated)::To(vec)
     RETURN updated
 
+// Replace the exceptions JSON of a business hours memory (embedding unchanged)
+QUERY update_business_hours_exceptions(business_id: String, hours_id: String, exceptions: String, timestamp: I64) =>
+    memory <- N<BusinessHoursMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(_::{hours_id}::EQ(hours_id))
+    updated <- memory::UPDATE({exceptions: exceptions, updated_at: timestamp})
+    RETURN updated
+
 // Test File: Update Business Social Memory
 // Status: Ready for validation
 
@@ -2412,6 +2925,24 @@ QUERY update_business_event_memory(
     edge <- AddE<HasEventEmbedding>({created_at: timestamp})::From(updated)::To(vec)
     RETURN updated
 
+// Retire a policy version in favour of its successor (embedding unchanged)
+QUERY supersede_policy_version(business_id: String, policy_id: String, superseded_by: String, timestamp: I64) =>
+    memory <- N<BusinessPolicyMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(_::{policy_id}::EQ(policy_id))
+    updated <- memory::UPDATE({is_active: false, superseded_by: superseded_by, updated_at: timestamp})
+    RETURN updated
+
+// Set the registration count of an event (embedding unchanged)
+QUERY update_event_registrations(business_id: String, event_id: String, registered_count: I32, timestamp: I64) =>
+    memory <- N<BusinessEventMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(_::{event_id}::EQ(event_id))
+    updated <- memory::UPDATE({registered_count: registered_count, updated_at: timestamp})
+    RETURN updated
+
+// Set the lifecycle status of an event ("scheduled", "closed", "cancelled")
+QUERY update_event_status(business_id: String, event_id: String, status: String, timestamp: I64) =>
+    memory <- N<BusinessEventMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(_::{event_id}::EQ(event_id))
+    updated <- memory::UPDATE({status: status, updated_at: timestamp})
+    RETURN updated
+
 // UNCOMMENTED FOR TESTING - update_business_information_memory
 QUERY update_business_information_memory(
     business_id: String,
@@ -2598,3 +3129,920 @@ QUERY update_direction_path_memory(
     edge <- AddE<HasPathEmbedding>({created_at: timestamp})::From(memory)::To(vec)
     RETURN memory
 
+
+
+// ============================================================================
+// EMBEDDING EXPORT QUERIES - Vector-store-agnostic dataset export
+// ============================================================================
+// Return the embedding vectors (with properties) owned by a business/customer
+
+QUERY export_business_products_embeddings(business_id: String) =>
+    products <- N<BusinessProductMemory>::WHERE(_::{business_id}::EQ(business_id))
+    embeddings <- products::Out<HasProductEmbedding>
+    RETURN embeddings
+
+QUERY export_business_services_embeddings(business_id: String) =>
+    services <- N<BusinessServiceMemory>::WHERE(_::{business_id}::EQ(business_id))
+    embeddings <- services::Out<HasServiceEmbedding>
+    RETURN embeddings
+
+QUERY export_business_locations_embeddings(business_id: String) =>
+    locations <- N<BusinessLocationMemory>::WHERE(_::{business_id}::EQ(business_id))
+    embeddings <- locations::Out<HasLocationEmbedding>
+    RETURN embeddings
+
+QUERY export_business_hours_embeddings(business_id: String) =>
+    hours <- N<BusinessHoursMemory>::WHERE(_::{business_id}::EQ(business_id))
+    embeddings <- hours::Out<HasHoursEmbedding>
+    RETURN embeddings
+
+QUERY export_business_social_embeddings(business_id: String) =>
+    social <- N<BusinessSocialMemory>::WHERE(_::{business_id}::EQ(business_id))
+    embeddings <- social::Out<HasSocialEmbedding>
+    RETURN embeddings
+
+QUERY export_business_policies_embeddings(business_id: String) =>
+    policies <- N<BusinessPolicyMemory>::WHERE(_::{business_id}::EQ(business_id))
+    embeddings <- policies::Out<HasPolicyEmbedding>
+    RETURN embeddings
+
+QUERY export_business_events_embeddings(business_id: String) =>
+    events <- N<BusinessEventMemory>::WHERE(_::{business_id}::EQ(business_id))
+    embeddings <- events::Out<HasEventEmbedding>
+    RETURN embeddings
+
+QUERY export_business_information_embeddings(business_id: String) =>
+    information <- N<BusinessInformationMemory>::WHERE(_::{business_id}::EQ(business_id))
+    embeddings <- information::Out<HasInformationEmbedding>
+    RETURN embeddings
+
+QUERY export_customer_behaviors_embeddings(customer_id: String) =>
+    behaviors <- N<CustomerBehaviorMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    embeddings <- behaviors::Out<HasBehaviorEmbedding>
+    RETURN embeddings
+
+QUERY export_customer_preferences_embeddings(customer_id: String) =>
+    preferences <- N<CustomerPreferenceMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    embeddings <- preferences::Out<HasPreferenceEmbedding>
+    RETURN embeddings
+
+QUERY export_customer_desires_embeddings(customer_id: String) =>
+    desires <- N<CustomerDesireMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    embeddings <- desires::Out<HasDesireEmbedding>
+    RETURN embeddings
+
+QUERY export_customer_rules_embeddings(customer_id: String) =>
+    rules <- N<CustomerRuleMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    embeddings <- rules::Out<HasRuleEmbedding>
+    RETURN embeddings
+
+QUERY export_customer_feedback_embeddings(customer_id: String) =>
+    feedback <- N<CustomerFeedbackMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    embeddings <- feedback::Out<HasFeedbackEmbedding>
+    RETURN embeddings
+
+QUERY export_customer_communications_embeddings(customer_id: String) =>
+    communications <- N<CustomerBusinessCommunication>::WHERE(_::{customer_id}::EQ(customer_id))
+    embeddings <- communications::Out<HasCommunicationEmbedding>
+    RETURN embeddings
+
+// One memory with its stored embedding vector (embedding consistency checks)
+QUERY get_product_with_embedding(product_id: String) =>
+    memory <- N<BusinessProductMemory>::WHERE(_::{product_id}::EQ(product_id))
+    embedding <- memory::Out<HasProductEmbedding>
+    RETURN memory, embedding
+
+QUERY get_service_with_embedding(service_id: String) =>
+    memory <- N<BusinessServiceMemory>::WHERE(_::{service_id}::EQ(service_id))
+    embedding <- memory::Out<HasServiceEmbedding>
+    RETURN memory, embedding
+
+QUERY get_location_with_embedding(location_id: String) =>
+    memory <- N<BusinessLocationMemory>::WHERE(_::{location_id}::EQ(location_id))
+    embedding <- memory::Out<HasLocationEmbedding>
+    RETURN memory, embedding
+
+QUERY get_hours_with_embedding(hours_id: String) =>
+    memory <- N<BusinessHoursMemory>::WHERE(_::{hours_id}::EQ(hours_id))
+    embedding <- memory::Out<HasHoursEmbedding>
+    RETURN memory, embedding
+
+QUERY get_social_with_embedding(social_id: String) =>
+    memory <- N<BusinessSocialMemory>::WHERE(_::{social_id}::EQ(social_id))
+    embedding <- memory::Out<HasSocialEmbedding>
+    RETURN memory, embedding
+
+QUERY get_policy_with_embedding(policy_id: String) =>
+    memory <- N<BusinessPolicyMemory>::WHERE(_::{policy_id}::EQ(policy_id))
+    embedding <- memory::Out<HasPolicyEmbedding>
+    RETURN memory, embedding
+
+QUERY get_event_with_embedding(event_id: String) =>
+    memory <- N<BusinessEventMemory>::WHERE(_::{event_id}::EQ(event_id))
+    embedding <- memory::Out<HasEventEmbedding>
+    RETURN memory, embedding
+
+QUERY get_information_with_embedding(info_id: String) =>
+    memory <- N<BusinessInformationMemory>::WHERE(_::{info_id}::EQ(info_id))
+    embedding <- memory::Out<HasInformationEmbedding>
+    RETURN memory, embedding
+
+QUERY get_behavior_with_embedding(behavior_id: String) =>
+    memory <- N<CustomerBehaviorMemory>::WHERE(_::{behavior_id}::EQ(behavior_id))
+    embedding <- memory::Out<HasBehaviorEmbedding>
+    RETURN memory, embedding
+
+QUERY get_preference_with_embedding(preference_id: String) =>
+    memory <- N<CustomerPreferenceMemory>::WHERE(_::{preference_id}::EQ(preference_id))
+    embedding <- memory::Out<HasPreferenceEmbedding>
+    RETURN memory, embedding
+
+QUERY get_desire_with_embedding(desire_id: String) =>
+    memory <- N<CustomerDesireMemory>::WHERE(_::{desire_id}::EQ(desire_id))
+    embedding <- memory::Out<HasDesireEmbedding>
+    RETURN memory, embedding
+
+QUERY get_rule_with_embedding(rule_id: String) =>
+    memory <- N<CustomerRuleMemory>::WHERE(_::{rule_id}::EQ(rule_id))
+    embedding <- memory::Out<HasRuleEmbedding>
+    RETURN memory, embedding
+
+QUERY get_feedback_with_embedding(feedback_id: String) =>
+    memory <- N<CustomerFeedbackMemory>::WHERE(_::{feedback_id}::EQ(feedback_id))
+    embedding <- memory::Out<HasFeedbackEmbedding>
+    RETURN memory, embedding
+
+
+// ============================================================================
+// DEDUPLICATION QUERIES - Nearest neighbours for near-duplicate detection
+// ============================================================================
+// Return the `limit` nearest vectors, the embedding edges leading to them and
+// their memories, so the MCP server pairs each vector with its memory by ID
+// and only compares the candidates that belong to the new memory's owner
+
+QUERY find_similar_business_products(query_embedding: [F64], limit: I64) =>
+    embeddings <- SearchV<BusinessProductEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasProductEmbedding>
+    memories <- embeddings::In<HasProductEmbedding>
+    RETURN embeddings, links, memories
+
+QUERY find_similar_business_services(query_embedding: [F64], limit: I64) =>
+    embeddings <- SearchV<BusinessServiceEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasServiceEmbedding>
+    memories <- embeddings::In<HasServiceEmbedding>
+    RETURN embeddings, links, memories
+
+QUERY find_similar_business_locations(query_embedding: [F64], limit: I64) =>
+    embeddings <- SearchV<BusinessLocationEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasLocationEmbedding>
+    memories <- embeddings::In<HasLocationEmbedding>
+    RETURN embeddings, links, memories
+
+QUERY find_similar_business_hours(query_embedding: [F64], limit: I64) =>
+    embeddings <- SearchV<BusinessHoursEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasHoursEmbedding>
+    memories <- embeddings::In<HasHoursEmbedding>
+    RETURN embeddings, links, memories
+
+QUERY find_similar_business_social(query_embedding: [F64], limit: I64) =>
+    embeddings <- SearchV<BusinessSocialEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasSocialEmbedding>
+    memories <- embeddings::In<HasSocialEmbedding>
+    RETURN embeddings, links, memories
+
+QUERY find_similar_business_policies(query_embedding: [F64], limit: I64) =>
+    embeddings <- SearchV<BusinessPolicyEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasPolicyEmbedding>
+    memories <- embeddings::In<HasPolicyEmbedding>
+    RETURN embeddings, links, memories
+
+QUERY find_similar_business_events(query_embedding: [F64], limit: I64) =>
+    embeddings <- SearchV<BusinessEventEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasEventEmbedding>
+    memories <- embeddings::In<HasEventEmbedding>
+    RETURN embeddings, links, memories
+
+QUERY find_similar_business_information(query_embedding: [F64], limit: I64) =>
+    embeddings <- SearchV<BusinessInformationEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasInformationEmbedding>
+    memories <- embeddings::In<HasInformationEmbedding>
+    RETURN embeddings, links, memories
+
+QUERY find_similar_customer_behaviors(query_embedding: [F64], limit: I64) =>
+    embeddings <- SearchV<CustomerBehaviorEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasBehaviorEmbedding>
+    memories <- embeddings::In<HasBehaviorEmbedding>
+    RETURN embeddings, links, memories
+
+QUERY find_similar_customer_preferences(query_embedding: [F64], limit: I64) =>
+    embeddings <- SearchV<CustomerPreferenceEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasPreferenceEmbedding>
+    memories <- embeddings::In<HasPreferenceEmbedding>
+    RETURN embeddings, links, memories
+
+QUERY find_similar_customer_desires(query_embedding: [F64], limit: I64) =>
+    embeddings <- SearchV<CustomerDesireEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasDesireEmbedding>
+    memories <- embeddings::In<HasDesireEmbedding>
+    RETURN embeddings, links, memories
+
+QUERY find_similar_customer_rules(query_embedding: [F64], limit: I64) =>
+    embeddings <- SearchV<CustomerRuleEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasRuleEmbedding>
+    memories <- embeddings::In<HasRuleEmbedding>
+    RETURN embeddings, links, memories
+
+QUERY find_similar_customer_feedback(query_embedding: [F64], limit: I64) =>
+    embeddings <- SearchV<CustomerFeedbackEmbedding>(query_embedding, limit)
+    links <- embeddings::InE<HasFeedbackEmbedding>
+    memories <- embeddings::In<HasFeedbackEmbedding>
+    RETURN embeddings, links, memories
+
+QUERY find_similar_navigation_waypoints(query_embedding: [F64], limit: I64) =>
+    embeddings <- SearchV<NavigationWaypointEmbedding>(query_embedding, limit)
+    memories <- embeddings::In<HasWaypointEmbedding>
+    RETURN embeddings, memories
+
+
+// ============================================================================
+// MERGE QUERIES - Consolidate duplicate memories into a survivor
+// ============================================================================
+
+// Lookup single memories by their entity ID
+
+QUERY get_business_product_by_id(product_id: String) =>
+    memory <- N<BusinessProductMemory>::WHERE(_::{product_id}::EQ(product_id))
+    RETURN memory
+
+QUERY get_business_service_by_id(service_id: String) =>
+    memory <- N<BusinessServiceMemory>::WHERE(_::{service_id}::EQ(service_id))
+    RETURN memory
+
+QUERY get_business_location_by_id(location_id: String) =>
+    memory <- N<BusinessLocationMemory>::WHERE(_::{location_id}::EQ(location_id))
+    RETURN memory
+
+QUERY get_business_hours_by_id(hours_id: String) =>
+    memory <- N<BusinessHoursMemory>::WHERE(_::{hours_id}::EQ(hours_id))
+    RETURN memory
+
+QUERY get_business_social_by_id(social_id: String) =>
+    memory <- N<BusinessSocialMemory>::WHERE(_::{social_id}::EQ(social_id))
+    RETURN memory
+
+QUERY get_business_policy_by_id(policy_id: String) =>
+    memory <- N<BusinessPolicyMemory>::WHERE(_::{policy_id}::EQ(policy_id))
+    RETURN memory
+
+QUERY get_business_event_by_id(event_id: String) =>
+    memory <- N<BusinessEventMemory>::WHERE(_::{event_id}::EQ(event_id))
+    RETURN memory
+
+QUERY get_business_information_by_id(info_id: String) =>
+    memory <- N<BusinessInformationMemory>::WHERE(_::{info_id}::EQ(info_id))
+    RETURN memory
+
+QUERY get_customer_behavior_by_id(behavior_id: String) =>
+    memory <- N<CustomerBehaviorMemory>::WHERE(_::{behavior_id}::EQ(behavior_id))
+    RETURN memory
+
+QUERY get_customer_preference_by_id(preference_id: String) =>
+    memory <- N<CustomerPreferenceMemory>::WHERE(_::{preference_id}::EQ(preference_id))
+    RETURN memory
+
+QUERY get_customer_desire_by_id(desire_id: String) =>
+    memory <- N<CustomerDesireMemory>::WHERE(_::{desire_id}::EQ(desire_id))
+    RETURN memory
+
+QUERY get_customer_rule_by_id(rule_id: String) =>
+    memory <- N<CustomerRuleMemory>::WHERE(_::{rule_id}::EQ(rule_id))
+    RETURN memory
+
+QUERY get_customer_feedback_by_id(feedback_id: String) =>
+    memory <- N<CustomerFeedbackMemory>::WHERE(_::{feedback_id}::EQ(feedback_id))
+    RETURN memory
+
+// Move product references (interactions, information links) to the survivor
+QUERY merge_rewire_product(from_product_id: String, to_product_id: String) =>
+    interactions <- N<CustomerProductInteraction>::WHERE(_::{product_id}::EQ(from_product_id))
+    updated <- interactions::UPDATE({product_id: to_product_id})
+    survivor <- N<BusinessProductMemory>({product_id: to_product_id})
+    info <- N<BusinessProductMemory>({product_id: from_product_id})::In<InformationAboutProduct>
+    edges <- AddE<InformationAboutProduct>({info_type: "documentation", notes: "merged"})::From(info)::To(survivor)
+    RETURN updated
+
+// Move service references (interactions, information links) to the survivor
+QUERY merge_rewire_service(from_service_id: String, to_service_id: String) =>
+    interactions <- N<CustomerServiceInteraction>::WHERE(_::{service_id}::EQ(from_service_id))
+    updated <- interactions::UPDATE({service_id: to_service_id})
+    survivor <- N<BusinessServiceMemory>({service_id: to_service_id})
+    info <- N<BusinessServiceMemory>({service_id: from_service_id})::In<InformationAboutService>
+    edges <- AddE<InformationAboutService>({info_type: "documentation", notes: "merged"})::From(info)::To(survivor)
+    RETURN updated
+
+// Move location references (visits, information links) to the survivor
+QUERY merge_rewire_location(from_location_id: String, to_location_id: String) =>
+    visits <- N<CustomerLocationVisit>::WHERE(_::{location_id}::EQ(from_location_id))
+    updated <- visits::UPDATE({location_id: to_location_id})
+    survivor <- N<BusinessLocationMemory>({location_id: to_location_id})
+    info <- N<BusinessLocationMemory>({location_id: from_location_id})::In<InformationForLocation>
+    edges <- AddE<InformationForLocation>({info_type: "guide", notes: "merged"})::From(info)::To(survivor)
+    RETURN updated
+
+// Move event information links to the survivor
+QUERY merge_rewire_event(from_event_id: String, to_event_id: String) =>
+    survivor <- N<BusinessEventMemory>({event_id: to_event_id})
+    info <- N<BusinessEventMemory>({event_id: from_event_id})::In<InformationForEvent>
+    edges <- AddE<InformationForEvent>({info_type: "details", notes: "merged"})::From(info)::To(survivor)
+    RETURN survivor
+
+// Move behavior links (product interactions, visits, communications) to the survivor
+QUERY merge_rewire_behavior(from_behavior_id: String, to_behavior_id: String, timestamp: I64) =>
+    survivor <- N<CustomerBehaviorMemory>({behavior_id: to_behavior_id})
+    duplicate <- N<CustomerBehaviorMemory>({behavior_id: from_behavior_id})
+    product_interactions <- duplicate::Out<CustomerHasProductInteraction>
+    product_edges <- AddE<CustomerHasProductInteraction>({created_at: timestamp})::From(survivor)::To(product_interactions)
+    visits <- duplicate::Out<CustomerHasLocationVisit>
+    visit_edges <- AddE<CustomerHasLocationVisit>({created_at: timestamp})::From(survivor)::To(visits)
+    communications <- duplicate::Out<CustomerHasCommunication>
+    communication_edges <- AddE<CustomerHasCommunication>({created_at: timestamp})::From(survivor)::To(communications)
+    RETURN survivor
+
+// Move preference links (service interactions) to the survivor
+QUERY merge_rewire_preference(from_preference_id: String, to_preference_id: String, timestamp: I64) =>
+    survivor <- N<CustomerPreferenceMemory>({preference_id: to_preference_id})
+    duplicate <- N<CustomerPreferenceMemory>({preference_id: from_preference_id})
+    service_interactions <- duplicate::Out<CustomerHasServiceInteraction>
+    service_edges <- AddE<CustomerHasServiceInteraction>({created_at: timestamp})::From(survivor)::To(service_interactions)
+    RETURN survivor
+
+// Move everything stored under a duplicate customer onto the canonical one,
+// record the alias and drop the duplicate customer node
+QUERY merge_customers(from_customer_id: String, to_customer_id: String, reason: String, timestamp: I64) =>
+    behaviors <- N<CustomerBehaviorMemory>::WHERE(_::{customer_id}::EQ(from_customer_id))::UPDATE({customer_id: to_customer_id})
+    preferences <- N<CustomerPreferenceMemory>::WHERE(_::{customer_id}::EQ(from_customer_id))::UPDATE({customer_id: to_customer_id})
+    desires <- N<CustomerDesireMemory>::WHERE(_::{customer_id}::EQ(from_customer_id))::UPDATE({customer_id: to_customer_id})
+    rules <- N<CustomerRuleMemory>::WHERE(_::{customer_id}::EQ(from_customer_id))::UPDATE({customer_id: to_customer_id})
+    feedback <- N<CustomerFeedbackMemory>::WHERE(_::{customer_id}::EQ(from_customer_id))::UPDATE({customer_id: to_customer_id})
+    product_interactions <- N<CustomerProductInteraction>::WHERE(_::{customer_id}::EQ(from_customer_id))::UPDATE({customer_id: to_customer_id})
+    service_interactions <- N<CustomerServiceInteraction>::WHERE(_::{customer_id}::EQ(from_customer_id))::UPDATE({customer_id: to_customer_id})
+    location_visits <- N<CustomerLocationVisit>::WHERE(_::{customer_id}::EQ(from_customer_id))::UPDATE({customer_id: to_customer_id})
+    communications <- N<CustomerBusinessCommunication>::WHERE(_::{customer_id}::EQ(from_customer_id))::UPDATE({customer_id: to_customer_id})
+    points <- N<PointsTransaction>::WHERE(_::{customer_id}::EQ(from_customer_id))::UPDATE({customer_id: to_customer_id})
+    survivor <- N<Customer>::WHERE(_::{customer_id}::EQ(to_customer_id))
+    duplicate <- N<Customer>::WHERE(_::{customer_id}::EQ(from_customer_id))
+    businesses <- duplicate::Out<CustomerOf>
+    business_edges <- AddE<CustomerOf>({
+        first_interaction: timestamp,
+        last_interaction: timestamp,
+        interaction_count: 0,
+        customer_status: "active",
+        consent_to_share: false
+    })::From(survivor)::To(businesses)
+    points_edges <- AddE<HasPointsTransaction>({created_at: timestamp})::From(survivor)::To(points)
+    alias <- AddN<CustomerAlias>({
+        alias_customer_id: from_customer_id,
+        customer_id: to_customer_id,
+        reason: reason,
+        merged_at: timestamp
+    })
+    DROP N<Customer>::WHERE(_::{customer_id}::EQ(from_customer_id))
+    RETURN alias, behaviors, preferences, desires, rules, feedback, product_interactions, service_interactions, location_visits, communications, points
+
+QUERY get_customer_alias(alias_customer_id: String) =>
+    alias <- N<CustomerAlias>::WHERE(_::{alias_customer_id}::EQ(alias_customer_id))
+    RETURN alias
+
+QUERY deactivate_preference(preference_id: String, updated_at: I64) =>
+    preference <- N<CustomerPreferenceMemory>::WHERE(_::{preference_id}::EQ(preference_id))::UPDATE({
+        is_active: false,
+        updated_at: updated_at
+    })
+    RETURN preference
+
+
+// ============================================================================
+// CONFIDENCE DECAY QUERIES - Age out stale preferences and behaviors
+// ============================================================================
+
+QUERY get_all_customer_preferences() =>
+    preferences <- N<CustomerPreferenceMemory>
+    RETURN preferences
+
+QUERY get_all_customer_behaviors() =>
+    behaviors <- N<CustomerBehaviorMemory>
+    RETURN behaviors
+
+QUERY apply_preference_decay(preference_id: String, confidence_score: F64, is_active: Boolean, decayed_at: I64) =>
+    preference <- N<CustomerPreferenceMemory>::WHERE(_::{preference_id}::EQ(preference_id))::UPDATE({
+        confidence_score: confidence_score,
+        is_active: is_active,
+        decayed_at: decayed_at
+    })
+    RETURN preference
+
+QUERY apply_behavior_decay(behavior_id: String, relevance_score: F64, is_active: Boolean, decayed_at: I64) =>
+    behavior <- N<CustomerBehaviorMemory>::WHERE(_::{behavior_id}::EQ(behavior_id))::UPDATE({
+        relevance_score: relevance_score,
+        is_active: is_active,
+        decayed_at: decayed_at
+    })
+    RETURN behavior
+
+// Embedding model metadata per collection
+QUERY get_embedding_metadata() =>
+    metadata <- N<EmbeddingCollectionMeta>
+    RETURN metadata
+
+QUERY add_embedding_metadata(memory_type: String, embedding_model: String, dimensions: I64, target_model: String, updated_at: I64) =>
+    metadata <- AddN<EmbeddingCollectionMeta>({
+        memory_type: memory_type,
+        embedding_model: embedding_model,
+        dimensions: dimensions,
+        target_model: target_model,
+        updated_at: updated_at
+    })
+    RETURN metadata
+
+QUERY update_embedding_metadata(memory_type: String, embedding_model: String, dimensions: I64, target_model: String, updated_at: I64) =>
+    metadata <- N<EmbeddingCollectionMeta>::WHERE(_::{memory_type}::EQ(memory_type))::UPDATE({
+        embedding_model: embedding_model,
+        dimensions: dimensions,
+        target_model: target_model,
+        updated_at: updated_at
+    })
+    RETURN metadata
+
+// Scheduled maintenance
+QUERY get_all_businesses() =>
+    businesses <- N<Business>
+    RETURN businesses
+
+// Embedding vectors whose memory node is gone (cascade deletes drop nodes only)
+QUERY count_orphan_embeddings() =>
+    products <- V<BusinessProductEmbedding>::WHERE(!EXISTS(_::In<HasProductEmbedding>))::COUNT
+    services <- V<BusinessServiceEmbedding>::WHERE(!EXISTS(_::In<HasServiceEmbedding>))::COUNT
+    locations <- V<BusinessLocationEmbedding>::WHERE(!EXISTS(_::In<HasLocationEmbedding>))::COUNT
+    hours <- V<BusinessHoursEmbedding>::WHERE(!EXISTS(_::In<HasHoursEmbedding>))::COUNT
+    social <- V<BusinessSocialEmbedding>::WHERE(!EXISTS(_::In<HasSocialEmbedding>))::COUNT
+    policies <- V<BusinessPolicyEmbedding>::WHERE(!EXISTS(_::In<HasPolicyEmbedding>))::COUNT
+    events <- V<BusinessEventEmbedding>::WHERE(!EXISTS(_::In<HasEventEmbedding>))::COUNT
+    information <- V<BusinessInformationEmbedding>::WHERE(!EXISTS(_::In<HasInformationEmbedding>))::COUNT
+    behaviors <- V<CustomerBehaviorEmbedding>::WHERE(!EXISTS(_::In<HasBehaviorEmbedding>))::COUNT
+    preferences <- V<CustomerPreferenceEmbedding>::WHERE(!EXISTS(_::In<HasPreferenceEmbedding>))::COUNT
+    desires <- V<CustomerDesireEmbedding>::WHERE(!EXISTS(_::In<HasDesireEmbedding>))::COUNT
+    rules <- V<CustomerRuleEmbedding>::WHERE(!EXISTS(_::In<HasRuleEmbedding>))::COUNT
+    feedback <- V<CustomerFeedbackEmbedding>::WHERE(!EXISTS(_::In<HasFeedbackEmbedding>))::COUNT
+    RETURN products, services, locations, hours, social, policies, events, information, behaviors, preferences, desires, rules, feedback
+
+QUERY delete_orphan_embeddings() =>
+    DROP V<BusinessProductEmbedding>::WHERE(!EXISTS(_::In<HasProductEmbedding>))
+    DROP V<BusinessServiceEmbedding>::WHERE(!EXISTS(_::In<HasServiceEmbedding>))
+    DROP V<BusinessLocationEmbedding>::WHERE(!EXISTS(_::In<HasLocationEmbedding>))
+    DROP V<BusinessHoursEmbedding>::WHERE(!EXISTS(_::In<HasHoursEmbedding>))
+    DROP V<BusinessSocialEmbedding>::WHERE(!EXISTS(_::In<HasSocialEmbedding>))
+    DROP V<BusinessPolicyEmbedding>::WHERE(!EXISTS(_::In<HasPolicyEmbedding>))
+    DROP V<BusinessEventEmbedding>::WHERE(!EXISTS(_::In<HasEventEmbedding>))
+    DROP V<BusinessInformationEmbedding>::WHERE(!EXISTS(_::In<HasInformationEmbedding>))
+    DROP V<CustomerBehaviorEmbedding>::WHERE(!EXISTS(_::In<HasBehaviorEmbedding>))
+    DROP V<CustomerPreferenceEmbedding>::WHERE(!EXISTS(_::In<HasPreferenceEmbedding>))
+    DROP V<CustomerDesireEmbedding>::WHERE(!EXISTS(_::In<HasDesireEmbedding>))
+    DROP V<CustomerRuleEmbedding>::WHERE(!EXISTS(_::In<HasRuleEmbedding>))
+    DROP V<CustomerFeedbackEmbedding>::WHERE(!EXISTS(_::In<HasFeedbackEmbedding>))
+    RETURN "Deleted orphaned embeddings"
+
+// Memories without their embedding vector (the embedding step of a create or update failed)
+QUERY find_business_memories_missing_embeddings(business_id: String) =>
+    products <- N<BusinessProductMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(!EXISTS(_::Out<HasProductEmbedding>))
+    services <- N<BusinessServiceMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(!EXISTS(_::Out<HasServiceEmbedding>))
+    locations <- N<BusinessLocationMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(!EXISTS(_::Out<HasLocationEmbedding>))
+    hours <- N<BusinessHoursMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(!EXISTS(_::Out<HasHoursEmbedding>))
+    social <- N<BusinessSocialMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(!EXISTS(_::Out<HasSocialEmbedding>))
+    policies <- N<BusinessPolicyMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(!EXISTS(_::Out<HasPolicyEmbedding>))
+    events <- N<BusinessEventMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(!EXISTS(_::Out<HasEventEmbedding>))
+    information <- N<BusinessInformationMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(!EXISTS(_::Out<HasInformationEmbedding>))
+    RETURN products, services, locations, hours, social, policies, events, information
+
+QUERY find_customer_memories_missing_embeddings(customer_id: String) =>
+    behaviors <- N<CustomerBehaviorMemory>::WHERE(_::{customer_id}::EQ(customer_id))::WHERE(!EXISTS(_::Out<HasBehaviorEmbedding>))
+    preferences <- N<CustomerPreferenceMemory>::WHERE(_::{customer_id}::EQ(customer_id))::WHERE(!EXISTS(_::Out<HasPreferenceEmbedding>))
+    desires <- N<CustomerDesireMemory>::WHERE(_::{customer_id}::EQ(customer_id))::WHERE(!EXISTS(_::Out<HasDesireEmbedding>))
+    rules <- N<CustomerRuleMemory>::WHERE(_::{customer_id}::EQ(customer_id))::WHERE(!EXISTS(_::Out<HasRuleEmbedding>))
+    feedback <- N<CustomerFeedbackMemory>::WHERE(_::{customer_id}::EQ(customer_id))::WHERE(!EXISTS(_::Out<HasFeedbackEmbedding>))
+    RETURN behaviors, preferences, desires, rules, feedback
+
+// Memory store statistics (get_memory_stats)
+QUERY count_memory_nodes() =>
+    businesses <- N<Business>::COUNT
+    customers <- N<Customer>::COUNT
+    products <- N<BusinessProductMemory>::COUNT
+    services <- N<BusinessServiceMemory>::COUNT
+    locations <- N<BusinessLocationMemory>::COUNT
+    hours <- N<BusinessHoursMemory>::COUNT
+    social <- N<BusinessSocialMemory>::COUNT
+    policies <- N<BusinessPolicyMemory>::COUNT
+    events <- N<BusinessEventMemory>::COUNT
+    information <- N<BusinessInformationMemory>::COUNT
+    behaviors <- N<CustomerBehaviorMemory>::COUNT
+    preferences <- N<CustomerPreferenceMemory>::COUNT
+    desires <- N<CustomerDesireMemory>::COUNT
+    rules <- N<CustomerRuleMemory>::COUNT
+    feedback <- N<CustomerFeedbackMemory>::COUNT
+    product_interactions <- N<CustomerProductInteraction>::COUNT
+    service_interactions <- N<CustomerServiceInteraction>::COUNT
+    location_visits <- N<CustomerLocationVisit>::COUNT
+    communications <- N<CustomerBusinessCommunication>::COUNT
+    navigation_hubs <- N<BusinessNavigationHub>::COUNT
+    waypoints <- N<NavigationWaypoint>::COUNT
+    paths <- N<DirectionPath>::COUNT
+    RETURN businesses, customers, products, services, locations, hours, social, policies, events, information, behaviors, preferences, desires, rules, feedback, product_interactions, service_interactions, location_visits, communications, navigation_hubs, waypoints, paths
+
+QUERY count_memory_vectors() =>
+    products <- V<BusinessProductEmbedding>::COUNT
+    services <- V<BusinessServiceEmbedding>::COUNT
+    locations <- V<BusinessLocationEmbedding>::COUNT
+    hours <- V<BusinessHoursEmbedding>::COUNT
+    social <- V<BusinessSocialEmbedding>::COUNT
+    policies <- V<BusinessPolicyEmbedding>::COUNT
+    events <- V<BusinessEventEmbedding>::COUNT
+    information <- V<BusinessInformationEmbedding>::COUNT
+    behaviors <- V<CustomerBehaviorEmbedding>::COUNT
+    preferences <- V<CustomerPreferenceEmbedding>::COUNT
+    desires <- V<CustomerDesireEmbedding>::COUNT
+    rules <- V<CustomerRuleEmbedding>::COUNT
+    feedback <- V<CustomerFeedbackEmbedding>::COUNT
+    product_interactions <- V<CustomerProductInteractionEmbedding>::COUNT
+    service_interactions <- V<CustomerServiceInteractionEmbedding>::COUNT
+    location_visits <- V<CustomerLocationVisitEmbedding>::COUNT
+    communications <- V<CustomerCommunicationEmbedding>::COUNT
+    navigation_hubs <- V<BusinessNavigationEmbedding>::COUNT
+    waypoints <- V<NavigationWaypointEmbedding>::COUNT
+    paths <- V<DirectionPathEmbedding>::COUNT
+    RETURN products, services, locations, hours, social, policies, events, information, behaviors, preferences, desires, rules, feedback, product_interactions, service_interactions, location_visits, communications, navigation_hubs, waypoints, paths
+
+// Most recently written node of each type
+QUERY get_latest_memory_activity() =>
+    businesses <- N<Business>::ORDER<Desc>(_::{created_at})::RANGE(0, 1)
+    customers <- N<Customer>::ORDER<Desc>(_::{created_at})::RANGE(0, 1)
+    products <- N<BusinessProductMemory>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    services <- N<BusinessServiceMemory>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    locations <- N<BusinessLocationMemory>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    hours <- N<BusinessHoursMemory>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    social <- N<BusinessSocialMemory>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    policies <- N<BusinessPolicyMemory>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    events <- N<BusinessEventMemory>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    information <- N<BusinessInformationMemory>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    behaviors <- N<CustomerBehaviorMemory>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    preferences <- N<CustomerPreferenceMemory>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    desires <- N<CustomerDesireMemory>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    rules <- N<CustomerRuleMemory>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    feedback <- N<CustomerFeedbackMemory>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    product_interactions <- N<CustomerProductInteraction>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    service_interactions <- N<CustomerServiceInteraction>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    location_visits <- N<CustomerLocationVisit>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    communications <- N<CustomerBusinessCommunication>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    navigation_hubs <- N<BusinessNavigationHub>::ORDER<Desc>(_::{updated_at})::RANGE(0, 1)
+    waypoints <- N<NavigationWaypoint>::ORDER<Desc>(_::{created_at})::RANGE(0, 1)
+    paths <- N<DirectionPath>::ORDER<Desc>(_::{created_at})::RANGE(0, 1)
+    RETURN businesses, customers, products, services, locations, hours, social, policies, events, information, behaviors, preferences, desires, rules, feedback, product_interactions, service_interactions, location_visits, communications, navigation_hubs, waypoints, paths
+
+QUERY count_business_memories(business_id: String) =>
+    products <- N<BusinessProductMemory>::WHERE(_::{business_id}::EQ(business_id))::COUNT
+    services <- N<BusinessServiceMemory>::WHERE(_::{business_id}::EQ(business_id))::COUNT
+    locations <- N<BusinessLocationMemory>::WHERE(_::{business_id}::EQ(business_id))::COUNT
+    hours <- N<BusinessHoursMemory>::WHERE(_::{business_id}::EQ(business_id))::COUNT
+    social <- N<BusinessSocialMemory>::WHERE(_::{business_id}::EQ(business_id))::COUNT
+    policies <- N<BusinessPolicyMemory>::WHERE(_::{business_id}::EQ(business_id))::COUNT
+    events <- N<BusinessEventMemory>::WHERE(_::{business_id}::EQ(business_id))::COUNT
+    information <- N<BusinessInformationMemory>::WHERE(_::{business_id}::EQ(business_id))::COUNT
+    communications <- N<CustomerBusinessCommunication>::WHERE(_::{business_id}::EQ(business_id))::COUNT
+    business <- N<Business>::WHERE(_::{business_id}::EQ(business_id))
+    customers <- business::In<CustomerOf>::COUNT
+    RETURN products, services, locations, hours, social, policies, events, information, communications, customers
+
+// Inventory (adjust_product_stock / get_low_stock_products)
+QUERY update_product_stock(
+    business_id: String,
+    product_id: String,
+    stock_quantity: I64,
+    reorder_threshold: I64,
+    availability: String,
+    updated_at: I64
+) =>
+    product <- N<BusinessProductMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(_::{product_id}::EQ(product_id))::UPDATE({
+        stock_quantity: stock_quantity,
+        reorder_threshold: reorder_threshold,
+        availability: availability,
+        updated_at: updated_at
+    })
+    RETURN product
+
+QUERY get_stock_tracked_products(business_id: String) =>
+    products <- N<BusinessProductMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(_::{stock_quantity}::GTE(0))
+    RETURN products
+
+// Price history (update_business_memory / get_price_history)
+QUERY add_price_change(
+    business_id: String,
+    entity_id: String,
+    memory_type: String,
+    entity_name: String,
+    old_price: F64,
+    new_price: F64,
+    old_currency: String,
+    new_currency: String,
+    source: String,
+    changed_at: I64
+) =>
+    change <- AddN<PriceChange>({
+        business_id: business_id,
+        entity_id: entity_id,
+        memory_type: memory_type,
+        entity_name: entity_name,
+        old_price: old_price,
+        new_price: new_price,
+        old_currency: old_currency,
+        new_currency: new_currency,
+        source: source,
+        changed_at: changed_at
+    })
+    RETURN change
+
+QUERY get_price_history(business_id: String, entity_id: String) =>
+    changes <- N<PriceChange>::WHERE(_::{entity_id}::EQ(entity_id))::WHERE(_::{business_id}::EQ(business_id))
+    RETURN changes
+
+QUERY get_business_price_history(business_id: String) =>
+    changes <- N<PriceChange>::WHERE(_::{business_id}::EQ(business_id))
+    RETURN changes
+
+// Loyalty (set_loyalty_rule / get_customer_points / redeem_points)
+QUERY add_loyalty_rule(
+    business_id: String,
+    points_per_unit: F64,
+    currency: String,
+    min_purchase: F64,
+    point_value: F64,
+    active: Boolean,
+    created_at: I64
+) =>
+    rule <- AddN<LoyaltyRule>({
+        business_id: business_id,
+        points_per_unit: points_per_unit,
+        currency: currency,
+        min_purchase: min_purchase,
+        point_value: point_value,
+        active: active,
+        created_at: created_at,
+        updated_at: created_at
+    })
+    business <- N<Business>::WHERE(_::{business_id}::EQ(business_id))
+    rule_edge <- AddE<HasLoyaltyRule>({
+        created_at: created_at
+    })::From(business)::To(rule)
+    RETURN rule
+
+QUERY update_loyalty_rule(
+    business_id: String,
+    points_per_unit: F64,
+    currency: String,
+    min_purchase: F64,
+    point_value: F64,
+    active: Boolean,
+    updated_at: I64
+) =>
+    rule <- N<LoyaltyRule>::WHERE(_::{business_id}::EQ(business_id))::UPDATE({
+        points_per_unit: points_per_unit,
+        currency: currency,
+        min_purchase: min_purchase,
+        point_value: point_value,
+        active: active,
+        updated_at: updated_at
+    })
+    RETURN rule
+
+QUERY get_loyalty_rule(business_id: String) =>
+    rule <- N<LoyaltyRule>::WHERE(_::{business_id}::EQ(business_id))
+    RETURN rule
+
+QUERY add_points_transaction(
+    transaction_id: String,
+    business_id: String,
+    customer_id: String,
+    points: I64,
+    kind: String,
+    interaction_id: String,
+    amount: F64,
+    currency: String,
+    reason: String,
+    created_at: I64
+) =>
+    transaction <- AddN<PointsTransaction>({
+        transaction_id: transaction_id,
+        business_id: business_id,
+        customer_id: customer_id,
+        points: points,
+        kind: kind,
+        interaction_id: interaction_id,
+        amount: amount,
+        currency: currency,
+        reason: reason,
+        created_at: created_at
+    })
+    customer <- N<Customer>::WHERE(_::{customer_id}::EQ(customer_id))
+    transaction_edge <- AddE<HasPointsTransaction>({
+        created_at: created_at
+    })::From(customer)::To(transaction)
+    RETURN transaction
+
+QUERY get_customer_points_transactions(business_id: String, customer_id: String) =>
+    transactions <- N<PointsTransaction>::WHERE(_::{customer_id}::EQ(customer_id))::WHERE(_::{business_id}::EQ(business_id))
+    RETURN transactions
+
+// iCal import (import_events_ics)
+QUERY update_business_event_fields(
+    business_id: String,
+    event_id: String,
+    event_name: String,
+    event_type: String,
+    start_date: I64,
+    end_date: I64,
+    description: String,
+    location: String,
+    capacity: I32,
+    registration_required: Boolean,
+    registered_count: I32,
+    status: String,
+    tags: [String],
+    text_description: String,
+    composite_text: String,
+    new_embedding: [F64],
+    timestamp: I64
+) =>
+    memory <- N<BusinessEventMemory>::WHERE(_::{business_id}::EQ(business_id))::WHERE(_::{event_id}::EQ(event_id))
+    updated <- memory::UPDATE({
+        event_name: event_name,
+        event_type: event_type,
+        start_date: start_date,
+        end_date: end_date,
+        description: description,
+        location: location,
+        capacity: capacity,
+        registration_required: registration_required,
+        registered_count: registered_count,
+        status: status,
+        tags: tags,
+        text_description: text_description,
+        updated_at: timestamp
+    })
+    DROP memory::Out<HasEventEmbedding>
+    DROP memory::OutE<HasEventEmbedding>
+    vec <- AddV<BusinessEventEmbedding>(new_embedding, {composite_embedding_text: composite_text, event_name: event_name, type_context: event_type})
+    edge <- AddE<HasEventEmbedding>({created_at: timestamp})::From(updated)::To(vec)
+    RETURN updated
+
+// Interaction fields (update_interaction; embedding unchanged)
+QUERY get_customer_product_interaction_node(memory_id: ID) =>
+    interaction <- N<CustomerProductInteraction>(memory_id)
+    RETURN interaction
+
+QUERY get_customer_service_interaction_node(memory_id: ID) =>
+    interaction <- N<CustomerServiceInteraction>(memory_id)
+    RETURN interaction
+
+QUERY update_customer_product_interaction_fields(memory_id: ID, rating: I32, resolution_status: String, purchase_amount: F64, timestamp: I64) =>
+    memory <- N<CustomerProductInteraction>(memory_id)::UPDATE({
+        rating: rating,
+        resolution_status: resolution_status,
+        purchase_amount: purchase_amount,
+        updated_at: timestamp
+    })
+    RETURN memory
+
+QUERY update_customer_service_interaction_fields(memory_id: ID, satisfaction_rating: I32, outcome: String, cost_actual: F64, timestamp: I64) =>
+    memory <- N<CustomerServiceInteraction>(memory_id)::UPDATE({
+        satisfaction_rating: satisfaction_rating,
+        outcome: outcome,
+        cost_actual: cost_actual,
+        updated_at: timestamp
+    })
+    RETURN memory
+
+// Issue queue (list_open_issues, resolve_issue)
+QUERY get_customer_feedback_node(memory_id: ID) =>
+    feedback <- N<CustomerFeedbackMemory>(memory_id)
+    RETURN feedback
+
+QUERY resolve_product_interaction_issue(memory_id: ID, timestamp: I64) =>
+    memory <- N<CustomerProductInteraction>(memory_id)::UPDATE({
+        resolution_status: "resolved",
+        resolved_at: timestamp,
+        updated_at: timestamp
+    })
+    RETURN memory
+
+QUERY resolve_customer_feedback(memory_id: ID, timestamp: I64) =>
+    memory <- N<CustomerFeedbackMemory>(memory_id)::UPDATE({
+        resolved: true,
+        resolved_at: timestamp,
+        updated_at: timestamp
+    })
+    RETURN memory
+
+// Memory importance and pinning (pin_memory, update_customer_memory)
+QUERY set_customer_behavior_importance(memory_id: ID, importance: I32, timestamp: I64) =>
+    memory <- N<CustomerBehaviorMemory>(memory_id)::UPDATE({importance: importance, updated_at: timestamp})
+    RETURN memory
+
+QUERY pin_customer_behavior_memory(memory_id: ID, pinned: Boolean, timestamp: I64) =>
+    memory <- N<CustomerBehaviorMemory>(memory_id)::UPDATE({pinned: pinned, updated_at: timestamp})
+    RETURN memory
+
+QUERY set_customer_preference_importance(memory_id: ID, importance: I32, timestamp: I64) =>
+    memory <- N<CustomerPreferenceMemory>(memory_id)::UPDATE({importance: importance, updated_at: timestamp})
+    RETURN memory
+
+QUERY pin_customer_preference_memory(memory_id: ID, pinned: Boolean, timestamp: I64) =>
+    memory <- N<CustomerPreferenceMemory>(memory_id)::UPDATE({pinned: pinned, updated_at: timestamp})
+    RETURN memory
+
+QUERY set_customer_desire_importance(memory_id: ID, importance: I32, timestamp: I64) =>
+    memory <- N<CustomerDesireMemory>(memory_id)::UPDATE({importance: importance, updated_at: timestamp})
+    RETURN memory
+
+QUERY pin_customer_desire_memory(memory_id: ID, pinned: Boolean, timestamp: I64) =>
+    memory <- N<CustomerDesireMemory>(memory_id)::UPDATE({pinned: pinned, updated_at: timestamp})
+    RETURN memory
+
+QUERY set_customer_rule_importance(memory_id: ID, importance: I32, timestamp: I64) =>
+    memory <- N<CustomerRuleMemory>(memory_id)::UPDATE({importance: importance, updated_at: timestamp})
+    RETURN memory
+
+QUERY pin_customer_rule_memory(memory_id: ID, pinned: Boolean, timestamp: I64) =>
+    memory <- N<CustomerRuleMemory>(memory_id)::UPDATE({pinned: pinned, updated_at: timestamp})
+    RETURN memory
+
+QUERY set_customer_feedback_importance(memory_id: ID, importance: I32, timestamp: I64) =>
+    memory <- N<CustomerFeedbackMemory>(memory_id)::UPDATE({importance: importance, updated_at: timestamp})
+    RETURN memory
+
+QUERY pin_customer_feedback_memory(memory_id: ID, pinned: Boolean, timestamp: I64) =>
+    memory <- N<CustomerFeedbackMemory>(memory_id)::UPDATE({pinned: pinned, updated_at: timestamp})
+    RETURN memory
+
+// Graph export (export_graph_viz): nodes and the edges between them
+QUERY graph_business(business_id: String) =>
+    business <- N<Business>::WHERE(_::{business_id}::EQ(business_id))
+    products <- N<BusinessProductMemory>::WHERE(_::{business_id}::EQ(business_id))
+    services <- N<BusinessServiceMemory>::WHERE(_::{business_id}::EQ(business_id))
+    locations <- N<BusinessLocationMemory>::WHERE(_::{business_id}::EQ(business_id))
+    hours <- N<BusinessHoursMemory>::WHERE(_::{business_id}::EQ(business_id))
+    social <- N<BusinessSocialMemory>::WHERE(_::{business_id}::EQ(business_id))
+    policies <- N<BusinessPolicyMemory>::WHERE(_::{business_id}::EQ(business_id))
+    events <- N<BusinessEventMemory>::WHERE(_::{business_id}::EQ(business_id))
+    information <- N<BusinessInformationMemory>::WHERE(_::{business_id}::EQ(business_id))
+    customers <- business::In<CustomerOf>
+    collaborators <- business::Out<CollaboratesWith>
+    navigation_hubs <- business::Out<HasNavigation>
+    owned_by <- business::InE<OwnedBy>
+    shared_with <- business::InE<SharedWith>
+    customer_of <- business::InE<CustomerOf>
+    collaborates_with <- business::OutE<CollaboratesWith>
+    has_navigation <- business::OutE<HasNavigation>
+    related_information <- information::OutE<RelatedInformation>
+    prerequisite_for <- information::OutE<PrerequisiteFor>
+    part_of_series <- information::OutE<PartOfSeries>
+    references <- information::OutE<References>
+    information_about_product <- information::OutE<InformationAboutProduct>
+    information_about_service <- information::OutE<InformationAboutService>
+    information_for_location <- information::OutE<InformationForLocation>
+    information_for_event <- information::OutE<InformationForEvent>
+    RETURN business, products, services, locations, hours, social, policies, events, information, customers, collaborators, navigation_hubs, owned_by, shared_with, customer_of, collaborates_with, has_navigation, related_information, prerequisite_for, part_of_series, references, information_about_product, information_about_service, information_for_location, information_for_event
+
+QUERY graph_customer(customer_id: String) =>
+    customer <- N<Customer>::WHERE(_::{customer_id}::EQ(customer_id))
+    businesses <- customer::Out<CustomerOf>
+    behaviors <- N<CustomerBehaviorMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    preferences <- N<CustomerPreferenceMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    desires <- N<CustomerDesireMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    rules <- N<CustomerRuleMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    feedback <- N<CustomerFeedbackMemory>::WHERE(_::{customer_id}::EQ(customer_id))
+    product_interactions <- N<CustomerProductInteraction>::WHERE(_::{customer_id}::EQ(customer_id))
+    service_interactions <- N<CustomerServiceInteraction>::WHERE(_::{customer_id}::EQ(customer_id))
+    location_visits <- N<CustomerLocationVisit>::WHERE(_::{customer_id}::EQ(customer_id))
+    communications <- N<CustomerBusinessCommunication>::WHERE(_::{customer_id}::EQ(customer_id))
+    products <- product_interactions::Out<InteractionAboutProduct>
+    services <- service_interactions::Out<InteractionAboutService>
+    locations <- location_visits::Out<VisitAtLocation>
+    policies <- communications::Out<CommunicationAboutPolicy>
+    customer_of <- customer::OutE<CustomerOf>
+    about_customer <- customer::InE<AboutCustomer>
+    behavior_interaction_links <- behaviors::OutE<CustomerHasProductInteraction>
+    behavior_visit_links <- behaviors::OutE<CustomerHasLocationVisit>
+    behavior_communication_links <- behaviors::OutE<CustomerHasCommunication>
+    preference_interaction_links <- preferences::OutE<CustomerHasServiceInteraction>
+    product_interaction_links <- product_interactions::OutE<InteractionAboutProduct>
+    service_interaction_links <- service_interactions::OutE<InteractionAboutService>
+    location_visit_links <- location_visits::OutE<VisitAtLocation>
+    communication_links <- communications::OutE<CommunicationAboutPolicy>
+    RETURN customer, businesses, behaviors, preferences, desires, rules, feedback, product_interactions, service_interactions, location_visits, communications, products, services, locations, policies, customer_of, about_customer, behavior_interaction_links, behavior_visit_links, behavior_communication_links, preference_interaction_links, product_interaction_links, service_interaction_links, location_visit_links, communication_links
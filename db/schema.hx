@@ -27,6 +27,14 @@ N::Customer {
     metadata: String DEFAULT "{}"       // JSON for additional fields - optional
 }
 
+// Customer Alias Node - A duplicate customer ID merged into a canonical customer
+N::CustomerAlias {
+    INDEX alias_customer_id: String,    // Customer ID that was merged away (REQUIRED)
+    customer_id: String,                // Canonical customer it now refers to (REQUIRED)
+    reason: String DEFAULT "",          // Why they were merged - optional, e.g. "same phone on WhatsApp and web"
+    merged_at: I64 DEFAULT NOW          // When the merge happened
+}
+
 // ============================================================================
 // BUSINESS MEMORY NODES
 // Each business aspect (products, services, location, etc.) gets its own node
@@ -40,7 +48,9 @@ N::BusinessProductMemory {
     product_category: String DEFAULT "",    // Category (electronics, clothing, etc.) - optional
     price: F64 DEFAULT 0.0,                // Product price - optional, defaults to 0.0
     currency: String DEFAULT "",            // Currency code - optional, empty if not specified
-    availability: String DEFAULT "",        // Availability status - optional, empty if not specified
+    availability: String DEFAULT "",        // Availability status - optional, derived from stock_quantity while stock is tracked
+    stock_quantity: I64 DEFAULT -1,         // Units in stock - optional, -1 when stock isn't tracked
+    reorder_threshold: I64 DEFAULT 0,       // Low-stock level for get_low_stock_products - optional
     description: String DEFAULT "",         // Detailed product description - optional
     features: [String],                     // List of product features (REQUIRED - use empty array if none)
     specifications: String DEFAULT "{}",    // Technical specs as JSON string - optional
@@ -131,6 +141,21 @@ E::HasServiceEmbedding {
     }
 }
 
+// Price Change Node - One entry per product/service price or currency change
+// Kept when the product/service is deleted so its price history stays answerable
+N::PriceChange {
+    business_id: String,                    // Business identifier (REQUIRED)
+    INDEX entity_id: String,                // product_id or service_id (REQUIRED)
+    memory_type: String,                    // "product" or "service" (REQUIRED)
+    entity_name: String DEFAULT "",         // Product/service name when the price changed
+    old_price: F64 DEFAULT 0.0,             // Price before the change
+    new_price: F64 DEFAULT 0.0,             // Price after the change
+    old_currency: String DEFAULT "",        // Currency before the change
+    new_currency: String DEFAULT "",        // Currency after the change
+    source: String DEFAULT "",              // Tool that made the change, e.g. "update_business_memory"
+    changed_at: I64 DEFAULT NOW             // When the change was made
+}
+
 // Location Memory Node - Business location information
 N::BusinessLocationMemory {
     business_id: String,                    // Business identifier (REQUIRED)
@@ -291,6 +316,7 @@ N::BusinessPolicyMemory {
     effective_date: I64 DEFAULT NOW,       // When policy takes effect - optional, defaults to now
     version: String DEFAULT "",            // Policy version - optional, empty if not specified
     is_active: Boolean DEFAULT false,       // Whether policy is currently active - optional, safer to default false
+    superseded_by: String DEFAULT "",       // policy_id of the version that replaced this one - empty if current
     tags: [String],                         // Search tags (REQUIRED - use empty array if none)
     created_at: I64 DEFAULT NOW,           // Auto-generated timestamp
     updated_at: I64 DEFAULT NOW,           // Auto-generated timestamp
@@ -338,6 +364,8 @@ N::BusinessEventMemory {
     location: String DEFAULT "",               // Event location or virtual link - optional
     capacity: I32 DEFAULT 0,                   // Maximum capacity - optional, 0 if not specified
     registration_required: Boolean DEFAULT false, // Whether registration is needed - optional, defaults to false
+    registered_count: I32 DEFAULT 0,           // Registrations so far - optional, 0 if not specified
+    status: String DEFAULT "scheduled",        // Lifecycle: "scheduled", "closed" (ended) or "cancelled"
     tags: [String],                            // Search tags (REQUIRED - use empty array if none)
     created_at: I64 DEFAULT NOW,              // Auto-generated timestamp
     updated_at: I64 DEFAULT NOW,              // Auto-generated timestamp
@@ -513,6 +541,11 @@ N::CustomerBehaviorMemory {
     channel: String DEFAULT "",            // Channel - optional, empty if not specified
     duration_seconds: I32 DEFAULT 0,       // How long the behavior lasted - optional, defaults to 0
     metadata: String DEFAULT "{}",         // Additional structured data as JSON - optional
+    relevance_score: F64 DEFAULT 1.0,      // Decays with age (see apply_decay)
+    is_active: Boolean DEFAULT true,       // False once relevance decays below threshold
+    decayed_at: I64 DEFAULT 0,             // Last decay run (0 = never decayed)
+    importance: I32 DEFAULT 0,             // 0-5; 5 = critical fact that must surface first (see pin_memory)
+    pinned: Boolean DEFAULT false,         // Pinned memories rank ahead of everything else
     created_at: I64 DEFAULT NOW,          // Auto-generated timestamp
     updated_at: I64 DEFAULT NOW,          // Auto-generated timestamp
     text_description: String DEFAULT ""    // Natural language description - optional
@@ -560,7 +593,10 @@ N::CustomerPreferenceMemory {
     evidence_count: I32 DEFAULT 0,          // How many times observed - optional, 0 if not specified
     last_evidence: I64 DEFAULT NOW,         // When preference was last observed - optional, defaults to now
     confidence_score: F64 DEFAULT 0.0,      // Confidence score - optional, 0.0 if not specified
+    decayed_at: I64 DEFAULT 0,              // Last confidence decay run (0 = never decayed)
     source_channels: [String],               // Where this preference was observed (REQUIRED - use empty array if none)
+    importance: I32 DEFAULT 0,             // 0-5; 5 = critical fact that must surface first (see pin_memory)
+    pinned: Boolean DEFAULT false,         // Pinned memories rank ahead of everything else
     created_at: I64 DEFAULT NOW,            // Auto-generated timestamp
     updated_at: I64 DEFAULT NOW,            // Auto-generated timestamp
     text_description: String DEFAULT ""      // Natural language description - optional
@@ -607,6 +643,8 @@ N::CustomerDesireMemory {
     timeframe: String DEFAULT "",              // "immediate", "short_term", "long_term" - empty if not specified
     budget_range: String DEFAULT "",           // Budget consideration if mentioned
     is_active: Boolean DEFAULT false,          // Whether desire is still relevant - safer to default false
+    importance: I32 DEFAULT 0,             // 0-5; 5 = critical fact that must surface first (see pin_memory)
+    pinned: Boolean DEFAULT false,         // Pinned memories rank ahead of everything else
     created_at: I64 DEFAULT NOW,              // Timestamp
     updated_at: I64 DEFAULT NOW,              // Last update timestamp
     text_description: String DEFAULT ""       // Natural language description for embeddings
@@ -653,6 +691,8 @@ N::CustomerRuleMemory {
     enforcement: String DEFAULT "",              // "strict", "flexible", "guideline" - empty if not specified
     exceptions: [String],                       // When rule doesn't apply (must remain required - arrays can't have defaults)
     is_active: Boolean DEFAULT false,           // Whether rule is still in effect - safer to default false
+    importance: I32 DEFAULT 0,             // 0-5; 5 = critical fact that must surface first (see pin_memory)
+    pinned: Boolean DEFAULT false,         // Pinned memories rank ahead of everything else
     created_at: I64 DEFAULT NOW,               // Timestamp
     updated_at: I64 DEFAULT NOW,               // Last update timestamp
     text_description: String DEFAULT ""         // Natural language description for embeddings
@@ -699,6 +739,9 @@ N::CustomerFeedbackMemory {
     channel: String DEFAULT "",                // Where feedback was given - empty if not specified
     response_required: Boolean DEFAULT false,  // Whether business response is needed
     resolved: Boolean DEFAULT false,           // Whether feedback has been addressed
+    resolved_at: I64 DEFAULT 0,                // When it was resolved - 0 while open
+    importance: I32 DEFAULT 0,             // 0-5; 5 = critical fact that must surface first (see pin_memory)
+    pinned: Boolean DEFAULT false,         // Pinned memories rank ahead of everything else
     created_at: I64 DEFAULT NOW,              // Timestamp
     updated_at: I64 DEFAULT NOW,              // Last update timestamp
     text_description: String DEFAULT ""       // Natural language description for embeddings
@@ -753,6 +796,7 @@ N::CustomerProductInteraction {
     currency: String DEFAULT "",                 // Currency - empty if not specified
     issue_category: String DEFAULT "",           // For dislikes: "quality", "price", "functionality", "service"
     resolution_status: String DEFAULT "",        // For issues: "resolved", "pending", "escalated" - empty if not specified
+    resolved_at: I64 DEFAULT 0,                  // When the issue was resolved - 0 while open
     created_at: I64 DEFAULT NOW,                 // Timestamp
     updated_at: I64 DEFAULT NOW,                 // Last update timestamp
     text_reason: String DEFAULT ""               // Natural language reason for interaction
@@ -944,6 +988,54 @@ E::HasLocationVisitEmbedding {
     }
 }
 
+// ============================================================================
+// LOYALTY (points accrued on purchases, redeemed by customers)
+// ============================================================================
+
+// Loyalty Rule Node - One per business; how purchases earn points
+N::LoyaltyRule {
+    INDEX business_id: String,              // Business identifier (REQUIRED)
+    points_per_unit: F64 DEFAULT 1.0,       // Points per unit of currency spent
+    currency: String DEFAULT "",            // Currency amounts are converted into first - empty to count any currency as is
+    min_purchase: F64 DEFAULT 0.0,          // Smallest purchase that earns points
+    point_value: F64 DEFAULT 0.01,          // Currency value of one point when redeemed
+    active: Boolean DEFAULT true,           // Accrual switched on
+    created_at: I64 DEFAULT NOW,
+    updated_at: I64 DEFAULT NOW
+}
+
+// Points Transaction Node - Ledger entry; a customer's balance is the sum of their entries
+N::PointsTransaction {
+    INDEX transaction_id: String,           // Unique transaction identifier (REQUIRED)
+    business_id: String,                    // Business identifier (REQUIRED)
+    customer_id: String,                    // Customer identifier (REQUIRED)
+    points: I64,                            // Positive for accruals, negative for redemptions (REQUIRED)
+    kind: String DEFAULT "",                // "accrual" or "redemption"
+    interaction_id: String DEFAULT "",      // Purchase interaction that earned the points - empty for redemptions
+    amount: F64 DEFAULT 0.0,                // Purchase amount that earned the points
+    currency: String DEFAULT "",            // Currency of amount
+    reason: String DEFAULT "",              // Free text, e.g. "Free coffee"
+    created_at: I64 DEFAULT NOW
+}
+
+// Link business to its loyalty rule
+E::HasLoyaltyRule {
+    From: Business,
+    To: LoyaltyRule,
+    Properties: {
+        created_at: I64
+    }
+}
+
+// Link customer to their points ledger entries
+E::HasPointsTransaction {
+    From: Customer,
+    To: PointsTransaction,
+    Properties: {
+        created_at: I64
+    }
+}
+
 // ============================================================================
 // DATA ISOLATION & COLLABORATION EDGES
 // ============================================================================
@@ -1077,6 +1169,19 @@ E::HasCommunicationEmbedding {
     }
 }
 
+// ============================================================================
+// EMBEDDING METADATA
+// ============================================================================
+
+// Embedding model that produced a collection's vectors (one node per memory type)
+N::EmbeddingCollectionMeta {
+    INDEX memory_type: String,                 // Collection key, e.g. "products", "preferences", "waypoints"
+    embedding_model: String,                   // Model name recorded with the collection's vectors
+    dimensions: I64 DEFAULT 0,                 // Vector dimensions - 0 if unknown
+    target_model: String DEFAULT "",           // Model being migrated to - empty when no migration is running
+    updated_at: I64 DEFAULT NOW                // Auto-generated timestamp
+}
+
 // ============================================================================
 // UNIVERSAL BUSINESS NAVIGATION SYSTEM (Multi-Tenant Flexible)
 // For LLM-powered customer direction and guidance via MCP server
@@ -1283,6 +1388,16 @@ V::DirectionPathEmbedding {
     embedding_version: String DEFAULT "1.0"        // Version for tracking updates
 }
 
+// Floor of an indoor navigation hub; groups waypoints by floor_level
+N::NavigationFloor {
+    INDEX floor_id: String,                         // Required
+    navigation_id: String,                          // Links to BusinessNavigationHub
+    floor_level: I32 DEFAULT 0,                     // Matches NavigationWaypoint.floor_level (0 = ground, negative = basement)
+    floor_name: String DEFAULT "",                  // "Ground Floor", "Mezzanine", "Level 3 - Offices"
+    description: String DEFAULT "",                 // "Food court and restrooms"
+    created_at: I64 DEFAULT NOW
+}
+
 // ============================================================================
 // NAVIGATION SYSTEM EDGES
 // ============================================================================
@@ -1330,6 +1445,35 @@ E::PathThroughWaypoint {
     }
 }
 
+// Link navigation hub to its floors
+E::HasFloor {
+    From: BusinessNavigationHub,
+    To: NavigationFloor,
+    Properties: {
+        created_at: I64
+    }
+}
+
+// Group waypoints on a floor
+E::FloorHasWaypoint {
+    From: NavigationFloor,
+    To: NavigationWaypoint,
+    Properties: {
+        created_at: I64
+    }
+}
+
+// Vertical connection between waypoints on different floors (one direction)
+E::FloorConnector {
+    From: NavigationWaypoint,
+    To: NavigationWaypoint,
+    Properties: {
+        connector_type: String,     // "elevator", "escalator", "ramp", "stairs"
+        is_accessible: Boolean,     // Usable with mobility aids
+        created_at: I64
+    }
+}
+
 // Link navigation hub to its embedding
 E::HasNavigationEmbedding {
     From: BusinessNavigationHub,
@@ -0,0 +1,168 @@
+//! Memory access log
+//!
+//! Records which memories each read tool call returned, keyed by the MCP
+//! request ID and the customer it concerned, so "what data did the agent use
+//! when it told the customer X?" can be answered afterwards. Recent entries
+//! are kept in memory; with a path configured, every entry is also appended
+//! to a JSONL file that serves as the full history.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, VecDeque};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Owner fields that identify who was asked about, not which memory was read
+const OWNER_FIELDS: &[&str] = &["business_id", "customer_id", "navigation_id"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessEntry {
+    pub tool_call_id: String,
+    pub timestamp: i64,
+    pub session_id: String,
+    pub tool: String,
+    pub customer_id: Option<String>,
+    pub business_id: Option<String>,
+    pub memory_ids: Vec<String>,
+}
+
+/// Filter for [`AccessLog::query`]
+#[derive(Debug, Default)]
+pub struct AccessQuery<'a> {
+    pub customer_id: Option<&'a str>,
+    pub session_id: Option<&'a str>,
+    pub tool_call_id: Option<&'a str>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+}
+
+impl AccessQuery<'_> {
+    fn matches(&self, entry: &AccessEntry) -> bool {
+        self.customer_id.is_none_or(|c| entry.customer_id.as_deref() == Some(c))
+            && self.session_id.is_none_or(|s| entry.session_id == s)
+            && self.tool_call_id.is_none_or(|id| entry.tool_call_id == id)
+            && self.since.is_none_or(|t| entry.timestamp >= t)
+            && self.until.is_none_or(|t| entry.timestamp <= t)
+    }
+}
+
+pub struct AccessLog {
+    recent: Mutex<VecDeque<AccessEntry>>,
+    capacity: usize,
+    path: Option<PathBuf>,
+}
+
+impl AccessLog {
+    pub fn new(path: Option<PathBuf>, capacity: usize) -> Self {
+        AccessLog {
+            recent: Mutex::new(VecDeque::new()),
+            capacity,
+            path,
+        }
+    }
+
+    pub fn record(&self, entry: AccessEntry) {
+        if let Some(path) = &self.path {
+            let line = serde_json::to_string(&entry).unwrap_or_default();
+            let written = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| writeln!(file, "{}", line));
+            if let Err(e) = written {
+                tracing::warn!("Failed to append access log {}: {}", path.display(), e);
+            }
+        }
+
+        let mut recent = self.recent.lock().unwrap();
+        if recent.len() >= self.capacity.max(1) {
+            recent.pop_front();
+        }
+        recent.push_back(entry);
+    }
+
+    /// Matching entries, newest first
+    pub fn query(&self, filter: &AccessQuery, limit: usize) -> Vec<AccessEntry> {
+        let mut entries: Vec<AccessEntry> = match self.path.as_ref().and_then(|p| std::fs::File::open(p).ok()) {
+            Some(file) => BufReader::new(file)
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| serde_json::from_str::<AccessEntry>(&line).ok())
+                .filter(|e| filter.matches(e))
+                .collect(),
+            None => self.recent.lock().unwrap().iter().filter(|e| filter.matches(e)).cloned().collect(),
+        };
+        entries.reverse();
+        entries.truncate(limit);
+        entries
+    }
+}
+
+/// Memory IDs in a tool result: `id` and `*_id` strings other than owner IDs
+pub fn memory_ids(result: &Value) -> Vec<String> {
+    fn collect(value: &Value, ids: &mut BTreeSet<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, field) in map {
+                    match field {
+                        Value::String(id)
+                            if (key == "id" || key.ends_with("_id")) && !OWNER_FIELDS.contains(&key.as_str()) =>
+                        {
+                            ids.insert(id.clone());
+                        }
+                        _ => collect(field, ids),
+                    }
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|item| collect(item, ids)),
+            _ => {}
+        }
+    }
+
+    let mut ids = BTreeSet::new();
+    collect(result, &mut ids);
+    ids.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(call: &str, customer: &str, timestamp: i64) -> AccessEntry {
+        AccessEntry {
+            tool_call_id: call.to_string(),
+            timestamp,
+            session_id: "s1".to_string(),
+            tool: "query_customer_memory".to_string(),
+            customer_id: Some(customer.to_string()),
+            business_id: None,
+            memory_ids: vec!["pref-1".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_memory_ids() {
+        let result = json!({
+            "customer_id": "C1",
+            "results": {"preferences": [{"id": "n1", "preference_id": "P1", "customer_id": "C1"}, {"id": "n2"}]}
+        });
+        assert_eq!(memory_ids(&result), vec!["P1", "n1", "n2"]);
+    }
+
+    #[test]
+    fn test_query_in_memory() {
+        let log = AccessLog::new(None, 2);
+        log.record(entry("1", "C1", 10));
+        log.record(entry("2", "C2", 20));
+        log.record(entry("3", "C1", 30));
+
+        // Capacity 2: the oldest entry was dropped
+        let c1 = log.query(&AccessQuery { customer_id: Some("C1"), ..Default::default() }, 10);
+        assert_eq!(c1.len(), 1);
+        assert_eq!(c1[0].tool_call_id, "3");
+        assert_eq!(log.query(&AccessQuery { since: Some(15), ..Default::default() }, 1)[0].tool_call_id, "3");
+    }
+}
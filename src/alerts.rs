@@ -0,0 +1,216 @@
+//! Alert rules on memory content
+//!
+//! A rule is a set of conditions such as `rating <= 2` or
+//! `purchase_amount > 500`, optionally limited to a memory type and a
+//! business. Every successful create or update is checked against the rules
+//! using the fields the tool was called with (including those nested in
+//! `data`/`updates`); a rule whose conditions all hold fires an
+//! `alert.triggered` webhook and an `alert` event on the change feed.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::RwLock;
+
+/// A rule as configured in `[[alerts.rules]]` or given to `create_alert_rule`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Rule {
+    pub name: String,
+    /// Conditions that must all hold, e.g. `"rating <= 2"`
+    pub when: Vec<String>,
+    /// Memory type the rule applies to ("*" for any)
+    #[serde(default = "any_type")]
+    pub memory_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub business_id: Option<String>,
+}
+
+fn any_type() -> String {
+    "*".to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    Contains,
+}
+
+/// Longest operators first so `<=` isn't read as `<`
+const OPS: &[(&str, Op)] = &[
+    (" contains ", Op::Contains),
+    ("<=", Op::Le),
+    (">=", Op::Ge),
+    ("!=", Op::Ne),
+    ("==", Op::Eq),
+    ("<", Op::Lt),
+    (">", Op::Gt),
+    ("=", Op::Eq),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    field: String,
+    op: Op,
+    value: Value,
+}
+
+impl Condition {
+    fn parse(condition: &str) -> Result<Condition, String> {
+        let (field, op, value) = OPS
+            .iter()
+            .find_map(|(token, op)| condition.split_once(token).map(|(field, value)| (field.trim(), *op, value.trim())))
+            .ok_or_else(|| format!("Condition '{}' has no operator (<, <=, >, >=, =, !=, contains)", condition))?;
+        if field.is_empty() || value.is_empty() {
+            return Err(format!("Condition '{}' needs a field and a value", condition));
+        }
+        let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.trim_matches('\'').to_string()));
+        if matches!(op, Op::Lt | Op::Le | Op::Gt | Op::Ge) && !value.is_number() {
+            return Err(format!("Condition '{}' compares with a non-number", condition));
+        }
+        Ok(Condition { field: field.to_string(), op, value })
+    }
+
+    fn holds(&self, record: &Value) -> bool {
+        let Some(actual) = record.get(&self.field).filter(|v| !v.is_null()) else {
+            return false;
+        };
+        let text = |v: &Value| v.as_str().map(str::to_lowercase).unwrap_or_else(|| v.to_string());
+        match (self.op, actual.as_f64(), self.value.as_f64()) {
+            (Op::Lt, Some(a), Some(b)) => a < b,
+            (Op::Le, Some(a), Some(b)) => a <= b,
+            (Op::Gt, Some(a), Some(b)) => a > b,
+            (Op::Ge, Some(a), Some(b)) => a >= b,
+            (Op::Eq, Some(a), Some(b)) => a == b,
+            (Op::Ne, Some(a), Some(b)) => a != b,
+            (Op::Eq, _, _) => text(actual) == text(&self.value),
+            (Op::Ne, _, _) => text(actual) != text(&self.value),
+            (Op::Contains, _, _) => match actual {
+                Value::Array(items) => items.iter().any(|item| text(item) == text(&self.value)),
+                _ => text(actual).contains(&text(&self.value)),
+            },
+            _ => false,
+        }
+    }
+}
+
+struct Compiled {
+    rule: Rule,
+    conditions: Vec<Condition>,
+}
+
+/// A rule that fired, with the values that matched its conditions
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Alert {
+    pub rule: String,
+    pub matched: Map<String, Value>,
+}
+
+fn compile(rule: Rule) -> Result<Compiled, String> {
+    if rule.name.trim().is_empty() {
+        return Err("Alert rule name is required".to_string());
+    }
+    if rule.when.is_empty() {
+        return Err(format!("Alert rule '{}' has no conditions", rule.name));
+    }
+    let conditions = rule.when.iter().map(|c| Condition::parse(c)).collect::<Result<_, _>>()?;
+    Ok(Compiled { rule, conditions })
+}
+
+/// The fields a tool call wrote: its arguments, with `data`/`updates` objects flattened in
+pub fn record(arguments: &Map<String, Value>) -> Value {
+    let mut record = arguments.clone();
+    for nested in ["data", "updates"] {
+        if let Some(Value::Object(fields)) = arguments.get(nested) {
+            record.extend(fields.clone());
+        }
+    }
+    Value::Object(record)
+}
+
+#[derive(Default)]
+pub struct AlertRules {
+    rules: RwLock<Vec<Compiled>>,
+}
+
+impl AlertRules {
+    /// Rules from config; invalid ones are reported and skipped
+    pub fn new(rules: &[Rule]) -> (Self, Vec<String>) {
+        let alerts = AlertRules::default();
+        let errors = rules.iter().cloned().filter_map(|rule| alerts.add(rule).err()).collect();
+        (alerts, errors)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.read().map(|rules| rules.is_empty()).unwrap_or(true)
+    }
+
+    /// Add a rule, replacing one with the same name; returns the rule count
+    pub fn add(&self, rule: Rule) -> Result<usize, String> {
+        let compiled = compile(rule)?;
+        let mut rules = self.rules.write().map_err(|_| "Alert rules unavailable".to_string())?;
+        rules.retain(|r| r.rule.name != compiled.rule.name);
+        rules.push(compiled);
+        Ok(rules.len())
+    }
+
+    pub fn list(&self) -> Vec<Rule> {
+        self.rules.read().map(|rules| rules.iter().map(|r| r.rule.clone()).collect()).unwrap_or_default()
+    }
+
+    /// Rules whose conditions all hold for a write of `memory_type` in `business_id`
+    pub fn triggered(&self, memory_type: &str, business_id: Option<&str>, record: &Value) -> Vec<Alert> {
+        let Ok(rules) = self.rules.read() else {
+            return Vec::new();
+        };
+        rules
+            .iter()
+            .filter(|r| r.rule.memory_type == "*" || r.rule.memory_type == memory_type)
+            .filter(|r| r.rule.business_id.as_deref().is_none_or(|b| business_id == Some(b)))
+            .filter(|r| r.conditions.iter().all(|c| c.holds(record)))
+            .map(|r| Alert {
+                rule: r.rule.name.clone(),
+                matched: r.conditions.iter().map(|c| (c.field.clone(), record[&c.field].clone())).collect(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rules_trigger() {
+        let rule = |name: &str, when: &[&str], memory_type: &str| Rule {
+            name: name.to_string(),
+            when: when.iter().map(|c| c.to_string()).collect(),
+            memory_type: memory_type.to_string(),
+            business_id: None,
+        };
+        let (alerts, errors) = AlertRules::new(&[
+            rule("low rating", &["rating <= 2"], "interaction"),
+            rule("big complaint", &["purchase_amount > 500", "issue_category = quality"], "*"),
+            rule("broken", &["rating ~ 2"], "*"),
+        ]);
+        assert_eq!(errors.len(), 1);
+        assert!(alerts.add(rule("tagged", &["tags contains vip"], "preference")).is_ok());
+        assert!(alerts.add(rule("nan", &["rating < high"], "*")).is_err());
+
+        let arguments = json!({"customer_id": "C1", "rating": 1, "purchase_amount": 750.0, "issue_category": "Quality"});
+        let record = record(arguments.as_object().unwrap());
+        let fired: Vec<String> = alerts.triggered("interaction", None, &record).into_iter().map(|a| a.rule).collect();
+        assert_eq!(fired, ["low rating", "big complaint"]);
+        assert!(alerts.triggered("product", None, &json!({"rating": 5})).is_empty());
+
+        let nested = json!({"memory_type": "preference", "data": {"tags": ["VIP", "vegan"]}});
+        let fired = alerts.triggered("preference", Some("B1"), &super::record(nested.as_object().unwrap()));
+        assert_eq!(fired[0].matched["tags"], json!(["VIP", "vegan"]));
+
+        assert_eq!(alerts.add(rule("low rating", &["rating < 2"], "interaction")), Ok(3));
+    }
+}
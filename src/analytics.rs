@@ -0,0 +1,296 @@
+//! Aggregations over customer interactions and feedback
+//!
+//! Rollups are computed server-side so agents receive counts, averages and
+//! trends instead of raw interaction rows.
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::importance;
+
+/// Time bucket for trend series
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeBucket {
+    Day,
+    Week,
+    Month,
+}
+
+impl TimeBucket {
+    pub fn parse(bucket: &str) -> Option<Self> {
+        match bucket.to_lowercase().as_str() {
+            "day" | "daily" => Some(Self::Day),
+            "week" | "weekly" => Some(Self::Week),
+            "month" | "monthly" => Some(Self::Month),
+            _ => None,
+        }
+    }
+
+    /// Bucket label for a unix timestamp (UTC)
+    pub fn label(&self, timestamp: i64) -> Option<String> {
+        let datetime: DateTime<Utc> = DateTime::from_timestamp(timestamp, 0)?;
+        let format = match self {
+            Self::Day => "%Y-%m-%d",
+            Self::Week => "%G-W%V",
+            Self::Month => "%Y-%m",
+        };
+        Some(datetime.format(format).to_string())
+    }
+}
+
+/// Interaction types counted as positive signals for top-product ranking
+pub const LIKE_TYPES: &[&str] = &["liked", "favorited", "purchased"];
+
+/// Aggregate product and service interactions.
+///
+/// Product interactions use `rating` and `purchase_amount`; service
+/// interactions use `satisfaction_rating` and `cost_actual`. Ratings of 0 are
+/// treated as "not rated".
+pub fn summarize_interactions(product: &[Value], service: &[Value], bucket: TimeBucket, top_n: usize) -> Value {
+    let mut by_type: BTreeMap<String, u64> = BTreeMap::new();
+    let mut totals: BTreeMap<String, f64> = BTreeMap::new();
+    let mut trend: BTreeMap<String, u64> = BTreeMap::new();
+    let mut likes: HashMap<String, u64> = HashMap::new();
+    let mut product_ratings = Vec::new();
+    let mut service_ratings = Vec::new();
+
+    let records = product
+        .iter()
+        .map(|i| ("product", i, "rating", "purchase_amount"))
+        .chain(service.iter().map(|i| ("service", i, "satisfaction_rating", "cost_actual")));
+
+    for (kind, interaction, rating_field, amount_field) in records {
+        let interaction_type = str_field(interaction, "interaction_type").unwrap_or("unspecified");
+        *by_type.entry(format!("{}:{}", kind, interaction_type)).or_default() += 1;
+
+        if let Some(rating) = interaction.get(rating_field).and_then(|v| v.as_f64()).filter(|r| *r > 0.0) {
+            if kind == "product" { product_ratings.push(rating) } else { service_ratings.push(rating) }
+        }
+
+        let amount = interaction.get(amount_field).and_then(|v| v.as_f64()).unwrap_or(0.0);
+        if amount > 0.0 {
+            let currency = str_field(interaction, "currency").unwrap_or("unspecified");
+            *totals.entry(currency.to_uppercase()).or_default() += amount;
+        }
+
+        let timestamp = interaction.get("timestamp").or(interaction.get("created_at")).and_then(|v| v.as_i64());
+        if let Some(label) = timestamp.and_then(|t| bucket.label(t)) {
+            *trend.entry(label).or_default() += 1;
+        }
+
+        if kind == "product" && LIKE_TYPES.contains(&interaction_type) {
+            if let Some(product_id) = str_field(interaction, "product_id") {
+                *likes.entry(product_id.to_string()).or_default() += 1;
+            }
+        }
+    }
+
+    let mut top_products: Vec<(String, u64)> = likes.into_iter().collect();
+    top_products.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top_products.truncate(top_n);
+
+    json!({
+        "total_interactions": product.len() + service.len(),
+        "product_interactions": product.len(),
+        "service_interactions": service.len(),
+        "counts_by_type": by_type,
+        "average_product_rating": average(&product_ratings),
+        "average_service_rating": average(&service_ratings),
+        "total_amount_by_currency": totals,
+        "top_products": top_products
+            .into_iter()
+            .map(|(product_id, count)| json!({"product_id": product_id, "positive_interactions": count}))
+            .collect::<Vec<_>>(),
+        "trend": trend
+            .into_iter()
+            .map(|(period, count)| json!({"period": period, "count": count}))
+            .collect::<Vec<_>>()
+    })
+}
+
+/// Sentiment of a feedback memory: the stored `sentiment`, else derived from `rating`
+pub fn feedback_sentiment(feedback: &Value) -> &str {
+    if let Some(sentiment) = str_field(feedback, "sentiment") {
+        return sentiment;
+    }
+    match feedback.get("rating").and_then(|v| v.as_i64()).unwrap_or(0) {
+        4.. => "positive",
+        3 => "neutral",
+        1 | 2 => "negative",
+        _ => "unspecified",
+    }
+}
+
+/// Aggregate feedback memories: sentiment distribution, average rating,
+/// unresolved counts and the `top_n` most recent negative items.
+pub fn summarize_feedback(feedback: &[Value], top_n: usize) -> Value {
+    let mut sentiments: BTreeMap<String, u64> = BTreeMap::new();
+    let mut ratings = Vec::new();
+    let mut unresolved = 0;
+    let mut awaiting_response = 0;
+    let mut negative = Vec::new();
+
+    for item in feedback {
+        let sentiment = feedback_sentiment(item).to_lowercase();
+        if let Some(rating) = item.get("rating").and_then(|v| v.as_f64()).filter(|r| *r > 0.0) {
+            ratings.push(rating);
+        }
+        if item.get("resolved").and_then(|v| v.as_bool()) != Some(true) {
+            unresolved += 1;
+            if item.get("response_required").and_then(|v| v.as_bool()) == Some(true) {
+                awaiting_response += 1;
+            }
+        }
+        if sentiment == "negative" {
+            negative.push(item);
+        }
+        *sentiments.entry(sentiment).or_default() += 1;
+    }
+
+    // Pinned and important feedback first, then the most recent
+    negative.sort_by_key(|item| (importance::priority(item), std::cmp::Reverse(item.get("created_at").and_then(|v| v.as_i64()).unwrap_or(0))));
+    let top_negative: Vec<Value> = negative
+        .into_iter()
+        .take(top_n)
+        .map(|item| json!({
+            "feedback_id": item.get("feedback_id"),
+            "customer_id": item.get("customer_id"),
+            "subject": item.get("subject"),
+            "rating": item.get("rating"),
+            "resolved": item.get("resolved"),
+            "importance": item.get("importance"),
+            "pinned": item.get("pinned"),
+            "created_at": item.get("created_at"),
+            "text_description": item.get("text_description")
+        }))
+        .collect();
+
+    json!({
+        "total_feedback": feedback.len(),
+        "sentiment_distribution": sentiments,
+        "average_rating": average(&ratings),
+        "unresolved": unresolved,
+        "awaiting_response": awaiting_response,
+        "top_negative": top_negative
+    })
+}
+
+/// "Customers who liked A also liked B" for each product in `targets`.
+///
+/// `interactions` are product interactions; those whose type is in `positive`
+/// link a customer to a product. For each target, every other product shared
+/// by at least `min_support` of its customers is ranked by `co_customers`,
+/// with `confidence` the share of the target's customers who also chose it.
+pub fn product_affinities(interactions: &[Value], positive: &[&str], targets: &[String], min_support: usize, top_n: usize) -> Vec<Value> {
+    let mut customers_by_product: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for interaction in interactions {
+        let positive_type = str_field(interaction, "interaction_type").is_some_and(|t| positive.contains(&t));
+        if let (true, Some(product_id), Some(customer_id)) =
+            (positive_type, str_field(interaction, "product_id"), str_field(interaction, "customer_id"))
+        {
+            customers_by_product.entry(product_id).or_default().insert(customer_id);
+        }
+    }
+
+    targets
+        .iter()
+        .map(|target| {
+            let customers = customers_by_product.get(target.as_str()).cloned().unwrap_or_default();
+            let mut affinities: Vec<(usize, &str)> = customers_by_product
+                .iter()
+                .filter(|(product_id, _)| **product_id != target.as_str())
+                .map(|(product_id, others)| (customers.intersection(others).count(), *product_id))
+                .filter(|(co_customers, _)| *co_customers > 0 && *co_customers >= min_support)
+                .collect();
+            affinities.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+            let ranked: Vec<Value> = affinities
+                .into_iter()
+                .take(top_n)
+                .map(|(co_customers, product_id)| json!({
+                    "product_id": product_id,
+                    "co_customers": co_customers,
+                    "confidence": co_customers as f64 / customers.len() as f64
+                }))
+                .collect();
+            json!({"product_id": target, "customers": customers.len(), "affinities": ranked})
+        })
+        .collect()
+}
+
+fn str_field<'a>(item: &'a Value, field: &str) -> Option<&'a str> {
+    item.get(field).and_then(|v| v.as_str()).filter(|s| !s.is_empty())
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_interactions() {
+        let product = vec![
+            json!({"product_id": "P1", "interaction_type": "liked", "rating": 5, "timestamp": 1_700_000_000}),
+            json!({"product_id": "P1", "interaction_type": "purchased", "rating": 0, "purchase_amount": 20.0, "currency": "usd", "timestamp": 1_700_000_000}),
+            json!({"product_id": "P2", "interaction_type": "liked", "rating": 3, "timestamp": 1_710_000_000}),
+        ];
+        let service = vec![json!({"interaction_type": "completed", "satisfaction_rating": 4, "cost_actual": 50.0, "currency": "USD", "timestamp": 1_710_000_000})];
+
+        let summary = summarize_interactions(&product, &service, TimeBucket::Month, 1);
+        assert_eq!(summary["total_interactions"], 4);
+        assert_eq!(summary["counts_by_type"]["product:liked"], 2);
+        assert_eq!(summary["average_product_rating"], 4.0);
+        assert_eq!(summary["total_amount_by_currency"]["USD"], 70.0);
+        assert_eq!(summary["top_products"][0]["product_id"], "P1");
+        assert_eq!(summary["top_products"].as_array().unwrap().len(), 1);
+        assert_eq!(summary["trend"][0]["period"], "2023-11");
+        assert_eq!(summary["trend"][1]["count"], 2);
+    }
+
+    #[test]
+    fn test_product_affinities() {
+        let interactions = vec![
+            json!({"customer_id": "C1", "product_id": "A", "interaction_type": "liked"}),
+            json!({"customer_id": "C1", "product_id": "B", "interaction_type": "purchased"}),
+            json!({"customer_id": "C2", "product_id": "A", "interaction_type": "liked"}),
+            json!({"customer_id": "C2", "product_id": "B", "interaction_type": "liked"}),
+            json!({"customer_id": "C2", "product_id": "C", "interaction_type": "liked"}),
+            json!({"customer_id": "C3", "product_id": "C", "interaction_type": "disliked"}),
+        ];
+        let rows = product_affinities(&interactions, LIKE_TYPES, &["A".to_string()], 1, 10);
+        assert_eq!(rows[0]["customers"], 2);
+        assert_eq!(rows[0]["affinities"][0]["product_id"], "B");
+        assert_eq!(rows[0]["affinities"][0]["confidence"], 1.0);
+        assert_eq!(rows[0]["affinities"][1]["co_customers"], 1);
+
+        let strict = product_affinities(&interactions, LIKE_TYPES, &["A".to_string()], 2, 10);
+        assert_eq!(strict[0]["affinities"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_summarize_feedback() {
+        let feedback = vec![
+            json!({"feedback_id": "F1", "sentiment": "negative", "rating": 1, "resolved": false, "response_required": true, "created_at": 10}),
+            json!({"feedback_id": "F2", "sentiment": "", "rating": 2, "resolved": true, "created_at": 20}),
+            json!({"feedback_id": "F3", "sentiment": "positive", "rating": 5, "resolved": false, "created_at": 30}),
+        ];
+        let summary = summarize_feedback(&feedback, 5);
+        assert_eq!(summary["sentiment_distribution"]["negative"], 2);
+        assert_eq!(summary["sentiment_distribution"]["positive"], 1);
+        assert_eq!(summary["unresolved"], 2);
+        assert_eq!(summary["awaiting_response"], 1);
+        assert_eq!(summary["top_negative"][0]["feedback_id"], "F2");
+        assert!((summary["average_rating"].as_f64().unwrap() - 8.0 / 3.0).abs() < 1e-9);
+
+        // A pinned or important complaint isn't pushed out by newer ones
+        let mut flagged = feedback.clone();
+        flagged.push(json!({"feedback_id": "F0", "sentiment": "negative", "importance": 5, "created_at": 1}));
+        flagged.push(json!({"feedback_id": "F9", "sentiment": "negative", "created_at": 90}));
+        let summary = summarize_feedback(&flagged, 2);
+        let ids: Vec<&str> = summary["top_negative"].as_array().unwrap().iter().map(|f| f["feedback_id"].as_str().unwrap()).collect();
+        assert_eq!(ids, ["F0", "F9"]);
+    }
+}
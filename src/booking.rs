@@ -0,0 +1,94 @@
+//! Open booking slots for a service
+//!
+//! A day's opening interval (from the business hours memory, exceptions
+//! applied) is cut into slots as long as the service, starting every
+//! `step` minutes. A slot stays open while fewer than `capacity` bookings
+//! overlap it. Bookings are service interactions of type `booked`: they
+//! start at `timestamp` and last `duration_actual` minutes, or the
+//! service's duration when that is 0.
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDateTime};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Booking {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl Booking {
+    fn overlaps(&self, start: NaiveDateTime, end: NaiveDateTime) -> bool {
+        self.start < end && start < self.end
+    }
+}
+
+/// Booked interactions as local-time bookings
+pub fn bookings(interactions: &[Value], offset: FixedOffset, default_minutes: i64) -> Vec<Booking> {
+    interactions
+        .iter()
+        .filter(|i| i.get("interaction_type").and_then(|v| v.as_str()).is_some_and(|t| t.eq_ignore_ascii_case("booked")))
+        .filter_map(|i| {
+            let start = DateTime::from_timestamp(i.get("timestamp").and_then(|v| v.as_i64())?, 0)?
+                .with_timezone(&offset)
+                .naive_local();
+            let minutes = i.get("duration_actual").and_then(|v| v.as_i64()).filter(|m| *m > 0).unwrap_or(default_minutes);
+            Some(Booking { start, end: start + Duration::minutes(minutes) })
+        })
+        .collect()
+}
+
+/// Slots of `duration` inside `window` with fewer than `capacity` overlapping
+/// bookings, starting every `step`; slots starting before `not_before` are skipped
+pub fn open_slots(
+    window: (NaiveDateTime, NaiveDateTime),
+    duration: Duration,
+    step: Duration,
+    bookings: &[Booking],
+    capacity: usize,
+    not_before: Option<NaiveDateTime>,
+) -> Vec<Booking> {
+    let (open, close) = window;
+    let mut slots = Vec::new();
+    let mut start = open;
+    while start + duration <= close && step > Duration::zero() {
+        let end = start + duration;
+        let taken = bookings.iter().filter(|b| b.overlaps(start, end)).count();
+        if taken < capacity && not_before.is_none_or(|t| start >= t) {
+            slots.push(Booking { start, end });
+        }
+        start += step;
+    }
+    slots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_open_slots_around_bookings() {
+        // 2026-03-03 14:00 UTC, booked for the default hour; the canceled one is ignored
+        let interactions = vec![
+            json!({"interaction_type": "booked", "timestamp": 1_772_546_400, "duration_actual": 0}),
+            json!({"interaction_type": "canceled", "timestamp": 1_772_528_400}),
+        ];
+        let offset = FixedOffset::east_opt(3600).unwrap();
+        let booked = bookings(&interactions, offset, 60);
+        assert_eq!(booked, vec![Booking { start: at("2026-03-03 15:00"), end: at("2026-03-03 16:00") }]);
+
+        let window = (at("2026-03-03 09:00"), at("2026-03-03 17:00"));
+        let slots = open_slots(window, Duration::minutes(60), Duration::minutes(60), &booked, 1, None);
+        let starts: Vec<String> = slots.iter().map(|s| s.start.format("%H:%M").to_string()).collect();
+        assert_eq!(starts, vec!["09:00", "10:00", "11:00", "12:00", "13:00", "14:00", "16:00"]);
+
+        let slots = open_slots(window, Duration::minutes(60), Duration::minutes(30), &booked, 1, Some(at("2026-03-03 13:10")));
+        assert_eq!(slots.first().map(|s| s.start), Some(at("2026-03-03 13:30")));
+        assert!(slots.iter().all(|s| !booked[0].overlaps(s.start, s.end)));
+        assert_eq!(open_slots(window, Duration::minutes(60), Duration::minutes(60), &booked, 2, None).len(), 8);
+    }
+}
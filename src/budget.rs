@@ -0,0 +1,120 @@
+//! Per-session usage budgets
+//!
+//! Caps tool calls, generated embeddings and rows fetched from HelixDB for
+//! one client session, so a runaway agent loop on a long-lived connection
+//! can't monopolize the backend. A limit of 0 means unlimited.
+
+use serde_json::{json, Value};
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use thiserror::Error;
+
+/// Limits for one session (0 = unlimited)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BudgetLimits {
+    pub max_tool_calls: u64,
+    pub max_embeddings: u64,
+    pub max_rows: u64,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BudgetExceeded {
+    #[error("Session tool-call budget exhausted ({limit} calls)")]
+    ToolCalls { limit: u64 },
+    #[error("Session embedding budget exhausted ({limit} embeddings)")]
+    Embeddings { limit: u64 },
+    #[error("Session row budget exhausted ({limit} rows fetched)")]
+    Rows { limit: u64 },
+}
+
+/// Usage counters for one session
+#[derive(Debug, Default)]
+pub struct SessionBudget {
+    limits: BudgetLimits,
+    tool_calls: AtomicU64,
+    embeddings: AtomicU64,
+    rows: AtomicU64,
+}
+
+tokio::task_local! {
+    static CURRENT: Arc<SessionBudget>;
+}
+
+impl SessionBudget {
+    pub fn new(limits: BudgetLimits) -> Self {
+        SessionBudget { limits, ..Default::default() }
+    }
+
+    pub fn charge_tool_call(&self) -> Result<(), BudgetExceeded> {
+        charge(&self.tool_calls, 1, self.limits.max_tool_calls).map_err(|limit| BudgetExceeded::ToolCalls { limit })
+    }
+
+    pub fn charge_embedding(&self) -> Result<(), BudgetExceeded> {
+        charge(&self.embeddings, 1, self.limits.max_embeddings).map_err(|limit| BudgetExceeded::Embeddings { limit })
+    }
+
+    pub fn charge_rows(&self, rows: u64) -> Result<(), BudgetExceeded> {
+        charge(&self.rows, rows, self.limits.max_rows).map_err(|limit| BudgetExceeded::Rows { limit })
+    }
+
+    /// Current usage and limits
+    pub fn usage(&self) -> Value {
+        json!({
+            "tool_calls": {"used": self.tool_calls.load(Ordering::Relaxed), "limit": self.limits.max_tool_calls},
+            "embeddings": {"used": self.embeddings.load(Ordering::Relaxed), "limit": self.limits.max_embeddings},
+            "rows": {"used": self.rows.load(Ordering::Relaxed), "limit": self.limits.max_rows}
+        })
+    }
+}
+
+/// Add `amount` to `counter`; `Err(limit)` once the total exceeds a non-zero limit
+fn charge(counter: &AtomicU64, amount: u64, limit: u64) -> Result<(), u64> {
+    let used = counter.fetch_add(amount, Ordering::Relaxed) + amount;
+    if limit > 0 && used > limit { Err(limit) } else { Ok(()) }
+}
+
+/// Run `future` with `budget` as the session budget of the current task
+pub async fn scope<F: Future>(budget: Arc<SessionBudget>, future: F) -> F::Output {
+    CURRENT.scope(budget, future).await
+}
+
+/// Charge one embedding to the current session (no-op outside a session)
+pub fn charge_current_embedding() -> Result<(), BudgetExceeded> {
+    CURRENT.try_with(|b| b.charge_embedding()).unwrap_or(Ok(()))
+}
+
+/// Charge fetched rows to the current session (no-op outside a session)
+pub fn charge_current_rows(rows: u64) -> Result<(), BudgetExceeded> {
+    CURRENT.try_with(|b| b.charge_rows(rows)).unwrap_or(Ok(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits() {
+        let budget = SessionBudget::new(BudgetLimits { max_tool_calls: 2, max_embeddings: 0, max_rows: 10 });
+        assert!(budget.charge_tool_call().is_ok());
+        assert!(budget.charge_tool_call().is_ok());
+        assert_eq!(budget.charge_tool_call(), Err(BudgetExceeded::ToolCalls { limit: 2 }));
+
+        // 0 = unlimited
+        for _ in 0..100 {
+            assert!(budget.charge_embedding().is_ok());
+        }
+
+        assert!(budget.charge_rows(10).is_ok());
+        assert_eq!(budget.charge_rows(1), Err(BudgetExceeded::Rows { limit: 10 }));
+        assert_eq!(budget.usage()["rows"]["used"], 11);
+    }
+
+    #[tokio::test]
+    async fn test_task_scope() {
+        let budget = Arc::new(SessionBudget::new(BudgetLimits { max_rows: 5, ..Default::default() }));
+        assert!(charge_current_rows(100).is_ok());
+        let result = scope(budget.clone(), async { charge_current_rows(6) }).await;
+        assert_eq!(result, Err(BudgetExceeded::Rows { limit: 5 }));
+    }
+}
@@ -0,0 +1,59 @@
+//! Deleting memories by filter
+//!
+//! `delete_memories_by_filter` never deletes on the first call: a dry run
+//! (the default) lists the matching IDs, and the delete only runs when
+//! called again with `expected_count` equal to the number that match at
+//! that moment, so a set that changed since the preview is left alone.
+
+use crate::errors::ErrorCode;
+use crate::filters;
+use serde_json::Value;
+
+/// IDs (`id_field`) of the memories matching `filters`, in list order
+pub fn matching_ids(memories: &[Value], id_field: &str, filters: &Value) -> Vec<String> {
+    memories
+        .iter()
+        .filter(|m| filters::matches(m, filters))
+        .filter_map(|m| m.get(id_field).and_then(|v| v.as_str()).map(String::from))
+        .collect()
+}
+
+/// Check the previewed count before deleting: it is required, and must equal
+/// the number of memories matching now
+pub fn confirm(expected_count: Option<usize>, matched: usize) -> Result<(), (ErrorCode, String)> {
+    match expected_count {
+        Some(expected) if expected == matched => Ok(()),
+        None => Err((ErrorCode::ValidationFailed, "expected_count is required when dry_run is false - run a dry_run preview first".to_string())),
+        Some(expected) => Err((ErrorCode::Conflict, format!("{} memories match now but expected_count is {}; nothing was deleted", matched, expected))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_matching_ids() {
+        let memories = vec![
+            json!({"product_id": "P1", "availability": "discontinued", "created_at": 100}),
+            json!({"product_id": "P2", "availability": "in_stock", "created_at": 100}),
+            json!({"product_id": "P3", "availability": "discontinued", "created_at": 300}),
+            json!({"availability": "discontinued", "created_at": 100}),
+        ];
+        let filters = json!({"availability": "discontinued", "created_at": {"lte": 200}});
+        assert_eq!(matching_ids(&memories, "product_id", &filters), ["P1"]);
+        assert_eq!(matching_ids(&memories, "product_id", &json!({"availability": "discontinued"})), ["P1", "P3"]);
+    }
+
+    #[test]
+    fn test_confirm_guard() {
+        assert!(confirm(Some(2), 2).is_ok());
+        assert_eq!(confirm(None, 2).unwrap_err().0, ErrorCode::ValidationFailed);
+        // Zero matches still need the preview's zero
+        assert_eq!(confirm(None, 0).unwrap_err().0, ErrorCode::ValidationFailed);
+        let (code, message) = confirm(Some(2), 3).unwrap_err();
+        assert_eq!(code, ErrorCode::Conflict);
+        assert_eq!(message, "3 memories match now but expected_count is 2; nothing was deleted");
+    }
+}
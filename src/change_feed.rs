@@ -0,0 +1,106 @@
+//! Memory change feed
+//!
+//! Every successful create/update/delete is published as a [`ChangeEvent`]
+//! on a broadcast channel, as are fired alert rules (operation `alert`). The
+//! HTTP transport streams it to dashboards as Server-Sent Events on
+//! `GET /events`, optionally filtered by business_id.
+//! Subscribers that fall too far behind skip the missed events and are told
+//! how many they lost.
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// Events buffered per subscriber before it starts lagging
+const CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangeEvent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub memory_type: String,
+    /// "created", "updated" or "deleted"
+    pub operation: String,
+    pub memory_ids: Vec<String>,
+    pub business_id: Option<String>,
+    pub customer_id: Option<String>,
+    pub tool: String,
+    pub timestamp: i64,
+    /// Rule name, for "alert" events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alert: Option<String>,
+}
+
+impl ChangeEvent {
+    pub fn matches(&self, business_id: Option<&str>) -> bool {
+        business_id.is_none_or(|b| self.business_id.as_deref() == Some(b))
+    }
+
+    /// SSE frame: `id`, `event` (the operation) and JSON `data`
+    pub fn to_sse(&self) -> String {
+        format!(
+            "id: {}\nevent: {}\ndata: {}\n\n",
+            self.id,
+            self.operation,
+            serde_json::to_string(self).unwrap_or_default()
+        )
+    }
+}
+
+pub struct ChangeFeed {
+    tx: broadcast::Sender<ChangeEvent>,
+}
+
+impl Default for ChangeFeed {
+    fn default() -> Self {
+        ChangeFeed { tx: broadcast::channel(CHANNEL_CAPACITY).0 }
+    }
+}
+
+impl ChangeFeed {
+    pub fn has_subscribers(&self) -> bool {
+        self.tx.receiver_count() > 0
+    }
+
+    pub fn publish(&self, event: ChangeEvent) {
+        // No subscribers is not an error
+        let _ = self.tx.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ChangeEvent> {
+        self.tx.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_and_filter() {
+        let feed = ChangeFeed::default();
+        assert!(!feed.has_subscribers());
+        let mut rx = feed.subscribe();
+        assert!(feed.has_subscribers());
+
+        feed.publish(ChangeEvent {
+            id: "e1".to_string(),
+            memory_type: "product".to_string(),
+            operation: "created".to_string(),
+            memory_ids: vec!["PRODUCT_1".to_string()],
+            business_id: Some("biz".to_string()),
+            customer_id: None,
+            tool: "create_business_memory".to_string(),
+            timestamp: 100,
+            alert: None,
+        });
+        let event = rx.recv().await.unwrap();
+        assert!(event.matches(Some("biz")));
+        assert!(!event.matches(Some("other")));
+        assert!(event.matches(None));
+
+        let frame = event.to_sse();
+        assert!(frame.starts_with("id: e1\nevent: created\ndata: {"));
+        assert!(frame.contains("\"type\":\"product\""));
+        assert!(frame.ends_with("\n\n"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Channel filtering for query and search tools
+//!
+//! Memories record where they came from in different fields: `channel` on
+//! behaviors, feedback and product interactions, `source_channels` on
+//! preferences, `contact_method` on communications. Location visits happen
+//! in store. Channel names are normalized so "WhatsApp", "wa" and
+//! "whatsapp" match each other; memories with no channel never match a
+//! channel filter.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Results fetched per kept result when searching with a channel filter
+pub const OVERFETCH: i32 = 5;
+
+const ALIASES: &[(&str, &[&str])] = &[
+    ("whatsapp", &["wa", "whats_app", "whats app"]),
+    ("website", &["web", "online", "site", "web_chat", "webchat"]),
+    ("store", &["in_store", "in-store", "instore", "in person", "in_person", "shop", "walk_in", "walk-in"]),
+    ("phone", &["call", "telephone", "voice"]),
+    ("email", &["e-mail", "mail"]),
+    ("mobile_app", &["app", "mobile", "mobile app"]),
+    ("social_media", &["social", "instagram", "facebook"]),
+    ("sms", &["text", "text message"]),
+];
+
+/// Canonical name of a channel ("WhatsApp" → "whatsapp", "in-store" → "store")
+pub fn normalize(channel: &str) -> String {
+    let channel = channel.trim().to_lowercase();
+    ALIASES
+        .iter()
+        .find(|(name, aliases)| *name == channel || aliases.contains(&channel.as_str()))
+        .map_or(channel, |(name, _)| name.to_string())
+}
+
+/// Channels of a comma-separated filter ("whatsapp, web")
+pub fn parse(filter: &str) -> Vec<String> {
+    filter.split(',').map(normalize).filter(|c| !c.is_empty()).collect()
+}
+
+/// Channels a memory came from
+pub fn of(memory: &Value) -> Vec<String> {
+    let mut channels: Vec<String> = ["channel", "contact_method"]
+        .iter()
+        .filter_map(|field| memory.get(*field).and_then(|v| v.as_str()))
+        .chain(memory.get("source_channels").and_then(|v| v.as_array()).into_iter().flatten().filter_map(|v| v.as_str()))
+        .map(normalize)
+        .filter(|c| !c.is_empty())
+        .collect();
+    if memory.get("visit_id").is_some() {
+        channels.push("store".to_string());
+    }
+    channels
+}
+
+pub fn matches(memory: &Value, wanted: &[String]) -> bool {
+    wanted.is_empty() || of(memory).iter().any(|c| wanted.contains(c))
+}
+
+/// Drop memories from other channels, descending into HelixDB's wrapped RETURN objects
+pub fn filter(results: Value, wanted: &[String]) -> Value {
+    match results {
+        Value::Array(items) => items.into_iter().filter(|item| !item.is_object() || matches(item, wanted)).collect(),
+        Value::Object(map) => map
+            .into_iter()
+            .map(|(key, value)| match value {
+                Value::Array(_) | Value::Object(_) => (key, filter(value, wanted)),
+                value => (key, value),
+            })
+            .collect(),
+        other => other,
+    }
+}
+
+/// Search results from the wanted channels, at most `limit` per memory type
+pub fn keep(results: Vec<Value>, wanted: &[String], limit: usize) -> Vec<Value> {
+    let mut per_type: HashMap<String, usize> = HashMap::new();
+    results
+        .into_iter()
+        .filter(|result| matches(result, wanted))
+        .filter(|result| {
+            let memory_type = result.get("memory_type").and_then(|v| v.as_str()).unwrap_or("").to_string();
+            let count = per_type.entry(memory_type).or_insert(0);
+            *count += 1;
+            *count <= limit
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_channel_matching() {
+        assert_eq!(normalize(" WhatsApp "), "whatsapp");
+        assert_eq!(normalize("in-store"), "store");
+        assert_eq!(normalize("kiosk"), "kiosk");
+        assert_eq!(parse("wa, Web,"), vec!["whatsapp", "website"]);
+
+        let wanted = parse("whatsapp");
+        assert!(matches(&json!({"behavior_id": "b1", "channel": "WA"}), &wanted));
+        assert!(matches(&json!({"preference_id": "p1", "source_channels": ["web", "whatsapp"]}), &wanted));
+        assert!(matches(&json!({"communication_id": "c1", "contact_method": "whatsapp"}), &wanted));
+        assert!(!matches(&json!({"desire_id": "d1"}), &wanted));
+        assert!(matches(&json!({"visit_id": "v1"}), &parse("store")));
+
+        let wrapped = json!({"behaviors": [{"channel": "whatsapp"}, {"channel": "website"}], "count": 2});
+        assert_eq!(filter(wrapped, &wanted), json!({"behaviors": [{"channel": "whatsapp"}], "count": 2}));
+
+        let results = vec![
+            json!({"memory_type": "behaviors", "channel": "whatsapp", "n": 1}),
+            json!({"memory_type": "behaviors", "channel": "store", "n": 2}),
+            json!({"memory_type": "behaviors", "channel": "wa", "n": 3}),
+            json!({"memory_type": "feedback", "channel": "whatsapp", "n": 4}),
+        ];
+        let kept: Vec<i64> = keep(results, &wanted, 1).iter().map(|r| r["n"].as_i64().unwrap()).collect();
+        assert_eq!(kept, vec![1, 4]);
+    }
+}
@@ -0,0 +1,90 @@
+//! Transcript chunking for conversational memory
+//!
+//! Splits raw conversation transcripts into embedding-sized chunks.
+//! Chunks are built from whole lines (speaker turns) where possible so
+//! each stored memory keeps enough context for semantic recall.
+
+/// Default maximum characters per chunk
+pub const DEFAULT_CHUNK_CHARS: usize = 1000;
+
+/// Split a transcript into chunks of at most `max_chars` characters.
+///
+/// Lines are kept together when they fit; a single line longer than
+/// `max_chars` is split on whitespace. Empty lines are dropped.
+pub fn chunk_transcript(transcript: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for line in transcript.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if line.chars().count() > max_chars {
+            if !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+            }
+            chunks.extend(split_long_line(line, max_chars));
+            continue;
+        }
+
+        let needed = if current.is_empty() { 0 } else { 1 } + line.chars().count();
+        if current.chars().count() + needed > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(line);
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Split a single long line on whitespace, hard-splitting oversized words
+fn split_long_line(line: &str, max_chars: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+
+    for word in line.split_whitespace() {
+        let word_chars: Vec<char> = word.chars().collect();
+        for piece in word_chars.chunks(max_chars) {
+            let piece: String = piece.iter().collect();
+            let needed = if current.is_empty() { 0 } else { 1 } + piece.chars().count();
+            if current.chars().count() + needed > max_chars {
+                parts.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(&piece);
+        }
+    }
+
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_keeps_turns_together() {
+        let transcript = "Customer: Hi there\nAgent: Hello!\n\nCustomer: Where is my order?";
+        let chunks = chunk_transcript(transcript, 35);
+        assert_eq!(chunks, vec![
+            "Customer: Hi there\nAgent: Hello!".to_string(),
+            "Customer: Where is my order?".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_chunk_splits_long_lines() {
+        let chunks = chunk_transcript("aaaa bbbb cccc dddddddddd", 8);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 8));
+        assert_eq!(chunks.concat().replace(' ', ""), "aaaabbbbccccdddddddddd");
+    }
+}
@@ -0,0 +1,93 @@
+//! Time and ID providers
+//!
+//! Tools read the current time and generate memory IDs through these traits
+//! instead of calling `chrono::Utc::now()` / `Uuid::new_v4()` directly, so a
+//! test or record/replay run can swap in a stepping clock and sequential IDs
+//! and get identical payloads every run.
+
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use uuid::Uuid;
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Current Unix timestamp in seconds
+    fn timestamp(&self) -> i64 {
+        self.now().timestamp()
+    }
+}
+
+pub trait IdGenerator: Send + Sync {
+    fn next_id(&self) -> Uuid;
+}
+
+/// Wall clock
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock starting at a fixed instant that advances `step_secs` on every read
+pub struct SteppingClock {
+    next: AtomicI64,
+    step_secs: i64,
+}
+
+impl SteppingClock {
+    pub fn new(start: i64, step_secs: i64) -> Self {
+        SteppingClock { next: AtomicI64::new(start), step_secs }
+    }
+}
+
+impl Clock for SteppingClock {
+    fn now(&self) -> DateTime<Utc> {
+        let secs = self.next.fetch_add(self.step_secs, Ordering::SeqCst);
+        DateTime::from_timestamp(secs, 0).unwrap_or_default()
+    }
+}
+
+/// Random v4 UUIDs
+pub struct RandomIds;
+
+impl IdGenerator for RandomIds {
+    fn next_id(&self) -> Uuid {
+        Uuid::new_v4()
+    }
+}
+
+/// Counter-based UUIDs (`00000000-0000-0000-0000-000000000001`, ...)
+pub struct SequentialIds {
+    next: AtomicU64,
+}
+
+impl SequentialIds {
+    pub fn new() -> Self {
+        SequentialIds { next: AtomicU64::new(1) }
+    }
+}
+
+impl IdGenerator for SequentialIds {
+    fn next_id(&self) -> Uuid {
+        Uuid::from_u128(self.next.fetch_add(1, Ordering::SeqCst) as u128)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic_providers() {
+        let clock = SteppingClock::new(1_700_000_000, 5);
+        assert_eq!(clock.timestamp(), 1_700_000_000);
+        assert_eq!(clock.timestamp(), 1_700_000_005);
+
+        let ids = SequentialIds::new();
+        assert_eq!(ids.next_id().to_string(), "00000000-0000-0000-0000-000000000001");
+        assert_eq!(ids.next_id().to_string(), "00000000-0000-0000-0000-000000000002");
+    }
+}
@@ -1,6 +1,7 @@
 // Configuration module for AI Memory Layer MCP Server
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
@@ -9,6 +10,66 @@ pub struct Config {
     pub server: ServerConfig,
     pub helix: HelixConfig,
     pub embedding: EmbeddingConfig,
+    #[serde(default)]
+    pub dedup: DedupConfig,
+    #[serde(default)]
+    pub export: ExportConfig,
+    #[serde(default)]
+    pub decay: DecayConfig,
+    #[serde(default)]
+    pub ingest: IngestConfig,
+    #[serde(default)]
+    pub backfill: BackfillConfig,
+    #[serde(default)]
+    pub priority: PriorityConfig,
+    #[serde(default)]
+    pub query_cache: QueryCacheConfig,
+    #[serde(default)]
+    pub session_memory: SessionMemoryConfig,
+    #[serde(default)]
+    pub budget: BudgetConfig,
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+    #[serde(default)]
+    pub differential_privacy: DifferentialPrivacyConfig,
+    #[serde(default)]
+    pub currency: CurrencyConfig,
+    #[serde(default)]
+    pub access_log: AccessLogConfig,
+    #[serde(default)]
+    pub field_encryption: FieldEncryptionConfig,
+    #[serde(default)]
+    pub deterministic: DeterministicConfig,
+    #[serde(default)]
+    pub embedding_metadata: EmbeddingMetadataConfig,
+    #[serde(default)]
+    pub webhooks: WebhooksConfig,
+    #[serde(default)]
+    pub alerts: AlertsConfig,
+    #[serde(default)]
+    pub live_location: LiveLocationConfig,
+    #[serde(default)]
+    pub dry_run: DryRunConfig,
+    #[serde(default)]
+    pub rerank: RerankConfig,
+    #[serde(default)]
+    pub traversal: TraversalConfig,
+    #[serde(default)]
+    pub idempotency: IdempotencyConfig,
+    #[serde(default)]
+    pub jobs: JobsConfig,
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    #[serde(default)]
+    pub do_query: DoQueryConfig,
+    #[serde(default)]
+    pub erasure: ErasureConfig,
+    #[serde(default)]
+    pub snapshots: SnapshotsConfig,
+    #[serde(default)]
+    pub shopify: ShopifyConfig,
+    #[serde(default)]
+    pub calendar_import: CalendarImportConfig,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -41,6 +102,15 @@ pub struct ServerConfig {
     pub tcp_keepalive_interval: u64,
     #[serde(default = "default_tcp_keepalive_retries")]
     pub tcp_keepalive_retries: u32,
+    // Largest request accepted: HTTP body, or one JSON-RPC line on TCP (0 = no limit)
+    #[serde(default = "default_max_request_bytes")]
+    pub max_request_bytes: usize,
+    // Tool results larger than this are sent in pages (fetch_result_page) (0 = no limit)
+    #[serde(default = "default_max_response_bytes")]
+    pub max_response_bytes: usize,
+    // How long the remaining pages of a spilled result can be fetched
+    #[serde(default = "default_result_page_ttl")]
+    pub result_page_ttl_secs: u64,
 }
 
 fn default_transport() -> String {
@@ -91,10 +161,48 @@ fn default_tcp_keepalive_retries() -> u32 {
     3 // 3 retries before closing
 }
 
+fn default_max_request_bytes() -> usize {
+    4 * 1024 * 1024
+}
+
+fn default_max_response_bytes() -> usize {
+    1024 * 1024
+}
+
+fn default_result_page_ttl() -> u64 {
+    600
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct HelixConfig {
     pub endpoint: String,
     pub port: u16,
+    /// Queries run at once when a search fans out over memory types
+    #[serde(default = "default_max_parallel_queries")]
+    pub max_parallel_queries: usize,
+    /// Read replica host for read-only queries (get_, search_, find_, ...); unset sends everything to `endpoint`
+    #[serde(default)]
+    pub read_endpoint: Option<String>,
+    /// Read replica port (default: `port`)
+    #[serde(default)]
+    pub read_port: Option<u16>,
+    /// Query payload encoding; JSON is used if HelixDB refuses it
+    #[serde(default)]
+    pub payload_format: PayloadFormat,
+}
+
+fn default_max_parallel_queries() -> usize {
+    8
+}
+
+impl HelixConfig {
+    /// Read replica (host, port): the `HELIX_READ_ENDPOINT` / `HELIX_READ_PORT`
+    /// overrides, else `read_endpoint` / `read_port`; the port defaults to the primary's
+    pub fn read_replica(&self, endpoint_override: Option<String>, port_override: Option<&str>, primary_port: u16) -> Option<(String, u16)> {
+        let endpoint = endpoint_override.or_else(|| self.read_endpoint.clone()).filter(|e| !e.is_empty())?;
+        let port = port_override.and_then(|p| p.parse::<u16>().ok()).or(self.read_port).unwrap_or(primary_port);
+        Some((endpoint, port))
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -112,21 +220,978 @@ pub struct EmbeddingConfig {
     pub local_api_url: Option<String>,
     #[serde(default)]
     pub api_key: Option<String>,
-    #[serde(default = "default_dimensions")]
-    pub dimensions: usize,
+    // Vector size of the model; see EmbeddingConfig::vector_dimensions for the default
+    #[serde(default)]
+    pub dimensions: Option<usize>,
     // TCP embedding server configuration
     #[serde(default)]
     pub tcp_address: Option<String>,
     #[serde(default = "default_tcp_timeout")]
     pub tcp_timeout_secs: u64,
+    // Idle connections kept open to the TCP embedding server (0 = connect per request)
+    #[serde(default = "default_tcp_pool_size")]
+    pub tcp_pool_size: usize,
+    // Azure OpenAI deployment; openai_api_url is then the resource endpoint
+    #[serde(default)]
+    pub azure_deployment: Option<String>,
+    #[serde(default = "default_azure_api_version")]
+    pub azure_api_version: String,
 }
 
 fn default_tcp_timeout() -> u64 {
     30
 }
 
-fn default_dimensions() -> usize {
-    1536
+fn default_tcp_pool_size() -> usize {
+    4
+}
+
+fn default_azure_api_version() -> String {
+    "2024-02-01".to_string()
+}
+
+impl EmbeddingConfig {
+    /// Configured `dimensions`, else 384 for the TCP server (all-MiniLM-L6-v2)
+    /// and 1536 for the other providers
+    pub fn vector_dimensions(&self) -> usize {
+        self.dimensions.unwrap_or(match self.provider {
+            Some(EmbeddingProvider::Tcp) => 384,
+            _ => 1536,
+        })
+    }
+}
+
+/// Near-duplicate detection on memory creation (MCP embedding mode)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DedupConfig {
+    // Check for similar memories before creating (can be overridden per call)
+    #[serde(default)]
+    pub enabled: bool,
+    // Cosine similarity at or above which a memory is considered a duplicate
+    #[serde(default = "default_similarity_threshold")]
+    pub similarity_threshold: f64,
+    // Nearest neighbours fetched per check, before keeping the owner's own
+    #[serde(default = "default_dedup_candidates")]
+    pub candidate_limit: usize,
+}
+
+fn default_similarity_threshold() -> f64 {
+    0.92
+}
+
+fn default_dedup_candidates() -> usize {
+    20
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        DedupConfig {
+            enabled: false,
+            similarity_threshold: default_similarity_threshold(),
+            candidate_limit: default_dedup_candidates(),
+        }
+    }
+}
+
+/// Export/snapshot archive settings
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ExportConfig {
+    // AES-256-GCM key: base64 of 32 bytes, passphrases are rejected (falls back to HELIX_EXPORT_KEY env)
+    #[serde(default)]
+    pub encryption_key: Option<String>,
+    // Encrypt archives even when the caller doesn't ask for it
+    #[serde(default)]
+    pub encrypt_by_default: bool,
+    // Directory export tools write output_path files to; callers only name the file
+    #[serde(default = "default_export_directory")]
+    pub directory: String,
+}
+
+fn default_export_directory() -> String {
+    "exports".to_string()
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            encryption_key: None,
+            encrypt_by_default: false,
+            directory: default_export_directory(),
+        }
+    }
+}
+
+/// Field-level encryption of sensitive properties before they reach HelixDB
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct FieldEncryptionConfig {
+    // Property names to encrypt (String schema properties only)
+    #[serde(default)]
+    pub fields: Vec<String>,
+    // Tenant key: base64 of 32 bytes, passphrases are rejected (falls back to HELIX_FIELD_KEY env)
+    #[serde(default)]
+    pub key: Option<String>,
+    // Keys of businesses whose data (and their customers') gets its own key;
+    // "env:VAR" reads the key from an environment variable (e.g. injected by a KMS)
+    #[serde(default)]
+    pub business_keys: BTreeMap<String, String>,
+}
+
+/// Per-collection embedding model tracking (MCP embedding mode)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct EmbeddingMetadataConfig {
+    // Record the model of each collection's vectors and check it on search
+    #[serde(default = "default_embedding_metadata_enabled")]
+    pub enabled: bool,
+    // What search does when the query model differs from the collection's model
+    #[serde(default)]
+    pub on_mismatch: ModelMismatchAction,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelMismatchAction {
+    #[default]
+    Warn,    // Search anyway and return a warning
+    Refuse,  // Skip the collection and return an error for it
+}
+
+fn default_embedding_metadata_enabled() -> bool {
+    true
+}
+
+impl Default for EmbeddingMetadataConfig {
+    fn default() -> Self {
+        EmbeddingMetadataConfig {
+            enabled: true,
+            on_mismatch: ModelMismatchAction::default(),
+        }
+    }
+}
+
+/// Fixed clock and sequential IDs for golden tests and record/replay runs
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DeterministicConfig {
+    // Replace wall-clock time and random UUIDs (never enable in production)
+    #[serde(default)]
+    pub enabled: bool,
+    // Unix timestamp the clock starts at
+    #[serde(default = "default_deterministic_start")]
+    pub start_timestamp: i64,
+    // Seconds the clock advances on every read
+    #[serde(default = "default_deterministic_step")]
+    pub step_secs: i64,
+}
+
+fn default_deterministic_start() -> i64 {
+    1_700_000_000
+}
+
+fn default_deterministic_step() -> i64 {
+    1
+}
+
+impl Default for DeterministicConfig {
+    fn default() -> Self {
+        DeterministicConfig {
+            enabled: false,
+            start_timestamp: default_deterministic_start(),
+            step_secs: default_deterministic_step(),
+        }
+    }
+}
+
+/// Alert rules checked on every memory create/update (more via create_alert_rule)
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct AlertsConfig {
+    #[serde(default)]
+    pub rules: Vec<crate::alerts::Rule>,
+}
+
+/// Outbound webhook notifications for memory changes
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct WebhooksConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    // Endpoint receiving POSTed JSON events
+    #[serde(default)]
+    pub url: String,
+    // HMAC-SHA256 key for the X-Signature header; empty sends unsigned events
+    #[serde(default)]
+    pub secret: String,
+    // Event filters such as "product.created", "*.deleted", "preference.*"; empty sends all
+    #[serde(default)]
+    pub events: Vec<String>,
+    // Delivery attempts after the first before an event is dead-lettered
+    #[serde(default = "default_webhook_retries")]
+    pub max_retries: u32,
+    // Delay before the first retry, doubled on each further retry
+    #[serde(default = "default_webhook_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "default_webhook_timeout")]
+    pub timeout_secs: u64,
+    // JSONL file for events that could not be delivered
+    #[serde(default = "default_webhook_dead_letter")]
+    pub dead_letter_path: String,
+}
+
+fn default_webhook_retries() -> u32 {
+    5
+}
+
+fn default_webhook_backoff_ms() -> u64 {
+    1000
+}
+
+fn default_webhook_timeout() -> u64 {
+    10
+}
+
+fn default_webhook_dead_letter() -> String {
+    "webhooks_dead_letter.jsonl".to_string()
+}
+
+impl Default for WebhooksConfig {
+    fn default() -> Self {
+        WebhooksConfig {
+            enabled: false,
+            url: String::new(),
+            secret: String::new(),
+            events: Vec::new(),
+            max_retries: default_webhook_retries(),
+            initial_backoff_ms: default_webhook_backoff_ms(),
+            timeout_secs: default_webhook_timeout(),
+            dead_letter_path: default_webhook_dead_letter(),
+        }
+    }
+}
+
+/// Live location sharing sessions for real-time guidance
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LiveLocationConfig {
+    // Session lifetime when start_location_sharing doesn't give one
+    #[serde(default = "default_live_ttl")]
+    pub default_ttl_minutes: u64,
+    #[serde(default = "default_live_max_ttl")]
+    pub max_ttl_minutes: u64,
+    // A waypoint counts as reached within this distance (or the fix accuracy, if larger)
+    #[serde(default = "default_arrival_radius")]
+    pub arrival_radius_meters: f64,
+    // Distance from the current route leg beyond which the customer is reported off route
+    #[serde(default = "default_off_route")]
+    pub off_route_meters: f64,
+}
+
+fn default_live_ttl() -> u64 {
+    30
+}
+
+fn default_live_max_ttl() -> u64 {
+    240
+}
+
+fn default_arrival_radius() -> f64 {
+    10.0
+}
+
+fn default_off_route() -> f64 {
+    40.0
+}
+
+impl Default for LiveLocationConfig {
+    fn default() -> Self {
+        LiveLocationConfig {
+            default_ttl_minutes: default_live_ttl(),
+            max_ttl_minutes: default_live_max_ttl(),
+            arrival_radius_meters: default_arrival_radius(),
+            off_route_meters: default_off_route(),
+        }
+    }
+}
+
+/// Dry-run mode for mutating tools
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct DryRunConfig {
+    // Run every mutating tool as a dry run, whatever the caller asks (safe prompt testing against production)
+    #[serde(default)]
+    pub enforce: bool,
+}
+
+/// Relevance reranking of semantic search candidates
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RerankConfig {
+    // Rerank every semantic search (callers can still pass rerank per call)
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub provider: RerankProvider,
+    // Cohere-compatible rerank endpoint
+    #[serde(default = "default_rerank_url")]
+    pub api_url: String,
+    #[serde(default = "default_rerank_model")]
+    pub model: String,
+    // Falls back to the COHERE_API_KEY environment variable
+    #[serde(default)]
+    pub api_key: Option<String>,
+    // Rerank server address for the tcp provider (same protocol as the TCP embedding server)
+    #[serde(default)]
+    pub tcp_address: Option<String>,
+    // Candidates fetched per memory type and sent to the reranker
+    #[serde(default = "default_rerank_candidates")]
+    pub candidates: usize,
+    #[serde(default = "default_rerank_timeout")]
+    pub timeout_secs: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RerankProvider {
+    #[default]
+    Cohere,  // Cohere rerank API or a compatible endpoint
+    Tcp,     // Local rerank service over TCP
+}
+
+fn default_rerank_url() -> String {
+    "https://api.cohere.com/v2/rerank".to_string()
+}
+
+fn default_rerank_model() -> String {
+    "rerank-v3.5".to_string()
+}
+
+fn default_rerank_candidates() -> usize {
+    50
+}
+
+fn default_rerank_timeout() -> u64 {
+    10
+}
+
+impl Default for RerankConfig {
+    fn default() -> Self {
+        RerankConfig {
+            enabled: false,
+            provider: RerankProvider::default(),
+            api_url: default_rerank_url(),
+            model: default_rerank_model(),
+            api_key: None,
+            tcp_address: None,
+            candidates: default_rerank_candidates(),
+            timeout_secs: default_rerank_timeout(),
+        }
+    }
+}
+
+/// Low-level graph traversal tools (HelixDB MCP endpoints)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TraversalConfig {
+    // Expose traversal_init, n_from_type, out_step, filter_items, ... to agents
+    #[serde(default)]
+    pub enabled: bool,
+    // Server-managed connections idle this long are closed
+    #[serde(default = "default_traversal_session_ttl")]
+    pub session_ttl_secs: u64,
+    // Open traversal connections across all MCP sessions
+    #[serde(default = "default_traversal_max_sessions")]
+    pub max_sessions: usize,
+}
+
+fn default_traversal_session_ttl() -> u64 {
+    600
+}
+
+fn default_traversal_max_sessions() -> usize {
+    32
+}
+
+impl Default for TraversalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            session_ttl_secs: default_traversal_session_ttl(),
+            max_sessions: default_traversal_max_sessions(),
+        }
+    }
+}
+
+/// Low-level traversal tools, listed and callable only with `[traversal] enabled`
+pub const TRAVERSAL_TOOLS: &[&str] = &[
+    "traversal_init", "traversal_next", "traversal_collect", "traversal_reset", "traversal_schema",
+    "n_from_type", "e_from_type", "out_step", "out_e_step", "in_step", "in_e_step",
+    "filter_items", "search_vector_text", "search_keyword",
+];
+
+impl TraversalConfig {
+    /// Whether agents may list and call `tool`
+    pub fn exposes(&self, tool: &str) -> bool {
+        self.enabled || !TRAVERSAL_TOOLS.contains(&tool)
+    }
+}
+
+/// Replay protection for create tools called with an idempotency_key
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct IdempotencyConfig {
+    // How long a key's result is replayed
+    #[serde(default = "default_idempotency_ttl")]
+    pub ttl_secs: u64,
+    // Keys remembered at once (oldest dropped first)
+    #[serde(default = "default_idempotency_max_keys")]
+    pub max_keys: usize,
+}
+
+fn default_idempotency_ttl() -> u64 {
+    86400
+}
+
+fn default_idempotency_max_keys() -> usize {
+    10000
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            ttl_secs: default_idempotency_ttl(),
+            max_keys: default_idempotency_max_keys(),
+        }
+    }
+}
+
+/// Background job queue for long-running operations (submit_job)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JobsConfig {
+    // Jobs running at once; the rest wait queued
+    #[serde(default = "default_jobs_max_concurrent")]
+    pub max_concurrent: usize,
+    // How long finished jobs (and their results) can be fetched
+    #[serde(default = "default_jobs_retention")]
+    pub retention_secs: u64,
+}
+
+fn default_jobs_max_concurrent() -> usize {
+    2
+}
+
+fn default_jobs_retention() -> u64 {
+    3600
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: default_jobs_max_concurrent(),
+            retention_secs: default_jobs_retention(),
+        }
+    }
+}
+
+/// Scheduled maintenance tasks; schedules are cron expressions in UTC
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MaintenanceConfig {
+    // Run the scheduler (maintenance_status is always available)
+    #[serde(default)]
+    pub enabled: bool,
+    // Close scheduled events whose end date has passed, for every business
+    #[serde(default = "default_expire_events_schedule")]
+    pub expire_events: String,
+    // Confidence decay with the [decay] policy
+    #[serde(default = "default_decay_schedule")]
+    pub decay: String,
+    // Drop embedding vectors left behind by cascade deletes
+    #[serde(default = "default_prune_orphan_vectors_schedule")]
+    pub prune_orphan_vectors: String,
+    // Check recorded and generated embedding dimensions against [embedding] dimensions
+    #[serde(default = "default_verify_embeddings_schedule")]
+    pub verify_embeddings: String,
+}
+
+fn default_expire_events_schedule() -> String {
+    "0 3 * * *".to_string()
+}
+
+fn default_decay_schedule() -> String {
+    "30 3 * * *".to_string()
+}
+
+fn default_prune_orphan_vectors_schedule() -> String {
+    "0 4 * * 0".to_string()
+}
+
+fn default_verify_embeddings_schedule() -> String {
+    "0 5 * * *".to_string()
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            expire_events: default_expire_events_schedule(),
+            decay: default_decay_schedule(),
+            prune_orphan_vectors: default_prune_orphan_vectors_schedule(),
+            verify_embeddings: default_verify_embeddings_schedule(),
+        }
+    }
+}
+
+/// Payload schemas for do_query endpoints
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DoQueryConfig {
+    // HelixQL query file (its QUERY signatures) or .json manifest; "" disables validation
+    #[serde(default = "default_query_manifest_path")]
+    pub manifest_path: String,
+}
+
+fn default_query_manifest_path() -> String {
+    "db/queries.hx".to_string()
+}
+
+impl Default for DoQueryConfig {
+    fn default() -> Self {
+        Self {
+            manifest_path: default_query_manifest_path(),
+        }
+    }
+}
+
+/// Right-to-erasure purges (forget_customer)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ErasureConfig {
+    // JSONL file each erasure's deletion manifest is appended to; "" keeps no audit file
+    #[serde(default = "default_erasure_audit_path")]
+    pub audit_path: String,
+}
+
+fn default_erasure_audit_path() -> String {
+    "erasure_audit.jsonl".to_string()
+}
+
+impl Default for ErasureConfig {
+    fn default() -> Self {
+        Self {
+            audit_path: default_erasure_audit_path(),
+        }
+    }
+}
+
+/// Business memory snapshots (create_snapshot / restore_snapshot)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SnapshotsConfig {
+    // Directory snapshot files are written to and restored from
+    #[serde(default = "default_snapshots_directory")]
+    pub directory: String,
+}
+
+fn default_snapshots_directory() -> String {
+    "snapshots".to_string()
+}
+
+impl Default for SnapshotsConfig {
+    fn default() -> Self {
+        Self {
+            directory: default_snapshots_directory(),
+        }
+    }
+}
+
+/// Where import_events_ics may read calendars from
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CalendarImportConfig {
+    // Directory `path` imports are read from; callers only name the file
+    #[serde(default = "default_calendar_import_directory")]
+    pub directory: String,
+    // Hosts feed URLs may point at; empty allows any host with a public address
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+}
+
+fn default_calendar_import_directory() -> String {
+    "imports".to_string()
+}
+
+impl Default for CalendarImportConfig {
+    fn default() -> Self {
+        Self {
+            directory: default_calendar_import_directory(),
+            allowed_hosts: Vec::new(),
+        }
+    }
+}
+
+/// Shopify Admin API credentials for sync_shopify_catalog
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ShopifyConfig {
+    // Store domain, e.g. "my-store.myshopify.com"; "" disables catalog sync
+    #[serde(default)]
+    pub shop_domain: String,
+    // Admin API access token (falls back to SHOPIFY_ACCESS_TOKEN env)
+    #[serde(default)]
+    pub access_token: Option<String>,
+    #[serde(default = "default_shopify_api_version")]
+    pub api_version: String,
+    // Products fetched per page (Shopify allows up to 250)
+    #[serde(default = "default_shopify_page_size")]
+    pub page_size: u32,
+}
+
+fn default_shopify_api_version() -> String {
+    "2024-10".to_string()
+}
+
+fn default_shopify_page_size() -> u32 {
+    250
+}
+
+impl Default for ShopifyConfig {
+    fn default() -> Self {
+        Self {
+            shop_domain: String::new(),
+            access_token: None,
+            api_version: default_shopify_api_version(),
+            page_size: default_shopify_page_size(),
+        }
+    }
+}
+
+/// Confidence decay for preference and behavior memories
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DecayConfig {
+    // Run decay periodically in the background (apply_decay tool is always available)
+    #[serde(default)]
+    pub enabled: bool,
+    // Days for a score to fall to half its value without new evidence
+    #[serde(default = "default_half_life_days")]
+    pub half_life_days: f64,
+    // Memories decaying below this score are deactivated
+    #[serde(default = "default_min_score")]
+    pub min_score: f64,
+    // Hours between background decay runs
+    #[serde(default = "default_decay_interval_hours")]
+    pub interval_hours: u64,
+}
+
+fn default_half_life_days() -> f64 {
+    90.0
+}
+
+fn default_min_score() -> f64 {
+    0.2
+}
+
+fn default_decay_interval_hours() -> u64 {
+    24
+}
+
+impl Default for DecayConfig {
+    fn default() -> Self {
+        DecayConfig {
+            enabled: false,
+            half_life_days: default_half_life_days(),
+            min_score: default_min_score(),
+            interval_hours: default_decay_interval_hours(),
+        }
+    }
+}
+
+/// Rate budget for embedding backfill / re-embed jobs
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BackfillConfig {
+    // Embedding requests per minute spent by backfill jobs (0 = unlimited)
+    #[serde(default = "default_backfill_rpm")]
+    pub requests_per_minute: u32,
+    // Re-embeds running at the same time
+    #[serde(default = "default_backfill_concurrency")]
+    pub max_concurrency: usize,
+    // Off-peak window in UTC hours [start, end); jobs only run inside it when both are set
+    #[serde(default)]
+    pub off_peak_start_hour: Option<u32>,
+    #[serde(default)]
+    pub off_peak_end_hour: Option<u32>,
+}
+
+fn default_backfill_rpm() -> u32 {
+    60
+}
+
+fn default_backfill_concurrency() -> usize {
+    2
+}
+
+impl Default for BackfillConfig {
+    fn default() -> Self {
+        BackfillConfig {
+            requests_per_minute: default_backfill_rpm(),
+            max_concurrency: default_backfill_concurrency(),
+            off_peak_start_hour: None,
+            off_peak_end_hour: None,
+        }
+    }
+}
+
+/// Cache of search-query embeddings (MCP embedding mode)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct QueryCacheConfig {
+    // Cached query texts (0 disables the cache)
+    #[serde(default = "default_query_cache_capacity")]
+    pub capacity: usize,
+    // Seconds a cached query embedding stays valid
+    #[serde(default = "default_query_cache_ttl_secs")]
+    pub ttl_secs: u64,
+}
+
+fn default_query_cache_capacity() -> usize {
+    2000
+}
+
+fn default_query_cache_ttl_secs() -> u64 {
+    300
+}
+
+impl Default for QueryCacheConfig {
+    fn default() -> Self {
+        QueryCacheConfig {
+            capacity: default_query_cache_capacity(),
+            ttl_secs: default_query_cache_ttl_secs(),
+        }
+    }
+}
+
+/// Ephemeral per-session working memory (remember_for_session / recall_session)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SessionMemoryConfig {
+    // Seconds of inactivity before a session's notes are discarded
+    #[serde(default = "default_session_ttl_secs")]
+    pub ttl_secs: u64,
+    // Maximum notes per session
+    #[serde(default = "default_session_max_notes")]
+    pub max_notes: usize,
+}
+
+fn default_session_ttl_secs() -> u64 {
+    3600
+}
+
+fn default_session_max_notes() -> usize {
+    200
+}
+
+impl Default for SessionMemoryConfig {
+    fn default() -> Self {
+        SessionMemoryConfig {
+            ttl_secs: default_session_ttl_secs(),
+            max_notes: default_session_max_notes(),
+        }
+    }
+}
+
+/// Sandbox (shadow tenant) sessions
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SandboxConfig {
+    // Seconds after which a sandbox's memories are deleted
+    #[serde(default = "default_sandbox_ttl_secs")]
+    pub ttl_secs: u64,
+    // Start every session in sandbox mode (for development servers)
+    #[serde(default)]
+    pub always_on: bool,
+}
+
+fn default_sandbox_ttl_secs() -> u64 {
+    3600
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        SandboxConfig {
+            ttl_secs: default_sandbox_ttl_secs(),
+            always_on: false,
+        }
+    }
+}
+
+/// Laplace noise for aggregate tools (analyze_interactions, summarize_feedback)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DifferentialPrivacyConfig {
+    // Privacy budget per response; smaller is more private and noisier
+    #[serde(default = "default_dp_epsilon")]
+    pub epsilon: f64,
+    // Per-record cap on purchase/service amounts, bounding spend sensitivity
+    #[serde(default = "default_dp_max_amount")]
+    pub max_amount: f64,
+    // Always add noise to business-wide (cross-customer) aggregates
+    #[serde(default)]
+    pub enforce_cross_customer: bool,
+}
+
+fn default_dp_epsilon() -> f64 {
+    1.0
+}
+
+fn default_dp_max_amount() -> f64 {
+    1000.0
+}
+
+impl Default for DifferentialPrivacyConfig {
+    fn default() -> Self {
+        DifferentialPrivacyConfig {
+            epsilon: default_dp_epsilon(),
+            max_amount: default_dp_max_amount(),
+            enforce_cross_customer: false,
+        }
+    }
+}
+
+/// Exchange rates for reporting mixed-currency amounts in one currency
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CurrencyConfig {
+    // Currency analytics totals are converted to; "" reports per-currency totals only
+    #[serde(default)]
+    pub reporting_currency: String,
+    // Currency the rates table is quoted against
+    #[serde(default = "default_currency_base")]
+    pub base: String,
+    // Static rates: units of each currency per one unit of `base`
+    #[serde(default)]
+    pub rates: BTreeMap<String, f64>,
+    // Live rates endpoint returning {"base": ..., "rates": {...}}; "" keeps the static table
+    #[serde(default)]
+    pub rates_url: String,
+    // How often live rates are refreshed
+    #[serde(default = "default_rates_refresh_secs")]
+    pub refresh_secs: u64,
+}
+
+fn default_currency_base() -> String {
+    "USD".to_string()
+}
+
+fn default_rates_refresh_secs() -> u64 {
+    3600
+}
+
+impl Default for CurrencyConfig {
+    fn default() -> Self {
+        CurrencyConfig {
+            reporting_currency: String::new(),
+            base: default_currency_base(),
+            rates: BTreeMap::new(),
+            rates_url: String::new(),
+            refresh_secs: default_rates_refresh_secs(),
+        }
+    }
+}
+
+/// Memory access log (get_access_log)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AccessLogConfig {
+    #[serde(default = "default_access_log_enabled")]
+    pub enabled: bool,
+    // JSONL file holding the full history; empty keeps only recent entries in memory
+    #[serde(default)]
+    pub path: String,
+    // Recent entries kept in memory
+    #[serde(default = "default_access_log_capacity")]
+    pub memory_capacity: usize,
+}
+
+fn default_access_log_enabled() -> bool {
+    true
+}
+
+fn default_access_log_capacity() -> usize {
+    10000
+}
+
+impl Default for AccessLogConfig {
+    fn default() -> Self {
+        AccessLogConfig {
+            enabled: default_access_log_enabled(),
+            path: String::new(),
+            memory_capacity: default_access_log_capacity(),
+        }
+    }
+}
+
+/// Per-session usage limits (0 = unlimited)
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct BudgetConfig {
+    // Tool calls per session
+    #[serde(default)]
+    pub max_tool_calls: u64,
+    // Embeddings generated per session (query cache hits are free)
+    #[serde(default)]
+    pub max_embeddings: u64,
+    // Rows fetched from HelixDB per session
+    #[serde(default)]
+    pub max_rows: u64,
+}
+
+impl BudgetConfig {
+    pub fn limits(&self) -> crate::budget::BudgetLimits {
+        crate::budget::BudgetLimits {
+            max_tool_calls: self.max_tool_calls,
+            max_embeddings: self.max_embeddings,
+            max_rows: self.max_rows,
+        }
+    }
+}
+
+/// Interactive vs background work scheduling
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PriorityConfig {
+    // Background (import, re-embed, consolidation) requests in flight at once,
+    // per resource (HelixDB, embedding provider); they also yield to interactive calls
+    #[serde(default = "default_background_concurrency")]
+    pub background_concurrency: usize,
+}
+
+fn default_background_concurrency() -> usize {
+    crate::priority::DEFAULT_BACKGROUND_CONCURRENCY
+}
+
+impl Default for PriorityConfig {
+    fn default() -> Self {
+        PriorityConfig {
+            background_concurrency: default_background_concurrency(),
+        }
+    }
+}
+
+/// Signature verification for events pushed by external platforms (`POST /ingest`)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct IngestConfig {
+    // Shared HMAC secret (falls back to HELIX_INGEST_SECRET env); without one every event is rejected
+    #[serde(default)]
+    pub signing_secret: Option<String>,
+    // "shopify", "stripe" or "hmac-sha256"
+    #[serde(default = "default_signature_scheme")]
+    pub signature_scheme: String,
+    // Maximum age of a timestamped (Stripe-style) signature
+    #[serde(default = "default_signature_tolerance_secs")]
+    pub timestamp_tolerance_secs: i64,
+}
+
+fn default_signature_scheme() -> String {
+    "hmac-sha256".to_string()
+}
+
+fn default_signature_tolerance_secs() -> i64 {
+    300
+}
+
+impl Default for IngestConfig {
+    fn default() -> Self {
+        IngestConfig {
+            signing_secret: None,
+            signature_scheme: default_signature_scheme(),
+            timestamp_tolerance_secs: default_signature_tolerance_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PayloadFormat {
+    #[default]
+    Json,     // Plain JSON
+    Msgpack,  // MessagePack bodies (Content-Type: application/msgpack)
+    Gzip,     // gzip-compressed JSON (Content-Encoding: gzip)
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
@@ -175,6 +1240,9 @@ impl Config {
 
         // Check environment based on provider
         match self.embedding.provider {
+            Some(EmbeddingProvider::OpenAI) if self.is_azure_openai() => {
+                std::env::var("AZURE_OPENAI_API_KEY").or_else(|_| std::env::var("OPENAI_API_KEY")).ok()
+            }
             Some(EmbeddingProvider::OpenAI) => {
                 std::env::var("OPENAI_API_KEY").ok()
             }
@@ -185,6 +1253,98 @@ impl Config {
         }
     }
 
+    /// Whether the OpenAI embedding path targets an Azure OpenAI deployment
+    pub fn is_azure_openai(&self) -> bool {
+        self.embedding.azure_deployment.as_deref().is_some_and(|d| !d.is_empty())
+    }
+
+    /// Embeddings URL for the OpenAI path; for Azure,
+    /// `<resource endpoint>/openai/deployments/<deployment>/embeddings?api-version=<version>`
+    pub fn openai_embeddings_url(&self) -> Option<String> {
+        let api_url = self.embedding.openai_api_url.as_deref()?;
+        if !self.is_azure_openai() {
+            return Some(api_url.to_string());
+        }
+        let deployment = self.embedding.azure_deployment.as_deref().unwrap_or_default();
+        let url = if api_url.contains("/deployments/") {
+            api_url.to_string()
+        } else {
+            format!("{}/openai/deployments/{}/embeddings", api_url.trim_end_matches('/'), deployment)
+        };
+        if url.contains("api-version=") {
+            return Some(url);
+        }
+        let separator = if url.contains('?') { '&' } else { '?' };
+        Some(format!("{}{}api-version={}", url, separator, self.embedding.azure_api_version))
+    }
+
+    /// Get the export encryption key from config or HELIX_EXPORT_KEY environment variable
+    pub fn get_export_key(&self) -> Option<String> {
+        if let Some(ref key) = self.export.encryption_key {
+            if !key.is_empty() {
+                return Some(key.clone());
+            }
+        }
+        std::env::var("HELIX_EXPORT_KEY").ok().filter(|k| !k.is_empty())
+    }
+
+    /// Get the rerank API key from config or COHERE_API_KEY environment variable
+    pub fn get_rerank_key(&self) -> Option<String> {
+        if let Some(ref key) = self.rerank.api_key {
+            if !key.is_empty() {
+                return Some(key.clone());
+            }
+        }
+        std::env::var("COHERE_API_KEY").ok().filter(|k| !k.is_empty())
+    }
+
+    /// Get the field encryption key from config or HELIX_FIELD_KEY env
+    pub fn get_field_key(&self) -> Option<String> {
+        if let Some(ref key) = self.field_encryption.key {
+            if !key.is_empty() {
+                return Some(key.clone());
+            }
+        }
+        std::env::var("HELIX_FIELD_KEY").ok().filter(|k| !k.is_empty())
+    }
+
+    /// Per-business field encryption keys, with `env:` references resolved
+    pub fn get_business_field_keys(&self) -> Result<Vec<(String, String)>, String> {
+        self.field_encryption
+            .business_keys
+            .iter()
+            .map(|(business_id, key)| {
+                let key = match key.strip_prefix("env:") {
+                    Some(var) => std::env::var(var).ok().filter(|k| !k.is_empty()).ok_or_else(|| {
+                        format!("Field encryption key for business '{}' not found in environment variable {}", business_id, var)
+                    })?,
+                    None => key.clone(),
+                };
+                Ok((business_id.clone(), key))
+            })
+            .collect()
+    }
+
+    /// Get the ingest signing secret from config or HELIX_INGEST_SECRET env
+    pub fn get_ingest_secret(&self) -> Option<String> {
+        if let Some(ref secret) = self.ingest.signing_secret {
+            if !secret.is_empty() {
+                return Some(secret.clone());
+            }
+        }
+        std::env::var("HELIX_INGEST_SECRET").ok().filter(|s| !s.is_empty())
+    }
+
+    /// Get the Shopify Admin API token from config or SHOPIFY_ACCESS_TOKEN env
+    pub fn get_shopify_token(&self) -> Option<String> {
+        if let Some(ref token) = self.shopify.access_token {
+            if !token.is_empty() {
+                return Some(token.clone());
+            }
+        }
+        std::env::var("SHOPIFY_ACCESS_TOKEN").ok().filter(|s| !s.is_empty())
+    }
+
     /// Check if MCP server should handle embedding generation
     pub fn is_mcp_embedding_enabled(&self) -> bool {
         self.embedding.mode == EmbeddingMode::Mcp
@@ -196,6 +1356,7 @@ impl Config {
     }
 
     /// Get the appropriate embedding API URL based on the provider
+    #[allow(dead_code)]
     pub fn get_embedding_api_url(&self) -> Option<String> {
         match self.embedding.provider {
             Some(EmbeddingProvider::OpenAI) => {
@@ -233,10 +1394,17 @@ impl Default for Config {
                 tcp_keepalive_retries: default_tcp_keepalive_retries(),
                 http_host: default_http_host(),
                 http_port: default_http_port(),
+                max_request_bytes: default_max_request_bytes(),
+                max_response_bytes: default_max_response_bytes(),
+                result_page_ttl_secs: default_result_page_ttl(),
             },
             helix: HelixConfig {
                 endpoint: "127.0.0.1".to_string(),
                 port: 6969,
+                max_parallel_queries: default_max_parallel_queries(),
+                read_endpoint: None,
+                read_port: None,
+                payload_format: PayloadFormat::default(),
             },
             embedding: EmbeddingConfig {
                 mode: EmbeddingMode::Helixdb, // Default to simpler mode
@@ -246,10 +1414,43 @@ impl Default for Config {
                 gemini_api_url: None,
                 local_api_url: None,
                 api_key: None,
-                dimensions: 1536,
+                dimensions: None,
                 tcp_address: None,
                 tcp_timeout_secs: 30,
+                tcp_pool_size: default_tcp_pool_size(),
+                azure_deployment: None,
+                azure_api_version: default_azure_api_version(),
             },
+            dedup: DedupConfig::default(),
+            export: ExportConfig::default(),
+            decay: DecayConfig::default(),
+            ingest: IngestConfig::default(),
+            backfill: BackfillConfig::default(),
+            priority: PriorityConfig::default(),
+            query_cache: QueryCacheConfig::default(),
+            session_memory: SessionMemoryConfig::default(),
+            budget: BudgetConfig::default(),
+            sandbox: SandboxConfig::default(),
+            differential_privacy: DifferentialPrivacyConfig::default(),
+            currency: CurrencyConfig::default(),
+            access_log: AccessLogConfig::default(),
+            field_encryption: FieldEncryptionConfig::default(),
+            deterministic: DeterministicConfig::default(),
+            embedding_metadata: EmbeddingMetadataConfig::default(),
+            webhooks: WebhooksConfig::default(),
+            alerts: AlertsConfig::default(),
+            live_location: LiveLocationConfig::default(),
+            dry_run: DryRunConfig::default(),
+            rerank: RerankConfig::default(),
+            traversal: TraversalConfig::default(),
+            idempotency: IdempotencyConfig::default(),
+            jobs: JobsConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+            do_query: DoQueryConfig::default(),
+            erasure: ErasureConfig::default(),
+            snapshots: SnapshotsConfig::default(),
+            shopify: ShopifyConfig::default(),
+            calendar_import: CalendarImportConfig::default(),
         }
     }
 }
@@ -266,6 +1467,57 @@ mod tests {
         assert!(!config.is_mcp_embedding_enabled());
     }
 
+    #[test]
+    fn test_azure_embeddings_url() {
+        let mut config = Config::default();
+        config.embedding.openai_api_url = Some("https://contoso.openai.azure.com/".to_string());
+        config.embedding.azure_deployment = Some("embeddings-small".to_string());
+        assert_eq!(
+            config.openai_embeddings_url().unwrap(),
+            "https://contoso.openai.azure.com/openai/deployments/embeddings-small/embeddings?api-version=2024-02-01"
+        );
+        config.embedding.azure_deployment = None;
+        assert_eq!(config.openai_embeddings_url().unwrap(), "https://contoso.openai.azure.com/");
+    }
+
+    #[test]
+    fn test_vector_dimensions_default() {
+        let mut config = Config::default();
+        assert_eq!(config.embedding.vector_dimensions(), 1536);
+        // TCP deployments configured before the setting existed keep 384
+        config.embedding.provider = Some(EmbeddingProvider::Tcp);
+        assert_eq!(config.embedding.vector_dimensions(), 384);
+        config.embedding.dimensions = Some(768);
+        assert_eq!(config.embedding.vector_dimensions(), 768);
+    }
+
+    #[test]
+    fn test_read_replica() {
+        let mut helix = Config::default().helix;
+        assert_eq!(helix.read_replica(None, None, 6969), None);
+        assert_eq!(helix.read_replica(Some(String::new()), Some("7000"), 6969), None);
+        assert_eq!(helix.read_replica(Some("replica".to_string()), None, 6969), Some(("replica".to_string(), 6969)));
+
+        helix.read_endpoint = Some("10.0.0.2".to_string());
+        helix.read_port = Some(7070);
+        assert_eq!(helix.read_replica(None, None, 6969), Some(("10.0.0.2".to_string(), 7070)));
+        // Environment overrides win; an unparsable port falls back to the config
+        assert_eq!(helix.read_replica(Some("replica".to_string()), Some("7000"), 6969), Some(("replica".to_string(), 7000)));
+        assert_eq!(helix.read_replica(None, Some("port"), 6969), Some(("10.0.0.2".to_string(), 7070)));
+    }
+
+    #[test]
+    fn test_traversal_tools_gated() {
+        let config = Config::default();
+        assert!(!config.traversal.exposes("out_step"));
+        assert!(!config.traversal.exposes("traversal_init"));
+        assert!(config.traversal.exposes("search_semantic"));
+
+        let traversal: TraversalConfig = toml::from_str("enabled = true").unwrap();
+        assert!(traversal.exposes("out_step"));
+        assert_eq!(traversal.session_ttl_secs, 600);
+    }
+
     #[test]
     fn test_api_key_from_env() {
         std::env::set_var("OPENAI_API_KEY", "test-key");
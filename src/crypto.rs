@@ -0,0 +1,365 @@
+//! AES-256-GCM encryption for data written to disk and sensitive fields
+//!
+//! Archive layout: `HXENC1` magic, 12-byte random nonce, ciphertext+tag.
+//! Encrypted field values are `enc:v1:` followed by base64 of nonce+ciphertext,
+//! or `enc:v2:<key id>:` for values under a business's own key.
+//! Keys are base64 of 32 random bytes (e.g. `openssl rand -base64 32`);
+//! passphrases are rejected rather than stretched into a weak key.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// Magic prefix identifying an encrypted archive
+pub const ARCHIVE_MAGIC: &[u8; 6] = b"HXENC1";
+const NONCE_LEN: usize = 12;
+
+/// Parse configured key material: base64 of exactly 32 bytes
+pub fn parse_key(key_material: &str) -> Result<[u8; 32], String> {
+    BASE64
+        .decode(key_material.trim())
+        .ok()
+        .and_then(|raw| <[u8; 32]>::try_from(raw.as_slice()).ok())
+        .ok_or_else(|| "Encryption key must be base64 of 32 bytes (generate one with `openssl rand -base64 32`); passphrases are not accepted".to_string())
+}
+
+/// Encrypt plaintext into an archive
+pub fn encrypt_archive(plaintext: &[u8], key_material: &str) -> Result<Vec<u8>, String> {
+    let key = parse_key(key_material)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|e| format!("Encryption failed: {}", e))?;
+
+    let mut archive = Vec::with_capacity(ARCHIVE_MAGIC.len() + NONCE_LEN + ciphertext.len());
+    archive.extend_from_slice(ARCHIVE_MAGIC);
+    archive.extend_from_slice(&nonce);
+    archive.extend_from_slice(&ciphertext);
+    Ok(archive)
+}
+
+/// Decrypt an archive produced by [`encrypt_archive`]
+pub fn decrypt_archive(archive: &[u8], key_material: &str) -> Result<Vec<u8>, String> {
+    let header_len = ARCHIVE_MAGIC.len() + NONCE_LEN;
+    if archive.len() < header_len || !archive.starts_with(ARCHIVE_MAGIC) {
+        return Err("Not an encrypted archive (missing HXENC1 header)".to_string());
+    }
+
+    let key = parse_key(key_material)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let nonce = Nonce::from_slice(&archive[ARCHIVE_MAGIC.len()..header_len]);
+    cipher
+        .decrypt(nonce, &archive[header_len..])
+        .map_err(|_| "Decryption failed: wrong key or corrupted archive".to_string())
+}
+
+/// Base64-encode bytes for inline (JSON) transport
+pub fn to_base64(bytes: &[u8]) -> String {
+    BASE64.encode(bytes)
+}
+
+/// Prefix marking a field value encrypted with the default key
+pub const FIELD_PREFIX: &str = "enc:v1:";
+/// Prefix of values encrypted with a business key: `enc:v2:<key id>:` then base64
+const BUSINESS_FIELD_PREFIX: &str = "enc:v2:";
+/// Properties holding embedding text, scrubbed of encrypted values
+const COMPOSITE_FIELDS: &[&str] = &["composite_text", "composite_embedding_text"];
+
+/// HelixDB schema, to check that configured fields can hold ciphertext
+const SCHEMA: &str = include_str!("../db/schema.hx");
+
+/// Configured fields that some schema node or edge stores as a type other
+/// than `String` (numbers, booleans, lists), which can't hold ciphertext
+fn non_string_fields(fields: &[String]) -> Vec<String> {
+    let mut rejected: Vec<String> = SCHEMA
+        .lines()
+        .filter_map(|line| {
+            let line = line.split("//").next()?.trim();
+            let line = line.strip_prefix("INDEX ").unwrap_or(line);
+            let (name, rest) = line.split_once(':')?;
+            let field_type = rest.split(|c: char| c.is_whitespace() || c == ',').find(|t| !t.is_empty())?;
+            (field_type != "String" && fields.iter().any(|f| f == name.trim())).then(|| name.trim().to_string())
+        })
+        .collect();
+    rejected.sort();
+    rejected.dedup();
+    rejected
+}
+
+/// Short public identifier of a key, stored with values so reads find their key
+fn key_id(key: &[u8; 32]) -> String {
+    hex::encode(&Sha256::digest(key)[..4])
+}
+
+/// Field-level encryption of configured properties sent to HelixDB.
+///
+/// Values are encrypted with a random nonce, so encrypted fields can't be
+/// matched in WHERE filters or BM25 search. Only string values can be
+/// encrypted: the ciphertext has to fit the property's schema type.
+/// Businesses with their own key get their data encrypted with it; the
+/// default key covers everything else. Plaintext of encrypted fields is
+/// replaced by a redacted form in composite embedding text.
+pub struct FieldCipher {
+    default: Option<Aes256Gcm>,
+    business_keys: HashMap<String, String>,
+    ciphers: HashMap<String, Aes256Gcm>,
+    fields: HashSet<String>,
+    // Business whose key covers each customer, once resolved
+    customer_businesses: Mutex<HashMap<String, String>>,
+}
+
+impl FieldCipher {
+    pub fn new(key_material: Option<&str>, fields: &[String]) -> Result<Self, String> {
+        let rejected = non_string_fields(fields);
+        if !rejected.is_empty() {
+            return Err(format!(
+                "Field encryption can't cover {}: the schema stores them as non-String types, which can't hold ciphertext; only String properties can be encrypted",
+                rejected.join(", ")
+            ));
+        }
+        let default = match key_material {
+            Some(k) => Some(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&parse_key(k)?))),
+            None => None,
+        };
+        Ok(FieldCipher {
+            default,
+            business_keys: HashMap::new(),
+            ciphers: HashMap::new(),
+            fields: fields.iter().cloned().collect(),
+            customer_businesses: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Encrypt `business_id`'s fields with its own key
+    pub fn with_business_key(mut self, business_id: &str, key_material: &str) -> Result<Self, String> {
+        let key = parse_key(key_material).map_err(|e| format!("Business '{}': {}", business_id, e))?;
+        let id = key_id(&key);
+        self.ciphers.insert(id.clone(), Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)));
+        self.business_keys.insert(business_id.to_string(), id);
+        Ok(self)
+    }
+
+    pub fn has_business_key(&self, business_id: &str) -> bool {
+        self.business_keys.contains_key(business_id)
+    }
+
+    pub fn has_business_keys(&self) -> bool {
+        !self.business_keys.is_empty()
+    }
+
+    /// Whether `payload` holds a configured field that still needs encrypting
+    pub fn needs_encryption(&self, payload: &Value) -> bool {
+        match payload {
+            Value::Object(map) => map.iter().any(|(key, value)| match value {
+                Value::String(s) => self.fields.contains(key) && !s.is_empty() && !s.starts_with("enc:"),
+                other => self.needs_encryption(other),
+            }),
+            Value::Array(items) => items.iter().any(|item| self.needs_encryption(item)),
+            _ => false,
+        }
+    }
+
+    /// Business whose key covers a customer, if already resolved
+    pub fn customer_business(&self, customer_id: &str) -> Option<String> {
+        self.customer_businesses.lock().unwrap().get(customer_id).cloned()
+    }
+
+    /// The first of a customer's linked businesses with a key of its own.
+    /// Remembered once found; customers not linked to one yet use the default key.
+    pub fn remember_customer(&self, customer_id: &str, businesses: &[String]) -> Option<String> {
+        let business = businesses.iter().find(|b| self.has_business_key(b)).cloned()?;
+        self.customer_businesses.lock().unwrap().insert(customer_id.to_string(), business.clone());
+        Some(business)
+    }
+
+    fn encrypt_value(&self, plaintext: &str, business_id: Option<&str>) -> Result<String, String> {
+        let (cipher, prefix) = match business_id.and_then(|b| self.business_keys.get(b)) {
+            Some(id) => (&self.ciphers[id], format!("{}{}:", BUSINESS_FIELD_PREFIX, id)),
+            None => (
+                self.default.as_ref().ok_or_else(|| match business_id {
+                    Some(b) => format!("No field encryption key for business '{}' and no default key is set", b),
+                    None => "No business to choose a field encryption key for and no default key is set".to_string(),
+                })?,
+                FIELD_PREFIX.to_string(),
+            ),
+        };
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|e| format!("Field encryption failed: {}", e))?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", prefix, BASE64.encode(sealed)))
+    }
+
+    fn decrypt_value(&self, value: &str) -> Option<String> {
+        let (cipher, encoded) = match value.strip_prefix(FIELD_PREFIX) {
+            Some(encoded) => (self.default.as_ref()?, encoded),
+            None => {
+                let (id, encoded) = value.strip_prefix(BUSINESS_FIELD_PREFIX)?.split_once(':')?;
+                (self.ciphers.get(id)?, encoded)
+            }
+        };
+        let sealed = BASE64.decode(encoded).ok()?;
+        if sealed.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let plaintext = cipher.decrypt(Nonce::from_slice(nonce), ciphertext).ok()?;
+        String::from_utf8(plaintext).ok()
+    }
+
+    /// Encrypt configured fields in a query payload (recursively) with the
+    /// key of `business_id`, or the default key, and redact their plaintext
+    /// from composite text. A configured field holding a number or bool is
+    /// an error rather than being sent in plaintext.
+    pub fn encrypt_payload(&self, payload: &mut Value, business_id: Option<&str>) -> Result<(), String> {
+        let mut secrets = Vec::new();
+        self.encrypt_fields(payload, business_id, &mut secrets)?;
+        // Longest first, so a value containing another is redacted whole
+        secrets.sort_by_key(|(plaintext, _)| std::cmp::Reverse(plaintext.len()));
+        redact_composites(payload, &secrets);
+        Ok(())
+    }
+
+    fn encrypt_fields(&self, payload: &mut Value, business_id: Option<&str>, secrets: &mut Vec<(String, String)>) -> Result<(), String> {
+        match payload {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if !self.fields.contains(key) {
+                        self.encrypt_fields(value, business_id, secrets)?;
+                        continue;
+                    }
+                    match value {
+                        Value::String(s) if s.is_empty() || s.starts_with("enc:") => {}
+                        Value::String(s) => {
+                            let sealed = self.encrypt_value(s, business_id)?;
+                            secrets.push((std::mem::replace(s, sealed), key.clone()));
+                        }
+                        Value::Null => {}
+                        _ => {
+                            return Err(format!(
+                                "Field '{}' is configured for encryption but is not a string; only String schema properties can hold ciphertext",
+                                key
+                            ));
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Value::Array(items) => items.iter_mut().try_for_each(|item| self.encrypt_fields(item, business_id, secrets)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Decrypt every encrypted string in a query result (recursively).
+    /// Values that fail to decrypt are left as stored.
+    pub fn decrypt_result(&self, result: &mut Value) {
+        match result {
+            Value::String(s) if s.starts_with("enc:") => {
+                if let Some(plaintext) = self.decrypt_value(s) {
+                    *s = plaintext;
+                }
+            }
+            Value::Object(map) => map.values_mut().for_each(|v| self.decrypt_result(v)),
+            Value::Array(items) => items.iter_mut().for_each(|v| self.decrypt_result(v)),
+            _ => {}
+        }
+    }
+}
+
+/// Replace encrypted plaintext in composite text with `[<field> redacted]`
+fn redact_composites(payload: &mut Value, secrets: &[(String, String)]) {
+    match payload {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match value {
+                    Value::String(text) if COMPOSITE_FIELDS.contains(&key.as_str()) => {
+                        for (plaintext, field) in secrets.iter().filter(|(p, _)| p.trim().chars().count() >= 3) {
+                            *text = text.replace(plaintext.trim(), &format!("[{} redacted]", field));
+                        }
+                    }
+                    other => redact_composites(other, secrets),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| redact_composites(item, secrets)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_key(byte: u8) -> String {
+        BASE64.encode([byte; 32])
+    }
+
+    #[test]
+    fn test_archive_roundtrip() {
+        let archive = encrypt_archive(b"{\"id\":\"1\"}\n", &test_key(1)).unwrap();
+        assert!(archive.starts_with(ARCHIVE_MAGIC));
+        assert_eq!(decrypt_archive(&archive, &test_key(1)).unwrap(), b"{\"id\":\"1\"}\n");
+        assert!(decrypt_archive(&archive, &test_key(2)).is_err());
+
+        // Passphrases and short keys are refused, not stretched
+        assert!(encrypt_archive(b"x", "correct horse battery staple").is_err());
+        assert!(parse_key(&BASE64.encode([1u8; 16])).is_err());
+    }
+
+    #[test]
+    fn test_field_roundtrip() {
+        // Numeric schema properties can't hold ciphertext and are refused up front
+        let error = FieldCipher::new(Some(&test_key(1)), &["contact_info".to_string(), "purchase_amount".to_string()]).err().unwrap();
+        assert!(error.contains("purchase_amount") && !error.contains("contact_info"));
+
+        let cipher = FieldCipher::new(Some(&test_key(1)), &["contact_info".to_string(), "custom_score".to_string()]).unwrap();
+        let mut payload = serde_json::json!({"contact_info": "+1 555 0100", "platform": "instagram", "items": [{"contact_info": "a@b.c"}]});
+        cipher.encrypt_payload(&mut payload, None).unwrap();
+        let stored = payload["contact_info"].as_str().unwrap().to_string();
+        assert!(stored.starts_with(FIELD_PREFIX));
+        assert_eq!(payload["platform"], "instagram");
+        assert!(payload["items"][0]["contact_info"].as_str().unwrap().starts_with(FIELD_PREFIX));
+
+        cipher.decrypt_result(&mut payload);
+        assert_eq!(payload["contact_info"], "+1 555 0100");
+        assert_eq!(payload["items"][0]["contact_info"], "a@b.c");
+
+        // Non-string values of fields outside the schema are refused, not leaked
+        assert!(cipher.encrypt_payload(&mut serde_json::json!({"custom_score": 12.5}), None).is_err());
+    }
+
+    #[test]
+    fn test_business_keys_and_redaction() {
+        let fields = ["contact_info".to_string()];
+        let cipher = FieldCipher::new(None, &fields).unwrap().with_business_key("biz_a", &test_key(2)).unwrap();
+        let mut payload = serde_json::json!({
+            "business_id": "biz_a",
+            "contact_info": "hello@cafe.example",
+            "composite_text": "Instagram @cafe. Contact: hello@cafe.example"
+        });
+        cipher.encrypt_payload(&mut payload, Some("biz_a")).unwrap();
+        assert!(payload["contact_info"].as_str().unwrap().starts_with("enc:v2:"));
+        assert_eq!(payload["composite_text"], "Instagram @cafe. Contact: [contact_info redacted]");
+
+        // Another business's key can't read it, and without a default key nothing is sent in plaintext
+        let other = FieldCipher::new(Some(&test_key(1)), &fields).unwrap().with_business_key("biz_b", &test_key(3)).unwrap();
+        let mut foreign = payload.clone();
+        other.decrypt_result(&mut foreign);
+        assert!(foreign["contact_info"].as_str().unwrap().starts_with("enc:v2:"));
+        assert!(cipher.encrypt_payload(&mut serde_json::json!({"contact_info": "x@y.z"}), Some("biz_c")).is_err());
+
+        cipher.decrypt_result(&mut payload);
+        assert_eq!(payload["contact_info"], "hello@cafe.example");
+        assert_eq!(cipher.remember_customer("cust_1", &["biz_b".to_string(), "biz_a".to_string()]), Some("biz_a".to_string()));
+        assert_eq!(cipher.customer_business("cust_1"), Some("biz_a".to_string()));
+        assert_eq!(cipher.remember_customer("cust_2", &["biz_b".to_string()]), None);
+    }
+}
@@ -0,0 +1,157 @@
+//! Exchange rates for normalizing amounts recorded in mixed currencies
+//!
+//! Interactions store `purchase_amount` / `cost_actual` in whatever currency
+//! the sale used. Aggregations keep the per-currency totals and, given a
+//! reporting currency, add one converted total. Rates come from the static
+//! `[currency] rates` table; a `RateProvider` (the built-in one polls
+//! `rates_url`) can replace them at runtime.
+
+use async_trait::async_trait;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+/// Rate table: units of each currency per one unit of `base`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rates {
+    pub base: String,
+    pub rates: BTreeMap<String, f64>,
+    pub source: String,
+    pub updated_at: Option<i64>,
+}
+
+impl Rates {
+    pub fn new(base: &str, rates: &BTreeMap<String, f64>, source: &str) -> Self {
+        let base = base.to_uppercase();
+        let mut rates: BTreeMap<String, f64> = rates
+            .iter()
+            .filter(|(_, rate)| rate.is_finite() && **rate > 0.0)
+            .map(|(code, rate)| (code.to_uppercase(), *rate))
+            .collect();
+        rates.insert(base.clone(), 1.0);
+        Rates { base, rates, source: source.to_string(), updated_at: None }
+    }
+
+    /// Units of `to` per one unit of `from`
+    pub fn rate(&self, from: &str, to: &str) -> Option<f64> {
+        let from = self.rates.get(&from.to_uppercase())?;
+        let to = self.rates.get(&to.to_uppercase())?;
+        Some(to / from)
+    }
+}
+
+/// Where live rates come from
+#[async_trait]
+pub trait RateProvider: Send + Sync {
+    async fn fetch(&self) -> Result<Rates, String>;
+}
+
+/// Polls a JSON endpoint answering `{"base": "USD", "rates": {"EUR": 0.92, ...}}`
+pub struct HttpRateProvider {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl HttpRateProvider {
+    pub fn new(url: &str) -> Self {
+        HttpRateProvider { url: url.to_string(), client: reqwest::Client::new() }
+    }
+}
+
+#[async_trait]
+impl RateProvider for HttpRateProvider {
+    async fn fetch(&self) -> Result<Rates, String> {
+        let body: Value = self.client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Failed to fetch exchange rates: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid exchange rate response: {}", e))?;
+        let base = body.get("base").and_then(|v| v.as_str()).ok_or("Exchange rate response has no base")?;
+        let rates: BTreeMap<String, f64> = body
+            .get("rates")
+            .and_then(|v| v.as_object())
+            .ok_or("Exchange rate response has no rates")?
+            .iter()
+            .filter_map(|(code, rate)| Some((code.clone(), rate.as_f64()?)))
+            .collect();
+        Ok(Rates::new(base, &rates, "live"))
+    }
+}
+
+/// Current rate table, swapped whole when a provider refreshes it
+pub struct ExchangeRates {
+    current: RwLock<Rates>,
+}
+
+impl ExchangeRates {
+    pub fn new(rates: Rates) -> Self {
+        ExchangeRates { current: RwLock::new(rates) }
+    }
+
+    pub fn current(&self) -> Rates {
+        self.current.read().unwrap().clone()
+    }
+
+    pub fn update(&self, rates: Rates) {
+        *self.current.write().unwrap() = rates;
+    }
+}
+
+/// Per-currency totals (`{"EUR": 12.0, "USD": 5.0}`) as one total in `reporting`.
+/// Amounts without a known rate (or currency) are listed as unconverted.
+pub fn normalize_totals(totals: &Map<String, Value>, rates: &Rates, reporting: &str) -> Value {
+    let reporting = reporting.to_uppercase();
+    let mut total = 0.0;
+    let mut used = BTreeMap::new();
+    let mut unconverted = Map::new();
+    for (currency, amount) in totals {
+        let Some(amount) = amount.as_f64() else {
+            continue;
+        };
+        match rates.rate(currency, &reporting) {
+            Some(rate) => {
+                total += amount * rate;
+                used.insert(currency.clone(), rate);
+            }
+            None => {
+                unconverted.insert(currency.clone(), json!(amount));
+            }
+        }
+    }
+    json!({
+        "currency": reporting,
+        "total": (total * 100.0).round() / 100.0,
+        "rates_used": used,
+        "unconverted": unconverted,
+        "rates_source": rates.source,
+        "rates_updated_at": rates.updated_at
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_totals() {
+        let table: BTreeMap<String, f64> = [("eur".to_string(), 0.5), ("GBP".to_string(), 0.25), ("BAD".to_string(), 0.0)].into();
+        let rates = Rates::new("usd", &table, "static");
+        assert_eq!(rates.rate("EUR", "GBP"), Some(0.5));
+        assert_eq!(rates.rate("GBP", "USD"), Some(4.0));
+        assert!(rates.rate("BAD", "USD").is_none());
+
+        let totals = json!({"EUR": 10.0, "USD": 5.0, "UNSPECIFIED": 3.0});
+        let normalized = normalize_totals(totals.as_object().unwrap(), &rates, "eur");
+        assert_eq!(normalized["currency"], "EUR");
+        assert_eq!(normalized["total"], 12.5);
+        assert_eq!(normalized["unconverted"], json!({"UNSPECIFIED": 3.0}));
+
+        let live = ExchangeRates::new(rates);
+        live.update(Rates::new("USD", &BTreeMap::new(), "live"));
+        assert_eq!(live.current().source, "live");
+    }
+}
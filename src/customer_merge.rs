@@ -0,0 +1,105 @@
+//! Merging duplicate customer profiles
+//!
+//! The same person often reaches a business from several channels
+//! (WhatsApp, web chat, the store) under different customer IDs.
+//! `merge_customers` moves everything stored under the duplicate ID onto
+//! the canonical one and leaves a `CustomerAlias` behind. Preferences about
+//! the same subject are reconciled first: the most recently observed one
+//! stays active and the other is deactivated, whether they agree or not.
+
+use serde_json::{json, Value};
+
+/// Preference kept and preference deactivated for one subject
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolution {
+    pub subject: String,
+    pub kept: String,
+    pub deactivated: String,
+    /// `conflict` when the preference types differ, else `duplicate`
+    pub kind: &'static str,
+}
+
+impl Resolution {
+    pub fn to_json(&self) -> Value {
+        json!({"subject": self.subject, "kept": self.kept, "deactivated": self.deactivated, "kind": self.kind})
+    }
+}
+
+fn text(memory: &Value, field: &str) -> String {
+    memory.get(field).and_then(|v| v.as_str()).unwrap_or("").trim().to_lowercase()
+}
+
+/// Subject a preference is about: category and subject, or nothing to match on
+fn subject_key(preference: &Value) -> Option<(String, String)> {
+    let subject = text(preference, "subject");
+    (!subject.is_empty()).then(|| (text(preference, "category"), subject))
+}
+
+fn observed_at(preference: &Value) -> i64 {
+    ["last_evidence", "updated_at", "created_at"]
+        .iter()
+        .find_map(|f| preference.get(*f).and_then(|v| v.as_i64()))
+        .unwrap_or(0)
+}
+
+/// Reconcile active preferences of both customers that share a subject;
+/// ties go to the canonical customer
+pub fn resolve_preferences(canonical: &[Value], duplicate: &[Value]) -> Vec<Resolution> {
+    let active = |p: &&Value| p.get("is_active").and_then(|v| v.as_bool()).unwrap_or(true);
+    let mut resolutions = Vec::new();
+    for theirs in duplicate.iter().filter(active) {
+        let Some(key) = subject_key(theirs) else {
+            continue;
+        };
+        let Some(ours) = canonical.iter().filter(active).find(|p| subject_key(p).as_ref() == Some(&key)) else {
+            continue;
+        };
+        let (kept, deactivated) = if observed_at(theirs) > observed_at(ours) { (theirs, ours) } else { (ours, theirs) };
+        resolutions.push(Resolution {
+            subject: key.1,
+            kept: text(kept, "preference_id"),
+            deactivated: text(deactivated, "preference_id"),
+            kind: if text(ours, "preference_type") == text(theirs, "preference_type") { "duplicate" } else { "conflict" },
+        });
+    }
+    resolutions
+}
+
+/// Contact fields the canonical profile lacks and the duplicate has
+pub fn missing_contact(canonical: &Value, duplicate: &Value) -> Vec<(&'static str, String)> {
+    ["phone", "email", "language"]
+        .into_iter()
+        .filter(|field| text(canonical, field).is_empty())
+        .filter_map(|field| {
+            let value = duplicate.get(field).and_then(|v| v.as_str()).unwrap_or("").trim();
+            (!value.is_empty()).then(|| (field, value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_preferences_and_contact() {
+        let canonical = vec![
+            json!({"preference_id": "p1", "preference_type": "like", "category": "drinks", "subject": "Oat milk", "last_evidence": 100}),
+            json!({"preference_id": "p2", "preference_type": "like", "category": "food", "subject": "croissants", "last_evidence": 300}),
+            json!({"preference_id": "p3", "preference_type": "like", "subject": "", "last_evidence": 100}),
+        ];
+        let duplicate = vec![
+            json!({"preference_id": "d1", "preference_type": "dislike", "category": "Drinks", "subject": "oat milk", "last_evidence": 200}),
+            json!({"preference_id": "d2", "preference_type": "like", "category": "food", "subject": "croissants", "last_evidence": 300}),
+            json!({"preference_id": "d3", "preference_type": "like", "subject": "", "last_evidence": 900}),
+            json!({"preference_id": "d4", "preference_type": "like", "subject": "jazz", "is_active": false}),
+        ];
+        let resolutions = resolve_preferences(&canonical, &duplicate);
+        assert_eq!(resolutions.len(), 2);
+        assert_eq!((resolutions[0].kept.as_str(), resolutions[0].deactivated.as_str(), resolutions[0].kind), ("d1", "p1", "conflict"));
+        assert_eq!((resolutions[1].kept.as_str(), resolutions[1].kind), ("p2", "duplicate"));
+
+        let filled = missing_contact(&json!({"phone": "+100", "email": ""}), &json!({"phone": "+200", "email": "a@b.c", "language": " "}));
+        assert_eq!(filled, vec![("email", "a@b.c".to_string())]);
+    }
+}
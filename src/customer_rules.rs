@@ -0,0 +1,252 @@
+//! Checking proposed actions against customer rule memories
+//!
+//! `evaluate_customer_rules` reads the customer's active rules and looks for
+//! ones the action would break:
+//! - time windows: "no messages after 8pm", "only call between 9am and 5pm"
+//! - channels: "email only", "no phone calls"
+//! - exclusions: "never recommend dairy", "no promotions"
+//!
+//! A rule limited to channels ("no calls after 8pm") only applies to those
+//! channels, and a rule doesn't apply when the action mentions one of its
+//! exceptions. Strict rules, and rules without an enforcement level, deny the
+//! action; flexible rules and guidelines come back as advisories.
+
+use serde_json::{json, Value};
+
+const NEGATIONS: &[&str] = &["no", "not", "never", "don't", "dont", "avoid", "without", "stop"];
+
+const CHANNELS: &[(&str, &[&str])] = &[
+    ("email", &["email", "e-mail", "mail", "newsletter"]),
+    ("phone", &["phone", "call", "calling", "telephone"]),
+    ("sms", &["sms", "text", "texting"]),
+    ("whatsapp", &["whatsapp"]),
+    ("push", &["push", "notification"]),
+];
+
+/// Words that don't name what a rule rules out
+const FILLER: &[&str] = &[
+    "a", "an", "the", "to", "me", "my", "any", "at", "on", "in", "of", "for", "with", "or", "and", "be", "is", "are",
+    "do", "send", "sending", "recommend", "recommending", "suggest", "offer", "offering", "contact", "contacting",
+    "product", "item", "service", "category", "thing", "anything", "ever", "please", "customer", "them", "him", "her",
+];
+
+fn stem(word: &str) -> &str {
+    match word.strip_suffix('s') {
+        Some(stem) if stem.len() > 2 && !stem.ends_with('s') => stem,
+        _ => word,
+    }
+}
+
+fn tokens(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == ':' || c == '\'' || c == '-'))
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Minutes after midnight of a time at `tokens[i]`: "9pm", "9:30 pm", "21:00", "noon"
+fn time_at(tokens: &[String], i: usize) -> Option<u32> {
+    let token = tokens.get(i)?.as_str();
+    match token {
+        "noon" | "midday" => return Some(12 * 60),
+        "midnight" => return Some(0),
+        _ => {}
+    }
+    let (clock, suffix) = match token.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(at) => (&token[..at], Some(&token[at..])),
+        None => (token, tokens.get(i + 1).map(String::as_str).filter(|t| matches!(*t, "am" | "pm"))),
+    };
+    let (hours, minutes) = match clock.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok().filter(|m| *m < 60)?),
+        None if suffix.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hours = match suffix {
+        Some("am") if (1..=12).contains(&hours) => hours % 12,
+        Some("pm") if (1..=12).contains(&hours) => hours % 12 + 12,
+        None if hours < 24 => hours,
+        _ => return None,
+    };
+    Some(hours * 60 + minutes)
+}
+
+/// First time of day mentioned in `text`
+pub fn time_of_day(text: &str) -> Option<u32> {
+    let tokens = tokens(text);
+    (0..tokens.len()).find_map(|i| time_at(&tokens, i))
+}
+
+fn channels(tokens: &[String]) -> Vec<&'static str> {
+    CHANNELS
+        .iter()
+        .filter(|(_, words)| tokens.iter().any(|t| words.contains(&stem(t))))
+        .map(|(channel, _)| *channel)
+        .collect()
+}
+
+fn clock(minutes: u32) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// A proposed action, as words plus the channel and time it would use
+pub struct Action {
+    words: Vec<String>,
+    channels: Vec<&'static str>,
+    minute: Option<u32>,
+}
+
+impl Action {
+    pub fn new(text: &str, channel: Option<&str>, minute: Option<u32>) -> Self {
+        let words = tokens(&format!("{} {}", text, channel.unwrap_or("")));
+        let channels = channels(&words);
+        Action { minute: minute.or_else(|| time_of_day(text)), words, channels }
+    }
+
+    fn mentions(&self, word: &str) -> bool {
+        self.words.iter().any(|w| stem(w) == stem(word))
+    }
+}
+
+/// Allowed time of day from "after", "before" and "between ... and ..." in a rule
+enum Window {
+    After(u32),
+    Before(u32),
+    Between(u32, u32),
+}
+
+impl Window {
+    fn parse(tokens: &[String]) -> Option<Window> {
+        let at = |word: &str| tokens.iter().position(|t| t == word);
+        if let Some(i) = at("between") {
+            let from = time_at(tokens, i + 1)?;
+            let and = tokens[i + 1..].iter().position(|t| t == "and")? + i + 1;
+            return Some(Window::Between(from, time_at(tokens, and + 1)?));
+        }
+        if let Some(i) = at("after") {
+            return time_at(tokens, i + 1).map(Window::After);
+        }
+        at("before").and_then(|i| time_at(tokens, i + 1)).map(Window::Before)
+    }
+
+    fn contains(&self, minute: u32) -> bool {
+        match *self {
+            Window::After(t) => minute >= t,
+            Window::Before(t) => minute < t,
+            Window::Between(from, to) if from <= to => (from..to).contains(&minute),
+            Window::Between(from, to) => minute >= from || minute < to,
+        }
+    }
+}
+
+/// Why `action` breaks `rule`, if it does
+pub fn conflict(rule: &Value, action: &Action) -> Option<String> {
+    let text = |field: &str| rule.get(field).and_then(|v| v.as_str()).unwrap_or("");
+    let description = match text("rule_description") {
+        "" => text("text_description"),
+        d => d,
+    };
+    let exceptions = rule.get("exceptions").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    let excepted = exceptions.iter().filter_map(|e| e.as_str()).any(|exception| {
+        let words: Vec<String> = tokens(exception).into_iter().filter(|w| w.len() > 2 && !FILLER.contains(&stem(w))).collect();
+        !words.is_empty() && words.iter().all(|w| action.mentions(w))
+    });
+    if excepted {
+        return None;
+    }
+
+    let words = tokens(description);
+    let negated = words.iter().position(|w| NEGATIONS.contains(&w.as_str()));
+    let (before, after) = words.split_at(negated.unwrap_or(words.len()));
+    let rule_channels = channels(&words);
+    let channel_applies = rule_channels.is_empty() || action.channels.iter().any(|c| rule_channels.contains(c));
+
+    if let Some(window) = Window::parse(&words) {
+        let minute = action.minute?;
+        // "no calls after 8pm" forbids the window; "call after 8pm" allows only it
+        let forbidden = if negated.is_some() { window.contains(minute) } else { !window.contains(minute) };
+        return (channel_applies && forbidden).then(|| format!("{} is outside the allowed hours", clock(minute)));
+    }
+    if !rule_channels.is_empty() {
+        // "email only, no phone calls": channels before the negation are the allowed ones
+        let allowed = if before.iter().any(|w| w == "only" || w == "exclusively") { channels(before) } else { Vec::new() };
+        if !allowed.is_empty() && !action.channels.is_empty() && !action.channels.iter().any(|c| allowed.contains(c)) {
+            return Some(format!("only {} is allowed", allowed.join("/")));
+        }
+        let forbidden: Vec<&str> = channels(after).into_iter().filter(|c| action.channels.contains(c)).collect();
+        return (!forbidden.is_empty()).then(|| format!("{} is ruled out", forbidden.join("/")));
+    }
+    negated?;
+    let excluded: Vec<&String> = after[1..]
+        .iter()
+        .take_while(|w| !matches!(w.as_str(), "unless" | "except" | "but"))
+        .filter(|w| w.len() > 2 && !FILLER.contains(&stem(w)) && !NEGATIONS.contains(&w.as_str()))
+        .collect();
+    let hit = excluded.into_iter().find(|w| action.mentions(w))?;
+    Some(format!("mentions \"{}\", which the rule rules out", hit))
+}
+
+/// Verdict on `action` under the customer's rules: denying conflicts, advisories and the rules without conflicts
+pub fn evaluate(rules: &[Value], action: &Action) -> Value {
+    let active: Vec<&Value> = rules.iter().filter(|r| r.get("is_active").and_then(|v| v.as_bool()).unwrap_or(false)).collect();
+    let (mut violations, mut advisories, mut other) = (Vec::new(), Vec::new(), Vec::new());
+    for rule in &active {
+        let field = |name: &str| rule.get(name).cloned().unwrap_or(Value::Null);
+        let summary = json!({
+            "rule_id": field("rule_id"),
+            "rule_description": field("rule_description"),
+            "category": field("category"),
+            "enforcement": field("enforcement"),
+        });
+        match conflict(rule, action) {
+            Some(reason) => {
+                let mut entry = summary;
+                entry["reason"] = json!(reason);
+                let soft = matches!(rule.get("enforcement").and_then(|v| v.as_str()), Some("flexible" | "guideline"));
+                if soft { advisories.push(entry) } else { violations.push(entry) }
+            }
+            None => other.push(summary),
+        }
+    }
+    json!({
+        "decision": if violations.is_empty() { "allow" } else { "deny" },
+        "violations": violations,
+        "advisories": advisories,
+        "rules_checked": active.len(),
+        "other_active_rules": other
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_rules() {
+        let rule = |id: &str, description: &str, enforcement: &str, exceptions: &[&str]| {
+            json!({"rule_id": id, "rule_description": description, "enforcement": enforcement, "exceptions": exceptions, "is_active": true})
+        };
+        let rules = vec![
+            rule("R1", "No promotional messages after 8pm", "strict", &["order updates"]),
+            rule("R2", "Email only, no phone calls", "strict", &[]),
+            rule("R3", "Never recommend dairy products", "flexible", &[]),
+            json!({"rule_id": "R4", "rule_description": "No SMS", "is_active": false}),
+        ];
+        assert_eq!(time_of_day("send promo at 9:30 pm"), Some(21 * 60 + 30));
+        assert_eq!(time_of_day("between 21:00 and noon"), Some(21 * 60));
+
+        let verdict = evaluate(&rules, &Action::new("Send promo at 9pm", Some("email"), None));
+        assert_eq!(verdict["decision"], "deny");
+        assert_eq!(verdict["violations"][0]["rule_id"], "R1");
+        assert_eq!(verdict["rules_checked"], 3);
+
+        let verdict = evaluate(&rules, &Action::new("Send order update at 9pm", Some("email"), None));
+        assert_eq!(verdict["decision"], "allow");
+
+        let verdict = evaluate(&rules, &Action::new("Call about the new menu", None, Some(10 * 60)));
+        assert_eq!(verdict["violations"][0]["rule_id"], "R2");
+
+        let verdict = evaluate(&rules, &Action::new("Recommend product category Dairy", None, None));
+        assert_eq!((verdict["decision"].as_str(), verdict["advisories"][0]["rule_id"].as_str()), (Some("allow"), Some("R3")));
+    }
+}
@@ -0,0 +1,89 @@
+//! Confidence decay for preference and behavior memories
+//!
+//! Scores decay exponentially with a configurable half-life, measured from
+//! the later of the last evidence and the last decay run, so repeated runs
+//! compound correctly instead of re-applying the full age each time.
+
+use serde_json::Value;
+
+/// Seconds per day
+const DAY_SECS: f64 = 86_400.0;
+
+/// Decay parameters for one run
+#[derive(Debug, Clone, Copy)]
+pub struct DecayPolicy {
+    pub half_life_days: f64,
+    pub min_score: f64,
+    pub now: i64,
+}
+
+/// Planned change for a single memory
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecayUpdate {
+    pub previous_score: f64,
+    pub score: f64,
+    pub is_active: bool,
+}
+
+/// Apply exponential decay to `score` over `elapsed_secs`
+pub fn decayed_score(score: f64, elapsed_secs: i64, half_life_days: f64) -> f64 {
+    if elapsed_secs <= 0 || half_life_days <= 0.0 {
+        return score;
+    }
+    let elapsed_days = elapsed_secs as f64 / DAY_SECS;
+    score * 0.5_f64.powf(elapsed_days / half_life_days)
+}
+
+/// Plan the decay of one memory node.
+///
+/// `score_field` holds the current score and `evidence_field` the timestamp of
+/// the most recent supporting evidence. Returns `None` for inactive memories
+/// or when nothing changes.
+pub fn plan_decay(memory: &Value, score_field: &str, evidence_field: &str, policy: &DecayPolicy) -> Option<DecayUpdate> {
+    if memory.get("is_active").and_then(|v| v.as_bool()) == Some(false) {
+        return None;
+    }
+
+    let previous_score = memory.get(score_field).and_then(|v| v.as_f64())?;
+    let last_evidence = memory.get(evidence_field).and_then(|v| v.as_i64()).unwrap_or(policy.now);
+    let decayed_at = memory.get("decayed_at").and_then(|v| v.as_i64()).unwrap_or(0);
+    let since = last_evidence.max(decayed_at);
+
+    let score = decayed_score(previous_score, policy.now - since, policy.half_life_days);
+    let is_active = score >= policy.min_score;
+    if (score - previous_score).abs() < f64::EPSILON && is_active {
+        return None;
+    }
+
+    Some(DecayUpdate { previous_score, score, is_active })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_half_life() {
+        let halved = decayed_score(0.8, 30 * 86_400, 30.0);
+        assert!((halved - 0.4).abs() < 1e-9);
+        assert_eq!(decayed_score(0.8, 0, 30.0), 0.8);
+    }
+
+    #[test]
+    fn test_plan_decay_compounds_from_last_run() {
+        let policy = DecayPolicy { half_life_days: 30.0, min_score: 0.3, now: 60 * 86_400 };
+        let memory = json!({
+            "confidence_score": 0.5,
+            "last_evidence": 0,
+            "decayed_at": 30 * 86_400,
+            "is_active": true
+        });
+        let update = plan_decay(&memory, "confidence_score", "last_evidence", &policy).unwrap();
+        assert!((update.score - 0.25).abs() < 1e-9);
+        assert!(!update.is_active);
+
+        let inactive = json!({"confidence_score": 0.9, "is_active": false});
+        assert!(plan_decay(&inactive, "confidence_score", "last_evidence", &policy).is_none());
+    }
+}
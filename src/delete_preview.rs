@@ -0,0 +1,315 @@
+//! What a cascade or complete delete would remove
+//!
+//! `preview_business_delete` / `preview_customer_delete` return the memory
+//! nodes the cascade queries drop, the edges attached to them (which go with
+//! the nodes), their embedding vectors, and the related nodes that survive.
+//! The cascade queries drop memory nodes but not their vectors, so vectors
+//! are reported as orphaned rather than removed.
+
+use crate::helix_client::HelixClient;
+use serde_json::{json, Map, Value};
+
+/// IDs listed per memory type; counts are always exact
+const MAX_LISTED_IDS: usize = 50;
+
+/// One memory type dropped by the cascade, keyed by the preview query's return names
+struct Group {
+    memory_type: &'static str,
+    nodes: &'static str,
+    id_field: &'static str,
+    vectors: &'static str,
+    embedding_edge: &'static str,
+    /// Edges to the root node; in a complete delete these are counted from the root instead
+    owner_edges: &'static [(&'static str, &'static str)],
+    edges: &'static [(&'static str, &'static str)],
+}
+
+struct Target {
+    root_type: &'static str,
+    query: &'static str,
+    id_field: &'static str,
+    groups: &'static [Group],
+    /// Edges of the root node itself, removed only by a complete delete
+    root_edges: &'static [(&'static str, &'static str)],
+    /// Related nodes that neither strategy deletes
+    survivors: &'static [(&'static str, &'static str)],
+}
+
+const BUSINESS: Target = Target {
+    root_type: "business",
+    query: "preview_business_delete",
+    id_field: "business_id",
+    groups: &[
+        Group {
+            memory_type: "product",
+            nodes: "products",
+            id_field: "product_id",
+            vectors: "product_vectors",
+            embedding_edge: "HasProductEmbedding",
+            owner_edges: &[("product_owned_by", "OwnedBy")],
+            edges: &[
+                ("product_shared_with", "SharedWith"),
+                ("product_interaction_about_product", "InteractionAboutProduct"),
+                ("product_information_about_product", "InformationAboutProduct"),
+            ],
+        },
+        Group {
+            memory_type: "service",
+            nodes: "services",
+            id_field: "service_id",
+            vectors: "service_vectors",
+            embedding_edge: "HasServiceEmbedding",
+            owner_edges: &[("service_owned_by", "OwnedBy")],
+            edges: &[
+                ("service_shared_with", "SharedWith"),
+                ("service_interaction_about_service", "InteractionAboutService"),
+                ("service_information_about_service", "InformationAboutService"),
+            ],
+        },
+        Group {
+            memory_type: "location",
+            nodes: "locations",
+            id_field: "location_id",
+            vectors: "location_vectors",
+            embedding_edge: "HasLocationEmbedding",
+            owner_edges: &[("location_owned_by", "OwnedBy")],
+            edges: &[
+                ("location_shared_with", "SharedWith"),
+                ("location_visit_at_location", "VisitAtLocation"),
+                ("location_information_for_location", "InformationForLocation"),
+            ],
+        },
+        Group {
+            memory_type: "hours",
+            nodes: "hours",
+            id_field: "hours_id",
+            vectors: "hours_vectors",
+            embedding_edge: "HasHoursEmbedding",
+            owner_edges: &[("hours_owned_by", "OwnedBy")],
+            edges: &[("hours_shared_with", "SharedWith")],
+        },
+        Group {
+            memory_type: "social",
+            nodes: "social",
+            id_field: "social_id",
+            vectors: "social_vectors",
+            embedding_edge: "HasSocialEmbedding",
+            owner_edges: &[("social_owned_by", "OwnedBy")],
+            edges: &[("social_shared_with", "SharedWith")],
+        },
+        Group {
+            memory_type: "policy",
+            nodes: "policies",
+            id_field: "policy_id",
+            vectors: "policy_vectors",
+            embedding_edge: "HasPolicyEmbedding",
+            owner_edges: &[("policy_owned_by", "OwnedBy")],
+            edges: &[
+                ("policy_shared_with", "SharedWith"),
+                ("policy_communication_about_policy", "CommunicationAboutPolicy"),
+            ],
+        },
+        Group {
+            memory_type: "event",
+            nodes: "events",
+            id_field: "event_id",
+            vectors: "event_vectors",
+            embedding_edge: "HasEventEmbedding",
+            owner_edges: &[("event_owned_by", "OwnedBy")],
+            edges: &[
+                ("event_shared_with", "SharedWith"),
+                ("event_information_for_event", "InformationForEvent"),
+            ],
+        },
+    ],
+    root_edges: &[
+        ("business_owned_by", "OwnedBy"),
+        ("business_shared_with", "SharedWith"),
+        ("business_customer_of", "CustomerOf"),
+        ("business_has_navigation", "HasNavigation"),
+        ("business_collaborates_with", "CollaboratesWith"),
+        ("business_collaborator_of", "CollaboratesWith"),
+    ],
+    survivors: &[("information", "information"), ("navigation_hubs", "navigation_hub")],
+};
+
+const CUSTOMER: Target = Target {
+    root_type: "customer",
+    query: "preview_customer_delete",
+    id_field: "customer_id",
+    groups: &[
+        Group {
+            memory_type: "behavior",
+            nodes: "behaviors",
+            id_field: "behavior_id",
+            vectors: "behavior_vectors",
+            embedding_edge: "HasBehaviorEmbedding",
+            owner_edges: &[("behavior_about_customer", "AboutCustomer")],
+            edges: &[
+                ("behavior_customer_has_product_interaction", "CustomerHasProductInteraction"),
+                ("behavior_customer_has_communication", "CustomerHasCommunication"),
+            ],
+        },
+        Group {
+            memory_type: "preference",
+            nodes: "preferences",
+            id_field: "preference_id",
+            vectors: "preference_vectors",
+            embedding_edge: "HasPreferenceEmbedding",
+            owner_edges: &[("preference_about_customer", "AboutCustomer")],
+            edges: &[],
+        },
+        Group {
+            memory_type: "desire",
+            nodes: "desires",
+            id_field: "desire_id",
+            vectors: "desire_vectors",
+            embedding_edge: "HasDesireEmbedding",
+            owner_edges: &[("desire_about_customer", "AboutCustomer")],
+            edges: &[],
+        },
+        Group {
+            memory_type: "rule",
+            nodes: "rules",
+            id_field: "rule_id",
+            vectors: "rule_vectors",
+            embedding_edge: "HasRuleEmbedding",
+            owner_edges: &[("rule_about_customer", "AboutCustomer")],
+            edges: &[],
+        },
+        Group {
+            memory_type: "feedback",
+            nodes: "feedback",
+            id_field: "feedback_id",
+            vectors: "feedback_vectors",
+            embedding_edge: "HasFeedbackEmbedding",
+            owner_edges: &[("feedback_about_customer", "AboutCustomer")],
+            edges: &[],
+        },
+    ],
+    root_edges: &[
+        ("customer_about_customer", "AboutCustomer"),
+        ("customer_customer_of", "CustomerOf"),
+    ],
+    survivors: &[
+        ("product_interactions", "product_interaction"),
+        ("service_interactions", "service_interaction"),
+        ("location_visits", "location_visit"),
+        ("communications", "communication"),
+    ],
+};
+
+/// Preview query and ID field for a cascade/complete target type
+pub fn query_for(memory_type: &str) -> Option<(&'static str, &'static str)> {
+    target(memory_type).map(|t| (t.query, t.id_field))
+}
+
+fn target(memory_type: &str) -> Option<&'static Target> {
+    match memory_type {
+        "business" => Some(&BUSINESS),
+        "customer" => Some(&CUSTOMER),
+        _ => None,
+    }
+}
+
+fn items(result: &Value, key: &str) -> Vec<Value> {
+    result.get(key).map(HelixClient::result_items).unwrap_or_default()
+}
+
+/// Report what `strategy` ("cascade" or "complete") would remove, from a preview query result
+pub fn report(memory_type: &str, strategy: &str, result: &Value) -> Option<Value> {
+    let target = target(memory_type)?;
+    let complete = strategy == "complete";
+    let mut nodes = Map::new();
+    let mut edges: Map<String, Value> = Map::new();
+    let mut orphaned = Map::new();
+    let mut add_edges = |edge: &str, count: usize| {
+        if count > 0 {
+            let total = edges.get(edge).and_then(|v| v.as_u64()).unwrap_or(0) + count as u64;
+            edges.insert(edge.to_string(), json!(total));
+        }
+    };
+
+    for group in target.groups {
+        let found = items(result, group.nodes);
+        if found.is_empty() {
+            continue;
+        }
+        let ids: Vec<&str> = found.iter().filter_map(|n| n.get(group.id_field).and_then(|v| v.as_str())).take(MAX_LISTED_IDS).collect();
+        nodes.insert(group.memory_type.to_string(), json!({"count": found.len(), "ids": ids}));
+
+        let vectors = items(result, group.vectors).len();
+        add_edges(group.embedding_edge, vectors);
+        if vectors > 0 {
+            orphaned.insert(group.memory_type.to_string(), json!(vectors));
+        }
+        let owner_edges = if complete { &[][..] } else { group.owner_edges };
+        for (key, edge) in owner_edges.iter().chain(group.edges) {
+            add_edges(edge, items(result, key).len());
+        }
+    }
+
+    let root_found = !items(result, target.root_type).is_empty();
+    if complete && root_found {
+        nodes.insert(target.root_type.to_string(), json!({"count": 1}));
+        for (key, edge) in target.root_edges {
+            add_edges(edge, items(result, key).len());
+        }
+    }
+
+    let mut not_deleted = Map::new();
+    for (key, label) in target.survivors {
+        let count = items(result, key).len();
+        if count > 0 {
+            not_deleted.insert(label.to_string(), json!(count));
+        }
+    }
+
+    let node_total: u64 = nodes.values().filter_map(|n| n["count"].as_u64()).sum();
+    let edge_total: u64 = edges.values().filter_map(|v| v.as_u64()).sum();
+    let orphaned_total: u64 = orphaned.values().filter_map(|v| v.as_u64()).sum();
+    Some(json!({
+        "root_exists": root_found,
+        "nodes_removed": nodes,
+        "edges_removed": edges,
+        "vectors_removed": 0,
+        "vectors_orphaned": orphaned,
+        "not_deleted": not_deleted,
+        "totals": {"nodes": node_total, "edges": edge_total, "vectors_orphaned": orphaned_total}
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_cascade_and_complete() {
+        let result = json!({
+            "business": [{"business_id": "B1"}],
+            "products": [{"product_id": "P1"}, {"product_id": "P2"}],
+            "product_vectors": [{"id": "v1"}, {"id": "v2"}],
+            "product_owned_by": [{"id": "e1"}, {"id": "e2"}],
+            "product_interaction_about_product": [{"id": "e3"}],
+            "business_owned_by": [{"id": "e1"}, {"id": "e2"}, {"id": "e4"}],
+            "business_customer_of": [{"id": "e5"}],
+            "information": [{"info_id": "I1"}]
+        });
+
+        let cascade = report("business", "cascade", &result).unwrap();
+        assert_eq!(cascade["nodes_removed"]["product"]["ids"], json!(["P1", "P2"]));
+        assert!(cascade["nodes_removed"].get("business").is_none());
+        assert_eq!(cascade["edges_removed"]["OwnedBy"], 2);
+        assert_eq!(cascade["edges_removed"]["HasProductEmbedding"], 2);
+        assert_eq!(cascade["vectors_orphaned"]["product"], 2);
+        assert_eq!(cascade["not_deleted"]["information"], 1);
+
+        let complete = report("business", "complete", &result).unwrap();
+        assert_eq!(complete["nodes_removed"]["business"]["count"], 1);
+        assert_eq!(complete["edges_removed"]["OwnedBy"], 3);
+        assert_eq!(complete["edges_removed"]["CustomerOf"], 1);
+        assert_eq!(complete["totals"]["nodes"], 3);
+
+        assert!(report("product", "cascade", &result).is_none());
+    }
+}
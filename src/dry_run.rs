@@ -0,0 +1,123 @@
+//! Dry-run execution of mutating tools
+//!
+//! A mutating tool called with `dry_run: true` (or any mutating tool when
+//! `[dry_run] enforce` is set) runs normally, validation and lookups
+//! included, but inside a recording scope: `HelixClient::query` lets read
+//! queries through and records write queries instead of sending them. The
+//! caller gets back the writes that would have run and the tool's own
+//! result, so agent prompts can be exercised against production config.
+
+use rmcp::model::Tool;
+use serde_json::{json, Value};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Query name prefixes that only read
+const READ_PREFIXES: &[&str] = &["get_", "search_", "find_", "count_", "export_", "preview_"];
+
+tokio::task_local! {
+    static RECORDER: Arc<Recorder>;
+}
+
+#[derive(Default)]
+pub struct Recorder {
+    writes: Mutex<Vec<Value>>,
+    reads: AtomicUsize,
+}
+
+pub fn is_read_query(endpoint: &str) -> bool {
+    READ_PREFIXES.iter().any(|prefix| endpoint.starts_with(prefix))
+}
+
+/// Whether the tool takes its own `dry_run` argument (bulk deletes, imports)
+pub fn native(tool: &Tool) -> bool {
+    tool.input_schema
+        .get("properties")
+        .and_then(|p| p.get("dry_run"))
+        .is_some()
+}
+
+/// Add the `dry_run` argument to a mutating tool's input schema
+pub fn advertise(tool: &mut Tool) {
+    if native(tool) {
+        return;
+    }
+    let mut schema = (*tool.input_schema).clone();
+    if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+        properties.insert(
+            "dry_run".to_string(),
+            json!({
+                "type": "boolean",
+                "description": "Validate and report the database writes this call would make, without making them"
+            }),
+        );
+        tool.input_schema = Arc::new(schema);
+    }
+}
+
+/// Run `future` with HelixDB writes recorded into `recorder` instead of executed
+pub async fn scope<F: Future>(recorder: Arc<Recorder>, future: F) -> F::Output {
+    RECORDER.scope(recorder, future).await
+}
+
+/// Whether the current task is a dry run
+pub fn active() -> bool {
+    RECORDER.try_with(|_| ()).is_ok()
+}
+
+/// Called before each HelixDB query: the stand-in result when a write must
+/// not run, `None` to execute the query
+pub fn intercept(endpoint: &str, payload: &Value) -> Option<Value> {
+    RECORDER
+        .try_with(|recorder| {
+            if is_read_query(endpoint) {
+                recorder.reads.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+            let mut writes = recorder.writes.lock().unwrap();
+            writes.push(json!({"query": endpoint, "payload": payload}));
+            Some(json!({"dry_run": true, "query": endpoint}))
+        })
+        .ok()
+        .flatten()
+}
+
+/// Result for a dry-run call: the writes that would have run and what the tool reported
+pub fn report(tool: &str, recorder: &Recorder, tool_result: Option<Value>, tool_error: bool) -> Value {
+    let writes = recorder.writes.lock().unwrap().clone();
+    json!({
+        "dry_run": true,
+        "tool": tool,
+        "valid": !tool_error,
+        "would_execute": writes,
+        "write_count": writes.len(),
+        "reads_executed": recorder.reads.load(Ordering::Relaxed),
+        "tool_result": tool_result,
+        "note": "No changes were made. Results of the recorded writes are placeholders, so IDs and counts in tool_result may differ from a real run."
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_records_writes_only() {
+        assert!(intercept("add_business_product", &json!({})).is_none());
+
+        let recorder = Arc::new(Recorder::default());
+        scope(recorder.clone(), async {
+            assert!(intercept("get_business_products", &json!({"business_id": "B1"})).is_none());
+            let stand_in = intercept("add_business_product", &json!({"product_id": "P1"})).unwrap();
+            assert_eq!(stand_in["dry_run"], true);
+        })
+        .await;
+
+        let report = report("create_business_memory", &recorder, Some(json!({"success": true})), false);
+        assert_eq!(report["write_count"], 1);
+        assert_eq!(report["reads_executed"], 1);
+        assert_eq!(report["would_execute"][0]["query"], "add_business_product");
+        assert_eq!(report["valid"], true);
+    }
+}
@@ -0,0 +1,136 @@
+//! Short-lived cache of query-text embeddings
+//!
+//! Agents re-issue near-identical search queries within a session, so query
+//! embeddings are cached by normalized text. Document embeddings are never
+//! cached here: they are generated once per write and would only evict
+//! useful query entries.
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+struct Entry {
+    embedding: Vec<f32>,
+    inserted: Instant,
+    last_used: u64,
+}
+
+/// TTL + LRU cache of query embeddings with hit metrics
+pub struct QueryEmbeddingCache {
+    entries: Mutex<HashMap<String, Entry>>,
+    capacity: usize,
+    ttl: Duration,
+    clock: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+/// Cache key: lowercase with whitespace collapsed, so trivially different queries share an entry
+pub fn normalize_query(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+impl QueryEmbeddingCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        QueryEmbeddingCache {
+            entries: Mutex::new(HashMap::new()),
+            capacity,
+            ttl,
+            clock: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self, query: &str) -> Option<Vec<f32>> {
+        let key = normalize_query(query);
+        let mut entries = self.entries.lock().unwrap();
+
+        let fresh = entries.get(&key).is_some_and(|e| e.inserted.elapsed() < self.ttl);
+        if !fresh {
+            entries.remove(&key);
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+
+        let entry = entries.get_mut(&key)?;
+        entry.last_used = self.clock.fetch_add(1, Ordering::Relaxed);
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Some(entry.embedding.clone())
+    }
+
+    pub fn insert(&self, query: &str, embedding: Vec<f32>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let key = normalize_query(query);
+        let mut entries = self.entries.lock().unwrap();
+
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            let before = entries.len();
+            entries.retain(|_, e| e.inserted.elapsed() < self.ttl);
+            if entries.len() >= self.capacity {
+                let oldest = entries.iter().min_by_key(|(_, e)| e.last_used).map(|(k, _)| k.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+            self.evictions.fetch_add((before - entries.len()) as u64, Ordering::Relaxed);
+        }
+
+        let last_used = self.clock.fetch_add(1, Ordering::Relaxed);
+        entries.insert(key, Entry { embedding, inserted: Instant::now(), last_used });
+    }
+
+    /// Hit/miss metrics for the embedding-cache resource
+    pub fn stats(&self) -> Value {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let lookups = hits + misses;
+        json!({
+            "entries": self.entries.lock().unwrap().len(),
+            "capacity": self.capacity,
+            "ttl_secs": self.ttl.as_secs(),
+            "hits": hits,
+            "misses": misses,
+            "evictions": self.evictions.load(Ordering::Relaxed),
+            "hit_rate": if lookups == 0 { 0.0 } else { hits as f64 / lookups as f64 }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hits_and_lru_eviction() {
+        let cache = QueryEmbeddingCache::new(2, Duration::from_secs(60));
+        assert!(cache.get("red shoes").is_none());
+
+        cache.insert("red shoes", vec![1.0]);
+        cache.insert("blue shoes", vec![2.0]);
+        assert_eq!(cache.get("  Red   SHOES "), Some(vec![1.0]));
+
+        // "blue shoes" is least recently used and gets evicted
+        cache.insert("green shoes", vec![3.0]);
+        assert!(cache.get("blue shoes").is_none());
+        assert_eq!(cache.get("red shoes"), Some(vec![1.0]));
+
+        let stats = cache.stats();
+        assert_eq!(stats["hits"], 2);
+        assert_eq!(stats["misses"], 2);
+        assert_eq!(stats["evictions"], 1);
+    }
+
+    #[test]
+    fn test_expired_entries_miss() {
+        let cache = QueryEmbeddingCache::new(10, Duration::ZERO);
+        cache.insert("query", vec![1.0]);
+        assert!(cache.get("query").is_none());
+    }
+}
@@ -2,16 +2,27 @@
 //!
 //! High-performance TCP client using OVNT protocol to communicate
 //! with the standalone EmbeddingServer
+//!
+//! Connections are kept in a small pool and reused; a pooled connection
+//! the server has since closed is replaced transparently. Batches are
+//! pipelined: all requests are written before the responses, which the
+//! server sends in order, are read back.
+//!
+//! At startup `handshake` asks the server for its model and dimensions with
+//! an info message; servers without that extension are probed with a test
+//! embedding instead.
 
 use serde::{Deserialize, Serialize};
 use std::io;
 use tokio::net::TcpStream;
+use tokio::sync::Mutex;
 use uuid::Uuid;
 
 /// OVNT Protocol magic bytes
 const MAGIC_BYTES: [u8; 4] = [0x4F, 0x56, 0x4E, 0x54]; // "OVNT"
 const VERSION: u8 = 0x01;
 const MSG_TYPE_DATA: u8 = 4;
+const MSG_TYPE_INFO: u8 = 6;
 
 /// Embedding request
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,17 +53,64 @@ impl EmbedResponse {
     }
 }
 
+/// Rerank request: relevance of each document to the query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankRequest {
+    pub query: String,
+    pub documents: Vec<String>,
+    pub model: Option<String>,
+}
+
+/// Rerank response: one score per document, in request order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankResponse {
+    pub scores: Vec<f32>,
+}
+
+/// Capabilities a server reports in reply to an info message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerInfo {
+    #[serde(default)]
+    pub model: Option<String>,
+    pub dimensions: usize,
+}
+
 /// Error response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorResponse {
     pub error: String,
 }
 
+/// The server's vectors don't have the size `[embedding] dimensions` expects
+#[derive(Debug)]
+pub struct DimensionMismatch {
+    pub server_address: String,
+    pub returned: usize,
+    pub expected: usize,
+}
+
+impl std::fmt::Display for DimensionMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the embedding server at {} returned {} dimensions but [embedding] dimensions = {} in mcpconfig.toml; set it to the model's output size",
+            self.server_address, self.returned, self.expected
+        )
+    }
+}
+
+impl std::error::Error for DimensionMismatch {}
+
 /// TCP Embedding Client
 pub struct EmbeddingClient {
     server_address: String,
     client_id: Uuid,
     timeout: std::time::Duration,
+    // Idle connections kept for reuse, at most pool_size (0 = connect per request)
+    idle: Mutex<Vec<TcpStream>>,
+    pool_size: usize,
+    // Vector size the configured model produces; None accepts any
+    expected_dimensions: Option<usize>,
 }
 
 impl EmbeddingClient {
@@ -62,6 +120,107 @@ impl EmbeddingClient {
             server_address,
             client_id: Uuid::new_v4(),
             timeout: std::time::Duration::from_secs(timeout_secs),
+            idle: Mutex::new(Vec::new()),
+            pool_size: 0,
+            expected_dimensions: None,
+        }
+    }
+
+    /// Reject embeddings that are not `dimensions` long (`[embedding] dimensions`)
+    pub fn with_dimensions(mut self, dimensions: usize) -> Self {
+        self.expected_dimensions = Some(dimensions);
+        self
+    }
+
+    /// Keep up to `pool_size` idle connections open for reuse
+    pub fn with_pool_size(mut self, pool_size: usize) -> Self {
+        self.pool_size = pool_size;
+        self
+    }
+
+    async fn connect(&self) -> io::Result<TcpStream> {
+        let stream = tokio::time::timeout(self.timeout, TcpStream::connect(&self.server_address))
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, format!("Connecting to {} timed out", self.server_address)))??;
+        stream.set_nodelay(true)?;
+        Ok(stream)
+    }
+
+    /// Send `payloads` on one connection and read a response for each, in order.
+    /// A reused connection that fails is dropped and the batch retried on a new one.
+    async fn exchange(&self, payloads: Vec<Vec<u8>>) -> io::Result<Vec<Vec<u8>>> {
+        let pooled = self.idle.lock().await.pop();
+        if let Some(mut stream) = pooled {
+            match self.exchange_on(&mut stream, &payloads).await {
+                Ok(responses) => {
+                    self.release(stream).await;
+                    return Ok(responses);
+                }
+                Err(e) => tracing::debug!("Pooled embedding connection failed ({}), reconnecting", e),
+            }
+        }
+        let mut stream = self.connect().await?;
+        let responses = self.exchange_on(&mut stream, &payloads).await?;
+        self.release(stream).await;
+        Ok(responses)
+    }
+
+    async fn exchange_on(&self, stream: &mut TcpStream, payloads: &[Vec<u8>]) -> io::Result<Vec<Vec<u8>>> {
+        for payload in payloads {
+            self.write_protocol_message(stream, MSG_TYPE_DATA, payload).await?;
+        }
+        let mut responses = Vec::with_capacity(payloads.len());
+        for _ in payloads {
+            responses.push(self.read_protocol_message(stream).await?);
+        }
+        Ok(responses)
+    }
+
+    /// Model and dimensions of the server: from an info message, or measured
+    /// from a probe embedding (no model name) when the server lacks the extension
+    pub async fn handshake(&self) -> Result<ServerInfo, Box<dyn std::error::Error>> {
+        if let Some(info) = self.request_info().await {
+            return Ok(info);
+        }
+        let payload = rmp_serde::to_vec(&EmbedRequest { text: "capability probe".to_string(), model: None })?;
+        let response_payload = self.exchange(vec![payload]).await?.remove(0);
+        match rmp_serde::from_slice::<EmbedResponse>(&response_payload) {
+            Ok(response) => Ok(ServerInfo { model: None, dimensions: response.get_embedding().len() }),
+            Err(_) => match rmp_serde::from_slice::<ErrorResponse>(&response_payload) {
+                Ok(error) => Err(format!("Server error: {}", error.error).into()),
+                Err(_) => Err("Invalid response format".into()),
+            },
+        }
+    }
+
+    /// Send an info message on a fresh connection; None if the server does not answer with ServerInfo
+    async fn request_info(&self) -> Option<ServerInfo> {
+        let mut stream = self.connect().await.ok()?;
+        self.write_protocol_message(&mut stream, MSG_TYPE_INFO, &[]).await.ok()?;
+        let wait = self.timeout.min(std::time::Duration::from_secs(5));
+        let response_payload = tokio::time::timeout(wait, self.read_protocol_message(&mut stream)).await.ok()?.ok()?;
+        rmp_serde::from_slice::<ServerInfo>(&response_payload).ok().filter(|info| info.dimensions > 0)
+    }
+
+    /// Return a healthy connection to the pool, or close it when the pool is full
+    async fn release(&self, stream: TcpStream) {
+        let mut idle = self.idle.lock().await;
+        if idle.len() < self.pool_size {
+            idle.push(stream);
+        }
+    }
+
+    /// Embedding from a response payload, or the server's error
+    fn parse_embedding(&self, response_payload: &[u8]) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        if let Ok(response) = rmp_serde::from_slice::<EmbedResponse>(response_payload) {
+            let embedding = response.get_embedding().clone();
+            // Validate embedding before returning
+            self.validate_embedding(&embedding)?;
+            Ok(embedding)
+        } else if let Ok(error) = rmp_serde::from_slice::<ErrorResponse>(response_payload) {
+            Err(format!("Server error: {}", error.error).into())
+        } else {
+            Err("Invalid response format".into())
         }
     }
 
@@ -76,15 +235,19 @@ impl EmbeddingClient {
         Ok(())
     }
 
-    /// Validate embedding dimensions
-    async fn validate_embedding(&self, embedding: &[f32]) -> Result<(), String> {
-        // Check vector dimensions
-        let expected_dim = 384; // Should match model output
-        if embedding.len() != expected_dim {
-            return Err(format!("Invalid embedding dimension: got {}, expected {}", 
-                             embedding.len(), expected_dim));
+    /// Validate embedding dimensions against the configured model size
+    fn validate_embedding(&self, embedding: &[f32]) -> Result<(), Box<dyn std::error::Error>> {
+        if embedding.is_empty() {
+            return Err("Server returned an empty embedding".into());
+        }
+        match self.expected_dimensions {
+            Some(expected) if embedding.len() != expected => Err(Box::new(DimensionMismatch {
+                server_address: self.server_address.clone(),
+                returned: embedding.len(),
+                expected,
+            })),
+            _ => Ok(()),
         }
-        Ok(())
     }
 
     /// Generate embedding for text using default model
@@ -102,13 +265,6 @@ impl EmbeddingClient {
         self.validate_text_for_embedding(text)
             .map_err(|e| Box::new(std::io::Error::new(std::io::ErrorKind::InvalidInput, e)) as Box<dyn std::error::Error>)?;
 
-        // Connect to server
-        let mut stream = tokio::time::timeout(
-            self.timeout,
-            TcpStream::connect(&self.server_address),
-        )
-        .await??;
-
         // Create request
         let request = EmbedRequest {
             text: text.to_string(),
@@ -118,19 +274,38 @@ impl EmbeddingClient {
         // Serialize request
         let payload = rmp_serde::to_vec(&request)?;
 
-        // Write OVNT protocol message
-        self.write_protocol_message(&mut stream, payload).await?;
+        // Send it and read the response over a pooled connection
+        let response_payload = self.exchange(vec![payload]).await?.remove(0);
+        self.parse_embedding(&response_payload)
+    }
 
-        // Read response
-        let response_payload = self.read_protocol_message(&mut stream).await?;
+    /// Embeddings for several texts, pipelined over one connection
+    pub async fn embed_texts(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, Box<dyn std::error::Error>> {
+        let mut payloads = Vec::with_capacity(texts.len());
+        for text in texts {
+            self.validate_text_for_embedding(text)
+                .map_err(|e| Box::new(std::io::Error::new(std::io::ErrorKind::InvalidInput, e)) as Box<dyn std::error::Error>)?;
+            payloads.push(rmp_serde::to_vec(&EmbedRequest { text: text.to_string(), model: None })?);
+        }
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for response_payload in self.exchange(payloads).await? {
+            embeddings.push(self.parse_embedding(&response_payload)?);
+        }
+        Ok(embeddings)
+    }
 
-        // Try to deserialize as EmbedResponse first
-        if let Ok(response) = rmp_serde::from_slice::<EmbedResponse>(&response_payload) {
-            let embedding = response.get_embedding().clone();
-            // Validate embedding before returning
-            self.validate_embedding(&embedding).await
-                .map_err(|e| format!("Embedding validation failed: {}", e))?;
-            Ok(embedding)
+    /// Score documents against a query on a rerank server
+    pub async fn rerank(&self, query: &str, documents: Vec<String>, model: Option<String>) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        let expected = documents.len();
+        let request = RerankRequest { query: query.to_string(), documents, model };
+        let payload = rmp_serde::to_vec(&request)?;
+        let response_payload = self.exchange(vec![payload]).await?.remove(0);
+
+        if let Ok(response) = rmp_serde::from_slice::<RerankResponse>(&response_payload) {
+            if response.scores.len() != expected {
+                return Err(format!("Rerank server returned {} scores for {} documents", response.scores.len(), expected).into());
+            }
+            Ok(response.scores)
         } else if let Ok(error) = rmp_serde::from_slice::<ErrorResponse>(&response_payload) {
             Err(format!("Server error: {}", error.error).into())
         } else {
@@ -142,7 +317,8 @@ impl EmbeddingClient {
     async fn write_protocol_message(
         &self,
         stream: &mut TcpStream,
-        payload: Vec<u8>,
+        msg_type: u8,
+        payload: &[u8],
     ) -> io::Result<()> {
         use tokio::io::AsyncWriteExt;
 
@@ -153,7 +329,7 @@ impl EmbeddingClient {
         stream.write_u8(VERSION).await?;
 
         // Message type
-        stream.write_u8(MSG_TYPE_DATA).await?;
+        stream.write_u8(msg_type).await?;
 
         // Length
         stream.write_u32_le(payload.len() as u32).await?;
@@ -169,7 +345,7 @@ impl EmbeddingClient {
         stream.write_all(message_id.as_bytes()).await?;
 
         // Payload
-        stream.write_all(&payload).await?;
+        stream.write_all(payload).await?;
 
         stream.flush().await?;
         Ok(())
@@ -227,6 +403,7 @@ impl EmbeddingClient {
     }
 
     /// Test connection to embedding server
+    #[allow(dead_code)]
     pub async fn test_connection(&self) -> Result<(), Box<dyn std::error::Error>> {
         tokio::time::timeout(
             self.timeout,
@@ -241,6 +418,84 @@ impl EmbeddingClient {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_pooled_pipelined_requests() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let server = tokio::spawn(async move {
+            let protocol = EmbeddingClient::new(String::new(), 1);
+            // The first connection dies after one request; the client must reconnect
+            for requests_before_close in [1, usize::MAX] {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut served = 0;
+                while served < requests_before_close {
+                    let Ok(payload) = protocol.read_protocol_message(&mut stream).await else {
+                        break;
+                    };
+                    let request: EmbedRequest = rmp_serde::from_slice(&payload).unwrap();
+                    let embedding = vec![request.text.len() as f32; 384];
+                    protocol.write_protocol_message(&mut stream, MSG_TYPE_DATA, &rmp_serde::to_vec(&embedding).unwrap()).await.unwrap();
+                    served += 1;
+                }
+            }
+        });
+
+        let client = EmbeddingClient::new(address, 5).with_pool_size(2).with_dimensions(384);
+        assert_eq!(client.embed_text("abc").await.unwrap()[0], 3.0);
+        assert_eq!(client.idle.lock().await.len(), 1);
+        // The pooled connection was closed by the server; the batch goes out on a new one
+        let embeddings = client.embed_texts(&["abcd", "abcdefgh", "abcde"]).await.unwrap();
+        let lengths: Vec<f32> = embeddings.iter().map(|e| e[0]).collect();
+        assert_eq!(lengths, vec![4.0, 8.0, 5.0]);
+        let mismatch = client.validate_embedding(&[0.5; 768]).unwrap_err();
+        assert!(mismatch.is::<DimensionMismatch>() && mismatch.to_string().contains("[embedding] dimensions = 384"));
+        drop(client);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_handshake() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let server = tokio::spawn(async move {
+            let protocol = EmbeddingClient::new(String::new(), 1);
+            // A server with the info extension describes itself
+            let (mut stream, _) = listener.accept().await.unwrap();
+            protocol.read_protocol_message(&mut stream).await.unwrap();
+            let info = ServerInfo { model: Some("all-MiniLM-L6-v2".to_string()), dimensions: 384 };
+            protocol.write_protocol_message(&mut stream, MSG_TYPE_DATA, &rmp_serde::to_vec(&info).unwrap()).await.unwrap();
+            drop(stream);
+            // One without it drops the info request and is probed with an embedding
+            let (mut stream, _) = listener.accept().await.unwrap();
+            protocol.read_protocol_message(&mut stream).await.unwrap();
+            drop(stream);
+            let (mut stream, _) = listener.accept().await.unwrap();
+            protocol.read_protocol_message(&mut stream).await.unwrap();
+            protocol.write_protocol_message(&mut stream, MSG_TYPE_DATA, &rmp_serde::to_vec(&vec![0.1f32; 768]).unwrap()).await.unwrap();
+        });
+
+        let client = EmbeddingClient::new(address, 5);
+        let info = client.handshake().await.unwrap();
+        assert_eq!((info.model.as_deref(), info.dimensions), (Some("all-MiniLM-L6-v2"), 384));
+        let info = client.handshake().await.unwrap();
+        assert_eq!((info.model, info.dimensions), (None, 768));
+        server.await.unwrap();
+    }
+
+    #[test]
+    fn test_validate_embedding() {
+        let client = EmbeddingClient::new("127.0.0.1:8787".to_string(), 1);
+        assert!(client.validate_embedding(&[0.5; 768]).is_ok());
+        assert_eq!(client.validate_embedding(&[]).unwrap_err().to_string(), "Server returned an empty embedding");
+
+        let client = client.with_dimensions(384);
+        assert!(client.validate_embedding(&[0.5; 384]).is_ok());
+        let error = client.validate_embedding(&[0.5; 1536]).unwrap_err();
+        let mismatch = error.downcast_ref::<DimensionMismatch>().unwrap();
+        assert_eq!((mismatch.returned, mismatch.expected), (1536, 384));
+        assert_eq!(mismatch.server_address, "127.0.0.1:8787");
+    }
+
     #[tokio::test]
     #[ignore] // Only run when server is running
     async fn test_embed_text() {
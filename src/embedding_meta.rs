@@ -0,0 +1,204 @@
+//! Embedding model metadata per collection
+//!
+//! Each memory type's vectors were produced by one embedding model. The model
+//! and dimensions are recorded in an `EmbeddingCollectionMeta` node per
+//! collection the first time a vector is written, and search tools compare
+//! them with the configured query model so vectors from different models are
+//! never compared silently. While a collection is re-embedded with a new
+//! model, `target_model` marks the migration as in progress; it completes
+//! when the metadata is set to the new model.
+//!
+//! At startup the embedding provider's own model and dimensions are
+//! discovered and checked against `[embedding] dimensions` and the recorded
+//! collections before the server accepts requests. Only a confirmed mismatch
+//! stops startup; a provider that can't be reached is logged and skipped.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectionModel {
+    pub model: String,
+    pub dimensions: usize,
+    pub target_model: Option<String>,
+}
+
+impl CollectionModel {
+    pub fn from_node(node: &Value) -> Option<(String, Self)> {
+        let memory_type = node.get("memory_type")?.as_str()?.to_string();
+        let model = CollectionModel {
+            model: node.get("embedding_model")?.as_str()?.to_string(),
+            dimensions: node.get("dimensions").and_then(|v| v.as_u64()).unwrap_or(0) as usize,
+            target_model: node.get("target_model").and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(String::from),
+        };
+        Some((memory_type, model))
+    }
+}
+
+/// How a collection's stored model compares with the query model
+#[derive(Debug, PartialEq)]
+pub enum ModelCheck {
+    Match,
+    /// Nothing recorded yet (collection predates metadata or is empty)
+    Unrecorded,
+    /// Re-embedding to the query model is under way; results mix both models
+    Migrating { from: String },
+    Mismatch { stored: String, stored_dimensions: usize },
+}
+
+pub fn check(stored: Option<&CollectionModel>, model: &str, dimensions: usize) -> ModelCheck {
+    match stored {
+        None => ModelCheck::Unrecorded,
+        Some(s) if s.model == model && (s.dimensions == 0 || s.dimensions == dimensions) => ModelCheck::Match,
+        Some(s) if s.target_model.as_deref() == Some(model) => ModelCheck::Migrating { from: s.model.clone() },
+        Some(s) => ModelCheck::Mismatch { stored: s.model.clone(), stored_dimensions: s.dimensions },
+    }
+}
+
+/// Model and dimensions the embedding provider reported at startup
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderModel {
+    pub model: String,
+    pub dimensions: usize,
+    /// "handshake" when the provider described itself, "probe" when measured from a test embedding
+    pub source: &'static str,
+}
+
+/// Why the startup negotiation with the embedding provider failed
+#[derive(Debug, PartialEq)]
+pub enum NegotiationError {
+    /// The provider couldn't be reached or probed, so nothing was checked
+    Unreachable(String),
+    /// The provider's dimensions don't fit the configuration or the recorded collections
+    Mismatch(String),
+}
+
+impl std::fmt::Display for NegotiationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NegotiationError::Unreachable(message) | NegotiationError::Mismatch(message) => f.write_str(message),
+        }
+    }
+}
+
+/// Mismatches between the provider and `[embedding] dimensions` (first) and
+/// between the provider and recorded collections not being migrated (second)
+pub fn provider_mismatches(
+    provider: &ProviderModel,
+    configured_dimensions: usize,
+    collections: &HashMap<String, CollectionModel>,
+) -> (Option<String>, Vec<String>) {
+    let config = (provider.dimensions != configured_dimensions).then(|| {
+        format!(
+            "Configuration error: the embedding provider ({}) produces {} dimensions but [embedding] dimensions = {} in mcpconfig.toml",
+            provider.model, provider.dimensions, configured_dimensions
+        )
+    });
+    let mut stored: Vec<String> = collections
+        .iter()
+        .filter(|(_, c)| c.dimensions > 0 && c.dimensions != provider.dimensions && c.target_model.is_none())
+        .map(|(collection, c)| {
+            format!("Collection {} holds {}-dimension vectors from {}; the provider produces {}", collection, c.dimensions, c.model, provider.dimensions)
+        })
+        .collect();
+    stored.sort();
+    (config, stored)
+}
+
+/// Whether startup may go on with `provider`: a mismatch with `[embedding]
+/// dimensions` always stops it, collection mismatches only when `refuse` is
+/// set (`on_mismatch = "refuse"`); otherwise they come back as warnings
+pub fn check_provider(
+    provider: &ProviderModel,
+    configured_dimensions: usize,
+    collections: &HashMap<String, CollectionModel>,
+    refuse: bool,
+) -> Result<Vec<String>, NegotiationError> {
+    let (config_problem, collection_problems) = provider_mismatches(provider, configured_dimensions, collections);
+    if let Some(problem) = config_problem {
+        return Err(NegotiationError::Mismatch(problem));
+    }
+    if refuse && !collection_problems.is_empty() {
+        return Err(NegotiationError::Mismatch(format!(
+            "{} (re-embed with backfill_embeddings or set on_mismatch = \"warn\")",
+            collection_problems.join("; ")
+        )));
+    }
+    Ok(collection_problems)
+}
+
+/// Cached view of the metadata nodes, loaded from HelixDB on first use
+#[derive(Default)]
+pub struct CorpusModels {
+    models: RwLock<Option<HashMap<String, CollectionModel>>>,
+}
+
+impl CorpusModels {
+    /// `None` until loaded
+    pub fn get_all(&self) -> Option<HashMap<String, CollectionModel>> {
+        self.models.read().unwrap().clone()
+    }
+
+    pub fn load(&self, nodes: &[Value]) -> HashMap<String, CollectionModel> {
+        let models: HashMap<String, CollectionModel> = nodes.iter().filter_map(CollectionModel::from_node).collect();
+        *self.models.write().unwrap() = Some(models.clone());
+        models
+    }
+
+    pub fn set(&self, memory_type: &str, model: CollectionModel) {
+        if let Some(models) = self.models.write().unwrap().as_mut() {
+            models.insert(memory_type.to_string(), model);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_model_check() {
+        let cache = CorpusModels::default();
+        let models = cache.load(&[
+            json!({"memory_type": "products", "embedding_model": "text-embedding-3-small", "dimensions": 1536, "target_model": ""}),
+            json!({"memory_type": "services", "embedding_model": "local", "dimensions": 384, "target_model": "text-embedding-3-small"}),
+        ]);
+
+        assert_eq!(check(models.get("products"), "text-embedding-3-small", 1536), ModelCheck::Match);
+        assert_eq!(
+            check(models.get("products"), "text-embedding-3-small", 512),
+            ModelCheck::Mismatch { stored: "text-embedding-3-small".to_string(), stored_dimensions: 1536 }
+        );
+        assert_eq!(check(models.get("services"), "text-embedding-3-small", 1536), ModelCheck::Migrating { from: "local".to_string() });
+        assert_eq!(check(models.get("events"), "local", 384), ModelCheck::Unrecorded);
+
+        let provider = ProviderModel { model: "local".to_string(), dimensions: 384, source: "probe" };
+        let (config, stored) = provider_mismatches(&provider, 1536, &models);
+        assert!(config.unwrap().contains("[embedding] dimensions = 1536"));
+        // services is migrating, so only products is reported
+        assert_eq!(stored.len(), 1);
+        assert!(stored[0].starts_with("Collection products"));
+    }
+
+    #[test]
+    fn test_check_provider() {
+        let collections: HashMap<String, CollectionModel> = [(
+            "products".to_string(),
+            CollectionModel { model: "local".to_string(), dimensions: 384, target_model: None },
+        )]
+        .into();
+        let provider = ProviderModel { model: "text-embedding-3-small".to_string(), dimensions: 1536, source: "handshake" };
+
+        let warnings = check_provider(&provider, 1536, &collections, false).unwrap();
+        assert_eq!(warnings, ["Collection products holds 384-dimension vectors from local; the provider produces 1536"]);
+        match check_provider(&provider, 1536, &collections, true) {
+            Err(NegotiationError::Mismatch(message)) => assert!(message.ends_with("set on_mismatch = \"warn\")")),
+            other => panic!("expected a mismatch, got {:?}", other),
+        }
+        // The configured size is checked first and regardless of on_mismatch
+        assert!(matches!(check_provider(&provider, 384, &HashMap::new(), false), Err(NegotiationError::Mismatch(_))));
+        assert_eq!(check_provider(&provider, 1536, &HashMap::new(), true), Ok(vec![]));
+    }
+}
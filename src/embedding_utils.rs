@@ -1,5 +1,12 @@
+//! Composite embedding text for products and services
+//!
+//! Builds the text a product or service is embedded from out of its
+//! structured fields (name, category, price, availability, features, tags)
+//! so search matches what is actually stored, with the caller's
+//! text_description appended rather than used alone.
+
 use serde_json::Value;
-use crate::BusinessProductMemory;
+use crate::models::{Product, Service};
 
 pub trait RichTextGenerator {
     fn generate_category_context(&self, categories: &[String]) -> Vec<String> {
@@ -224,7 +231,7 @@ pub trait RichTextGenerator {
         contexts.join(" ")
     }
 
-    fn generate_search_context(&self, item: &BusinessProductMemory) -> String {
+    fn generate_search_context(&self, item: &Product) -> String {
         let mut contexts = Vec::new();
 
         // Add basic item/service info
@@ -264,6 +271,7 @@ pub trait RichTextGenerator {
         contexts.join(" ")
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn generate_rich_composite_text(
         &self,
         name: &str,
@@ -298,4 +306,71 @@ pub trait RichTextGenerator {
                .collect::<Vec<String>>()
                .join("\n\n")
     }
-}
\ No newline at end of file
+}
+
+pub struct CompositeText;
+
+impl RichTextGenerator for CompositeText {}
+
+impl CompositeText {
+    fn service_text(&self, service: &Service) -> String {
+        let offered: Vec<String> = service.deliverables.iter().chain(&service.requirements).cloned().collect();
+        let mut sections = vec![self.generate_rich_composite_text(
+            &service.service_name,
+            &service.description,
+            &offered,
+            "{}",
+            service.price,
+            &service.currency,
+            &service.availability,
+            &service.tags,
+            "{}",
+        )];
+        if !service.service_category.is_empty() {
+            sections.push(self.generate_category_context(std::slice::from_ref(&service.service_category)).join(". "));
+        }
+        if service.duration_minutes > 0 {
+            sections.push(format!("Duration: {} minutes", service.duration_minutes));
+        }
+        sections.join("\n\n")
+    }
+}
+
+/// Embedding text for a complete product or service `data` object (schema
+/// defaults filled in), with its text_description appended; `None` for other
+/// memory types or data that doesn't fit the model
+pub fn composite_text(memory_type: &str, data: &Value) -> Option<String> {
+    let generator = CompositeText;
+    let text = match memory_type {
+        "product" => generator.generate_search_context(&serde_json::from_value(data.clone()).ok()?),
+        "service" => generator.service_text(&serde_json::from_value(data.clone()).ok()?),
+        _ => return None,
+    };
+    let summary = data.get("text_description").and_then(|v| v.as_str()).unwrap_or("").trim();
+    Some(if summary.is_empty() { text } else { format!("{}\n\nSummary: {}", text, summary) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_composite_text() {
+        let product = crate::models::business_memory(
+            "product",
+            json!({"product_name": "Oat Latte", "product_category": "Drinks", "price": 4.5, "currency": "USD", "tags": ["vegan"], "text_description": "Our best seller"}),
+            0,
+        )
+        .unwrap();
+        let text = composite_text("product", &product).unwrap();
+        assert!(text.starts_with("Name: Oat Latte. Type: Drinks."));
+        assert!(text.contains("Price: 4.5 USD"));
+        assert!(text.contains("Tagged as: vegan"));
+        assert!(text.ends_with("Summary: Our best seller"));
+
+        let service = crate::models::business_memory("service", json!({"service_name": "Haircut", "service_category": "Salon"}), 0).unwrap();
+        assert!(composite_text("service", &service).unwrap().contains("Duration: 60 minutes"));
+        assert!(composite_text("hours", &json!({})).is_none());
+    }
+}
@@ -0,0 +1,183 @@
+//! Right-to-erasure purges for `forget_customer`
+//!
+//! `preview_forget_customer` inventories everything stored about a customer:
+//! their memories, product/service interactions, location visits and
+//! communications (conversation summaries included), each with its
+//! embedding vector, their loyalty points ledger and the aliases of
+//! customer IDs merged into them, plus the edges attached to them. The deletion manifest
+//! lists what the purge removes by ID and count, never the personal data
+//! itself, so it can be kept as the audit record of the erasure.
+
+use crate::helix_client::HelixClient;
+use serde_json::{json, Map, Value};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+/// One kind of record erased, keyed by the inventory query's return names
+struct Records {
+    kind: &'static str,
+    nodes: &'static str,
+    id_field: &'static str,
+    vectors: &'static str,
+    embedding_edge: &'static str,
+}
+
+const RECORDS: &[Records] = &[
+    Records { kind: "behavior", nodes: "behaviors", id_field: "behavior_id", vectors: "behavior_vectors", embedding_edge: "HasBehaviorEmbedding" },
+    Records { kind: "preference", nodes: "preferences", id_field: "preference_id", vectors: "preference_vectors", embedding_edge: "HasPreferenceEmbedding" },
+    Records { kind: "desire", nodes: "desires", id_field: "desire_id", vectors: "desire_vectors", embedding_edge: "HasDesireEmbedding" },
+    Records { kind: "rule", nodes: "rules", id_field: "rule_id", vectors: "rule_vectors", embedding_edge: "HasRuleEmbedding" },
+    Records { kind: "feedback", nodes: "feedback", id_field: "feedback_id", vectors: "feedback_vectors", embedding_edge: "HasFeedbackEmbedding" },
+    Records {
+        kind: "product_interaction",
+        nodes: "product_interactions",
+        id_field: "interaction_id",
+        vectors: "product_interaction_vectors",
+        embedding_edge: "HasProductInteractionEmbedding",
+    },
+    Records {
+        kind: "service_interaction",
+        nodes: "service_interactions",
+        id_field: "interaction_id",
+        vectors: "service_interaction_vectors",
+        embedding_edge: "HasServiceInteractionEmbedding",
+    },
+    Records {
+        kind: "location_visit",
+        nodes: "location_visits",
+        id_field: "visit_id",
+        vectors: "location_visit_vectors",
+        embedding_edge: "HasLocationVisitEmbedding",
+    },
+    Records {
+        kind: "communication",
+        nodes: "communications",
+        id_field: "communication_id",
+        vectors: "communication_vectors",
+        embedding_edge: "HasCommunicationEmbedding",
+    },
+];
+
+/// Records stored without an embedding, by kind, return name and ID field
+const PLAIN_RECORDS: &[(&str, &str, &str)] = &[
+    ("points_transaction", "points_transactions", "transaction_id"),
+    ("customer_alias", "aliases", "alias_customer_id"),
+];
+
+/// Other edges removed with the nodes, by return name and edge label
+const EDGES: &[(&str, &str)] = &[
+    ("customer_of", "CustomerOf"),
+    ("about_customer", "AboutCustomer"),
+    ("behavior_interaction_links", "CustomerHasProductInteraction"),
+    ("behavior_visit_links", "CustomerHasLocationVisit"),
+    ("behavior_communication_links", "CustomerHasCommunication"),
+    ("preference_interaction_links", "CustomerHasServiceInteraction"),
+    ("product_interaction_links", "InteractionAboutProduct"),
+    ("service_interaction_links", "InteractionAboutService"),
+    ("location_visit_links", "VisitAtLocation"),
+    ("communication_links", "CommunicationAboutPolicy"),
+    ("points_links", "HasPointsTransaction"),
+];
+
+fn items(inventory: &Value, key: &str) -> Vec<Value> {
+    HelixClient::result_items(&json!({ key: inventory.get(key).cloned().unwrap_or(Value::Null) }))
+}
+
+/// What erasing the customer in `inventory` (a `preview_forget_customer`
+/// result) removes: IDs and counts per record kind, vectors and edges
+pub fn manifest(inventory: &Value) -> Value {
+    let customers = items(inventory, "customer").len();
+    let mut records = Map::new();
+    let mut vectors = Map::new();
+    let mut edges = Map::new();
+    let (mut node_total, mut vector_total) = (customers, 0);
+    for group in RECORDS {
+        let nodes = items(inventory, group.nodes);
+        let ids: Vec<&str> = nodes.iter().filter_map(|n| n.get(group.id_field).and_then(|v| v.as_str())).collect();
+        let vector_count = items(inventory, group.vectors).len();
+        node_total += nodes.len();
+        vector_total += vector_count;
+        if !nodes.is_empty() {
+            records.insert(group.kind.to_string(), json!({"count": nodes.len(), "ids": ids}));
+        }
+        if vector_count > 0 {
+            vectors.insert(group.kind.to_string(), json!(vector_count));
+            edges.insert(group.embedding_edge.to_string(), json!(vector_count));
+        }
+    }
+    for (kind, key, id_field) in PLAIN_RECORDS {
+        let nodes = items(inventory, key);
+        let ids: Vec<&str> = nodes.iter().filter_map(|n| n.get(*id_field).and_then(|v| v.as_str())).collect();
+        node_total += nodes.len();
+        if !nodes.is_empty() {
+            records.insert(kind.to_string(), json!({"count": nodes.len(), "ids": ids}));
+        }
+    }
+    for (key, label) in EDGES {
+        let count = items(inventory, key).len();
+        if count > 0 {
+            let previous = edges.get(*label).and_then(|v| v.as_u64()).unwrap_or(0);
+            edges.insert(label.to_string(), json!(previous + count as u64));
+        }
+    }
+    let edge_total: u64 = edges.values().filter_map(|v| v.as_u64()).sum();
+    json!({
+        "customer_found": customers > 0,
+        "records": records,
+        "vectors": vectors,
+        "edges": edges,
+        "totals": {"nodes": node_total, "vectors": vector_total, "edges": edge_total}
+    })
+}
+
+/// Nodes and vectors still present in an inventory taken after the purge
+pub fn remaining(inventory: &Value) -> usize {
+    let groups = RECORDS.iter().flat_map(|g| [g.nodes, g.vectors]);
+    let plain = PLAIN_RECORDS.iter().map(|(_, key, _)| *key);
+    std::iter::once("customer").chain(groups).chain(plain).map(|key| items(inventory, key).len()).sum()
+}
+
+/// Append an erasure record to the audit file (JSON Lines)
+pub fn append_audit(path: &Path, record: &Value) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_counts() {
+        let inventory = json!({
+            "customer": {"customer_id": "c1", "email": "a@b.c"},
+            "customer_of": [{"id": "e1"}],
+            "about_customer": [{"id": "e2"}, {"id": "e3"}],
+            "preferences": [{"preference_id": "pref_1", "text_description": "likes oat milk"}],
+            "preference_vectors": [{"id": "v1"}],
+            "feedback": [{"feedback_id": "fb_1"}],
+            "feedback_vectors": [],
+            "communications": [{"communication_id": "comm_1"}, {"communication_id": "comm_2"}],
+            "communication_vectors": [{"id": "v2"}, {"id": "v3"}],
+            "behavior_communication_links": [{"id": "e4"}],
+            "points_transactions": [{"transaction_id": "pts_1", "points": 40}],
+            "points_links": [{"id": "e5"}],
+            "aliases": [{"alias_customer_id": "c0", "customer_id": "c1"}]
+        });
+        let manifest = manifest(&inventory);
+        assert_eq!(manifest["customer_found"], true);
+        assert_eq!(manifest["records"]["communication"]["ids"], json!(["comm_1", "comm_2"]));
+        assert_eq!(manifest["vectors"]["preference"], 1);
+        assert_eq!(manifest["edges"]["AboutCustomer"], 2);
+        assert_eq!(manifest["records"]["points_transaction"]["ids"], json!(["pts_1"]));
+        assert_eq!(manifest["edges"]["HasPointsTransaction"], 1);
+        assert_eq!(manifest["records"]["customer_alias"]["ids"], json!(["c0"]));
+        assert_eq!(manifest["totals"], json!({"nodes": 7, "vectors": 3, "edges": 8}));
+        // Personal data stays out of the manifest
+        assert!(!manifest.to_string().contains("oat milk"));
+
+        assert_eq!(remaining(&inventory), 10);
+        assert_eq!(remaining(&json!({"customer": [], "preferences": []})), 0);
+    }
+}
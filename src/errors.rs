@@ -0,0 +1,160 @@
+//! Machine-readable error codes for tool errors
+//!
+//! Every tool error carries `code` and `retryable` (with `retry_after_secs`
+//! for transient failures) next to its `error` message, so agents can decide
+//! whether to retry, fix their arguments or give up without parsing prose.
+//! Tools set the code explicitly with [`tool_error`]. Errors that reach
+//! `call_tool` without one (protocol errors, results built elsewhere) are
+//! classified from their message as a last resort.
+
+use rmcp::model::{CallToolResult, ErrorData};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// The memory, entity or query doesn't exist
+    NotFound,
+    /// Arguments are missing, malformed or out of range
+    ValidationFailed,
+    /// The embedding provider failed or isn't configured
+    EmbeddingUnavailable,
+    /// HelixDB couldn't be reached or failed server-side
+    HelixUnreachable,
+    /// Refused by sandbox, signature or permission checks
+    Unauthorized,
+    /// Session budget or rate limit exhausted
+    RateLimited,
+    /// Conflicts with existing data (duplicates, version mismatch)
+    Conflict,
+    /// The feature is switched off in mcpconfig.toml
+    Disabled,
+    Internal,
+}
+
+impl ErrorCode {
+    /// Whether the same call may succeed later without changes
+    pub fn retryable(self) -> bool {
+        matches!(self, Self::EmbeddingUnavailable | Self::HelixUnreachable | Self::RateLimited)
+    }
+
+    /// Suggested wait before retrying a retryable error
+    pub fn retry_after_secs(self) -> Option<u64> {
+        match self {
+            Self::HelixUnreachable => Some(2),
+            Self::EmbeddingUnavailable => Some(5),
+            Self::RateLimited => Some(30),
+            _ => None,
+        }
+    }
+
+    /// Last-resort code for an error message that arrived without one; substring
+    /// guesses, so tools should pass their code to [`tool_error`] instead
+    pub fn classify(message: &str) -> Self {
+        let message = message.to_lowercase();
+        let any = |needles: &[&str]| needles.iter().any(|n| message.contains(n));
+        if any(&["budget", "rate limit", "too many", "429"]) {
+            Self::RateLimited
+        } else if any(&["sandbox", "unauthorized", "forbidden", "not allowed", "permission", "signature", "401", "403"]) {
+            Self::Unauthorized
+        } else if any(&["connection refused", "error sending request", "timed out", "timeout", "failed to connect", "status 5", "unreachable"]) {
+            Self::HelixUnreachable
+        } else if message.contains("embedding") && any(&["fail", "api key", "provider", "not configured", "model"]) {
+            Self::EmbeddingUnavailable
+        } else if any(&["not found", "no such", "does not exist", "couldn't find", "unknown memory"]) {
+            Self::NotFound
+        } else if any(&["already exists", "duplicate", "conflict", "version mismatch"]) {
+            Self::Conflict
+        } else if any(&["disabled", "not enabled"]) {
+            Self::Disabled
+        } else if any(&["invalid", "required", "must ", "missing", "exceed", "unsupported", "unknown", "expected"]) {
+            Self::ValidationFailed
+        } else {
+            Self::Internal
+        }
+    }
+}
+
+/// Error result with an explicit code: `{error, code, retryable, ..details}`
+pub fn tool_error(code: ErrorCode, message: impl Into<String>, details: Value) -> CallToolResult {
+    let mut content = json!({"error": message.into(), "code": code, "retryable": code.retryable()});
+    if let Some(secs) = code.retry_after_secs() {
+        content["retry_after_secs"] = json!(secs);
+    }
+    if let (Some(fields), Value::Object(details)) = (content.as_object_mut(), details) {
+        fields.extend(details);
+    }
+    CallToolResult::structured_error(content)
+}
+
+fn add_code(content: &mut Value) -> bool {
+    let Some(fields) = content.as_object_mut().filter(|f| !f.contains_key("code")) else {
+        return false;
+    };
+    let message = fields.get("error").map(|e| e.as_str().map(str::to_string).unwrap_or_else(|| e.to_string())).unwrap_or_default();
+    let code = ErrorCode::classify(&message);
+    fields.insert("code".to_string(), json!(code));
+    fields.insert("retryable".to_string(), json!(code.retryable()));
+    if let Some(secs) = code.retry_after_secs() {
+        fields.insert("retry_after_secs".to_string(), json!(secs));
+    }
+    true
+}
+
+/// Give an error result that lacks one a `code` classified from its message
+pub fn annotate(result: CallToolResult) -> CallToolResult {
+    if result.is_error != Some(true) {
+        return result;
+    }
+    let Some(mut content) = result.structured_content.clone() else {
+        return result;
+    };
+    if add_code(&mut content) {
+        CallToolResult::structured_error(content)
+    } else {
+        result
+    }
+}
+
+/// Same for protocol-level errors, in their `data`
+pub fn annotate_protocol(mut error: ErrorData) -> ErrorData {
+    let mut data = error.data.take().unwrap_or_else(|| json!({}));
+    if data.is_object() {
+        if data.get("error").is_none() {
+            data["error"] = json!(error.message);
+        }
+        add_code(&mut data);
+    }
+    error.data = Some(data);
+    error
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_and_annotate() {
+        assert_eq!(ErrorCode::classify("Memory not found: prod_1"), ErrorCode::NotFound);
+        assert_eq!(ErrorCode::classify("Embedding generation failed: 401"), ErrorCode::Unauthorized);
+        assert_eq!(ErrorCode::classify("Embedding generation failed: bad model"), ErrorCode::EmbeddingUnavailable);
+        assert_eq!(ErrorCode::classify("error sending request for url (http://localhost:6969)"), ErrorCode::HelixUnreachable);
+        assert_eq!(ErrorCode::classify("Either customer_id or business_id is required"), ErrorCode::ValidationFailed);
+        assert_eq!(ErrorCode::classify("something odd"), ErrorCode::Internal);
+
+        let annotated = annotate(CallToolResult::structured_error(json!({"error": "Session tool call budget exhausted"})));
+        let content = annotated.structured_content.unwrap();
+        assert_eq!(content["code"], "RATE_LIMITED");
+        assert_eq!(content["retryable"], true);
+        assert_eq!(content["retry_after_secs"], 30);
+
+        // Explicit codes are kept, successes untouched
+        let explicit = annotate(tool_error(ErrorCode::Disabled, "Traversal disabled", json!({"tool": "out_step"})));
+        let content = explicit.structured_content.unwrap();
+        assert_eq!(content["code"], "DISABLED");
+        assert_eq!(content["tool"], "out_step");
+        let ok = annotate(CallToolResult::structured(json!({"success": true})));
+        assert!(ok.structured_content.unwrap().get("code").is_none());
+    }
+}
@@ -0,0 +1,91 @@
+//! Event lifecycle
+//!
+//! Time-aware reads over event memories: which events are still ahead (or
+//! running) in a window, how many places are left, and which have ended but
+//! are still marked scheduled. A capacity of 0 means unlimited.
+
+use serde_json::{json, Value};
+
+pub const STATUS_SCHEDULED: &str = "scheduled";
+pub const STATUS_CLOSED: &str = "closed";
+
+fn int(event: &Value, field: &str) -> i64 {
+    event.get(field).and_then(|v| v.as_i64()).unwrap_or(0)
+}
+
+/// Events stored before status tracking have no status and count as scheduled
+pub fn status(event: &Value) -> &str {
+    event.get("status").and_then(|v| v.as_str()).filter(|s| !s.is_empty()).unwrap_or(STATUS_SCHEDULED)
+}
+
+/// Places left, or `None` for unlimited capacity
+pub fn capacity_remaining(event: &Value) -> Option<i64> {
+    let capacity = int(event, "capacity");
+    (capacity > 0).then(|| (capacity - int(event, "registered_count")).max(0))
+}
+
+/// Validate a registration count against the event's capacity
+pub fn check_registrations(registered_count: i64, capacity: i64) -> Result<(), String> {
+    if registered_count < 0 {
+        return Err("registered_count cannot be negative".to_string());
+    }
+    if capacity > 0 && registered_count > capacity {
+        return Err(format!("registered_count {} exceeds capacity {}", registered_count, capacity));
+    }
+    Ok(())
+}
+
+/// Scheduled events overlapping `[from, to]` with at least `min_remaining`
+/// places left, soonest first, each annotated with `capacity_remaining`
+pub fn upcoming(events: &[Value], from: i64, to: Option<i64>, min_remaining: Option<i64>) -> Vec<Value> {
+    let mut upcoming: Vec<Value> = events
+        .iter()
+        .filter(|e| status(e) == STATUS_SCHEDULED)
+        .filter(|e| int(e, "end_date").max(int(e, "start_date")) >= from)
+        .filter(|e| to.is_none_or(|to| int(e, "start_date") <= to))
+        .filter(|e| min_remaining.is_none_or(|min| capacity_remaining(e).is_none_or(|left| left >= min)))
+        .map(|e| {
+            let mut event = e.clone();
+            event["capacity_remaining"] = json!(capacity_remaining(e));
+            event["in_progress"] = json!(int(e, "start_date") < from);
+            event
+        })
+        .collect();
+    upcoming.sort_by_key(|e| int(e, "start_date"));
+    upcoming
+}
+
+/// Scheduled events that ended before `now`
+pub fn past_scheduled(events: &[Value], now: i64) -> Vec<&Value> {
+    events
+        .iter()
+        .filter(|e| status(e) == STATUS_SCHEDULED && int(e, "end_date").max(int(e, "start_date")) < now)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upcoming_and_past() {
+        let events = vec![
+            json!({"event_id": "past", "start_date": 10, "end_date": 20}),
+            json!({"event_id": "running", "start_date": 90, "end_date": 150, "capacity": 10, "registered_count": 4}),
+            json!({"event_id": "full", "start_date": 200, "end_date": 210, "capacity": 5, "registered_count": 5}),
+            json!({"event_id": "later", "start_date": 500, "end_date": 510}),
+            json!({"event_id": "closed", "start_date": 120, "end_date": 130, "status": "closed"}),
+        ];
+
+        let ids = |list: Vec<Value>| list.iter().map(|e| e["event_id"].as_str().unwrap().to_string()).collect::<Vec<_>>();
+        assert_eq!(ids(upcoming(&events, 100, None, None)), vec!["running", "full", "later"]);
+        assert_eq!(ids(upcoming(&events, 100, Some(300), Some(1))), vec!["running"]);
+        assert_eq!(upcoming(&events, 100, None, None)[0]["capacity_remaining"], 6);
+        assert!(upcoming(&events, 100, None, None)[2]["capacity_remaining"].is_null());
+
+        let past: Vec<&str> = past_scheduled(&events, 100).iter().map(|e| e["event_id"].as_str().unwrap()).collect();
+        assert_eq!(past, vec!["past"]);
+        assert!(check_registrations(6, 5).is_err());
+        assert!(check_registrations(6, 0).is_ok());
+    }
+}
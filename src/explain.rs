@@ -0,0 +1,198 @@
+//! Evidence paths between a customer and a product or service
+//!
+//! `explain_relationship` collects a customer's interactions, preferences,
+//! desires and feedback and keeps what ties them to the target: interactions
+//! that reference it (the interaction edges), and memories whose text names
+//! it or, for preferences and desires, share its category. Each piece of
+//! evidence is a path of nodes and edges from the customer to the target,
+//! strongest links first, so agents can cite why they recommend something.
+
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetKind {
+    Product,
+    Service,
+}
+
+impl TargetKind {
+    pub fn id_field(self) -> &'static str {
+        match self {
+            Self::Product => "product_id",
+            Self::Service => "service_id",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Product => "BusinessProductMemory",
+            Self::Service => "BusinessServiceMemory",
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            Self::Product => "product",
+            Self::Service => "service",
+        }
+    }
+}
+
+/// The product or service being explained
+pub struct Target {
+    pub kind: TargetKind,
+    pub id: String,
+    name: String,
+    category: String,
+}
+
+impl Target {
+    /// From the stored node; `id` is used when the node wasn't found
+    pub fn new(kind: TargetKind, id: &str, node: Option<&Value>) -> Self {
+        let field = |suffix: &str| {
+            node.and_then(|n| n.get(format!("{}_{}", kind.prefix(), suffix)))
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+        Self { kind, id: id.to_string(), name: field("name"), category: field("category") }
+    }
+
+    fn node(&self) -> Value {
+        json!({"node": self.kind.label(), "id": self.id, "name": self.name})
+    }
+
+    /// Whether `text` names the target (by name or ID, case-insensitive)
+    fn mentioned_in(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        [&self.name, &self.id].iter().any(|needle| !needle.is_empty() && text.contains(&needle.to_lowercase()))
+    }
+}
+
+/// How a memory is tied to the target, strongest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Link {
+    Category,
+    Mention,
+    Interaction,
+}
+
+impl Link {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Interaction => "interaction",
+            Self::Mention => "mention",
+            Self::Category => "category",
+        }
+    }
+}
+
+/// Customer memory types searched for mentions: (memory_type, node label, ID field, text fields)
+const MEMORY_KINDS: &[(&str, &str, &str, &[&str])] = &[
+    ("preference", "CustomerPreferenceMemory", "preference_id", &["subject", "text_description"]),
+    ("desire", "CustomerDesireMemory", "desire_id", &["description", "text_description"]),
+    ("feedback", "CustomerFeedbackMemory", "feedback_id", &["subject", "text_description"]),
+];
+
+fn str_field<'a>(item: &'a Value, field: &str) -> &'a str {
+    item.get(field).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+fn timestamp(item: &Value) -> i64 {
+    ["timestamp", "created_at"].iter().find_map(|f| item.get(*f).and_then(|v| v.as_i64())).unwrap_or(0)
+}
+
+/// Evidence paths from `customer_id` to `target`.
+///
+/// `interactions` are the customer's interactions of the target's kind;
+/// `memories` pairs each memory type in [`MEMORY_KINDS`] with the customer's
+/// memories of that type.
+pub fn paths(customer_id: &str, target: &Target, interactions: &[Value], memories: &[(&str, Vec<Value>)]) -> Vec<Value> {
+    let customer = json!({"node": "Customer", "id": customer_id});
+    let mut found: Vec<(Link, i64, Value)> = Vec::new();
+
+    let (has_edge, about_edge, interaction_label) = match target.kind {
+        TargetKind::Product => ("CustomerHasProductInteraction", "InteractionAboutProduct", "CustomerProductInteraction"),
+        TargetKind::Service => ("CustomerHasServiceInteraction", "InteractionAboutService", "CustomerServiceInteraction"),
+    };
+    for interaction in interactions.iter().filter(|i| str_field(i, target.kind.id_field()) == target.id) {
+        let interaction_type = str_field(interaction, "interaction_type");
+        let evidence = ["text_reason", "text_feedback"].iter().map(|f| str_field(interaction, f)).find(|t| !t.is_empty()).unwrap_or("");
+        found.push((Link::Interaction, timestamp(interaction), json!({
+            "link": Link::Interaction.name(),
+            "memory_type": interaction_label,
+            "interaction_type": interaction_type,
+            "evidence": evidence,
+            "path": [
+                customer,
+                {"edge": has_edge},
+                {"node": interaction_label, "id": str_field(interaction, "interaction_id"), "interaction_type": interaction_type},
+                {"edge": about_edge},
+                target.node()
+            ]
+        })));
+    }
+
+    for (memory_type, items) in memories {
+        let Some((_, label, id_field, text_fields)) = MEMORY_KINDS.iter().find(|(t, ..)| t == memory_type) else {
+            continue;
+        };
+        for memory in items {
+            let text: Vec<&str> = text_fields.iter().map(|f| str_field(memory, f)).filter(|t| !t.is_empty()).collect();
+            let category = str_field(memory, "category");
+            let link = if text.iter().any(|t| target.mentioned_in(t)) {
+                Link::Mention
+            } else if !target.category.is_empty() && category.eq_ignore_ascii_case(&target.category) {
+                Link::Category
+            } else {
+                continue;
+            };
+            let via = if link == Link::Mention { "mentions" } else { "same_category" };
+            found.push((link, timestamp(memory), json!({
+                "link": link.name(),
+                "memory_type": memory_type,
+                "evidence": text.last().copied().unwrap_or(""),
+                "path": [
+                    customer,
+                    {"edge": "AboutCustomer", "direction": "in"},
+                    {"node": label, "id": str_field(memory, id_field)},
+                    {"edge": via, "inferred": true},
+                    target.node()
+                ]
+            })));
+        }
+    }
+
+    found.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+    found.into_iter().map(|(_, _, path)| path).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths_rank_interactions_first() {
+        let product = json!({"product_id": "P1", "product_name": "Trail Runner", "product_category": "shoes"});
+        let target = Target::new(TargetKind::Product, "P1", Some(&product));
+        let interactions = vec![
+            json!({"interaction_id": "I1", "product_id": "P1", "interaction_type": "liked", "text_reason": "Comfy", "timestamp": 10}),
+            json!({"interaction_id": "I2", "product_id": "P2", "interaction_type": "liked", "timestamp": 20}),
+        ];
+        let memories = vec![
+            ("preference", vec![
+                json!({"preference_id": "PR1", "category": "Shoes", "subject": "running gear", "created_at": 5}),
+                json!({"preference_id": "PR2", "category": "food", "subject": "snacks"}),
+            ]),
+            ("feedback", vec![json!({"feedback_id": "F1", "subject": "trail runner sizing", "created_at": 30})]),
+        ];
+
+        let paths = paths("C1", &target, &interactions, &memories);
+        let links: Vec<&str> = paths.iter().map(|p| p["link"].as_str().unwrap()).collect();
+        assert_eq!(links, vec!["interaction", "mention", "category"]);
+        assert_eq!(paths[0]["path"][2]["id"], "I1");
+        assert_eq!(paths[0]["path"][4]["name"], "Trail Runner");
+        assert_eq!(paths[1]["path"][2]["id"], "F1");
+        assert_eq!(paths[2]["path"][3]["edge"], "same_category");
+    }
+}
@@ -0,0 +1,242 @@
+//! Vector-store-agnostic embedding export
+//!
+//! Converts HelixDB embedding vectors into a generic dataset record
+//! (`id`, `text`, `vector`, `metadata`) that can be loaded into other
+//! vector stores or evaluation tooling without a custom extractor.
+//!
+//! Also writes CSV for tools with `format: "csv"`, so reports open directly
+//! in a spreadsheet: one column per field, lists joined with "; ".
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+/// File for an export tool's `output_path` inside the export `directory`;
+/// names can't reach paths outside it
+pub fn path_for(directory: &Path, file_name: &str) -> Result<PathBuf, String> {
+    if file_name.is_empty() || file_name.contains(['/', '\\']) || file_name.contains("..") {
+        return Err(format!("Invalid output_path: {} (use a file name; files are written to the export directory)", file_name));
+    }
+    Ok(directory.join(file_name))
+}
+
+/// Owner scope of an exportable memory type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportOwner {
+    Business,
+    Customer,
+}
+
+/// One record of the exported embedding dataset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingRecord {
+    pub id: String,
+    pub text: String,
+    pub vector: Vec<f64>,
+    pub metadata: Value,
+}
+
+/// Export query and owner scope for a (plural) memory type
+pub fn export_query(memory_type: &str) -> Option<(&'static str, ExportOwner)> {
+    let entry = match memory_type {
+        "products" => ("export_business_products_embeddings", ExportOwner::Business),
+        "services" => ("export_business_services_embeddings", ExportOwner::Business),
+        "locations" => ("export_business_locations_embeddings", ExportOwner::Business),
+        "hours" => ("export_business_hours_embeddings", ExportOwner::Business),
+        "social" => ("export_business_social_embeddings", ExportOwner::Business),
+        "policies" => ("export_business_policies_embeddings", ExportOwner::Business),
+        "events" => ("export_business_events_embeddings", ExportOwner::Business),
+        "information" => ("export_business_information_embeddings", ExportOwner::Business),
+        "behaviors" => ("export_customer_behaviors_embeddings", ExportOwner::Customer),
+        "preferences" => ("export_customer_preferences_embeddings", ExportOwner::Customer),
+        "desires" => ("export_customer_desires_embeddings", ExportOwner::Customer),
+        "rules" => ("export_customer_rules_embeddings", ExportOwner::Customer),
+        "feedback" => ("export_customer_feedback_embeddings", ExportOwner::Customer),
+        "communications" => ("export_customer_communications_embeddings", ExportOwner::Customer),
+        _ => return None,
+    };
+    Some(entry)
+}
+
+/// Build a dataset record from a HelixDB vector item.
+///
+/// The vector is read from `data`, `vector` or `embedding`; the text from
+/// `composite_embedding_text`. All remaining properties become metadata,
+/// together with the memory type and owner id. Items without a vector are skipped.
+pub fn record_from_vector(item: &Value, memory_type: &str, owner_field: &str, owner_id: &str) -> Option<EmbeddingRecord> {
+    let obj = item.as_object()?;
+
+    let vector = crate::similarity::vector_of(item)?;
+
+    let id = obj.get("id")
+        .map(|v| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string()))
+        .unwrap_or_default();
+    let text = obj.get("composite_embedding_text")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+
+    let mut metadata = Map::new();
+    metadata.insert("memory_type".to_string(), Value::from(memory_type));
+    metadata.insert(owner_field.to_string(), Value::from(owner_id));
+    for (key, value) in obj {
+        match key.as_str() {
+            "id" | "data" | "vector" | "embedding" | "composite_embedding_text" => {}
+            _ => {
+                metadata.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    Some(EmbeddingRecord { id, text, vector, metadata: Value::Object(metadata) })
+}
+
+/// Serialize records as JSON Lines (one record per line)
+pub fn to_jsonl(records: &[EmbeddingRecord]) -> String {
+    records
+        .iter()
+        .filter_map(|r| serde_json::to_string(r).ok())
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// A CSV cell: RFC 4180 quoting when the text needs it
+fn csv_cell(value: &Value) -> String {
+    let text = match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) if items.iter().all(|v| !v.is_array() && !v.is_object()) => {
+            items.iter().map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_string)).collect::<Vec<_>>().join("; ")
+        }
+        other => other.to_string(),
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+/// Records as CSV: `leading` columns first, then every other field in first-seen order
+pub fn to_csv(records: &[Value], leading: &[&str]) -> String {
+    let mut columns: Vec<String> = leading.iter().map(|c| c.to_string()).collect();
+    for record in records {
+        for key in record.as_object().into_iter().flat_map(|map| map.keys()) {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+    let mut out = columns.iter().map(|c| csv_cell(&Value::from(c.as_str()))).collect::<Vec<_>>().join(",") + "\r\n";
+    for record in records {
+        let row: Vec<String> = columns.iter().map(|c| csv_cell(record.get(c).unwrap_or(&Value::Null))).collect();
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Embedding records as CSV, metadata as columns and the vector space-separated
+pub fn records_to_csv(records: &[EmbeddingRecord]) -> String {
+    let rows: Vec<Value> = records
+        .iter()
+        .map(|r| {
+            let mut row = Map::new();
+            row.insert("id".to_string(), Value::from(r.id.as_str()));
+            row.insert("text".to_string(), Value::from(r.text.as_str()));
+            row.insert("vector".to_string(), Value::from(r.vector.iter().map(f64::to_string).collect::<Vec<_>>().join(" ")));
+            row.extend(r.metadata.as_object().cloned().unwrap_or_default());
+            Value::Object(row)
+        })
+        .collect();
+    to_csv(&rows, &["id", "text", "vector"])
+}
+
+/// A nested report as long-format CSV (`section,key,value`): top-level numbers
+/// go in section "summary", objects give one row per entry and arrays of
+/// objects one row per item, keyed by the item's text field
+pub fn report_to_csv(report: &Value) -> String {
+    let mut rows = Vec::new();
+    let mut row = |section: &str, key: &str, value: &Value| rows.push(serde_json::json!({"section": section, "key": key, "value": value}));
+    for (field, value) in report.as_object().into_iter().flatten() {
+        match value {
+            Value::Object(entries) => entries.iter().for_each(|(key, value)| row(field, key, value)),
+            Value::Array(items) => {
+                for item in items {
+                    let fields: Vec<&Value> = item.as_object().into_iter().flat_map(|map| map.values()).collect();
+                    match fields.as_slice() {
+                        [Value::String(key), value] | [value, Value::String(key)] => row(field, key, value),
+                        _ => row(field, "", item),
+                    }
+                }
+            }
+            value => row("summary", field, value),
+        }
+    }
+    to_csv(&rows, &["section", "key", "value"])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_record_from_vector() {
+        let item = json!({
+            "id": "vec-1",
+            "label": "CustomerPreferenceEmbedding",
+            "data": [0.1, 0.2, 0.3],
+            "composite_embedding_text": "Prefers oat milk",
+            "embedding_model": "local"
+        });
+        let record = record_from_vector(&item, "preferences", "customer_id", "CUST_1").unwrap();
+        assert_eq!(record.id, "vec-1");
+        assert_eq!(record.text, "Prefers oat milk");
+        assert_eq!(record.vector, vec![0.1, 0.2, 0.3]);
+        assert_eq!(record.metadata["customer_id"], "CUST_1");
+        assert_eq!(record.metadata["embedding_model"], "local");
+        assert!(record.metadata.get("data").is_none());
+
+        assert!(record_from_vector(&json!({"id": "no-vector"}), "preferences", "customer_id", "CUST_1").is_none());
+        assert_eq!(path_for(Path::new("exports"), "prefs.jsonl"), Ok(PathBuf::from("exports/prefs.jsonl")));
+        for name in ["", "../etc/passwd", "/etc/passwd", "sub/file.jsonl", "..\\x"] {
+            assert!(path_for(Path::new("exports"), name).is_err(), "{}", name);
+        }
+        assert_eq!(to_jsonl(std::slice::from_ref(&record)).lines().count(), 1);
+        assert!(records_to_csv(&[record]).starts_with("id,text,vector,customer_id,embedding_model,label,memory_type\r\nvec-1,Prefers oat milk,0.1 0.2 0.3,CUST_1,"));
+    }
+
+    #[test]
+    fn test_csv_cells_and_columns() {
+        let rows = [json!({"name": "Oat latte", "tags": ["vegan", "hot"]}), json!({"name": "Say \"hi\", then", "price": 3.5})];
+        assert_eq!(to_csv(&rows, &["id"]), "id,name,tags,price\r\n,Oat latte,vegan; hot,\r\n,\"Say \"\"hi\"\", then\",,3.5\r\n");
+
+        assert_eq!(csv_cell(&json!(null)), "");
+        assert_eq!(csv_cell(&json!(true)), "true");
+        assert_eq!(csv_cell(&json!("two\nlines")), "\"two\nlines\"");
+        assert_eq!(csv_cell(&json!([1, "a"])), "1; a");
+        // Nested values stay JSON, quoted for their commas and quotes
+        assert_eq!(csv_cell(&json!({"a": 1, "b": 2})), "\"{\"\"a\"\":1,\"\"b\"\":2}\"");
+        assert_eq!(csv_cell(&json!([[1, 2]])), "\"[[1,2]]\"");
+
+        // No records still gives the header
+        assert_eq!(to_csv(&[], &["section"]), "section\r\n");
+    }
+
+    #[test]
+    fn test_report_to_csv() {
+        let summary = json!({"total_interactions": 3, "counts_by_type": {"purchased": 2}, "trend": [{"period": "2026-03", "count": 3}]});
+        assert_eq!(report_to_csv(&summary), "section,key,value\r\ncounts_by_type,purchased,2\r\nsummary,total_interactions,3\r\ntrend,2026-03,3\r\n");
+
+        // The text field keys the row whichever side of the value it is on;
+        // items that aren't a key/value pair go in whole
+        let report = json!({
+            "top_products": [{"count": 4, "product_id": "P1"}, {"product_id": "P2", "likes": 1, "purchases": 2}]
+        });
+        assert_eq!(
+            report_to_csv(&report),
+            "section,key,value\r\ntop_products,P1,4\r\ntop_products,,\"{\"\"likes\"\":1,\"\"product_id\"\":\"\"P2\"\",\"\"purchases\"\":2}\"\r\n"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Running one query per memory section
+//!
+//! Tools that return every memory type at once (`query_business_memory` /
+//! `query_customer_memory` with `"all"`) run the per-type queries
+//! concurrently. A failed query doesn't hide the others: its section is
+//! left out and the error listed under `errors`, keyed by section.
+//! Semantic search fans out over memory types the same way, at most
+//! `[helix] max_parallel_queries` queries at once.
+
+use serde_json::{json, Map, Value};
+use std::fmt::Display;
+use std::future::Future;
+use tokio::sync::Semaphore;
+
+/// Await `tasks` with at most `limit` running at once; outputs keep task order
+pub async fn bounded<F: Future>(tasks: impl IntoIterator<Item = F>, limit: usize) -> Vec<F::Output> {
+    let slots = Semaphore::new(limit.max(1));
+    futures::future::join_all(tasks.into_iter().map(|task| {
+        let slots = &slots;
+        async move {
+            let _slot = slots.acquire().await;
+            task.await
+        }
+    }))
+    .await
+}
+
+/// Results keyed by section, plus `errors` when any section failed
+pub fn sections<E: Display>(outcomes: impl IntoIterator<Item = (&'static str, Result<Value, E>)>) -> Value {
+    let mut all_memories = json!({});
+    let mut errors = Map::new();
+    for (section, outcome) in outcomes {
+        match outcome {
+            Ok(memories) => all_memories[section] = memories,
+            Err(e) => {
+                errors.insert(section.to_string(), json!(e.to_string()));
+            }
+        }
+    }
+    if !errors.is_empty() {
+        all_memories["errors"] = json!(errors);
+    }
+    all_memories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections_with_errors() {
+        let all = sections([
+            ("products", Ok(json!({"products": [{"product_id": "P1"}]}))),
+            ("hours", Err("connection reset")),
+            ("events", Ok(json!({"events": []}))),
+        ]);
+        assert_eq!(all["products"]["products"][0]["product_id"], "P1");
+        assert_eq!(all["events"], json!({"events": []}));
+        assert!(all.get("hours").is_none());
+        assert_eq!(all["errors"], json!({"hours": "connection reset"}));
+
+        let all = sections([("rules", Ok::<_, String>(json!({"rules": []})))]);
+        assert!(all.get("errors").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_bounded_parallelism() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let tasks = (0..6u64).map(|i| {
+            let (running, peak) = (&running, &peak);
+            async move {
+                peak.fetch_max(running.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+                // Later tasks finish first; outputs still follow task order
+                tokio::time::sleep(std::time::Duration::from_millis(10 - i)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                i
+            }
+        });
+        assert_eq!(bounded(tasks, 2).await, [0, 1, 2, 3, 4, 5]);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+
+        // A zero limit still runs everything, one at a time
+        assert_eq!(bounded((1..=2).map(|i| async move { i }), 0).await, [1, 2]);
+    }
+}
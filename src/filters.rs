@@ -0,0 +1,212 @@
+//! Client-side filters on memory lists
+//!
+//! Tools that accept a `filters` object match each item against it after
+//! loading: exact values, `{"gte": .., "lte": ..}` numeric ranges, and
+//! `since` / `until` on the item's `timestamp` (behaviors, interactions) or
+//! `created_at`. Items lacking a filtered field don't match. The same tools
+//! sort by any field (`sort_by` / `order`) and page with `limit` / `offset`.
+
+use crate::importance;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// Keep the items of `results` that match: the top-level array, or each array
+/// in HelixDB's wrapped RETURN object. Arrays nested in memories (tags, ...)
+/// are left alone
+pub fn apply(results: Value, filters: &Value) -> Value {
+    match results {
+        Value::Array(items) => items.into_iter().filter(|item| !item.is_object() || matches(item, filters)).collect(),
+        Value::Object(map) => map
+            .into_iter()
+            .map(|(key, value)| match value {
+                Value::Array(_) => (key, apply(value, filters)),
+                value => (key, value),
+            })
+            .collect(),
+        other => other,
+    }
+}
+
+/// Whether `item` passes every filter; anything but an object filters nothing
+pub fn matches(item: &Value, filters: &Value) -> bool {
+    let Some(filter_obj) = filters.as_object() else {
+        return true;
+    };
+
+    // Time range: since/until apply to `timestamp` (behaviors, interactions) or `created_at`
+    let since = filter_obj.get("since").and_then(parse_timestamp);
+    let until = filter_obj.get("until").and_then(parse_timestamp);
+    if since.is_some() || until.is_some() {
+        let Some(item_time) = item.get("timestamp").or(item.get("created_at")).and_then(|v| v.as_i64()) else {
+            return false;
+        };
+        if since.is_some_and(|since| item_time < since) || until.is_some_and(|until| item_time > until) {
+            return false;
+        }
+    }
+
+    for (key, value) in filter_obj {
+        if key == "since" || key == "until" {
+            continue;
+        }
+        match item.get(key) {
+            Some(item_value) => {
+                // Handle range filters for numbers
+                if let Some(range) = value.as_object() {
+                    if let Some(lte) = range.get("lte") {
+                        if let (Some(item_num), Some(filter_num)) = (item_value.as_f64(), lte.as_f64()) {
+                            if item_num > filter_num {
+                                return false;
+                            }
+                        }
+                    }
+                    if let Some(gte) = range.get("gte") {
+                        if let (Some(item_num), Some(filter_num)) = (item_value.as_f64(), gte.as_f64()) {
+                            if item_num < filter_num {
+                                return false;
+                            }
+                        }
+                    }
+                } else if item_value != value {
+                    // Exact match for non-range filters
+                    return false;
+                }
+            }
+            None => return false,
+        }
+    }
+
+    true
+}
+
+/// Sort and page `results` at the same level as [`apply`]: by `sort_by`
+/// ascending (or `order: "desc"`), else pinned and important memories first
+pub fn order(results: Value, sort_by: Option<&str>, order_by: Option<&str>, limit: Option<usize>, offset: Option<usize>) -> Value {
+    match results {
+        Value::Array(mut items) => {
+            if let Some(field) = sort_by {
+                let descending = order_by.is_some_and(|o| o.eq_ignore_ascii_case("desc"));
+                items.sort_by(|a, b| compare_field(a.get(field), b.get(field), descending));
+            } else {
+                importance::prioritize(&mut items);
+            }
+            items.into_iter().skip(offset.unwrap_or(0)).take(limit.unwrap_or(usize::MAX)).collect()
+        }
+        Value::Object(map) => map
+            .into_iter()
+            .map(|(key, value)| match value {
+                Value::Array(_) => (key, order(value, sort_by, order_by, limit, offset)),
+                value => (key, value),
+            })
+            .collect(),
+        other => other,
+    }
+}
+
+/// Compare sort values: numbers numerically, strings lexically, missing values last
+fn compare_field(a: Option<&Value>, b: Option<&Value>, descending: bool) -> Ordering {
+    match (a.filter(|v| !v.is_null()), b.filter(|v| !v.is_null())) {
+        (Some(a), Some(b)) => {
+            let ordering = match (a.as_f64(), b.as_f64()) {
+                (Some(x), Some(y)) => x.total_cmp(&y),
+                _ => match (a.as_str(), b.as_str()) {
+                    (Some(x), Some(y)) => x.cmp(y),
+                    _ => a.to_string().cmp(&b.to_string()),
+                },
+            };
+            if descending { ordering.reverse() } else { ordering }
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Read a since/until filter: unix seconds, RFC 3339 or YYYY-MM-DD (UTC)
+pub fn parse_timestamp(value: &Value) -> Option<i64> {
+    if let Some(secs) = value.as_i64() {
+        return Some(secs);
+    }
+    let text = value.as_str()?.trim();
+    if let Ok(secs) = text.parse::<i64>() {
+        return Some(secs);
+    }
+    if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(text) {
+        return Some(datetime.timestamp());
+    }
+    chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|datetime| datetime.and_utc().timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_matches() {
+        let product = json!({"availability": "discontinued", "price": 12.5, "created_at": 1_700_000_000});
+        assert!(matches(&product, &json!({"availability": "discontinued"})));
+        assert!(!matches(&product, &json!({"availability": "in_stock"})));
+        assert!(matches(&product, &json!({"price": {"gte": 10, "lte": 15}})));
+        assert!(!matches(&product, &json!({"price": {"lte": 10}})));
+        assert!(matches(&product, &json!({"created_at": {"lte": 1_700_000_000}})));
+        // A filtered field the item lacks never matches
+        assert!(!matches(&product, &json!({"category": "drinks"})));
+        assert!(matches(&product, &json!(null)));
+
+        assert!(matches(&product, &json!({"since": "2023-11-14", "until": 1_700_000_001})));
+        assert!(!matches(&product, &json!({"since": "2023-11-15T00:00:00Z"})));
+        assert!(!matches(&json!({"price": 1}), &json!({"until": 1_700_000_000})));
+    }
+
+    #[test]
+    fn test_apply_to_wrapped_results() {
+        let results = json!({
+            "behaviors": [
+                {"behavior_id": "B1", "timestamp": 100, "tags": ["a", "b"]},
+                {"behavior_id": "B2", "timestamp": 300, "tags": ["a"]}
+            ],
+            "customer": {"customer_id": "C1"},
+            "count": 2
+        });
+        let filtered = apply(results, &json!({"since": 200}));
+        assert_eq!(filtered["behaviors"], json!([{"behavior_id": "B2", "timestamp": 300, "tags": ["a"]}]));
+        // Non-array fields of the wrapper are kept as they are
+        assert_eq!(filtered["customer"]["customer_id"], "C1");
+        assert_eq!(filtered["count"], 2);
+
+        assert_eq!(apply(json!([{"timestamp": 100}, {"timestamp": 300}]), &json!({"until": "1970-01-01T00:02:30Z"})), json!([{"timestamp": 100}]));
+    }
+
+    #[test]
+    fn test_order_and_page() {
+        let items = json!([
+            {"id": "a", "price": 9.5, "name": "latte"},
+            {"id": "b", "name": "americano"},
+            {"id": "c", "price": 3, "name": "espresso"},
+            {"id": "d", "price": 12, "name": "mocha"}
+        ]);
+        let ids = |value: &Value| value.as_array().unwrap().iter().map(|i| i["id"].clone()).collect::<Vec<_>>();
+
+        // Missing values sort last in both directions
+        assert_eq!(ids(&order(items.clone(), Some("price"), None, None, None)), ["c", "a", "d", "b"]);
+        assert_eq!(ids(&order(items.clone(), Some("price"), Some("DESC"), None, None)), ["d", "a", "c", "b"]);
+        assert_eq!(ids(&order(items.clone(), Some("name"), Some("asc"), Some(2), Some(1))), ["c", "a"]);
+
+        let wrapped = order(json!({"products": items, "count": 4}), Some("price"), None, Some(1), None);
+        assert_eq!(ids(&wrapped["products"]), ["c"]);
+        assert_eq!(wrapped["count"], 4);
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp(&json!(1_700_000_000)), Some(1_700_000_000));
+        assert_eq!(parse_timestamp(&json!(" 1700000000 ")), Some(1_700_000_000));
+        assert_eq!(parse_timestamp(&json!("2023-11-14T22:13:20Z")), Some(1_700_000_000));
+        assert_eq!(parse_timestamp(&json!("2023-11-14")), Some(1_699_920_000));
+        assert_eq!(parse_timestamp(&json!("last week")), None);
+    }
+}
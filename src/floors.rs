@@ -0,0 +1,141 @@
+//! Indoor floor levels
+//!
+//! Waypoints are grouped into `NavigationFloor` nodes by `floor_level`, and
+//! `FloorConnector` edges join a waypoint on one floor to a waypoint on
+//! another (elevator, stairs, escalator, ramp). When consecutive route steps
+//! change floor, the route gets a transition step through the best matching
+//! connector ("Take the elevator up to the 3rd floor") instead of jumping
+//! between floor numbers.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Known connector types, in order of preference when several join two floors
+pub const CONNECTOR_TYPES: &[&str] = &["elevator", "escalator", "ramp", "stairs"];
+
+/// "ground floor", "3rd floor", "basement level 2"
+pub fn floor_label(level: i64) -> String {
+    match level {
+        0 => "ground floor".to_string(),
+        l if l < 0 => format!("basement level {}", -l),
+        l => {
+            let suffix = match (l % 10, l % 100) {
+                (1, n) if n != 11 => "st",
+                (2, n) if n != 12 => "nd",
+                (3, n) if n != 13 => "rd",
+                _ => "th",
+            };
+            format!("{}{} floor", l, suffix)
+        }
+    }
+}
+
+/// A connector edge resolved to floor levels
+#[derive(Debug, Clone)]
+pub struct Connector {
+    pub connector_type: String,
+    pub is_accessible: bool,
+    /// Waypoint on the departure floor
+    pub from_waypoint: Value,
+    pub from_level: i64,
+    pub to_level: i64,
+}
+
+pub fn level_of(waypoint: &Value) -> i64 {
+    waypoint.get("floor_level").and_then(|v| v.as_i64()).unwrap_or(0)
+}
+
+/// Resolve connector edges (`from_node`/`to_node` internal IDs) against the
+/// hub's waypoints; edges whose ends are missing or on one floor are dropped
+pub fn connectors(edges: &[Value], waypoints: &[Value]) -> Vec<Connector> {
+    let by_id: HashMap<&str, &Value> = waypoints
+        .iter()
+        .filter_map(|w| w.get("id").and_then(|v| v.as_str()).map(|id| (id, w)))
+        .collect();
+    edges
+        .iter()
+        .filter_map(|edge| {
+            let from = by_id.get(edge.get("from_node")?.as_str()?)?;
+            let to = by_id.get(edge.get("to_node")?.as_str()?)?;
+            (level_of(from) != level_of(to)).then(|| Connector {
+                connector_type: edge.get("connector_type").and_then(|v| v.as_str()).unwrap_or("stairs").to_string(),
+                is_accessible: edge.get("is_accessible").and_then(|v| v.as_bool()).unwrap_or(false),
+                from_waypoint: (*from).clone(),
+                from_level: level_of(from),
+                to_level: level_of(to),
+            })
+        })
+        .collect()
+}
+
+/// Connector from `from_level` to `to_level`: accessible ones only when
+/// required, elevators first, then escalators, ramps and stairs
+pub fn pick_connector(connectors: &[Connector], from_level: i64, to_level: i64, accessible_only: bool) -> Option<&Connector> {
+    let rank = |c: &Connector| CONNECTOR_TYPES.iter().position(|t| *t == c.connector_type).unwrap_or(usize::MAX);
+    connectors
+        .iter()
+        .filter(|c| c.from_level == from_level && c.to_level == to_level)
+        .filter(|c| !accessible_only || c.is_accessible)
+        .min_by_key(|c| rank(c))
+}
+
+/// "At Lift lobby, take the elevator up to the 3rd floor (Offices)"
+pub fn transition_instruction(connector_type: Option<&str>, via: Option<&str>, from_level: i64, to_level: i64, to_name: Option<&str>) -> String {
+    let direction = if to_level > from_level { "up" } else { "down" };
+    let label = match to_name {
+        Some(name) if !name.is_empty() => format!("{} ({})", floor_label(to_level), name),
+        _ => floor_label(to_level),
+    };
+    match (connector_type, via) {
+        (Some(kind), Some(via)) => format!("At {}, take the {} {} to the {}", via, kind, direction, label),
+        (Some(kind), None) => format!("Take the {} {} to the {}", kind, direction, label),
+        (None, _) => format!("Go {} to the {}", direction, label),
+    }
+}
+
+/// Rough minutes to change floors by connector type
+pub fn transition_minutes(connector_type: Option<&str>, floors: i64) -> f64 {
+    let per_floor = match connector_type {
+        Some("elevator") => 0.3,
+        Some("escalator") => 0.4,
+        _ => 0.5,
+    };
+    // Elevators also mean waiting for the car
+    let wait = if connector_type == Some("elevator") { 1.0 } else { 0.0 };
+    wait + per_floor * floors.abs() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_connectors_and_labels() {
+        assert_eq!(floor_label(0), "ground floor");
+        assert_eq!(floor_label(3), "3rd floor");
+        assert_eq!(floor_label(11), "11th floor");
+        assert_eq!(floor_label(-2), "basement level 2");
+
+        let waypoints = vec![
+            json!({"id": "a", "waypoint_name": "Lift lobby", "floor_level": 0}),
+            json!({"id": "b", "waypoint_name": "Lift lobby 3", "floor_level": 3}),
+            json!({"id": "c", "waypoint_name": "Stairwell", "floor_level": 0}),
+            json!({"id": "d", "waypoint_name": "Stairwell 3", "floor_level": 3}),
+        ];
+        let edges = vec![
+            json!({"from_node": "c", "to_node": "d", "connector_type": "stairs", "is_accessible": false}),
+            json!({"from_node": "a", "to_node": "b", "connector_type": "elevator", "is_accessible": true}),
+            json!({"from_node": "a", "to_node": "c", "connector_type": "stairs"}),
+        ];
+        let connectors = connectors(&edges, &waypoints);
+        assert_eq!(connectors.len(), 2);
+        assert_eq!(pick_connector(&connectors, 0, 3, false).unwrap().connector_type, "elevator");
+        assert!(pick_connector(&connectors, 3, 0, false).is_none());
+        assert_eq!(transition_instruction(None, None, 3, -1, None), "Go down to the basement level 1");
+        assert_eq!(
+            transition_instruction(Some("elevator"), Some("Lift lobby"), 0, 3, Some("Offices")),
+            "At Lift lobby, take the elevator up to the 3rd floor (Offices)"
+        );
+    }
+}
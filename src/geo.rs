@@ -0,0 +1,121 @@
+//! Geographic helpers for location and navigation data
+//!
+//! Coordinates are WGS84 degrees. A (0, 0) coordinate is the schema default
+//! and is treated as "not set".
+
+use serde_json::Value;
+
+/// Mean Earth radius in meters
+const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// Great-circle distance in meters between two coordinates
+pub fn haversine_meters(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+}
+
+/// Initial great-circle bearing in degrees (0-360, clockwise from true north)
+pub fn initial_bearing(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lambda = (lon2 - lon1).to_radians();
+
+    let y = d_lambda.sin() * phi2.cos();
+    let x = phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * d_lambda.cos();
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
+/// Point `distance` meters from a coordinate along a true bearing
+pub fn destination_point(lat: f64, lon: f64, bearing: f64, distance: f64) -> (f64, f64) {
+    let (phi1, lambda1) = (lat.to_radians(), lon.to_radians());
+    let (theta, delta) = (bearing.to_radians(), distance / EARTH_RADIUS_M);
+
+    let phi2 = (phi1.sin() * delta.cos() + phi1.cos() * delta.sin() * theta.cos()).asin();
+    let lambda2 = lambda1 + (theta.sin() * delta.sin() * phi1.cos()).atan2(delta.cos() - phi1.sin() * phi2.sin());
+    (phi2.to_degrees(), (lambda2.to_degrees() + 540.0).rem_euclid(360.0) - 180.0)
+}
+
+/// Convert a true bearing to a magnetic one; declination is positive east
+pub fn magnetic_bearing(true_bearing: f64, declination: f64) -> f64 {
+    (true_bearing - declination).rem_euclid(360.0)
+}
+
+const COMPASS_POINTS: [&str; 16] = [
+    "north", "north-northeast", "northeast", "east-northeast",
+    "east", "east-southeast", "southeast", "south-southeast",
+    "south", "south-southwest", "southwest", "west-southwest",
+    "west", "west-northwest", "northwest", "north-northwest",
+];
+
+/// 16-point compass name for a bearing in degrees
+pub fn compass_point(bearing: f64) -> &'static str {
+    let index = (bearing.rem_euclid(360.0) / 22.5).round() as usize % 16;
+    COMPASS_POINTS[index]
+}
+
+/// Human-readable distance ("350 meters", "2.4 km")
+pub fn format_distance(meters: f64) -> String {
+    if meters < 1000.0 {
+        format!("{} meters", meters.round())
+    } else {
+        format!("{:.1} km", meters / 1000.0)
+    }
+}
+
+/// Typical travel speed in meters per second for a path transport_mode
+pub fn travel_speed_mps(transport_mode: &str) -> f64 {
+    match transport_mode {
+        "driving" => 8.3,
+        "cycling" => 4.2,
+        "public_transport" => 5.5,
+        "wheelchair" => 1.0,
+        _ => 1.4,
+    }
+}
+
+/// Read `latitude`/`longitude` from a node, ignoring unset (0, 0) and out-of-range values
+pub fn coordinates_of(item: &Value) -> Option<(f64, f64)> {
+    let lat = item.get("latitude").and_then(|v| v.as_f64())?;
+    let lon = item.get("longitude").and_then(|v| v.as_f64())?;
+    let unset = lat == 0.0 && lon == 0.0;
+    let valid = (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon);
+    (!unset && valid).then_some((lat, lon))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_haversine() {
+        // London -> Paris is roughly 343.5 km
+        let d = haversine_meters(51.5074, -0.1278, 48.8566, 2.3522);
+        assert!((d - 343_500.0).abs() < 1_500.0, "{}", d);
+        assert_eq!(haversine_meters(10.0, 10.0, 10.0, 10.0), 0.0);
+    }
+
+    #[test]
+    fn test_bearing() {
+        assert!((initial_bearing(0.0, 0.0, 1.0, 0.0) - 0.0).abs() < 1e-9);
+        assert!((initial_bearing(0.0, 0.0, 0.0, 1.0) - 90.0).abs() < 1e-9);
+        assert!((initial_bearing(0.0, 0.0, -1.0, 0.0) - 180.0).abs() < 1e-9);
+        assert_eq!(compass_point(359.0), "north");
+        assert_eq!(compass_point(225.0), "southwest");
+        assert!((magnetic_bearing(5.0, 10.0) - 355.0).abs() < 1e-9);
+
+        let (lat, lon) = destination_point(51.5, -0.1, 45.0, 120.0);
+        assert!((haversine_meters(51.5, -0.1, lat, lon) - 120.0).abs() < 0.01);
+        assert!((initial_bearing(51.5, -0.1, lat, lon) - 45.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_coordinates_of() {
+        assert_eq!(coordinates_of(&json!({"latitude": 1.5, "longitude": 2.5})), Some((1.5, 2.5)));
+        assert_eq!(coordinates_of(&json!({"latitude": 0.0, "longitude": 0.0})), None);
+        assert_eq!(coordinates_of(&json!({"latitude": 95.0, "longitude": 2.5})), None);
+    }
+}
@@ -0,0 +1,197 @@
+//! GeoJSON navigation import
+//!
+//! Maps a GeoJSON FeatureCollection drawn in a GIS tool onto navigation data:
+//! - `Point` features become waypoints; compass bearing and distance are
+//!   computed from the navigation hub's coordinates when it has them
+//! - `LineString` features become direction paths; distance, start/end
+//!   bearings and per-segment bearings come from the geometry, and
+//!   instructions from a `steps` property (or are generated per segment)
+//!
+//! Points lying on a line (within [`SNAP_METERS`]) are linked into that path
+//! in travel order. Coordinates are `[longitude, latitude]` as in the spec.
+
+use crate::geo;
+use serde_json::{Map, Value};
+
+/// How close a line vertex must be to a point feature to pass through it
+pub const SNAP_METERS: f64 = 3.0;
+
+pub struct PointFeature {
+    pub index: usize,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub properties: Map<String, Value>,
+}
+
+pub struct LineFeature {
+    pub index: usize,
+    pub name: String,
+    /// (latitude, longitude) vertices
+    pub coordinates: Vec<(f64, f64)>,
+    pub properties: Map<String, Value>,
+}
+
+#[derive(Default)]
+pub struct ParsedCollection {
+    pub points: Vec<PointFeature>,
+    pub lines: Vec<LineFeature>,
+    /// Features that could not be imported, with the reason
+    pub skipped: Vec<String>,
+}
+
+fn position(value: &Value) -> Option<(f64, f64)> {
+    let pair = value.as_array()?;
+    let lon = pair.first()?.as_f64()?;
+    let lat = pair.get(1)?.as_f64()?;
+    ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)).then_some((lat, lon))
+}
+
+fn feature_name(properties: &Map<String, Value>, fallback: String) -> String {
+    ["name", "title", "label"]
+        .iter()
+        .find_map(|key| properties.get(*key).and_then(|v| v.as_str()).filter(|s| !s.is_empty()))
+        .map(String::from)
+        .unwrap_or(fallback)
+}
+
+/// Split a FeatureCollection (or a single Feature) into points and lines
+pub fn parse_collection(document: &Value) -> Result<ParsedCollection, String> {
+    let features = match document.get("type").and_then(|t| t.as_str()) {
+        Some("FeatureCollection") => document.get("features").and_then(|f| f.as_array()).cloned().unwrap_or_default(),
+        Some("Feature") => vec![document.clone()],
+        _ => return Err("Expected a GeoJSON FeatureCollection or Feature".to_string()),
+    };
+
+    let mut parsed = ParsedCollection::default();
+    for (index, feature) in features.iter().enumerate() {
+        let properties = feature.get("properties").and_then(|p| p.as_object()).cloned().unwrap_or_default();
+        let geometry = feature.get("geometry").unwrap_or(&Value::Null);
+        let coordinates = geometry.get("coordinates").unwrap_or(&Value::Null);
+        match geometry.get("type").and_then(|t| t.as_str()) {
+            Some("Point") => match position(coordinates) {
+                Some((latitude, longitude)) => parsed.points.push(PointFeature {
+                    index,
+                    name: feature_name(&properties, format!("Waypoint {}", index + 1)),
+                    latitude,
+                    longitude,
+                    properties,
+                }),
+                None => parsed.skipped.push(format!("feature {}: invalid Point coordinates", index)),
+            },
+            Some("LineString") => {
+                let vertices: Option<Vec<(f64, f64)>> = coordinates.as_array().map(|c| c.iter().filter_map(position).collect());
+                match vertices.filter(|v| v.len() >= 2 && v.len() == coordinates.as_array().map_or(0, |c| c.len())) {
+                    Some(coordinates) => parsed.lines.push(LineFeature {
+                        index,
+                        name: feature_name(&properties, format!("Path {}", index + 1)),
+                        coordinates,
+                        properties,
+                    }),
+                    None => parsed.skipped.push(format!("feature {}: LineString needs at least 2 valid positions", index)),
+                }
+            }
+            Some(other) => parsed.skipped.push(format!("feature {}: unsupported geometry {}", index, other)),
+            None => parsed.skipped.push(format!("feature {}: missing geometry", index)),
+        }
+    }
+    Ok(parsed)
+}
+
+pub struct PathMetrics {
+    pub distance_meters: f64,
+    pub start_bearing: f64,
+    pub end_bearing: f64,
+    pub segment_bearings: Vec<f64>,
+}
+
+pub fn path_metrics(coordinates: &[(f64, f64)]) -> PathMetrics {
+    let segments: Vec<(f64, f64)> = coordinates
+        .windows(2)
+        .map(|w| {
+            let ((lat1, lon1), (lat2, lon2)) = (w[0], w[1]);
+            (geo::haversine_meters(lat1, lon1, lat2, lon2), geo::initial_bearing(lat1, lon1, lat2, lon2))
+        })
+        .collect();
+    let bearings: Vec<f64> = segments.iter().map(|(_, b)| (b * 10.0).round() / 10.0).collect();
+    PathMetrics {
+        distance_meters: segments.iter().map(|(d, _)| d).sum(),
+        start_bearing: bearings.first().copied().unwrap_or(0.0),
+        end_bearing: bearings.last().copied().unwrap_or(0.0),
+        segment_bearings: bearings,
+    }
+}
+
+/// Numbered instructions: the `steps` property if given, else one per segment
+pub fn instructions(line: &LineFeature) -> String {
+    let steps: Vec<String> = match line.properties.get("steps") {
+        Some(Value::Array(items)) => items.iter().filter_map(|s| s.as_str().map(String::from)).collect(),
+        Some(Value::String(text)) => text.lines().map(str::trim).filter(|l| !l.is_empty()).map(String::from).collect(),
+        _ => Vec::new(),
+    };
+    let steps = if steps.is_empty() {
+        line.coordinates
+            .windows(2)
+            .map(|w| {
+                let ((lat1, lon1), (lat2, lon2)) = (w[0], w[1]);
+                format!(
+                    "Head {} for {}",
+                    geo::compass_point(geo::initial_bearing(lat1, lon1, lat2, lon2)),
+                    geo::format_distance(geo::haversine_meters(lat1, lon1, lat2, lon2))
+                )
+            })
+            .collect()
+    } else {
+        steps
+    };
+    steps.iter().enumerate().map(|(i, s)| format!("{}. {}", i + 1, s)).collect::<Vec<_>>().join("\n")
+}
+
+/// Indices into `points` of the points the line passes through, in travel order
+pub fn points_on_line(line: &LineFeature, points: &[PointFeature]) -> Vec<usize> {
+    let mut on_line = Vec::new();
+    for &(lat, lon) in &line.coordinates {
+        let nearest = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (i, geo::haversine_meters(lat, lon, p.latitude, p.longitude)))
+            .filter(|(_, d)| *d <= SNAP_METERS)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((i, _)) = nearest {
+            if on_line.last() != Some(&i) {
+                on_line.push(i);
+            }
+        }
+    }
+    on_line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_and_link() {
+        let document = json!({
+            "type": "FeatureCollection",
+            "features": [
+                {"type": "Feature", "geometry": {"type": "Point", "coordinates": [-0.1000, 51.5000]}, "properties": {"name": "Gate"}},
+                {"type": "Feature", "geometry": {"type": "Point", "coordinates": [-0.1000, 51.5010]}, "properties": {"name": "Lobby"}},
+                {"type": "Feature", "geometry": {"type": "LineString", "coordinates": [[-0.1000, 51.5000], [-0.1000, 51.5010]]}, "properties": {"name": "Gate to lobby"}},
+                {"type": "Feature", "geometry": {"type": "Polygon", "coordinates": []}, "properties": {}}
+            ]
+        });
+        let parsed = parse_collection(&document).unwrap();
+        assert_eq!(parsed.points.len(), 2);
+        assert_eq!(parsed.lines.len(), 1);
+        assert_eq!(parsed.skipped.len(), 1);
+
+        let line = &parsed.lines[0];
+        let metrics = path_metrics(&line.coordinates);
+        assert!((metrics.distance_meters - 111.2).abs() < 1.0, "{}", metrics.distance_meters);
+        assert_eq!(metrics.start_bearing, 0.0);
+        assert_eq!(points_on_line(line, &parsed.points), vec![0, 1]);
+        assert_eq!(instructions(line), "1. Head north for 111 meters");
+    }
+}
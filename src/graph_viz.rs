@@ -0,0 +1,278 @@
+//! Graph exports for external visualization tools
+//!
+//! `graph_business` / `graph_customer` return the nodes around a business or
+//! a customer and the edges between them. Nodes are labeled with their
+//! memory type and name, edges with their relationship (the edge label), and
+//! the graph is written as GraphViz DOT or as Cytoscape.js elements JSON.
+//! Edges to nodes outside the export (other customers' interactions,
+//! filtered-out types, nodes past `max_nodes`) are left out and counted.
+
+use crate::helix_client::HelixClient;
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+
+/// Node groups by the graph query's return names, with memory type and ID field
+const NODES: &[(&str, &str, &str)] = &[
+    ("business", "business", "business_id"),
+    ("customer", "customer", "customer_id"),
+    ("businesses", "business", "business_id"),
+    ("collaborators", "business", "business_id"),
+    ("customers", "customer", "customer_id"),
+    ("products", "product", "product_id"),
+    ("services", "service", "service_id"),
+    ("locations", "location", "location_id"),
+    ("hours", "hours", "hours_id"),
+    ("social", "social", "social_id"),
+    ("policies", "policy", "policy_id"),
+    ("events", "event", "event_id"),
+    ("information", "information", "info_id"),
+    ("navigation_hubs", "navigation", "navigation_id"),
+    ("behaviors", "behavior", "behavior_id"),
+    ("preferences", "preference", "preference_id"),
+    ("desires", "desire", "desire_id"),
+    ("rules", "rule", "rule_id"),
+    ("feedback", "feedback", "feedback_id"),
+    ("product_interactions", "product_interaction", "interaction_id"),
+    ("service_interactions", "service_interaction", "interaction_id"),
+    ("location_visits", "location_visit", "visit_id"),
+    ("communications", "communication", "communication_id"),
+];
+
+/// Edge groups by return name and relationship
+const EDGES: &[(&str, &str)] = &[
+    ("owned_by", "OwnedBy"),
+    ("shared_with", "SharedWith"),
+    ("customer_of", "CustomerOf"),
+    ("collaborates_with", "CollaboratesWith"),
+    ("has_navigation", "HasNavigation"),
+    ("related_information", "RelatedInformation"),
+    ("prerequisite_for", "PrerequisiteFor"),
+    ("part_of_series", "PartOfSeries"),
+    ("references", "References"),
+    ("information_about_product", "InformationAboutProduct"),
+    ("information_about_service", "InformationAboutService"),
+    ("information_for_location", "InformationForLocation"),
+    ("information_for_event", "InformationForEvent"),
+    ("about_customer", "AboutCustomer"),
+    ("behavior_interaction_links", "CustomerHasProductInteraction"),
+    ("behavior_visit_links", "CustomerHasLocationVisit"),
+    ("behavior_communication_links", "CustomerHasCommunication"),
+    ("preference_interaction_links", "CustomerHasServiceInteraction"),
+    ("product_interaction_links", "InteractionAboutProduct"),
+    ("service_interaction_links", "InteractionAboutService"),
+    ("location_visit_links", "VisitAtLocation"),
+    ("communication_links", "CommunicationAboutPolicy"),
+];
+
+/// Node types that can be filtered with `memory_types`
+pub fn memory_types() -> Vec<&'static str> {
+    let mut types: Vec<&str> = NODES.iter().map(|(_, memory_type, _)| *memory_type).collect();
+    types.sort_unstable();
+    types.dedup();
+    types
+}
+
+/// Fields naming a node, in order of preference
+const NAME_FIELDS: &[&str] = &[
+    "business_name", "customer_name", "product_name", "service_name", "location_name", "policy_name", "event_name",
+    "title", "building_name", "platform", "subject", "action", "rule_description", "description", "schedule_type",
+    "interaction_type", "visit_type", "contact_reason",
+];
+
+const MAX_LABEL_CHARS: usize = 40;
+
+const COLORS: &[(&str, &str)] = &[
+    ("business", "#4e79a7"),
+    ("customer", "#f28e2b"),
+    ("product", "#59a14f"),
+    ("service", "#76b7b2"),
+    ("location", "#edc948"),
+    ("hours", "#bab0ac"),
+    ("social", "#ff9da7"),
+    ("policy", "#9c755f"),
+    ("event", "#b07aa1"),
+    ("information", "#8cd17d"),
+    ("navigation", "#d4a6c8"),
+];
+
+fn color(memory_type: &str) -> &'static str {
+    COLORS.iter().find(|(t, _)| *t == memory_type).map(|(_, c)| *c).unwrap_or("#e0e0e0")
+}
+
+fn items(result: &Value, key: &str) -> Vec<Value> {
+    HelixClient::result_items(&json!({ key: result.get(key).cloned().unwrap_or(Value::Null) }))
+}
+
+fn text<'a>(item: &'a Value, field: &str) -> Option<&'a str> {
+    item.get(field).and_then(|v| v.as_str()).filter(|t| !t.is_empty())
+}
+
+/// A node's display name: its name field, else its ID
+fn name(item: &Value, id_field: &str) -> String {
+    let name = NAME_FIELDS.iter().find_map(|f| text(item, f)).or_else(|| text(item, id_field)).unwrap_or("");
+    match name.char_indices().nth(MAX_LABEL_CHARS) {
+        Some((at, _)) => format!("{}…", &name[..at]),
+        None => name.to_string(),
+    }
+}
+
+pub struct Node {
+    /// Internal node ID
+    pub id: String,
+    pub memory_type: &'static str,
+    pub name: String,
+}
+
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub relationship: &'static str,
+}
+
+#[derive(Default)]
+pub struct Graph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+    /// Nodes dropped by `max_nodes`
+    pub truncated_nodes: usize,
+    /// Edges with an end outside the export
+    pub omitted_edges: usize,
+}
+
+impl Graph {
+    /// Graph from a `graph_business` / `graph_customer` result, keeping the given
+    /// node types (all when `None`) and at most `max_nodes` nodes
+    pub fn from_result(result: &Value, memory_types: Option<&[&str]>, max_nodes: usize) -> Graph {
+        let mut graph = Graph::default();
+        let mut seen = HashSet::new();
+        for (key, memory_type, id_field) in NODES {
+            if memory_types.is_some_and(|types| !types.contains(memory_type)) {
+                continue;
+            }
+            for item in items(result, key) {
+                let Some(id) = text(&item, "id") else { continue };
+                if !seen.insert(id.to_string()) {
+                    continue;
+                }
+                if graph.nodes.len() >= max_nodes {
+                    graph.truncated_nodes += 1;
+                    continue;
+                }
+                graph.nodes.push(Node { id: id.to_string(), memory_type, name: name(&item, id_field) });
+            }
+        }
+
+        let included: HashSet<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        let mut linked = HashSet::new();
+        let mut edges = Vec::new();
+        for (key, relationship) in EDGES {
+            for edge in items(result, key) {
+                let (Some(from), Some(to)) = (text(&edge, "from_node"), text(&edge, "to_node")) else { continue };
+                if !linked.insert((from.to_string(), to.to_string(), *relationship)) {
+                    continue;
+                }
+                if included.contains(from) && included.contains(to) {
+                    edges.push(Edge { from: from.to_string(), to: to.to_string(), relationship });
+                } else {
+                    graph.omitted_edges += 1;
+                }
+            }
+        }
+        graph.edges = edges;
+        graph
+    }
+
+    /// Node counts per memory type and edge counts per relationship
+    pub fn summary(&self) -> Value {
+        let count = |keys: Vec<&str>| {
+            let mut counts = Map::new();
+            for key in keys {
+                let n = counts.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+                counts.insert(key.to_string(), json!(n + 1));
+            }
+            counts
+        };
+        json!({
+            "nodes": count(self.nodes.iter().map(|n| n.memory_type).collect()),
+            "edges": count(self.edges.iter().map(|e| e.relationship).collect())
+        })
+    }
+
+    /// GraphViz DOT, one box per node colored by memory type
+    pub fn to_dot(&self, title: &str) -> String {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', " "));
+        let mut dot = format!("digraph {} {{\n", quote(title));
+        dot.push_str("    rankdir=LR;\n");
+        dot.push_str("    node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];\n");
+        dot.push_str("    edge [fontname=\"Helvetica\", fontsize=10];\n");
+        for node in &self.nodes {
+            // The label's line break is DOT's own \n escape, added after quoting
+            let label = quote(&node.name);
+            let label = format!("\"{}\\n{}", node.memory_type, &label[1..]);
+            dot.push_str(&format!("    {} [label={}, fillcolor=\"{}\"];\n", quote(&node.id), label, color(node.memory_type)));
+        }
+        for edge in &self.edges {
+            dot.push_str(&format!("    {} -> {} [label={}];\n", quote(&edge.from), quote(&edge.to), quote(edge.relationship)));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Cytoscape.js elements JSON
+    pub fn to_cytoscape(&self) -> Value {
+        let nodes: Vec<Value> = self
+            .nodes
+            .iter()
+            .map(|node| json!({"data": {"id": node.id, "label": node.name, "memory_type": node.memory_type}}))
+            .collect();
+        let edges: Vec<Value> = self
+            .edges
+            .iter()
+            .map(|edge| {
+                json!({"data": {
+                    "id": format!("{}-{}-{}", edge.from, edge.relationship, edge.to),
+                    "source": edge.from,
+                    "target": edge.to,
+                    "label": edge.relationship
+                }})
+            })
+            .collect();
+        json!({"elements": {"nodes": nodes, "edges": edges}})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_exports() {
+        let result = json!({
+            "business": [{"id": "b", "label": "Business", "business_id": "B1", "business_name": "Cafe \"Bean\""}],
+            "products": [
+                {"id": "p1", "product_id": "P1", "product_name": "Espresso"},
+                {"id": "p2", "product_id": "P2", "product_name": ""},
+            ],
+            "customers": [{"id": "c", "customer_id": "C1"}],
+            "owned_by": [{"from_node": "p1", "to_node": "b"}, {"from_node": "p2", "to_node": "b"}, {"from_node": "p1", "to_node": "b"}],
+            "customer_of": [{"from_node": "c", "to_node": "b"}],
+            "information_about_product": [{"from_node": "i9", "to_node": "p1"}]
+        });
+        let graph = Graph::from_result(&result, None, 500);
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(graph.nodes[3].name, "P2");
+        assert_eq!((graph.edges.len(), graph.omitted_edges), (3, 1));
+        assert_eq!(graph.summary()["nodes"]["product"], 2);
+
+        let dot = graph.to_dot("business B1");
+        assert!(dot.starts_with("digraph \"business B1\" {"));
+        assert!(dot.contains("\"b\" [label=\"business\\nCafe \\\"Bean\\\"\", fillcolor=\"#4e79a7\"];"));
+        assert!(dot.contains("\"c\" -> \"b\" [label=\"CustomerOf\"];"));
+
+        let limited = Graph::from_result(&result, Some(&["business", "product"]), 2);
+        assert_eq!((limited.nodes.len(), limited.truncated_nodes, limited.edges.len()), (2, 1, 1));
+        let cytoscape = limited.to_cytoscape();
+        assert_eq!(cytoscape["elements"]["edges"][0]["data"]["source"], "p1");
+        assert_eq!(cytoscape["elements"]["nodes"][1]["data"]["memory_type"], "product");
+    }
+}
@@ -1,16 +1,36 @@
 use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use reqwest::header::{HeaderName, CONTENT_ENCODING, CONTENT_TYPE};
 use reqwest::Client as HttpClient;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tracing::debug;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use crate::config::PayloadFormat;
+use crate::crypto::FieldCipher;
+use crate::wire;
+use crate::priority::{PriorityGate, DEFAULT_BACKGROUND_CONCURRENCY};
+
+/// Queries of a [`HelixClient::query_batch`] in flight at once
+const BATCH_CONCURRENCY: usize = 8;
 
 /// HelixDB HTTP client for MCP endpoints
 #[derive(Clone)]
 pub struct HelixClient {
     base_url: String,
+    read_url: Option<String>,
+    payload_format: PayloadFormat,
+    /// Set once HelixDB refuses `payload_format`
+    json_only: Arc<AtomicBool>,
     http_client: HttpClient,
+    gate: Arc<PriorityGate>,
+    field_cipher: Option<Arc<FieldCipher>>,
 }
 
+// Client API for HelixDB's MCP endpoints; the tool handlers don't use it yet
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct QueryRequest {
     pub connection_id: Option<String>,
@@ -23,22 +43,86 @@ impl HelixClient {
         let base_url = format!("http://{}:{}", endpoint, port);
         Self {
             base_url,
+            read_url: None,
+            payload_format: PayloadFormat::Json,
+            json_only: Arc::new(AtomicBool::new(false)),
             http_client: HttpClient::new(),
+            gate: Arc::new(PriorityGate::new(DEFAULT_BACKGROUND_CONCURRENCY)),
+            field_cipher: None,
         }
     }
 
+    /// Encrypt configured fields before they reach HelixDB and decrypt them on read
+    pub fn with_field_encryption(mut self, cipher: FieldCipher) -> Self {
+        self.field_cipher = Some(Arc::new(cipher));
+        self
+    }
+
+    /// Send read-only queries to a replica; they retry on the primary if it fails
+    pub fn with_read_replica(mut self, endpoint: &str, port: u16) -> Self {
+        self.read_url = Some(format!("http://{}:{}", endpoint, port));
+        self
+    }
+
+    /// Encode query payloads as MessagePack or gzip JSON (see [`crate::wire`])
+    pub fn with_payload_format(mut self, payload_format: PayloadFormat) -> Self {
+        self.payload_format = payload_format;
+        self
+    }
+
+    /// The replica a query goes to first: only read-only queries, and only when one is configured
+    fn replica_for(&self, endpoint: &str) -> Option<&str> {
+        self.read_url.as_deref().filter(|_| crate::dry_run::is_read_query(endpoint))
+    }
+
+    /// Limit concurrent background queries (interactive queries always go first)
+    pub fn with_background_concurrency(mut self, background_concurrency: usize) -> Self {
+        self.gate = Arc::new(PriorityGate::new(background_concurrency));
+        self
+    }
+
     /// Execute a HelixDB MCP query
-    pub async fn query(&self, endpoint: &str, payload: Value) -> Result<Value> {
-        let url = format!("{}/{}", self.base_url, endpoint);
-        
+    pub async fn query(&self, endpoint: &str, mut payload: Value) -> Result<Value> {
+        if let Some(recorded) = crate::dry_run::intercept(endpoint, &payload) {
+            debug!("Dry run: recorded {} instead of executing it", endpoint);
+            return Ok(recorded);
+        }
+        if let Some(cipher) = &self.field_cipher {
+            let business_id = self.encryption_scope(cipher, &payload).await?;
+            cipher.encrypt_payload(&mut payload, business_id.as_deref()).map_err(anyhow::Error::msg)?;
+        }
+
+        let _ticket = self.gate.enter().await;
+        let mut result = match self.replica_for(endpoint) {
+            Some(read_url) => match self.post(read_url, endpoint, &payload).await {
+                Ok(result) => result,
+                Err(e) => {
+                    warn!("Read replica failed for {}, retrying on primary: {}", endpoint, e);
+                    self.post(&self.base_url, endpoint, &payload).await?
+                }
+            },
+            None => self.post(&self.base_url, endpoint, &payload).await?,
+        };
+        if let Some(cipher) = &self.field_cipher {
+            cipher.decrypt_result(&mut result);
+        }
+
+        crate::budget::charge_current_rows(Self::result_count(&result) as u64)?;
+
+        Ok(result)
+    }
+
+    async fn post(&self, base_url: &str, endpoint: &str, payload: &Value) -> Result<Value> {
+        let url = format!("{}/{}", base_url, endpoint);
         debug!("Querying HelixDB: {} with payload: {}", url, payload);
-        
-        let response = self.http_client
-            .post(&url)
-            .json(&payload)
-            .send()
-            .await
-            .context("Failed to send request to HelixDB")?;
+
+        let format = if self.json_only.load(Ordering::Relaxed) { &PayloadFormat::Json } else { &self.payload_format };
+        let mut response = self.send(&url, format, payload).await?;
+        if *format != PayloadFormat::Json && wire::rejected(response.status()) {
+            warn!("HelixDB refused {:?} payloads, switching to JSON", format);
+            self.json_only.store(true, Ordering::Relaxed);
+            response = self.send(&url, &PayloadFormat::Json, payload).await?;
+        }
 
         if !response.status().is_success() {
             let status = response.status();
@@ -46,12 +130,96 @@ impl HelixClient {
             anyhow::bail!("HelixDB query failed with status {}: {}", status, error_text);
         }
 
-        let result = response
-            .json::<Value>()
-            .await
-            .context("Failed to parse HelixDB response")?;
+        let header = |name: HeaderName| response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        let (content_type, content_encoding) = (header(CONTENT_TYPE), header(CONTENT_ENCODING));
+        let body = response.bytes().await.context("Failed to read HelixDB response")?;
+        wire::decode(content_type.as_deref(), content_encoding.as_deref(), &body)
+            .map_err(anyhow::Error::msg)
+            .context("Failed to parse HelixDB response")
+    }
 
-        Ok(result)
+    async fn send(&self, url: &str, format: &PayloadFormat, payload: &Value) -> Result<reqwest::Response> {
+        let encoded = wire::encode(format, payload).map_err(anyhow::Error::msg)?;
+        let mut request = self.http_client.post(url);
+        for (name, value) in encoded.headers {
+            request = request.header(name, value);
+        }
+        request.body(encoded.body).send().await.context("Failed to send request to HelixDB")
+    }
+
+    /// Run several queries, results in call order
+    ///
+    /// HelixDB serves one query per request, so the calls are pipelined over
+    /// the connection pool a few at a time instead. Each call succeeds or
+    /// fails on its own; a batch is not a transaction.
+    pub async fn query_batch(&self, calls: Vec<(&'static str, Value)>) -> Vec<Result<Value>> {
+        // Futures are lazy: only BATCH_CONCURRENCY of them run at a time
+        let queries: Vec<_> = calls.into_iter().map(|(endpoint, payload)| self.query(endpoint, payload)).collect();
+        stream::iter(queries).buffered(BATCH_CONCURRENCY).collect().await
+    }
+
+    /// Business whose key encrypts `payload`: its `business_id`, or for
+    /// customer data the customer's linked business (looked up once)
+    async fn encryption_scope(&self, cipher: &FieldCipher, payload: &Value) -> Result<Option<String>> {
+        if let Some(business_id) = payload.get("business_id").and_then(|v| v.as_str()) {
+            return Ok(Some(business_id.to_string()));
+        }
+        let Some(customer_id) = payload.get("customer_id").and_then(|v| v.as_str()) else {
+            return Ok(None);
+        };
+        if !cipher.has_business_keys() || !cipher.needs_encryption(payload) {
+            return Ok(None);
+        }
+        if let Some(business_id) = cipher.customer_business(customer_id) {
+            return Ok(Some(business_id));
+        }
+        let profile = Box::pin(self.query("get_customer_profile", serde_json::json!({"customer_id": customer_id}))).await?;
+        let businesses: Vec<String> = profile
+            .get("businesses")
+            .map(|b| match b {
+                Value::Array(items) => items.clone(),
+                other => vec![other.clone()],
+            })
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|b| b.get("business_id").and_then(|v| v.as_str()).map(str::to_string))
+            .collect();
+        Ok(cipher.remember_customer(customer_id, &businesses))
+    }
+
+    /// Number of result items in a query response (see [`Self::result_items`])
+    pub fn result_count(result: &Value) -> usize {
+        match result {
+            Value::Array(items) => items.len(),
+            Value::Object(map) => map
+                .values()
+                .map(|v| match v {
+                    Value::Array(items) => items.len(),
+                    Value::Object(_) => 1,
+                    _ => 0,
+                })
+                .sum(),
+            _ => 0,
+        }
+    }
+
+    /// Flatten a query response into its result items
+    ///
+    /// HelixDB wraps each RETURN variable in an object keyed by name
+    /// (e.g. `{"products": [...]}`); bare arrays are passed through.
+    pub fn result_items(result: &Value) -> Vec<Value> {
+        match result {
+            Value::Array(items) => items.clone(),
+            Value::Object(map) => map
+                .values()
+                .flat_map(|v| match v {
+                    Value::Array(items) => items.clone(),
+                    Value::Object(_) => vec![v.clone()],
+                    _ => Vec::new(),
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
     }
 
     /// Initialize a new MCP session
@@ -211,6 +379,7 @@ impl HelixClient {
     }
 
     /// Vector similarity search
+    #[allow(dead_code)]
     pub async fn search_vector(&self, connection_id: &str, vector: Vec<f32>, k: usize, min_score: Option<f32>) -> Result<Value> {
         let mut data = serde_json::json!({
             "vector": vector,
@@ -266,3 +435,43 @@ impl HelixClient {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replica_routing() {
+        let primary = HelixClient::new("127.0.0.1", 6969);
+        assert_eq!(primary.replica_for("get_business_products"), None);
+
+        let client = primary.with_read_replica("10.0.0.2", 7070);
+        for read in ["get_business_products", "search_products_semantic", "find_similar_products", "count_memories", "export_customer_data"] {
+            assert_eq!(client.replica_for(read), Some("http://10.0.0.2:7070"), "{}", read);
+        }
+        // Writes and session calls always go to the primary
+        for write in ["create_business_product_memory", "update_business_product", "delete_customer_behavior", "mcp/init"] {
+            assert_eq!(client.replica_for(write), None, "{}", write);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_batch_results_in_call_order() {
+        // Nothing listens on port 1, so only the read reaches the network and fails
+        let client = HelixClient::new("127.0.0.1", 1);
+        let recorder = Arc::new(crate::dry_run::Recorder::default());
+        let calls: Vec<(&'static str, Value)> = (1..=10)
+            .map(|order| ("link_path_waypoint", serde_json::json!({"sequence_order": order})))
+            .chain([("get_path_route", serde_json::json!({"path_id": "PATH_1"}))])
+            .collect();
+        let results = crate::dry_run::scope(recorder.clone(), client.query_batch(calls)).await;
+
+        assert_eq!(results.len(), 11);
+        assert!(results[..10].iter().all(|r| r.as_ref().is_ok_and(|v| v["query"] == "link_path_waypoint")));
+        // One failed call doesn't fail the batch
+        assert!(results[10].is_err());
+        let report = crate::dry_run::report("create_direction_path", &recorder, None, false);
+        let orders: Vec<i64> = report["would_execute"].as_array().unwrap().iter().map(|w| w["payload"]["sequence_order"].as_i64().unwrap()).collect();
+        assert_eq!(orders, (1..=10).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,415 @@
+//! Business hours evaluation
+//!
+//! Interprets hours memories (`monday_open` .. `sunday_close`, `timezone`,
+//! `exceptions`) so "is it open now?" is answered server-side. Timezones are
+//! fixed UTC offsets or common abbreviations; daylight saving is not applied
+//! automatically, so store the abbreviation in effect (e.g. "EDT") or pass an
+//! explicit offset.
+//!
+//! `exceptions` is a JSON object keyed by `YYYY-MM-DD`; a value is `"closed"`,
+//! `"HH:MM-HH:MM"`, or `{"closed": true}` / `{"open": "HH:MM", "close": "HH:MM"}`
+//! with an optional `"reason"`.
+
+use chrono::{Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+pub const DAYS: [&str; 7] = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+
+/// How far ahead to look for the next opening
+const LOOKAHEAD_DAYS: i64 = 14;
+
+/// Opening interval for one day; `close <= open` means it closes after midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
+    pub open: NaiveTime,
+    pub close: NaiveTime,
+}
+
+impl Interval {
+    /// Start and end on `date`, the end on the next day for overnight hours
+    pub fn on(&self, date: NaiveDate) -> (NaiveDateTime, NaiveDateTime) {
+        let start = date.and_time(self.open);
+        let mut end = date.and_time(self.close);
+        if end <= start {
+            end += Duration::days(1);
+        }
+        (start, end)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exception {
+    pub hours: Option<Interval>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WeeklyHours {
+    pub days: [Option<Interval>; 7],
+    pub exceptions: BTreeMap<NaiveDate, Exception>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenStatus {
+    pub open: bool,
+    pub closes_at: Option<NaiveDateTime>,
+    pub next_open: Option<NaiveDateTime>,
+    pub reason: Option<String>,
+}
+
+/// Parse "09:00", "9:30", "9am", "5:30 pm", "1730"; "24:00" means end of day
+pub fn parse_time(input: &str) -> Option<NaiveTime> {
+    let s = input.trim().to_lowercase().replace([' ', '.'], "");
+    if s.is_empty() {
+        return None;
+    }
+    if s == "24:00" || s == "midnight" {
+        return NaiveTime::from_hms_opt(0, 0, 0);
+    }
+    if s == "noon" {
+        return NaiveTime::from_hms_opt(12, 0, 0);
+    }
+
+    let (digits, meridiem) = match s.strip_suffix("am").or_else(|| s.strip_suffix('a')) {
+        Some(rest) => (rest, Some(false)),
+        None => match s.strip_suffix("pm").or_else(|| s.strip_suffix('p')) {
+            Some(rest) => (rest, Some(true)),
+            None => (s.as_str(), None),
+        },
+    };
+    let (hour, minute) = match digits.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        None if digits.len() > 2 => (digits[..digits.len() - 2].parse().ok()?, digits[digits.len() - 2..].parse().ok()?),
+        None => (digits.parse().ok()?, 0),
+    };
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(false) => hour % 12,
+        Some(true) => hour % 12 + 12,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// Parse a timezone as a fixed UTC offset: "", "UTC", "+05:30", "UTC-5", "GMT+3", "EST", ...
+pub fn parse_offset(timezone: &str) -> Option<FixedOffset> {
+    let tz = timezone.trim().to_uppercase();
+    let hours = |h: i32| FixedOffset::east_opt(h * 3600);
+    match tz.as_str() {
+        "" | "UTC" | "GMT" | "Z" => return hours(0),
+        "BST" | "CET" | "WAT" => return hours(1),
+        "CEST" | "EET" | "SAST" | "CAT" => return hours(2),
+        "EEST" | "EAT" | "MSK" => return hours(3),
+        "IST" => return FixedOffset::east_opt(5 * 3600 + 1800),
+        "SGT" | "AWST" => return hours(8),
+        "JST" | "KST" => return hours(9),
+        "AEST" => return hours(10),
+        "AEDT" => return hours(11),
+        "NZST" => return hours(12),
+        "AST" | "EDT" => return hours(-4),
+        "EST" | "CDT" => return hours(-5),
+        "CST" | "MDT" => return hours(-6),
+        "MST" | "PDT" => return hours(-7),
+        "PST" | "AKDT" => return hours(-8),
+        "AKST" => return hours(-9),
+        "HST" => return hours(-10),
+        _ => {}
+    }
+
+    let offset = tz.strip_prefix("UTC").or_else(|| tz.strip_prefix("GMT")).unwrap_or(&tz);
+    let (sign, rest) = match offset.chars().next()? {
+        '+' => (1, &offset[1..]),
+        '-' => (-1, &offset[1..]),
+        _ => return None,
+    };
+    let (h, m) = match rest.split_once(':') {
+        Some((h, m)) => (h.parse::<i32>().ok()?, m.parse::<i32>().ok()?),
+        None if rest.len() == 4 => (rest[..2].parse().ok()?, rest[2..].parse().ok()?),
+        None => (rest.parse().ok()?, 0),
+    };
+    if h > 14 || m >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (h * 3600 + m * 60))
+}
+
+fn parse_interval(open: &str, close: &str) -> Option<Interval> {
+    Some(Interval { open: parse_time(open)?, close: parse_time(close)? })
+}
+
+/// Parse one exception value; `Err` describes why it is invalid
+pub fn parse_exception(value: &Value) -> Result<Exception, String> {
+    match value {
+        Value::String(s) if s.eq_ignore_ascii_case("closed") => Ok(Exception { hours: None, reason: None }),
+        Value::String(s) => {
+            let (open, close) = s.split_once('-').ok_or_else(|| format!("expected \"closed\" or \"HH:MM-HH:MM\", got \"{}\"", s))?;
+            let hours = parse_interval(open, close).ok_or_else(|| format!("invalid time range \"{}\"", s))?;
+            Ok(Exception { hours: Some(hours), reason: None })
+        }
+        Value::Object(map) => {
+            let reason = map.get("reason").and_then(|v| v.as_str()).filter(|r| !r.is_empty()).map(String::from);
+            if map.get("closed").and_then(|v| v.as_bool()) == Some(true) {
+                return Ok(Exception { hours: None, reason });
+            }
+            let open = map.get("open").and_then(|v| v.as_str()).ok_or("missing \"open\" (or \"closed\": true)")?;
+            let close = map.get("close").and_then(|v| v.as_str()).ok_or("missing \"close\"")?;
+            let hours = parse_interval(open, close).ok_or_else(|| format!("invalid times \"{}\" - \"{}\"", open, close))?;
+            Ok(Exception { hours: Some(hours), reason })
+        }
+        _ => Err("exception must be a string or object".to_string()),
+    }
+}
+
+/// Parse the `exceptions` field (a JSON object or a JSON-encoded string), skipping invalid entries
+pub fn parse_exceptions(value: Option<&Value>) -> BTreeMap<NaiveDate, Exception> {
+    let parsed = match value {
+        Some(Value::String(s)) => serde_json::from_str(s).unwrap_or(Value::Null),
+        Some(v) => v.clone(),
+        None => Value::Null,
+    };
+    parsed
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(date, v)| Some((NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?, parse_exception(v).ok()?)))
+        .collect()
+}
+
+/// Raw `exceptions` field as a JSON object (it is stored as a JSON string)
+pub fn exceptions_object(value: Option<&Value>) -> Result<Map<String, Value>, String> {
+    let parsed = match value {
+        Some(Value::String(s)) if s.trim().is_empty() => return Ok(Map::new()),
+        Some(Value::String(s)) => serde_json::from_str(s).map_err(|e| format!("stored exceptions are not valid JSON: {}", e))?,
+        Some(v) => v.clone(),
+        None => return Ok(Map::new()),
+    };
+    match parsed {
+        Value::Object(map) => Ok(map),
+        Value::Null => Ok(Map::new()),
+        _ => Err("stored exceptions are not a JSON object".to_string()),
+    }
+}
+
+/// Canonical stored form of an exception
+fn exception_json(exception: &Exception) -> Value {
+    let mut value = match exception.hours {
+        Some(interval) => json!({"open": interval.open.format("%H:%M").to_string(), "close": interval.close.format("%H:%M").to_string()}),
+        None => json!({"closed": true}),
+    };
+    if let Some(reason) = &exception.reason {
+        value["reason"] = json!(reason);
+    }
+    value
+}
+
+/// Result of [`merge_exceptions`]
+#[derive(Debug, Default)]
+pub struct ExceptionMerge {
+    pub merged: Map<String, Value>,
+    pub added: Vec<String>,
+    pub replaced: Vec<String>,
+    pub removed: Vec<String>,
+    /// Dates whose existing exception differs from the update (not applied unless overwriting)
+    pub conflicts: Vec<Value>,
+}
+
+/// Merge dated exception updates into an existing exceptions object.
+///
+/// Each update is validated and normalized; `Err` lists every invalid entry.
+/// An update for a date that already has a different exception is a conflict
+/// and is only applied when `overwrite` is set.
+pub fn merge_exceptions(
+    existing: Map<String, Value>,
+    updates: &[(String, Value)],
+    remove: &[String],
+    overwrite: bool,
+) -> Result<ExceptionMerge, Vec<String>> {
+    let mut errors = Vec::new();
+    let mut normalized: BTreeMap<NaiveDate, Value> = BTreeMap::new();
+    for (date, value) in updates {
+        let parsed_date = match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            Ok(d) => d,
+            Err(_) => {
+                errors.push(format!("{}: date must be YYYY-MM-DD", date));
+                continue;
+            }
+        };
+        let closed = value.get("closed").and_then(|v| v.as_bool()) == Some(true);
+        if closed && (value.get("open").is_some() || value.get("close").is_some()) {
+            errors.push(format!("{}: \"closed\" cannot be combined with open/close times", date));
+            continue;
+        }
+        match parse_exception(value) {
+            Ok(exception) => {
+                if normalized.insert(parsed_date, exception_json(&exception)).is_some() {
+                    errors.push(format!("{}: listed more than once", date));
+                }
+            }
+            Err(e) => errors.push(format!("{}: {}", date, e)),
+        }
+    }
+    for date in remove {
+        if NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+            errors.push(format!("{}: date to remove must be YYYY-MM-DD", date));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut outcome = ExceptionMerge { merged: existing, ..Default::default() };
+    for date in remove {
+        if outcome.merged.remove(date).is_some() {
+            outcome.removed.push(date.clone());
+        }
+    }
+    for (date, value) in normalized {
+        let key = date.format("%Y-%m-%d").to_string();
+        match outcome.merged.get(&key) {
+            None => outcome.added.push(key.clone()),
+            Some(current) if *current == value => continue,
+            Some(current) if !overwrite => {
+                outcome.conflicts.push(json!({"date": key, "existing": current, "requested": value}));
+                continue;
+            }
+            Some(_) => outcome.replaced.push(key.clone()),
+        }
+        outcome.merged.insert(key, value);
+    }
+    Ok(outcome)
+}
+
+/// Regular schedule among a business's hours memories, with the dated
+/// exceptions of all of them applied; also returns the memory it came from
+pub fn business_schedule(memories: &[Value]) -> Option<(WeeklyHours, &Value)> {
+    let mut primary: Option<&Value> = None;
+    for memory in memories {
+        let schedule_type = memory.get("schedule_type").and_then(|v| v.as_str()).unwrap_or("");
+        if primary.is_none() || matches!(schedule_type, "" | "regular" | "standard") {
+            primary = Some(memory);
+        }
+    }
+    let primary = primary?;
+    let mut weekly = WeeklyHours::from_memory(primary);
+    for memory in memories {
+        for (date, exception) in parse_exceptions(memory.get("exceptions")) {
+            weekly.exceptions.entry(date).or_insert(exception);
+        }
+    }
+    Some((weekly, primary))
+}
+
+impl WeeklyHours {
+    pub fn from_memory(memory: &Value) -> Self {
+        let field = |name: String| memory.get(&name).and_then(|v| v.as_str()).unwrap_or("");
+        let mut days = [None; 7];
+        for (i, day) in DAYS.iter().enumerate() {
+            days[i] = parse_interval(field(format!("{}_open", day)), field(format!("{}_close", day)));
+        }
+        WeeklyHours { days, exceptions: parse_exceptions(memory.get("exceptions")) }
+    }
+
+    pub fn has_hours(&self) -> bool {
+        self.days.iter().any(Option::is_some) || !self.exceptions.is_empty()
+    }
+
+    /// Hours for a date, honoring exceptions
+    pub fn for_date(&self, date: NaiveDate) -> (Option<Interval>, Option<String>) {
+        match self.exceptions.get(&date) {
+            Some(exception) => (exception.hours, exception.reason.clone()),
+            None => (self.days[date.weekday().num_days_from_monday() as usize], None),
+        }
+    }
+
+    pub fn status_at(&self, now: NaiveDateTime) -> OpenStatus {
+        let today = now.date();
+
+        // An overnight interval from yesterday may still be running
+        for date in [today - Duration::days(1), today] {
+            if let (Some(interval), reason) = self.for_date(date) {
+                let (start, end) = interval.on(date);
+                if start <= now && now < end {
+                    return OpenStatus { open: true, closes_at: Some(end), next_open: None, reason };
+                }
+            }
+        }
+
+        let next_open = (0..=LOOKAHEAD_DAYS)
+            .map(|offset| today + Duration::days(offset))
+            .filter_map(|date| self.for_date(date).0.map(|interval| interval.on(date).0))
+            .find(|start| *start > now);
+        OpenStatus { open: false, closes_at: None, next_open, reason: self.for_date(today).1 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_parse_time_and_offset() {
+        assert_eq!(parse_time("9am"), NaiveTime::from_hms_opt(9, 0, 0));
+        assert_eq!(parse_time("5:30 PM"), NaiveTime::from_hms_opt(17, 30, 0));
+        assert_eq!(parse_time("1730"), NaiveTime::from_hms_opt(17, 30, 0));
+        assert_eq!(parse_time("closed"), None);
+        assert_eq!(parse_offset("UTC+5:30").unwrap().local_minus_utc(), 19800);
+        assert_eq!(parse_offset("EST").unwrap().local_minus_utc(), -18000);
+        assert!(parse_offset("America/New_York").is_none());
+    }
+
+    #[test]
+    fn test_merge_exceptions() {
+        let existing = exceptions_object(Some(&json!("{\"2025-12-25\": {\"closed\": true}}"))).unwrap();
+        let updates = vec![
+            ("2025-12-25".to_string(), json!({"open": "10am", "close": "2pm", "reason": "Short day"})),
+            ("2025-12-31".to_string(), json!("09:00-13:00")),
+        ];
+
+        let merge = merge_exceptions(existing.clone(), &updates, &[], false).unwrap();
+        assert_eq!(merge.added, vec!["2025-12-31"]);
+        assert_eq!(merge.conflicts.len(), 1);
+        assert_eq!(merge.merged["2025-12-25"], json!({"closed": true}));
+        assert_eq!(merge.merged["2025-12-31"], json!({"open": "09:00", "close": "13:00"}));
+
+        let merge = merge_exceptions(existing, &updates, &["2026-01-01".to_string()], true).unwrap();
+        assert_eq!(merge.replaced, vec!["2025-12-25"]);
+        assert_eq!(merge.merged["2025-12-25"]["open"], "10:00");
+        assert!(merge.removed.is_empty());
+
+        let invalid = vec![
+            ("25/12/2025".to_string(), json!("closed")),
+            ("2025-12-26".to_string(), json!({"open": "9"})),
+            ("2025-12-27".to_string(), json!({"closed": true, "open": "09:00", "close": "12:00"})),
+        ];
+        assert_eq!(merge_exceptions(Map::new(), &invalid, &[], false).unwrap_err().len(), 3);
+    }
+
+    #[test]
+    fn test_status_with_overnight_and_exceptions() {
+        // 2025-12-22 is a Monday
+        let hours = WeeklyHours::from_memory(&json!({
+            "monday_open": "09:00", "monday_close": "17:00",
+            "friday_open": "20:00", "friday_close": "02:00",
+            "exceptions": "{\"2025-12-29\": {\"closed\": true, \"reason\": \"Holiday\"}}"
+        }));
+
+        let status = hours.status_at(at("2025-12-22 10:00"));
+        assert!(status.open);
+        assert_eq!(status.closes_at, Some(at("2025-12-22 17:00")));
+
+        // Saturday 01:00 is still inside Friday's overnight shift
+        assert!(hours.status_at(at("2025-12-27 01:00")).open);
+
+        // Holiday Monday: closed, next opening is Friday evening
+        let status = hours.status_at(at("2025-12-29 10:00"));
+        assert!(!status.open);
+        assert_eq!(status.reason.as_deref(), Some("Holiday"));
+        assert_eq!(status.next_open, Some(at("2026-01-02 20:00")));
+    }
+}
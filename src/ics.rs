@@ -0,0 +1,456 @@
+//! iCalendar (ICS) import for business events
+//!
+//! `import_events_ics` reads the `VEVENT`s of a feed and turns each one
+//! starting within the horizon into an event memory keyed by external ID
+//! `ics_<UID>` (recurring events: `ics_<UID>_<occurrence start>`), so a
+//! re-import updates events instead of duplicating them. Recurrence rules
+//! with FREQ DAILY/WEEKLY/MONTHLY/YEARLY, INTERVAL, COUNT, UNTIL and weekly
+//! BYDAY are expanded; EXDATEs are skipped and RECURRENCE-ID overrides
+//! replace the occurrence they name. There is no time zone database, so
+//! TZID and floating times are read in the caller's UTC offset.
+//!
+//! Files are only read from `[calendar_import] directory`. Feed URLs must be
+//! http(s) and point at a host on `allowed_hosts`, or, with no allowlist, at
+//! a host whose addresses are all public; redirects are not followed.
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Months, NaiveDate, NaiveDateTime, TimeZone, Weekday};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+
+/// Occurrences generated per recurring event at most
+const MAX_OCCURRENCES: usize = 1000;
+
+/// A `VEVENT`, times as Unix seconds
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub summary: String,
+    pub description: String,
+    pub location: String,
+    pub categories: Vec<String>,
+    pub cancelled: bool,
+    pub start: i64,
+    pub end: i64,
+    pub rrule: Option<String>,
+    pub exdates: Vec<i64>,
+    pub recurrence_id: Option<i64>,
+}
+
+/// One event memory to create or update
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    pub external_id: String,
+    pub text_description: String,
+    pub data: Value,
+}
+
+fn unescape(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match (c, c == '\\') {
+            (_, true) => match chars.next() {
+                Some('n') | Some('N') => text.push('\n'),
+                Some(other) => text.push(other),
+                None => {}
+            },
+            (c, false) => text.push(c),
+        }
+    }
+    text
+}
+
+/// `NAME;PARAMS:VALUE` split into name, params and value (colons in quoted params kept)
+fn split_property(line: &str) -> Option<(String, &str, &str)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find(|(_, c)| {
+        if *c == '"' {
+            quoted = !quoted;
+        }
+        *c == ':' && !quoted
+    })?.0;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((name.to_uppercase(), params, value))
+}
+
+/// A DATE or DATE-TIME value; UTC (`Z`) times are exact, others are read in `offset`
+fn parse_time(value: &str, offset: FixedOffset) -> Option<(i64, bool)> {
+    let value = value.trim();
+    if let Some(utc) = value.strip_suffix('Z') {
+        let time = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((time.and_utc().timestamp(), false));
+    }
+    let (local, all_day) = match NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        Ok(time) => (time, false),
+        Err(_) => (NaiveDate::parse_from_str(value, "%Y%m%d").ok()?.and_hms_opt(0, 0, 0)?, true),
+    };
+    Some((offset.from_local_datetime(&local).single()?.timestamp(), all_day))
+}
+
+/// `P1W`, `P1DT2H`, `PT1H30M` in seconds
+fn parse_duration(value: &str) -> Option<i64> {
+    let (sign, rest) = match value.trim().strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value.trim().trim_start_matches('+')),
+    };
+    let mut seconds = 0;
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.strip_prefix('P')?.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => in_time = true,
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                seconds += n * match (unit, in_time) {
+                    ('W', false) => 7 * 86_400,
+                    ('D', false) => 86_400,
+                    ('H', true) => 3600,
+                    ('M', true) => 60,
+                    ('S', true) => 1,
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(sign * seconds)
+}
+
+/// The `VEVENT`s of an ICS document
+pub fn parse(ics: &str, offset: FixedOffset) -> Result<Vec<CalendarEvent>, String> {
+    // Unfold continuation lines (starting with a space or tab)
+    let mut lines: Vec<String> = Vec::new();
+    for raw in ics.lines() {
+        match raw.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+            _ => lines.push(raw.trim_end_matches('\r').to_string()),
+        }
+    }
+    if !lines.iter().any(|l| l.trim().eq_ignore_ascii_case("BEGIN:VCALENDAR")) {
+        return Err("Not an iCalendar document (no BEGIN:VCALENDAR)".to_string());
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<(CalendarEvent, bool, Option<i64>, bool)> = None;
+    let mut depth = 0;
+    for line in &lines {
+        let Some((name, params, value)) = split_property(line) else {
+            continue;
+        };
+        match (name.as_str(), value.trim().to_uppercase().as_str()) {
+            ("BEGIN", "VEVENT") => current = Some((CalendarEvent::default(), false, None, false)),
+            ("BEGIN", _) if current.is_some() => depth += 1,
+            ("END", "VEVENT") => {
+                if let Some((mut event, all_day, duration, has_end)) = current.take() {
+                    if !has_end {
+                        event.end = event.start + duration.unwrap_or(if all_day { 86_400 } else { 0 });
+                    }
+                    if !event.uid.is_empty() && event.start > 0 {
+                        events.push(event);
+                    }
+                }
+            }
+            ("END", _) if current.is_some() && depth > 0 => depth -= 1,
+            _ => {}
+        }
+        let Some((event, all_day, duration, has_end)) = current.as_mut().filter(|_| depth == 0) else {
+            continue;
+        };
+        match name.as_str() {
+            "UID" => event.uid = value.trim().to_string(),
+            "SUMMARY" => event.summary = unescape(value),
+            "DESCRIPTION" => event.description = unescape(value),
+            "LOCATION" => event.location = unescape(value),
+            "CATEGORIES" => event.categories.extend(value.split(',').map(|c| unescape(c).trim().to_string()).filter(|c| !c.is_empty())),
+            "STATUS" => event.cancelled = value.trim().eq_ignore_ascii_case("CANCELLED"),
+            "RRULE" => event.rrule = Some(value.trim().to_uppercase()),
+            "DTSTART" => {
+                let (start, date_only) = parse_time(value, offset).ok_or_else(|| format!("Invalid DTSTART '{}'", value))?;
+                event.start = start;
+                *all_day = date_only || params.to_uppercase().contains("VALUE=DATE");
+            }
+            "DTEND" => {
+                event.end = parse_time(value, offset).ok_or_else(|| format!("Invalid DTEND '{}'", value))?.0;
+                *has_end = true;
+            }
+            "DURATION" => *duration = parse_duration(value),
+            "EXDATE" => event.exdates.extend(value.split(',').filter_map(|v| parse_time(v, offset)).map(|(t, _)| t)),
+            "RECURRENCE-ID" => event.recurrence_id = parse_time(value, offset).map(|(t, _)| t),
+            _ => {}
+        }
+    }
+    Ok(events)
+}
+
+fn weekday(code: &str) -> Option<Weekday> {
+    // Ordinal prefixes ("1MO", "-1FR") are monthly forms and not expanded
+    match code.trim() {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Start times of a recurring event's occurrences up to `until`, in local time `offset`
+fn recurrences(event: &CalendarEvent, rule: &str, until: i64, offset: FixedOffset) -> Vec<i64> {
+    let part = |key: &str| rule.split(';').find_map(|p| p.strip_prefix(key)?.strip_prefix('=')).map(str::to_string);
+    let interval = part("INTERVAL").and_then(|i| i.parse::<u32>().ok()).unwrap_or(1).max(1);
+    let count = part("COUNT").and_then(|c| c.parse::<usize>().ok());
+    let rule_until = part("UNTIL").and_then(|u| parse_time(&u, offset)).map(|(t, _)| t);
+    let until = rule_until.map_or(until, |u| u.min(until));
+    let Some(start) = DateTime::from_timestamp(event.start, 0).map(|t| t.with_timezone(&offset).naive_local()) else {
+        return Vec::new();
+    };
+    let mut by_day: Vec<Weekday> = part("BYDAY").map(|d| d.split(',').filter_map(weekday).collect()).unwrap_or_default();
+    by_day.sort_by_key(|d| d.num_days_from_monday());
+
+    let to_timestamp = |local: NaiveDateTime| offset.from_local_datetime(&local).single().map(|t| t.timestamp());
+    let mut starts = Vec::new();
+    let mut generated = 0;
+    for step in 0..(MAX_OCCURRENCES as u32 * 4) {
+        let period: Vec<NaiveDateTime> = match part("FREQ").as_deref() {
+            Some("DAILY") => vec![start + Duration::days(i64::from(step * interval))],
+            Some("WEEKLY") if !by_day.is_empty() => {
+                let week = start - Duration::days(i64::from(start.weekday().num_days_from_monday())) + Duration::weeks(i64::from(step * interval));
+                by_day.iter().map(|d| week + Duration::days(i64::from(d.num_days_from_monday()))).filter(|t| *t >= start).collect()
+            }
+            Some("WEEKLY") => vec![start + Duration::weeks(i64::from(step * interval))],
+            Some("MONTHLY") => start.checked_add_months(Months::new(step * interval)).filter(|t| t.day() == start.day()).into_iter().collect(),
+            Some("YEARLY") => start.checked_add_months(Months::new(12 * step * interval)).filter(|t| t.day() == start.day()).into_iter().collect(),
+            _ => return vec![event.start],
+        };
+        for local in period {
+            let Some(timestamp) = to_timestamp(local) else {
+                continue;
+            };
+            if timestamp > until || count.is_some_and(|c| generated >= c) || starts.len() >= MAX_OCCURRENCES {
+                return starts;
+            }
+            generated += 1;
+            starts.push(timestamp);
+        }
+    }
+    starts
+}
+
+fn occurrence(event: &CalendarEvent, external_id: String, start: i64, offset: FixedOffset) -> Occurrence {
+    let end = start + (event.end - event.start).max(0);
+    let local = |t: i64| DateTime::from_timestamp(t, 0).map(|t| t.with_timezone(&offset).format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default();
+    let mut text_description = format!("Event: {}. When: {} to {}.", event.summary, local(start), local(end));
+    if !event.location.is_empty() {
+        text_description.push_str(&format!(" Location: {}.", event.location));
+    }
+    if !event.description.is_empty() {
+        text_description.push_str(&format!(" {}", event.description));
+    }
+    if event.cancelled {
+        text_description.push_str(" (Cancelled)");
+    }
+    Occurrence {
+        external_id,
+        text_description,
+        data: json!({
+            "event_name": if event.summary.is_empty() { "Untitled event" } else { event.summary.as_str() },
+            "event_type": event.categories.first().map(|c| c.to_lowercase()).unwrap_or_default(),
+            "start_date": start,
+            "end_date": end,
+            "description": event.description,
+            "location": event.location,
+            "status": if event.cancelled { "cancelled" } else { "scheduled" },
+            "tags": event.categories
+        }),
+    }
+}
+
+/// Event memories for the occurrences that end after `from` and start by `until`, by start
+pub fn occurrences(events: &[CalendarEvent], from: i64, until: i64, offset: FixedOffset) -> Vec<Occurrence> {
+    let overridden: HashSet<(&str, i64)> = events.iter().filter_map(|e| Some((e.uid.as_str(), e.recurrence_id?))).collect();
+    let in_window = |start: i64, event: &CalendarEvent| start <= until && start + (event.end - event.start).max(0) >= from;
+    let mut found = Vec::new();
+    for event in events {
+        match (&event.rrule, event.recurrence_id) {
+            // An override keeps the ID of the occurrence it replaces
+            (_, Some(original)) => {
+                if in_window(event.start, event) {
+                    found.push(occurrence(event, format!("ics_{}_{}", event.uid, original), event.start, offset));
+                }
+            }
+            (Some(rule), None) => {
+                for start in recurrences(event, rule, until, offset) {
+                    if in_window(start, event) && !event.exdates.contains(&start) && !overridden.contains(&(event.uid.as_str(), start)) {
+                        found.push(occurrence(event, format!("ics_{}_{}", event.uid, start), start, offset));
+                    }
+                }
+            }
+            (None, None) => {
+                if in_window(event.start, event) {
+                    found.push(occurrence(event, format!("ics_{}", event.uid), event.start, offset));
+                }
+            }
+        }
+    }
+    found.sort_by_key(|o| o.data["start_date"].as_i64());
+    found
+}
+
+/// Path of a calendar file in the import directory; `file_name` may not leave it
+pub fn path_for(directory: &Path, file_name: &str) -> Result<PathBuf, String> {
+    if file_name.is_empty() || file_name.contains(['/', '\\']) || file_name.contains("..") {
+        return Err(format!("Invalid path: {} (use a file name; calendars are read from the import directory)", file_name));
+    }
+    Ok(directory.join(file_name))
+}
+
+/// Whether an address is reachable on the public internet (not loopback,
+/// private, link-local, CGNAT, documentation or unspecified)
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public_ip(IpAddr::V4(v4)),
+            None => {
+                let first = v6.segments()[0];
+                !(v6.is_loopback() || v6.is_unspecified() || (first & 0xfe00) == 0xfc00 || (first & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
+
+/// Check a feed URL before resolving it: http(s) (`webcal://` read as
+/// https) and, when `allowed_hosts` is set, a host on it
+pub fn check_feed_url(url: &str, allowed_hosts: &[String]) -> Result<reqwest::Url, String> {
+    let url = url.trim();
+    let url = url.strip_prefix("webcal://").map_or_else(|| url.to_string(), |rest| format!("https://{}", rest));
+    let parsed = reqwest::Url::parse(&url).map_err(|e| format!("Invalid feed URL {}: {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("Feed URL must use http or https, not {}", parsed.scheme()));
+    }
+    let host = parsed.host_str().ok_or_else(|| format!("Feed URL {} has no host", url))?.to_lowercase();
+    if !allowed_hosts.is_empty() && !allowed_hosts.iter().any(|allowed| allowed.eq_ignore_ascii_case(&host)) {
+        return Err(format!("Feed host {} is not in [calendar_import] allowed_hosts", host));
+    }
+    Ok(parsed)
+}
+
+/// Resolve the feed's host and refuse non-public addresses unless the host
+/// is allowlisted. Returns the address to connect to.
+pub fn check_feed_addresses(host: &str, addresses: &[SocketAddr], allowlisted: bool) -> Result<SocketAddr, String> {
+    let first = *addresses.first().ok_or_else(|| format!("Feed host {} did not resolve", host))?;
+    if !allowlisted && addresses.iter().any(|a| !is_public_ip(a.ip())) {
+        return Err(format!("Feed host {} resolves to a private or local address", host));
+    }
+    Ok(first)
+}
+
+/// Fetch a calendar feed after `check_feed_url`; the connection is pinned to
+/// the checked address and redirects are refused
+pub async fn fetch_feed(url: &reqwest::Url, allowed_hosts: &[String]) -> Result<String, String> {
+    let host = url.host_str().unwrap_or_default().to_string();
+    let port = url.port_or_known_default().unwrap_or(443);
+    let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), port))
+        .await
+        .map_err(|e| format!("Failed to resolve {}: {}", host, e))?
+        .collect();
+    let address = check_feed_addresses(&host, &addresses, !allowed_hosts.is_empty())?;
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .resolve(&host, address)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client.get(url.clone()).send().await.map_err(|e| format!("Failed to fetch calendar feed: {}", e))?;
+    if response.status().is_redirection() {
+        return Err(format!("Calendar feed redirected ({}); use the final URL", response.status()));
+    }
+    let response = response.error_for_status().map_err(|e| format!("Failed to fetch calendar feed: {}", e))?;
+    response.text().await.map_err(|e| format!("Failed to read calendar feed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_confined_to_import_directory() {
+        let directory = Path::new("imports");
+        assert_eq!(path_for(directory, "cafe.ics").unwrap(), directory.join("cafe.ics"));
+        assert!(path_for(directory, "/etc/passwd").is_err());
+        assert!(path_for(directory, "../mcpconfig.toml").is_err());
+        assert!(path_for(directory, "sub\\cal.ics").is_err());
+        assert!(path_for(directory, "").is_err());
+    }
+
+    #[test]
+    fn test_feed_url_checks() {
+        assert_eq!(check_feed_url("webcal://cal.example.com/feed.ics", &[]).unwrap().as_str(), "https://cal.example.com/feed.ics");
+        assert!(check_feed_url("file:///etc/passwd", &[]).is_err());
+        assert!(check_feed_url("ftp://cal.example.com/feed.ics", &[]).is_err());
+        assert!(check_feed_url("not a url", &[]).is_err());
+
+        let allowed = vec!["calendar.google.com".to_string()];
+        assert!(check_feed_url("https://calendar.google.com/ical/x.ics", &allowed).is_ok());
+        assert!(check_feed_url("https://evil.example.com/x.ics", &allowed).is_err());
+
+        for ip in ["127.0.0.1", "10.0.0.5", "192.168.1.1", "172.16.0.1", "169.254.169.254", "100.64.0.1", "0.0.0.0", "::1", "fd00::1", "fe80::1", "::ffff:127.0.0.1"] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{} should be private", ip);
+        }
+        assert!(is_public_ip("93.184.216.34".parse().unwrap()));
+        assert!(is_public_ip("2606:4700::1111".parse().unwrap()));
+
+        let metadata: SocketAddr = "169.254.169.254:80".parse().unwrap();
+        let public: SocketAddr = "93.184.216.34:443".parse().unwrap();
+        assert!(check_feed_addresses("metadata.internal", &[metadata], false).is_err());
+        assert!(check_feed_addresses("mixed.example.com", &[public, metadata], false).is_err());
+        assert_eq!(check_feed_addresses("cal.example.com", &[public], false), Ok(public));
+        assert_eq!(check_feed_addresses("intranet", &[metadata], true), Ok(metadata));
+        assert!(check_feed_addresses("nowhere", &[], false).is_err());
+    }
+
+    #[test]
+    fn test_parse_and_expand() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VEVENT\r\nUID:yoga@cafe\r\nSUMMARY:Morning yoga\\, beginners\r\nDTSTART:20260302T080000Z\r\nDURATION:PT1H\r\n\
+            RRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=5\r\nEXDATE:20260304T080000Z\r\nCATEGORIES:Class,Wellness\r\n\
+            BEGIN:VALARM\r\nDESCRIPTION:Reminder\r\nEND:VALARM\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:yoga@cafe\r\nRECURRENCE-ID:20260309T080000Z\r\nSUMMARY:Morning yoga (moved)\r\nDTSTART:20260309T100000Z\r\nDTEND:20260309T110000Z\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:launch@cafe\r\nSUMMARY:Menu\r\n  launch\r\nLOCATION:Main St\r\nDTSTART;VALUE=DATE:20260320\r\nSTATUS:CANCELLED\r\nEND:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let offset = FixedOffset::east_opt(3600).unwrap();
+        let events = parse(ics, offset).unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].summary, "Morning yoga, beginners");
+        assert_eq!(events[0].description, "");
+        assert_eq!(events[2].summary, "Menu launch");
+        assert_eq!(events[2].end - events[2].start, 86_400);
+
+        // 2026-03-01 .. 2026-04-01: Mon 2, (Wed 4 excluded), Mon 9 moved, Wed 11, Mon 16, launch
+        let found = occurrences(&events, 1_772_323_200, 1_775_001_600, offset);
+        let ids: Vec<&str> = found.iter().map(|o| o.external_id.as_str()).collect();
+        assert_eq!(ids, vec!["ics_yoga@cafe_1772438400", "ics_yoga@cafe_1773043200", "ics_yoga@cafe_1773216000", "ics_yoga@cafe_1773648000", "ics_launch@cafe"]);
+        assert_eq!(found[1].data["event_name"], "Morning yoga (moved)");
+        assert_eq!(found[2].data["event_type"], "class");
+        assert_eq!(found[4].data["status"], "cancelled");
+        assert!(found[4].text_description.contains("Location: Main St."));
+
+        assert!(parse("BEGIN:VEVENT\nEND:VEVENT", offset).is_err());
+        assert_eq!(parse_duration("P1DT2H30M"), Some(95_400));
+    }
+}
@@ -0,0 +1,131 @@
+//! Idempotency keys for create tools
+//!
+//! MCP clients retry calls whose response got lost, which used to create the
+//! same memory or interaction twice. A create tool called with an
+//! `idempotency_key` runs once; repeats of the key (with the same arguments)
+//! within the TTL get the first call's result back. Failed calls don't
+//! claim the key, so they can be retried with it.
+
+use rmcp::model::Tool;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+enum State {
+    Running,
+    Done(Value),
+}
+
+struct Entry {
+    fingerprint: String,
+    state: State,
+    at: Instant,
+}
+
+/// What to do with a call carrying an idempotency key
+#[derive(Debug, PartialEq)]
+pub enum Claim {
+    /// First use: run the tool, then `complete` or `release`
+    New,
+    /// Already done: the original result
+    Replay(Value),
+    /// The first call with this key hasn't finished yet
+    InFlight,
+    /// The key was used with different arguments
+    Mismatch,
+}
+
+pub struct IdempotencyStore {
+    entries: Mutex<HashMap<(String, String), Entry>>,
+    ttl: Duration,
+    max_keys: usize,
+}
+
+/// The arguments a key is bound to, without the key itself
+fn fingerprint(arguments: &Map<String, Value>) -> String {
+    let mut arguments = arguments.clone();
+    arguments.remove("idempotency_key");
+    Value::Object(arguments).to_string()
+}
+
+impl IdempotencyStore {
+    pub fn new(ttl: Duration, max_keys: usize) -> Self {
+        IdempotencyStore { entries: Mutex::new(HashMap::new()), ttl, max_keys }
+    }
+
+    pub fn claim(&self, tool: &str, key: &str, arguments: &Map<String, Value>) -> Claim {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, e| e.at.elapsed() < self.ttl);
+        let fingerprint = fingerprint(arguments);
+        let id = (tool.to_string(), key.to_string());
+        if let Some(entry) = entries.get(&id) {
+            return match &entry.state {
+                _ if entry.fingerprint != fingerprint => Claim::Mismatch,
+                State::Running => Claim::InFlight,
+                State::Done(result) => Claim::Replay(result.clone()),
+            };
+        }
+        if entries.len() >= self.max_keys {
+            // Oldest keys go first
+            if let Some(oldest) = entries.iter().min_by_key(|(_, e)| e.at).map(|(id, _)| id.clone()) {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(id, Entry { fingerprint, state: State::Running, at: Instant::now() });
+        Claim::New
+    }
+
+    /// Remember the successful result for replays
+    pub fn complete(&self, tool: &str, key: &str, result: Value) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(&(tool.to_string(), key.to_string())) {
+            entry.state = State::Done(result);
+            entry.at = Instant::now();
+        }
+    }
+
+    /// Forget a key whose call failed, so it can be retried
+    pub fn release(&self, tool: &str, key: &str) {
+        self.entries.lock().unwrap().remove(&(tool.to_string(), key.to_string()));
+    }
+}
+
+/// Add the `idempotency_key` argument to a create tool's input schema
+pub fn advertise(tool: &mut Tool) {
+    let mut schema = (*tool.input_schema).clone();
+    if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+        properties.insert(
+            "idempotency_key".to_string(),
+            json!({
+                "type": "string",
+                "description": "Client-chosen unique key; repeating a call with the same key returns the original result instead of creating a duplicate"
+            }),
+        );
+        tool.input_schema = Arc::new(schema);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claim_replay_and_release() {
+        let store = IdempotencyStore::new(Duration::from_secs(60), 10);
+        let args = json!({"customer_id": "C1", "idempotency_key": "k1"});
+        let args = args.as_object().unwrap();
+
+        assert_eq!(store.claim("create_customer_memory", "k1", args), Claim::New);
+        assert_eq!(store.claim("create_customer_memory", "k1", args), Claim::InFlight);
+        store.complete("create_customer_memory", "k1", json!({"memory_id": "M1"}));
+        assert_eq!(store.claim("create_customer_memory", "k1", args), Claim::Replay(json!({"memory_id": "M1"})));
+
+        let other = json!({"customer_id": "C2"});
+        assert_eq!(store.claim("create_customer_memory", "k1", other.as_object().unwrap()), Claim::Mismatch);
+        // Keys are per tool
+        assert_eq!(store.claim("create_business_memory", "k1", args), Claim::New);
+
+        store.release("create_business_memory", "k1");
+        assert_eq!(store.claim("create_business_memory", "k1", args), Claim::New);
+    }
+}
@@ -0,0 +1,89 @@
+//! Importance and pinning in ranking
+//!
+//! Customer memories carry an `importance` (0-5) and a `pinned` flag so a
+//! critical fact ("allergic to nuts") isn't drowned out by a closer but
+//! trivial behavior. Pinned memories always come first, in search results
+//! and listings alike. Search results get `IMPORTANCE_WEIGHT` per importance
+//! level added to their similarity score and are re-sorted by it behind the
+//! pinned ones; listings without an explicit sort put the most important
+//! next, otherwise keeping their order. `summarize_feedback` picks the
+//! negative feedback it shows the same way.
+
+use serde_json::{json, Value};
+
+/// Memory types with importance and pinned fields
+pub const MEMORY_TYPES: &[&str] = &["behavior", "preference", "desire", "rule", "feedback"];
+
+pub const IMPORTANCE_WEIGHT: f64 = 0.04;
+
+fn importance(item: &Value) -> i64 {
+    item.get("importance").and_then(|v| v.as_i64()).unwrap_or(0).clamp(0, 5)
+}
+
+fn pinned(item: &Value) -> bool {
+    item.get("pinned").and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+/// Score added to a memory's similarity
+pub fn boost(item: &Value) -> f64 {
+    importance(item) as f64 * IMPORTANCE_WEIGHT
+}
+
+/// Sort key putting pinned memories first, then the most important
+pub fn priority(item: &Value) -> (bool, i64) {
+    (!pinned(item), -importance(item))
+}
+
+/// Pinned first, then by importance; stable otherwise
+pub fn prioritize(items: &mut [Value]) {
+    items.sort_by_key(priority);
+}
+
+/// Search results of one memory type, pinned first, then by boosted score.
+/// Boosted results keep their similarity as `vector_score`; unscored results are prioritized.
+pub fn rank(mut results: Vec<Value>) -> Vec<Value> {
+    if results.iter().any(|r| r.get("score").and_then(|s| s.as_f64()).is_none()) {
+        prioritize(&mut results);
+        return results;
+    }
+    for result in results.iter_mut() {
+        let boost = boost(result);
+        if boost > 0.0 {
+            let score = result["score"].as_f64().unwrap_or(0.0);
+            result["vector_score"] = json!(score);
+            result["score"] = json!(score + boost);
+            result["importance_boost"] = json!(boost);
+        }
+    }
+    let score = |r: &Value| r["score"].as_f64().unwrap_or(f64::MIN);
+    results.sort_by(|a, b| pinned(b).cmp(&pinned(a)).then(score(b).total_cmp(&score(a))));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_and_prioritize() {
+        let results = vec![
+            json!({"id": "b1", "score": 0.82}),
+            json!({"id": "b2", "score": 0.80, "importance": 1}),
+            json!({"id": "allergy", "score": 0.71, "importance": 5}),
+            json!({"id": "pin", "score": 0.40, "pinned": true}),
+        ];
+        let ranked = rank(results);
+        let ids: Vec<&str> = ranked.iter().map(|r| r["id"].as_str().unwrap()).collect();
+        // Pinned ranks first however low its similarity
+        assert_eq!(ids, ["pin", "allergy", "b2", "b1"]);
+        assert_eq!(ranked[1]["vector_score"], 0.71);
+        assert!(ranked[0].get("vector_score").is_none());
+        assert!(ranked[3].get("vector_score").is_none());
+
+        let mut listed = vec![json!({"id": "a"}), json!({"id": "b", "importance": 3}), json!({"id": "c", "pinned": true}), json!({"id": "d"})];
+        prioritize(&mut listed);
+        let ids: Vec<&str> = listed.iter().map(|r| r["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, ["c", "b", "a", "d"]);
+        assert_eq!(rank(vec![json!({"id": "x"}), json!({"id": "y", "pinned": true})])[0]["id"], "y");
+    }
+}
@@ -0,0 +1,262 @@
+//! Importers for third-party agent memory exports
+//!
+//! Maps mem0, Zep and LangChain export formats onto records the memory
+//! layer understands:
+//! - Facts/memories become customer memories (behavior, preference, desire, rule, feedback)
+//! - Chat histories become conversation transcripts stored as communication memories
+
+use serde_json::Value;
+
+/// Supported import sources
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportSource {
+    Mem0,
+    Zep,
+    LangChain,
+}
+
+impl ImportSource {
+    pub fn parse(source: &str) -> Option<Self> {
+        match source.to_lowercase().as_str() {
+            "mem0" => Some(Self::Mem0),
+            "zep" => Some(Self::Zep),
+            "langchain" => Some(Self::LangChain),
+            _ => None,
+        }
+    }
+}
+
+/// A record mapped from a foreign export
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportedRecord {
+    /// A single fact stored as a customer memory
+    Memory {
+        memory_type: String,
+        text: String,
+        external_id: Option<String>,
+        created_at: Option<i64>,
+    },
+    /// A chat history stored as conversation chunks
+    Conversation {
+        session_id: Option<String>,
+        transcript: String,
+    },
+}
+
+/// Parse an export document into memory-layer records
+pub fn parse_export(source: ImportSource, export: &Value, default_memory_type: &str) -> Vec<ImportedRecord> {
+    match source {
+        ImportSource::Mem0 => parse_mem0(export, default_memory_type),
+        ImportSource::Zep => parse_zep(export, default_memory_type),
+        ImportSource::LangChain => parse_langchain(export, default_memory_type),
+    }
+}
+
+/// mem0: `[{"id", "memory", "metadata", "categories", ...}]`, optionally
+/// wrapped in `{"results": [...]}` or `{"memories": [...]}`
+fn parse_mem0(export: &Value, default_memory_type: &str) -> Vec<ImportedRecord> {
+    list_under(export, &["results", "memories"])
+        .iter()
+        .filter_map(|item| {
+            let text = item.get("memory").or_else(|| item.get("text"))?.as_str()?.trim();
+            if text.is_empty() {
+                return None;
+            }
+            Some(ImportedRecord::Memory {
+                memory_type: infer_memory_type(item, default_memory_type),
+                text: text.to_string(),
+                external_id: item.get("id").and_then(|v| v.as_str()).map(str::to_string),
+                created_at: item.get("created_at").and_then(parse_timestamp),
+            })
+        })
+        .collect()
+}
+
+/// Zep: sessions `{"session_id", "messages": [{"role_type"|"role", "content"}]}`
+/// and facts `{"facts": [{"fact"|"content", "uuid"}]}`, single or as arrays
+fn parse_zep(export: &Value, default_memory_type: &str) -> Vec<ImportedRecord> {
+    let mut records = Vec::new();
+
+    let sessions = match export {
+        Value::Array(items) => items.clone(),
+        _ => list_under(export, &["sessions"]).into_iter()
+            .chain(std::iter::once(export.clone()))
+            .collect(),
+    };
+
+    for session in &sessions {
+        if let Some(messages) = session.get("messages").and_then(|v| v.as_array()) {
+            let transcript = messages
+                .iter()
+                .filter_map(|m| {
+                    let role = m.get("role_type").or_else(|| m.get("role"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("unknown");
+                    let content = m.get("content")?.as_str()?.trim();
+                    (!content.is_empty()).then(|| format!("{}: {}", speaker_label(role), content))
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            if !transcript.is_empty() {
+                records.push(ImportedRecord::Conversation {
+                    session_id: session.get("session_id").and_then(|v| v.as_str()).map(str::to_string),
+                    transcript,
+                });
+            }
+        }
+
+        if let Some(facts) = session.get("facts").and_then(|v| v.as_array()) {
+            for fact in facts {
+                let text = fact.get("fact").or_else(|| fact.get("content")).and_then(|v| v.as_str());
+                let Some(text) = text.map(str::trim).filter(|t| !t.is_empty()) else {
+                    continue;
+                };
+                records.push(ImportedRecord::Memory {
+                    memory_type: infer_memory_type(fact, default_memory_type),
+                    text: text.to_string(),
+                    external_id: fact.get("uuid").and_then(|v| v.as_str()).map(str::to_string),
+                    created_at: fact.get("created_at").and_then(parse_timestamp),
+                });
+            }
+        }
+    }
+
+    records
+}
+
+/// LangChain: `messages_to_dict` output `[{"type": "human", "data": {"content"}}]`
+/// (optionally under `{"messages": [...]}`) and documents `[{"page_content", "metadata"}]`
+fn parse_langchain(export: &Value, default_memory_type: &str) -> Vec<ImportedRecord> {
+    let items = list_under(export, &["messages", "documents"]);
+    let mut records = Vec::new();
+    let mut turns = Vec::new();
+
+    for item in &items {
+        if let Some(content) = item.get("page_content").and_then(|v| v.as_str()) {
+            if content.trim().is_empty() {
+                continue;
+            }
+            let metadata = item.get("metadata").cloned().unwrap_or(Value::Null);
+            records.push(ImportedRecord::Memory {
+                memory_type: infer_memory_type(&metadata, default_memory_type),
+                text: content.trim().to_string(),
+                external_id: metadata.get("id").and_then(|v| v.as_str()).map(str::to_string),
+                created_at: None,
+            });
+            continue;
+        }
+
+        let role = item.get("type").and_then(|v| v.as_str()).unwrap_or("unknown");
+        let content = item.get("data")
+            .and_then(|d| d.get("content"))
+            .or_else(|| item.get("content"))
+            .and_then(|v| v.as_str());
+        if let Some(content) = content.map(str::trim).filter(|c| !c.is_empty()) {
+            turns.push(format!("{}: {}", speaker_label(role), content));
+        }
+    }
+
+    if !turns.is_empty() {
+        records.push(ImportedRecord::Conversation {
+            session_id: export.get("session_id").and_then(|v| v.as_str()).map(str::to_string),
+            transcript: turns.join("\n"),
+        });
+    }
+
+    records
+}
+
+/// Return the array at the top level or under the first matching key
+fn list_under(export: &Value, keys: &[&str]) -> Vec<Value> {
+    if let Some(items) = export.as_array() {
+        return items.clone();
+    }
+    keys.iter()
+        .find_map(|key| export.get(*key).and_then(|v| v.as_array()))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Map a foreign record onto a customer memory type using its metadata/categories
+fn infer_memory_type(item: &Value, default_memory_type: &str) -> String {
+    let explicit = item.get("memory_type")
+        .or_else(|| item.get("metadata").and_then(|m| m.get("memory_type")))
+        .and_then(|v| v.as_str());
+    if let Some(memory_type) = explicit {
+        return memory_type.to_string();
+    }
+
+    let categories: Vec<String> = item.get("categories")
+        .and_then(|v| v.as_array())
+        .map(|c| c.iter().filter_map(|v| v.as_str()).map(str::to_lowercase).collect())
+        .unwrap_or_default();
+
+    for category in &categories {
+        let mapped = match category.as_str() {
+            "preference" | "preferences" | "likes" | "dislikes" | "food" => "preference",
+            "goal" | "goals" | "wishlist" | "plans" => "desire",
+            "rule" | "rules" | "constraint" | "constraints" => "rule",
+            "feedback" | "review" | "complaint" => "feedback",
+            "behavior" | "habit" | "habits" | "activity" => "behavior",
+            _ => continue,
+        };
+        return mapped.to_string();
+    }
+
+    default_memory_type.to_string()
+}
+
+fn speaker_label(role: &str) -> &'static str {
+    match role.to_lowercase().as_str() {
+        "human" | "user" => "Customer",
+        "ai" | "assistant" => "Agent",
+        "system" => "System",
+        _ => "Participant",
+    }
+}
+
+/// Accept unix seconds or RFC 3339 strings
+fn parse_timestamp(value: &Value) -> Option<i64> {
+    value.as_i64().or_else(|| {
+        value.as_str()
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.timestamp())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_mem0() {
+        let export = json!({"results": [
+            {"id": "m1", "memory": "Likes dark roast coffee", "categories": ["food"]},
+            {"id": "m2", "memory": "Wants to buy a standing desk", "categories": ["goals"]},
+            {"id": "m3", "memory": ""}
+        ]});
+        let records = parse_export(ImportSource::Mem0, &export, "behavior");
+        assert_eq!(records.len(), 2);
+        match &records[1] {
+            ImportedRecord::Memory { memory_type, external_id, .. } => {
+                assert_eq!(memory_type, "desire");
+                assert_eq!(external_id.as_deref(), Some("m2"));
+            }
+            other => panic!("unexpected record: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_langchain_messages() {
+        let export = json!([
+            {"type": "human", "data": {"content": "Do you ship to Canada?"}},
+            {"type": "ai", "data": {"content": "Yes, within 5 days."}}
+        ]);
+        let records = parse_export(ImportSource::LangChain, &export, "preference");
+        assert_eq!(records, vec![ImportedRecord::Conversation {
+            session_id: None,
+            transcript: "Customer: Do you ship to Canada?\nAgent: Yes, within 5 days.".to_string(),
+        }]);
+    }
+}
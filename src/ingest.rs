@@ -0,0 +1,142 @@
+//! Event ingestion for producers that aren't MCP clients
+//!
+//! In HTTP mode, `POST /ingest` accepts one JSON event or an array of them,
+//! signed as configured in `[ingest]`. Events are checked and answered with
+//! 202 right away, then stored in the background through the create tools:
+//! - `purchase`: product interaction `purchased` or service interaction `completed`
+//! - `review`: product or service interaction `reviewed`
+//! - `page_view`: product interaction `viewed`, else a `browsing` behavior
+//!
+//! Events carrying an `event_id` are stored once; redeliveries are skipped.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Event {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<String>,
+    pub customer_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+}
+
+/// The memory an event is stored as
+#[derive(Debug, Clone, PartialEq)]
+pub enum Record {
+    ProductInteraction { product_id: String, interaction_type: &'static str },
+    ServiceInteraction { service_id: String, interaction_type: &'static str },
+    Behavior { behavior_type: &'static str, action: String },
+}
+
+impl Event {
+    /// The memory this event becomes, or why it can't be stored
+    pub fn record(&self) -> Result<Record, String> {
+        if self.customer_id.trim().is_empty() {
+            return Err("customer_id is required".to_string());
+        }
+        if self.rating.is_some_and(|r| !(1..=5).contains(&r)) {
+            return Err("rating must be between 1 and 5".to_string());
+        }
+        let product = self.product_id.clone().filter(|id| !id.is_empty());
+        let service = self.service_id.clone().filter(|id| !id.is_empty());
+        let interaction = |product_type: &'static str, service_type: &'static str| match (&product, &service) {
+            (Some(product_id), _) => Ok(Record::ProductInteraction { product_id: product_id.clone(), interaction_type: product_type }),
+            (None, Some(service_id)) => Ok(Record::ServiceInteraction { service_id: service_id.clone(), interaction_type: service_type }),
+            (None, None) => Err(format!("{} events need a product_id or service_id", self.event_type)),
+        };
+        match self.event_type.to_lowercase().as_str() {
+            "purchase" => interaction("purchased", "completed"),
+            "review" => interaction("reviewed", "reviewed"),
+            "page_view" => Ok(match product.clone() {
+                Some(product_id) => Record::ProductInteraction { product_id, interaction_type: "viewed" },
+                None => Record::Behavior {
+                    behavior_type: "browsing",
+                    action: format!("Viewed {}", self.url.as_deref().filter(|u| !u.is_empty()).unwrap_or("a page")),
+                },
+            }),
+            other => Err(format!("Unsupported event type '{}' (purchase, page_view, review)", other)),
+        }
+    }
+
+    /// The event's own text, else a sentence describing it (used for embeddings)
+    pub fn summary(&self) -> String {
+        if let Some(text) = self.text.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+            return text.to_string();
+        }
+        let subject = match (&self.product_id, &self.service_id) {
+            (Some(id), _) => format!("product {}", id),
+            (None, Some(id)) => format!("service {}", id),
+            (None, None) => self.url.clone().unwrap_or_else(|| "a page".to_string()),
+        };
+        let mut summary = match self.event_type.to_lowercase().as_str() {
+            "purchase" => format!("Purchased {}", subject),
+            "review" => format!("Reviewed {}", subject),
+            _ => format!("Viewed {}", subject),
+        };
+        if let Some(amount) = self.amount {
+            summary.push_str(format!(" for {:.2} {}", amount, self.currency.as_deref().unwrap_or("")).trim_end());
+        }
+        if let Some(rating) = self.rating {
+            summary.push_str(&format!(", rated {}/5", rating));
+        }
+        if let Some(channel) = self.channel.as_deref().filter(|c| !c.is_empty()) {
+            summary.push_str(&format!(" via {}", channel));
+        }
+        summary
+    }
+}
+
+/// Events in a request body: one object or an array of them
+pub fn parse(body: &[u8]) -> Result<Vec<Event>, String> {
+    let value: Value = serde_json::from_slice(body).map_err(|e| format!("invalid JSON: {}", e))?;
+    match value {
+        Value::Array(items) => items
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| serde_json::from_value(item).map_err(|e| format!("event {}: {}", i, e)))
+            .collect(),
+        item => Ok(vec![serde_json::from_value(item).map_err(|e| format!("invalid event: {}", e))?]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_map_events() {
+        let body = br#"[
+            {"type": "purchase", "event_id": "e1", "customer_id": "C1", "product_id": "P1", "amount": 12.5, "currency": "EUR", "channel": "web"},
+            {"type": "page_view", "customer_id": "C1", "url": "/menu"},
+            {"type": "review", "customer_id": "C1", "service_id": "S1", "rating": 5, "text": "Great haircut"}
+        ]"#;
+        let events = parse(body).unwrap();
+        assert_eq!(events[0].record(), Ok(Record::ProductInteraction { product_id: "P1".to_string(), interaction_type: "purchased" }));
+        assert_eq!(events[0].summary(), "Purchased product P1 for 12.50 EUR via web");
+        assert_eq!(events[1].record(), Ok(Record::Behavior { behavior_type: "browsing", action: "Viewed /menu".to_string() }));
+        assert_eq!(events[2].record(), Ok(Record::ServiceInteraction { service_id: "S1".to_string(), interaction_type: "reviewed" }));
+        assert_eq!(events[2].summary(), "Great haircut");
+
+        let bad = parse(br#"{"type": "review", "customer_id": "C1", "product_id": "P1", "rating": 9}"#).unwrap();
+        assert!(bad[0].record().is_err());
+        assert!(parse(br#"{"type": "refund", "customer_id": "C1"}"#).unwrap()[0].record().is_err());
+        assert!(parse(br#"{"customer_id": "C1"}"#).is_err());
+    }
+}
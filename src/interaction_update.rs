@@ -0,0 +1,127 @@
+//! Field updates of customer interactions
+//!
+//! `update_interaction` can change an interaction's text (which is
+//! re-embedded) and its rating, amount and status fields (which are not).
+//! Product and service interactions name these fields differently; the
+//! field update query rewrites all three, so unchanged ones are carried
+//! over from the stored node.
+
+use serde_json::{json, Value};
+
+/// Queries and field names of one interaction type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Target {
+    pub lookup_query: &'static str,
+    /// Updates the text and re-embeds it
+    pub text_query: &'static str,
+    pub fields_query: &'static str,
+    pub text_field: &'static str,
+    /// Rating, amount and status fields
+    pub fields: [&'static str; 3],
+}
+
+pub fn target(interaction_type: &str) -> Option<Target> {
+    match interaction_type {
+        "product" => Some(Target {
+            lookup_query: "get_customer_product_interaction_node",
+            text_query: "update_customer_product_interaction_memory",
+            fields_query: "update_customer_product_interaction_fields",
+            text_field: "text_reason",
+            fields: ["rating", "purchase_amount", "resolution_status"],
+        }),
+        "service" => Some(Target {
+            lookup_query: "get_customer_service_interaction_node",
+            text_query: "update_customer_service_interaction_memory",
+            fields_query: "update_customer_service_interaction_fields",
+            text_field: "text_feedback",
+            fields: ["satisfaction_rating", "cost_actual", "outcome"],
+        }),
+        _ => None,
+    }
+}
+
+/// Check the requested changes and return the new status, if any:
+/// resolution_status applies to products and outcome to services
+pub fn validate(
+    interaction_type: &str,
+    has_text: bool,
+    rating: Option<i32>,
+    purchase_amount: Option<f64>,
+    resolution_status: Option<&str>,
+    outcome: Option<&str>,
+) -> Result<Option<String>, (String, Value)> {
+    let status = match (interaction_type, resolution_status, outcome) {
+        ("service", Some(_), _) => return Err(("resolution_status only applies to product interactions".to_string(), json!({}))),
+        ("product", _, Some(_)) => return Err(("outcome only applies to service interactions".to_string(), json!({}))),
+        (_, resolution_status, outcome) => resolution_status.or(outcome).map(str::to_string),
+    };
+    if purchase_amount.is_some_and(|amount| amount < 0.0) {
+        return Err(("purchase_amount cannot be negative".to_string(), json!({"purchase_amount": purchase_amount})));
+    }
+    if !has_text && rating.is_none() && purchase_amount.is_none() && status.is_none() {
+        return Err(("Nothing to update".to_string(), json!({
+            "suggestion": "Pass composite_text, rating, purchase_amount, resolution_status or outcome"
+        })));
+    }
+    Ok(status)
+}
+
+/// Field values for the field update query - the stored ones with the
+/// requested changes applied - and the fields that actually change
+pub fn merge_fields(target: &Target, current: &Value, rating: Option<i32>, purchase_amount: Option<f64>, status: Option<String>) -> (Value, Vec<&'static str>) {
+    let [rating_field, amount_field, status_field] = target.fields;
+    let mut fields = json!({
+        rating_field: current.get(rating_field).and_then(|v| v.as_i64()).unwrap_or(0),
+        amount_field: current.get(amount_field).and_then(|v| v.as_f64()).unwrap_or(0.0),
+        status_field: current.get(status_field).and_then(|v| v.as_str()).unwrap_or("")
+    });
+    let mut changed = Vec::new();
+    for (field, value) in [(rating_field, rating.map(|r| json!(r))), (amount_field, purchase_amount.map(|a| json!(a))), (status_field, status.map(|s| json!(s)))] {
+        if let Some(value) = value.filter(|value| fields[field] != *value) {
+            fields[field] = value;
+            changed.push(field);
+        }
+    }
+    (fields, changed)
+}
+
+/// The new text, unless it equals the stored one
+pub fn changed_text<'a>(target: &Target, current: &Value, composite_text: Option<&'a str>) -> Option<&'a str> {
+    composite_text.filter(|text| current.get(target.text_field).and_then(|v| v.as_str()) != Some(*text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(validate("product", false, None, None, Some("resolved"), None), Ok(Some("resolved".to_string())));
+        assert_eq!(validate("service", false, None, None, None, Some("completed")), Ok(Some("completed".to_string())));
+        assert_eq!(validate("product", true, None, None, None, None), Ok(None));
+
+        let message = |result: Result<Option<String>, (String, Value)>| result.unwrap_err().0;
+        assert_eq!(message(validate("service", false, None, None, Some("resolved"), None)), "resolution_status only applies to product interactions");
+        assert_eq!(message(validate("product", false, None, None, None, Some("completed"))), "outcome only applies to service interactions");
+        assert_eq!(message(validate("product", false, None, Some(-1.0), None, None)), "purchase_amount cannot be negative");
+        assert_eq!(message(validate("product", false, None, None, None, None)), "Nothing to update");
+    }
+
+    #[test]
+    fn test_merge_fields() {
+        let service = target("service").unwrap();
+        let current = json!({"satisfaction_rating": 3, "cost_actual": 40.0, "outcome": "pending", "text_feedback": "Haircut"});
+        let (fields, changed) = merge_fields(&service, &current, Some(5), Some(40.0), None);
+        assert_eq!(fields, json!({"satisfaction_rating": 5, "cost_actual": 40.0, "outcome": "pending"}));
+        assert_eq!(changed, ["satisfaction_rating"]);
+
+        // Missing stored fields default, so the query always gets all three
+        let (fields, changed) = merge_fields(&target("product").unwrap(), &json!({}), None, None, Some("resolved".to_string()));
+        assert_eq!(fields, json!({"rating": 0, "purchase_amount": 0.0, "resolution_status": "resolved"}));
+        assert_eq!(changed, ["resolution_status"]);
+
+        assert_eq!(changed_text(&service, &current, Some("Haircut")), None);
+        assert_eq!(changed_text(&service, &current, Some("Haircut and beard trim")), Some("Haircut and beard trim"));
+        assert!(target("navigation").is_none());
+    }
+}
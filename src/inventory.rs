@@ -0,0 +1,104 @@
+//! Stock tracking on product memories
+//!
+//! Products carry `stock_quantity` (-1 while stock isn't tracked) and
+//! `reorder_threshold`. While stock is tracked, `availability` follows the
+//! quantity: `out_of_stock` at 0, `low_stock` at or below the threshold,
+//! otherwise `in_stock`. Availabilities set by hand for other reasons
+//! (`preorder`, `discontinued`, ...) are left alone.
+
+use serde_json::{json, Value};
+
+/// `stock_quantity` of a product whose stock isn't tracked
+pub const UNTRACKED: i64 = -1;
+
+/// Availability values derived from the quantity, which it may overwrite
+const DERIVED: &[&str] = &["", "in_stock", "low_stock", "out_of_stock", "in stock", "out of stock", "available"];
+
+/// Availability implied by `quantity` against `reorder_threshold`
+pub fn availability_for(quantity: i64, reorder_threshold: i64) -> &'static str {
+    match quantity {
+        ..=0 => "out_of_stock",
+        q if q <= reorder_threshold => "low_stock",
+        _ => "in_stock",
+    }
+}
+
+/// Availability to store: derived while stock is tracked, unless set by hand
+pub fn derive_availability(current: &str, quantity: i64, reorder_threshold: i64) -> String {
+    if quantity <= UNTRACKED || !DERIVED.contains(&current.trim().to_lowercase().as_str()) {
+        return current.to_string();
+    }
+    availability_for(quantity, reorder_threshold).to_string()
+}
+
+/// New quantity after a stock adjustment; `Err` explains why it can't apply
+pub fn adjust(current: i64, delta: Option<i64>, set_quantity: Option<i64>) -> Result<i64, String> {
+    match (set_quantity, delta) {
+        (Some(_), Some(_)) => Err("Pass either delta or set_quantity, not both".to_string()),
+        (Some(quantity), None) if quantity < 0 => Err(format!("set_quantity must be 0 or more, got {}", quantity)),
+        (Some(quantity), None) => Ok(quantity),
+        (None, Some(_)) if current <= UNTRACKED => {
+            Err("Stock isn't tracked for this product yet; set a starting quantity with set_quantity".to_string())
+        }
+        (None, Some(delta)) if current + delta < 0 => {
+            Err(format!("Only {} in stock; cannot remove {}", current, -delta))
+        }
+        (None, Some(delta)) => Ok(current + delta),
+        (None, None) => Err("Pass delta (e.g. -2 for a sale, 10 for a delivery) or set_quantity".to_string()),
+    }
+}
+
+/// Tracked products at or below their reorder threshold, lowest stock first
+pub fn low_stock(products: &[Value], include_out_of_stock: bool) -> Vec<Value> {
+    let mut low: Vec<(i64, Value)> = products
+        .iter()
+        .filter_map(|product| {
+            let quantity = product.get("stock_quantity").and_then(|v| v.as_i64()).unwrap_or(UNTRACKED);
+            let threshold = product.get("reorder_threshold").and_then(|v| v.as_i64()).unwrap_or(0);
+            let low = quantity > UNTRACKED && quantity <= threshold.max(0) && (include_out_of_stock || quantity > 0);
+            low.then(|| {
+                (quantity, json!({
+                    "product_id": product.get("product_id"),
+                    "product_name": product.get("product_name"),
+                    "stock_quantity": quantity,
+                    "reorder_threshold": threshold,
+                    "shortfall": (threshold - quantity).max(0),
+                    "availability": availability_for(quantity, threshold)
+                }))
+            })
+        })
+        .collect();
+    low.sort_by_key(|(quantity, _)| *quantity);
+    low.into_iter().map(|(_, product)| product).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stock_rules() {
+        assert_eq!(derive_availability("", 0, 5), "out_of_stock");
+        assert_eq!(derive_availability("in_stock", 3, 5), "low_stock");
+        assert_eq!(derive_availability("low_stock", 30, 5), "in_stock");
+        assert_eq!(derive_availability("preorder", 0, 5), "preorder");
+        assert_eq!(derive_availability("available", UNTRACKED, 5), "available");
+
+        assert_eq!(adjust(10, Some(-3), None), Ok(7));
+        assert_eq!(adjust(UNTRACKED, None, Some(4)), Ok(4));
+        assert!(adjust(2, Some(-3), None).is_err());
+        assert!(adjust(UNTRACKED, Some(5), None).is_err());
+        assert!(adjust(1, Some(1), Some(1)).is_err());
+
+        let products = vec![
+            json!({"product_id": "P1", "stock_quantity": 2, "reorder_threshold": 5}),
+            json!({"product_id": "P2", "stock_quantity": 0, "reorder_threshold": 5}),
+            json!({"product_id": "P3", "stock_quantity": 50, "reorder_threshold": 5}),
+            json!({"product_id": "P4", "stock_quantity": -1, "reorder_threshold": 5}),
+        ];
+        let low = low_stock(&products, true);
+        assert_eq!(low.iter().map(|p| p["product_id"].as_str().unwrap()).collect::<Vec<_>>(), vec!["P2", "P1"]);
+        assert_eq!(low[1]["shortfall"], 3);
+        assert_eq!(low_stock(&products, false).len(), 1);
+    }
+}
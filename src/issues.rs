@@ -0,0 +1,134 @@
+//! Open issue queue
+//!
+//! An issue is a product interaction the customer disliked or rated 1-2 that
+//! isn't `resolved` yet, or feedback that is a complaint, negative or rated
+//! 1-2 and not marked `resolved`. `list_open_issues` returns them oldest
+//! first; `resolve_issue` closes one, stamps `resolved_at` and stores the
+//! resolution note as a communication memory.
+
+use serde_json::{json, Value};
+
+/// Where an issue is recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    ProductInteraction,
+    Feedback,
+}
+
+impl Kind {
+    pub fn parse(kind: &str) -> Option<Kind> {
+        match kind.trim().to_lowercase().as_str() {
+            "product_interaction" | "interaction" | "product" => Some(Kind::ProductInteraction),
+            "feedback" => Some(Kind::Feedback),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Kind::ProductInteraction => "product_interaction",
+            Kind::Feedback => "feedback",
+        }
+    }
+
+    /// Node lookup and resolve queries
+    pub fn queries(self) -> (&'static str, &'static str) {
+        match self {
+            Kind::ProductInteraction => ("get_customer_product_interaction_node", "resolve_product_interaction_issue"),
+            Kind::Feedback => ("get_customer_feedback_node", "resolve_customer_feedback"),
+        }
+    }
+}
+
+fn text<'a>(item: &'a Value, field: &str) -> &'a str {
+    item.get(field).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+fn low_rating(item: &Value) -> bool {
+    item.get("rating").and_then(|v| v.as_i64()).is_some_and(|r| (1..=2).contains(&r))
+}
+
+/// Whether a node of this kind is an issue that is still open
+pub fn is_open(kind: Kind, item: &Value) -> bool {
+    match kind {
+        Kind::ProductInteraction => {
+            (text(item, "interaction_type") == "disliked" || low_rating(item)) && text(item, "resolution_status") != "resolved"
+        }
+        Kind::Feedback => {
+            let resolved = item.get("resolved").and_then(|v| v.as_bool()).unwrap_or(false);
+            !resolved && (text(item, "sentiment") == "negative" || text(item, "feedback_type") == "complaint" || low_rating(item))
+        }
+    }
+}
+
+/// A node as a queue entry
+pub fn entry(kind: Kind, item: &Value) -> Value {
+    let (issue_id, subject, status, body, opened_at) = match kind {
+        Kind::ProductInteraction => (
+            text(item, "interaction_id"),
+            text(item, "product_id"),
+            match text(item, "resolution_status") {
+                "" => "open",
+                status => status,
+            },
+            text(item, "text_reason"),
+            item.get("timestamp").or_else(|| item.get("created_at")),
+        ),
+        Kind::Feedback => (
+            text(item, "feedback_id"),
+            text(item, "subject"),
+            "open",
+            text(item, "text_description"),
+            item.get("created_at"),
+        ),
+    };
+    json!({
+        "memory_id": item.get("id").cloned().unwrap_or(Value::Null),
+        "kind": kind.as_str(),
+        "issue_id": issue_id,
+        "customer_id": text(item, "customer_id"),
+        "subject": subject,
+        "rating": item.get("rating").and_then(|v| v.as_i64()).unwrap_or(0),
+        "status": status,
+        "text": body,
+        "opened_at": opened_at.and_then(|v| v.as_i64()).unwrap_or(0)
+    })
+}
+
+/// Open issues among interactions and feedback, oldest first
+pub fn open_issues(interactions: &[Value], feedback: &[Value]) -> Vec<Value> {
+    let mut issues: Vec<Value> = [(Kind::ProductInteraction, interactions), (Kind::Feedback, feedback)]
+        .into_iter()
+        .flat_map(|(kind, items)| items.iter().filter(move |item| is_open(kind, item)).map(move |item| entry(kind, item)))
+        .collect();
+    issues.sort_by_key(|issue| issue["opened_at"].as_i64().unwrap_or(0));
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_issues() {
+        let interactions = vec![
+            json!({"id": "n1", "interaction_id": "I1", "customer_id": "C1", "product_id": "P1", "interaction_type": "disliked", "resolution_status": "pending", "timestamp": 300, "text_reason": "Cold"}),
+            json!({"id": "n2", "interaction_id": "I2", "customer_id": "C1", "interaction_type": "disliked", "resolution_status": "resolved", "timestamp": 100}),
+            json!({"id": "n3", "interaction_id": "I3", "customer_id": "C1", "interaction_type": "purchased", "rating": 2, "timestamp": 200}),
+            json!({"id": "n4", "interaction_id": "I4", "customer_id": "C1", "interaction_type": "liked", "rating": 5, "timestamp": 50}),
+        ];
+        let feedback = vec![
+            json!({"id": "f1", "feedback_id": "F1", "customer_id": "C1", "feedback_type": "complaint", "resolved": false, "created_at": 250}),
+            json!({"id": "f2", "feedback_id": "F2", "customer_id": "C1", "sentiment": "negative", "resolved": true, "created_at": 10}),
+            json!({"id": "f3", "feedback_id": "F3", "customer_id": "C1", "sentiment": "positive", "rating": 0, "created_at": 20}),
+        ];
+        let issues = open_issues(&interactions, &feedback);
+        let ids: Vec<&str> = issues.iter().map(|i| i["issue_id"].as_str().unwrap()).collect();
+        assert_eq!(ids, ["I3", "F1", "I1"]);
+        assert_eq!(issues[0]["status"], "open");
+        assert_eq!(issues[2]["status"], "pending");
+        assert_eq!(issues[1]["kind"], "feedback");
+        assert_eq!(Kind::parse("Feedback"), Some(Kind::Feedback));
+        assert_eq!(Kind::parse("booking"), None);
+    }
+}
@@ -0,0 +1,228 @@
+//! Background jobs for long-running operations
+//!
+//! Bulk imports, re-embeddings and cascade deletes can outlast an MCP
+//! client's request timeout. `submit_job` spawns them as tokio tasks and
+//! returns a job ID straight away; at most `max_concurrent` run at once and
+//! the rest wait queued. `get_job_status` reports the state and, once
+//! finished, the tool's result; `cancel_job` aborts a queued or running job.
+//! Jobs run as [`priority::background`] work and are only visible to the
+//! session that submitted them. Finished jobs are kept for the retention period.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::AbortHandle;
+
+use crate::clock::Clock;
+use crate::priority;
+
+/// Tools that can run as jobs
+pub const JOB_TYPES: &[&str] = &["import_memories", "backfill_embeddings", "delete_memory", "delete_memories_by_filter", "sync_shopify_catalog"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+struct Job {
+    job_type: String,
+    session_id: String,
+    status: JobStatus,
+    submitted_at: i64,
+    started_at: Option<i64>,
+    finished_at: Option<i64>,
+    // Tool result when completed, error content when failed
+    output: Option<Value>,
+    finished: Option<Instant>,
+    handle: Option<AbortHandle>,
+}
+
+impl Job {
+    fn report(&self, job_id: &str) -> Value {
+        let mut report = json!({
+            "job_id": job_id,
+            "job_type": self.job_type,
+            "status": self.status,
+            "submitted_at": self.submitted_at,
+            "started_at": self.started_at,
+            "finished_at": self.finished_at,
+        });
+        match self.status {
+            JobStatus::Completed => report["result"] = self.output.clone().unwrap_or(Value::Null),
+            JobStatus::Failed => report["error"] = self.output.clone().unwrap_or(Value::Null),
+            _ => {}
+        }
+        report
+    }
+}
+
+pub struct JobQueue {
+    jobs: Mutex<HashMap<String, Job>>,
+    slots: Arc<Semaphore>,
+    retention: Duration,
+    clock: Arc<dyn Clock>,
+}
+
+impl JobQueue {
+    pub fn new(max_concurrent: usize, retention: Duration, clock: Arc<dyn Clock>) -> Self {
+        JobQueue {
+            jobs: Mutex::new(HashMap::new()),
+            slots: Arc::new(Semaphore::new(max_concurrent.max(1))),
+            retention,
+            clock,
+        }
+    }
+
+    /// Queue `work` as job `job_id`; its `Ok` value is the job result, its
+    /// `Err` value the failure reported by `status`
+    pub fn submit<F>(self: &Arc<Self>, job_id: &str, job_type: &str, session_id: &str, work: F) -> Value
+    where
+        F: Future<Output = Result<Value, Value>> + Send + 'static,
+    {
+        let job = Job {
+            job_type: job_type.to_string(),
+            session_id: session_id.to_string(),
+            status: JobStatus::Queued,
+            submitted_at: self.clock.timestamp(),
+            started_at: None,
+            finished_at: None,
+            output: None,
+            finished: None,
+            handle: None,
+        };
+        let report = job.report(job_id);
+        {
+            let mut jobs = self.jobs.lock().unwrap();
+            jobs.retain(|_, j| j.finished.is_none_or(|at| at.elapsed() < self.retention));
+            jobs.insert(job_id.to_string(), job);
+        }
+
+        let queue = self.clone();
+        let id = job_id.to_string();
+        let handle = tokio::spawn(async move {
+            let _slot = queue.slots.clone().acquire_owned().await;
+            if !queue.transition(&id, JobStatus::Running, None) {
+                return;
+            }
+            let (status, output) = match priority::background(work).await {
+                Ok(result) => (JobStatus::Completed, result),
+                Err(error) => (JobStatus::Failed, error),
+            };
+            queue.transition(&id, status, Some(output));
+        });
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(job_id) {
+            job.handle = Some(handle.abort_handle());
+        }
+        report
+    }
+
+    /// Move an unfinished job to `status`; false if it was already finished (cancelled)
+    fn transition(&self, job_id: &str, status: JobStatus, output: Option<Value>) -> bool {
+        let mut jobs = self.jobs.lock().unwrap();
+        let Some(job) = jobs.get_mut(job_id).filter(|j| !j.status.finished()) else {
+            return false;
+        };
+        let now = self.clock.timestamp();
+        job.status = status;
+        job.output = output;
+        if status.finished() {
+            job.finished_at = Some(now);
+            job.finished = Some(Instant::now());
+            job.handle = None;
+        } else {
+            job.started_at = Some(now);
+        }
+        true
+    }
+
+    /// A job's report; jobs of other sessions are reported as missing
+    pub fn status(&self, job_id: &str, session_id: &str) -> Option<Value> {
+        self.jobs.lock().unwrap().get(job_id).filter(|j| j.session_id == session_id).map(|job| job.report(job_id))
+    }
+
+    /// Jobs submitted by a session, newest first, without their results
+    pub fn list(&self, session_id: &str) -> Vec<Value> {
+        let jobs = self.jobs.lock().unwrap();
+        let mut listed: Vec<(&String, &Job)> = jobs.iter().filter(|(_, j)| j.session_id == session_id).collect();
+        listed.sort_by(|a, b| b.1.submitted_at.cmp(&a.1.submitted_at).then(b.0.cmp(a.0)));
+        listed
+            .into_iter()
+            .map(|(id, job)| {
+                let mut report = job.report(id);
+                if let Some(fields) = report.as_object_mut() {
+                    fields.remove("result");
+                    fields.remove("error");
+                }
+                report
+            })
+            .collect()
+    }
+
+    /// Abort a queued or running job, returning the status it had
+    pub fn cancel(&self, job_id: &str, session_id: &str) -> Result<JobStatus, String> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let job = jobs.get_mut(job_id).filter(|j| j.session_id == session_id).ok_or_else(|| format!("Job not found: {}", job_id))?;
+        if job.status.finished() {
+            return Err(format!("Job {} already finished ({:?})", job_id, job.status).to_lowercase());
+        }
+        let previous = job.status;
+        if let Some(handle) = job.handle.take() {
+            handle.abort();
+        }
+        job.status = JobStatus::Cancelled;
+        job.finished_at = Some(self.clock.timestamp());
+        job.finished = Some(Instant::now());
+        Ok(previous)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::SystemClock;
+
+    #[tokio::test]
+    async fn test_queue_runs_and_cancels() {
+        let queue = Arc::new(JobQueue::new(1, Duration::from_secs(60), Arc::new(SystemClock)));
+        let (release, wait) = tokio::sync::oneshot::channel::<()>();
+        queue.submit("j1", "import_memories", "s1", async move {
+            let _ = wait.await;
+            Ok(json!({"imported": 3}))
+        });
+        // Only one slot, so the second job waits queued
+        let report = queue.submit("j2", "backfill_embeddings", "s1", async { Ok(json!({})) });
+        assert_eq!(report["status"], "queued");
+        tokio::task::yield_now().await;
+        assert_eq!(queue.status("j1", "s1").unwrap()["status"], "running");
+
+        // Another session can neither see nor cancel the jobs
+        assert!(queue.status("j2", "s2").is_none());
+        assert!(queue.cancel("j2", "s2").is_err());
+        assert_eq!(queue.cancel("j2", "s1").unwrap(), JobStatus::Queued);
+        release.send(()).unwrap();
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        let done = queue.status("j1", "s1").unwrap();
+        assert_eq!(done["status"], "completed");
+        assert_eq!(done["result"]["imported"], 3);
+        assert_eq!(queue.status("j2", "s1").unwrap()["status"], "cancelled");
+        assert!(queue.cancel("j1", "s1").is_err());
+        assert_eq!(queue.list("s1").len(), 2);
+    }
+}
@@ -0,0 +1,259 @@
+//! Live location sharing
+//!
+//! A short-lived session ties the customer's phone, pushing GPS fixes to
+//! `POST /location/<share_id>` on the HTTP transport, to a route through a
+//! navigation hub. `get_live_guidance` compares the latest fix with the
+//! route's waypoints, moves past the ones already reached and returns the
+//! next instruction. Waypoint positions are derived from the hub coordinates
+//! and each waypoint's compass bearing and distance from the main entrance.
+//! Sessions are held in memory only and expire after their TTL.
+
+use crate::{floors, geo};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use thiserror::Error;
+
+/// A fix older than this is reported as stale
+pub const STALE_FIX_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Target {
+    pub waypoint_id: String,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub floor_level: i64,
+    pub visual_cues: String,
+}
+
+impl Target {
+    /// Position a waypoint from the hub's coordinates and its bearing/distance from the main entrance
+    pub fn from_waypoint(waypoint: &Value, hub: (f64, f64)) -> Self {
+        let bearing = waypoint.get("compass_bearing").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let distance = waypoint.get("compass_distance").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let (latitude, longitude) = geo::coordinates_of(waypoint)
+            .unwrap_or_else(|| geo::destination_point(hub.0, hub.1, bearing, distance.max(0.0)));
+        let text = |field: &str| waypoint.get(field).and_then(|v| v.as_str()).unwrap_or("").to_string();
+        Target {
+            waypoint_id: text("waypoint_id"),
+            name: Some(text("waypoint_name")).filter(|n| !n.is_empty()).unwrap_or_else(|| "the next waypoint".to_string()),
+            latitude,
+            longitude,
+            floor_level: floors::level_of(waypoint),
+            visual_cues: text("visual_cues"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fix {
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default)]
+    pub accuracy_meters: Option<f64>,
+    #[serde(default)]
+    pub floor_level: Option<i64>,
+    #[serde(default)]
+    pub timestamp: i64,
+}
+
+pub struct LiveSession {
+    pub share_id: String,
+    /// Secret the customer's device presents when pushing fixes
+    pub token: String,
+    pub navigation_id: String,
+    pub path_id: Option<String>,
+    pub customer_id: Option<String>,
+    pub targets: Vec<Target>,
+    pub expires_at: i64,
+    pub last_fix: Option<Fix>,
+    pub fixes: u64,
+    /// Targets already reached, in route order
+    pub reached: usize,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PushError {
+    #[error("location sharing session not found or expired")]
+    NotFound,
+    #[error("invalid share token")]
+    Unauthorized,
+    #[error("invalid location: {0}")]
+    Invalid(String),
+}
+
+#[derive(Default)]
+pub struct LiveSessions {
+    sessions: Mutex<HashMap<String, LiveSession>>,
+}
+
+impl LiveSessions {
+    pub fn open(&self, session: LiveSession, now: i64) {
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, s| s.expires_at > now);
+        sessions.insert(session.share_id.clone(), session);
+    }
+
+    pub fn push(&self, share_id: &str, token: &str, mut fix: Fix, now: i64) -> Result<i64, PushError> {
+        if !(-90.0..=90.0).contains(&fix.latitude) || !(-180.0..=180.0).contains(&fix.longitude) {
+            return Err(PushError::Invalid("latitude must be within [-90, 90] and longitude within [-180, 180]".to_string()));
+        }
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions.get_mut(share_id).filter(|s| s.expires_at > now).ok_or(PushError::NotFound)?;
+        if session.token != token {
+            return Err(PushError::Unauthorized);
+        }
+        // Device clocks are not trusted
+        fix.timestamp = now;
+        session.last_fix = Some(fix);
+        session.fixes += 1;
+        Ok(session.expires_at)
+    }
+
+    /// Run `f` on a live (unexpired) session
+    pub fn with_session<R>(&self, share_id: &str, now: i64, f: impl FnOnce(&mut LiveSession) -> R) -> Option<R> {
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, s| s.expires_at > now);
+        sessions.get_mut(share_id).map(f)
+    }
+
+    pub fn close(&self, share_id: &str) -> Option<LiveSession> {
+        self.sessions.lock().unwrap().remove(share_id)
+    }
+}
+
+/// Distance in meters from `p` to the segment `a`-`b` (local flat projection)
+fn distance_to_segment(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let scale = p.0.to_radians().cos();
+    let project = |q: (f64, f64)| ((q.1 - p.1) * scale, q.0 - p.0);
+    let ((ax, ay), (bx, by)) = (project(a), project(b));
+    let (dx, dy) = (bx - ax, by - ay);
+    let length = dx * dx + dy * dy;
+    let t = if length == 0.0 { 0.0 } else { (-(ax * dx + ay * dy) / length).clamp(0.0, 1.0) };
+    let (cx, cy) = (ax + t * dx, ay + t * dy);
+    // Back to meters on the local tangent plane
+    geo::haversine_meters(p.0, p.1, p.0 + cy, p.1 + cx / scale.max(1e-9))
+}
+
+/// Next instruction for the session's latest fix, advancing past reached waypoints
+pub fn guide(session: &mut LiveSession, now: i64, arrival_radius: f64, off_route_meters: f64) -> Value {
+    let total = session.targets.len();
+    let Some(fix) = session.last_fix.clone() else {
+        return json!({
+            "status": "waiting_for_location",
+            "instruction": "No location received yet; ask the customer to allow location sharing",
+            "progress": {"reached": session.reached, "total": total}
+        });
+    };
+    let here = (fix.latitude, fix.longitude);
+    let distance_to = |t: &Target| geo::haversine_meters(here.0, here.1, t.latitude, t.longitude);
+    let radius = arrival_radius.max(fix.accuracy_meters.unwrap_or(0.0));
+
+    // A later waypoint within reach also counts, so missed corners don't stall guidance
+    let remaining = &session.targets[session.reached.min(total)..];
+    if let Some(i) = remaining.iter().rposition(|t| distance_to(t) <= radius) {
+        session.reached += i + 1;
+    }
+    let age = now - fix.timestamp;
+    let progress = json!({"reached": session.reached, "total": total});
+
+    let Some(next) = session.targets.get(session.reached) else {
+        let destination = session.targets.last().map_or("your destination", |t| t.name.as_str());
+        return json!({
+            "status": "arrived",
+            "instruction": format!("You have arrived at {}", destination),
+            "progress": progress,
+            "last_fix_age_secs": age
+        });
+    };
+
+    let distance = distance_to(next);
+    let bearing = geo::initial_bearing(here.0, here.1, next.latitude, next.longitude);
+    let previous = session.reached.checked_sub(1).map(|i| &session.targets[i]);
+    let off_route = previous.is_some_and(|prev| {
+        distance_to_segment(here, (prev.latitude, prev.longitude), (next.latitude, next.longitude)) > off_route_meters + fix.accuracy_meters.unwrap_or(0.0)
+    });
+
+    let mut instruction = format!("Head {} for about {} to {}", geo::compass_point(bearing), geo::format_distance(distance), next.name);
+    if !next.visual_cues.is_empty() {
+        instruction.push_str(&format!(" (look for: {})", next.visual_cues));
+    }
+    let current_floor = fix.floor_level.or(previous.map(|p| p.floor_level));
+    if let Some(floor) = current_floor.filter(|f| *f != next.floor_level) {
+        instruction = format!("{}, then {}", floors::transition_instruction(None, None, floor, next.floor_level, None), instruction.replacen("Head", "head", 1));
+    }
+    if off_route {
+        instruction = format!("You seem to be off the route. {}", instruction);
+    }
+
+    json!({
+        "status": "guiding",
+        "instruction": instruction,
+        "next_waypoint": {
+            "waypoint_id": next.waypoint_id,
+            "name": next.name,
+            "floor_level": next.floor_level,
+            "visual_cues": next.visual_cues
+        },
+        "distance_meters": distance.round(),
+        "bearing": (bearing * 10.0).round() / 10.0,
+        "compass_direction": geo::compass_point(bearing),
+        "off_route": off_route,
+        "progress": progress,
+        "last_fix": fix,
+        "last_fix_age_secs": age,
+        "stale": age > STALE_FIX_SECS
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_and_guide() {
+        let hub = (51.5, -0.1);
+        let waypoint = |id: &str, bearing: f64, distance: f64, floor: i64| {
+            Target::from_waypoint(
+                &json!({"waypoint_id": id, "waypoint_name": id, "compass_bearing": bearing, "compass_distance": distance, "floor_level": floor}),
+                hub,
+            )
+        };
+        let sessions = LiveSessions::default();
+        sessions.open(LiveSession {
+            share_id: "s1".to_string(),
+            token: "secret".to_string(),
+            navigation_id: "nav".to_string(),
+            path_id: None,
+            customer_id: None,
+            targets: vec![waypoint("Lobby", 0.0, 50.0, 0), waypoint("Lift", 90.0, 100.0, 0)],
+            expires_at: 1000,
+            last_fix: None,
+            fixes: 0,
+            reached: 0,
+        }, 0);
+
+        let at = |lat: f64, lon: f64| Fix { latitude: lat, longitude: lon, accuracy_meters: None, floor_level: None, timestamp: 0 };
+        assert_eq!(sessions.push("s1", "wrong", at(51.5, -0.1), 10), Err(PushError::Unauthorized));
+        assert_eq!(sessions.push("s1", "secret", at(51.5, -0.1), 2000), Err(PushError::NotFound));
+        sessions.push("s1", "secret", at(51.5, -0.1), 10).unwrap();
+
+        let first = sessions.with_session("s1", 20, |s| guide(s, 20, 10.0, 40.0)).unwrap();
+        assert_eq!(first["status"], "guiding");
+        assert_eq!(first["next_waypoint"]["name"], "Lobby");
+        assert_eq!(first["compass_direction"], "north");
+
+        let lobby = waypoint("Lobby", 0.0, 50.0, 0);
+        sessions.push("s1", "secret", at(lobby.latitude, lobby.longitude), 30).unwrap();
+        let second = sessions.with_session("s1", 30, |s| guide(s, 30, 10.0, 40.0)).unwrap();
+        assert_eq!(second["next_waypoint"]["name"], "Lift");
+        assert_eq!(second["off_route"], false);
+
+        let lift = waypoint("Lift", 90.0, 100.0, 0);
+        sessions.push("s1", "secret", at(lift.latitude, lift.longitude), 40).unwrap();
+        let done = sessions.with_session("s1", 40, |s| guide(s, 40, 10.0, 40.0)).unwrap();
+        assert_eq!(done["status"], "arrived");
+    }
+}
@@ -0,0 +1,264 @@
+//! Locale-aware `display` section for read tool results
+//!
+//! Read tools accept `locale` (and optionally `timezone`); the raw result is
+//! left as is and a `display` section maps JSON paths to formatted values:
+//! prices with their currency, Unix timestamps as local date/time, and
+//! opening hours (`monday_open`, ...) in the locale's clock style. The model
+//! then quotes `display` instead of doing separator, currency and timezone
+//! conversion itself.
+
+use chrono::{DateTime, FixedOffset, NaiveTime};
+use rmcp::model::Tool;
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+
+use crate::hours;
+
+/// Formatted values listed at most; large results are truncated
+const MAX_ENTRIES: usize = 500;
+
+/// Amount fields formatted with the object's `currency`
+const AMOUNT_FIELDS: &[&str] = &["price", "amount", "total_amount", "total_spent", "average_price", "min_price", "max_price", "cost"];
+
+/// Unix timestamps (seconds) from 2000 to 2100 count as dates
+const TIMESTAMP_RANGE: std::ops::Range<i64> = 946_684_800..4_102_444_800;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateOrder {
+    MonthDayYear,
+    DayMonthYear,
+    YearMonthDay,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Locale {
+    pub tag: &'static str,
+    decimal: char,
+    group: &'static str,
+    currency_after: bool,
+    date: DateOrder,
+    date_separator: char,
+    hour12: bool,
+}
+
+const LOCALES: &[Locale] = &[
+    Locale { tag: "en-US", decimal: '.', group: ",", currency_after: false, date: DateOrder::MonthDayYear, date_separator: '/', hour12: true },
+    Locale { tag: "en-GB", decimal: '.', group: ",", currency_after: false, date: DateOrder::DayMonthYear, date_separator: '/', hour12: false },
+    Locale { tag: "en-AU", decimal: '.', group: ",", currency_after: false, date: DateOrder::DayMonthYear, date_separator: '/', hour12: true },
+    Locale { tag: "en-CA", decimal: '.', group: ",", currency_after: false, date: DateOrder::YearMonthDay, date_separator: '-', hour12: true },
+    Locale { tag: "en-IN", decimal: '.', group: ",", currency_after: false, date: DateOrder::DayMonthYear, date_separator: '/', hour12: true },
+    Locale { tag: "de-DE", decimal: ',', group: ".", currency_after: true, date: DateOrder::DayMonthYear, date_separator: '.', hour12: false },
+    Locale { tag: "fr-FR", decimal: ',', group: " ", currency_after: true, date: DateOrder::DayMonthYear, date_separator: '/', hour12: false },
+    Locale { tag: "es-ES", decimal: ',', group: ".", currency_after: true, date: DateOrder::DayMonthYear, date_separator: '/', hour12: false },
+    Locale { tag: "it-IT", decimal: ',', group: ".", currency_after: true, date: DateOrder::DayMonthYear, date_separator: '/', hour12: false },
+    Locale { tag: "nl-NL", decimal: ',', group: ".", currency_after: false, date: DateOrder::DayMonthYear, date_separator: '-', hour12: false },
+    Locale { tag: "pt-BR", decimal: ',', group: ".", currency_after: false, date: DateOrder::DayMonthYear, date_separator: '/', hour12: false },
+    Locale { tag: "ja-JP", decimal: '.', group: ",", currency_after: false, date: DateOrder::YearMonthDay, date_separator: '/', hour12: false },
+    Locale { tag: "zh-CN", decimal: '.', group: ",", currency_after: false, date: DateOrder::YearMonthDay, date_separator: '/', hour12: false },
+];
+
+/// Locale for a BCP 47 tag ("de-DE", "en_GB", "fr"); a bare language picks its first region
+pub fn parse(tag: &str) -> Option<Locale> {
+    let tag = tag.trim().replace('_', "-");
+    LOCALES
+        .iter()
+        .find(|l| l.tag.eq_ignore_ascii_case(&tag))
+        .or_else(|| {
+            let language = tag.split('-').next()?.to_lowercase();
+            LOCALES.iter().find(|l| l.tag.starts_with(&format!("{}-", language)))
+        })
+        .copied()
+}
+
+pub fn supported() -> Vec<&'static str> {
+    LOCALES.iter().map(|l| l.tag).collect()
+}
+
+fn currency_symbol(code: &str) -> Option<&'static str> {
+    let symbol = match code.to_uppercase().as_str() {
+        "USD" => "$",
+        "EUR" => "€",
+        "GBP" => "£",
+        "JPY" | "CNY" => "¥",
+        "INR" => "₹",
+        "BRL" => "R$",
+        "CAD" => "CA$",
+        "AUD" => "A$",
+        "CHF" => "CHF",
+        _ => return None,
+    };
+    Some(symbol)
+}
+
+fn minor_units(code: &str) -> usize {
+    match code.to_uppercase().as_str() {
+        "JPY" | "KRW" => 0,
+        _ => 2,
+    }
+}
+
+impl Locale {
+    /// `amount` with the locale's separators and `decimals` fraction digits
+    pub fn number(&self, amount: f64, decimals: usize) -> String {
+        let fixed = format!("{:.*}", decimals, amount.abs());
+        let (whole, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+        let mut grouped = String::new();
+        for (i, digit) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i) % 3 == 0 {
+                grouped.push_str(self.group);
+            }
+            grouped.push(digit);
+        }
+        let sign = if amount < 0.0 && fixed.chars().any(|c| c.is_ascii_digit() && c != '0') { "-" } else { "" };
+        if fraction.is_empty() {
+            format!("{}{}", sign, grouped)
+        } else {
+            format!("{}{}{}{}", sign, grouped, self.decimal, fraction)
+        }
+    }
+
+    /// `amount` in `currency` (ISO code); unknown codes are written out
+    pub fn money(&self, amount: f64, currency: &str) -> String {
+        let number = self.number(amount, minor_units(currency));
+        match (currency_symbol(currency), currency.trim().is_empty()) {
+            (_, true) => number,
+            (Some(symbol), _) if !self.currency_after => format!("{}{}", symbol, number),
+            (Some(symbol), _) => format!("{} {}", number, symbol),
+            (None, _) => format!("{} {}", number, currency.to_uppercase()),
+        }
+    }
+
+    pub fn time(&self, time: NaiveTime) -> String {
+        if self.hour12 {
+            time.format("%-I:%M %p").to_string()
+        } else {
+            time.format("%H:%M").to_string()
+        }
+    }
+
+    pub fn date_time(&self, instant: DateTime<FixedOffset>) -> String {
+        let s = self.date_separator;
+        let pattern = match self.date {
+            DateOrder::MonthDayYear => format!("%m{s}%d{s}%Y"),
+            DateOrder::DayMonthYear => format!("%d{s}%m{s}%Y"),
+            DateOrder::YearMonthDay => format!("%Y{s}%m{s}%d"),
+        };
+        format!("{} {}", instant.format(&pattern), self.time(instant.time()))
+    }
+}
+
+fn is_hours_field(field: &str) -> bool {
+    ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"]
+        .iter()
+        .any(|day| field.strip_prefix(day).is_some_and(|rest| rest == "_open" || rest == "_close"))
+}
+
+fn is_timestamp_field(field: &str) -> bool {
+    field.ends_with("_at") || field == "timestamp" || field.ends_with("_date") || field.ends_with("_time")
+}
+
+fn collect(value: &Value, path: &str, locale: &Locale, offset: FixedOffset, out: &mut Map<String, Value>) {
+    if out.len() >= MAX_ENTRIES {
+        return;
+    }
+    match value {
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect(item, &format!("{}[{}]", path, i), locale, offset, out);
+            }
+        }
+        Value::Object(map) => {
+            let currency = map.get("currency").and_then(|v| v.as_str()).unwrap_or("");
+            for (field, field_value) in map {
+                let field_path = if path.is_empty() { field.clone() } else { format!("{}.{}", path, field) };
+                let formatted = match field_value {
+                    Value::Number(n) if AMOUNT_FIELDS.contains(&field.as_str()) => n.as_f64().map(|amount| locale.money(amount, currency)),
+                    Value::Number(n) if is_timestamp_field(field) => n
+                        .as_i64()
+                        .filter(|t| TIMESTAMP_RANGE.contains(t))
+                        .and_then(|t| DateTime::from_timestamp(t, 0))
+                        .map(|t| locale.date_time(t.with_timezone(&offset))),
+                    Value::String(text) if is_hours_field(field) => hours::parse_time(text).map(|t| locale.time(t)),
+                    Value::Object(_) | Value::Array(_) => {
+                        collect(field_value, &field_path, locale, offset, out);
+                        None
+                    }
+                    _ => None,
+                };
+                if let Some(formatted) = formatted {
+                    if out.len() >= MAX_ENTRIES {
+                        return;
+                    }
+                    out.insert(field_path, json!(formatted));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `display` section for a tool result: formatted values by JSON path
+pub fn display(content: &Value, locale: &Locale, timezone: &str, offset: FixedOffset) -> Value {
+    let mut values = Map::new();
+    collect(content, "", locale, offset, &mut values);
+    json!({
+        "locale": locale.tag,
+        "timezone": if timezone.is_empty() { "UTC" } else { timezone },
+        "truncated": values.len() >= MAX_ENTRIES,
+        "values": values
+    })
+}
+
+/// Add the `locale` and `timezone` arguments to a read tool's input schema
+pub fn advertise(tool: &mut Tool) {
+    let mut schema = (*tool.input_schema).clone();
+    if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+        properties.insert(
+            "locale".to_string(),
+            json!({
+                "type": "string",
+                "description": "Locale (e.g. en-US, de-DE) for a `display` section with prices, dates and opening hours formatted for the reader"
+            }),
+        );
+        properties.insert(
+            "timezone".to_string(),
+            json!({
+                "type": "string",
+                "description": "Timezone for `display` dates, as a UTC offset or abbreviation (e.g. +02:00, EST; default UTC)"
+            }),
+        );
+        tool.input_schema = Arc::new(schema);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_display() {
+        let us = parse("en_US").unwrap();
+        let de = parse("de").unwrap();
+        assert_eq!(de.tag, "de-DE");
+        assert!(parse("xx-YY").is_none());
+        assert_eq!(us.money(1234.5, "USD"), "$1,234.50");
+        assert_eq!(de.money(1234.5, "EUR"), "1.234,50 €");
+        assert_eq!(parse("ja-JP").unwrap().money(1500.0, "JPY"), "¥1,500");
+        assert_eq!(us.money(-3.0, "XYZ"), "-3.00 XYZ");
+
+        let result = json!({
+            "results": [{"product_name": "Latte", "price": 4.5, "currency": "EUR", "updated_at": 1_767_225_600}],
+            "hours": {"monday_open": "09:00", "monday_close": "17:30", "timezone": "CET"}
+        });
+        let offset = hours::parse_offset("+01:00").unwrap();
+        let shown = display(&result, &us, "+01:00", offset);
+        assert_eq!(shown["values"]["results[0].price"], "€4.50");
+        assert_eq!(shown["values"]["results[0].updated_at"], "01/01/2026 1:00 AM");
+        assert_eq!(shown["values"]["hours.monday_close"], "5:30 PM");
+
+        let shown = display(&result, &de, "", FixedOffset::east_opt(0).unwrap());
+        assert_eq!(shown["values"]["results[0].price"], "4,50 €");
+        assert_eq!(shown["values"]["results[0].updated_at"], "01.01.2026 00:00");
+        assert_eq!(shown["timezone"], "UTC");
+    }
+}
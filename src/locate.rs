@@ -0,0 +1,113 @@
+//! Locating a customer from what they can see
+//!
+//! Scores a hub's waypoints against a free-text description ("next to a red
+//! pharmacy sign") by combining embedding similarity, when available, with
+//! word overlap on the waypoint's visual and audio cues, name and
+//! description. Cues are weighted highest since they are what a lost
+//! customer describes.
+
+use crate::{floors, geo};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Share of the score from embedding similarity when it is available
+const SEMANTIC_WEIGHT: f64 = 0.7;
+
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "the", "i", "im", "i'm", "am", "is", "are", "to", "of", "at", "in", "on", "by", "and", "or",
+    "next", "near", "see", "can", "there", "here", "some", "with", "it", "its", "my", "me", "front", "standing",
+];
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|w| w.trim_matches('\'').to_lowercase())
+        .filter(|w| w.len() > 1 && !STOP_WORDS.contains(&w.as_str()))
+        .collect()
+}
+
+fn field<'a>(waypoint: &'a Value, name: &str) -> &'a str {
+    waypoint.get(name).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+/// Share of the description's words found in the waypoint, cues counting double
+pub fn cue_overlap(description: &str, waypoint: &Value) -> f64 {
+    let query = words(description);
+    if query.is_empty() {
+        return 0.0;
+    }
+    let cues = words(&format!("{} {}", field(waypoint, "visual_cues"), field(waypoint, "audio_cues")));
+    let other = words(&format!(
+        "{} {} {} {}",
+        field(waypoint, "waypoint_name"),
+        field(waypoint, "description"),
+        field(waypoint, "business_specific_notes"),
+        field(waypoint, "waypoint_type")
+    ));
+    let hits: f64 = query
+        .iter()
+        .map(|w| if cues.contains(w) { 2.0 } else if other.contains(w) { 1.0 } else { 0.0 })
+        .sum();
+    (hits / (2.0 * query.len() as f64)).min(1.0)
+}
+
+/// Combined score; `semantic` is cosine similarity when embeddings were compared
+pub fn score(description: &str, waypoint: &Value, semantic: Option<f64>) -> f64 {
+    let lexical = cue_overlap(description, waypoint);
+    match semantic {
+        Some(similarity) => SEMANTIC_WEIGHT * similarity.max(0.0) + (1.0 - SEMANTIC_WEIGHT) * lexical,
+        None => lexical,
+    }
+}
+
+/// "about 40 meters northeast of Main Entrance, on the 2nd floor"; waypoint
+/// bearings and distances are measured from the hub's main entrance (`anchor`)
+pub fn relative_position(waypoint: &Value, anchor: &str) -> String {
+    let distance = waypoint
+        .get("compass_distance")
+        .and_then(|v| v.as_f64())
+        .filter(|d| *d > 0.0)
+        .or_else(|| waypoint.get("distance_from_main").and_then(|v| v.as_f64()).filter(|d| *d > 0.0));
+    let direction = Some(field(waypoint, "compass_direction"))
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+        .or_else(|| waypoint.get("compass_bearing").and_then(|v| v.as_f64()).filter(|b| *b > 0.0).map(|b| geo::compass_point(b).to_string()));
+    let anchor = if anchor.is_empty() { "the main entrance" } else { anchor };
+
+    let mut position = match (distance, direction) {
+        (Some(d), Some(dir)) => format!("about {} {} of {}", geo::format_distance(d), dir, anchor),
+        (Some(d), None) => format!("about {} from {}", geo::format_distance(d), anchor),
+        (None, Some(dir)) => format!("{} of {}", dir, anchor),
+        (None, None) => match field(waypoint, "relative_position") {
+            "" => format!("near {}", anchor),
+            relative => relative.replace('_', " "),
+        },
+    };
+    let level = floors::level_of(waypoint);
+    if level != 0 {
+        position.push_str(&format!(", on the {}", floors::floor_label(level)));
+    }
+    position
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_score_and_position() {
+        let pharmacy = json!({
+            "waypoint_name": "East corridor", "visual_cues": "Red pharmacy sign, vending machines",
+            "compass_direction": "northeast", "compass_distance": 40.0, "floor_level": 2
+        });
+        let lobby = json!({"waypoint_name": "Lobby", "description": "Main lobby with reception desk", "visual_cues": "Fountain"});
+
+        let description = "I'm next to a red pharmacy sign";
+        assert!(cue_overlap(description, &pharmacy) > cue_overlap(description, &lobby));
+        assert_eq!(cue_overlap(description, &lobby), 0.0);
+        assert!(score(description, &lobby, Some(0.9)) > score(description, &pharmacy, None) * 0.5);
+
+        assert_eq!(relative_position(&pharmacy, "Main Entrance"), "about 40 meters northeast of Main Entrance, on the 2nd floor");
+        assert_eq!(relative_position(&lobby, ""), "near the main entrance");
+    }
+}
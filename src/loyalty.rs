@@ -0,0 +1,118 @@
+//! Loyalty points
+//!
+//! Each business can have one `LoyaltyRule`: purchases of at least
+//! `min_purchase` earn `points_per_unit` points per unit spent (rounded
+//! down), after converting the amount into the rule's currency. Points live
+//! in a ledger of `PointsTransaction` nodes, positive for accruals and
+//! negative for redemptions, so a balance is always the sum of its entries.
+//! Redemptions for one customer of one business run one at a time, so two
+//! concurrent ones can't both pass the balance check.
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::currency::Rates;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub points_per_unit: f64,
+    pub currency: String,
+    pub min_purchase: f64,
+    pub point_value: f64,
+    pub active: bool,
+}
+
+impl Rule {
+    pub fn from_node(node: &Value) -> Self {
+        let number = |field: &str, default: f64| node.get(field).and_then(|v| v.as_f64()).unwrap_or(default);
+        Rule {
+            points_per_unit: number("points_per_unit", 1.0),
+            currency: node.get("currency").and_then(|v| v.as_str()).unwrap_or("").to_uppercase(),
+            min_purchase: number("min_purchase", 0.0),
+            point_value: number("point_value", 0.01),
+            active: node.get("active").and_then(|v| v.as_bool()).unwrap_or(true),
+        }
+    }
+
+    /// Points earned by a purchase; `Err` says why it earns none
+    pub fn points_for(&self, amount: f64, currency: &str, rates: &Rates) -> Result<i64, String> {
+        if !self.active {
+            return Err("Loyalty rule is inactive".to_string());
+        }
+        let amount = if self.currency.is_empty() || currency.eq_ignore_ascii_case(&self.currency) {
+            amount
+        } else {
+            rates
+                .rate(currency, &self.currency)
+                .map(|rate| amount * rate)
+                .ok_or_else(|| format!("No exchange rate from {} to {}", currency, self.currency))?
+        };
+        if amount <= 0.0 || amount < self.min_purchase {
+            return Err(format!("Purchase of {:.2} is below the minimum of {}", amount, self.min_purchase));
+        }
+        Ok((amount * self.points_per_unit).floor() as i64)
+    }
+}
+
+/// Balance and lifetime totals of a customer's ledger
+pub fn balance(transactions: &[Value]) -> Value {
+    let points: Vec<i64> = transactions.iter().filter_map(|t| t.get("points").and_then(|v| v.as_i64())).collect();
+    let earned: i64 = points.iter().filter(|p| **p > 0).sum();
+    let redeemed: i64 = -points.iter().filter(|p| **p < 0).sum::<i64>();
+    json!({"balance": earned - redeemed, "lifetime_earned": earned, "lifetime_redeemed": redeemed})
+}
+
+type CustomerLock = Arc<tokio::sync::Mutex<()>>;
+
+/// One lock per (business, customer) held across a redemption's balance
+/// check and ledger write
+#[derive(Default)]
+pub struct RedemptionLocks {
+    locks: Mutex<HashMap<(String, String), CustomerLock>>,
+}
+
+impl RedemptionLocks {
+    pub async fn lock(&self, business_id: &str, customer_id: &str) -> tokio::sync::OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap();
+            // Drop locks nobody holds or waits for
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            locks.entry((business_id.to_string(), customer_id.to_string())).or_default().clone()
+        };
+        lock.lock_owned().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_points_and_balance() {
+        let rule = Rule::from_node(&json!({"points_per_unit": 2.0, "currency": "eur", "min_purchase": 5.0}));
+        let table: BTreeMap<String, f64> = [("EUR".to_string(), 0.5)].into();
+        let rates = Rates::new("USD", &table, "static");
+        assert_eq!(rule.points_for(12.75, "EUR", &rates), Ok(25));
+        assert_eq!(rule.points_for(20.0, "USD", &rates), Ok(20));
+        assert!(rule.points_for(4.0, "EUR", &rates).is_err());
+        assert!(rule.points_for(10.0, "GBP", &rates).is_err());
+        assert!(Rule { active: false, ..rule }.points_for(100.0, "EUR", &rates).is_err());
+
+        let ledger = vec![json!({"points": 25}), json!({"points": 20}), json!({"points": -30})];
+        assert_eq!(balance(&ledger), json!({"balance": 15, "lifetime_earned": 45, "lifetime_redeemed": 30}));
+    }
+
+    #[tokio::test]
+    async fn test_redemption_locks_are_per_customer() {
+        let locks = RedemptionLocks::default();
+        let held = locks.lock("biz_1", "cust_1").await;
+        // Another customer isn't blocked
+        drop(locks.lock("biz_1", "cust_2").await);
+        let waiting = tokio::time::timeout(std::time::Duration::from_millis(50), locks.lock("biz_1", "cust_1")).await;
+        assert!(waiting.is_err());
+        drop(held);
+        drop(locks.lock("biz_1", "cust_1").await);
+    }
+}
@@ -1,21 +1,97 @@
 use anyhow::Result;
-use rmcp::{tool_router, tool, tool_handler, ServerHandler, serve_server, schemars, transport::stdio};
+use rmcp::{tool_router, tool, ServerHandler, serve_server, schemars, transport::stdio};
 use rmcp::handler::server::tool::ToolRouter;
 use rmcp::handler::server::wrapper::Parameters;
-use rmcp::model::{CallToolResult, ServerCapabilities, ServerInfo, AnnotateAble};
+use rmcp::model::{CallToolResult, Content, ServerCapabilities, ServerInfo, AnnotateAble};
 use rmcp::ErrorData as McpError;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::Arc;
 use tracing::{info, error, warn};
-use uuid::Uuid;
 
 mod helix_client;
 mod session;
 mod config;
 mod embedding_client;
 mod server;
-
+mod chunking;
+mod export;
+mod importers;
+mod similarity;
+mod crypto;
+mod decay;
+mod signature;
+mod throttle;
+mod priority;
+mod analytics;
+mod embedding_cache;
+mod working_memory;
+mod geo;
+mod budget;
+mod sandbox;
+mod privacy;
+mod access_log;
+mod hours;
+mod subject_access;
+mod clock;
+mod events;
+mod policies;
+mod embedding_meta;
+mod geojson;
+mod floors;
+mod webhooks;
+mod locate;
+mod live;
+mod change_feed;
+mod prompts;
+mod resources;
+mod delete_preview;
+mod dry_run;
+mod validation;
+mod models;
+mod embedding_utils;
+mod rerank;
+mod search_results;
+mod explain;
+mod errors;
+mod idempotency;
+mod jobs;
+mod maintenance;
+mod repair;
+mod query_manifest;
+mod erasure;
+mod result_pages;
+mod memory_diff;
+mod snapshots;
+mod stats;
+mod locale;
+mod currency;
+mod inventory;
+mod price_history;
+mod booking;
+mod loyalty;
+mod customer_merge;
+mod channels;
+mod ingest;
+mod shopify;
+mod ics;
+mod wire;
+mod issues;
+mod alerts;
+mod customer_rules;
+mod importance;
+mod graph_viz;
+mod memory_merge;
+mod filters;
+mod bulk_delete;
+mod upsert;
+mod interaction_update;
+mod route;
+mod memory_types;
+mod fan_out;
+mod onboarding;
+
+use errors::{tool_error, ErrorCode};
 use helix_client::HelixClient;
 use config::Config;
 
@@ -30,6 +106,14 @@ struct QueryBusinessMemoryParam {
     memory_type: String,  // "products", "services", "locations", "hours", "social", "policies", "events", "all"
     #[serde(skip_serializing_if = "Option::is_none")]
     filters: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort_by: Option<String>,  // Field to sort by (e.g., "created_at", "price", "name")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order: Option<String>,  // "asc" (default) or "desc"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,  // Maximum results to return (per memory type)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<usize>,  // Results to skip before limit is applied
 }
 
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
@@ -37,7 +121,17 @@ struct QueryCustomerMemoryParam {
     customer_id: String,
     memory_type: String,  // "behaviors", "preferences", "desires", "rules", "feedback", "all"
     #[serde(skip_serializing_if = "Option::is_none")]
-    filters: Option<serde_json::Value>,
+    filters: Option<serde_json::Value>,  // Field matches, {"gte"/"lte"} ranges, "since"/"until" timestamps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channel: Option<String>,  // Only memories from these channels, comma-separated (e.g. "whatsapp", "store")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort_by: Option<String>,  // Field to sort by (e.g., "created_at", "price", "name")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order: Option<String>,  // "asc" (default) or "desc"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,  // Maximum results to return (per memory type)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<usize>,  // Results to skip before limit is applied
 }
 
 // Create parameters
@@ -62,6 +156,74 @@ struct CreateBusinessMemoryParam {
     #[serde(skip_serializing_if = "Option::is_none")]
     title: Option<String>,
     data: serde_json::Value,  // JSON object with additional memory-specific fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dedup: Option<bool>,  // Override [dedup] enabled from config for this call
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct OnboardBusinessParam {
+    business_id: String,  // New business's ID; refused if it already exists
+    business_name: String,
+    business_type: String,  // e.g. "restaurant", "retail", "salon"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allow_collaboration: Option<bool>,  // Default: false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<serde_json::Value>,  // Free-form business details (contact, website, ...)
+    // Initial memories: objects with text_description and the fields create_business_memory takes in data
+    #[serde(skip_serializing_if = "Option::is_none")]
+    products: Option<Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    services: Option<Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    locations: Option<Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hours: Option<Vec<serde_json::Value>>,
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct CloneBusinessMemoriesParam {
+    source_business_id: String,  // Template business to copy from
+    target_business_id: String,  // Business receiving the copies (must exist)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory_types: Option<Vec<String>>,  // Default: all business memory types
+    #[serde(skip_serializing_if = "Option::is_none")]
+    overrides: Option<serde_json::Value>,  // Fields set on every copy; a memory type key (e.g. "product") holds that type's overrides
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct DiffMemoriesParam {
+    memory_type: String,  // e.g. "product", "policy", "preference"
+    // Two memories: their entity IDs (e.g. product_id)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory_id_a: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory_id_b: Option<String>,
+    // Or every memory of the type across two businesses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_id_a: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_id_b: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    match_field: Option<String>,  // Field pairing memories across businesses (default: the type's name field)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_embeddings: Option<bool>,  // Compare stored embedding vectors too (default: true)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct CreateSnapshotParam {
+    business_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory_types: Option<Vec<String>>,  // Default: all business memory types
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encrypt: Option<bool>,  // Encrypt the file with the export key (default: [export] encrypt_by_default)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct RestoreSnapshotParam {
+    business_id: String,
+    snapshot_id: String,  // From create_snapshot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory_types: Option<Vec<String>>,  // Restore only these types (default: all in the snapshot)
 }
 
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
@@ -70,6 +232,25 @@ struct CreateCustomerMemoryParam {
     memory_type: String,  // "behavior", "preference", "desire", "rule", "feedback"
     text_description: String,  // Required for embedding generation in MCP mode
     data: serde_json::Value,  // JSON object with memory-specific fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dedup: Option<bool>,  // Override [dedup] enabled from config for this call
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct StoreConversationSummaryParam {
+    customer_id: String,
+    business_id: String,
+    transcript: String,  // Raw conversation transcript, one speaker turn per line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,  // Conversation session identifier (auto-generated if omitted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contact_method: Option<String>,  // "phone", "email", "chat", "social_media", "whatsapp"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contact_reason: Option<String>,  // "inquiry", "complaint", "feedback", "support"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    agent_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_chunk_chars: Option<usize>,  // Default: 1000
 }
 
 // Update parameters
@@ -80,6 +261,72 @@ struct UpdateBusinessMemoryParam {
     updates: serde_json::Value,  // JSON object with fields to update
 }
 
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct UpsertBusinessMemoryParam {
+    business_id: String,
+    memory_type: String,  // "product", "service", "location", "hours", "social", "policy", "event", "information"
+    external_id: String,  // Key in the source system (SKU, product_id, ...)
+    text_description: String,  // Re-embedded when it changes
+    #[serde(default)]
+    data: serde_json::Value,  // Memory-specific fields, used when creating (product_name, price, ...)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct SyncShopifyCatalogParam {
+    business_id: String,  // Business the store's products belong to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mark_discontinued: Option<bool>,  // Mark synced products gone from the store discontinued (default: true)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct ImportEventsIcsParam {
+    business_id: String,  // Business the events belong to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,  // Feed URL (http(s):// or webcal://)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,  // .ics file name in the server's calendar import directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ics: Option<String>,  // ICS text pasted inline
+    #[serde(skip_serializing_if = "Option::is_none")]
+    horizon_days: Option<i64>,  // Expand recurring events this many days ahead (default: 180)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    utc_offset: Option<String>,  // Offset for TZID/floating times, e.g. "+02:00", "CET" (default: UTC)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct AdjustProductStockParam {
+    business_id: String,
+    product_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delta: Option<i64>,  // Change in units: negative for sales, positive for deliveries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    set_quantity: Option<i64>,  // Absolute count (stock take); starts tracking an untracked product
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reorder_threshold: Option<i64>,  // New low-stock level
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,  // e.g. "sale", "delivery", "stock take" (logged)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct GetLowStockProductsParam {
+    business_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_out_of_stock: Option<bool>,  // Default: true
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct GetPriceHistoryParam {
+    business_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entity_id: Option<String>,  // product_id or service_id; omit for every price change of the business
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory_type: Option<String>,  // "product" or "service"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    since: Option<serde_json::Value>,  // Unix seconds, RFC3339 or YYYY-MM-DD
+    #[serde(skip_serializing_if = "Option::is_none")]
+    until: Option<serde_json::Value>,
+}
+
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
 struct UpdateCustomerMemoryParam {
     memory_id: String,  // behavior_id, preference_id, desire_id, etc.
@@ -87,11 +334,30 @@ struct UpdateCustomerMemoryParam {
     updates: serde_json::Value,  // JSON object with fields to update
 }
 
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct PinMemoryParam {
+    memory_id: String,  // Internal ID from query_customer_memory
+    memory_type: String,  // "behavior", "preference", "desire", "rule", "feedback"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pinned: Option<bool>,  // false unpins (default: true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    importance: Option<i32>,  // 0-5; 5 = critical fact
+}
+
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
 struct UpdateInteractionParam {
     interaction_id: String,
     interaction_type: String,  // "product" or "service"
-    composite_text: String,  // Updated text description for re-embedding
+    #[serde(skip_serializing_if = "Option::is_none")]
+    composite_text: Option<String>,  // Updated text description; re-embedded when it changes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rating: Option<i32>,  // 1-5 (satisfaction_rating on services)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    purchase_amount: Option<f64>,  // Amount paid (cost_actual on services)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolution_status: Option<String>,  // Products: "resolved", "pending", "escalated"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outcome: Option<String>,  // Services: outcome/result
 }
 
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
@@ -105,710 +371,5748 @@ struct UpdateNavigationParam {
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
 struct DeleteMemoryParam {
     memory_id: String,
-    memory_type: String,  // "product", "service", "behavior", "preference", "business", "customer", etc.
+    memory_type: String,  // "product", "service", "behavior", "preference", "interaction", "navigation_hub", "waypoint", "path", "business", "customer", etc.
     #[serde(skip_serializing_if = "Option::is_none")]
     delete_embedding: Option<bool>,  // Whether to also delete embedding (default: true)
     #[serde(skip_serializing_if = "Option::is_none")]
     delete_strategy: Option<String>,  // "node_only", "with_embedding", "cascade", "complete" (default: "with_embedding")
 }
 
-// Advanced: Direct query execution
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
-struct DoQueryParam {
-    endpoint: String,  // Query name from queries.hx (e.g., "get_business_products")
-    payload: serde_json::Value,  // JSON object with query parameters
+struct PreviewDeleteParam {
+    memory_id: String,  // business_id or customer_id
+    memory_type: String,  // "business" or "customer"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delete_strategy: Option<String>,  // "cascade" or "complete" (default: "complete")
 }
 
-// Search and insights parameters
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
-struct SearchSemanticParam {
-    query: String,
-    memory_types: Vec<String>,  // e.g., ["products", "preferences"]
+struct DeleteMemoriesByFilterParam {
+    memory_type: String,  // "product", "service", "preference", etc.
+    owner_id: String,  // business_id for business types, customer_id for customer types
+    filters: serde_json::Value,  // e.g., {"availability": "discontinued", "created_at": {"lte": 1700000000}}
     #[serde(skip_serializing_if = "Option::is_none")]
-    business_id: Option<String>,
+    dry_run: Option<bool>,  // Preview matches without deleting (default: true)
     #[serde(skip_serializing_if = "Option::is_none")]
-    customer_id: Option<String>,
+    expected_count: Option<usize>,  // Required to delete: the count returned by the preview
     #[serde(skip_serializing_if = "Option::is_none")]
-    limit: Option<i32>,
+    delete_embedding: Option<bool>,  // Also delete embeddings (default: true)
 }
 
+// Merge parameters
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
-struct SearchBM25Param {
-    query: String,  // Keyword search query
-    memory_types: Vec<String>,  // e.g., ["products", "services", "preferences"]
-    #[serde(skip_serializing_if = "Option::is_none")]
-    business_id: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    customer_id: Option<String>,
+struct MergeMemoriesParam {
+    memory_ids: Vec<String>,  // Entity IDs (e.g. product_id, preference_id); the first one survives
+    memory_type: String,  // "product", "service", "location", "hours", "social", "policy", "event", "information", "behavior", "preference", "desire", "rule", "feedback"
     #[serde(skip_serializing_if = "Option::is_none")]
-    limit: Option<i32>,  // Default: 10
+    composite_text: Option<String>,  // Combined description; defaults to the merged text descriptions
 }
 
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
-struct FindCustomerInsightsParam {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    customer_id: Option<String>,
+struct MergeCustomersParam {
+    customer_id: String,  // Canonical customer that survives
+    duplicate_customer_id: String,  // Merged into customer_id and left behind as an alias
     #[serde(skip_serializing_if = "Option::is_none")]
-    product_id: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    service_id: Option<String>,
-    relationship_type: String,  // "liked", "disliked", "used_service", "visited_location", "all"
+    reason: Option<String>,  // Recorded on the alias, e.g. "same phone on WhatsApp and web"
 }
 
-// Customer Interaction parameters
+// Decay parameters
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
-struct CreateCustomerProductInteractionParam {
-    customer_id: String,
-    product_id: String,
-    interaction_type: String,  // "liked", "disliked", "purchased", "viewed", "favorited", "reviewed"
-    #[serde(skip_serializing_if = "Option::is_none")]
-    rating: Option<i32>,  // Rating if applicable (1-5 scale)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    channel: Option<String>,  // "whatsapp", "website", "store", etc.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    session_duration: Option<i32>,  // How long customer engaged (seconds)
+struct ApplyDecayParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    purchase_amount: Option<f64>,  // Amount spent if purchased
+    customer_id: Option<String>,  // Limit to one customer (default: all customers)
     #[serde(skip_serializing_if = "Option::is_none")]
-    currency: Option<String>,  // Currency of purchase
+    half_life_days: Option<f64>,  // Override [decay] half_life_days
     #[serde(skip_serializing_if = "Option::is_none")]
-    issue_category: Option<String>,  // For dislikes: "quality", "price", "functionality", "service"
+    min_score: Option<f64>,  // Override [decay] min_score
     #[serde(skip_serializing_if = "Option::is_none")]
-    resolution_status: Option<String>,  // For issues: "resolved", "pending", "escalated"
-    text_reason: String,  // Natural language reason for interaction (used for embedding)
+    dry_run: Option<bool>,  // Report planned changes without writing them
 }
 
+// Session working memory parameters
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
-struct CreateCustomerServiceInteractionParam {
-    customer_id: String,
-    service_id: String,
-    interaction_type: String,  // "booked", "completed", "reviewed", "canceled"
+struct RememberForSessionParam {
+    key: String,  // Note name (e.g., "shortlisted_products")
+    value: serde_json::Value,  // Any JSON value
     #[serde(skip_serializing_if = "Option::is_none")]
-    satisfaction_rating: Option<i32>,  // Rating (1-5 scale)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    duration_actual: Option<i32>,  // Actual duration in minutes
+    session_id: Option<String>,  // Explicit session (required for HTTP; defaults to the connection)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct RecallSessionParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    cost_actual: Option<f64>,  // Actual cost paid
+    key: Option<String>,  // Note to read (default: all notes)
     #[serde(skip_serializing_if = "Option::is_none")]
-    currency: Option<String>,  // Currency code
+    clear: Option<bool>,  // Remove the returned notes
     #[serde(skip_serializing_if = "Option::is_none")]
-    outcome: Option<String>,  // Service outcome
-    text_feedback: String,  // Natural language feedback (used for embedding)
+    session_id: Option<String>,  // Explicit session (required for HTTP; defaults to the connection)
 }
 
+// Backfill parameters
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
-struct QueryCustomerInteractionsParam {
-    customer_id: String,
-    interaction_type: String,  // "product", "service", "all"
+struct BackfillEmbeddingsParam {
+    memory_type: String,  // Business or customer memory type (e.g., "products", "preference")
+    owner_id: String,  // business_id for business types, customer_id for customer types
     #[serde(skip_serializing_if = "Option::is_none")]
-    filters: Option<serde_json::Value>,
+    limit: Option<usize>,  // Maximum memories to re-embed in this run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    requests_per_minute: Option<u32>,  // Override [backfill] requests_per_minute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ignore_schedule: Option<bool>,  // Run outside the configured off-peak window
 }
 
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
-struct SearchCustomerInteractionsParam {
-    query: String,
-    interaction_types: Vec<String>,  // e.g., ["product", "service"]
+struct VerifyEmbeddingsParam {
+    memory_type: String,  // Business or customer memory type (e.g., "products", "preference")
+    owner_id: String,  // business_id for business types, customer_id for customer types
     #[serde(skip_serializing_if = "Option::is_none")]
-    customer_id: Option<String>,
+    sample_size: Option<usize>,  // Memories checked, spread over the collection (default: 20)
     #[serde(skip_serializing_if = "Option::is_none")]
-    limit: Option<i32>,
+    min_similarity: Option<f64>,  // Flag stored vectors less similar than this to a fresh embedding (default: 0.9)
 }
 
-// Navigation System parameters
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
-struct CreateNavigationHubParam {
-    business_id: String,
-    navigation_id: String,
-    primary_address: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    secondary_address: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    building_name: Option<String>,
+struct RepairGraphParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    building_type: Option<String>,
-    latitude: f64,
-    longitude: f64,
+    business_id: Option<String>,  // Check this business's memories for missing embeddings
     #[serde(skip_serializing_if = "Option::is_none")]
-    what3words_code: Option<String>,
+    customer_id: Option<String>,  // Check this customer's memories and interactions
     #[serde(skip_serializing_if = "Option::is_none")]
-    plus_code: Option<String>,
+    checks: Option<Vec<String>>,  // "orphan_vectors", "missing_embeddings", "dangling_references" (default: all)
     #[serde(skip_serializing_if = "Option::is_none")]
-    compass_bearing: Option<f64>,
+    fix: Option<bool>,  // Repair what was found (default: false, report only)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct GetMemoryStatsParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    compass_reference: Option<String>,
+    business_id: Option<String>,  // Break down only this business (default: every business)
     #[serde(skip_serializing_if = "Option::is_none")]
-    magnetic_declination: Option<f64>,
+    include_businesses: Option<bool>,  // Include the per-business breakdown (default: true)
     #[serde(skip_serializing_if = "Option::is_none")]
-    building_description: Option<String>,
+    max_businesses: Option<usize>,  // Largest businesses listed in the breakdown (default: 50)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct GetEmbeddingMetadataParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    building_floors: Option<i32>,
+    memory_type: Option<String>,  // Only this collection (default: all)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct SetEmbeddingMetadataParam {
+    memory_type: String,  // Collection, e.g., "products", "preferences", "waypoints"
     #[serde(skip_serializing_if = "Option::is_none")]
-    business_floor: Option<i32>,
+    embedding_model: Option<String>,  // Default: the configured model
     #[serde(skip_serializing_if = "Option::is_none")]
-    building_color: Option<String>,
+    dimensions: Option<usize>,  // Default: [embedding] dimensions
+}
+
+// Import parameters
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct ImportMemoriesParam {
+    source: String,  // "mem0", "zep", "langchain"
+    customer_id: String,
+    export: serde_json::Value,  // The exported JSON document from the source system
     #[serde(skip_serializing_if = "Option::is_none")]
-    building_size: Option<String>,
+    business_id: Option<String>,  // Required to import chat histories as communication memories
     #[serde(skip_serializing_if = "Option::is_none")]
-    main_entrance_description: Option<String>,
+    default_memory_type: Option<String>,  // Used when a record's type can't be inferred (default: "preference")
+}
+
+// Background job parameters
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct SubmitJobParam {
+    job_type: String,  // "import_memories", "backfill_embeddings", "delete_memory", "delete_memories_by_filter", "sync_shopify_catalog"
+    arguments: serde_json::Value,  // The arguments that tool takes, e.g., {"memory_id": "...", "memory_type": "business", "delete_strategy": "cascade"}
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct GetJobStatusParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    alternative_entrances: Option<String>,
+    job_id: Option<String>,  // Omit to list this session's jobs
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct CancelJobParam {
+    job_id: String,
+}
+
+// Export parameters
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct ExportCustomerDataParam {
+    customer_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    entrance_restrictions: Option<String>,
+    format: Option<String>,  // "markdown" (default), "json" (one structured document, embeddings excluded) or "csv" (one row per record)
     #[serde(skip_serializing_if = "Option::is_none")]
-    wheelchair_accessible: Option<bool>,
+    output_path: Option<String>,  // File name in the export directory to write the report to instead of returning it inline
     #[serde(skip_serializing_if = "Option::is_none")]
-    elevator_available: Option<bool>,
+    encrypt: Option<bool>,  // AES-256-GCM encrypt with the configured export key
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct ExportGraphVizParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    stairs_required: Option<bool>,
+    business_id: Option<String>,  // Export the business's subgraph (one of business_id / customer_id)
     #[serde(skip_serializing_if = "Option::is_none")]
-    accessibility_notes: Option<String>,
+    customer_id: Option<String>,  // Export the customer's subgraph
     #[serde(skip_serializing_if = "Option::is_none")]
-    parking_available: Option<bool>,
+    format: Option<String>,  // "dot" (GraphViz, default) or "cytoscape" (Cytoscape.js elements JSON)
     #[serde(skip_serializing_if = "Option::is_none")]
-    parking_description: Option<String>,
+    memory_types: Option<Vec<String>>,  // Node types to include (default all); the root node is always included
     #[serde(skip_serializing_if = "Option::is_none")]
-    public_transport_notes: Option<String>,
+    max_nodes: Option<usize>,  // Default 500
     #[serde(skip_serializing_if = "Option::is_none")]
-    direction_varies_by_hours: Option<bool>,
+    output_path: Option<String>,  // File name in the export directory to write the graph to instead of returning it inline
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct FetchResultPageParam {
+    cursor: String,  // pagination.next_cursor from the previous page
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct ForgetCustomerParam {
+    customer_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    after_hours_instructions: Option<String>,
-    navigation_summary: String,  // Used for embedding
+    reason: Option<String>,  // Request reference recorded in the audit trail (e.g. "GDPR request #1234")
 }
 
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
-struct CreateNavigationWaypointParam {
-    navigation_id: String,
-    waypoint_name: String,
-    waypoint_type: String,  // "landmark", "turn", "intersection", "building", "sign"
+struct ExportEmbeddingDatasetParam {
+    memory_types: Vec<String>,  // e.g., ["products", "preferences", "communications"]
     #[serde(skip_serializing_if = "Option::is_none")]
-    waypoint_category: Option<String>,
-    description: String,  // Used for embedding
+    business_id: Option<String>,  // Required for business memory types
     #[serde(skip_serializing_if = "Option::is_none")]
-    visual_cues: Option<String>,
+    customer_id: Option<String>,  // Required for customer memory types
     #[serde(skip_serializing_if = "Option::is_none")]
-    audio_cues: Option<String>,
+    format: Option<String>,  // "jsonl" (default) or "csv" (metadata as columns, vector space-separated)
     #[serde(skip_serializing_if = "Option::is_none")]
-    relative_position: Option<String>,
+    output_path: Option<String>,  // File name in the export directory to write the dataset to instead of returning it inline
     #[serde(skip_serializing_if = "Option::is_none")]
-    distance_from_main: Option<i32>,
+    encrypt: Option<bool>,  // AES-256-GCM encrypt with the configured export key
+}
+
+// Advanced: Direct query execution
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct DoQueryParam {
+    endpoint: String,  // Query name from queries.hx (e.g., "get_business_products")
+    payload: serde_json::Value,  // JSON object with query parameters
+}
+
+// Graph traversal parameters
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct TraversalConnectionParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    floor_level: Option<i32>,
+    connection_id: Option<String>,  // Default: this session's managed connection
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct TraversalRange {
+    start: usize,
+    end: usize,
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct TraversalCollectParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    compass_direction: Option<String>,
+    connection_id: Option<String>,  // Default: this session's managed connection
     #[serde(skip_serializing_if = "Option::is_none")]
-    compass_bearing: Option<f64>,
+    range: Option<TraversalRange>,  // Slice of the results
     #[serde(skip_serializing_if = "Option::is_none")]
-    compass_distance: Option<f64>,
+    drop: Option<bool>,  // Close the connection after collecting
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct NFromTypeParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    business_specific_notes: Option<String>,
+    connection_id: Option<String>,  // Default: this session's managed connection
+    node_type: String,  // e.g., "BusinessProductMemory"
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct EFromTypeParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    accessibility_info: Option<String>,
+    connection_id: Option<String>,  // Default: this session's managed connection
+    edge_type: String,  // e.g., "AboutCustomer"
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct TraversalStepParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    seasonal_availability: Option<String>,
+    connection_id: Option<String>,  // Default: this session's managed connection
+    edge_label: String,  // e.g., "HasProductEmbedding"
+    edge_type: String,  // "node" or "vec"
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct TraversalEdgeStepParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    time_restrictions: Option<String>,
+    connection_id: Option<String>,  // Default: this session's managed connection
+    edge_label: String,
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct FilterItemsParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    weather_dependent: Option<bool>,
+    connection_id: Option<String>,  // Default: this session's managed connection
+    filter: serde_json::Value,  // HelixDB filter object
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct TraversalSearchParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    priority_level: Option<i32>,
+    connection_id: Option<String>,  // Default: this session's managed connection
+    query: String,
+    label: String,  // Vector or node type to search
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,  // search_keyword only (default: 10)
 }
 
+// Search and insights parameters
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
-struct CreateDirectionPathParam {
-    navigation_id: String,
-    path_name: String,
-    path_type: String,  // "primary", "alternative", "accessible", "emergency"
+struct SearchSemanticParam {
+    query: String,
+    memory_types: Vec<String>,  // e.g., ["products", "preferences"]
     #[serde(skip_serializing_if = "Option::is_none")]
-    transport_mode: Option<String>,  // "walking", "driving", "cycling", "public_transport"
+    business_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    estimated_duration_minutes: Option<i32>,
+    customer_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    difficulty_level: Option<String>,
+    channel: Option<String>,  // Only memories from these channels, comma-separated (e.g. "whatsapp", "store")
     #[serde(skip_serializing_if = "Option::is_none")]
-    distance_meters: Option<i32>,
+    limit: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    starting_compass_bearing: Option<f64>,
+    rerank: Option<bool>,  // Reorder candidates with the rerank provider (default: [rerank] enabled)
     #[serde(skip_serializing_if = "Option::is_none")]
-    ending_compass_bearing: Option<f64>,
+    min_score: Option<f64>,  // Drop hits with a similarity score below this (0.0-1.0)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct SearchSemanticBatchParam {
+    queries: Vec<String>,  // Up to 20 search queries
+    memory_types: Vec<String>,  // Searched for every query
     #[serde(skip_serializing_if = "Option::is_none")]
-    path_compass_waypoints: Option<String>,  // JSON array of compass bearings
+    business_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    suitable_for_mobility_aids: Option<bool>,
+    customer_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    suitable_for_children: Option<bool>,
+    channel: Option<String>,  // Only memories from these channels, comma-separated (e.g. "whatsapp", "store")
     #[serde(skip_serializing_if = "Option::is_none")]
-    suitable_in_rain: Option<bool>,
+    limit: Option<i32>,  // Per query (default: 10)
     #[serde(skip_serializing_if = "Option::is_none")]
-    suitable_at_night: Option<bool>,
+    rerank: Option<bool>,  // Rerank each query's candidates (default: [rerank] enabled)
     #[serde(skip_serializing_if = "Option::is_none")]
-    requires_appointment: Option<bool>,
+    min_score: Option<f64>,  // Drop hits with a similarity score below this (0.0-1.0)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct SearchBM25Param {
+    query: String,  // Keyword search query
+    memory_types: Vec<String>,  // e.g., ["products", "services", "preferences"]
     #[serde(skip_serializing_if = "Option::is_none")]
-    requires_security_clearance: Option<bool>,
+    business_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    visitor_badge_required: Option<bool>,
-    step_by_step_instructions: String,  // Used for embedding
+    customer_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    quick_summary: Option<String>,
+    channel: Option<String>,  // Only memories from these channels, comma-separated (e.g. "whatsapp", "store")
     #[serde(skip_serializing_if = "Option::is_none")]
-    is_recommended: Option<bool>,
+    limit: Option<i32>,  // Default: 10
 }
 
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
-struct QueryNavigationParam {
+struct FindCustomerInsightsParam {
     #[serde(skip_serializing_if = "Option::is_none")]
-    business_id: Option<String>,
+    customer_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    navigation_id: Option<String>,
+    product_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    include_waypoints: Option<bool>,
+    service_id: Option<String>,
+    relationship_type: String,  // "liked", "disliked", "used_service", "visited_location", "all"
+}
+
+// Customer Interaction parameters
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct CreateCustomerProductInteractionParam {
+    customer_id: String,
+    product_id: String,
+    interaction_type: String,  // "liked", "disliked", "purchased", "viewed", "favorited", "reviewed"
     #[serde(skip_serializing_if = "Option::is_none")]
-    include_paths: Option<bool>,
+    rating: Option<i32>,  // Rating if applicable (1-5 scale)
     #[serde(skip_serializing_if = "Option::is_none")]
-    filter_accessible_only: Option<bool>,
+    channel: Option<String>,  // "whatsapp", "website", "store", etc.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_duration: Option<i32>,  // How long customer engaged (seconds)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    purchase_amount: Option<f64>,  // Amount spent if purchased
+    #[serde(skip_serializing_if = "Option::is_none")]
+    currency: Option<String>,  // Currency of purchase
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issue_category: Option<String>,  // For dislikes: "quality", "price", "functionality", "service"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolution_status: Option<String>,  // For issues: "resolved", "pending", "escalated"
+    text_reason: String,  // Natural language reason for interaction (used for embedding)
 }
 
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
-struct SearchNavigationParam {
-    query: String,
-    search_types: Vec<String>,  // e.g., ["hubs", "waypoints", "paths"]
+struct CreateCustomerServiceInteractionParam {
+    customer_id: String,
+    service_id: String,
+    interaction_type: String,  // "booked", "completed", "reviewed", "canceled"
     #[serde(skip_serializing_if = "Option::is_none")]
-    business_id: Option<String>,
+    satisfaction_rating: Option<i32>,  // Rating (1-5 scale)
     #[serde(skip_serializing_if = "Option::is_none")]
-    limit: Option<i32>,
+    duration_actual: Option<i32>,  // Actual duration in minutes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost_actual: Option<f64>,  // Actual cost paid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    currency: Option<String>,  // Currency code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outcome: Option<String>,  // Service outcome
+    text_feedback: String,  // Natural language feedback (used for embedding)
 }
 
-// Information Relationship Parameters
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
-struct ManageInformationRelationshipsParam {
-    /// The operation to perform (create_related, create_prerequisite, create_series, create_reference, link_to_product, link_to_service, link_to_location, link_to_event)
-    operation: String,
-    /// ID of the source information document (required for most operations)
-    from_info_id: String,
-    /// ID of the target information document (required for document-to-document relationships)
-    to_info_id: String,
-    /// Name of the series (required for create_series operation)
-    #[serde(default)]
-    series_name: Option<String>,
-    /// ID of the business asset (required for link operations: link_to_product, link_to_service, link_to_location, link_to_event)
-    #[serde(default)]
-    target_business_id: Option<String>,
-    /// Type of relationship (optional, defaults to "related")
-    #[serde(default)]
-    relationship_type: Option<String>,
-    /// Type of reference (optional, defaults to "citation")
-    #[serde(default)]
-    reference_type: Option<String>,
-    /// Page section for references (optional)
-    #[serde(default)]
-    page_section: Option<String>,
-    /// Type of information link (optional, defaults vary by operation)
-    #[serde(default)]
-    info_type: Option<String>,
-    /// Additional notes (optional)
-    #[serde(default)]
-    notes: Option<String>,
-    /// Strength of relationship (1-10, defaults to 1)
-    strength: i32,
-    /// Order in series (optional, defaults to 1)
-    #[serde(default)]
-    order: Option<i32>,
+struct SetLoyaltyRuleParam {
+    business_id: String,
+    points_per_unit: f64,  // Points per unit of currency spent (e.g. 1.0 = 1 point per dollar)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    currency: Option<String>,  // Purchases are converted into this currency first (default: count any currency as is)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_purchase: Option<f64>,  // Smallest purchase that earns points (default: 0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    point_value: Option<f64>,  // Value of one point when redeemed (default: 0.01)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    active: Option<bool>,  // false pauses accrual (default: true)
 }
 
 #[derive(Deserialize, Serialize, schemars::JsonSchema)]
-struct QueryInformationRelationshipsParam {
-    operation: String,
-    #[serde(default)]
-    from_info_id: Option<String>,
-    #[serde(default)]
-    series_name: Option<String>,
-    #[serde(default)]
-    target_business_id: Option<String>,
+struct GetCustomerPointsParam {
+    business_id: String,
+    customer_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,  // Recent transactions listed, newest first (default: 20)
 }
 
-#[derive(Clone)]
-pub struct HelixMcpServer {
-    helix_client: Arc<HelixClient>,
-    config: Arc<Config>,
-    tool_router: ToolRouter<Self>,
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct RedeemPointsParam {
+    business_id: String,
+    customer_id: String,
+    points: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,  // What the points paid for, e.g. "Free coffee"
 }
 
-#[tool_router]
-impl HelixMcpServer {
-    fn new(helix_client: Arc<HelixClient>, config: Arc<Config>) -> Self {
-        Self {
-            helix_client,
-            config,
-            tool_router: Self::tool_router(),
-        }
-    }
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct QueryCustomerInteractionsParam {
+    customer_id: String,
+    interaction_type: String,  // "product", "service", "visit", "all"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filters: Option<serde_json::Value>,  // Field matches, {"gte"/"lte"} ranges, "since"/"until" timestamps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channel: Option<String>,  // Only memories from these channels, comma-separated (e.g. "whatsapp", "store")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort_by: Option<String>,  // Field to sort by (e.g., "created_at", "price", "name")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order: Option<String>,  // "asc" (default) or "desc"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,  // Maximum results to return (per memory type)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<usize>,  // Results to skip before limit is applied
+}
 
-    // Helper function to normalize memory_type to SINGULAR (for create/update/delete operations)
-    fn normalize_memory_type(memory_type: &str) -> &str {
-        match memory_type {
-            // Plural to singular for create/update/delete operations
-            "products" => "product",
-            "services" => "service",
-            "locations" => "location",
-            "policies" => "policy",
-            "events" => "event",
-            // Plural to singular for customer operations
-            "behaviors" => "behavior",
-            "preferences" => "preference",
-            "desires" => "desire",
-            "rules" => "rule",
-            // Already singular or special cases
-            _ => memory_type
-        }
-    }
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct AnalyzeInteractionsParam {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    customer_id: Option<String>,  // Scope to one customer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_id: Option<String>,  // Scope to interactions with a business's products and services
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interaction_type: Option<String>,  // "product", "service", "all" (default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bucket: Option<String>,  // Trend bucket: "day", "week", "month" (default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_n: Option<usize>,  // Number of top products to return (default: 5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filters: Option<serde_json::Value>,  // Same filters as query_customer_interactions (e.g., since/until)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    differential_privacy: Option<bool>,  // Add Laplace noise (epsilon from [differential_privacy] config)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reporting_currency: Option<String>,  // Convert spend into this currency (default: [currency] reporting_currency)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<String>,  // "json" (default) or "csv" (section,key,value rows as text)
+}
 
-    // Helper function to normalize memory_type to PLURAL (for query operations)
-    fn normalize_to_plural(memory_type: &str) -> &str {
-        match memory_type {
-            // Singular to plural for query operations
-            "product" => "products",
-            "service" => "services",
-            "location" => "locations",
-            "policy" => "policies",
-            "event" => "events",
-            // Singular to plural for customer operations
-            "behavior" => "behaviors",
-            "preference" => "preferences",
-            "desire" => "desires",
-            "rule" => "rules",
-            // Already plural or special cases
-            _ => memory_type
-        }
-    }
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct ExplainRelationshipParam {
+    customer_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    product_id: Option<String>,  // Target product (or service_id)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    service_id: Option<String>,  // Target service (or product_id)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_paths: Option<usize>,  // Default: 20
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct GetProductAffinitiesParam {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    product_id: Option<String>,  // Affinities of one product
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_id: Option<String>,  // Affinities of every product in the catalog (with product_id: restrict results to the catalog)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interaction_types: Option<Vec<String>>,  // Positive signals (default: liked, favorited, purchased)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_support: Option<usize>,  // Minimum shared customers (default: 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_n: Option<usize>,  // Affinities per product (default: 10)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct SummarizeFeedbackParam {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    customer_id: Option<String>,  // Scope to one customer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_id: Option<String>,  // Scope to all customers linked to a business
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_n: Option<usize>,  // Number of recent negative items to return (default: 5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cluster_themes: Option<bool>,  // Group feedback into themes by embedding similarity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    theme_threshold: Option<f64>,  // Similarity needed to join a theme (default: 0.8)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filters: Option<serde_json::Value>,  // Same filters as query_customer_memory (e.g., since/until)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    differential_privacy: Option<bool>,  // Add Laplace noise and withhold individual items
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct ListOpenIssuesParam {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    customer_id: Option<String>,  // Issues of one customer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_id: Option<String>,  // Issues of all customers linked to a business
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,  // Maximum issues to return, oldest first (default: 50)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct ResolveIssueParam {
+    memory_id: String,  // Internal ID of the issue, from list_open_issues
+    kind: String,  // "product_interaction" or "feedback"
+    business_id: String,  // Business the resolution note is stored under
+    resolution_note: String,  // How the issue was resolved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    agent_id: Option<String>,  // Who resolved it
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct CreateAlertRuleParam {
+    name: String,  // Rule name; an existing rule with this name is replaced
+    when: Vec<String>,  // Conditions that must all hold, e.g. ["rating <= 2"], ["purchase_amount > 500"]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory_type: Option<String>,  // e.g. "interaction", "feedback", "product" (default: any)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_id: Option<String>,  // Only writes of this business
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct EvaluateCustomerRulesParam {
+    customer_id: String,
+    action: String,  // Proposed action in plain words, e.g. "send promo at 9pm", "recommend product category dairy"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channel: Option<String>,  // Channel the action would use: email, phone, sms, whatsapp, push
+    #[serde(skip_serializing_if = "Option::is_none")]
+    at: Option<String>,  // When it would happen: "21:00", "9pm" or RFC3339 (customer local time)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct IsBusinessOpenParam {
+    business_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    at: Option<String>,  // RFC3339 instant, or "YYYY-MM-DD HH:MM" in business local time (default: now)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    utc_offset: Option<String>,  // Override the stored timezone (e.g., "+02:00", "EDT")
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct GetServiceAvailabilityParam {
+    business_id: String,
+    service_id: String,
+    date: String,  // YYYY-MM-DD in business local time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<String>,  // Requested start, e.g. "15:00" or "3pm"; checked against the open slots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    step_minutes: Option<i64>,  // Minutes between slot starts (default: 30)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    capacity: Option<usize>,  // Bookings the service can take at once (default: 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    utc_offset: Option<String>,  // Override the stored timezone (e.g., "+02:00", "EDT")
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct HoursExceptionInput {
+    date: String,  // YYYY-MM-DD
+    #[serde(skip_serializing_if = "Option::is_none")]
+    closed: Option<bool>,  // true = closed all day (do not combine with open/close)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    open: Option<String>,  // Special opening time, e.g., "10:00"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    close: Option<String>,  // Special closing time, e.g., "14:00"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,  // e.g., "Christmas Day"
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct UpdateHoursExceptionsParam {
+    business_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hours_id: Option<String>,  // Hours memory to update (default: the regular schedule)
+    #[serde(default)]
+    exceptions: Vec<HoursExceptionInput>,  // Exceptions to add or change
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remove_dates: Option<Vec<String>>,  // Dates (YYYY-MM-DD) whose exceptions to remove
+    #[serde(skip_serializing_if = "Option::is_none")]
+    overwrite: Option<bool>,  // Replace conflicting existing exceptions (default: false, report conflicts)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct ListUpcomingEventsParam {
+    business_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<String>,  // Window start: RFC3339, YYYY-MM-DD or Unix seconds (default: now)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<String>,  // Window end (default: no limit)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    days_ahead: Option<i64>,  // Window length in days when `to` is not given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_capacity_remaining: Option<i64>,  // Only events with at least this many places left
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,  // Max events (default: 20)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct ClosePastEventsParam {
+    business_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    before: Option<String>,  // Close events that ended before this time (default: now)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dry_run: Option<bool>,  // List what would be closed without changing anything
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct GetEffectivePolicyParam {
+    business_id: String,
+    policy_type: String,  // e.g., "return", "refund", "shipping"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<String>,  // RFC3339, YYYY-MM-DD or Unix seconds (default: now)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct SupersedePolicyParam {
+    business_id: String,
+    policy_id: String,  // Version being replaced
+    content: String,  // Full text of the new version
+    text_description: String,  // Required for embedding generation in MCP mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    effective_date: Option<String>,  // When the new version takes effect (default: now)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,  // Default: previous version bumped (e.g., "1.2" -> "1.3")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    policy_name: Option<String>,  // Default: previous version's name
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct SearchCustomerInteractionsParam {
+    query: String,
+    interaction_types: Vec<String>,  // e.g., ["product", "service"]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    customer_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<i32>,
+}
+
+// Navigation System parameters
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct CreateNavigationHubParam {
+    business_id: String,
+    navigation_id: String,
+    primary_address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secondary_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    building_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    building_type: Option<String>,
+    latitude: f64,
+    longitude: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    what3words_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    plus_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compass_bearing: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compass_reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    magnetic_declination: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    building_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    building_floors: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_floor: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    building_color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    building_size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    main_entrance_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alternative_entrances: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entrance_restrictions: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wheelchair_accessible: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    elevator_available: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stairs_required: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accessibility_notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parking_available: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parking_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public_transport_notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    direction_varies_by_hours: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    after_hours_instructions: Option<String>,
+    navigation_summary: String,  // Used for embedding
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct CreateNavigationWaypointParam {
+    navigation_id: String,
+    waypoint_name: String,
+    waypoint_type: String,  // "landmark", "turn", "intersection", "building", "sign"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    waypoint_category: Option<String>,
+    description: String,  // Used for embedding
+    #[serde(skip_serializing_if = "Option::is_none")]
+    visual_cues: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    audio_cues: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    relative_position: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    distance_from_main: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    floor_level: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compass_direction: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compass_bearing: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compass_distance: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_specific_notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accessibility_info: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seasonal_availability: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_restrictions: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weather_dependent: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    priority_level: Option<i32>,
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct CreateDirectionPathParam {
+    navigation_id: String,
+    path_name: String,
+    path_type: String,  // "primary", "alternative", "accessible", "emergency"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transport_mode: Option<String>,  // "walking", "driving", "cycling", "public_transport"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimated_duration_minutes: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    difficulty_level: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    distance_meters: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    starting_compass_bearing: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ending_compass_bearing: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path_compass_waypoints: Option<String>,  // JSON array of compass bearings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suitable_for_mobility_aids: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suitable_for_children: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suitable_in_rain: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suitable_at_night: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    requires_appointment: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    requires_security_clearance: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    visitor_badge_required: Option<bool>,
+    step_by_step_instructions: String,  // Used for embedding
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quick_summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_recommended: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    waypoint_ids: Option<Vec<String>>,  // Waypoints the path passes through, in order
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct CreateNavigationFloorParam {
+    navigation_id: String,
+    floor_level: i32,  // Matches waypoint floor_level (0 = ground, negative = basement)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    floor_name: Option<String>,  // "Mezzanine", "Level 3 - Offices"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct CreateFloorConnectorParam {
+    navigation_id: String,
+    from_waypoint_id: String,  // Waypoint where the connector is boarded
+    to_waypoint_id: String,  // Waypoint where it arrives, on another floor
+    connector_type: String,  // "elevator", "escalator", "ramp", "stairs"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_accessible: Option<bool>,  // Usable with mobility aids (default: true for elevator/ramp)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bidirectional: Option<bool>,  // Also connect to -> from (default: true, false for escalator)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct LocateCustomerParam {
+    description: String,  // What the customer sees or hears: "next to a red pharmacy sign"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    navigation_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_id: Option<String>,  // Used to find the navigation hub when navigation_id is omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alternatives: Option<usize>,  // Other candidate waypoints to return (default: 2)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct StartLocationSharingParam {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    navigation_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_id: Option<String>,  // Used to find the navigation hub when navigation_id is omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path_id: Option<String>,  // Route to follow, waypoint by waypoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    destination_waypoint_id: Option<String>,  // Single destination when there's no path (default: main entrance)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    customer_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ttl_minutes: Option<u64>,  // Session lifetime (default from [live_location])
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct LocationShareParam {
+    share_id: String,
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct ImportNavigationGeojsonParam {
+    navigation_id: String,
+    geojson: serde_json::Value,  // FeatureCollection with Point (waypoint) and LineString (path) features
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transport_mode: Option<String>,  // Default for paths without a transport_mode property (default: "walking")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dry_run: Option<bool>,  // Return what would be created without writing
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct GetRouteParam {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path_id: Option<String>,  // Specific path; otherwise the best matching path is chosen
+    #[serde(skip_serializing_if = "Option::is_none")]
+    navigation_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transport_mode: Option<String>,  // "walking", "driving", "wheelchair", "public_transport"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accessible_only: Option<bool>,  // Only paths suitable for mobility aids
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_optional: Option<bool>,  // Include optional waypoints (default: true)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct QueryNavigationParam {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    navigation_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_waypoints: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_paths: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter_accessible_only: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct SearchNavigationParam {
+    query: String,
+    search_types: Vec<String>,  // e.g., ["hubs", "waypoints", "paths"]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<i32>,
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct SearchNearbyParam {
+    latitude: f64,
+    longitude: f64,
+    radius_meters: f64,  // Search radius around the point
+    #[serde(skip_serializing_if = "Option::is_none")]
+    search_types: Option<Vec<String>>,  // "locations", "hubs" (default: both)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_id: Option<String>,  // Limit to one business (default: all businesses)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,  // Maximum results (default: 20)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct ComputeBearingParam {
+    latitude: f64,   // User's current position
+    longitude: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    business_id: Option<String>,  // Look up the hub by business (preferred)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    navigation_id: Option<String>,  // Or by navigation hub ID
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct GetAccessLogParam {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    customer_id: Option<String>,  // Reads made about this customer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_call_id: Option<String>,  // MCP request ID of one tool call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    since: Option<serde_json::Value>,  // Unix seconds, RFC3339 or YYYY-MM-DD
+    #[serde(skip_serializing_if = "Option::is_none")]
+    until: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,  // Maximum entries, newest first (default: 50)
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct SetSandboxModeParam {
+    enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    discard: Option<bool>,  // When disabling, delete the sandbox's memories now (default: true)
+}
+
+// Information Relationship Parameters
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct ManageInformationRelationshipsParam {
+    /// The operation to perform (create_related, create_prerequisite, create_series, create_reference, link_to_product, link_to_service, link_to_location, link_to_event)
+    operation: String,
+    /// ID of the source information document (required for most operations)
+    from_info_id: String,
+    /// ID of the target information document (required for document-to-document relationships)
+    to_info_id: String,
+    /// Name of the series (required for create_series operation)
+    #[serde(default)]
+    series_name: Option<String>,
+    /// ID of the business asset (required for link operations: link_to_product, link_to_service, link_to_location, link_to_event)
+    #[serde(default)]
+    target_business_id: Option<String>,
+    /// Type of relationship (optional, defaults to "related")
+    #[serde(default)]
+    relationship_type: Option<String>,
+    /// Type of reference (optional, defaults to "citation")
+    #[serde(default)]
+    reference_type: Option<String>,
+    /// Page section for references (optional)
+    #[serde(default)]
+    page_section: Option<String>,
+    /// Type of information link (optional, defaults vary by operation)
+    #[serde(default)]
+    info_type: Option<String>,
+    /// Additional notes (optional)
+    #[serde(default)]
+    notes: Option<String>,
+    /// Strength of relationship (1-10, defaults to 1)
+    strength: i32,
+    /// Order in series (optional, defaults to 1)
+    #[serde(default)]
+    order: Option<i32>,
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+struct QueryInformationRelationshipsParam {
+    operation: String,
+    #[serde(default)]
+    from_info_id: Option<String>,
+    #[serde(default)]
+    series_name: Option<String>,
+    #[serde(default)]
+    target_business_id: Option<String>,
+}
+
+/// Queries do_query may call (security - prevent dangerous operations)
+const DO_QUERY_ENDPOINTS: &[&str] = &[
+        // Business product queries
+        "add_business_product_memory",
+        "get_business_products",
+        "search_business_products",
+        "search_business_products_hybrid",
+        "update_product_price",
+        "update_product_availability",
+        "update_product_full",
+        "delete_product",
+        "delete_product_with_embedding",
+        
+        // Business service queries
+        "add_business_service_memory",
+        "get_business_services",
+        "search_business_services",
+        "update_service_price",
+        "update_service_availability",
+        "update_service_full",
+        "delete_service",
+        "delete_service_with_embedding",
+        
+        // Business location queries
+        "add_business_location_memory",
+        "get_business_locations",
+        "update_location_address",
+        "delete_location",
+        "delete_location_with_embedding",
+        
+        // Business hours queries
+        "add_business_hours_memory",
+        "get_business_hours",
+        "update_business_hours_monday",
+        "delete_hours",
+        "delete_hours_with_embedding",
+        
+        // Business social media queries
+        "add_business_social_media_memory",
+        "get_business_social_media",
+        "update_social_stats",
+        "delete_social",
+        "delete_social_with_embedding",
+        
+        // Business policy queries
+        "add_business_policy_memory",
+        "get_business_policies",
+        "update_policy_content",
+        "delete_policy",
+        "delete_policy_with_embedding",
+        
+        // Business event queries
+        "add_business_event_memory",
+        "get_business_events",
+        "update_event_dates",
+        "delete_event",
+        "delete_event_with_embedding",
+
+        // Business information queries
+        "add_business_information_memory",
+        "get_business_information",
+        "update_business_information_memory",
+        "delete_information",
+        "delete_information_with_embedding",
+        "delete_all_business_information",
+
+        // Information relationship queries
+        "link_related_information",
+        "link_prerequisite_information",
+        "link_series_information",
+        "link_reference_information",
+        "link_information_to_product",
+        "link_information_to_service",
+        "link_information_to_location",
+        "link_information_to_event",
+        "get_related_information",
+        "get_prerequisites_for_info",
+        "get_dependent_information",
+        "get_series_information",
+        "get_information_references",
+        "get_referenced_by_info",
+        "get_product_information",
+        "get_service_information",
+        "get_location_information",
+        "get_event_information",
+
+        // Customer behavior queries
+        "add_customer_behavior_memory",
+        "get_customer_behaviors",
+        "update_behavior_context",
+        "delete_behavior",
+        "delete_behavior_with_embedding",
+        
+        // Customer preference queries
+        "add_customer_preference_memory",
+        "get_customer_preferences",
+        "search_customer_preferences",
+        "search_customer_preferences_hybrid",
+        "update_preference_strength",
+        "delete_preference",
+        "delete_preference_with_embedding",
+        
+        // Customer desire queries
+        "add_customer_desire_memory",
+        "get_customer_desires",
+        "update_desire_priority",
+        "delete_desire",
+        "delete_desire_with_embedding",
+        
+        // Customer rule queries
+        "add_customer_rule_memory",
+        "get_customer_rules",
+        "update_rule_enforcement",
+        "delete_rule",
+        "delete_rule_with_embedding",
+        
+        // Customer feedback queries
+        "add_customer_feedback_memory",
+        "get_customer_feedback",
+        "update_feedback_rating",
+        "delete_feedback",
+        "delete_feedback_with_embedding",
+
+        // Customer communication queries
+        "add_customer_communication_memory",
+        "get_customer_communications",
+        "search_customer_communication_semantic",
+        "search_customer_communication_hybrid",
+
+        // Embedding export queries
+        "export_business_products_embeddings",
+        "export_business_services_embeddings",
+        "export_business_locations_embeddings",
+        "export_business_hours_embeddings",
+        "export_business_social_embeddings",
+        "export_business_policies_embeddings",
+        "export_business_events_embeddings",
+        "export_business_information_embeddings",
+        "export_customer_behaviors_embeddings",
+        "export_customer_preferences_embeddings",
+        "export_customer_desires_embeddings",
+        "export_customer_rules_embeddings",
+        "export_customer_feedback_embeddings",
+        "export_customer_communications_embeddings",
+
+        // Interaction analytics queries
+        "get_customer_product_interactions",
+        "get_customer_service_interactions",
+        "get_product_interactions_by_product",
+        "get_service_interactions_by_service",
+        "get_business_customers",
+
+        // Geo queries
+        "get_all_business_locations",
+        "get_all_navigation_hubs",
+        "get_navigation_hub",
+
+        // Route queries
+        "link_path_waypoint",
+        "get_path_route",
+
+        // Customer profile queries
+        "get_customer_profile",
+
+        // Hours exception queries
+        "update_business_hours_exceptions",
+
+        // Embedding metadata queries
+        "get_embedding_metadata",
+        "add_embedding_metadata",
+        "update_embedding_metadata",
+
+        // Policy version queries
+        "supersede_policy_version",
+
+        // Event lifecycle queries
+        "update_event_registrations",
+        "update_event_status",
+
+        // Floor queries
+        "add_navigation_floor",
+        "get_navigation_floors",
+        "link_floor_waypoint",
+        "add_floor_connector",
+        "get_floor_connectors",
+        "find_similar_navigation_waypoints",
+
+        // Interaction and navigation deletes
+        "delete_interaction",
+        "delete_interaction_with_embedding",
+        "delete_navigation_hub",
+        "delete_navigation_hub_with_embedding",
+        "delete_waypoint",
+        "delete_waypoint_with_embedding",
+        "delete_path",
+        "delete_path_with_embedding",
+
+        // Delete previews
+        "preview_business_delete",
+        "preview_customer_delete",
+];
+
+#[derive(Clone)]
+pub struct HelixMcpServer {
+    helix_client: Arc<HelixClient>,
+    config: Arc<Config>,
+    embedding_gate: Arc<priority::PriorityGate>,
+    query_cache: Arc<embedding_cache::QueryEmbeddingCache>,
+    working_memory: Arc<working_memory::WorkingMemory>,
+    session_id: Arc<str>,
+    budget: Arc<budget::SessionBudget>,
+    sandbox: Arc<sandbox::SandboxRegistry>,
+    access_log: Arc<access_log::AccessLog>,
+    clock: Arc<dyn clock::Clock>,
+    ids: Arc<dyn clock::IdGenerator>,
+    corpus_models: Arc<embedding_meta::CorpusModels>,
+    webhooks: Arc<webhooks::Webhooks>,
+    alerts: Arc<alerts::AlertRules>,
+    live: Arc<live::LiveSessions>,
+    changes: Arc<change_feed::ChangeFeed>,
+    subscriptions: Arc<resources::Subscriptions>,
+    traversal_sessions: Arc<session::TraversalSessions>,
+    idempotency: Arc<idempotency::IdempotencyStore>,
+    jobs: Arc<jobs::JobQueue>,
+    maintenance: Arc<maintenance::MaintenanceLog>,
+    query_manifest: Arc<Option<query_manifest::QueryManifest>>,
+    result_pages: Arc<result_pages::ResultPages>,
+    tcp_embedder: Option<Arc<embedding_client::EmbeddingClient>>,
+    provider_model: Arc<std::sync::OnceLock<embedding_meta::ProviderModel>>,
+    exchange_rates: Arc<currency::ExchangeRates>,
+    redemption_locks: Arc<loyalty::RedemptionLocks>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl HelixMcpServer {
+    fn new(helix_client: Arc<HelixClient>, config: Arc<Config>) -> Self {
+        let embedding_gate = Arc::new(priority::PriorityGate::new(config.priority.background_concurrency));
+        let query_cache = Arc::new(embedding_cache::QueryEmbeddingCache::new(
+            config.query_cache.capacity,
+            std::time::Duration::from_secs(config.query_cache.ttl_secs),
+        ));
+        let working_memory = Arc::new(working_memory::WorkingMemory::new(
+            std::time::Duration::from_secs(config.session_memory.ttl_secs),
+            config.session_memory.max_notes,
+        ));
+        let budget = Arc::new(budget::SessionBudget::new(config.budget.limits()));
+        let sandbox = Arc::new(sandbox::SandboxRegistry::new(
+            std::time::Duration::from_secs(config.sandbox.ttl_secs),
+        ));
+        let (clock, ids): (Arc<dyn clock::Clock>, Arc<dyn clock::IdGenerator>) = if config.deterministic.enabled {
+            (
+                Arc::new(clock::SteppingClock::new(config.deterministic.start_timestamp, config.deterministic.step_secs)),
+                Arc::new(clock::SequentialIds::new()),
+            )
+        } else {
+            (Arc::new(clock::SystemClock), Arc::new(clock::RandomIds))
+        };
+        let session_id: Arc<str> = Arc::from(ids.next_id().to_string());
+        if config.sandbox.always_on {
+            sandbox.enable(&session_id);
+        }
+        let access_log = Arc::new(access_log::AccessLog::new(
+            Some(&config.access_log.path).filter(|p| !p.is_empty()).map(std::path::PathBuf::from),
+            config.access_log.memory_capacity,
+        ));
+        let webhooks = Arc::new(webhooks::Webhooks::new(config.webhooks.clone()));
+        let (alerts, invalid_rules) = alerts::AlertRules::new(&config.alerts.rules);
+        for e in invalid_rules {
+            warn!("Skipping alert rule: {}", e);
+        }
+        let traversal_sessions = Arc::new(session::TraversalSessions::new(
+            std::time::Duration::from_secs(config.traversal.session_ttl_secs),
+            config.traversal.max_sessions,
+        ));
+        let idempotency = Arc::new(idempotency::IdempotencyStore::new(
+            std::time::Duration::from_secs(config.idempotency.ttl_secs),
+            config.idempotency.max_keys,
+        ));
+        let jobs = Arc::new(jobs::JobQueue::new(
+            config.jobs.max_concurrent,
+            std::time::Duration::from_secs(config.jobs.retention_secs),
+            clock.clone(),
+        ));
+        let manifest_path = &config.do_query.manifest_path;
+        let query_manifest = match query_manifest::QueryManifest::load(manifest_path) {
+            _ if manifest_path.is_empty() => None,
+            Ok(manifest) => {
+                info!("Loaded do_query schemas for {} endpoints from {}", manifest.len(), manifest_path);
+                Some(manifest)
+            }
+            Err(e) => {
+                warn!("do_query payloads will not be validated: {}", e);
+                None
+            }
+        };
+        let tcp_embedder = config.embedding.tcp_address.as_ref().map(|address| {
+            Arc::new(
+                embedding_client::EmbeddingClient::new(address.clone(), config.embedding.tcp_timeout_secs)
+                    .with_pool_size(config.embedding.tcp_pool_size)
+                    .with_dimensions(config.embedding.vector_dimensions()),
+            )
+        });
+        let result_pages = Arc::new(result_pages::ResultPages::new(std::time::Duration::from_secs(config.server.result_page_ttl_secs)));
+        let exchange_rates = Arc::new(currency::ExchangeRates::new(currency::Rates::new(&config.currency.base, &config.currency.rates, "static")));
+        Self {
+            helix_client,
+            config,
+            embedding_gate,
+            query_cache,
+            working_memory,
+            session_id,
+            budget,
+            sandbox,
+            access_log,
+            clock,
+            ids,
+            corpus_models: Arc::new(embedding_meta::CorpusModels::default()),
+            webhooks,
+            alerts: Arc::new(alerts),
+            live: Arc::new(live::LiveSessions::default()),
+            changes: Arc::new(change_feed::ChangeFeed::default()),
+            subscriptions: Arc::new(resources::Subscriptions::default()),
+            traversal_sessions,
+            idempotency,
+            jobs,
+            maintenance: Arc::new(maintenance::MaintenanceLog::default()),
+            query_manifest: Arc::new(query_manifest),
+            result_pages,
+            tcp_embedder,
+            provider_model: Arc::new(std::sync::OnceLock::new()),
+            exchange_rates,
+            redemption_locks: Arc::new(loyalty::RedemptionLocks::default()),
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    /// Clone for a new client connection with its own session state
+    pub fn new_session(&self) -> Self {
+        let session_id: Arc<str> = Arc::from(self.ids.next_id().to_string());
+        if self.config.sandbox.always_on {
+            self.sandbox.enable(&session_id);
+        }
+        Self {
+            session_id,
+            budget: Arc::new(budget::SessionBudget::new(self.config.budget.limits())),
+            subscriptions: Arc::new(resources::Subscriptions::default()),
+            ..self.clone()
+        }
+    }
+
+    // Helper function to normalize memory_type to PLURAL (for query operations)
+    fn normalize_to_plural(memory_type: &str) -> &str {
+        match memory_type {
+            // Singular to plural for query operations
+            "product" => "products",
+            "service" => "services",
+            "location" => "locations",
+            "policy" => "policies",
+            "event" => "events",
+            // Singular to plural for customer operations
+            "behavior" => "behaviors",
+            "preference" => "preferences",
+            "desire" => "desires",
+            "rule" => "rules",
+            "communication" => "communications",
+            // Already plural or special cases
+            _ => memory_type
+        }
+    }
+
+    /// Run one query per section concurrently; failures go under `errors` by section
+    async fn query_sections(&self, sections: &[(&'static str, &str)], payload: serde_json::Value) -> serde_json::Value {
+        let results = futures::future::join_all(
+            sections.iter().map(|(_, query_name)| self.helix_client.query(query_name, payload.clone())),
+        ).await;
+        fan_out::sections(sections.iter().zip(results).map(|((section, query_name), result)| {
+            if let Err(e) = &result {
+                warn!("{} failed: {}", query_name, e);
+            }
+            (*section, result)
+        }))
+    }
+
+    // ========================================================================
+    // HIGH-LEVEL DOMAIN-SPECIFIC TOOLS FOR AI MEMORY LAYER
+    // ========================================================================
+
+    #[tool(description = "Query business memories - unified access to products, services, locations, hours, social media, policies, events, and information (documentation, manuals, guides, etc.) for a specific business. Optional filters, sort_by, order (asc/desc), limit, offset")]
+    async fn query_business_memory(&self, params: Parameters<QueryBusinessMemoryParam>) -> Result<CallToolResult, McpError> {
+        let business_id = &params.0.business_id;
+        let memory_type_input = &params.0.memory_type;
+        
+        // Normalize to plural (accept both "product" and "products")
+        let memory_type = Self::normalize_to_plural(memory_type_input);
+        
+        info!("query_business_memory: business_id={}, type={} (normalized from: {})", business_id, memory_type, memory_type_input);
+
+        // Determine which query to execute based on memory_type
+        let query_name = match memory_type {
+            "products" => "get_business_products",
+            "services" => "get_business_services",
+            "locations" => "get_business_locations",
+            "hours" => "get_business_hours",
+            "social" => "get_business_social_media",
+            "policies" => "get_business_policies",
+            "events" => "get_business_events",
+            "information" => "get_business_information",
+            "all" => {
+                // Query every business memory type at once; failed sections are listed under "errors"
+                let mut all_memories = self.query_sections(&[
+                    ("products", "get_business_products"),
+                    ("services", "get_business_services"),
+                    ("locations", "get_business_locations"),
+                    ("hours", "get_business_hours"),
+                    ("social", "get_business_social_media"),
+                    ("policies", "get_business_policies"),
+                    ("events", "get_business_events"),
+                    ("information", "get_business_information"),
+                ], json!({"business_id": business_id})).await;
+
+                if let Some(filters) = &params.0.filters {
+                    all_memories = filters::apply(all_memories, filters);
+                }
+                all_memories = filters::order(all_memories, params.0.sort_by.as_deref(), params.0.order.as_deref(), params.0.limit, params.0.offset);
+
+                return Ok(CallToolResult::structured(all_memories));
+            }
+            _ => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}. Valid types: products, services, locations, hours, social, policies, events, information, all", memory_type), json!({})));
+            }
+        };
+
+        // Execute the query
+        let payload = json!({"business_id": business_id});
+        
+        match self.helix_client.query(query_name, payload).await {
+            Ok(mut results) => {
+                // Apply filters if provided
+                if let Some(filters) = &params.0.filters {
+                    results = filters::apply(results, filters);
+                }
+                results = filters::order(results, params.0.sort_by.as_deref(), params.0.order.as_deref(), params.0.limit, params.0.offset);
+                
+                Ok(CallToolResult::structured(json!({
+                    "business_id": business_id,
+                    "memory_type": memory_type,
+                    "count": HelixClient::result_items(&results).len(),
+                    "data": results
+                })))
+            }
+            Err(e) => {
+                error!("query_business_memory failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, e.to_string(), json!({})))
+            }
+        }
+    }
+
+    #[tool(description = "Query customer memories - unified access to behaviors, preferences, desires, rules, and feedback for a specific customer. Optional filters: exact field matches, {\"gte\"/\"lte\"} ranges, and since/until (unix seconds, RFC 3339 or YYYY-MM-DD) on timestamp/created_at. Optional channel (e.g. \"whatsapp\", comma-separated) keeps only memories from those channels. Optional sort_by, order (asc/desc), limit, offset")]
+    async fn query_customer_memory(&self, params: Parameters<QueryCustomerMemoryParam>) -> Result<CallToolResult, McpError> {
+        let customer_id = &params.0.customer_id;
+        let memory_type_input = &params.0.memory_type;
+        
+        // Normalize to plural (accept both "behavior" and "behaviors")
+        let memory_type = Self::normalize_to_plural(memory_type_input);
+        
+        info!("query_customer_memory: customer_id={}, type={} (normalized from: {})", customer_id, memory_type, memory_type_input);
+
+        // Determine which query to execute based on memory_type
+        let query_name = match memory_type {
+            "behaviors" => "get_customer_behaviors",
+            "preferences" => "get_customer_preferences",
+            "desires" => "get_customer_desires",
+            "rules" => "get_customer_rules",
+            "feedback" => "get_customer_feedback",
+            "all" => {
+                // Query every customer memory type at once; failed sections are listed under "errors"
+                let mut all_memories = self.query_sections(&[
+                    ("behaviors", "get_customer_behaviors"),
+                    ("preferences", "get_customer_preferences"),
+                    ("desires", "get_customer_desires"),
+                    ("rules", "get_customer_rules"),
+                    ("feedback", "get_customer_feedback"),
+                ], json!({"customer_id": customer_id})).await;
+
+                if let Some(filters) = &params.0.filters {
+                    all_memories = filters::apply(all_memories, filters);
+                }
+                if let Some(channel) = &params.0.channel {
+                    all_memories = channels::filter(all_memories, &channels::parse(channel));
+                }
+                all_memories = filters::order(all_memories, params.0.sort_by.as_deref(), params.0.order.as_deref(), params.0.limit, params.0.offset);
+                
+                return Ok(CallToolResult::structured(all_memories));
+            }
+            _ => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}. Valid types: behaviors, preferences, desires, rules, feedback, all", memory_type), json!({})));
+            }
+        };
+
+        // Execute the query
+        let payload = json!({"customer_id": customer_id});
+        
+        match self.helix_client.query(query_name, payload).await {
+            Ok(mut results) => {
+                // Apply filters if provided
+                if let Some(filters) = &params.0.filters {
+                    results = filters::apply(results, filters);
+                }
+                if let Some(channel) = &params.0.channel {
+                    results = channels::filter(results, &channels::parse(channel));
+                }
+                results = filters::order(results, params.0.sort_by.as_deref(), params.0.order.as_deref(), params.0.limit, params.0.offset);
+                
+                Ok(CallToolResult::structured(json!({
+                    "customer_id": customer_id,
+                    "memory_type": memory_type,
+                    "count": HelixClient::result_items(&results).len(),
+                    "data": results
+                })))
+            }
+            Err(e) => {
+                error!("query_customer_memory failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, e.to_string(), json!({})))
+            }
+        }
+    }
+
+    #[tool(description = "Semantic search across business and customer memories using AI embeddings - finds memories by meaning, not just keywords. Optional rerank: true reorders a wider candidate pool with the configured rerank model (Cohere or local TCP) and returns the best limit overall, each with rerank_score. Every result carries its vector similarity as score; min_score (0.0-1.0) drops weaker hits. Optional channel (e.g. \"whatsapp\", comma-separated) keeps only memories from those channels. Nodes found by more than one route are returned once, and results are grouped by memory_type with per-type counts.")]
+    async fn search_semantic(&self, params: Parameters<SearchSemanticParam>) -> Result<CallToolResult, McpError> {
+        let query = &params.0.query;
+        let memory_types_input = &params.0.memory_types;
+        let limit = params.0.limit.unwrap_or(10);
+        
+        // Normalize all memory types to plural (accept both "product" and "products"), each searched once
+        let mut memory_types: Vec<&str> = Vec::new();
+        for memory_type in memory_types_input.iter().map(|t| Self::normalize_to_plural(t.as_str())) {
+            if !memory_types.contains(&memory_type) {
+                memory_types.push(memory_type);
+            }
+        }
+        
+        info!("search_semantic: query='{}', types={:?} (normalized from: {:?}), limit={}", query, memory_types, memory_types_input, limit);
+
+        // Reranking fetches a wider candidate pool and cuts it to limit afterwards
+        let rerank = params.0.rerank.unwrap_or(self.config.rerank.enabled);
+        let candidates = if rerank { limit.max(self.config.rerank.candidates as i32) } else { limit };
+        // A channel filter over-fetches, then keeps `candidates` per type from the wanted channels
+        let wanted = params.0.channel.as_deref().map(channels::parse).unwrap_or_default();
+        let fetch_limit = if wanted.is_empty() { candidates } else { candidates.saturating_mul(channels::OVERFETCH) };
+
+        // Check embedding mode from config
+        if self.config.is_helixdb_embedding_enabled() {
+            // HelixDB mode: Just pass text query, HelixDB generates embedding via Embed()
+            info!("Using HelixDB embedding mode (Embed() function in queries)");
+            
+            let all_results = self.text_vector_search(&memory_types, query, params.0.business_id.as_deref(), params.0.customer_id.as_deref(), fetch_limit, params.0.min_score).await;
+            let mut all_results = channels::keep(all_results, &wanted, candidates.max(0) as usize);
+            let duplicates_removed = search_results::dedup(&mut all_results);
+            let (all_results, rerank_info) = self.maybe_rerank(rerank, query, all_results, limit).await;
+
+            return Ok(CallToolResult::structured(json!({
+                "query": query,
+                "memory_types": memory_types,
+                "total_results": all_results.len(),
+                "limit": limit,
+                "embedding_mode": "helixdb",
+                "rerank": rerank_info,
+                "duplicates_removed": duplicates_removed,
+                "results": search_results::group(all_results)
+            })));
+        }
+
+        // MCP mode: MCP server generates embedding via API
+        if self.config.is_mcp_embedding_enabled() {
+            info!("Using MCP embedding mode (API calls in MCP server)");
+            info!("Provider: {:?}", self.config.embedding.provider);
+            
+            // Check if we have API key (for cloud providers)
+            let api_key = self.config.get_api_key().unwrap_or_default();
+            
+            // Validate based on provider
+            match self.config.embedding.provider {
+                Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
+                    if api_key.is_empty() {
+                        error!("API key missing for cloud provider");
+                        return Ok(tool_error(ErrorCode::EmbeddingUnavailable, "API key not configured for cloud embedding provider", json!({
+                            "provider": format!("{:?}", self.config.embedding.provider),
+                            "suggestion": "Set OPENAI_API_KEY or GEMINI_API_KEY environment variable, or add api_key to mcpconfig.toml"
+                        })));
+                    }
+                }
+                Some(config::EmbeddingProvider::Local) => {
+                    info!("Using local HTTP embedding provider (no API key needed)");
+                }
+                Some(config::EmbeddingProvider::Tcp) => {
+                    info!("Using TCP embedding server (no API key needed)");
+                }
+                None => {
+                    error!("Embedding provider not configured");
+                    return Ok(tool_error(ErrorCode::EmbeddingUnavailable, "Embedding provider not configured", json!({
+                        "suggestion": "Set 'provider' in mcpconfig.toml to: openai, gemini, local, or tcp"
+                    })));
+                }
+            }
+
+            // Generate embedding from query text
+            info!("Generating embedding for query: {}", query);
+            let query_embedding = match self.generate_query_embedding(query, &api_key).await {
+                Ok(embedding) => {
+                    info!("? Generated embedding vector with {} dimensions", embedding.len());
+                    embedding
+                }
+                Err(e) => {
+                    error!("? Failed to generate embedding: {}", e);
+                    return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Embedding generation failed: {}", e), json!({
+                        "provider": format!("{:?}", self.config.embedding.provider),
+                        "suggestion": "Check API key, network connection, and local server status"
+                    })));
+                }
+            };
+
+            let (refused, model_warnings) = self.check_corpus_models(&memory_types, query_embedding.len()).await;
+            if !refused.is_empty() && refused.len() == memory_types.len() {
+                return Ok(tool_error(ErrorCode::Conflict, "Query embedding model differs from every requested collection's model", json!({
+                    "model_warnings": model_warnings
+                })));
+            }
+
+            let searchable: Vec<&str> = memory_types.iter().copied().filter(|t| !refused.iter().any(|r| r == t)).collect();
+            let all_results = self.vector_search(&searchable, &query_embedding, params.0.business_id.as_deref(), params.0.customer_id.as_deref(), fetch_limit, params.0.min_score).await;
+            let mut all_results = channels::keep(all_results, &wanted, candidates.max(0) as usize);
+            let duplicates_removed = search_results::dedup(&mut all_results);
+            let (all_results, rerank_info) = self.maybe_rerank(rerank, query, all_results, limit).await;
+
+            return Ok(CallToolResult::structured(json!({
+                "query": query,
+                "memory_types": memory_types,
+                "total_results": all_results.len(),
+                "limit": limit,
+                "embedding_mode": "mcp",
+                "provider": format!("{:?}", self.config.embedding.provider),
+                "model": self.config.embedding.model,
+                "model_warnings": model_warnings,
+                "rerank": rerank_info,
+                "duplicates_removed": duplicates_removed,
+                "results": search_results::group(all_results)
+            })));
+        }
+
+        // Fallback (should never reach here)
+        Ok(tool_error(ErrorCode::EmbeddingUnavailable, "Invalid embedding configuration. Check mcpconfig.toml", json!({})))
+    }
+
+    #[tool(description = "Batch semantic search - run several search queries (up to 20) against the same memory types in one call, e.g. to compare products. Query embeddings are generated in one provider call where supported and the searches run concurrently; results are grouped per query. REQUIRED: queries, memory_types. Optional: business_id, customer_id, channel, limit (per query, default 10), rerank, min_score.")]
+    async fn search_semantic_batch(&self, params: Parameters<SearchSemanticBatchParam>) -> Result<CallToolResult, McpError> {
+        const MAX_QUERIES: usize = 20;
+        let p = params.0;
+        let limit = p.limit.unwrap_or(10);
+        let queries = match search_results::batch_queries(&p.queries, MAX_QUERIES) {
+            Ok(queries) => queries,
+            Err(e) => return Ok(tool_error(ErrorCode::ValidationFailed, e, json!({}))),
+        };
+        let mut memory_types: Vec<&str> = Vec::new();
+        for memory_type in p.memory_types.iter().map(|t| Self::normalize_to_plural(t.as_str())) {
+            if !memory_types.contains(&memory_type) {
+                memory_types.push(memory_type);
+            }
+        }
+        let business_id = p.business_id.as_deref();
+        let customer_id = p.customer_id.as_deref();
+        let rerank = p.rerank.unwrap_or(self.config.rerank.enabled);
+        let candidates = if rerank { limit.max(self.config.rerank.candidates as i32) } else { limit };
+        let wanted = p.channel.as_deref().map(channels::parse).unwrap_or_default();
+        let fetch_limit = if wanted.is_empty() { candidates } else { candidates.saturating_mul(channels::OVERFETCH) };
+
+        info!("search_semantic_batch: {} queries, types={:?}, limit={}", queries.len(), memory_types, limit);
+
+        let (embedding_mode, grouped, model_warnings) = if self.config.is_helixdb_embedding_enabled() {
+            let searches = queries.iter().map(|q| self.text_vector_search(&memory_types, q, business_id, customer_id, fetch_limit, p.min_score));
+            ("helixdb", futures::future::join_all(searches).await, Vec::new())
+        } else if self.config.is_mcp_embedding_enabled() {
+            let api_key = self.config.get_api_key().unwrap_or_default();
+            let embeddings = match self.generate_query_embeddings(&queries, &api_key).await {
+                Ok(embeddings) => embeddings,
+                Err(e) => {
+                    error!("search_semantic_batch embedding failed: {}", e);
+                    return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Embedding generation failed: {}", e), json!({
+                        "provider": format!("{:?}", self.config.embedding.provider),
+                        "suggestion": "Check API key, network connection, and local server status"
+                    })));
+                }
+            };
+            let dimensions = embeddings.first().map(|e| e.len()).unwrap_or(0);
+            let (refused, model_warnings) = self.check_corpus_models(&memory_types, dimensions).await;
+            let searchable: Vec<&str> = memory_types.iter().copied().filter(|t| !refused.iter().any(|r| r == t)).collect();
+            let searches = embeddings.iter().map(|e| self.vector_search(&searchable, e, business_id, customer_id, fetch_limit, p.min_score));
+            ("mcp", futures::future::join_all(searches).await, model_warnings)
+        } else {
+            return Ok(tool_error(ErrorCode::EmbeddingUnavailable, "Invalid embedding configuration. Check mcpconfig.toml", json!({})));
+        };
+
+        let reranked = queries.iter().zip(grouped).map(|(query, results)| {
+            let mut results = channels::keep(results, &wanted, candidates.max(0) as usize);
+            search_results::dedup(&mut results);
+            self.maybe_rerank(rerank, query, results, limit)
+        });
+        let results: Vec<serde_json::Value> = queries
+            .iter()
+            .zip(futures::future::join_all(reranked).await)
+            .map(|(query, (results, rerank_info))| search_results::batch_entry(query, results, rerank_info))
+            .collect();
+        Ok(CallToolResult::structured(json!({
+            "query_count": queries.len(),
+            "memory_types": memory_types,
+            "limit": limit,
+            "embedding_mode": embedding_mode,
+            "model_warnings": model_warnings,
+            "results": results
+        })))
+    }
+
+    #[tool(description = "BM25 keyword search - fast text-based search across all memory types. Use for exact matches, IDs, phone numbers, or when embeddings unavailable. Optional channel (e.g. \"whatsapp\", comma-separated) keeps only memories from those channels. Always available as fallback.")]
+    async fn search_bm25(&self, params: Parameters<SearchBM25Param>) -> Result<CallToolResult, McpError> {
+        let query = &params.0.query;
+        let memory_types_input = &params.0.memory_types;
+        let limit = params.0.limit.unwrap_or(10);
+
+        // Normalize all memory types to plural (accept both "product" and "products")
+        let memory_types: Vec<&str> = memory_types_input
+            .iter()
+            .map(|t| Self::normalize_to_plural(t.as_str()))
+            .collect();
+
+        info!("search_bm25: query='{}', types={:?} (normalized from: {:?}), limit={}", query, memory_types, memory_types_input, limit);
+
+        let mut all_results = Vec::new();
+        let wanted = params.0.channel.as_deref().map(channels::parse).unwrap_or_default();
+        let k = if wanted.is_empty() { limit } else { limit.saturating_mul(channels::OVERFETCH) };
+
+        // Route each memory type to its BM25 query
+        for memory_type in &memory_types {
+            let query_name = match *memory_type {
+                "products" => "search_business_products_bm25",
+                "services" => "search_business_services_bm25",
+                "locations" => "search_business_locations_bm25",
+                "hours" => "search_business_hours_bm25",
+                "social" => "search_business_social_bm25",
+                "policies" => "search_business_policies_bm25",
+                "events" => "search_business_events_bm25",
+                "information" => "search_business_information_bm25",
+                "behaviors" => "search_customer_behaviors_bm25",
+                "preferences" => "search_customer_preferences_bm25",
+                "desires" => "search_customer_desires_bm25",
+                "rules" => "search_customer_rules_bm25",
+                "feedback" => "search_customer_feedback_bm25",
+                "communication" => "search_customer_communication_bm25",
+                "product_interactions" => "search_customer_product_interactions_bm25",
+                "service_interactions" => "search_customer_service_interactions_bm25",
+                "navigation_hubs" => "search_navigation_hubs_bm25",
+                "waypoints" => "search_waypoints_bm25",
+                "direction_paths" => "search_direction_paths_bm25",
+                _ => {
+                    warn!("Unknown memory type for BM25 search: {}", memory_type);
+                    continue;
+                }
+            };
+
+            // Build payload with query_text and k (limit)
+            let payload = json!({
+                "query_text": query,
+                "k": k
+            });
+
+            // Add filters if provided
+            if let Some(business_id) = &params.0.business_id {
+                // Note: BM25 queries don't use filters, but we can log it for context
+                info!("BM25 search context: business_id={}", business_id);
+            }
+
+            if let Some(customer_id) = &params.0.customer_id {
+                info!("BM25 search context: customer_id={}", customer_id);
+            }
+
+            // Execute BM25 query
+            match self.helix_client.query(query_name, payload).await {
+                Ok(results) => {
+                    if let Some(array) = results.as_array() {
+                        all_results.extend(array.iter().filter(|r| channels::matches(r, &wanted)).take(limit.max(0) as usize).cloned());
+                    }
+                }
+                Err(e) => {
+                    error!("BM25 search failed for {}: {}", memory_type, e);
+                }
+            }
+        }
+
+        Ok(CallToolResult::structured(json!({
+            "query": query,
+            "memory_types": memory_types,
+            "search_type": "bm25_keyword",
+            "total_results": all_results.len(),
+            "limit": limit,
+            "note": "BM25 uses keyword matching, not semantic embeddings. Best for exact terms, IDs, or specific phrases.",
+            "results": all_results
+        })))
+    }
+
+    #[tool(description = "Find customer insights - discover relationships between customers and products/services. Valid relationship_type values: 'liked' (products customer likes), 'disliked' (products customer dislikes), 'used_service' (services used), 'visited_location' (locations visited), 'all' (all relationships). Returns embedded reasons for each relationship.")]
+    async fn find_customer_insights(&self, params: Parameters<FindCustomerInsightsParam>) -> Result<CallToolResult, McpError> {
+        let relationship_type = &params.0.relationship_type;
+        
+        info!("find_customer_insights: relationship_type={}", relationship_type);
+
+        // Build query based on what we're looking for
+        let mut insights = json!({});
+
+        match relationship_type.as_str() {
+            "liked" | "disliked" | "used_service" | "visited_location" | "all" => {
+                // These require traversing edges with embedded reasons
+                // The schema has: CustomerLikedProduct, CustomerDislikedProduct, CustomerUsedService, CustomerVisitedLocation
+                
+                if let Some(customer_id) = &params.0.customer_id {
+                    // Find what this customer liked/disliked/used
+                    insights["customer_id"] = json!(customer_id);
+                    
+                    if relationship_type == "liked" || relationship_type == "all" {
+                        // Get customer preferences
+                        if let Ok(preferences) = self.helix_client.query(
+                            "get_customer_preferences",
+                            json!({"customer_id": customer_id})
+                        ).await {
+                            insights["liked_products"] = json!({
+                                "note": "Customer preferences found. To get actual product relationships, traverse CustomerLikedProduct edges.",
+                                "preferences": preferences
+                            });
+                        }
+                    }
+
+                    if relationship_type == "disliked" || relationship_type == "all" {
+                        // Get negative feedback
+                        if let Ok(feedback) = self.helix_client.query(
+                            "get_customer_feedback",
+                            json!({"customer_id": customer_id})
+                        ).await {
+                            // Filter for negative sentiment
+                            if let Some(feedback_array) = feedback.as_array() {
+                                let negative: Vec<_> = feedback_array
+                                    .iter()
+                                    .filter(|f| {
+                                        f.get("sentiment")
+                                            .and_then(|s| s.as_str())
+                                            .map(|s| s == "negative")
+                                            .unwrap_or(false)
+                                    })
+                                    .cloned()
+                                    .collect();
+                                
+                                insights["disliked_items"] = json!({
+                                    "count": negative.len(),
+                                    "negative_feedback": negative
+                                });
+                            }
+                        }
+                    }
+
+                    if relationship_type == "used_service" || relationship_type == "all" {
+                        // Get behaviors related to service usage
+                        if let Ok(behaviors) = self.helix_client.query(
+                            "get_customer_behaviors",
+                            json!({"customer_id": customer_id})
+                        ).await {
+                            if let Some(behaviors_array) = behaviors.as_array() {
+                                let service_usage: Vec<_> = behaviors_array
+                                    .iter()
+                                    .filter(|b| {
+                                        b.get("behavior_type")
+                                            .and_then(|bt| bt.as_str())
+                                            .map(|bt| bt == "interaction" || bt == "purchase")
+                                            .unwrap_or(false)
+                                    })
+                                    .cloned()
+                                    .collect();
+                                
+                                insights["service_usage"] = json!({
+                                    "count": service_usage.len(),
+                                    "behaviors": service_usage
+                                });
+                            }
+                        }
+                    }
+
+                } else if let Some(product_id) = &params.0.product_id {
+                    // Find which customers liked/disliked this product
+                    insights["product_id"] = json!(product_id);
+                    insights["note"] = json!("To find customers who liked/disliked this product, you need to traverse incoming CustomerLikedProduct/CustomerDislikedProduct edges. This requires graph traversal queries.");
+                    
+                    // Suggestion: Use low-level tools to traverse
+                    insights["suggestion"] = json!({
+                        "approach": "Use in_step tool to traverse CustomerLikedProduct edges from this product node",
+                        "example": "in_step(connection_id, edge_label='CustomerLikedProduct', edge_type='...')"
+                    });
+
+                } else if let Some(service_id) = &params.0.service_id {
+                    // Find which customers used this service
+                    insights["service_id"] = json!(service_id);
+                    insights["note"] = json!("To find customers who used this service, traverse incoming CustomerUsedService edges.");
+                    
+                    insights["suggestion"] = json!({
+                        "approach": "Use in_step tool to traverse CustomerUsedService edges from this service node"
+                    });
+
+                } else {
+                    return Ok(tool_error(ErrorCode::ValidationFailed, "Must provide at least one of: customer_id, product_id, or service_id", json!({})));
+                }
+
+                Ok(CallToolResult::structured(insights))
+            }
+            _ => {
+                Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid relationship_type: {}. Valid types: liked, disliked, used_service, visited_location, all", relationship_type), json!({})))
+            }
+        }
+    }
+
+    // ========================================================================
+    // CREATE TOOLS - Add new memories
+    // ========================================================================
+
+    #[tool(description = "Create new business memory - add products, services, locations, hours, social media, policies, events, or information. REQUIRED: business_id, memory_type, text_description (for embedding generation in MCP mode). Type-specific required fields: product_name (for product), service_name (for service), location_name (for location), policy_name (for policy), event_name (for event), platform (for social), title (for information). Provide these at top level or in data. No additional fields required for hours. Use 'information' memory_type for storing documentation, manuals, guides, teachings, product usage instructions, FAQs, tutorials, or any general knowledge content. All other fields will be auto-filled with schema defaults if not provided. Optional dedup (MCP mode): when enabled, returns an existing near-duplicate memory instead of creating a new one.")]
+    async fn create_business_memory(&self, params: Parameters<CreateBusinessMemoryParam>) -> Result<CallToolResult, McpError> {
+        self.create_business_memory_with_id(params, None).await
+    }
+
+    /// Create a business memory, using `memory_id` as its ID instead of generating one
+    async fn create_business_memory_with_id(&self, params: Parameters<CreateBusinessMemoryParam>, memory_id: Option<String>) -> Result<CallToolResult, McpError> {
+        let business_id = &params.0.business_id;
+        let memory_type_input = &params.0.memory_type;
+        let text_description = &params.0.text_description;
+        let mut data = params.0.data.clone();
+
+        // Add text_description to data
+        data["text_description"] = json!(text_description);
+
+        // Add type-specific fields to data if provided
+        if let Some(ref product_name) = params.0.product_name {
+            data["product_name"] = json!(product_name);
+        }
+        if let Some(ref service_name) = params.0.service_name {
+            data["service_name"] = json!(service_name);
+        }
+        if let Some(ref location_name) = params.0.location_name {
+            data["location_name"] = json!(location_name);
+        }
+        if let Some(ref policy_name) = params.0.policy_name {
+            data["policy_name"] = json!(policy_name);
+        }
+        if let Some(ref event_name) = params.0.event_name {
+            data["event_name"] = json!(event_name);
+        }
+        if let Some(ref platform) = params.0.platform {
+            data["platform"] = json!(platform);
+        }
+        if let Some(ref title) = params.0.title {
+            data["title"] = json!(title);
+        }
+        
+        // Normalize memory_type (accept both "products" and "product")
+        let memory_type = memory_types::normalize(memory_type_input);
+        
+        info!("create_business_memory: business_id={}, type={} (normalized from: {})", business_id, memory_type, memory_type_input);
+
+        // Add business_id to data (required in schema but provided as parameter)
+        data["business_id"] = json!(business_id);
+
+        // Auto-generate required IDs based on memory type
+        let id_field_name = match memory_type {
+            "product" => "product_id",
+            "service" => "service_id",
+            "location" => "location_id",
+            "hours" => "hours_id",
+            "social" => "social_id",
+            "policy" => "policy_id",
+            "event" => "event_id",
+            "information" => "info_id",
+            _ => return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}. Valid types: product, service, location, hours, social, policy, event, information", memory_type), json!({}))),
+        };
+        let generated_id = memory_id.unwrap_or_else(|| format!("{}_{}", memory_type.to_uppercase(), self.ids.next_id()));
+        data[id_field_name] = json!(generated_id);
+
+        // Typed model fills schema defaults and rejects missing or mistyped fields
+        let mut data = match models::business_memory(memory_type, data, self.clock.timestamp()) {
+            Ok(data) => data,
+            Err(e) => return Ok(tool_error(ErrorCode::ValidationFailed, e.to_string(), json!({
+                "expected_fields": models::schema(memory_type)
+            }))),
+        };
+        if memory_type == "event" {
+            let registered = data["registered_count"].as_i64().unwrap_or(0);
+            if let Err(e) = events::check_registrations(registered, data["capacity"].as_i64().unwrap_or(0)) {
+                return Ok(tool_error(ErrorCode::ValidationFailed, e, json!({})));
+            }
+        }
+
+        // Check if embedding needs to be generated (MCP mode)
+        if self.config.is_mcp_embedding_enabled() {
+            // Products and services embed their structured fields; other types their text_description
+            let embedding_text = embedding_utils::composite_text(memory_type, &data).unwrap_or_else(|| {
+                data.get("text_description").and_then(|v| v.as_str()).unwrap_or("").to_string()
+            });
+            
+            // Only generate embedding if there is text to embed
+            if embedding_text.is_empty() {
+                return Ok(tool_error(ErrorCode::ValidationFailed, "text_description is required for embedding generation in MCP mode", json!({
+                    "suggestion": "Provide text_description field with descriptive content"
+                })));
+            }
+
+            info!("Generating embedding for {} text...", memory_type);
+            
+            // Get API key (empty for local provider)
+            let api_key = self.config.get_api_key().unwrap_or_default();
+            
+            // Generate embedding
+            match self.generate_embedding(&embedding_text, &api_key).await {
+                Ok(embedding) => {
+                    info!("✓ Generated {} dimensional embedding", embedding.len());
+
+                    // Add embedding to data (convert f32 to f64 for HelixDB)
+                    let embedding: Vec<f64> = embedding.iter().map(|&x| x as f64).collect();
+
+                    // Return the existing memory instead of creating a near-duplicate
+                    if params.0.dedup.unwrap_or(self.config.dedup.enabled) {
+                        if let Some((existing, similarity)) = self.find_duplicate_memory(memory_type, "business_id", business_id, &embedding).await {
+                            info!("✓ Found existing {} memory (similarity {:.3}) - skipping create", memory_type, similarity);
+                            return Ok(CallToolResult::structured(json!({
+                                "success": true,
+                                "deduplicated": true,
+                                "memory_type": memory_type,
+                                "business_id": business_id,
+                                "similarity": similarity,
+                                "existing_memory": existing
+                            })));
+                        }
+                    }
+
+                    data["embedding"] = json!(embedding);
+
+                    // Add embedding model info
+                    let model_name = match self.config.embedding.provider {
+                        Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
+                            self.config.embedding.model.clone().unwrap_or_else(|| "unknown".to_string())
+                        }
+                        Some(config::EmbeddingProvider::Local) => "local".to_string(),
+                        Some(config::EmbeddingProvider::Tcp) => "tcp-local".to_string(),
+                        None => "unknown".to_string()
+                    };
+                    data["embedding_model"] = json!(model_name);
+                    self.note_corpus_model(Self::normalize_to_plural(memory_type), embedding.len()).await;
+                }
+                Err(e) => {
+                    error!("✗ Failed to generate embedding: {}", e);
+                    return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Failed to generate embedding: {}", e), json!({
+                        "suggestion": "Check embedding configuration and API connectivity"
+                    })));
+                }
+            }
+        } else {
+            // HelixDB mode - embeddings should be in the data already or will be generated by HelixDB
+            info!("Using HelixDB embedding mode - expecting embedding in data or HelixDB will generate it");
+        }
+
+        // Determine which query to execute based on memory_type
+        let query_name = match memory_type {
+            "product" => "add_business_product_memory",
+            "service" => "add_business_service_memory",
+            "location" => "add_business_location_memory",
+            "hours" => "add_business_hours_memory",
+            "social" => "add_business_social_media_memory",
+            "policy" => "add_business_policy_memory",
+            "event" => "add_business_event_memory",
+            "information" => "add_business_information_memory",
+            _ => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}. Valid types: product, service, location, hours, social, policy, event, information", memory_type), json!({})));
+            }
+        };
+
+        // Execute the query
+        match self.helix_client.query(query_name, data).await {
+            Ok(result) => {
+                Ok(CallToolResult::structured(json!({
+                    "success": true,
+                    "memory_type": memory_type,
+                    "business_id": business_id,
+                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
+                    "result": result
+                })))
+            }
+            Err(e) => {
+                error!("create_business_memory failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to create {} memory: {}", memory_type, e), json!({})))
+            }
+        }
+    }
+
+    #[tool(description = "Onboard a business - first-time setup in one call: creates the business entity, then its initial products, services, locations and hours in that order, and returns a manifest of the created IDs. Every entry is validated before anything is written. REQUIRED: business_id (new), business_name, business_type. Optional: allow_collaboration, metadata, and products/services/locations/hours as arrays of objects with text_description plus the same fields create_business_memory takes (e.g. product_name, price).")]
+    async fn onboard_business(&self, params: Parameters<OnboardBusinessParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let business_id = p.business_id.clone();
+        // (section, memory type, ID field, name field) in creation order
+        let sections = [
+            ("products", "product", "product_id", "product_name", p.products.unwrap_or_default()),
+            ("services", "service", "service_id", "service_name", p.services.unwrap_or_default()),
+            ("locations", "location", "location_id", "location_name", p.locations.unwrap_or_default()),
+            ("hours", "hours", "hours_id", "hours_id", p.hours.unwrap_or_default()),
+        ];
+        info!("onboard_business: business_id={}, {} memories", business_id, sections.iter().map(|s| s.4.len()).sum::<usize>());
+
+        // Validate every entry before writing anything
+        let mut invalid = Vec::new();
+        for (section, memory_type, id_field, _, items) in &sections {
+            for (index, item) in items.iter().enumerate() {
+                if let Some(problem) = onboarding::entry_problem(memory_type, id_field, &business_id, item, self.clock.timestamp()) {
+                    invalid.push(json!({"section": section, "index": index, "problem": problem, "expected_fields": models::schema(memory_type)}));
+                }
+            }
+        }
+        if !invalid.is_empty() {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("{} entries are invalid; nothing was created", invalid.len()), json!({
+                "business_id": business_id,
+                "invalid": invalid
+            })));
+        }
+
+        match self.helix_client.query("get_business", json!({"business_id": business_id})).await {
+            Ok(existing) if !HelixClient::result_items(&existing).is_empty() => {
+                return Ok(tool_error(ErrorCode::Conflict, format!("Business {} already exists", business_id), json!({
+                    "business_id": business_id,
+                    "suggestion": "Add memories to it with create_business_memory, or choose another business_id"
+                })));
+            }
+            Ok(_) => {}
+            Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to check for business {}: {}", business_id, e), json!({}))),
+        }
+
+        let business = onboarding::business_node(&business_id, &p.business_name, &p.business_type, p.allow_collaboration, p.metadata);
+        if let Err(e) = self.helix_client.query("create_business", business).await {
+            error!("onboard_business failed to create {}: {}", business_id, e);
+            return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to create business {}: {}", business_id, e), json!({"business_id": business_id})));
+        }
+
+        let mut manifest = json!({"business_id": business_id});
+        let mut failed = Vec::new();
+        let mut created_count = 0;
+        for (section, memory_type, id_field, name_field, items) in sections {
+            let mut created = Vec::new();
+            for (index, item) in items.into_iter().enumerate() {
+                let memory_id = format!("{}_{}", memory_type.to_uppercase(), self.ids.next_id());
+                let name = item.get(name_field).cloned();
+                let create = CreateBusinessMemoryParam {
+                    business_id: business_id.clone(),
+                    memory_type: memory_type.to_string(),
+                    text_description: item["text_description"].as_str().unwrap_or_default().to_string(),
+                    product_name: None,
+                    service_name: None,
+                    location_name: None,
+                    policy_name: None,
+                    event_name: None,
+                    platform: None,
+                    title: None,
+                    data: item,
+                    dedup: Some(false),
+                };
+                match self.create_business_memory_with_id(Parameters(create), Some(memory_id.clone())).await {
+                    Ok(result) if result.is_error != Some(true) => {
+                        created_count += 1;
+                        created.push(json!({ "index": index, id_field: memory_id, "name": name }));
+                    }
+                    Ok(result) => failed.push(json!({
+                        "section": section,
+                        "index": index,
+                        "error": result.structured_content.and_then(|c| c.get("error").cloned())
+                    })),
+                    Err(e) => failed.push(json!({"section": section, "index": index, "error": e.message})),
+                }
+            }
+            manifest[section] = json!(created);
+        }
+
+        let mut response = json!({
+            "success": failed.is_empty(),
+            "business_id": business_id,
+            "created": manifest,
+            "memories_created": created_count,
+            "failed": failed
+        });
+        if !failed.is_empty() {
+            response["next_step"] = json!(format!("The business and the other entries exist; add the failed entries with create_business_memory (business_id {})", business_id));
+        }
+        Ok(CallToolResult::structured(response))
+    }
+
+    #[tool(description = "Clone business memories - copy memories of selected types from a template business to another business (e.g. a franchise location), with new IDs and freshly generated embeddings. Optional overrides set fields on every copy, e.g. {\"currency\": \"EUR\", \"product\": {\"availability\": \"preorder\"}}. REQUIRED: source_business_id, target_business_id (must exist, e.g. from onboard_business). Optional: memory_types (default: products, services, locations, hours, social, policies, events, information), overrides.")]
+    async fn clone_business_memories(&self, params: Parameters<CloneBusinessMemoriesParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let requested: Vec<&str> = match &p.memory_types {
+            Some(types) => types.iter().map(|t| memory_types::normalize(t)).collect(),
+            None => onboarding::CLONE_SOURCES.iter().map(|(memory_type, _)| *memory_type).collect(),
+        };
+        if let Some(unknown) = requested.iter().find(|t| !onboarding::CLONE_SOURCES.iter().any(|(memory_type, _)| memory_type == *t)) {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}", unknown), json!({
+                "valid_values": onboarding::CLONE_SOURCES.iter().map(|(memory_type, _)| memory_type).collect::<Vec<_>>()
+            })));
+        }
+        let overrides = p.overrides.clone().unwrap_or_else(|| json!({}));
+        if !overrides.is_object() {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "overrides must be an object", json!({"overrides": overrides})));
+        }
+        info!("clone_business_memories: {} -> {}, types={:?}", p.source_business_id, p.target_business_id, requested);
+
+        match self.helix_client.query("get_business", json!({"business_id": p.target_business_id})).await {
+            Ok(target) if HelixClient::result_items(&target).is_empty() => {
+                return Ok(tool_error(ErrorCode::NotFound, format!("Target business {} does not exist", p.target_business_id), json!({
+                    "target_business_id": p.target_business_id,
+                    "suggestion": "Create it first with onboard_business"
+                })));
+            }
+            Ok(_) => {}
+            Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to look up business {}: {}", p.target_business_id, e), json!({}))),
+        }
+
+        let mut cloned = serde_json::Map::new();
+        let mut failed = Vec::new();
+        let mut total = 0;
+        for (memory_type, list_query) in onboarding::CLONE_SOURCES.iter().filter(|(memory_type, _)| requested.contains(memory_type)) {
+            let Some((id_field, _, _)) = memory_types::entity(memory_type) else {
+                continue;
+            };
+            let sources = match self.helix_client.query(list_query, json!({"business_id": p.source_business_id})).await {
+                Ok(result) => HelixClient::result_items(&result),
+                Err(e) => {
+                    failed.push(json!({"memory_type": memory_type, "error": e.to_string()}));
+                    continue;
+                }
+            };
+            let mut copies = Vec::new();
+            for source in sources {
+                let source_id = source.get(id_field).cloned().unwrap_or(serde_json::Value::Null);
+                let data = onboarding::clone_fields(source, memory_type, id_field, &overrides);
+                let new_id = format!("{}_{}", memory_type.to_uppercase(), self.ids.next_id());
+                let create = CreateBusinessMemoryParam {
+                    business_id: p.target_business_id.clone(),
+                    memory_type: memory_type.to_string(),
+                    text_description: data.get("text_description").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                    product_name: None,
+                    service_name: None,
+                    location_name: None,
+                    policy_name: None,
+                    event_name: None,
+                    platform: None,
+                    title: None,
+                    data,
+                    dedup: Some(false),
+                };
+                match self.create_business_memory_with_id(Parameters(create), Some(new_id.clone())).await {
+                    Ok(result) if result.is_error != Some(true) => copies.push(json!({"source_id": source_id, id_field: new_id})),
+                    Ok(result) => failed.push(json!({
+                        "memory_type": memory_type,
+                        "source_id": source_id,
+                        "error": result.structured_content.and_then(|c| c.get("error").cloned())
+                    })),
+                    Err(e) => failed.push(json!({"memory_type": memory_type, "source_id": source_id, "error": e.message})),
+                }
+            }
+            total += copies.len();
+            cloned.insert(memory_type.to_string(), json!(copies));
+        }
+
+        Ok(CallToolResult::structured(json!({
+            "success": failed.is_empty(),
+            "source_business_id": p.source_business_id,
+            "target_business_id": p.target_business_id,
+            "cloned_count": total,
+            "cloned": cloned,
+            "failed": failed
+        })))
+    }
+
+    #[tool(description = "Diff memories - compare two memories field by field and by embedding similarity, or every memory of a type across two businesses (catalog sync checks, franchise drift). Across businesses, memories are paired on their name field (product_name, policy_name, ...; override with match_field) and the diff lists changed pairs plus memories only one business has. REQUIRED: memory_type, and either memory_id_a + memory_id_b (entity IDs) or business_id_a + business_id_b. Optional: match_field, include_embeddings (default true).")]
+    async fn diff_memories(&self, params: Parameters<DiffMemoriesParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let memory_type = memory_types::normalize(&p.memory_type);
+        let Some((id_field, owner_field, lookup_query)) = memory_types::entity(memory_type) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}", memory_type), json!({
+                "valid_values": ["product", "service", "location", "hours", "social", "policy", "event", "information", "behavior", "preference", "desire", "rule", "feedback"]
+            })));
+        };
+        let include_embeddings = p.include_embeddings.unwrap_or(true);
+
+        match (&p.memory_id_a, &p.memory_id_b, &p.business_id_a, &p.business_id_b) {
+            (Some(id_a), Some(id_b), _, _) => {
+                let (a, b) = futures::future::join(
+                    self.helix_client.query(lookup_query, json!({ id_field: id_a })),
+                    self.helix_client.query(lookup_query, json!({ id_field: id_b })),
+                ).await;
+                let mut memories = Vec::new();
+                for (id, result) in [(id_a, a), (id_b, b)] {
+                    match result.map(|r| HelixClient::result_items(&r).into_iter().next()) {
+                        Ok(Some(memory)) => memories.push(memory),
+                        Ok(None) => return Ok(tool_error(ErrorCode::NotFound, format!("{} {} not found", memory_type, id), json!({ id_field: id }))),
+                        Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to load {} {}: {}", memory_type, id, e), json!({}))),
+                    }
+                }
+                let differences = memory_diff::field_diff(&memories[0], &memories[1], id_field);
+                let similarity = if include_embeddings { self.embedding_similarity(memory_type, id_field, id_a, id_b).await } else { None };
+                Ok(CallToolResult::structured(json!({
+                    "memory_type": memory_type,
+                    "a": { id_field: id_a, owner_field: memories[0].get(owner_field) },
+                    "b": { id_field: id_b, owner_field: memories[1].get(owner_field) },
+                    "identical": differences.is_empty(),
+                    "differences": differences,
+                    "embedding_similarity": similarity
+                })))
+            }
+            (_, _, Some(business_a), Some(business_b)) if owner_field == "business_id" => {
+                let Some(key) = p.match_field.as_deref().or(memory_diff::natural_key(memory_type)) else {
+                    return Ok(tool_error(ErrorCode::ValidationFailed, "match_field is required for this memory_type", json!({})));
+                };
+                let list_query = Self::memory_list_query(memory_type, owner_field);
+                let (a, b) = futures::future::join(
+                    self.helix_client.query(&list_query, json!({"business_id": business_a})),
+                    self.helix_client.query(&list_query, json!({"business_id": business_b})),
+                ).await;
+                let (a, b) = match (a, b) {
+                    (Ok(a), Ok(b)) => (HelixClient::result_items(&a), HelixClient::result_items(&b)),
+                    (Err(e), _) | (_, Err(e)) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to load {} memories: {}", memory_type, e), json!({}))),
+                };
+                let (pairs, only_a, only_b) = memory_diff::pair_by(a, b, key);
+                let id_of = |memory: &serde_json::Value| memory.get(id_field).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                let summary = |memory: &serde_json::Value| json!({ id_field: memory.get(id_field), key: memory.get(key) });
+                let compared = futures::future::join_all(pairs.iter().map(|(a, b)| async move {
+                    let differences = memory_diff::field_diff(a, b, id_field);
+                    let similarity = if include_embeddings && !differences.is_empty() {
+                        self.embedding_similarity(memory_type, id_field, &id_of(a), &id_of(b)).await
+                    } else {
+                        None
+                    };
+                    (a, b, differences, similarity)
+                })).await;
+                let unchanged = compared.iter().filter(|(_, _, differences, _)| differences.is_empty()).count();
+                let changed: Vec<serde_json::Value> = compared
+                    .into_iter()
+                    .filter(|(_, _, differences, _)| !differences.is_empty())
+                    .map(|(a, b, differences, similarity)| json!({
+                        key: a.get(key),
+                        "a": { id_field: a.get(id_field) },
+                        "b": { id_field: b.get(id_field) },
+                        "differences": differences,
+                        "embedding_similarity": similarity
+                    }))
+                    .collect();
+                Ok(CallToolResult::structured(json!({
+                    "memory_type": memory_type,
+                    "business_id_a": business_a,
+                    "business_id_b": business_b,
+                    "match_field": key,
+                    "in_sync": changed.is_empty() && only_a.is_empty() && only_b.is_empty(),
+                    "unchanged": unchanged,
+                    "changed": changed,
+                    "only_in_a": only_a.iter().map(summary).collect::<Vec<_>>(),
+                    "only_in_b": only_b.iter().map(summary).collect::<Vec<_>>()
+                })))
+            }
+            _ => Ok(tool_error(ErrorCode::ValidationFailed, "Provide memory_id_a and memory_id_b, or business_id_a and business_id_b (business memory types only)", json!({
+                "memory_type": memory_type
+            }))),
+        }
+    }
+
+    /// Cosine similarity of two memories' stored embedding vectors, if both have one
+    async fn embedding_similarity(&self, memory_type: &str, id_field: &str, id_a: &str, id_b: &str) -> Option<f64> {
+        let query = format!("get_{}_with_embedding", memory_type);
+        let (a, b) = futures::future::join(
+            self.helix_client.query(&query, json!({ id_field: id_a })),
+            self.helix_client.query(&query, json!({ id_field: id_b })),
+        ).await;
+        let vector = |result: anyhow::Result<serde_json::Value>| {
+            let result = result.ok()?;
+            HelixClient::result_items(result.get("embedding")?).first().and_then(similarity::vector_of)
+        };
+        let (a, b) = (vector(a)?, vector(b)?);
+        (a.len() == b.len()).then(|| similarity::cosine_similarity(&a, &b))
+    }
+
+    #[tool(description = "Create snapshot - save a business record and all its memories (products, services, locations, hours, social, policies, events, information) to a versioned snapshot file on the server, to roll back to with restore_snapshot after a bad bulk operation. Only the business record and these memories are covered: customers, interactions, communications, links between information memories and loyalty rules are not saved or restored. Embeddings are not stored; restore regenerates them. REQUIRED: business_id. Optional: memory_types, encrypt (with the configured export key).")]
+    async fn create_snapshot(&self, params: Parameters<CreateSnapshotParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let memory_types: Vec<&str> = match &p.memory_types {
+            Some(types) => types.iter().map(|t| memory_types::normalize(t)).collect(),
+            None => snapshots::MEMORY_TYPES.to_vec(),
+        };
+        if let Some(unknown) = memory_types.iter().find(|t| !snapshots::MEMORY_TYPES.contains(t)) {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}", unknown), json!({
+                "valid_values": snapshots::MEMORY_TYPES
+            })));
+        }
+        info!("create_snapshot: business_id={}, types={:?}", p.business_id, memory_types);
+
+        let business = match self.helix_client.query("get_business", json!({"business_id": p.business_id})).await {
+            Ok(result) => match HelixClient::result_items(&result).into_iter().next() {
+                Some(business) => business,
+                None => return Ok(tool_error(ErrorCode::NotFound, format!("Business {} does not exist", p.business_id), json!({"business_id": p.business_id}))),
+            },
+            Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to look up business {}: {}", p.business_id, e), json!({}))),
+        };
+
+        let mut memories = serde_json::Map::new();
+        for memory_type in &memory_types {
+            let list_query = Self::memory_list_query(memory_type, "business_id");
+            match self.helix_client.query(&list_query, json!({"business_id": p.business_id})).await {
+                Ok(result) => {
+                    let items: Vec<serde_json::Value> = HelixClient::result_items(&result)
+                        .into_iter()
+                        .map(|mut item| {
+                            if let Some(fields) = item.as_object_mut() {
+                                fields.remove("embedding");
+                            }
+                            item
+                        })
+                        .collect();
+                    memories.insert(memory_type.to_string(), json!(items));
+                }
+                // A partial snapshot would delete the missing type's memories on restore
+                Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to read {} memories: {}", memory_type, e), json!({"memory_type": memory_type}))),
+            }
+        }
+
+        let created_at = self.clock.now();
+        let snapshot_id = snapshots::snapshot_id(&p.business_id, created_at);
+        let document = snapshots::document(&snapshot_id, &p.business_id, created_at, business, memories);
+        let encrypted = p.encrypt.unwrap_or(self.config.export.encrypt_by_default);
+        let contents = if encrypted {
+            let Some(key) = self.config.get_export_key() else {
+                return Ok(tool_error(ErrorCode::ValidationFailed, "Encryption requested but no export key is configured", json!({
+                    "suggestion": "Set encryption_key in the [export] section of mcpconfig.toml or the HELIX_EXPORT_KEY environment variable"
+                })));
+            };
+            match crypto::encrypt_archive(document.to_string().as_bytes(), &key) {
+                Ok(archive) => archive,
+                Err(e) => return Ok(tool_error(ErrorCode::Internal, e, json!({}))),
+            }
+        } else {
+            serde_json::to_vec_pretty(&document).unwrap_or_default()
+        };
+
+        let directory = std::path::Path::new(&self.config.snapshots.directory);
+        let path = match snapshots::path_for(directory, &snapshot_id) {
+            Ok(path) => path,
+            Err(e) => return Ok(tool_error(ErrorCode::ValidationFailed, e, json!({"business_id": p.business_id}))),
+        };
+        let written = match tokio::fs::create_dir_all(directory).await {
+            Ok(()) => tokio::fs::write(&path, &contents).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = written {
+            error!("Failed to write snapshot {}: {}", path.display(), e);
+            return Ok(tool_error(ErrorCode::Internal, format!("Failed to write snapshot file: {}", e), json!({"path": path.display().to_string()})));
+        }
+        info!("✓ Snapshot {} written to {}", snapshot_id, path.display());
+
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "snapshot_id": snapshot_id,
+            "business_id": p.business_id,
+            "version": snapshots::FORMAT_VERSION,
+            "created_at": created_at.to_rfc3339(),
+            "encrypted": encrypted,
+            "counts": document["counts"],
+            "path": path.display().to_string()
+        })))
+    }
+
+    #[tool(description = "Restore snapshot - roll a business back to a snapshot from create_snapshot: memories deleted since are recreated under their original IDs, changed ones are replaced (the current copy is removed only after the snapshot copy is written), and ones added since are deleted (embeddings regenerated). Customers, interactions, communications, information links and loyalty rules are left as they are. Recreates the business record if it was deleted. Pass dry_run: true to see the writes first. REQUIRED: business_id, snapshot_id. Optional: memory_types to restore only some types.")]
+    async fn restore_snapshot(&self, params: Parameters<RestoreSnapshotParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let directory = std::path::Path::new(&self.config.snapshots.directory);
+        let path = match snapshots::path_for(directory, &p.snapshot_id) {
+            Ok(path) => path,
+            Err(e) => return Ok(tool_error(ErrorCode::ValidationFailed, e, json!({"snapshot_id": p.snapshot_id}))),
+        };
+        info!("restore_snapshot: business_id={}, snapshot={}", p.business_id, p.snapshot_id);
+
+        let mut contents = match tokio::fs::read(&path).await {
+            Ok(contents) => contents,
+            Err(e) => return Ok(tool_error(ErrorCode::NotFound, format!("Snapshot {} not found: {}", p.snapshot_id, e), json!({"path": path.display().to_string()}))),
+        };
+        if contents.starts_with(crypto::ARCHIVE_MAGIC) {
+            let Some(key) = self.config.get_export_key() else {
+                return Ok(tool_error(ErrorCode::ValidationFailed, "Snapshot is encrypted but no export key is configured", json!({"snapshot_id": p.snapshot_id})));
+            };
+            contents = match crypto::decrypt_archive(&contents, &key) {
+                Ok(plaintext) => plaintext,
+                Err(e) => return Ok(tool_error(ErrorCode::ValidationFailed, e, json!({"snapshot_id": p.snapshot_id}))),
+            };
+        }
+        let document: serde_json::Value = match serde_json::from_slice(&contents) {
+            Ok(document) => document,
+            Err(e) => return Ok(tool_error(ErrorCode::ValidationFailed, format!("Snapshot {} is not valid JSON: {}", p.snapshot_id, e), json!({}))),
+        };
+        if let Err(e) = snapshots::check(&document, &p.business_id) {
+            return Ok(tool_error(ErrorCode::ValidationFailed, e, json!({"snapshot_id": p.snapshot_id})));
+        }
+        let requested: Option<Vec<&str>> = p.memory_types.as_ref().map(|types| types.iter().map(|t| memory_types::normalize(t)).collect());
+
+        // Bring back the business record itself if it was deleted
+        let mut business_recreated = false;
+        match self.helix_client.query("get_business", json!({"business_id": p.business_id})).await {
+            Ok(result) if HelixClient::result_items(&result).is_empty() => {
+                let business = &document["business"];
+                let metadata = match business.get("metadata") {
+                    Some(serde_json::Value::String(text)) => text.clone(),
+                    Some(value) if !value.is_null() => value.to_string(),
+                    _ => "{}".to_string(),
+                };
+                let payload = json!({
+                    "business_id": p.business_id,
+                    "business_name": business.get("business_name").and_then(|v| v.as_str()).unwrap_or(&p.business_id),
+                    "business_type": business.get("business_type").and_then(|v| v.as_str()).unwrap_or_default(),
+                    "status": business.get("status").and_then(|v| v.as_str()).unwrap_or("active"),
+                    "allow_collaboration": business.get("allow_collaboration").and_then(|v| v.as_bool()).unwrap_or(false),
+                    "metadata": metadata
+                });
+                if let Err(e) = self.helix_client.query("create_business", payload).await {
+                    return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to recreate business {}: {}", p.business_id, e), json!({})));
+                }
+                business_recreated = true;
+            }
+            Ok(_) => {}
+            Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to look up business {}: {}", p.business_id, e), json!({}))),
+        }
+
+        let mut restored = serde_json::Map::new();
+        let mut failed = Vec::new();
+        for (memory_type, items) in document["memories"].as_object().into_iter().flatten() {
+            let memory_type = memory_type.as_str();
+            if requested.as_ref().is_some_and(|types| !types.contains(&memory_type)) {
+                continue;
+            }
+            let (Some((id_field, _, _)), Some(delete_query)) = (memory_types::entity(memory_type), memory_types::delete_query(memory_type, true)) else {
+                failed.push(json!({"memory_type": memory_type, "error": "unsupported memory type"}));
+                continue;
+            };
+            let current = match self.helix_client.query(&Self::memory_list_query(memory_type, "business_id"), json!({"business_id": p.business_id})).await {
+                Ok(result) => HelixClient::result_items(&result),
+                Err(e) => {
+                    failed.push(json!({"memory_type": memory_type, "error": e.to_string()}));
+                    continue;
+                }
+            };
+            let plan = snapshots::plan(&HelixClient::result_items(items), &current, id_field);
+
+            for id in &plan.delete {
+                if let Err(e) = self.helix_client.query(delete_query, json!({ id_field: id })).await {
+                    failed.push(json!({"memory_type": memory_type, id_field: id, "step": "delete", "error": e.to_string()}));
+                }
+            }
+            for memory in &plan.create {
+                if let Err((id, error)) = self.restore_snapshot_memory(&p.business_id, memory_type, memory, id_field).await {
+                    failed.push(json!({"memory_type": memory_type, id_field: id, "step": "create", "error": error}));
+                }
+            }
+            // Changed memories: write the snapshot copy first and drop the current
+            // node only once that succeeded, so a failed write loses nothing
+            let node_delete_query = format!("delete_{}_node_with_embedding", memory_type);
+            for memory in &plan.replace {
+                let id = match self.restore_snapshot_memory(&p.business_id, memory_type, memory, id_field).await {
+                    Ok(id) => id,
+                    Err((id, error)) => {
+                        failed.push(json!({"memory_type": memory_type, id_field: id, "step": "create", "error": error}));
+                        continue;
+                    }
+                };
+                let old_nodes = current
+                    .iter()
+                    .filter(|c| c.get(id_field).and_then(|v| v.as_str()) == Some(id.as_str()))
+                    .filter_map(|c| c.get("id").and_then(|v| v.as_str()));
+                for node_id in old_nodes {
+                    if let Err(e) = self.helix_client.query(&node_delete_query, json!({"memory_id": node_id})).await {
+                        failed.push(json!({"memory_type": memory_type, id_field: id, "step": "delete_replaced", "error": e.to_string()}));
+                    }
+                }
+            }
+            restored.insert(memory_type.to_string(), json!({
+                "recreated": plan.create.len(),
+                "replaced": plan.replace.len(),
+                "deleted": plan.delete.len(),
+                "unchanged": plan.unchanged
+            }));
+        }
+        info!("✓ Restored {} from snapshot {} ({} failures)", p.business_id, p.snapshot_id, failed.len());
+
+        Ok(CallToolResult::structured(json!({
+            "success": failed.is_empty(),
+            "business_id": p.business_id,
+            "snapshot_id": p.snapshot_id,
+            "snapshot_created_at": document["created_at"],
+            "business_recreated": business_recreated,
+            "restored": restored,
+            "failed": failed
+        })))
+    }
+
+    /// Recreate one snapshot memory under its original ID. Returns the ID,
+    /// or the ID and the error
+    async fn restore_snapshot_memory(&self, business_id: &str, memory_type: &str, memory: &serde_json::Value, id_field: &str) -> Result<String, (String, serde_json::Value)> {
+        let id = memory.get(id_field).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let data = snapshots::creation_data(memory, id_field);
+        let create = CreateBusinessMemoryParam {
+            business_id: business_id.to_string(),
+            memory_type: memory_type.to_string(),
+            text_description: data.get("text_description").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+            product_name: None,
+            service_name: None,
+            location_name: None,
+            policy_name: None,
+            event_name: None,
+            platform: None,
+            title: None,
+            data,
+            dedup: Some(false),
+        };
+        match self.create_business_memory_with_id(Parameters(create), Some(id.clone())).await {
+            Ok(result) if result.is_error != Some(true) => Ok(id),
+            Ok(result) => Err((id, result.structured_content.and_then(|c| c.get("error").cloned()).unwrap_or_default())),
+            Err(e) => Err((id, json!(e.message))),
+        }
+    }
+
+    #[tool(description = "Create new customer memory - add behaviors, preferences, desires, rules, or feedback. REQUIRED: customer_id, memory_type, text_description (for embedding generation in MCP mode), data with memory-specific fields. All other optional fields will be auto-filled with schema defaults if not provided. Optional dedup (MCP mode): when enabled, returns an existing near-duplicate memory instead of creating a new one. data.importance (0-5) and data.pinned rank critical facts ahead in search and listings.")]
+    async fn create_customer_memory(&self, params: Parameters<CreateCustomerMemoryParam>) -> Result<CallToolResult, McpError> {
+        let customer_id = &params.0.customer_id;
+        let memory_type_input = &params.0.memory_type;
+        let text_description = &params.0.text_description;
+        let mut data = params.0.data.clone();
+
+        // Add text_description to data
+        data["text_description"] = json!(text_description);
+        
+        // Normalize memory_type (accept both "behaviors" and "behavior")
+        let memory_type = memory_types::normalize(memory_type_input);
+        
+        info!("create_customer_memory: customer_id={}, type={} (normalized from: {})", customer_id, memory_type, memory_type_input);
+
+        // Check if embedding needs to be generated (MCP mode)
+        if self.config.is_mcp_embedding_enabled() {
+            // Get text_description from data
+            let text_description = data.get("text_description")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| McpError::invalid_params("Missing text_description field", None))?;
+
+            info!("Generating embedding for text_description...");
+            
+            // Get API key (empty for local provider)
+            let api_key = self.config.get_api_key().unwrap_or_default();
+            
+            // Generate embedding
+            match self.generate_embedding(text_description, &api_key).await {
+                Ok(embedding) => {
+                    info!("? Generated {} dimensional embedding", embedding.len());
+
+                    // Add embedding to data (convert f32 to f64 for HelixDB)
+                    let embedding: Vec<f64> = embedding.iter().map(|&x| x as f64).collect();
+
+                    // Return the existing memory instead of creating a near-duplicate
+                    if params.0.dedup.unwrap_or(self.config.dedup.enabled) {
+                        if let Some((existing, similarity)) = self.find_duplicate_memory(memory_type, "customer_id", customer_id, &embedding).await {
+                            info!("✓ Found existing {} memory (similarity {:.3}) - skipping create", memory_type, similarity);
+                            return Ok(CallToolResult::structured(json!({
+                                "success": true,
+                                "deduplicated": true,
+                                "memory_type": memory_type,
+                                "customer_id": customer_id,
+                                "similarity": similarity,
+                                "existing_memory": existing
+                            })));
+                        }
+                    }
+
+                    data["embedding"] = json!(embedding);
+
+                    // Add embedding model info
+                    let model_name = match self.config.embedding.provider {
+                        Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
+                            self.config.embedding.model.clone().unwrap_or_else(|| "unknown".to_string())
+                        }
+                        Some(config::EmbeddingProvider::Local) => "local".to_string(),
+                        Some(config::EmbeddingProvider::Tcp) => "tcp-local".to_string(),
+                        None => "unknown".to_string()
+                    };
+                    data["embedding_model"] = json!(model_name);
+                    self.note_corpus_model(Self::normalize_to_plural(memory_type), embedding.len()).await;
+                }
+                Err(e) => {
+                    error!("? Failed to generate embedding: {}", e);
+                    return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Failed to generate embedding: {}", e), json!({
+                        "suggestion": "Check embedding configuration and API connectivity"
+                    })));
+                }
+            }
+        } else {
+            info!("Using HelixDB embedding mode - expecting embedding in data or HelixDB will generate it");
+        }
+
+        // Add customer_id to data (required by all customer memory queries)
+        data["customer_id"] = json!(customer_id);
+
+        // Auto-generate required IDs based on memory type
+        let id_field_name = match memory_type {
+            "behavior" => "behavior_id",
+            "preference" => "preference_id", 
+            "desire" => "desire_id",
+            "rule" => "rule_id",
+            "feedback" => "feedback_id",
+            _ => return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}. Valid types: behavior, preference, desire, rule, feedback", memory_type), json!({}))),
+        };
+        let generated_id = format!("{}_{}", memory_type.to_uppercase(), self.ids.next_id());
+        data[id_field_name] = json!(generated_id);
+
+        // Typed model fills schema defaults and rejects missing or mistyped fields
+        let data = match models::customer_memory(memory_type, data, self.clock.timestamp()) {
+            Ok(data) => data,
+            Err(e) => return Ok(tool_error(ErrorCode::ValidationFailed, e.to_string(), json!({
+                "expected_fields": models::schema(memory_type)
+            }))),
+        };
+
+        // Determine which query to execute based on memory_type
+        let query_name = match memory_type {
+            "behavior" => "add_customer_behavior_memory",
+            "preference" => "add_customer_preference_memory",
+            "desire" => "add_customer_desire_memory",
+            "rule" => "add_customer_rule_memory",
+            "feedback" => "add_customer_feedback_memory",
+            _ => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}. Valid types: behavior, preference, desire, rule, feedback", memory_type), json!({})));
+            }
+        };
+
+        // Execute the query
+        match self.helix_client.query(query_name, data).await {
+            Ok(result) => {
+                Ok(CallToolResult::structured(json!({
+                    "success": true,
+                    "memory_type": memory_type,
+                    "customer_id": customer_id,
+                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
+                    "result": result
+                })))
+            }
+            Err(e) => {
+                error!("create_customer_memory failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to create {} memory: {}", memory_type, e), json!({})))
+            }
+        }
+    }
+
+    #[tool(description = "Store conversation summary - chunk a raw conversation transcript, generate embeddings per chunk, and store each chunk as a customer communication memory tagged with a session_id for later semantic recall. REQUIRED: customer_id, business_id, transcript. Optional: session_id (auto-generated if omitted), contact_method, contact_reason, agent_id, max_chunk_chars (default 1000).")]
+    async fn store_conversation_summary(&self, params: Parameters<StoreConversationSummaryParam>) -> Result<CallToolResult, McpError> {
+        let customer_id = &params.0.customer_id;
+        let business_id = &params.0.business_id;
+        let session_id = params.0.session_id.clone()
+            .unwrap_or_else(|| format!("SESSION_{}", self.ids.next_id()));
+        let max_chunk_chars = params.0.max_chunk_chars.unwrap_or(chunking::DEFAULT_CHUNK_CHARS);
+
+        let chunks = chunking::chunk_transcript(&params.0.transcript, max_chunk_chars);
+        if chunks.is_empty() {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "Transcript is empty", json!({
+                "suggestion": "Provide the conversation transcript with one speaker turn per line"
+            })));
+        }
+
+        info!("store_conversation_summary: customer_id={}, session_id={}, chunks={}", customer_id, session_id, chunks.len());
+
+        let api_key = self.config.get_api_key().unwrap_or_default();
+        let current_timestamp = self.clock.timestamp();
+        let chunk_count = chunks.len();
+        let mut stored = Vec::new();
+
+        for (chunk_index, chunk) in chunks.iter().enumerate() {
+            let communication_id = format!("{}_CHUNK_{}", session_id, chunk_index);
+
+            let mut data = json!({
+                "customer_id": customer_id,
+                "business_id": business_id,
+                "communication_id": communication_id,
+                "contact_method": params.0.contact_method.clone().unwrap_or_default(),
+                "contact_reason": params.0.contact_reason.clone().unwrap_or_else(|| "inquiry".to_string()),
+                "timestamp": current_timestamp,
+                "duration_seconds": 0,
+                "resolution_status": "pending",
+                "agent_id": params.0.agent_id.clone().unwrap_or_default(),
+                // Session metadata stored as JSON string for later recall
+                "channel_details": json!({
+                    "session_id": session_id,
+                    "chunk_index": chunk_index,
+                    "chunk_count": chunk_count
+                }).to_string(),
+                "created_at": current_timestamp,
+                "updated_at": current_timestamp,
+                "text_interaction": chunk,
+            });
+
+            if self.config.is_mcp_embedding_enabled() {
+                match self.generate_embedding(chunk, &api_key).await {
+                    Ok(embedding) => {
+                        data["embedding"] = json!(embedding.iter().map(|&x| x as f64).collect::<Vec<f64>>());
+                        data["embedding_model"] = json!(self.embedding_model_name());
+                        self.note_corpus_model("communications", embedding.len()).await;
+                    }
+                    Err(e) => {
+                        error!("✗ Failed to generate embedding for chunk {}: {}", chunk_index, e);
+                        return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Failed to generate embedding for chunk {}: {}", chunk_index, e), json!({
+                            "session_id": session_id,
+                            "chunks_stored": stored.len(),
+                            "suggestion": "Check embedding configuration and API connectivity"
+                        })));
+                    }
+                }
+            }
+
+            match self.helix_client.query("add_customer_communication_memory", data).await {
+                Ok(_) => stored.push(communication_id),
+                Err(e) => {
+                    error!("store_conversation_summary failed on chunk {}: {}", chunk_index, e);
+                    return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to store chunk {}: {}", chunk_index, e), json!({
+                        "session_id": session_id,
+                        "chunks_stored": stored.len(),
+                        "stored_communication_ids": stored
+                    })));
+                }
+            }
+        }
+
+        info!("✓ Stored {} conversation chunks for session {}", stored.len(), session_id);
+
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "customer_id": customer_id,
+            "business_id": business_id,
+            "session_id": session_id,
+            "chunks_stored": stored.len(),
+            "communication_ids": stored,
+            "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
+            "recall_hint": "Use search_semantic with memory_types=[\"communications\"] and customer_id to recall this conversation"
+        })))
+    }
+
+    // ========================================================================
+    // CUSTOMER INTERACTION TOOLS - Track detailed customer interactions
+    // ========================================================================
+
+    #[tool(description = "Create customer product interaction - track detailed customer-product interactions with reasons (likes, dislikes, purchases, views, reviews). Use query_business_memory to get product_id. Purchased interactions with a purchase_amount accrue loyalty points when the business has a loyalty rule.")]
+    async fn create_customer_product_interaction(&self, params: Parameters<CreateCustomerProductInteractionParam>) -> Result<CallToolResult, McpError> {
+        let customer_id = &params.0.customer_id;
+        let product_id = &params.0.product_id;
+        let interaction_type = &params.0.interaction_type;
+        let text_reason = &params.0.text_reason;
+        
+        // Always auto-generate interaction_id
+        let interaction_id = format!("INT_{}", self.ids.next_id());
+        
+        info!("create_customer_product_interaction: customer_id={}, product_id={}, interaction_id={}, type={}", customer_id, product_id, interaction_id, interaction_type);
+
+        // Build the data payload with all fields
+        let timestamp = self.clock.timestamp();
+        let mut data = json!({
+            "customer_id": customer_id,
+            "product_id": product_id,
+            "interaction_id": interaction_id,
+            "interaction_type": interaction_type,
+            "rating": params.0.rating.unwrap_or(0),
+            "timestamp": timestamp,
+            "channel": params.0.channel.as_ref().unwrap_or(&String::from("")),
+            "session_duration": params.0.session_duration.unwrap_or(0),
+            "purchase_amount": params.0.purchase_amount.unwrap_or(0.0),
+            "currency": params.0.currency.as_ref().unwrap_or(&String::from("USD")),
+            "issue_category": params.0.issue_category.as_ref().unwrap_or(&String::from("")),
+            "resolution_status": params.0.resolution_status.as_ref().unwrap_or(&String::from("none")),
+            "created_at": timestamp,
+            "updated_at": timestamp,
+            "text_reason": text_reason
+        });
+
+        // Check if embedding needs to be generated (MCP mode)
+        if self.config.is_mcp_embedding_enabled() {
+            info!("Generating embedding for text_reason...");
+            
+            let api_key = self.config.get_api_key().unwrap_or_default();
+            
+            match self.generate_embedding(text_reason, &api_key).await {
+                Ok(embedding) => {
+                    info!("✓ Generated {} dimensional embedding", embedding.len());
+
+                    // Add embedding to data (convert f32 to f64 for HelixDB)
+                    data["embedding"] = json!(embedding.iter().map(|&x| x as f64).collect::<Vec<f64>>());
+
+                    let model_name = match self.config.embedding.provider {
+                        Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
+                            self.config.embedding.model.clone().unwrap_or_else(|| "unknown".to_string())
+                        }
+                        Some(config::EmbeddingProvider::Local) => "local".to_string(),
+                        Some(config::EmbeddingProvider::Tcp) => "tcp-local".to_string(),
+                        None => "unknown".to_string()
+                    };
+                    data["embedding_model"] = json!(model_name);
+                    self.note_corpus_model("product_interactions", embedding.len()).await;
+                }
+                Err(e) => {
+                    error!("✗ Failed to generate embedding: {}", e);
+                    return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Failed to generate embedding: {}", e), json!({
+                        "suggestion": "Check embedding configuration and API connectivity"
+                    })));
+                }
+            }
+        } else {
+            info!("Using HelixDB embedding mode - HelixDB will generate embedding");
+        }
+
+        // Execute the query
+        match self.helix_client.query("add_customer_product_interaction", data).await {
+            Ok(result) => {
+                let purchase_amount = params.0.purchase_amount.unwrap_or(0.0);
+                let loyalty = if interaction_type == "purchased" && purchase_amount > 0.0 {
+                    let currency = params.0.currency.as_deref().unwrap_or("USD");
+                    self.accrue_points("product", product_id, customer_id, &interaction_id, purchase_amount, currency).await
+                } else {
+                    None
+                };
+                Ok(CallToolResult::structured(json!({
+                    "success": true,
+                    "interaction_type": "product",
+                    "customer_id": customer_id,
+                    "product_id": product_id,
+                    "loyalty": loyalty,
+                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
+                    "result": result
+                })))
+            }
+            Err(e) => {
+                error!("create_customer_product_interaction failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to create product interaction: {}", e), json!({})))
+            }
+        }
+    }
+
+    #[tool(description = "Create customer service interaction - track detailed customer-service interactions with feedback (bookings, completions, reviews, cancellations). Use query_business_memory to get service_id. Completed interactions with a cost_actual accrue loyalty points when the business has a loyalty rule.")]
+    async fn create_customer_service_interaction(&self, params: Parameters<CreateCustomerServiceInteractionParam>) -> Result<CallToolResult, McpError> {
+        let customer_id = &params.0.customer_id;
+        let service_id = &params.0.service_id;
+        let interaction_type = &params.0.interaction_type;
+        let text_feedback = &params.0.text_feedback;
+        
+        // Always auto-generate interaction_id
+        let interaction_id = format!("INT_{}", self.ids.next_id());
+        
+        info!("create_customer_service_interaction: customer_id={}, service_id={}, interaction_id={}, type={}", customer_id, service_id, interaction_id, interaction_type);
+
+        // Build the data payload with all fields
+        let timestamp = self.clock.timestamp();
+        let mut data = json!({
+            "customer_id": customer_id,
+            "service_id": service_id,
+            "interaction_id": interaction_id,
+            "interaction_type": interaction_type,
+            "satisfaction_rating": params.0.satisfaction_rating.unwrap_or(3),
+            "timestamp": timestamp,
+            "duration_actual": params.0.duration_actual.unwrap_or(0),
+            "cost_actual": params.0.cost_actual.unwrap_or(0.0),
+            "currency": params.0.currency.as_ref().unwrap_or(&String::from("USD")),
+            "outcome": params.0.outcome.as_ref().unwrap_or(&String::from("")),
+            "created_at": timestamp,
+            "updated_at": timestamp,
+            "text_feedback": text_feedback
+        });
+
+        // Check if embedding needs to be generated (MCP mode)
+        if self.config.is_mcp_embedding_enabled() {
+            info!("Generating embedding for text_feedback...");
+            
+            let api_key = self.config.get_api_key().unwrap_or_default();
+            
+            match self.generate_embedding(text_feedback, &api_key).await {
+                Ok(embedding) => {
+                    info!("✓ Generated {} dimensional embedding", embedding.len());
+
+                    // Add embedding to data (convert f32 to f64 for HelixDB)
+                    data["embedding"] = json!(embedding.iter().map(|&x| x as f64).collect::<Vec<f64>>());
+
+                    let model_name = match self.config.embedding.provider {
+                        Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
+                            self.config.embedding.model.clone().unwrap_or_else(|| "unknown".to_string())
+                        }
+                        Some(config::EmbeddingProvider::Local) => "local".to_string(),
+                        Some(config::EmbeddingProvider::Tcp) => "tcp-local".to_string(),
+                        None => "unknown".to_string()
+                    };
+                    data["embedding_model"] = json!(model_name);
+                    self.note_corpus_model("service_interactions", embedding.len()).await;
+                }
+                Err(e) => {
+                    error!("✗ Failed to generate embedding: {}", e);
+                    return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Failed to generate embedding: {}", e), json!({
+                        "suggestion": "Check embedding configuration and API connectivity"
+                    })));
+                }
+            }
+        } else {
+            info!("Using HelixDB embedding mode - HelixDB will generate embedding");
+        }
+
+        // Execute the query
+        match self.helix_client.query("add_customer_service_interaction", data).await {
+            Ok(result) => {
+                let cost = params.0.cost_actual.unwrap_or(0.0);
+                let loyalty = if interaction_type == "completed" && cost > 0.0 {
+                    let currency = params.0.currency.as_deref().unwrap_or("USD");
+                    self.accrue_points("service", service_id, customer_id, &interaction_id, cost, currency).await
+                } else {
+                    None
+                };
+                Ok(CallToolResult::structured(json!({
+                    "success": true,
+                    "interaction_type": "service",
+                    "customer_id": customer_id,
+                    "service_id": service_id,
+                    "loyalty": loyalty,
+                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
+                    "result": result
+                })))
+            }
+            Err(e) => {
+                error!("create_customer_service_interaction failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to create service interaction: {}", e), json!({})))
+            }
+        }
+    }
+
+    /// Accrue loyalty points for a purchase of a product/service under its
+    /// business's rule; `None` when the business has no loyalty rule
+    async fn accrue_points(&self, memory_type: &str, entity_id: &str, customer_id: &str, interaction_id: &str, amount: f64, currency: &str) -> Option<serde_json::Value> {
+        let (id_field, _, lookup_query) = memory_types::entity(memory_type)?;
+        let business_id = match self.helix_client.query(lookup_query, json!({ id_field: entity_id })).await {
+            Ok(result) => HelixClient::result_items(&result)
+                .first()
+                .and_then(|m| m.get("business_id").and_then(|v| v.as_str()).map(str::to_string))?,
+            Err(e) => {
+                warn!("Loyalty: failed to look up {} {}: {}", memory_type, entity_id, e);
+                return None;
+            }
+        };
+        let rule = match self.helix_client.query("get_loyalty_rule", json!({"business_id": business_id})).await {
+            Ok(result) => loyalty::Rule::from_node(HelixClient::result_items(&result).first()?),
+            Err(e) => {
+                warn!("Loyalty: failed to get rule for {}: {}", business_id, e);
+                return None;
+            }
+        };
+        let points = match rule.points_for(amount, currency, &self.exchange_rates.current()) {
+            Ok(points) if points > 0 => points,
+            Ok(_) => return Some(json!({"business_id": business_id, "points_earned": 0})),
+            Err(reason) => return Some(json!({"business_id": business_id, "points_earned": 0, "reason": reason})),
+        };
+        let transaction = json!({
+            "transaction_id": format!("PTS_{}", self.ids.next_id()),
+            "business_id": business_id,
+            "customer_id": customer_id,
+            "points": points,
+            "kind": "accrual",
+            "interaction_id": interaction_id,
+            "amount": amount,
+            "currency": currency,
+            "reason": format!("{} purchase", memory_type),
+            "created_at": self.clock.timestamp()
+        });
+        match self.helix_client.query("add_points_transaction", transaction).await {
+            Ok(_) => Some(json!({"business_id": business_id, "points_earned": points})),
+            Err(e) => {
+                warn!("Loyalty: failed to record {} points for {}: {}", points, customer_id, e);
+                Some(json!({"business_id": business_id, "points_earned": 0, "reason": format!("Failed to record points: {}", e)}))
+            }
+        }
+    }
+
+    #[tool(description = "Set loyalty rule - define how a business's customers earn points: points_per_unit per unit spent on purchased product interactions and completed service interactions, from min_purchase up, converted into the rule's currency first. Replaces the business's existing rule. REQUIRED: business_id, points_per_unit. Optional: currency, min_purchase (default 0), point_value (redemption value of one point, default 0.01), active (default true).")]
+    async fn set_loyalty_rule(&self, params: Parameters<SetLoyaltyRuleParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        info!("set_loyalty_rule: business_id={}, points_per_unit={}", p.business_id, p.points_per_unit);
+        let min_purchase = p.min_purchase.unwrap_or(0.0);
+        let point_value = p.point_value.unwrap_or(0.01);
+        if !(p.points_per_unit.is_finite() && p.points_per_unit >= 0.0) || min_purchase < 0.0 || point_value < 0.0 {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "points_per_unit, min_purchase and point_value must be 0 or more", json!({
+                "points_per_unit": p.points_per_unit,
+                "min_purchase": min_purchase,
+                "point_value": point_value
+            })));
+        }
+
+        let existing = match self.helix_client.query("get_loyalty_rule", json!({"business_id": p.business_id})).await {
+            Ok(result) => !HelixClient::result_items(&result).is_empty(),
+            Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get loyalty rule: {}", e), json!({}))),
+        };
+        let timestamp = self.clock.timestamp();
+        let mut payload = json!({
+            "business_id": p.business_id,
+            "points_per_unit": p.points_per_unit,
+            "currency": p.currency.as_deref().unwrap_or("").trim().to_uppercase(),
+            "min_purchase": min_purchase,
+            "point_value": point_value,
+            "active": p.active.unwrap_or(true)
+        });
+        let query_name = if existing {
+            payload["updated_at"] = json!(timestamp);
+            "update_loyalty_rule"
+        } else {
+            payload["created_at"] = json!(timestamp);
+            "add_loyalty_rule"
+        };
+        match self.helix_client.query(query_name, payload).await {
+            Ok(result) => Ok(CallToolResult::structured(json!({
+                "success": true,
+                "action": if existing { "updated" } else { "created" },
+                "business_id": p.business_id,
+                "rule": HelixClient::result_items(&result).into_iter().next()
+            }))),
+            Err(e) => {
+                error!("set_loyalty_rule failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to save loyalty rule: {}", e), json!({"query_used": query_name})))
+            }
+        }
+    }
+
+    #[tool(description = "Get customer points - a customer's loyalty points balance with a business, lifetime earned and redeemed, the balance's redemption value, and recent transactions. REQUIRED: business_id, customer_id. Optional: limit (recent transactions, default 20).")]
+    async fn get_customer_points(&self, params: Parameters<GetCustomerPointsParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        info!("get_customer_points: business_id={}, customer_id={}", p.business_id, p.customer_id);
+        let (rule, transactions) = tokio::join!(
+            self.helix_client.query("get_loyalty_rule", json!({"business_id": p.business_id})),
+            self.helix_client.query("get_customer_points_transactions", json!({"business_id": p.business_id, "customer_id": p.customer_id}))
+        );
+        let (rule, mut transactions) = match (rule, transactions) {
+            (Ok(rule), Ok(transactions)) => (HelixClient::result_items(&rule).into_iter().next(), HelixClient::result_items(&transactions)),
+            (Err(e), _) | (_, Err(e)) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get loyalty points: {}", e), json!({}))),
+        };
+        let mut summary = loyalty::balance(&transactions);
+        let point_value = rule.as_ref().map(|r| loyalty::Rule::from_node(r).point_value);
+        summary["value"] = json!(point_value.map(|v| (summary["balance"].as_i64().unwrap_or(0) as f64 * v * 100.0).round() / 100.0));
+        transactions.sort_by_key(|t| std::cmp::Reverse(t.get("created_at").and_then(|v| v.as_i64()).unwrap_or(0)));
+        transactions.truncate(p.limit.unwrap_or(20));
+
+        Ok(CallToolResult::structured(json!({
+            "business_id": p.business_id,
+            "customer_id": p.customer_id,
+            "points": summary,
+            "rule": rule,
+            "transactions": transactions
+        })))
+    }
+
+    #[tool(description = "Redeem points - spend a customer's loyalty points with a business. Refuses to redeem more than the balance. REQUIRED: business_id, customer_id, points (more than 0). Optional: reason (what the points paid for).")]
+    async fn redeem_points(&self, params: Parameters<RedeemPointsParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        info!("redeem_points: business_id={}, customer_id={}, points={}", p.business_id, p.customer_id, p.points);
+        if p.points <= 0 {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "points must be greater than 0", json!({"points": p.points})));
+        }
+        // Held until the redemption is written so the balance can't change underneath
+        let _redemption = self.redemption_locks.lock(&p.business_id, &p.customer_id).await;
+        let (rule, transactions) = tokio::join!(
+            self.helix_client.query("get_loyalty_rule", json!({"business_id": p.business_id})),
+            self.helix_client.query("get_customer_points_transactions", json!({"business_id": p.business_id, "customer_id": p.customer_id}))
+        );
+        let (rule, transactions) = match (rule, transactions) {
+            (Ok(rule), Ok(transactions)) => (HelixClient::result_items(&rule).into_iter().next(), HelixClient::result_items(&transactions)),
+            (Err(e), _) | (_, Err(e)) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get loyalty points: {}", e), json!({}))),
+        };
+        let Some(rule) = rule.map(|r| loyalty::Rule::from_node(&r)) else {
+            return Ok(tool_error(ErrorCode::NotFound, format!("Business {} has no loyalty rule", p.business_id), json!({
+                "suggestion": "Create one with set_loyalty_rule"
+            })));
+        };
+        let balance = loyalty::balance(&transactions)["balance"].as_i64().unwrap_or(0);
+        if p.points > balance {
+            return Ok(tool_error(ErrorCode::Conflict, format!("Only {} points available; cannot redeem {}", balance, p.points), json!({
+                "balance": balance,
+                "requested": p.points
+            })));
+        }
+
+        let transaction_id = format!("PTS_{}", self.ids.next_id());
+        let transaction = json!({
+            "transaction_id": transaction_id,
+            "business_id": p.business_id,
+            "customer_id": p.customer_id,
+            "points": -p.points,
+            "kind": "redemption",
+            "interaction_id": "",
+            "amount": 0.0,
+            "currency": rule.currency,
+            "reason": p.reason.clone().unwrap_or_default(),
+            "created_at": self.clock.timestamp()
+        });
+        if let Err(e) = self.helix_client.query("add_points_transaction", transaction).await {
+            error!("redeem_points failed: {}", e);
+            return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to redeem points: {}", e), json!({})));
+        }
+
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "business_id": p.business_id,
+            "customer_id": p.customer_id,
+            "transaction_id": transaction_id,
+            "points_redeemed": p.points,
+            "value": (p.points as f64 * rule.point_value * 100.0).round() / 100.0,
+            "currency": rule.currency,
+            "balance": balance - p.points,
+            "reason": p.reason
+        })))
+    }
+
+    #[tool(description = "Query customer interactions - get product interactions, service interactions and/or location visits for a customer. Optional filters: exact field matches, {\"gte\"/\"lte\"} ranges, and since/until (unix seconds, RFC 3339 or YYYY-MM-DD) on timestamp/created_at. Optional channel (e.g. \"store\", comma-separated) keeps only interactions from those channels; location visits count as \"store\". Optional sort_by, order (asc/desc), limit, offset")]
+    async fn query_customer_interactions(&self, params: Parameters<QueryCustomerInteractionsParam>) -> Result<CallToolResult, McpError> {
+        let customer_id = &params.0.customer_id;
+        let interaction_type_input = &params.0.interaction_type;
+        
+        // Normalize to singular (accept both "products" and "product")
+        let interaction_type = memory_types::normalize(interaction_type_input);
+        
+        info!("query_customer_interactions: customer_id={}, type={} (normalized from: {})", customer_id, interaction_type, interaction_type_input);
+
+        let mut all_interactions = json!({});
+
+        match interaction_type {
+            "product" => {
+                // Get product interactions only
+                match self.helix_client.query(
+                    "get_customer_product_interactions",
+                    json!({"customer_id": customer_id})
+                ).await {
+                    Ok(interactions) => {
+                        all_interactions["product_interactions"] = interactions;
+                    }
+                    Err(e) => {
+                        error!("Failed to get product interactions: {}", e);
+                        return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get product interactions: {}", e), json!({})));
+                    }
+                }
+            }
+            "service" => {
+                // Get service interactions only
+                match self.helix_client.query(
+                    "get_customer_service_interactions",
+                    json!({"customer_id": customer_id})
+                ).await {
+                    Ok(interactions) => {
+                        all_interactions["service_interactions"] = interactions;
+                    }
+                    Err(e) => {
+                        error!("Failed to get service interactions: {}", e);
+                        return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get service interactions: {}", e), json!({})));
+                    }
+                }
+            }
+            "visit" => {
+                // Get location visits only
+                match self.helix_client.query(
+                    "get_customer_location_visits",
+                    json!({"customer_id": customer_id})
+                ).await {
+                    Ok(visits) => {
+                        all_interactions["location_visits"] = visits;
+                    }
+                    Err(e) => {
+                        error!("Failed to get location visits: {}", e);
+                        return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get location visits: {}", e), json!({})));
+                    }
+                }
+            }
+            "all" => {
+                // Get product and service interactions and location visits
+                if let Ok(product_interactions) = self.helix_client.query(
+                    "get_customer_product_interactions",
+                    json!({"customer_id": customer_id})
+                ).await {
+                    all_interactions["product_interactions"] = product_interactions;
+                }
+                
+                if let Ok(service_interactions) = self.helix_client.query(
+                    "get_customer_service_interactions",
+                    json!({"customer_id": customer_id})
+                ).await {
+                    all_interactions["service_interactions"] = service_interactions;
+                }
+
+                if let Ok(visits) = self.helix_client.query(
+                    "get_customer_location_visits",
+                    json!({"customer_id": customer_id})
+                ).await {
+                    all_interactions["location_visits"] = visits;
+                }
+            }
+            _ => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid interaction_type: {}. Valid types: product, service, visit, all", interaction_type), json!({})));
+            }
+        }
+
+        // Apply filters if provided
+        if let Some(filters) = &params.0.filters {
+            all_interactions = filters::apply(all_interactions, filters);
+        }
+        if let Some(channel) = &params.0.channel {
+            all_interactions = channels::filter(all_interactions, &channels::parse(channel));
+        }
+        all_interactions = filters::order(all_interactions, params.0.sort_by.as_deref(), params.0.order.as_deref(), params.0.limit, params.0.offset);
+
+        // Count results
+        let product_count = all_interactions.get("product_interactions")
+            .map(|v| HelixClient::result_items(v).len())
+            .unwrap_or(0);
+        let service_count = all_interactions.get("service_interactions")
+            .map(|v| HelixClient::result_items(v).len())
+            .unwrap_or(0);
+        let visit_count = all_interactions.get("location_visits")
+            .map(|v| HelixClient::result_items(v).len())
+            .unwrap_or(0);
+
+        Ok(CallToolResult::structured(json!({
+            "customer_id": customer_id,
+            "interaction_type": interaction_type,
+            "product_count": product_count,
+            "service_count": service_count,
+            "visit_count": visit_count,
+            "total_count": product_count + service_count + visit_count,
+            "data": all_interactions
+        })))
+    }
+
+    #[tool(description = "Search customer interactions semantically - find product and service interactions by meaning using AI embeddings")]
+    async fn search_customer_interactions(&self, params: Parameters<SearchCustomerInteractionsParam>) -> Result<CallToolResult, McpError> {
+        let query = &params.0.query;
+        let interaction_types_input = &params.0.interaction_types;
+        let limit = params.0.limit.unwrap_or(10);
+        
+        // Normalize all interaction types to singular (accept both "products" and "product")
+        let interaction_types: Vec<&str> = interaction_types_input
+            .iter()
+            .map(|t| memory_types::normalize(t.as_str()))
+            .collect();
+        
+        info!("search_customer_interactions: query='{}', types={:?} (normalized from: {:?}), limit={}", query, interaction_types, interaction_types_input, limit);
+
+        // Check embedding mode
+        if self.config.is_helixdb_embedding_enabled() {
+            info!("Using HelixDB embedding mode (Embed() function in queries)");
+            
+            let mut all_results = Vec::new();
+            
+            // Search across requested interaction types
+            for interaction_type in &interaction_types {
+                let query_name = match *interaction_type {
+                    "product" => "search_customer_product_interactions",
+                    "service" => "search_customer_service_interactions",
+                    _ => {
+                        info!("Skipping unsupported interaction type: {}", interaction_type);
+                        continue;
+                    }
+                };
+
+                let mut payload = json!({
+                    "query_embedding": query,
+                    "limit": limit,
+                });
+
+                if let Some(customer_id) = &params.0.customer_id {
+                    payload["customer_id"] = json!(customer_id);
+                }
+
+                match self.helix_client.query(query_name, payload).await {
+                    Ok(results) => {
+                        if let Some(array) = results.as_array() {
+                            all_results.extend(array.iter().cloned());
+                        }
+                    }
+                    Err(e) => {
+                        error!("Interaction search failed for {}: {}", interaction_type, e);
+                    }
+                }
+            }
+
+            return Ok(CallToolResult::structured(json!({
+                "query": query,
+                "interaction_types": interaction_types,
+                "total_results": all_results.len(),
+                "limit": limit,
+                "embedding_mode": "helixdb",
+                "results": all_results
+            })));
+        }
+
+        // MCP mode: Generate embedding
+        if self.config.is_mcp_embedding_enabled() {
+            info!("Using MCP embedding mode");
+            
+            let api_key = self.config.get_api_key().unwrap_or_default();
+            
+            let query_embedding = match self.generate_query_embedding(query, &api_key).await {
+                Ok(embedding) => {
+                    info!("✓ Generated embedding vector with {} dimensions", embedding.len());
+                    embedding
+                }
+                Err(e) => {
+                    error!("✗ Failed to generate embedding: {}", e);
+                    return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Embedding generation failed: {}", e), json!({
+                        "suggestion": "Check API key, network connection, and server status"
+                    })));
+                }
+            };
+
+            let collections: Vec<String> = interaction_types.iter().map(|t| format!("{}_interactions", t)).collect();
+            let collections: Vec<&str> = collections.iter().map(String::as_str).collect();
+            let (refused, model_warnings) = self.check_corpus_models(&collections, query_embedding.len()).await;
+
+            let mut all_results = Vec::new();
+            
+            for interaction_type in &interaction_types {
+                if refused.contains(&format!("{}_interactions", interaction_type)) {
+                    continue;
+                }
+                let query_name = match *interaction_type {
+                    "product" => "search_customer_product_interactions",
+                    "service" => "search_customer_service_interactions",
+                    _ => {
+                        info!("Skipping unsupported interaction type: {}", interaction_type);
+                        continue;
+                    }
+                };
+
+                let mut payload = json!({
+                    "query_embedding": query_embedding,
+                    "limit": limit,
+                });
+
+                if let Some(customer_id) = &params.0.customer_id {
+                    payload["customer_id"] = json!(customer_id);
+                }
+
+                match self.helix_client.query(query_name, payload).await {
+                    Ok(results) => {
+                        if let Some(array) = results.as_array() {
+                            all_results.extend(array.iter().cloned());
+                        }
+                    }
+                    Err(e) => {
+                        error!("Interaction search failed for {}: {}", interaction_type, e);
+                    }
+                }
+            }
+
+            return Ok(CallToolResult::structured(json!({
+                "query": query,
+                "interaction_types": interaction_types,
+                "total_results": all_results.len(),
+                "limit": limit,
+                "embedding_mode": "mcp",
+                "model_warnings": model_warnings,
+                "results": all_results
+            })));
+        }
+
+        Ok(tool_error(ErrorCode::EmbeddingUnavailable, "Invalid embedding configuration. Check mcpconfig.toml", json!({})))
+    }
+
+    #[tool(description = "Analyze interactions - aggregate product and service interactions into counts by interaction_type, average ratings, total spend by currency (plus one total converted into a reporting currency), top products by likes/purchases, and a trend over time buckets. REQUIRED: customer_id or business_id. Optional: interaction_type (product, service, all), bucket (day, week, month), top_n, filters (e.g., since/until), differential_privacy (Laplace noise on all released statistics), reporting_currency (e.g. USD; default from [currency] config), format (json, or csv for section,key,value text ready for a spreadsheet).")]
+    async fn analyze_interactions(&self, params: Parameters<AnalyzeInteractionsParam>) -> Result<CallToolResult, McpError> {
+        let interaction_type = params.0.interaction_type.as_deref().map(memory_types::normalize).unwrap_or("all");
+        let bucket_input = params.0.bucket.as_deref().unwrap_or("month");
+        let Some(bucket) = analytics::TimeBucket::parse(bucket_input) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid bucket: {}. Valid buckets: day, week, month", bucket_input), json!({})));
+        };
+        if !matches!(interaction_type, "product" | "service" | "all") {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid interaction_type: {}. Valid types: product, service, all", interaction_type), json!({})));
+        }
+        let format = params.0.format.as_deref().unwrap_or("json");
+        if !matches!(format, "json" | "csv") {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Unsupported format: {}", format), json!({
+                "supported_formats": ["json", "csv"]
+            })));
+        }
+        let include_products = interaction_type != "service";
+        let include_services = interaction_type != "product";
+
+        info!("analyze_interactions: customer_id={:?}, business_id={:?}, type={}, bucket={:?}",
+            params.0.customer_id, params.0.business_id, interaction_type, bucket);
+
+        let mut product_interactions = Vec::new();
+        let mut service_interactions = Vec::new();
+
+        if let Some(customer_id) = &params.0.customer_id {
+            if include_products {
+                match self.helix_client.query("get_customer_product_interactions", json!({"customer_id": customer_id})).await {
+                    Ok(result) => product_interactions = HelixClient::result_items(&result),
+                    Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get product interactions: {}", e), json!({}))),
+                }
+            }
+            if include_services {
+                match self.helix_client.query("get_customer_service_interactions", json!({"customer_id": customer_id})).await {
+                    Ok(result) => service_interactions = HelixClient::result_items(&result),
+                    Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get service interactions: {}", e), json!({}))),
+                }
+            }
+        } else if let Some(business_id) = &params.0.business_id {
+            // Business scope: interactions referencing any of the business's products/services
+            let scopes = [
+                (include_products, "get_business_products", "product_id", "get_product_interactions_by_product", &mut product_interactions),
+                (include_services, "get_business_services", "service_id", "get_service_interactions_by_service", &mut service_interactions),
+            ];
+            for (included, list_query, id_field, interactions_query, target) in scopes {
+                if !included {
+                    continue;
+                }
+                let entities = match self.helix_client.query(list_query, json!({"business_id": business_id})).await {
+                    Ok(result) => HelixClient::result_items(&result),
+                    Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("{} failed: {}", list_query, e), json!({}))),
+                };
+                for entity_id in entities.iter().filter_map(|e| e.get(id_field).and_then(|v| v.as_str())) {
+                    match self.helix_client.query(interactions_query, json!({ id_field: entity_id })).await {
+                        Ok(result) => target.extend(HelixClient::result_items(&result)),
+                        Err(e) => error!("{} failed for {}: {}", interactions_query, entity_id, e),
+                    }
+                }
+            }
+        } else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "Either customer_id or business_id is required", json!({})));
+        }
+
+        if let Some(filters) = &params.0.filters {
+            product_interactions.retain(|item| filters::matches(item, filters));
+            service_interactions.retain(|item| filters::matches(item, filters));
+        }
+
+        let dp = self.differential_privacy(params.0.differential_privacy, params.0.customer_id.is_none());
+        if dp {
+            let max_amount = self.config.differential_privacy.max_amount;
+            privacy::clamp_amounts(&mut product_interactions, "purchase_amount", max_amount);
+            privacy::clamp_amounts(&mut service_interactions, "cost_actual", max_amount);
+        }
+
+        let mut summary = analytics::summarize_interactions(&product_interactions, &service_interactions, bucket, params.0.top_n.unwrap_or(5));
+        if dp {
+            let dp_config = &self.config.differential_privacy;
+            privacy::privatize_interactions(&mut summary, dp_config.epsilon, dp_config.max_amount, &mut rand::rng());
+        }
+        let reporting_currency = params.0.reporting_currency.clone().unwrap_or_else(|| self.config.currency.reporting_currency.clone());
+        if !reporting_currency.is_empty() {
+            let totals = summary["total_amount_by_currency"].as_object().cloned().unwrap_or_default();
+            summary["total_amount"] = currency::normalize_totals(&totals, &self.exchange_rates.current(), &reporting_currency);
+        }
+        if format == "csv" {
+            return Ok(CallToolResult::success(vec![Content::text(export::report_to_csv(&summary))]));
+        }
+
+        Ok(CallToolResult::structured(json!({
+            "customer_id": params.0.customer_id,
+            "business_id": params.0.business_id,
+            "interaction_type": interaction_type,
+            "bucket": bucket_input,
+            "differential_privacy": dp.then(|| json!({"epsilon": self.config.differential_privacy.epsilon})),
+            "summary": summary
+        })))
+    }
+
+    #[tool(description = "Product affinities (\"frequently liked together\") - customers who liked product A also liked B. Walks product -> customers with a positive interaction -> those customers' other product interactions, and ranks co-occurring products by shared customers (co_customers) and confidence (share of A's customers). REQUIRED: product_id, or business_id for every catalog product. Optional: interaction_types (positive signals, default liked/favorited/purchased), min_support (default 1), top_n (per product, default 10).")]
+    async fn get_product_affinities(&self, params: Parameters<GetProductAffinitiesParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let positive: Vec<&str> = match &p.interaction_types {
+            Some(types) => types.iter().map(String::as_str).collect(),
+            None => analytics::LIKE_TYPES.to_vec(),
+        };
+        let min_support = p.min_support.unwrap_or(1);
+        let top_n = p.top_n.unwrap_or(10);
+
+        info!("get_product_affinities: product_id={:?}, business_id={:?}, types={:?}", p.product_id, p.business_id, positive);
+
+        // Catalog: product names, and the scope recommendations are kept to
+        let catalog: Vec<serde_json::Value> = match &p.business_id {
+            Some(business_id) => match self.helix_client.query("get_business_products", json!({"business_id": business_id})).await {
+                Ok(result) => HelixClient::result_items(&result),
+                Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("get_business_products failed: {}", e), json!({}))),
+            },
+            None => Vec::new(),
+        };
+        let names: std::collections::HashMap<&str, &str> = catalog
+            .iter()
+            .filter_map(|product| Some((product.get("product_id")?.as_str()?, product.get("product_name")?.as_str()?)))
+            .collect();
+        let targets: Vec<String> = match (&p.product_id, &p.business_id) {
+            (Some(product_id), _) => vec![product_id.clone()],
+            (None, Some(_)) => names.keys().map(|id| id.to_string()).collect(),
+            (None, None) => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, "Either product_id or business_id is required", json!({})));
+            }
+        };
+
+        // Hop 1: customers with a positive interaction on a target product
+        let first_hop = futures::future::join_all(
+            targets.iter().map(|id| self.helix_client.query("get_product_interactions_by_product", json!({"product_id": id}))),
+        )
+        .await;
+        let mut customers = std::collections::BTreeSet::new();
+        for result in first_hop {
+            let interactions = match result {
+                Ok(result) => HelixClient::result_items(&result),
+                Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("get_product_interactions_by_product failed: {}", e), json!({}))),
+            };
+            customers.extend(
+                interactions
+                    .iter()
+                    .filter(|i| i.get("interaction_type").and_then(|t| t.as_str()).is_some_and(|t| positive.contains(&t)))
+                    .filter_map(|i| i.get("customer_id").and_then(|c| c.as_str()).map(str::to_string)),
+            );
+        }
+
+        // Hop 2: everything those customers interacted with
+        let second_hop = futures::future::join_all(
+            customers.iter().map(|id| self.helix_client.query("get_customer_product_interactions", json!({"customer_id": id}))),
+        )
+        .await;
+        let mut interactions = Vec::new();
+        for (customer_id, result) in customers.iter().zip(second_hop) {
+            match result {
+                Ok(result) => interactions.extend(HelixClient::result_items(&result)),
+                Err(e) => error!("get_customer_product_interactions failed for {}: {}", customer_id, e),
+            }
+        }
+        if !names.is_empty() {
+            interactions.retain(|i| i.get("product_id").and_then(|id| id.as_str()).is_some_and(|id| names.contains_key(id)));
+        }
+
+        let mut products = analytics::product_affinities(&interactions, &positive, &targets, min_support, top_n);
+        for row in &mut products {
+            let named = |id: &serde_json::Value| id.as_str().and_then(|id| names.get(id)).map(|name| json!(name));
+            if let Some(name) = named(&row["product_id"]) {
+                row["product_name"] = name;
+            }
+            for affinity in row["affinities"].as_array_mut().into_iter().flatten() {
+                if let Some(name) = named(&affinity["product_id"]) {
+                    affinity["product_name"] = name;
+                }
+            }
+        }
+
+        Ok(CallToolResult::structured(json!({
+            "product_id": p.product_id,
+            "business_id": p.business_id,
+            "interaction_types": positive,
+            "min_support": min_support,
+            "customers_considered": customers.len(),
+            "products": products
+        })))
+    }
+
+    #[tool(description = "Explain relationship - the graph evidence connecting a customer to a product or service, to justify a recommendation. Returns paths of nodes and edges, strongest first: interactions with the target (Customer -> Interaction -> Product/Service), then preferences, desires and feedback that mention it by name or ID, then preferences/desires in the same category (inferred links are marked). REQUIRED: customer_id and product_id or service_id. Optional: max_paths (default 20).")]
+    async fn explain_relationship(&self, params: Parameters<ExplainRelationshipParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let (kind, target_id, lookup_query, interactions_query) = match (&p.product_id, &p.service_id) {
+            (Some(id), None) => (explain::TargetKind::Product, id, "get_business_product_by_id", "get_customer_product_interactions"),
+            (None, Some(id)) => (explain::TargetKind::Service, id, "get_business_service_by_id", "get_customer_service_interactions"),
+            _ => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, "Exactly one of product_id or service_id is required", json!({})));
+            }
+        };
+        let customer = json!({"customer_id": p.customer_id});
+
+        info!("explain_relationship: customer_id={}, {}={}", p.customer_id, kind.id_field(), target_id);
+
+        let (node, interactions, preferences, desires, feedback) = tokio::join!(
+            self.helix_client.query(lookup_query, json!({ kind.id_field(): target_id })),
+            self.helix_client.query(interactions_query, customer.clone()),
+            self.helix_client.query("get_customer_preferences", customer.clone()),
+            self.helix_client.query("get_customer_desires", customer.clone()),
+            self.helix_client.query("get_customer_feedback", customer.clone()),
+        );
+        let interactions = match interactions {
+            Ok(result) => HelixClient::result_items(&result),
+            Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("{} failed: {}", interactions_query, e), json!({}))),
+        };
+        let node = node.ok().and_then(|result| HelixClient::result_items(&result).into_iter().next());
+        let target_found = node.is_some();
+        let target = explain::Target::new(kind, target_id, node.as_ref());
+        let memories: Vec<(&str, Vec<serde_json::Value>)> = [("preference", preferences), ("desire", desires), ("feedback", feedback)]
+            .into_iter()
+            .map(|(memory_type, result)| match result {
+                Ok(result) => (memory_type, HelixClient::result_items(&result)),
+                Err(e) => {
+                    error!("explain_relationship: {} lookup failed: {}", memory_type, e);
+                    (memory_type, Vec::new())
+                }
+            })
+            .collect();
+
+        let mut paths = explain::paths(&p.customer_id, &target, &interactions, &memories);
+        let total_paths = paths.len();
+        paths.truncate(p.max_paths.unwrap_or(20));
+
+        Ok(CallToolResult::structured(json!({
+            "customer_id": p.customer_id,
+            "target": node.unwrap_or_else(|| json!({ kind.id_field(): target_id })),
+            "target_found": target_found,
+            "connected": total_paths > 0,
+            "total_paths": total_paths,
+            "paths": paths
+        })))
+    }
+
+    /// Whether to add DP noise: requested explicitly, or enforced for cross-customer scope
+    fn differential_privacy(&self, requested: Option<bool>, cross_customer: bool) -> bool {
+        requested.unwrap_or(false) || (cross_customer && self.config.differential_privacy.enforce_cross_customer)
+    }
+
+    #[tool(description = "Summarize feedback - sentiment rollup of feedback memories for a customer or a business's customers: sentiment distribution, average rating, unresolved and awaiting-response counts, and the top-N negative items (pinned and high-importance first, then most recent). Optional: cluster_themes to group feedback into semantic themes, theme_threshold, top_n, filters (e.g., since/until), differential_privacy (noisy statistics, individual items withheld). REQUIRED: customer_id or business_id.")]
+    async fn summarize_feedback(&self, params: Parameters<SummarizeFeedbackParam>) -> Result<CallToolResult, McpError> {
+        let customer_ids = if let Some(customer_id) = &params.0.customer_id {
+            vec![customer_id.clone()]
+        } else if let Some(business_id) = &params.0.business_id {
+            match self.helix_client.query("get_business_customers", json!({"business_id": business_id})).await {
+                Ok(result) => HelixClient::result_items(&result)
+                    .iter()
+                    .filter_map(|c| c.get("customer_id").and_then(|v| v.as_str()).map(str::to_string))
+                    .collect(),
+                Err(e) => {
+                    error!("summarize_feedback customer lookup failed: {}", e);
+                    return Ok(tool_error(ErrorCode::HelixUnreachable, e.to_string(), json!({})));
+                }
+            }
+        } else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "Either customer_id or business_id is required", json!({})));
+        };
+
+        info!("summarize_feedback: customer_id={:?}, business_id={:?}, customers={}", params.0.customer_id, params.0.business_id, customer_ids.len());
+
+        let cluster_themes = params.0.cluster_themes.unwrap_or(false);
+        let mut feedback = Vec::new();
+        let mut theme_items: Vec<(String, Vec<f64>)> = Vec::new();
+
+        for customer_id in &customer_ids {
+            match self.helix_client.query("get_customer_feedback", json!({"customer_id": customer_id})).await {
+                Ok(result) => feedback.extend(HelixClient::result_items(&result)),
+                Err(e) => error!("get_customer_feedback failed for {}: {}", customer_id, e),
+            }
+            if cluster_themes {
+                if let Ok(result) = self.helix_client.query("export_customer_feedback_embeddings", json!({"customer_id": customer_id})).await {
+                    theme_items.extend(HelixClient::result_items(&result).iter().filter_map(|item| {
+                        let text = item.get("composite_embedding_text").and_then(|v| v.as_str())?;
+                        Some((text.to_string(), similarity::vector_of(item)?))
+                    }));
+                }
+            }
+        }
+
+        if let Some(filters) = &params.0.filters {
+            feedback.retain(|item| filters::matches(item, filters));
+        }
+
+        let mut summary = analytics::summarize_feedback(&feedback, params.0.top_n.unwrap_or(5));
+
+        if cluster_themes {
+            let threshold = params.0.theme_threshold.unwrap_or(0.8);
+            let vectors: Vec<Vec<f64>> = theme_items.iter().map(|(_, v)| v.clone()).collect();
+            let themes: Vec<serde_json::Value> = similarity::cluster(&vectors, threshold)
+                .into_iter()
+                .map(|members| json!({
+                    "size": members.len(),
+                    "representative": theme_items[members[0]].0,
+                    "examples": members.iter().skip(1).take(3).map(|&i| theme_items[i].0.clone()).collect::<Vec<_>>()
+                }))
+                .collect();
+            summary["themes"] = json!(themes);
+        }
+
+        let dp = self.differential_privacy(params.0.differential_privacy, params.0.customer_id.is_none());
+        if dp {
+            privacy::privatize_feedback(&mut summary, self.config.differential_privacy.epsilon, &mut rand::rng());
+        }
+
+        Ok(CallToolResult::structured(json!({
+            "customer_id": params.0.customer_id,
+            "business_id": params.0.business_id,
+            "customers_analyzed": if dp { None } else { Some(customer_ids.len()) },
+            "differential_privacy": dp.then(|| json!({"epsilon": self.config.differential_privacy.epsilon})),
+            "summary": summary
+        })))
+    }
+
+    #[tool(description = "List open issues - the support queue of a customer or a business's customers: product interactions that were disliked or rated 1-2 and aren't resolved, and complaint, negative or 1-2 rated feedback not yet resolved. Oldest first, each with its memory_id and kind for resolve_issue. REQUIRED: customer_id or business_id. Optional: limit (default 50).")]
+    async fn list_open_issues(&self, params: Parameters<ListOpenIssuesParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let customer_ids = if let Some(customer_id) = &p.customer_id {
+            vec![customer_id.clone()]
+        } else if let Some(business_id) = &p.business_id {
+            match self.helix_client.query("get_business_customers", json!({"business_id": business_id})).await {
+                Ok(result) => HelixClient::result_items(&result)
+                    .iter()
+                    .filter_map(|c| c.get("customer_id").and_then(|v| v.as_str()).map(str::to_string))
+                    .collect(),
+                Err(e) => {
+                    error!("list_open_issues customer lookup failed: {}", e);
+                    return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to list business customers: {}", e), json!({})));
+                }
+            }
+        } else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "Either customer_id or business_id is required", json!({})));
+        };
+
+        info!("list_open_issues: customer_id={:?}, business_id={:?}, customers={}", p.customer_id, p.business_id, customer_ids.len());
+
+        let queries = customer_ids
+            .iter()
+            .flat_map(|customer_id| {
+                [
+                    ("get_customer_product_interactions", json!({"customer_id": customer_id})),
+                    ("get_customer_feedback", json!({"customer_id": customer_id})),
+                ]
+            })
+            .collect();
+        let mut interactions = Vec::new();
+        let mut feedback = Vec::new();
+        for (i, result) in self.helix_client.query_batch(queries).await.into_iter().enumerate() {
+            match result {
+                Ok(result) if i % 2 == 0 => interactions.extend(HelixClient::result_items(&result)),
+                Ok(result) => feedback.extend(HelixClient::result_items(&result)),
+                Err(e) => error!("list_open_issues lookup failed for {}: {}", customer_ids[i / 2], e),
+            }
+        }
+
+        let mut issues = issues::open_issues(&interactions, &feedback);
+        let total_open = issues.len();
+        issues.truncate(p.limit.unwrap_or(50));
+
+        Ok(CallToolResult::structured(json!({
+            "customer_id": p.customer_id,
+            "business_id": p.business_id,
+            "total_open": total_open,
+            "issues": issues
+        })))
+    }
+
+    #[tool(description = "Resolve issue - close an open issue from list_open_issues: marks the product interaction resolved (or the feedback resolved), stamps resolved_at, and stores resolution_note as a resolved support communication of the customer with business_id, so the resolution is recallable. REQUIRED: memory_id, kind (product_interaction or feedback), business_id, resolution_note. Optional: agent_id.")]
+    async fn resolve_issue(&self, params: Parameters<ResolveIssueParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let Some(kind) = issues::Kind::parse(&p.kind) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid kind: {}. Valid: product_interaction, feedback", p.kind), json!({})));
+        };
+        let note = p.resolution_note.trim();
+        if note.is_empty() {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "resolution_note is required", json!({})));
+        }
+        info!("resolve_issue: memory_id={}, kind={}, business_id={}", p.memory_id, kind.as_str(), p.business_id);
+
+        let (lookup_query, resolve_query) = kind.queries();
+        let issue = match self.helix_client.query(lookup_query, json!({"memory_id": p.memory_id})).await {
+            Ok(result) => HelixClient::result_items(&result).into_iter().next(),
+            Err(e) => {
+                error!("resolve_issue lookup failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to look up issue: {}", e), json!({})));
+            }
+        };
+        let Some(issue) = issue else {
+            return Ok(tool_error(ErrorCode::NotFound, format!("{} {} not found", kind.as_str(), p.memory_id), json!({
+                "suggestion": "Use the memory_id from list_open_issues"
+            })));
+        };
+        if !issues::is_open(kind, &issue) {
+            return Ok(tool_error(ErrorCode::Conflict, "Issue is not open", json!({"issue": issues::entry(kind, &issue)})));
+        }
+        let entry = issues::entry(kind, &issue);
+        let customer_id = entry["customer_id"].as_str().unwrap_or("").to_string();
+
+        let timestamp = self.clock.timestamp();
+        if let Err(e) = self.helix_client.query(resolve_query, json!({"memory_id": p.memory_id, "timestamp": timestamp})).await {
+            error!("resolve_issue failed: {}", e);
+            return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to resolve issue: {}", e), json!({})));
+        }
+
+        let communication_id = format!("RESOLUTION_{}", self.ids.next_id());
+        let text_interaction = format!("Resolved {} {}: {}", kind.as_str().replace('_', " "), entry["issue_id"].as_str().unwrap_or(""), note);
+        let mut data = json!({
+            "customer_id": customer_id,
+            "business_id": p.business_id,
+            "communication_id": communication_id,
+            "contact_method": "",
+            "contact_reason": "support",
+            "timestamp": timestamp,
+            "duration_seconds": 0,
+            "resolution_status": "resolved",
+            "agent_id": p.agent_id.clone().unwrap_or_default(),
+            "channel_details": json!({"issue_kind": kind.as_str(), "issue_id": entry["issue_id"], "memory_id": p.memory_id}).to_string(),
+            "created_at": timestamp,
+            "updated_at": timestamp,
+            "text_interaction": text_interaction,
+        });
+        if self.config.is_mcp_embedding_enabled() {
+            let api_key = self.config.get_api_key().unwrap_or_default();
+            match self.generate_embedding(&text_interaction, &api_key).await {
+                Ok(embedding) => {
+                    data["embedding"] = json!(embedding.iter().map(|&x| x as f64).collect::<Vec<f64>>());
+                    data["embedding_model"] = json!(self.embedding_model_name());
+                    self.note_corpus_model("communications", embedding.len()).await;
+                }
+                Err(e) => {
+                    // The issue is already closed; report the missing note instead of failing
+                    error!("resolve_issue note embedding failed: {}", e);
+                    return Ok(CallToolResult::structured(json!({
+                        "success": true,
+                        "issue": entry,
+                        "resolved_at": timestamp,
+                        "note_stored": false,
+                        "note_error": format!("Failed to generate embedding: {}", e)
+                    })));
+                }
+            }
+        }
+        let note_stored = match self.helix_client.query("add_customer_communication_memory", data).await {
+            Ok(_) => true,
+            Err(e) => {
+                error!("resolve_issue note failed: {}", e);
+                false
+            }
+        };
+
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "issue": entry,
+            "resolved_at": timestamp,
+            "note_stored": note_stored,
+            "communication_id": note_stored.then_some(communication_id)
+        })))
+    }
+
+    #[tool(description = "Create alert rule - notify when a created or updated memory matches conditions. Conditions compare a written field with a value using <, <=, >, >=, =, != or contains (e.g. \"rating <= 2\", \"purchase_amount > 500\", \"tags contains vip\") and must all hold. A match sends an alert.triggered webhook and an alert event on the /events stream. Rules last until restart; permanent rules go in [alerts] of mcpconfig.toml. REQUIRED: name, when. Optional: memory_type (e.g. interaction, feedback; default any), business_id.")]
+    async fn create_alert_rule(&self, params: Parameters<CreateAlertRuleParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let rule = alerts::Rule {
+            name: p.name.trim().to_string(),
+            when: p.when,
+            memory_type: p.memory_type.as_deref().map(|t| memory_types::normalize(t).to_string()).unwrap_or_else(|| "*".to_string()),
+            business_id: p.business_id,
+        };
+        info!("create_alert_rule: name={}, memory_type={}, when={:?}", rule.name, rule.memory_type, rule.when);
+        match self.alerts.add(rule.clone()) {
+            Ok(total) => Ok(CallToolResult::structured(json!({
+                "success": true,
+                "rule": rule,
+                "total_rules": total,
+                "webhook_enabled": self.webhooks.wants("alert.triggered")
+            }))),
+            Err(e) => Ok(tool_error(ErrorCode::ValidationFailed, e, json!({
+                "suggestion": "Write conditions as \"<field> <op> <value>\", e.g. \"rating <= 2\""
+            }))),
+        }
+    }
+
+    #[tool(description = "List alert rules - the alert rules in effect, from [alerts] in mcpconfig.toml and create_alert_rule.")]
+    async fn list_alert_rules(&self) -> Result<CallToolResult, McpError> {
+        let rules = self.alerts.list();
+        Ok(CallToolResult::structured(json!({
+            "total_rules": rules.len(),
+            "rules": rules
+        })))
+    }
+
+    #[tool(description = "Evaluate customer rules - check a proposed action (e.g. \"send promo at 9pm\", \"recommend product category X\") against the customer's active rule memories before doing it. Catches forbidden hours (\"no messages after 8pm\"), channels (\"email only\", \"no phone calls\") and excluded subjects (\"never recommend dairy\"), honoring rule exceptions. Returns decision allow/deny, the violated strict rules, advisories from flexible rules and guidelines, and the other active rules to weigh. REQUIRED: customer_id, action. Optional: channel, at (time of day or RFC3339; otherwise read from the action text).")]
+    async fn evaluate_customer_rules(&self, params: Parameters<EvaluateCustomerRulesParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let minute = match p.at.as_deref() {
+            None => None,
+            Some(at) => match chrono::DateTime::parse_from_rfc3339(at) {
+                Ok(datetime) => Some(chrono::Timelike::hour(&datetime) * 60 + chrono::Timelike::minute(&datetime)),
+                Err(_) => match customer_rules::time_of_day(at) {
+                    Some(minute) => Some(minute),
+                    None => return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid at '{}'", at), json!({
+                        "suggestion": "Use a time of day such as \"21:00\" or \"9pm\", or RFC3339"
+                    }))),
+                },
+            },
+        };
+        info!("evaluate_customer_rules: customer_id={}, action={:?}", p.customer_id, p.action);
+
+        let rules = match self.helix_client.query("get_customer_rules", json!({"customer_id": p.customer_id})).await {
+            Ok(result) => HelixClient::result_items(&result),
+            Err(e) => {
+                error!("evaluate_customer_rules lookup failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to load customer rules: {}", e), json!({})));
+            }
+        };
+        let action = customer_rules::Action::new(&p.action, p.channel.as_deref(), minute);
+        let mut verdict = customer_rules::evaluate(&rules, &action);
+        verdict["customer_id"] = json!(p.customer_id);
+        verdict["action"] = json!(p.action);
+        Ok(CallToolResult::structured(verdict))
+    }
+
+    #[tool(description = "Is business open - evaluate a business's hours memories (weekly hours, timezone, dated exceptions such as holidays) at a given or current time. Returns open/closed, closing time when open, the next opening time when closed, and any exception reason, all in business local time. REQUIRED: business_id. Optional: at (RFC3339, or YYYY-MM-DD HH:MM local), utc_offset to override the stored timezone.")]
+    async fn is_business_open(&self, params: Parameters<IsBusinessOpenParam>) -> Result<CallToolResult, McpError> {
+        let business_id = &params.0.business_id;
+        info!("is_business_open: business_id={}, at={:?}", business_id, params.0.at);
+
+        let memories = match self.helix_client.query("get_business_hours", json!({"business_id": business_id})).await {
+            Ok(result) => HelixClient::result_items(&result),
+            Err(e) => {
+                error!("is_business_open failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get business hours: {}", e), json!({})));
+            }
+        };
+
+        // Regular schedule first; exceptions from every hours memory apply
+        let Some((weekly, primary)) = hours::business_schedule(&memories) else {
+            return Ok(tool_error(ErrorCode::NotFound, format!("No hours memory found for business_id {}", business_id), json!({
+                "suggestion": "Create one with create_business_memory(memory_type: hours)"
+            })));
+        };
+        if !weekly.has_hours() {
+            return Ok(tool_error(ErrorCode::Conflict, "Hours memory has no parseable opening times", json!({
+                "hours": primary
+            })));
+        }
+
+        let timezone = params.0.utc_offset.clone()
+            .unwrap_or_else(|| primary.get("timezone").and_then(|v| v.as_str()).unwrap_or("").to_string());
+        let Some(offset) = hours::parse_offset(&timezone) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Unsupported timezone: {}", timezone), json!({
+                "suggestion": "Pass utc_offset (e.g., \"-05:00\" or \"EST\"); IANA zone names are not supported"
+            })));
+        };
+
+        let local_now = match params.0.at.as_deref() {
+            None => self.clock.now().with_timezone(&offset).naive_local(),
+            Some(at) => match chrono::DateTime::parse_from_rfc3339(at) {
+                Ok(instant) => instant.with_timezone(&offset).naive_local(),
+                Err(_) => match chrono::NaiveDateTime::parse_from_str(&at.replace('T', " "), "%Y-%m-%d %H:%M") {
+                    Ok(local) => local,
+                    Err(_) => {
+                        return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid at: {}. Use RFC3339 or YYYY-MM-DD HH:MM", at), json!({})));
+                    }
+                },
+            },
+        };
+
+        let status = weekly.status_at(local_now);
+        let local = |t: chrono::NaiveDateTime| t.format("%Y-%m-%d %H:%M").to_string();
+        let (today_hours, _) = weekly.for_date(local_now.date());
+
+        Ok(CallToolResult::structured(json!({
+            "business_id": business_id,
+            "open": status.open,
+            "local_time": local(local_now),
+            "weekday": hours::DAYS[chrono::Datelike::weekday(&local_now).num_days_from_monday() as usize],
+            "timezone": timezone,
+            "utc_offset": offset.to_string(),
+            "todays_hours": today_hours.map(|i| format!("{}-{}", i.open.format("%H:%M"), i.close.format("%H:%M"))),
+            "closes_at": status.closes_at.map(local),
+            "next_open": status.next_open.map(local),
+            "exception_reason": status.reason
+        })))
+    }
+
+    #[tool(description = "Get service availability - open booking slots for a service on a day, from the service's duration_minutes, the business hours memory (exceptions such as holidays applied) and the service's existing booked interactions. Answers \"can I book Tuesday at 3?\": pass time to check one start time and get the nearest open slots if it is taken. Times are business local. REQUIRED: business_id, service_id, date (YYYY-MM-DD). Optional: time, step_minutes (default 30), capacity (concurrent bookings, default 1), utc_offset.")]
+    async fn get_service_availability(&self, params: Parameters<GetServiceAvailabilityParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        info!("get_service_availability: business_id={}, service_id={}, date={}, time={:?}", p.business_id, p.service_id, p.date, p.time);
+
+        let Ok(date) = chrono::NaiveDate::parse_from_str(p.date.trim(), "%Y-%m-%d") else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid date: {}. Use YYYY-MM-DD", p.date), json!({"date": p.date})));
+        };
+        let requested = match p.time.as_deref() {
+            Some(time) => match hours::parse_time(time) {
+                Some(time) => Some(date.and_time(time)),
+                None => return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid time: {}. Use e.g. 15:00 or 3pm", time), json!({"time": time}))),
+            },
+            None => None,
+        };
+        let step = p.step_minutes.unwrap_or(30);
+        if step <= 0 {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "step_minutes must be greater than 0", json!({"step_minutes": step})));
+        }
+
+        let (service, hours_memories, interactions) = tokio::join!(
+            self.helix_client.query("get_business_service_by_id", json!({"service_id": p.service_id})),
+            self.helix_client.query("get_business_hours", json!({"business_id": p.business_id})),
+            self.helix_client.query("get_service_interactions_by_service", json!({"service_id": p.service_id}))
+        );
+        let (service, hours_memories, interactions) = match (service, hours_memories, interactions) {
+            (Ok(service), Ok(hours_memories), Ok(interactions)) => (
+                HelixClient::result_items(&service)
+                    .into_iter()
+                    .find(|s| s.get("business_id").and_then(|v| v.as_str()) == Some(p.business_id.as_str())),
+                HelixClient::result_items(&hours_memories),
+                HelixClient::result_items(&interactions),
+            ),
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                error!("get_service_availability failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to load service availability: {}", e), json!({})));
+            }
+        };
+        let Some(service) = service else {
+            return Ok(tool_error(ErrorCode::NotFound, format!("Service {} not found for business {}", p.service_id, p.business_id), json!({
+                "service_id": p.service_id,
+                "business_id": p.business_id
+            })));
+        };
+        let Some((weekly, primary)) = hours::business_schedule(&hours_memories) else {
+            return Ok(tool_error(ErrorCode::NotFound, format!("No hours memory found for business_id {}", p.business_id), json!({
+                "suggestion": "Create one with create_business_memory(memory_type: hours)"
+            })));
+        };
+        let timezone = p.utc_offset.clone()
+            .unwrap_or_else(|| primary.get("timezone").and_then(|v| v.as_str()).unwrap_or("").to_string());
+        let Some(offset) = hours::parse_offset(&timezone) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Unsupported timezone: {}", timezone), json!({
+                "suggestion": "Pass utc_offset (e.g., \"-05:00\" or \"EST\"); IANA zone names are not supported"
+            })));
+        };
+
+        let duration_minutes = service.get("duration_minutes").and_then(|v| v.as_i64()).filter(|m| *m > 0).unwrap_or(60);
+        let capacity = p.capacity.unwrap_or(1).max(1);
+        let local = |t: chrono::NaiveDateTime| t.format("%H:%M").to_string();
+        let (interval, reason) = weekly.for_date(date);
+        let now = self.clock.now().with_timezone(&offset).naive_local();
+        let bookings = booking::bookings(&interactions, offset, duration_minutes);
+        let slots = interval
+            .map(|interval| booking::open_slots(
+                interval.on(date),
+                chrono::Duration::minutes(duration_minutes),
+                chrono::Duration::minutes(step),
+                &bookings,
+                capacity,
+                Some(now),
+            ))
+            .unwrap_or_default();
+
+        // For a requested time: whether it is free, else the open slots closest to it
+        let request = requested.map(|start| {
+            let available = slots.iter().any(|slot| slot.start == start);
+            let mut nearest: Vec<&booking::Booking> = slots.iter().collect();
+            nearest.sort_by_key(|slot| (slot.start - start).num_minutes().abs());
+            json!({
+                "time": local(start),
+                "available": available,
+                "alternatives": if available { vec![] } else { nearest.iter().take(3).map(|slot| local(slot.start)).collect() }
+            })
+        });
+
+        Ok(CallToolResult::structured(json!({
+            "business_id": p.business_id,
+            "service_id": p.service_id,
+            "service_name": service.get("service_name"),
+            "date": date.format("%Y-%m-%d").to_string(),
+            "weekday": hours::DAYS[chrono::Datelike::weekday(&date).num_days_from_monday() as usize],
+            "timezone": timezone,
+            "open": interval.is_some(),
+            "hours": interval.map(|i| format!("{}-{}", i.open.format("%H:%M"), i.close.format("%H:%M"))),
+            "exception_reason": reason,
+            "duration_minutes": duration_minutes,
+            "capacity": capacity,
+            "booked": bookings.iter().filter(|b| b.start.date() == date).count(),
+            "requested": request,
+            "slots": slots.iter().map(|slot| json!({"start": local(slot.start), "end": local(slot.end)})).collect::<Vec<_>>()
+        })))
+    }
+
+    #[tool(description = "Update business hours exceptions - add, change or remove dated exceptions (holidays, closures, special hours) on a business's hours memory without rewriting the whole exceptions JSON. Each exception has a date (YYYY-MM-DD) and either closed: true or open/close times, plus an optional reason. Entries are validated; an exception that differs from one already stored for that date is reported as a conflict and left unchanged unless overwrite is true. REQUIRED: business_id. Optional: hours_id (default: the regular schedule), exceptions, remove_dates, overwrite.")]
+    async fn update_business_hours_exceptions(&self, params: Parameters<UpdateHoursExceptionsParam>) -> Result<CallToolResult, McpError> {
+        let business_id = &params.0.business_id;
+        let remove_dates = params.0.remove_dates.clone().unwrap_or_default();
+        let overwrite = params.0.overwrite.unwrap_or(false);
+        info!("update_business_hours_exceptions: business_id={}, {} exception(s), {} removal(s)",
+              business_id, params.0.exceptions.len(), remove_dates.len());
+
+        if params.0.exceptions.is_empty() && remove_dates.is_empty() {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "Nothing to update: provide exceptions and/or remove_dates", json!({})));
+        }
+
+        let memories = match self.helix_client.query("get_business_hours", json!({"business_id": business_id})).await {
+            Ok(result) => HelixClient::result_items(&result),
+            Err(e) => {
+                error!("update_business_hours_exceptions failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get business hours: {}", e), json!({})));
+            }
+        };
+
+        let memory = match params.0.hours_id.as_deref() {
+            Some(hours_id) => memories.iter().find(|m| m.get("hours_id").and_then(|v| v.as_str()) == Some(hours_id)),
+            // Same choice of regular schedule as is_business_open
+            None => memories.iter().fold(None, |primary, memory| {
+                let schedule_type = memory.get("schedule_type").and_then(|v| v.as_str()).unwrap_or("");
+                if primary.is_none() || matches!(schedule_type, "" | "regular" | "standard") {
+                    Some(memory)
+                } else {
+                    primary
+                }
+            }),
+        };
+        let Some(memory) = memory else {
+            let hours_id = params.0.hours_id.as_deref().map(|id| format!(" with hours_id {}", id)).unwrap_or_default();
+            return Ok(tool_error(ErrorCode::NotFound, format!("No hours memory found for business_id {}{}", business_id, hours_id), json!({
+                "suggestion": "Create one with create_business_memory(memory_type: hours)"
+            })));
+        };
+        let hours_id = memory.get("hours_id").and_then(|v| v.as_str()).unwrap_or("").to_string();
+
+        let existing = match hours::exceptions_object(memory.get("exceptions")) {
+            Ok(existing) => existing,
+            Err(e) => {
+                return Ok(tool_error(ErrorCode::Conflict, format!("Cannot merge into hours_id {}: {}", hours_id, e), json!({
+                    "stored_exceptions": memory.get("exceptions"),
+                    "suggestion": "Fix the stored value with update_business_memory first"
+                })));
+            }
+        };
+
+        let updates: Vec<(String, serde_json::Value)> = params.0.exceptions.iter()
+            .map(|e| {
+                // Unset fields are skipped, leaving the stored exception shape
+                let mut value = serde_json::to_value(e).unwrap_or_default();
+                if let Some(map) = value.as_object_mut() {
+                    map.remove("date");
+                }
+                (e.date.clone(), value)
+            })
+            .collect();
+
+        let merge = match hours::merge_exceptions(existing, &updates, &remove_dates, overwrite) {
+            Ok(merge) => merge,
+            Err(errors) => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, "Invalid exceptions; nothing was changed", json!({
+                    "details": errors
+                })));
+            }
+        };
+
+        let changed = !(merge.added.is_empty() && merge.replaced.is_empty() && merge.removed.is_empty());
+        if changed {
+            let payload = json!({
+                "business_id": business_id,
+                "hours_id": hours_id,
+                "exceptions": serde_json::Value::Object(merge.merged.clone()).to_string(),
+                "timestamp": self.clock.timestamp()
+            });
+            if let Err(e) = self.helix_client.query("update_business_hours_exceptions", payload).await {
+                error!("update_business_hours_exceptions failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to update exceptions: {}", e), json!({})));
+            }
+        }
+
+        Ok(CallToolResult::structured(json!({
+            "success": merge.conflicts.is_empty(),
+            "business_id": business_id,
+            "hours_id": hours_id,
+            "added": merge.added,
+            "replaced": merge.replaced,
+            "removed": merge.removed,
+            "conflicts": merge.conflicts,
+            "exceptions": merge.merged,
+            "message": if merge.conflicts.is_empty() {
+                "Exceptions updated".to_string()
+            } else {
+                format!("{} conflicting date(s) left unchanged; pass overwrite: true to replace them", merge.conflicts.len())
+            }
+        })))
+    }
+
+    #[tool(description = "Get effective policy - resolve which version of a policy type was in effect on a date (default now). Among the business's policy memories of that type, picks the latest effective_date on or before the date that is active or was replaced via supersede_policy; drafts (inactive, never superseded) are ignored. Returns the policy plus its version history. REQUIRED: business_id, policy_type. Optional: date.")]
+    async fn get_effective_policy(&self, params: Parameters<GetEffectivePolicyParam>) -> Result<CallToolResult, McpError> {
+        let business_id = &params.0.business_id;
+        let policy_type = &params.0.policy_type;
+        info!("get_effective_policy: business_id={}, policy_type={}, date={:?}", business_id, policy_type, params.0.date);
+
+        let at = match params.0.date.as_ref() {
+            None => self.clock.timestamp(),
+            Some(text) => match filters::parse_timestamp(&json!(text)) {
+                Some(at) => at,
+                None => {
+                    return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid date: {}. Use RFC3339, YYYY-MM-DD or a Unix timestamp", text), json!({})));
+                }
+            },
+        };
+
+        let policies = match self.helix_client.query("get_business_policies", json!({"business_id": business_id})).await {
+            Ok(result) => HelixClient::result_items(&result),
+            Err(e) => {
+                error!("get_effective_policy failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get business policies: {}", e), json!({})));
+            }
+        };
+
+        let history: Vec<serde_json::Value> = policies::versions(&policies, policy_type).iter()
+            .map(|p| json!({
+                "policy_id": p.get("policy_id"),
+                "version": p.get("version"),
+                "effective_date": p.get("effective_date"),
+                "is_active": policies::is_active(p),
+                "superseded_by": policies::superseded_by(p)
+            }))
+            .collect();
+
+        match policies::effective(&policies, policy_type, at) {
+            Some(policy) => Ok(CallToolResult::structured(json!({
+                "business_id": business_id,
+                "policy_type": policy_type,
+                "date": at,
+                "policy": policy,
+                "versions": history
+            }))),
+            None => Ok(tool_error(ErrorCode::NotFound, format!("No {} policy in effect on {}", policy_type, at), json!({
+                "versions": history,
+                "suggestion": if history.is_empty() {
+                    "No policies of this type are stored; check policy_type or query_business_memory(policies)"
+                } else {
+                    "All versions take effect later or are inactive drafts"
+                }
+            }))),
+        }
+    }
+
+    #[tool(description = "Supersede policy - publish a new version of a policy: creates a new policy memory (same type, new content and version, is_active) and deactivates the old version, recording which version replaced it. The old version still resolves for dates before the new effective_date. REQUIRED: business_id, policy_id (version being replaced), content, text_description. Optional: effective_date (default now), version (default bumped), policy_name.")]
+    async fn supersede_policy(&self, params: Parameters<SupersedePolicyParam>) -> Result<CallToolResult, McpError> {
+        let business_id = &params.0.business_id;
+        let policy_id = &params.0.policy_id;
+        info!("supersede_policy: business_id={}, policy_id={}", business_id, policy_id);
+
+        let effective_date = match params.0.effective_date.as_ref() {
+            None => self.clock.timestamp(),
+            Some(text) => match filters::parse_timestamp(&json!(text)) {
+                Some(at) => at,
+                None => {
+                    return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid effective_date: {}. Use RFC3339, YYYY-MM-DD or a Unix timestamp", text), json!({})));
+                }
+            },
+        };
+
+        let current = match self.helix_client.query("get_business_policy_by_id", json!({"policy_id": policy_id})).await {
+            Ok(result) => HelixClient::result_items(&result).into_iter()
+                .find(|p| p.get("business_id").and_then(|v| v.as_str()) == Some(business_id.as_str())),
+            Err(e) => {
+                error!("supersede_policy failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get policy {}: {}", policy_id, e), json!({})));
+            }
+        };
+        let Some(current) = current else {
+            return Ok(tool_error(ErrorCode::NotFound, format!("No policy {} found for business_id {}", policy_id, business_id), json!({})));
+        };
+        if let Some(successor) = policies::superseded_by(&current) {
+            return Ok(tool_error(ErrorCode::Conflict, format!("Policy {} was already superseded by {}", policy_id, successor), json!({
+                "suggestion": format!("Supersede {} instead", successor)
+            })));
+        }
+        let current_effective = current.get("effective_date").and_then(|v| v.as_i64()).unwrap_or(0);
+        if effective_date < current_effective {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("effective_date {} is before the current version's effective_date {}", effective_date, current_effective), json!({})));
+        }
+
+        let field = |name: &str| current.get(name).and_then(|v| v.as_str()).unwrap_or("").to_string();
+        let version = params.0.version.clone().unwrap_or_else(|| policies::next_version(&field("version")));
+        let created = self.create_business_memory(Parameters(CreateBusinessMemoryParam {
+            business_id: business_id.clone(),
+            memory_type: "policy".to_string(),
+            text_description: params.0.text_description.clone(),
+            product_name: None,
+            service_name: None,
+            location_name: None,
+            policy_name: Some(params.0.policy_name.clone().unwrap_or_else(|| field("policy_name"))),
+            event_name: None,
+            platform: None,
+            title: None,
+            data: json!({
+                "policy_type": field("policy_type"),
+                "content": params.0.content,
+                "effective_date": effective_date,
+                "version": version,
+                "is_active": true,
+                "tags": current.get("tags").cloned().unwrap_or_else(|| json!([]))
+            }),
+            dedup: Some(false),
+        })).await?;
+        if created.is_error == Some(true) {
+            return Ok(created);
+        }
+        let new_policy = created.structured_content.as_ref()
+            .map(|c| HelixClient::result_items(&c["result"]))
+            .and_then(|items| items.into_iter().next())
+            .unwrap_or_default();
+        let new_policy_id = new_policy.get("policy_id").and_then(|v| v.as_str()).unwrap_or("").to_string();
+
+        let payload = json!({
+            "business_id": business_id,
+            "policy_id": policy_id,
+            "superseded_by": new_policy_id,
+            "timestamp": self.clock.timestamp()
+        });
+        if let Err(e) = self.helix_client.query("supersede_policy_version", payload).await {
+            error!("supersede_policy: failed to deactivate {}: {}", policy_id, e);
+            return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Created {} but failed to deactivate {}: {}", new_policy_id, policy_id, e), json!({
+                "new_policy": new_policy,
+                "suggestion": "Retry with do_query(supersede_policy_version) so only one version stays active"
+            })));
+        }
+
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "business_id": business_id,
+            "superseded_policy_id": policy_id,
+            "new_policy_id": new_policy_id,
+            "version": version,
+            "effective_date": effective_date,
+            "new_policy": new_policy
+        })))
+    }
+
+    #[tool(description = "List upcoming events - scheduled business events that are running or start within a date window, soonest first, with capacity_remaining (null = unlimited). Closed and cancelled events are excluded. REQUIRED: business_id. Optional: from (default now), to, days_ahead (alternative to to), min_capacity_remaining to find events with places left, limit (default 20).")]
+    async fn list_upcoming_events(&self, params: Parameters<ListUpcomingEventsParam>) -> Result<CallToolResult, McpError> {
+        let business_id = &params.0.business_id;
+        let limit = params.0.limit.unwrap_or(20);
+        info!("list_upcoming_events: business_id={}, from={:?}, to={:?}", business_id, params.0.from, params.0.to);
+
+        let parse = |field: &str, value: &Option<String>| match value {
+            None => Ok(None),
+            Some(text) => filters::parse_timestamp(&json!(text))
+                .map(Some)
+                .ok_or_else(|| format!("Invalid {}: {}. Use RFC3339, YYYY-MM-DD or a Unix timestamp", field, text)),
+        };
+        let (from, to) = match (parse("from", &params.0.from), parse("to", &params.0.to)) {
+            (Ok(from), Ok(to)) => (from.unwrap_or_else(|| self.clock.timestamp()), to),
+            (Err(e), _) | (_, Err(e)) => return Ok(tool_error(ErrorCode::ValidationFailed, e, json!({}))),
+        };
+        let to = to.or_else(|| params.0.days_ahead.map(|days| from + days * 86_400));
+
+        let events = match self.helix_client.query("get_business_events", json!({"business_id": business_id})).await {
+            Ok(result) => HelixClient::result_items(&result),
+            Err(e) => {
+                error!("list_upcoming_events failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get business events: {}", e), json!({})));
+            }
+        };
+
+        let mut upcoming = events::upcoming(&events, from, to, params.0.min_capacity_remaining);
+        let total = upcoming.len();
+        upcoming.truncate(limit);
+        Ok(CallToolResult::structured(json!({
+            "business_id": business_id,
+            "from": from,
+            "to": to,
+            "total": total,
+            "events": upcoming
+        })))
+    }
+
+    #[tool(description = "Close past events - mark scheduled events whose end date has passed as closed so they drop out of list_upcoming_events. REQUIRED: business_id. Optional: before (default now), dry_run to list what would be closed.")]
+    async fn close_past_events(&self, params: Parameters<ClosePastEventsParam>) -> Result<CallToolResult, McpError> {
+        let business_id = &params.0.business_id;
+        let dry_run = params.0.dry_run.unwrap_or(false);
+        info!("close_past_events: business_id={}, before={:?}, dry_run={}", business_id, params.0.before, dry_run);
+
+        let before = match params.0.before.as_ref() {
+            None => self.clock.timestamp(),
+            Some(text) => match filters::parse_timestamp(&json!(text)) {
+                Some(before) => before,
+                None => {
+                    return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid before: {}. Use RFC3339, YYYY-MM-DD or a Unix timestamp", text), json!({})));
+                }
+            },
+        };
+
+        let events = match self.helix_client.query("get_business_events", json!({"business_id": business_id})).await {
+            Ok(result) => HelixClient::result_items(&result),
+            Err(e) => {
+                error!("close_past_events failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get business events: {}", e), json!({})));
+            }
+        };
+
+        let mut closed = Vec::new();
+        let mut failed = Vec::new();
+        let timestamp = self.clock.timestamp();
+        let past = events::past_scheduled(&events, before);
+        let event_id = |event: &serde_json::Value| event.get("event_id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let results = if dry_run {
+            past.iter().map(|_| Ok(serde_json::Value::Null)).collect()
+        } else {
+            self.helix_client.query_batch(past.iter().map(|event| ("update_event_status", json!({
+                "business_id": business_id,
+                "event_id": event_id(event),
+                "status": events::STATUS_CLOSED,
+                "timestamp": timestamp
+            }))).collect()).await
+        };
+        for (event, result) in past.iter().zip(results) {
+            match result {
+                Ok(_) => closed.push(json!({
+                    "event_id": event_id(event),
+                    "event_name": event.get("event_name"),
+                    "end_date": event.get("end_date")
+                })),
+                Err(e) => {
+                    warn!("Failed to close event {}: {}", event_id(event), e);
+                    failed.push(json!({"event_id": event_id(event), "error": e.to_string()}));
+                }
+            }
+        }
+
+        Ok(CallToolResult::structured(json!({
+            "success": failed.is_empty(),
+            "business_id": business_id,
+            "dry_run": dry_run,
+            "before": before,
+            "closed": closed,
+            "failed": failed
+        })))
+    }
+
+    async fn navigation_floors(&self, navigation_id: &str) -> Vec<serde_json::Value> {
+        match self.helix_client.query("get_navigation_floors", json!({"navigation_id": navigation_id})).await {
+            Ok(result) => HelixClient::result_items(&result),
+            Err(e) => {
+                warn!("Failed to load floors for {}: {}", navigation_id, e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Put a new waypoint on its hub's floor for `floor_level`, if that floor exists
+    async fn link_waypoint_floor(&self, navigation_id: &str, waypoint_id: &str, floor_level: i32, timestamp: i64) -> Option<String> {
+        let floor_id = self.navigation_floors(navigation_id).await
+            .iter()
+            .find(|f| floors::level_of(f) == floor_level as i64)
+            .and_then(|f| f.get("floor_id"))
+            .and_then(|v| v.as_str())
+            .map(String::from)?;
+        match self.helix_client.query("link_floor_waypoint", json!({
+            "floor_id": floor_id,
+            "waypoint_id": waypoint_id,
+            "created_at": timestamp
+        })).await {
+            Ok(_) => Some(floor_id),
+            Err(e) => {
+                warn!("Failed to link waypoint {} to floor {}: {}", waypoint_id, floor_id, e);
+                None
+            }
+        }
+    }
+
+    /// Floor connectors and floor names for a navigation hub
+    async fn floor_layout(&self, navigation_id: &str) -> (Vec<floors::Connector>, std::collections::HashMap<i64, String>) {
+        let connectors = match self.helix_client.query("get_floor_connectors", json!({"navigation_id": navigation_id})).await {
+            Ok(result) => floors::connectors(&HelixClient::result_items(&result["connectors"]), &HelixClient::result_items(&result["waypoints"])),
+            Err(e) => {
+                warn!("Failed to load floor connectors for {}: {}", navigation_id, e);
+                Vec::new()
+            }
+        };
+        let names = self.navigation_floors(navigation_id).await
+            .iter()
+            .filter_map(|f| {
+                let name = f.get("floor_name").and_then(|v| v.as_str()).filter(|n| !n.is_empty())?;
+                Some((floors::level_of(f), name.to_string()))
+            })
+            .collect();
+        (connectors, names)
+    }
+
+    /// Apply `registered_count` (absolute) or `registration_delta` from event updates, checked against capacity
+    async fn update_event_registrations(&self, updates: &serde_json::Value) -> Result<serde_json::Value, (ErrorCode, String)> {
+        let invalid = |message: &str| (ErrorCode::ValidationFailed, message.to_string());
+        let business_id = updates.get("business_id").and_then(|v| v.as_str()).ok_or_else(|| invalid("Missing required field: business_id in updates"))?;
+        let event_id = updates.get("event_id").and_then(|v| v.as_str()).ok_or_else(|| invalid("Missing required field: event_id in updates"))?;
+
+        let result = self.helix_client.query("get_business_event_by_id", json!({"event_id": event_id})).await
+            .map_err(|e| (ErrorCode::HelixUnreachable, format!("Failed to load event {}: {}", event_id, e)))?;
+        let event = HelixClient::result_items(&result).into_iter()
+            .find(|e| e.get("business_id").and_then(|v| v.as_str()) == Some(business_id))
+            .ok_or_else(|| (ErrorCode::NotFound, format!("No event {} found for business_id {}", event_id, business_id)))?;
+
+        let current = event.get("registered_count").and_then(|v| v.as_i64()).unwrap_or(0);
+        let registered_count = match (updates.get("registered_count").and_then(|v| v.as_i64()), updates.get("registration_delta").and_then(|v| v.as_i64())) {
+            (Some(count), _) => count,
+            (None, Some(delta)) => current + delta,
+            (None, None) => return Err(invalid("registered_count and registration_delta must be integers")),
+        };
+        let capacity = event.get("capacity").and_then(|v| v.as_i64()).unwrap_or(0);
+        events::check_registrations(registered_count, capacity).map_err(|e| (ErrorCode::ValidationFailed, e))?;
+
+        self.helix_client.query("update_event_registrations", json!({
+            "business_id": business_id,
+            "event_id": event_id,
+            "registered_count": registered_count,
+            "timestamp": self.clock.timestamp()
+        })).await.map_err(|e| (ErrorCode::HelixUnreachable, format!("Failed to update registrations: {}", e)))?;
+
+        let mut updated = event.clone();
+        updated["registered_count"] = json!(registered_count);
+        Ok(json!({
+            "event_id": event_id,
+            "previous_count": current,
+            "registered_count": registered_count,
+            "capacity": capacity,
+            "capacity_remaining": events::capacity_remaining(&updated)
+        }))
+    }
 
     // ========================================================================
-    // HIGH-LEVEL DOMAIN-SPECIFIC TOOLS FOR AI MEMORY LAYER
+    // NAVIGATION SYSTEM TOOLS - Physical location navigation with compass data
     // ========================================================================
 
-    #[tool(description = "Query business memories - unified access to products, services, locations, hours, social media, policies, events, and information (documentation, manuals, guides, etc.) for a specific business")]
-    async fn query_business_memory(&self, params: Parameters<QueryBusinessMemoryParam>) -> Result<CallToolResult, McpError> {
-        let business_id = &params.0.business_id;
-        let memory_type_input = &params.0.memory_type;
+    #[tool(description = "Create navigation hub - set up comprehensive navigation center for a business with address, coordinates, compass bearings, and accessibility information")]
+    async fn create_navigation_hub(&self, params: Parameters<CreateNavigationHubParam>) -> Result<CallToolResult, McpError> {
+        let business_id = &params.0.business_id;
+        let navigation_id = &params.0.navigation_id;
+        let navigation_summary = &params.0.navigation_summary;
+        
+        info!("create_navigation_hub: business_id={}, navigation_id={}", business_id, navigation_id);
+
+        // Build the data payload with all fields
+        let timestamp = self.clock.timestamp();
+        let mut data = json!({
+            "business_id": business_id,
+            "navigation_id": navigation_id,
+            "primary_address": params.0.primary_address,
+            "secondary_address": params.0.secondary_address.as_ref().unwrap_or(&String::from("")),
+            "building_name": params.0.building_name.as_ref().unwrap_or(&String::from("")),
+            "building_type": params.0.building_type.as_ref().unwrap_or(&String::from("")),
+            "latitude": params.0.latitude,
+            "longitude": params.0.longitude,
+            "what3words_code": params.0.what3words_code.as_ref().unwrap_or(&String::from("")),
+            "plus_code": params.0.plus_code.as_ref().unwrap_or(&String::from("")),
+            "compass_bearing": params.0.compass_bearing.unwrap_or(0.0),
+            "compass_reference": params.0.compass_reference.as_ref().unwrap_or(&String::from("")),
+            "magnetic_declination": params.0.magnetic_declination.unwrap_or(0.0),
+            "building_description": params.0.building_description.as_ref().unwrap_or(&String::from("")),
+            "building_floors": params.0.building_floors.unwrap_or(1),
+            "business_floor": params.0.business_floor.unwrap_or(1),
+            "building_color": params.0.building_color.as_ref().unwrap_or(&String::from("")),
+            "building_size": params.0.building_size.as_ref().unwrap_or(&String::from("")),
+            "main_entrance_description": params.0.main_entrance_description.as_ref().unwrap_or(&String::from("")),
+            "alternative_entrances": params.0.alternative_entrances.as_ref().unwrap_or(&String::from("")),
+            "entrance_restrictions": params.0.entrance_restrictions.as_ref().unwrap_or(&String::from("")),
+            "wheelchair_accessible": params.0.wheelchair_accessible.unwrap_or(false),
+            "elevator_available": params.0.elevator_available.unwrap_or(false),
+            "stairs_required": params.0.stairs_required.unwrap_or(false),
+            "accessibility_notes": params.0.accessibility_notes.as_ref().unwrap_or(&String::from("")),
+            "parking_available": params.0.parking_available.unwrap_or(false),
+            "parking_description": params.0.parking_description.as_ref().unwrap_or(&String::from("")),
+            "public_transport_notes": params.0.public_transport_notes.as_ref().unwrap_or(&String::from("")),
+            "direction_varies_by_hours": params.0.direction_varies_by_hours.unwrap_or(false),
+            "after_hours_instructions": params.0.after_hours_instructions.as_ref().unwrap_or(&String::from("")),
+            "created_at": timestamp,
+            "updated_at": timestamp,
+            "last_verified_at": timestamp,
+            "verification_source": "user",
+            "navigation_summary": navigation_summary
+        });
+
+        // Check if embedding needs to be generated (MCP mode)
+        if self.config.is_mcp_embedding_enabled() {
+            info!("Generating embedding for navigation_summary...");
+            
+            let api_key = self.config.get_api_key().unwrap_or_default();
+            
+            match self.generate_embedding(navigation_summary, &api_key).await {
+                Ok(embedding) => {
+                    info!("✓ Generated {} dimensional embedding", embedding.len());
+
+                    // Add embedding to data (convert f32 to f64 for HelixDB)
+                    data["embedding"] = json!(embedding.iter().map(|&x| x as f64).collect::<Vec<f64>>());
+
+                    let model_name = match self.config.embedding.provider {
+                        Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
+                            self.config.embedding.model.clone().unwrap_or_else(|| "unknown".to_string())
+                        }
+                        Some(config::EmbeddingProvider::Local) => "local".to_string(),
+                        Some(config::EmbeddingProvider::Tcp) => "tcp-local".to_string(),
+                        None => "unknown".to_string()
+                    };
+                    data["embedding_model"] = json!(model_name);
+                    self.note_corpus_model("navigation_hubs", embedding.len()).await;
+                }
+                Err(e) => {
+                    error!("✗ Failed to generate embedding: {}", e);
+                    return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Failed to generate embedding: {}", e), json!({
+                        "suggestion": "Check embedding configuration and API connectivity"
+                    })));
+                }
+            }
+        } else {
+            info!("Using HelixDB embedding mode - HelixDB will generate embedding");
+        }
+
+        // Execute the query
+        match self.helix_client.query("add_business_navigation_hub", data).await {
+            Ok(result) => {
+                Ok(CallToolResult::structured(json!({
+                    "success": true,
+                    "business_id": business_id,
+                    "navigation_id": navigation_id,
+                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
+                    "result": result
+                })))
+            }
+            Err(e) => {
+                error!("create_navigation_hub failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to create navigation hub: {}", e), json!({})))
+            }
+        }
+    }
+
+    #[tool(description = "Create navigation waypoint - add landmarks, reference points, and visual cues with compass bearings for navigation. Use query_navigation to get navigation_id.")]
+    async fn create_navigation_waypoint(&self, params: Parameters<CreateNavigationWaypointParam>) -> Result<CallToolResult, McpError> {
+        let navigation_id = &params.0.navigation_id;
+        let description = &params.0.description;
+        
+        // Always auto-generate waypoint_id
+        let waypoint_id = format!("WPT_{}", self.ids.next_id());
+        
+        info!("create_navigation_waypoint: waypoint_id={}, navigation_id={}", waypoint_id, navigation_id);
+
+        // Build the data payload
+        let timestamp = self.clock.timestamp();
+        let mut data = json!({
+            "waypoint_id": waypoint_id,
+            "navigation_id": navigation_id,
+            "waypoint_name": params.0.waypoint_name,
+            "waypoint_type": params.0.waypoint_type,
+            "waypoint_category": params.0.waypoint_category.as_ref().unwrap_or(&String::from("")),
+            "description": description,
+            "visual_cues": params.0.visual_cues.as_ref().unwrap_or(&String::from("")),
+            "audio_cues": params.0.audio_cues.as_ref().unwrap_or(&String::from("")),
+            "relative_position": params.0.relative_position.as_ref().unwrap_or(&String::from("")),
+            "distance_from_main": params.0.distance_from_main.unwrap_or(0),
+            "floor_level": params.0.floor_level.unwrap_or(0),
+            "compass_direction": params.0.compass_direction.as_ref().unwrap_or(&String::from("")),
+            "compass_bearing": params.0.compass_bearing.unwrap_or(0.0),
+            "compass_distance": params.0.compass_distance.unwrap_or(0.0),
+            "business_specific_notes": params.0.business_specific_notes.as_ref().unwrap_or(&String::from("")),
+            "accessibility_info": params.0.accessibility_info.as_ref().unwrap_or(&String::from("")),
+            "seasonal_availability": params.0.seasonal_availability.as_ref().unwrap_or(&String::from("")),
+            "time_restrictions": params.0.time_restrictions.as_ref().unwrap_or(&String::from("")),
+            "weather_dependent": params.0.weather_dependent.unwrap_or(false),
+            "created_at": timestamp,
+            "is_active": true,
+            "priority_level": params.0.priority_level.unwrap_or(1)
+        });
+
+        // Check if embedding needs to be generated (MCP mode)
+        if self.config.is_mcp_embedding_enabled() {
+            info!("Generating embedding for waypoint description...");
+            
+            let api_key = self.config.get_api_key().unwrap_or_default();
+            
+            match self.generate_embedding(description, &api_key).await {
+                Ok(embedding) => {
+                    info!("✓ Generated {} dimensional embedding", embedding.len());
+
+                    // Add embedding to data (convert f32 to f64 for HelixDB)
+                    data["embedding"] = json!(embedding.iter().map(|&x| x as f64).collect::<Vec<f64>>());
+
+                    let model_name = match self.config.embedding.provider {
+                        Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
+                            self.config.embedding.model.clone().unwrap_or_else(|| "unknown".to_string())
+                        }
+                        Some(config::EmbeddingProvider::Local) => "local".to_string(),
+                        Some(config::EmbeddingProvider::Tcp) => "tcp-local".to_string(),
+                        None => "unknown".to_string()
+                    };
+                    data["embedding_model"] = json!(model_name);
+                    self.note_corpus_model("waypoints", embedding.len()).await;
+                }
+                Err(e) => {
+                    error!("✗ Failed to generate embedding: {}", e);
+                    return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Failed to generate embedding: {}", e), json!({
+                        "suggestion": "Check embedding configuration and API connectivity"
+                    })));
+                }
+            }
+        } else {
+            info!("Using HelixDB embedding mode - HelixDB will generate embedding");
+        }
+
+        // Execute the query
+        match self.helix_client.query("add_navigation_waypoint", data).await {
+            Ok(result) => {
+                let floor_id = self.link_waypoint_floor(navigation_id, &waypoint_id, params.0.floor_level.unwrap_or(0), timestamp).await;
+                Ok(CallToolResult::structured(json!({
+                    "success": true,
+                    "waypoint_id": waypoint_id,
+                    "navigation_id": navigation_id,
+                    "floor_id": floor_id,
+                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
+                    "result": result
+                })))
+            }
+            Err(e) => {
+                error!("create_navigation_waypoint failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to create navigation waypoint: {}", e), json!({})))
+            }
+        }
+    }
+
+    #[tool(description = "Create direction path - add step-by-step directions with compass waypoints, suitability flags, and accessibility information. Use query_navigation to get navigation_id. Optional: waypoint_ids (in travel order) to link existing waypoints into the path for get_route.")]
+    async fn create_direction_path(&self, params: Parameters<CreateDirectionPathParam>) -> Result<CallToolResult, McpError> {
+        let navigation_id = &params.0.navigation_id;
+        let step_by_step_instructions = &params.0.step_by_step_instructions;
         
-        // Normalize to plural (accept both "product" and "products")
-        let memory_type = Self::normalize_to_plural(memory_type_input);
+        // Always auto-generate path_id
+        let path_id = format!("PTH_{}", self.ids.next_id());
         
-        info!("query_business_memory: business_id={}, type={} (normalized from: {})", business_id, memory_type, memory_type_input);
+        info!("create_direction_path: path_id={}, navigation_id={}", path_id, navigation_id);
 
-        // Determine which query to execute based on memory_type
-        let query_name = match memory_type {
-            "products" => "get_business_products",
-            "services" => "get_business_services",
-            "locations" => "get_business_locations",
-            "hours" => "get_business_hours",
-            "social" => "get_business_social_media",
-            "policies" => "get_business_policies",
-            "events" => "get_business_events",
-            "information" => "get_business_information",
-            "all" => {
-                // Return all business memory types
-                let mut all_memories = json!({});
-                
-                // Query each type and collect results
-                if let Ok(products) = self.helix_client.query(
-                    "get_business_products",
-                    json!({"business_id": business_id})
-                ).await {
-                    all_memories["products"] = products;
-                }
-                
-                if let Ok(services) = self.helix_client.query(
-                    "get_business_services",
-                    json!({"business_id": business_id})
-                ).await {
-                    all_memories["services"] = services;
+        // Build the data payload
+        let timestamp = self.clock.timestamp();
+        let mut data = json!({
+            "path_id": path_id,
+            "navigation_id": navigation_id,
+            "path_name": params.0.path_name,
+            "path_type": params.0.path_type,
+            "transport_mode": params.0.transport_mode.as_ref().unwrap_or(&String::from("walking")),
+            "estimated_duration_minutes": params.0.estimated_duration_minutes.unwrap_or(10),
+            "difficulty_level": params.0.difficulty_level.as_ref().unwrap_or(&String::from("easy")),
+            "distance_meters": params.0.distance_meters.unwrap_or(0),
+            "starting_compass_bearing": params.0.starting_compass_bearing.unwrap_or(0.0),
+            "ending_compass_bearing": params.0.ending_compass_bearing.unwrap_or(0.0),
+            "path_compass_waypoints": params.0.path_compass_waypoints.as_ref().unwrap_or(&String::from("[]")),
+            "suitable_for_mobility_aids": params.0.suitable_for_mobility_aids.unwrap_or(false),
+            "suitable_for_children": params.0.suitable_for_children.unwrap_or(true),
+            "suitable_in_rain": params.0.suitable_in_rain.unwrap_or(true),
+            "suitable_at_night": params.0.suitable_at_night.unwrap_or(true),
+            "requires_appointment": params.0.requires_appointment.unwrap_or(false),
+            "requires_security_clearance": params.0.requires_security_clearance.unwrap_or(false),
+            "visitor_badge_required": params.0.visitor_badge_required.unwrap_or(false),
+            "step_by_step_instructions": step_by_step_instructions,
+            "quick_summary": params.0.quick_summary.as_ref().unwrap_or(&String::from("")),
+            "created_at": timestamp,
+            "is_recommended": params.0.is_recommended.unwrap_or(false),
+            "is_active": true,
+            "last_used_feedback": ""
+        });
+
+        // Check if embedding needs to be generated (MCP mode)
+        if self.config.is_mcp_embedding_enabled() {
+            info!("Generating embedding for step-by-step instructions...");
+            
+            let api_key = self.config.get_api_key().unwrap_or_default();
+            
+            match self.generate_embedding(step_by_step_instructions, &api_key).await {
+                Ok(embedding) => {
+                    info!("✓ Generated {} dimensional embedding", embedding.len());
+
+                    // Add embedding to data (convert f32 to f64 for HelixDB)
+                    data["embedding"] = json!(embedding.iter().map(|&x| x as f64).collect::<Vec<f64>>());
+
+                    let model_name = match self.config.embedding.provider {
+                        Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
+                            self.config.embedding.model.clone().unwrap_or_else(|| "unknown".to_string())
+                        }
+                        Some(config::EmbeddingProvider::Local) => "local".to_string(),
+                        Some(config::EmbeddingProvider::Tcp) => "tcp-local".to_string(),
+                        None => "unknown".to_string()
+                    };
+                    data["embedding_model"] = json!(model_name);
+                    self.note_corpus_model("direction_paths", embedding.len()).await;
                 }
-                
-                if let Ok(locations) = self.helix_client.query(
-                    "get_business_locations",
-                    json!({"business_id": business_id})
-                ).await {
-                    all_memories["locations"] = locations;
+                Err(e) => {
+                    error!("✗ Failed to generate embedding: {}", e);
+                    return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Failed to generate embedding: {}", e), json!({
+                        "suggestion": "Check embedding configuration and API connectivity"
+                    })));
                 }
-                
-                if let Ok(hours) = self.helix_client.query(
-                    "get_business_hours",
-                    json!({"business_id": business_id})
-                ).await {
-                    all_memories["hours"] = hours;
+            }
+        } else {
+            info!("Using HelixDB embedding mode - HelixDB will generate embedding");
+        }
+
+        // Execute the query
+        match self.helix_client.query("add_direction_path", data).await {
+            Ok(result) => {
+                let mut linked_waypoints = Vec::new();
+                let waypoint_ids: Vec<&String> = params.0.waypoint_ids.iter().flatten().collect();
+                let links = self.helix_client.query_batch(waypoint_ids.iter().enumerate().map(|(order, waypoint_id)| ("link_path_waypoint", json!({
+                    "path_id": path_id,
+                    "waypoint_id": waypoint_id,
+                    "sequence_order": order as i32 + 1,
+                    "is_optional": false,
+                    "created_at": timestamp
+                }))).collect()).await;
+                for (waypoint_id, link) in waypoint_ids.into_iter().zip(links) {
+                    match link {
+                        Ok(_) => linked_waypoints.push(waypoint_id.clone()),
+                        Err(e) => warn!("Failed to link waypoint {} into path {}: {}", waypoint_id, path_id, e),
+                    }
                 }
-                
-                if let Ok(social) = self.helix_client.query(
-                    "get_business_social_media",
-                    json!({"business_id": business_id})
-                ).await {
-                    all_memories["social"] = social;
+
+                Ok(CallToolResult::structured(json!({
+                    "success": true,
+                    "path_id": path_id,
+                    "navigation_id": navigation_id,
+                    "linked_waypoints": linked_waypoints,
+                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
+                    "result": result
+                })))
+            }
+            Err(e) => {
+                error!("create_direction_path failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to create direction path: {}", e), json!({})))
+            }
+        }
+    }
+
+    #[tool(description = "Create navigation floor - add a floor level to an indoor navigation hub and group the hub's waypoints on that floor_level under it. Waypoints created later with the same floor_level join it automatically. Routes that change floor get an elevator/stairs step through a floor connector. REQUIRED: navigation_id, floor_level. Optional: floor_name, description.")]
+    async fn create_navigation_floor(&self, params: Parameters<CreateNavigationFloorParam>) -> Result<CallToolResult, McpError> {
+        let navigation_id = &params.0.navigation_id;
+        let floor_level = params.0.floor_level;
+        info!("create_navigation_floor: navigation_id={}, floor_level={}", navigation_id, floor_level);
+
+        let existing = self.navigation_floors(navigation_id).await;
+        if let Some(floor) = existing.iter().find(|f| floors::level_of(f) == floor_level as i64) {
+            return Ok(tool_error(ErrorCode::Conflict, format!("Navigation hub {} already has a floor at level {}", navigation_id, floor_level), json!({
+                "floor_id": floor.get("floor_id")
+            })));
+        }
+
+        let floor_id = format!("FLR_{}", self.ids.next_id());
+        let timestamp = self.clock.timestamp();
+        if let Err(e) = self.helix_client.query("add_navigation_floor", json!({
+            "floor_id": floor_id,
+            "navigation_id": navigation_id,
+            "floor_level": floor_level,
+            "floor_name": params.0.floor_name.clone().unwrap_or_default(),
+            "description": params.0.description.clone().unwrap_or_default(),
+            "created_at": timestamp
+        })).await {
+            error!("create_navigation_floor failed: {}", e);
+            return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to create navigation floor: {}", e), json!({})));
+        }
+
+        // Group the waypoints already on this level
+        let waypoints = match self.helix_client.query("get_navigation_waypoints", json!({"navigation_id": navigation_id})).await {
+            Ok(result) => HelixClient::result_items(&result),
+            Err(e) => {
+                warn!("create_navigation_floor: could not load waypoints: {}", e);
+                Vec::new()
+            }
+        };
+        let mut linked = Vec::new();
+        let on_level: Vec<&str> = waypoints
+            .iter()
+            .filter(|w| floors::level_of(w) == floor_level as i64)
+            .filter_map(|w| w.get("waypoint_id").and_then(|v| v.as_str()))
+            .collect();
+        let links = self.helix_client.query_batch(on_level.iter().map(|waypoint_id| ("link_floor_waypoint", json!({
+            "floor_id": floor_id,
+            "waypoint_id": waypoint_id,
+            "created_at": timestamp
+        }))).collect()).await;
+        for (waypoint_id, link) in on_level.into_iter().zip(links) {
+            match link {
+                Ok(_) => linked.push(waypoint_id.to_string()),
+                Err(e) => warn!("create_navigation_floor: failed to link waypoint {}: {}", waypoint_id, e),
+            }
+        }
+
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "floor_id": floor_id,
+            "navigation_id": navigation_id,
+            "floor_level": floor_level,
+            "label": floors::floor_label(floor_level as i64),
+            "linked_waypoints": linked
+        })))
+    }
+
+    #[tool(description = "Create floor connector - join a waypoint on one floor to a waypoint on another with an elevator, escalator, ramp or stairs, so get_route can sequence cross-floor routes (\"take the elevator up to the 3rd floor, then...\"). Accessible routes only use connectors marked is_accessible. REQUIRED: navigation_id, from_waypoint_id, to_waypoint_id, connector_type. Optional: is_accessible (default true for elevator/ramp), bidirectional (default true, false for escalator).")]
+    async fn create_floor_connector(&self, params: Parameters<CreateFloorConnectorParam>) -> Result<CallToolResult, McpError> {
+        let p = &params.0;
+        let connector_type = p.connector_type.to_lowercase();
+        if !floors::CONNECTOR_TYPES.contains(&connector_type.as_str()) {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Unknown connector_type '{}'", p.connector_type), json!({
+                "valid_types": floors::CONNECTOR_TYPES
+            })));
+        }
+        info!("create_floor_connector: {} -> {} via {}", p.from_waypoint_id, p.to_waypoint_id, connector_type);
+
+        let waypoints = match self.helix_client.query("get_navigation_waypoints", json!({"navigation_id": p.navigation_id})).await {
+            Ok(result) => HelixClient::result_items(&result),
+            Err(e) => {
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get navigation waypoints: {}", e), json!({})));
+            }
+        };
+        let find = |id: &str| waypoints.iter().find(|w| w.get("waypoint_id").and_then(|v| v.as_str()) == Some(id));
+        let (Some(from), Some(to)) = (find(&p.from_waypoint_id), find(&p.to_waypoint_id)) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Both waypoints must belong to navigation hub {}", p.navigation_id), json!({})));
+        };
+        let (from_level, to_level) = (floors::level_of(from), floors::level_of(to));
+        if from_level == to_level {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Both waypoints are on floor_level {}; a connector joins different floors", from_level), json!({})));
+        }
+
+        let is_accessible = p.is_accessible.unwrap_or(matches!(connector_type.as_str(), "elevator" | "ramp"));
+        let bidirectional = p.bidirectional.unwrap_or(connector_type != "escalator");
+        let timestamp = self.clock.timestamp();
+        let mut directions = vec![(&p.from_waypoint_id, &p.to_waypoint_id)];
+        if bidirectional {
+            directions.push((&p.to_waypoint_id, &p.from_waypoint_id));
+        }
+        for (from_id, to_id) in directions {
+            if let Err(e) = self.helix_client.query("add_floor_connector", json!({
+                "from_waypoint_id": from_id,
+                "to_waypoint_id": to_id,
+                "connector_type": connector_type,
+                "is_accessible": is_accessible,
+                "created_at": timestamp
+            })).await {
+                error!("create_floor_connector failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to create floor connector {} -> {}: {}", from_id, to_id, e), json!({})));
+            }
+        }
+
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "connector_type": connector_type,
+            "from": {"waypoint_id": p.from_waypoint_id, "floor": floors::floor_label(from_level)},
+            "to": {"waypoint_id": p.to_waypoint_id, "floor": floors::floor_label(to_level)},
+            "is_accessible": is_accessible,
+            "bidirectional": bidirectional
+        })))
+    }
+
+    #[tool(description = "Locate customer - for 'I'm lost' conversations: match a customer's description of what they see or hear (\"I'm next to a red pharmacy sign\") against the hub's waypoints (visual_cues, audio_cues, name, description) and return the best match with its position relative to the main entrance (compass direction, distance, floor), plus alternatives to confirm with the customer. Uses embedding similarity in MCP mode, word overlap otherwise. REQUIRED: description, and navigation_id or business_id. Optional: alternatives (default 2).")]
+    async fn locate_customer(&self, params: Parameters<LocateCustomerParam>) -> Result<CallToolResult, McpError> {
+        let p = &params.0;
+        let description = p.description.trim();
+        if description.is_empty() {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "description cannot be empty", json!({})));
+        }
+
+        let hub = match (&p.navigation_id, &p.business_id) {
+            (Some(navigation_id), _) => self.helix_client.query("get_navigation_hub", json!({"navigation_id": navigation_id})).await,
+            (None, Some(business_id)) => self.helix_client.query("get_business_navigation_hub", json!({"business_id": business_id})).await,
+            (None, None) => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, "Provide navigation_id or business_id", json!({})));
+            }
+        };
+        let hub = match hub {
+            Ok(result) => HelixClient::result_items(&result).into_iter().next(),
+            Err(e) => {
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get navigation hub: {}", e), json!({})));
+            }
+        };
+        let Some(navigation_id) = hub.as_ref()
+            .and_then(|h| h.get("navigation_id"))
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .or_else(|| p.navigation_id.clone()) else {
+            return Ok(tool_error(ErrorCode::NotFound, "No navigation hub found", json!({
+                "suggestion": "Use create_navigation_hub first"
+            })));
+        };
+        info!("locate_customer: navigation_id={}, description='{}'", navigation_id, description);
+
+        let waypoints: Vec<serde_json::Value> = match self.helix_client.query("get_navigation_waypoints", json!({"navigation_id": navigation_id})).await {
+            Ok(result) => HelixClient::result_items(&result)
+                .into_iter()
+                .filter(|w| w.get("is_active").and_then(|v| v.as_bool()).unwrap_or(true))
+                .collect(),
+            Err(e) => {
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get navigation waypoints: {}", e), json!({})));
+            }
+        };
+        if waypoints.is_empty() {
+            return Ok(tool_error(ErrorCode::NotFound, format!("Navigation hub {} has no active waypoints", navigation_id), json!({
+                "suggestion": "Add waypoints with visual_cues using create_navigation_waypoint"
+            })));
+        }
+
+        // Embedding similarity per waypoint_id, when vectors can be compared
+        let mut similarities: std::collections::HashMap<String, f64> = std::collections::HashMap::new();
+        let mut model_warnings = Vec::new();
+        if self.config.is_mcp_embedding_enabled() {
+            let api_key = self.config.get_api_key().unwrap_or_default();
+            match self.generate_query_embedding(description, &api_key).await {
+                Ok(embedding) => {
+                    let (refused, warnings) = self.check_corpus_models(&["waypoints"], embedding.len()).await;
+                    model_warnings = warnings;
+                    if refused.is_empty() {
+                        let payload = json!({
+                            "query_embedding": embedding,
+                            "limit": (waypoints.len() * 4).clamp(20, 200),
+                        });
+                        match self.helix_client.query("find_similar_navigation_waypoints", payload).await {
+                            Ok(result) => {
+                                let query_vector: Vec<f64> = embedding.iter().map(|&x| x as f64).collect();
+                                let embeddings = HelixClient::result_items(&result["embeddings"]);
+                                let memories = HelixClient::result_items(&result["memories"]);
+                                for (vector, waypoint) in embeddings.iter().zip(&memories) {
+                                    let (Some(id), Some(vector)) = (waypoint.get("waypoint_id").and_then(|v| v.as_str()), similarity::vector_of(vector)) else { continue };
+                                    similarities.insert(id.to_string(), similarity::cosine_similarity(&query_vector, &vector));
+                                }
+                            }
+                            Err(e) => warn!("locate_customer: waypoint vector search failed, using cue matching only: {}", e),
+                        }
+                    }
                 }
-                
-                if let Ok(policies) = self.helix_client.query(
-                    "get_business_policies",
-                    json!({"business_id": business_id})
-                ).await {
-                    all_memories["policies"] = policies;
+                Err(e) => warn!("locate_customer: embedding failed, using cue matching only: {}", e),
+            }
+        }
+
+        let mut ranked: Vec<(f64, &serde_json::Value)> = waypoints.iter()
+            .map(|w| {
+                // Waypoints outside the vector search results count as dissimilar
+                let semantic = (!similarities.is_empty()).then(|| {
+                    w.get("waypoint_id").and_then(|v| v.as_str()).and_then(|id| similarities.get(id)).copied().unwrap_or(0.0)
+                });
+                (locate::score(description, w, semantic), w)
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let describe = |(score, waypoint): &(f64, &serde_json::Value)| json!({
+            "waypoint_id": waypoint.get("waypoint_id"),
+            "waypoint_name": waypoint.get("waypoint_name"),
+            "score": (score * 1000.0).round() / 1000.0,
+            "position": locate::relative_position(waypoint, ""),
+            "compass_direction": waypoint.get("compass_direction"),
+            "compass_bearing": waypoint.get("compass_bearing"),
+            "distance_meters": waypoint.get("compass_distance"),
+            "floor_level": waypoint.get("floor_level"),
+            "visual_cues": waypoint.get("visual_cues"),
+            "description": waypoint.get("description")
+        });
+        let (best_score, best) = ranked[0];
+        let alternatives: Vec<serde_json::Value> = ranked.iter().skip(1).take(p.alternatives.unwrap_or(2)).map(describe).collect();
+        // A close runner-up means the description fits several places
+        let ambiguous = ranked.get(1).is_some_and(|(second, _)| best_score - second < 0.05);
+
+        Ok(CallToolResult::structured(json!({
+            "navigation_id": navigation_id,
+            "building_name": hub.as_ref().and_then(|h| h.get("building_name")),
+            "match_method": if similarities.is_empty() { "cue_overlap" } else { "semantic+cue_overlap" },
+            "found": best_score > 0.0,
+            "best_match": describe(&(best_score, best)),
+            "ambiguous": ambiguous,
+            "suggestion": if best_score <= 0.0 {
+                Some("No waypoint matches the description; ask the customer for a landmark, sign or shop name they can see")
+            } else if ambiguous {
+                Some("Several waypoints match; confirm with the customer using the alternatives' visual_cues")
+            } else {
+                None
+            },
+            "alternatives": alternatives,
+            "model_warnings": model_warnings
+        })))
+    }
+
+    #[tool(description = "Start location sharing - open a short-lived live guidance session for a customer on the way to (or inside) a business. Returns share_id and push_token: the customer's device POSTs {latitude, longitude, accuracy_meters?, floor_level?} to /location/<share_id> on the HTTP transport with header 'Authorization: Bearer <push_token>'. Then call get_live_guidance for the next instruction. Follows path_id waypoint by waypoint, or heads to destination_waypoint_id (default: the main entrance). The navigation hub needs coordinates. REQUIRED: navigation_id or business_id. Optional: path_id, destination_waypoint_id, customer_id, ttl_minutes.")]
+    async fn start_location_sharing(&self, params: Parameters<StartLocationSharingParam>) -> Result<CallToolResult, McpError> {
+        let p = &params.0;
+        let hub = match (&p.navigation_id, &p.business_id) {
+            (Some(navigation_id), _) => self.helix_client.query("get_navigation_hub", json!({"navigation_id": navigation_id})).await,
+            (None, Some(business_id)) => self.helix_client.query("get_business_navigation_hub", json!({"business_id": business_id})).await,
+            (None, None) => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, "Provide navigation_id or business_id", json!({})));
+            }
+        };
+        let hub = match hub {
+            Ok(result) => HelixClient::result_items(&result).into_iter().next(),
+            Err(e) => {
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get navigation hub: {}", e), json!({})));
+            }
+        };
+        let Some(hub) = hub else {
+            return Ok(tool_error(ErrorCode::NotFound, "No navigation hub found", json!({
+                "suggestion": "Use create_navigation_hub first"
+            })));
+        };
+        let navigation_id = hub.get("navigation_id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let Some(origin) = geo::coordinates_of(&hub) else {
+            return Ok(tool_error(ErrorCode::NotFound, format!("Navigation hub {} has no coordinates; live guidance needs latitude/longitude", navigation_id), json!({
+                "suggestion": "Set latitude and longitude on the hub"
+            })));
+        };
+
+        let targets: Vec<live::Target> = if let Some(path_id) = &p.path_id {
+            match self.helix_client.query("get_path_route", json!({"path_id": path_id})).await {
+                Ok(route) => route::waypoints(&route, false).iter().map(|(_, _, w)| live::Target::from_waypoint(w, origin)).collect(),
+                Err(e) => {
+                    return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get route: {}", e), json!({})));
                 }
-                
-                if let Ok(events) = self.helix_client.query(
-                    "get_business_events",
-                    json!({"business_id": business_id})
-                ).await {
-                    all_memories["events"] = events;
+            }
+        } else if let Some(waypoint_id) = &p.destination_waypoint_id {
+            let waypoints = match self.helix_client.query("get_navigation_waypoints", json!({"navigation_id": navigation_id})).await {
+                Ok(result) => HelixClient::result_items(&result),
+                Err(e) => {
+                    return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get navigation waypoints: {}", e), json!({})));
                 }
+            };
+            waypoints.iter()
+                .filter(|w| w.get("waypoint_id").and_then(|v| v.as_str()) == Some(waypoint_id.as_str()))
+                .map(|w| live::Target::from_waypoint(w, origin))
+                .collect()
+        } else {
+            let name = hub.get("building_name").and_then(|v| v.as_str()).filter(|n| !n.is_empty()).unwrap_or("the main entrance");
+            vec![live::Target::from_waypoint(&json!({"waypoint_name": name}), origin)]
+        };
+        if targets.is_empty() {
+            return Ok(tool_error(ErrorCode::NotFound, "The route has no waypoints to guide to", json!({
+                "suggestion": "Link waypoints to the path (create_direction_path waypoint_ids) or pass destination_waypoint_id"
+            })));
+        }
 
-                if let Ok(information) = self.helix_client.query(
-                    "get_business_information",
-                    json!({"business_id": business_id})
-                ).await {
-                    all_memories["information"] = information;
-                }
+        let ttl_minutes = p.ttl_minutes.unwrap_or(self.config.live_location.default_ttl_minutes)
+            .clamp(1, self.config.live_location.max_ttl_minutes.max(1));
+        let now = self.clock.timestamp();
+        let share_id = format!("LIVE_{}", self.ids.next_id().simple());
+        let push_token = self.ids.next_id().simple().to_string();
+        let expires_at = now + ttl_minutes as i64 * 60;
+        info!("start_location_sharing: share_id={}, navigation_id={}, targets={}", share_id, navigation_id, targets.len());
+
+        let route: Vec<serde_json::Value> = targets.iter().map(|t| json!({"waypoint_id": t.waypoint_id, "name": t.name, "floor_level": t.floor_level})).collect();
+        self.live.open(live::LiveSession {
+            share_id: share_id.clone(),
+            token: push_token.clone(),
+            navigation_id: navigation_id.clone(),
+            path_id: p.path_id.clone(),
+            customer_id: p.customer_id.clone(),
+            targets,
+            expires_at,
+            last_fix: None,
+            fixes: 0,
+            reached: 0,
+        }, now);
 
-                return Ok(CallToolResult::structured(all_memories));
-            }
-            _ => {
-                return Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Invalid memory_type: {}. Valid types: products, services, locations, hours, social, policies, events, information, all", memory_type)
-                })));
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "share_id": share_id,
+            "push_token": push_token,
+            "push_url": format!("/location/{}", share_id),
+            "navigation_id": navigation_id,
+            "expires_at": expires_at,
+            "route": route
+        })))
+    }
+
+    #[tool(description = "Get live guidance - next instruction for a location sharing session from the customer's latest pushed location: next waypoint, compass direction and distance to it, floor changes, off-route warning and progress. Waypoints are marked reached as the customer passes them. REQUIRED: share_id from start_location_sharing.")]
+    async fn get_live_guidance(&self, params: Parameters<LocationShareParam>) -> Result<CallToolResult, McpError> {
+        let now = self.clock.timestamp();
+        let settings = &self.config.live_location;
+        let guidance = self.live.with_session(&params.0.share_id, now, |session| {
+            let mut guidance = live::guide(session, now, settings.arrival_radius_meters, settings.off_route_meters);
+            guidance["share_id"] = json!(session.share_id);
+            guidance["navigation_id"] = json!(session.navigation_id);
+            guidance["path_id"] = json!(session.path_id);
+            guidance["customer_id"] = json!(session.customer_id);
+            guidance["expires_at"] = json!(session.expires_at);
+            guidance
+        });
+        match guidance {
+            Some(guidance) => Ok(CallToolResult::structured(guidance)),
+            None => Ok(tool_error(ErrorCode::NotFound, format!("Location sharing session {} not found or expired", params.0.share_id), json!({
+                "suggestion": "Start a new session with start_location_sharing"
+            }))),
+        }
+    }
+
+    #[tool(description = "Stop location sharing - end a live guidance session and discard the customer's location. REQUIRED: share_id.")]
+    async fn stop_location_sharing(&self, params: Parameters<LocationShareParam>) -> Result<CallToolResult, McpError> {
+        match self.live.close(&params.0.share_id) {
+            Some(session) => Ok(CallToolResult::structured(json!({
+                "success": true,
+                "share_id": session.share_id,
+                "locations_received": session.fixes,
+                "waypoints_reached": session.reached
+            }))),
+            None => Ok(tool_error(ErrorCode::NotFound, format!("Location sharing session {} not found", params.0.share_id), json!({}))),
+        }
+    }
+
+    #[tool(description = "Import navigation GeoJSON - create waypoints and direction paths from a GeoJSON FeatureCollection drawn in a GIS tool. Point features become waypoints (properties: name, waypoint_type, description, floor_level, visual_cues, accessibility_info); bearing and distance from the hub are computed when the hub has coordinates. LineString features become direction paths (properties: name, path_type, transport_mode, steps as a list of instructions, quick_summary, suitable_for_mobility_aids); distance, bearings and duration are computed from the geometry, and points on the line are linked in order. REQUIRED: navigation_id, geojson. Optional: transport_mode default for paths (walking), dry_run to preview.")]
+    async fn import_navigation_geojson(&self, params: Parameters<ImportNavigationGeojsonParam>) -> Result<CallToolResult, McpError> {
+        let navigation_id = &params.0.navigation_id;
+        let dry_run = params.0.dry_run.unwrap_or(false);
+        let default_mode = params.0.transport_mode.clone().unwrap_or_else(|| "walking".to_string());
+
+        let parsed = match geojson::parse_collection(&params.0.geojson) {
+            Ok(parsed) => parsed,
+            Err(e) => return Ok(tool_error(ErrorCode::ValidationFailed, e, json!({}))),
+        };
+        info!("import_navigation_geojson: navigation_id={}, points={}, lines={}, skipped={}, dry_run={}",
+              navigation_id, parsed.points.len(), parsed.lines.len(), parsed.skipped.len(), dry_run);
+
+        let hub = match self.helix_client.query("get_navigation_hub", json!({"navigation_id": navigation_id})).await {
+            Ok(result) => HelixClient::result_items(&result).into_iter().next(),
+            Err(e) => {
+                error!("import_navigation_geojson hub lookup failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get navigation hub: {}", e), json!({})));
             }
         };
+        let Some(hub) = hub else {
+            return Ok(tool_error(ErrorCode::NotFound, format!("No navigation hub found with navigation_id {}", navigation_id), json!({
+                "suggestion": "Create it first with create_navigation_hub"
+            })));
+        };
+        let origin = geo::coordinates_of(&hub);
 
-        // Execute the query
-        let payload = json!({"business_id": business_id});
-        
-        match self.helix_client.query(query_name, payload).await {
-            Ok(mut results) => {
-                // Apply filters if provided
-                if let Some(filters) = &params.0.filters {
-                    results = self.apply_filters(results, filters);
+        let text = |props: &serde_json::Map<String, serde_json::Value>, key: &str| {
+            props.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(String::from)
+        };
+
+        // Waypoints first so paths can link them
+        let mut waypoint_ids: Vec<Option<String>> = vec![None; parsed.points.len()];
+        let mut waypoints = Vec::new();
+        let mut failures = Vec::new();
+        for (i, point) in parsed.points.iter().enumerate() {
+            let props = &point.properties;
+            let from_hub = origin.map(|(lat, lon)| (
+                geo::initial_bearing(lat, lon, point.latitude, point.longitude),
+                geo::haversine_meters(lat, lon, point.latitude, point.longitude),
+            ));
+            let waypoint = CreateNavigationWaypointParam {
+                navigation_id: navigation_id.clone(),
+                waypoint_name: point.name.clone(),
+                waypoint_type: text(props, "waypoint_type").or_else(|| text(props, "type")).unwrap_or_else(|| "landmark".to_string()),
+                waypoint_category: text(props, "waypoint_category"),
+                description: text(props, "description").unwrap_or_else(|| point.name.clone()),
+                visual_cues: text(props, "visual_cues"),
+                audio_cues: text(props, "audio_cues"),
+                relative_position: text(props, "relative_position"),
+                distance_from_main: from_hub.map(|(_, d)| d.round() as i32),
+                floor_level: props.get("floor_level").or(props.get("level")).and_then(|v| v.as_i64()).map(|f| f as i32),
+                compass_direction: from_hub.map(|(b, _)| geo::compass_point(b).to_string()),
+                compass_bearing: from_hub.map(|(b, _)| (b * 10.0).round() / 10.0),
+                compass_distance: from_hub.map(|(_, d)| d.round()),
+                business_specific_notes: text(props, "business_specific_notes").or_else(|| text(props, "notes")),
+                accessibility_info: text(props, "accessibility_info"),
+                seasonal_availability: text(props, "seasonal_availability"),
+                time_restrictions: text(props, "time_restrictions"),
+                weather_dependent: props.get("weather_dependent").and_then(|v| v.as_bool()),
+                priority_level: props.get("priority_level").and_then(|v| v.as_i64()).map(|p| p as i32),
+            };
+            if dry_run {
+                waypoints.push(json!({"feature": point.index, "waypoint": waypoint}));
+                continue;
+            }
+            let result = priority::background(self.create_navigation_waypoint(Parameters(waypoint))).await?;
+            match result.structured_content.as_ref().filter(|_| result.is_error != Some(true)) {
+                Some(content) => {
+                    let id = content.get("waypoint_id").and_then(|v| v.as_str()).map(String::from);
+                    waypoints.push(json!({"feature": point.index, "name": point.name, "waypoint_id": id}));
+                    waypoint_ids[i] = id;
                 }
-                
-                Ok(CallToolResult::structured(json!({
-                    "business_id": business_id,
-                    "memory_type": memory_type,
-                    "count": results.as_array().map(|a| a.len()).unwrap_or(0),
-                    "data": results
-                })))
+                None => failures.push(json!({"feature": point.index, "name": point.name, "error": result.structured_content})),
             }
-            Err(e) => {
-                error!("query_business_memory failed: {}", e);
-                Ok(CallToolResult::structured_error(json!({"error": e.to_string()})))
+        }
+
+        let mut paths = Vec::new();
+        for line in &parsed.lines {
+            let props = &line.properties;
+            let metrics = geojson::path_metrics(&line.coordinates);
+            let transport_mode = text(props, "transport_mode").unwrap_or_else(|| default_mode.clone());
+            let duration_minutes = (metrics.distance_meters / geo::travel_speed_mps(&transport_mode) / 60.0).ceil().max(1.0);
+            let on_line = geojson::points_on_line(line, &parsed.points);
+            let path = CreateDirectionPathParam {
+                navigation_id: navigation_id.clone(),
+                path_name: line.name.clone(),
+                path_type: text(props, "path_type").unwrap_or_else(|| "primary".to_string()),
+                transport_mode: Some(transport_mode),
+                estimated_duration_minutes: Some(duration_minutes as i32),
+                difficulty_level: text(props, "difficulty_level"),
+                distance_meters: Some(metrics.distance_meters.round() as i32),
+                starting_compass_bearing: Some(metrics.start_bearing),
+                ending_compass_bearing: Some(metrics.end_bearing),
+                path_compass_waypoints: Some(json!(metrics.segment_bearings).to_string()),
+                suitable_for_mobility_aids: props.get("suitable_for_mobility_aids").and_then(|v| v.as_bool()),
+                suitable_for_children: props.get("suitable_for_children").and_then(|v| v.as_bool()),
+                suitable_in_rain: props.get("suitable_in_rain").and_then(|v| v.as_bool()),
+                suitable_at_night: props.get("suitable_at_night").and_then(|v| v.as_bool()),
+                requires_appointment: props.get("requires_appointment").and_then(|v| v.as_bool()),
+                requires_security_clearance: props.get("requires_security_clearance").and_then(|v| v.as_bool()),
+                visitor_badge_required: props.get("visitor_badge_required").and_then(|v| v.as_bool()),
+                step_by_step_instructions: geojson::instructions(line),
+                quick_summary: text(props, "quick_summary"),
+                is_recommended: props.get("is_recommended").and_then(|v| v.as_bool()),
+                waypoint_ids: Some(on_line.iter().filter_map(|i| waypoint_ids[*i].clone()).collect()),
+            };
+            if dry_run {
+                let through: Vec<&str> = on_line.iter().map(|i| parsed.points[*i].name.as_str()).collect();
+                paths.push(json!({"feature": line.index, "path": path, "through_waypoints": through}));
+                continue;
+            }
+            let result = priority::background(self.create_direction_path(Parameters(path))).await?;
+            match result.structured_content.as_ref().filter(|_| result.is_error != Some(true)) {
+                Some(content) => paths.push(json!({
+                    "feature": line.index,
+                    "name": line.name,
+                    "path_id": content.get("path_id"),
+                    "linked_waypoints": content.get("linked_waypoints")
+                })),
+                None => failures.push(json!({"feature": line.index, "name": line.name, "error": result.structured_content})),
             }
         }
+
+        Ok(CallToolResult::structured(json!({
+            "success": failures.is_empty(),
+            "dry_run": dry_run,
+            "navigation_id": navigation_id,
+            "hub_has_coordinates": origin.is_some(),
+            "waypoints": waypoints,
+            "paths": paths,
+            "skipped": parsed.skipped,
+            "failures": failures
+        })))
     }
 
-    #[tool(description = "Query customer memories - unified access to behaviors, preferences, desires, rules, and feedback for a specific customer")]
-    async fn query_customer_memory(&self, params: Parameters<QueryCustomerMemoryParam>) -> Result<CallToolResult, McpError> {
-        let customer_id = &params.0.customer_id;
-        let memory_type_input = &params.0.memory_type;
-        
-        // Normalize to plural (accept both "behavior" and "behaviors")
-        let memory_type = Self::normalize_to_plural(memory_type_input);
-        
-        info!("query_customer_memory: customer_id={}, type={} (normalized from: {})", customer_id, memory_type, memory_type_input);
+    #[tool(description = "Get route - assemble a direction path into an ordered step list: waypoints in path order (PathThroughWaypoint edges), compass bearing and direction per step, and estimated distance/timing. Provide path_id, or navigation_id/business_id to pick the best active path (recommended first) matching transport_mode and accessible_only. Optional: include_optional (default true).")]
+    async fn get_route(&self, params: Parameters<GetRouteParam>) -> Result<CallToolResult, McpError> {
+        let p = &params.0;
+        let accessible_only = p.accessible_only.unwrap_or(false);
 
-        // Determine which query to execute based on memory_type
-        let query_name = match memory_type {
-            "behaviors" => "get_customer_behaviors",
-            "preferences" => "get_customer_preferences",
-            "desires" => "get_customer_desires",
-            "rules" => "get_customer_rules",
-            "feedback" => "get_customer_feedback",
-            "all" => {
-                // Return all customer memory types
-                let mut all_memories = json!({});
-                
-                if let Ok(behaviors) = self.helix_client.query(
-                    "get_customer_behaviors",
-                    json!({"customer_id": customer_id})
-                ).await {
-                    all_memories["behaviors"] = behaviors;
-                }
-                
-                if let Ok(preferences) = self.helix_client.query(
-                    "get_customer_preferences",
-                    json!({"customer_id": customer_id})
-                ).await {
-                    all_memories["preferences"] = preferences;
-                }
-                
-                if let Ok(desires) = self.helix_client.query(
-                    "get_customer_desires",
-                    json!({"customer_id": customer_id})
-                ).await {
-                    all_memories["desires"] = desires;
-                }
-                
-                if let Ok(rules) = self.helix_client.query(
-                    "get_customer_rules",
-                    json!({"customer_id": customer_id})
-                ).await {
-                    all_memories["rules"] = rules;
-                }
-                
-                if let Ok(feedback) = self.helix_client.query(
-                    "get_customer_feedback",
-                    json!({"customer_id": customer_id})
-                ).await {
-                    all_memories["feedback"] = feedback;
+        let path_id = match &p.path_id {
+            Some(path_id) => path_id.clone(),
+            None => {
+                let navigation_id = match (&p.navigation_id, &p.business_id) {
+                    (Some(navigation_id), _) => navigation_id.clone(),
+                    (None, Some(business_id)) => {
+                        let hub = self.helix_client.query("get_business_navigation_hub", json!({"business_id": business_id})).await
+                            .map(|r| HelixClient::result_items(&r).into_iter().next())
+                            .ok()
+                            .flatten();
+                        match hub.as_ref().and_then(|h| h.get("navigation_id")).and_then(|v| v.as_str()) {
+                            Some(id) => id.to_string(),
+                            None => {
+                                return Ok(tool_error(ErrorCode::NotFound, format!("No navigation hub found for business_id {}", business_id), json!({
+                                    "suggestion": "Use create_navigation_hub first"
+                                })));
+                            }
+                        }
+                    }
+                    (None, None) => {
+                        return Ok(tool_error(ErrorCode::ValidationFailed, "Provide path_id, navigation_id or business_id", json!({})));
+                    }
+                };
+
+                let paths = match self.helix_client.query("get_direction_paths", json!({"navigation_id": navigation_id})).await {
+                    Ok(result) => HelixClient::result_items(&result),
+                    Err(e) => {
+                        return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get direction paths: {}", e), json!({})));
+                    }
+                };
+                let best = route::best_path(&paths, p.transport_mode.as_deref(), accessible_only);
+                match best.and_then(|path| path.get("path_id")).and_then(|v| v.as_str()) {
+                    Some(path_id) => path_id.to_string(),
+                    None => {
+                        return Ok(tool_error(ErrorCode::NotFound, "No active direction path matches the requested transport_mode/accessibility", json!({
+                            "available_paths": paths.len()
+                        })));
+                    }
                 }
-                
-                return Ok(CallToolResult::structured(all_memories));
             }
-            _ => {
-                return Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Invalid memory_type: {}. Valid types: behaviors, preferences, desires, rules, feedback, all", memory_type)
-                })));
+        };
+
+        info!("get_route: path_id={}, transport_mode={:?}, accessible_only={}", path_id, p.transport_mode, accessible_only);
+
+        let route = match self.helix_client.query("get_path_route", json!({"path_id": path_id})).await {
+            Ok(result) => result,
+            Err(e) => {
+                error!("get_route failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get route: {}", e), json!({})));
             }
         };
+        let Some(path) = HelixClient::result_items(&route["path"]).into_iter().next() else {
+            return Ok(tool_error(ErrorCode::NotFound, format!("Direction path not found: {}", path_id), json!({})));
+        };
+        if accessible_only && path.get("suitable_for_mobility_aids").and_then(|v| v.as_bool()) != Some(true) {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "This path is not marked suitable for mobility aids", json!({
+                "suggestion": "Omit path_id to pick an accessible path automatically"
+            })));
+        }
 
-        // Execute the query
-        let payload = json!({"customer_id": customer_id});
-        
-        match self.helix_client.query(query_name, payload).await {
-            Ok(mut results) => {
-                // Apply filters if provided
-                if let Some(filters) = &params.0.filters {
-                    results = self.apply_filters(results, filters);
+        let waypoints = route::waypoints(&route, p.include_optional.unwrap_or(true));
+
+        let transport_mode = path.get("transport_mode").and_then(|v| v.as_str()).filter(|m| !m.is_empty()).unwrap_or("walking");
+        let speed = geo::travel_speed_mps(transport_mode);
+        let leg_distances: Vec<f64> = waypoints.iter()
+            .map(|(_, _, w)| w.get("compass_distance").and_then(|v| v.as_f64()).unwrap_or(0.0))
+            .collect();
+        let total_leg_distance: f64 = leg_distances.iter().sum();
+        let path_minutes = path.get("estimated_duration_minutes").and_then(|v| v.as_f64()).filter(|m| *m > 0.0);
+
+        let mut steps: Vec<serde_json::Value> = waypoints.iter().zip(&leg_distances).enumerate().map(|(i, ((_, optional, waypoint), &distance))| {
+            route::step(i, waypoint, *optional, distance, route::leg_minutes(distance, total_leg_distance, path_minutes, speed))
+        }).collect();
+
+        // Sequence floor changes between consecutive waypoints through a connector
+        let levels: Vec<i64> = waypoints.iter().map(|(_, _, w)| floors::level_of(w)).collect();
+        let mut transition_minutes = 0.0;
+        let mut floor_warnings = Vec::new();
+        if levels.windows(2).any(|w| w[0] != w[1]) {
+            let navigation_id = path.get("navigation_id").and_then(|v| v.as_str()).unwrap_or("");
+            let (connectors, floor_names) = self.floor_layout(navigation_id).await;
+            let mut sequenced = Vec::with_capacity(steps.len() + 2);
+            for (i, step) in steps.into_iter().enumerate() {
+                if i > 0 && levels[i] != levels[i - 1] {
+                    let (from, to) = (levels[i - 1], levels[i]);
+                    let connector = floors::pick_connector(&connectors, from, to, accessible_only);
+                    if connector.is_none() {
+                        floor_warnings.push(format!(
+                            "No {}connector recorded from the {} to the {}",
+                            if accessible_only { "accessible " } else { "" },
+                            floors::floor_label(from),
+                            floors::floor_label(to)
+                        ));
+                    }
+                    let kind = connector.map(|c| c.connector_type.as_str());
+                    let via = connector.and_then(|c| c.from_waypoint.get("waypoint_name")).and_then(|v| v.as_str());
+                    let minutes = floors::transition_minutes(kind, to - from);
+                    transition_minutes += minutes;
+                    sequenced.push(json!({
+                        "transition": "floor_change",
+                        "instruction": floors::transition_instruction(kind, via, from, to, floor_names.get(&to).map(String::as_str)),
+                        "connector_type": kind,
+                        "connector_waypoint_id": connector.and_then(|c| c.from_waypoint.get("waypoint_id")),
+                        "from_floor_level": from,
+                        "floor_level": to,
+                        "distance_meters": 0.0,
+                        "estimated_minutes": (minutes * 10.0).round() / 10.0,
+                        "accessible": connector.map(|c| c.is_accessible)
+                    }));
                 }
-                
-                Ok(CallToolResult::structured(json!({
-                    "customer_id": customer_id,
-                    "memory_type": memory_type,
-                    "count": results.as_array().map(|a| a.len()).unwrap_or(0),
-                    "data": results
-                })))
+                sequenced.push(step);
             }
-            Err(e) => {
-                error!("query_customer_memory failed: {}", e);
-                Ok(CallToolResult::structured_error(json!({"error": e.to_string()})))
+            for (i, step) in sequenced.iter_mut().enumerate() {
+                step["step"] = json!(i + 1);
             }
+            steps = sequenced;
         }
+        let summary = steps.iter()
+            .filter_map(|s| s.get("instruction").and_then(|v| v.as_str()))
+            .enumerate()
+            .map(|(i, text)| {
+                let mut chars = text.chars();
+                match chars.next() {
+                    Some(first) if i > 0 => format!("then {}{}", first.to_lowercase(), chars.as_str()),
+                    _ => text.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let total_minutes = path_minutes.unwrap_or(total_leg_distance / speed / 60.0 + transition_minutes);
+
+        Ok(CallToolResult::structured(json!({
+            "path_id": path_id,
+            "path_name": path.get("path_name"),
+            "transport_mode": transport_mode,
+            "suitable_for_mobility_aids": path.get("suitable_for_mobility_aids"),
+            "starting_compass_bearing": path.get("starting_compass_bearing"),
+            "ending_compass_bearing": path.get("ending_compass_bearing"),
+            "quick_summary": path.get("quick_summary"),
+            "instructions": if steps.is_empty() { path.get("step_by_step_instructions").cloned() } else { None },
+            "step_count": steps.len(),
+            "total_distance_meters": path.get("distance_meters").and_then(|v| v.as_f64()).filter(|d| *d > 0.0).unwrap_or(total_leg_distance),
+            "estimated_minutes": (total_minutes * 10.0).round() / 10.0,
+            "floors": levels.iter().fold(Vec::new(), |mut seen: Vec<i64>, l| { if seen.last() != Some(l) { seen.push(*l); } seen }),
+            "floor_warnings": floor_warnings,
+            "summary": summary,
+            "steps": steps
+        })))
     }
 
-    // Helper function to apply filters to results
-    fn apply_filters(&self, results: serde_json::Value, filters: &serde_json::Value) -> serde_json::Value {
-        // If results is not an array, return as-is
-        let Some(array) = results.as_array() else {
-            return results;
-        };
+    #[tool(description = "Query navigation - get complete navigation data for a business including hub, waypoints, and paths with optional filtering. PREFERRED: Use business_id parameter to query navigation by business (recommended). ALTERNATIVE: Use navigation_id if you already know it. Returns hub details, waypoints, and direction paths.")]
+    async fn query_navigation(&self, params: Parameters<QueryNavigationParam>) -> Result<CallToolResult, McpError> {
+        info!("query_navigation");
 
-        // Filter the array based on criteria
-        let filtered: Vec<serde_json::Value> = array
-            .iter()
-            .filter(|item| self.matches_filters(item, filters))
-            .cloned()
-            .collect();
+        let mut navigation_data = json!({});
 
-        json!(filtered)
-    }
+        // Determine navigation_id from business_id or use provided navigation_id
+        let nav_id = if let Some(business_id) = &params.0.business_id {
+            // Get navigation hub for business
+            match self.helix_client.query(
+                "get_business_navigation_hub",
+                json!({"business_id": business_id})
+            ).await {
+                Ok(hub) => {
+                    navigation_data["hub"] = hub.clone();
+                    
+                    // Extract navigation_id from hub - try multiple paths as response structure may vary
+                    let nav_id = hub.get("navigation_id")
+                        .and_then(|v| v.as_str())
+                        .or_else(|| {
+                            // Try nav_hub.navigation_id (nested structure)
+                            hub.get("nav_hub")
+                                .and_then(|h| h.get("navigation_id"))
+                                .and_then(|v| v.as_str())
+                        })
+                        .or_else(|| {
+                            // Try if hub is an array, get first item
+                            hub.as_array()
+                                .and_then(|arr| arr.first())
+                                .and_then(|item| item.get("navigation_id"))
+                                .and_then(|v| v.as_str())
+                        })
+                        .or_else(|| {
+                            // Try nav_hub as array
+                            hub.get("nav_hub")
+                                .and_then(|h| h.as_array())
+                                .and_then(|arr| arr.first())
+                                .and_then(|item| item.get("navigation_id"))
+                                .and_then(|v| v.as_str())
+                        })
+                        .map(|s| s.to_string());
+                    
+                    if nav_id.is_none() {
+                        error!("Could not extract navigation_id from hub response. Hub structure: {:?}", hub);
+                    }
+                    
+                    nav_id
+                }
+                Err(e) => {
+                    error!("Failed to get navigation hub: {}", e);
+                    return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get navigation hub for business_id {}: {}", business_id, e), json!({
+                        "suggestion": "Make sure a navigation hub exists for this business_id. Use create_navigation_hub first."
+                    })));
+                }
+            }
+        } else if let Some(navigation_id) = &params.0.navigation_id {
+            navigation_data["hub"] = json!({"navigation_id": navigation_id});
+            Some(navigation_id.clone())
+        } else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "Must provide either business_id or navigation_id", json!({
+                "suggestion": "Provide business_id to look up navigation by business, or navigation_id if you know it directly"
+            })));
+        };
 
-    fn matches_filters(&self, item: &serde_json::Value, filters: &serde_json::Value) -> bool {
-        // Simple filter matching - can be extended
-        let Some(filter_obj) = filters.as_object() else {
-            return true;
+        let nav_id = match nav_id {
+            Some(id) => id,
+            None => {
+                return Ok(tool_error(ErrorCode::NotFound, "Could not determine navigation_id from the query response", json!({
+                    "suggestion": "The navigation hub may not exist for this business. Use create_navigation_hub first."
+                })));
+            }
         };
 
-        for (key, value) in filter_obj {
-            match item.get(key) {
-                Some(item_value) => {
-                    // Handle range filters for numbers
-                    if let Some(range) = value.as_object() {
-                        if let Some(lte) = range.get("lte") {
-                            if let (Some(item_num), Some(filter_num)) = (item_value.as_f64(), lte.as_f64()) {
-                                if item_num > filter_num {
-                                    return false;
-                                }
-                            }
-                        }
-                        if let Some(gte) = range.get("gte") {
-                            if let (Some(item_num), Some(filter_num)) = (item_value.as_f64(), gte.as_f64()) {
-                                if item_num < filter_num {
-                                    return false;
-                                }
-                            }
-                        }
-                    } else if item_value != value {
-                        // Exact match for non-range filters
-                        return false;
-                    }
+        // Get waypoints if requested
+        if params.0.include_waypoints.unwrap_or(true) {
+            match self.helix_client.query(
+                "get_navigation_waypoints",
+                json!({"navigation_id": nav_id})
+            ).await {
+                Ok(waypoints) => {
+                    navigation_data["waypoints"] = waypoints;
+                }
+                Err(e) => {
+                    error!("Failed to get waypoints: {}", e);
+                }
+            }
+        }
+
+        // Get paths if requested
+        if params.0.include_paths.unwrap_or(true) {
+            let query_name = if params.0.filter_accessible_only.unwrap_or(false) {
+                "get_accessible_navigation"
+            } else {
+                "get_direction_paths"
+            };
+
+            match self.helix_client.query(
+                query_name,
+                json!({"navigation_id": nav_id})
+            ).await {
+                Ok(paths) => {
+                    navigation_data["paths"] = paths;
+                }
+                Err(e) => {
+                    error!("Failed to get paths: {}", e);
                 }
-                None => return false,
             }
         }
 
-        true
+        // Count results
+        let waypoint_count = navigation_data.get("waypoints")
+            .and_then(|v| v.as_array())
+            .map(|a| a.len())
+            .unwrap_or(0);
+        let path_count = navigation_data.get("paths")
+            .and_then(|v| v.as_array())
+            .map(|a| a.len())
+            .unwrap_or(0);
+
+        Ok(CallToolResult::structured(json!({
+            "navigation_id": nav_id,
+            "waypoint_count": waypoint_count,
+            "path_count": path_count,
+            "data": navigation_data
+        })))
     }
 
-    #[tool(description = "Semantic search across business and customer memories using AI embeddings - finds memories by meaning, not just keywords")]
-    async fn search_semantic(&self, params: Parameters<SearchSemanticParam>) -> Result<CallToolResult, McpError> {
+    #[tool(description = "Search navigation semantically - find navigation hubs, waypoints, and paths by meaning using AI embeddings")]
+    async fn search_navigation(&self, params: Parameters<SearchNavigationParam>) -> Result<CallToolResult, McpError> {
         let query = &params.0.query;
-        let memory_types_input = &params.0.memory_types;
+        let search_types = &params.0.search_types;
         let limit = params.0.limit.unwrap_or(10);
         
-        // Normalize all memory types to plural (accept both "product" and "products")
-        let memory_types: Vec<&str> = memory_types_input
-            .iter()
-            .map(|t| Self::normalize_to_plural(t.as_str()))
-            .collect();
-        
-        info!("search_semantic: query='{}', types={:?} (normalized from: {:?}), limit={}", query, memory_types, memory_types_input, limit);
+        info!("search_navigation: query='{}', types={:?}, limit={}", query, search_types, limit);
 
-        // Check embedding mode from config
+        // Check embedding mode
         if self.config.is_helixdb_embedding_enabled() {
-            // HelixDB mode: Just pass text query, HelixDB generates embedding via Embed()
-            info!("Using HelixDB embedding mode (Embed() function in queries)");
+            info!("Using HelixDB embedding mode");
             
             let mut all_results = Vec::new();
             
-            // Search across requested memory types
-            for memory_type in &memory_types {
-                let query_name = match *memory_type {
-                    // Business memory types
-                    "products" => "search_business_products_semantic",
-                    "services" => "search_business_services_semantic",
-                    "locations" => "search_business_locations_semantic",
-                    "hours" => "search_business_hours_semantic",
-                    "social" => "search_business_social_semantic",
-                    "policies" => "search_business_policies_semantic",
-                    "events" => "search_business_events_semantic",
-                    "information" => "search_business_information_semantic",
-                    // Customer memory types
-                    "behaviors" => "search_customer_behaviors_semantic",
-                    "preferences" => "search_customer_preferences_semantic",
-                    "desires" => "search_customer_desires_semantic",
-                    "rules" => "search_customer_rules_semantic",
-                    "feedback" => "search_customer_feedback_semantic",
-                    // Customer interaction types
-                    "product_interactions" => "search_customer_product_interactions_semantic",
-                    "service_interactions" => "search_customer_service_interactions_semantic",
-                    // Navigation types
-                    "navigation_hubs" => "search_navigation_hubs_semantic",
-                    "waypoints" => "search_navigation_waypoints_semantic",
-                    "direction_paths" => "search_direction_paths_semantic",
+            for search_type in search_types {
+                let query_name = match search_type.as_str() {
+                    "hubs" => "search_navigation_hubs",
+                    "waypoints" => "search_navigation_waypoints",
+                    "paths" => "search_direction_paths",
                     _ => {
-                        info!("Skipping unsupported memory type for semantic search: {}", memory_type);
+                        info!("Skipping unsupported search type: {}", search_type);
                         continue;
                     }
                 };
 
-                // Build search payload - just text, HelixDB will call Embed()
                 let mut payload = json!({
-                    "query_text": query,
-                    "k": limit,
+                    "query_embedding": query,
+                    "limit": limit,
                 });
 
-                // Add filters based on optional parameters
                 if let Some(business_id) = &params.0.business_id {
                     payload["business_id"] = json!(business_id);
                 }
 
-                if let Some(customer_id) = &params.0.customer_id {
-                    payload["customer_id"] = json!(customer_id);
-                }
-
-                // Execute query
                 match self.helix_client.query(query_name, payload).await {
                     Ok(results) => {
                         if let Some(array) = results.as_array() {
@@ -816,14 +6120,14 @@ impl HelixMcpServer {
                         }
                     }
                     Err(e) => {
-                        error!("Semantic search failed for {}: {}", memory_type, e);
+                        error!("Navigation search failed for {}: {}", search_type, e);
                     }
                 }
             }
 
             return Ok(CallToolResult::structured(json!({
                 "query": query,
-                "memory_types": memory_types,
+                "search_types": search_types,
                 "total_results": all_results.len(),
                 "limit": limit,
                 "embedding_mode": "helixdb",
@@ -831,125 +6135,57 @@ impl HelixMcpServer {
             })));
         }
 
-        // MCP mode: MCP server generates embedding via API
+        // MCP mode: Generate embedding
         if self.config.is_mcp_embedding_enabled() {
-            info!("Using MCP embedding mode (API calls in MCP server)");
-            info!("Provider: {:?}", self.config.embedding.provider);
+            info!("Using MCP embedding mode");
             
-            // Check if we have API key (for cloud providers)
             let api_key = self.config.get_api_key().unwrap_or_default();
             
-            // Validate based on provider
-            match self.config.embedding.provider {
-                Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
-                    if api_key.is_empty() {
-                        error!("API key missing for cloud provider");
-                        return Ok(CallToolResult::structured_error(json!({
-                            "error": "API key not configured for cloud embedding provider",
-                            "provider": format!("{:?}", self.config.embedding.provider),
-                            "suggestion": "Set OPENAI_API_KEY or GEMINI_API_KEY environment variable, or add api_key to mcpconfig.toml"
-                        })));
-                    }
-                }
-                Some(config::EmbeddingProvider::Local) => {
-                    info!("Using local HTTP embedding provider (no API key needed)");
-                }
-                Some(config::EmbeddingProvider::Tcp) => {
-                    info!("Using TCP embedding server (no API key needed)");
-                }
-                None => {
-                    error!("Embedding provider not configured");
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": "Embedding provider not configured",
-                        "suggestion": "Set 'provider' in mcpconfig.toml to: openai, gemini, local, or tcp"
-                    })));
-                }
-            }
-
-            // Generate embedding from query text
-            info!("Generating embedding for query: {}", query);
-            let query_embedding = match self.generate_embedding(query, &api_key).await {
+            let query_embedding = match self.generate_query_embedding(query, &api_key).await {
                 Ok(embedding) => {
-                    info!("? Generated embedding vector with {} dimensions", embedding.len());
+                    info!("✓ Generated embedding vector with {} dimensions", embedding.len());
                     embedding
                 }
                 Err(e) => {
-                    error!("? Failed to generate embedding: {}", e);
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Embedding generation failed: {}", e),
-                        "provider": format!("{:?}", self.config.embedding.provider),
-                        "suggestion": "Check API key, network connection, and local server status"
+                    error!("✗ Failed to generate embedding: {}", e);
+                    return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Embedding generation failed: {}", e), json!({
+                        "suggestion": "Check API key, network connection, and server status"
                     })));
                 }
             };
 
+            let collections: Vec<&str> = search_types.iter().map(|t| match t.as_str() {
+                "hubs" => "navigation_hubs",
+                "paths" => "direction_paths",
+                other => other,
+            }).collect();
+            let (refused, model_warnings) = self.check_corpus_models(&collections, query_embedding.len()).await;
+
             let mut all_results = Vec::new();
             
-            // Search across requested memory types using generated embedding
-            for memory_type in &memory_types {
-                let query_name = match *memory_type {
-                    // Business memory types
-                    "products" => "search_business_products_hybrid",
-                    "services" => "search_business_services_hybrid",
-                    "locations" => "search_business_locations_hybrid",
-                    "hours" => "search_business_hours_hybrid",
-                    "social" => "search_business_social_hybrid",
-                    "policies" => "search_business_policies_hybrid",
-                    "events" => "search_business_events_hybrid",
-                    "information" => "search_business_information_hybrid",
-                    // Customer memory types
-                    "behaviors" => "search_customer_behaviors_hybrid",
-                    "preferences" => "search_customer_preferences_hybrid",
-                    "desires" => "search_customer_desires_hybrid",
-                    "rules" => "search_customer_rules_hybrid",
-                    "feedback" => "search_customer_feedback_hybrid",
-                    // Customer interaction types
-                    "product_interactions" => "search_customer_product_interactions_hybrid",
-                    "service_interactions" => "search_customer_service_interactions_hybrid",
-                    // Navigation types
-                    "navigation_hubs" => "search_navigation_hubs_hybrid",
-                    "waypoints" => "search_navigation_waypoints_hybrid",
-                    "direction_paths" => "search_direction_paths_hybrid",
+            for (search_type, collection) in search_types.iter().zip(&collections) {
+                if refused.iter().any(|r| r == collection) {
+                    continue;
+                }
+                let query_name = match search_type.as_str() {
+                    "hubs" => "search_navigation_hubs",
+                    "waypoints" => "search_navigation_waypoints",
+                    "paths" => "search_direction_paths",
                     _ => {
-                        info!("Skipping unsupported memory type for semantic search: {}", memory_type);
+                        info!("Skipping unsupported search type: {}", search_type);
                         continue;
                     }
                 };
 
-                // Build search payload with embedding vector
                 let mut payload = json!({
                     "query_embedding": query_embedding,
                     "limit": limit,
                 });
 
-                // Add filters based on optional parameters
                 if let Some(business_id) = &params.0.business_id {
-                    // Apply business_id filter to business memory types
-                    match *memory_type {
-                        "products" => {
-                            payload["business_id"] = json!(business_id);
-                            payload["min_price"] = json!(0.0);
-                            payload["max_price"] = json!(1000000.0);
-                        }
-                        "services" | "locations" | "hours" | "social" | "policies" | "events" | "information" => {
-                            payload["business_id"] = json!(business_id);
-                        }
-                        _ => {}
-                    }
-                }
-
-                if let Some(customer_id) = &params.0.customer_id {
-                    // Apply customer_id filter to customer memory types
-                    match *memory_type {
-                        "behaviors" | "preferences" | "desires" | "rules" | "feedback" 
-                        | "product_interactions" | "service_interactions" => {
-                            payload["customer_id"] = json!(customer_id);
-                        }
-                        _ => {}
-                    }
+                    payload["business_id"] = json!(business_id);
                 }
 
-                // Execute query with embedding
                 match self.helix_client.query(query_name, payload).await {
                     Ok(results) => {
                         if let Some(array) = results.as_array() {
@@ -957,2651 +6193,4070 @@ impl HelixMcpServer {
                         }
                     }
                     Err(e) => {
-                        error!("Semantic search failed for {}: {}", memory_type, e);
+                        error!("Navigation search failed for {}: {}", search_type, e);
                     }
                 }
             }
 
             return Ok(CallToolResult::structured(json!({
                 "query": query,
-                "memory_types": memory_types,
+                "search_types": search_types,
                 "total_results": all_results.len(),
                 "limit": limit,
                 "embedding_mode": "mcp",
-                "provider": format!("{:?}", self.config.embedding.provider),
-                "model": self.config.embedding.model,
+                "model_warnings": model_warnings,
                 "results": all_results
             })));
         }
-
-        // Fallback (should never reach here)
-        Ok(CallToolResult::structured_error(json!({
-            "error": "Invalid embedding configuration. Check mcpconfig.toml"
-        })))
+
+        Ok(tool_error(ErrorCode::EmbeddingUnavailable, "Invalid embedding configuration. Check mcpconfig.toml", json!({})))
+    }
+
+    #[tool(description = "Search nearby - find business locations and navigation hubs within radius_meters of a latitude/longitude, sorted by distance (haversine, in meters). REQUIRED: latitude, longitude, radius_meters. Optional: search_types (locations, hubs), business_id, limit. Entries without coordinates are skipped.")]
+    async fn search_nearby(&self, params: Parameters<SearchNearbyParam>) -> Result<CallToolResult, McpError> {
+        let (latitude, longitude) = (params.0.latitude, params.0.longitude);
+        let radius = params.0.radius_meters;
+        let limit = params.0.limit.unwrap_or(20);
+        let search_types: Vec<&str> = params.0.search_types
+            .as_ref()
+            .map(|types| types.iter().map(|t| t.as_str()).collect())
+            .unwrap_or_else(|| vec!["locations", "hubs"]);
+
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) || radius <= 0.0 {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "latitude must be within [-90, 90], longitude within [-180, 180] and radius_meters positive", json!({})));
+        }
+
+        info!("search_nearby: lat={}, lon={}, radius={}m, types={:?}, business_id={:?}", latitude, longitude, radius, search_types, params.0.business_id);
+
+        let mut results = Vec::new();
+        for search_type in &search_types {
+            let (query_name, payload) = match (*search_type, &params.0.business_id) {
+                ("locations" | "location", Some(business_id)) => ("get_business_locations", json!({"business_id": business_id})),
+                ("locations" | "location", None) => ("get_all_business_locations", json!({})),
+                ("hubs" | "hub", Some(business_id)) => ("get_business_navigation_hub", json!({"business_id": business_id})),
+                ("hubs" | "hub", None) => ("get_all_navigation_hubs", json!({})),
+                _ => {
+                    return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid search_type: {}. Valid types: locations, hubs", search_type), json!({})));
+                }
+            };
+
+            match self.helix_client.query(query_name, payload).await {
+                Ok(result) => {
+                    for item in HelixClient::result_items(&result) {
+                        let Some((lat, lon)) = geo::coordinates_of(&item) else {
+                            continue;
+                        };
+                        let distance = geo::haversine_meters(latitude, longitude, lat, lon);
+                        if distance <= radius {
+                            results.push(json!({
+                                "type": if query_name.contains("hub") { "hub" } else { "location" },
+                                "distance_meters": distance.round(),
+                                "data": item
+                            }));
+                        }
+                    }
+                }
+                Err(e) => error!("search_nearby {} failed: {}", query_name, e),
+            }
+        }
+
+        results.sort_by(|a, b| {
+            let da = a["distance_meters"].as_f64().unwrap_or(f64::MAX);
+            let db = b["distance_meters"].as_f64().unwrap_or(f64::MAX);
+            da.total_cmp(&db)
+        });
+        results.truncate(limit);
+
+        Ok(CallToolResult::structured(json!({
+            "latitude": latitude,
+            "longitude": longitude,
+            "radius_meters": radius,
+            "count": results.len(),
+            "results": results
+        })))
+    }
+
+    #[tool(description = "Compute bearing - compass bearing, distance and a plain-language direction from the user's latitude/longitude to a business navigation hub. Applies the hub's stored magnetic_declination (magnetic bearing for a phone compass) and mentions its compass_reference. REQUIRED: latitude, longitude and business_id or navigation_id.")]
+    async fn compute_bearing(&self, params: Parameters<ComputeBearingParam>) -> Result<CallToolResult, McpError> {
+        let (latitude, longitude) = (params.0.latitude, params.0.longitude);
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "latitude must be within [-90, 90] and longitude within [-180, 180]", json!({})));
+        }
+
+        let (query_name, payload) = match (&params.0.business_id, &params.0.navigation_id) {
+            (Some(business_id), _) => ("get_business_navigation_hub", json!({"business_id": business_id})),
+            (None, Some(navigation_id)) => ("get_navigation_hub", json!({"navigation_id": navigation_id})),
+            (None, None) => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, "Must provide either business_id or navigation_id", json!({})));
+            }
+        };
+
+        info!("compute_bearing: lat={}, lon={}, {}={}", latitude, longitude, query_name, payload);
+
+        let hub = match self.helix_client.query(query_name, payload).await {
+            Ok(result) => HelixClient::result_items(&result).into_iter().next(),
+            Err(e) => {
+                error!("Failed to get navigation hub: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get navigation hub: {}", e), json!({})));
+            }
+        };
+        let Some(hub) = hub else {
+            return Ok(tool_error(ErrorCode::NotFound, "Navigation hub not found", json!({
+                "suggestion": "Use create_navigation_hub first"
+            })));
+        };
+        let Some((hub_lat, hub_lon)) = geo::coordinates_of(&hub) else {
+            return Ok(tool_error(ErrorCode::NotFound, "Navigation hub has no GPS coordinates", json!({
+                "suggestion": "Set latitude/longitude on the hub with update_business_memory"
+            })));
+        };
+
+        let distance = geo::haversine_meters(latitude, longitude, hub_lat, hub_lon);
+        let true_bearing = geo::initial_bearing(latitude, longitude, hub_lat, hub_lon);
+        let declination = hub.get("magnetic_declination").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let magnetic = geo::magnetic_bearing(true_bearing, declination);
+        let compass_reference = hub.get("compass_reference").and_then(|v| v.as_str()).unwrap_or("");
+        let place = hub.get("building_name")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .unwrap_or("the destination");
+
+        let mut directions = if distance < 10.0 {
+            format!("You are at {}.", place)
+        } else {
+            format!(
+                "Head {} ({:.0}° on your compass) for about {} to reach {}.",
+                geo::compass_point(true_bearing),
+                magnetic,
+                geo::format_distance(distance),
+                place
+            )
+        };
+        if !compass_reference.is_empty() {
+            directions.push_str(&format!(" The building is {}.", compass_reference.replace('_', " ")));
+        }
+
+        Ok(CallToolResult::structured(json!({
+            "navigation_id": hub.get("navigation_id"),
+            "business_id": hub.get("business_id"),
+            "distance_meters": distance.round(),
+            "true_bearing": (true_bearing * 10.0).round() / 10.0,
+            "magnetic_bearing": (magnetic * 10.0).round() / 10.0,
+            "magnetic_declination": declination,
+            "cardinal_direction": geo::compass_point(true_bearing),
+            "compass_reference": compass_reference,
+            "directions": directions
+        })))
+    }
+
+    // ========================================================================
+    // UPDATE TOOLS - Modify existing memories
+    // ========================================================================
+
+    #[tool(description = "Update existing business memory (products, services, locations, hours, social media, policies, events, information such as documentation/manuals). REQUIRED: memory_id (internal UUID from database node), memory_type, updates dict with: business_id, entity-specific ID (e.g., product_id from query), text_description for embedding regeneration. Products/services: send only the changed fields (e.g. price) with business_id and product_id/service_id; the stored node is merged with them and the embedding rebuilt from all its fields (send composite_text to embed your own text instead). Price or currency changes are recorded for get_price_history. Events: registered_count or registration_delta (with event_id) updates registrations, checked against capacity, and needs no text. Get internal ID using query_business_memory.")]
+    async fn update_business_memory(&self, params: Parameters<UpdateBusinessMemoryParam>) -> Result<CallToolResult, McpError> {
+        let memory_id = &params.0.memory_id;
+        let memory_type_input = &params.0.memory_type;
+        let updates = &params.0.updates;
+        
+        // Normalize memory_type (accept both "products" and "product")
+        let memory_type = memory_types::normalize(memory_type_input);
+        
+        info!("update_business_memory: memory_id={}, type={} (normalized from: {})", memory_id, memory_type, memory_type_input);
+
+        // Registration counts change without touching the description or embedding
+        let mut registrations = None;
+        if memory_type == "event" && (updates.get("registered_count").is_some() || updates.get("registration_delta").is_some()) {
+            match self.update_event_registrations(updates).await {
+                Ok(result) => registrations = Some(result),
+                Err((code, e)) => return Ok(tool_error(code, e, json!({}))),
+            }
+            let has_text = ["composite_text", "text_description", "description"].iter().any(|f| updates.get(*f).is_some());
+            if !has_text {
+                return Ok(CallToolResult::structured(json!({
+                    "success": true,
+                    "memory_type": memory_type,
+                    "registrations": registrations
+                })));
+            }
+        }
+
+        // Note: If product doesn't exist, query_business_memory returns empty array, so no special error handling needed here
+        // The update query will fail naturally if memory_id doesn't exist
+
+        // Products and services take just the changed fields; the server merges
+        // them into the stored node and rebuilds the composite text
+        if matches!(memory_type, "product" | "service") && updates.get("composite_text").is_none() {
+            return self.update_business_fields(memory_type, updates).await;
+        }
+
+        // Extract composite_text from updates (required for vector-aware update queries)
+        let composite_text = updates.get("composite_text")
+            .or(updates.get("text_description"))
+            .or(updates.get("description"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| McpError::invalid_request(
+                "Missing required field: composite_text (or text_description/description)", 
+                None
+            ))?;
+
+        // Extract business_id (required for all business memory types)
+        let business_id = updates.get("business_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| McpError::invalid_request(
+                "Missing required field: business_id in updates", 
+                None
+            ))?;
+
+        // Extract entity-specific ID based on memory type
+        let (entity_id_field, query_name) = match memory_type {
+            "product" => ("product_id", "update_business_product_memory"),
+            "service" => ("service_id", "update_business_service_memory"),
+            "location" => ("location_id", "update_business_location_memory"),
+            "hours" => ("hours_id", "update_business_hours_memory"),
+            "social" => ("social_id", "update_business_social_memory"),
+            "policy" => ("policy_id", "update_business_policy_memory"),
+            "event" => ("event_id", "update_business_event_memory"),
+            "information" => ("info_id", "update_business_information_memory"),
+            _ => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}. Valid: product, service, location, hours, social, policy, event, information", memory_type), json!({})));
+            }
+        };
+
+        let entity_id = updates.get(entity_id_field)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                let msg = format!("Missing required field: {} in updates", entity_id_field);
+                McpError::invalid_request(msg, None)
+            })?;
+
+        info!("Updating {} with business_id={}, {}={}", memory_type, business_id, entity_id_field, entity_id);
+
+        // Generate embedding based on mode
+        let new_embedding = if self.config.is_mcp_embedding_enabled() {
+            // MCP Mode: Generate embedding via OpenAI/Gemini/Local/TCP
+            info!("MCP mode: Generating new embedding for {} {}", memory_type, entity_id);
+            let api_key = self.config.get_api_key().unwrap_or_default();
+            
+            match self.generate_embedding(composite_text, &api_key).await {
+                Ok(emb) => emb,
+                Err(e) => {
+                    return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Failed to generate embedding: {}", e), json!({})));
+                }
+            }
+        } else {
+            // HelixDB Mode: Use empty vector (HelixDB will generate via Embed() function)
+            info!("HelixDB mode: Using empty embedding placeholder for {} {}", memory_type, entity_id);
+            vec![]
+        };
+
+        // Build payload for vector-aware update query
+        let timestamp = self.clock.timestamp();
+        let payload = json!({
+            "memory_id": memory_id,
+            "business_id": business_id,
+            entity_id_field: entity_id,
+            "composite_text": composite_text,
+            "new_embedding": new_embedding,
+            "timestamp": timestamp
+        });
+
+        // Execute vector-aware update query (DROP old vector + CREATE new one)
+        match self.helix_client.query(query_name, payload).await {
+            Ok(result) => {
+                Ok(CallToolResult::structured(json!({
+                    "success": true,
+                    "memory_type": memory_type,
+                    "business_id": business_id,
+                    entity_id_field: entity_id,
+                    "query_used": query_name,
+                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
+                    "updated_at": timestamp,
+                    "registrations": registrations,
+                    "result": result
+                })))
+            }
+            Err(e) => {
+                error!("update_business_memory failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to update {} memory: {}", memory_type, e), json!({
+                    "query_used": query_name
+                })))
+            }
+        }
     }
 
-    #[tool(description = "BM25 keyword search - fast text-based search across all memory types. Use for exact matches, IDs, phone numbers, or when embeddings unavailable. Always available as fallback.")]
-    async fn search_bm25(&self, params: Parameters<SearchBM25Param>) -> Result<CallToolResult, McpError> {
-        let query = &params.0.query;
-        let memory_types_input = &params.0.memory_types;
-        let limit = params.0.limit.unwrap_or(10);
-
-        // Normalize all memory types to plural (accept both "product" and "products")
-        let memory_types: Vec<&str> = memory_types_input
-            .iter()
-            .map(|t| Self::normalize_to_plural(t.as_str()))
-            .collect();
-
-        info!("search_bm25: query='{}', types={:?} (normalized from: {:?}), limit={}", query, memory_types, memory_types_input, limit);
+    /// Partial product/service/event update: merge `updates` over the stored
+    /// node, rebuild its composite text and embedding, and rewrite every field
+    async fn update_business_fields(&self, memory_type: &str, updates: &serde_json::Value) -> Result<CallToolResult, McpError> {
+        let Some((id_field, _, lookup_query)) = memory_types::entity(memory_type) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}", memory_type), json!({})));
+        };
+        let query_name = match memory_type {
+            "product" => "update_business_product_fields",
+            "event" => "update_business_event_fields",
+            _ => "update_business_service_fields",
+        };
+        let (Some(business_id), Some(entity_id)) = (
+            updates.get("business_id").and_then(|v| v.as_str()),
+            updates.get(id_field).and_then(|v| v.as_str()),
+        ) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Missing required field: business_id and {} in updates", id_field), json!({})));
+        };
 
-        let mut all_results = Vec::new();
+        let current = match self.helix_client.query(lookup_query, json!({ id_field: entity_id })).await {
+            Ok(result) => HelixClient::result_items(&result)
+                .into_iter()
+                .find(|m| m.get("business_id").and_then(|v| v.as_str()) == Some(business_id)),
+            Err(e) => {
+                error!("update_business_memory lookup failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to look up {} {}: {}", memory_type, entity_id, e), json!({})));
+            }
+        };
+        let Some(mut merged) = current else {
+            return Ok(tool_error(ErrorCode::NotFound, format!("{} {} not found for business {}", memory_type, entity_id, business_id), json!({})));
+        };
+        let before = merged.clone();
+
+        let changed_fields = models::merge_updates(&mut merged, updates);
+        let timestamp = self.clock.timestamp();
+        let full = match models::business_memory(memory_type, merged, timestamp) {
+            Ok(full) => full,
+            Err(e) => return Ok(tool_error(ErrorCode::ValidationFailed, e.to_string(), json!({
+                "expected_fields": models::schema(memory_type)
+            }))),
+        };
+        let composite_text = embedding_utils::composite_text(memory_type, &full)
+            .unwrap_or_else(|| full.get("text_description").and_then(|v| v.as_str()).unwrap_or("").to_string());
+        info!("Updating {} {} fields {:?}", memory_type, entity_id, changed_fields);
 
-        // Route each memory type to its BM25 query
-        for memory_type in &memory_types {
-            let query_name = match *memory_type {
-                "products" => "search_business_products_bm25",
-                "services" => "search_business_services_bm25",
-                "locations" => "search_business_locations_bm25",
-                "hours" => "search_business_hours_bm25",
-                "social" => "search_business_social_bm25",
-                "policies" => "search_business_policies_bm25",
-                "events" => "search_business_events_bm25",
-                "information" => "search_business_information_bm25",
-                "behaviors" => "search_customer_behaviors_bm25",
-                "preferences" => "search_customer_preferences_bm25",
-                "desires" => "search_customer_desires_bm25",
-                "rules" => "search_customer_rules_bm25",
-                "feedback" => "search_customer_feedback_bm25",
-                "communication" => "search_customer_communication_bm25",
-                "product_interactions" => "search_customer_product_interactions_bm25",
-                "service_interactions" => "search_customer_service_interactions_bm25",
-                "navigation_hubs" => "search_navigation_hubs_bm25",
-                "waypoints" => "search_waypoints_bm25",
-                "direction_paths" => "search_direction_paths_bm25",
-                _ => {
-                    warn!("Unknown memory type for BM25 search: {}", memory_type);
-                    continue;
+        let new_embedding = if self.config.is_mcp_embedding_enabled() {
+            let api_key = self.config.get_api_key().unwrap_or_default();
+            match self.generate_embedding(&composite_text, &api_key).await {
+                Ok(emb) => emb,
+                Err(e) => {
+                    return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Failed to generate embedding: {}", e), json!({})));
                 }
-            };
-
-            // Build payload with query_text and k (limit)
-            let payload = json!({
-                "query_text": query,
-                "k": limit
-            });
-
-            // Add filters if provided
-            if let Some(business_id) = &params.0.business_id {
-                // Note: BM25 queries don't use filters, but we can log it for context
-                info!("BM25 search context: business_id={}", business_id);
             }
+        } else {
+            vec![]
+        };
 
-            if let Some(customer_id) = &params.0.customer_id {
-                info!("BM25 search context: customer_id={}", customer_id);
+        let payload = models::field_update_payload(memory_type, &full, &composite_text, &new_embedding, timestamp);
+
+        let result = match self.helix_client.query(query_name, payload).await {
+            Ok(result) => result,
+            Err(e) => {
+                error!("update_business_memory failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to update {} memory: {}", memory_type, e), json!({
+                    "query_used": query_name
+                })));
             }
+        };
 
-            // Execute BM25 query
-            match self.helix_client.query(query_name, payload).await {
-                Ok(results) => {
-                    if let Some(array) = results.as_array() {
-                        all_results.extend(array.iter().cloned());
-                    }
-                }
-                Err(e) => {
-                    error!("BM25 search failed for {}: {}", memory_type, e);
-                }
+        // The node only keeps the latest price; record the change for get_price_history
+        let price_change = price_history::change(memory_type, id_field, &before, &full, "update_business_memory", timestamp);
+        if let Some(change) = &price_change {
+            if let Err(e) = self.helix_client.query("add_price_change", change.clone()).await {
+                warn!("Failed to record price change for {} {}: {}", memory_type, entity_id, e);
             }
         }
 
         Ok(CallToolResult::structured(json!({
-            "query": query,
-            "memory_types": memory_types,
-            "search_type": "bm25_keyword",
-            "total_results": all_results.len(),
-            "limit": limit,
-            "note": "BM25 uses keyword matching, not semantic embeddings. Best for exact terms, IDs, or specific phrases.",
-            "results": all_results
+            "success": true,
+            "memory_type": memory_type,
+            "business_id": business_id,
+            id_field: entity_id,
+            "changed_fields": changed_fields,
+            "price_change": price_change,
+            "query_used": query_name,
+            "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
+            "updated_at": timestamp,
+            "result": result
         })))
     }
 
-    #[tool(description = "Find customer insights - discover relationships between customers and products/services. Valid relationship_type values: 'liked' (products customer likes), 'disliked' (products customer dislikes), 'used_service' (services used), 'visited_location' (locations visited), 'all' (all relationships). Returns embedded reasons for each relationship.")]
-    async fn find_customer_insights(&self, params: Parameters<FindCustomerInsightsParam>) -> Result<CallToolResult, McpError> {
-        let relationship_type = &params.0.relationship_type;
-        
-        info!("find_customer_insights: relationship_type={}", relationship_type);
-
-        // Build query based on what we're looking for
-        let mut insights = json!({});
-
-        match relationship_type.as_str() {
-            "liked" | "disliked" | "used_service" | "visited_location" | "all" => {
-                // These require traversing edges with embedded reasons
-                // The schema has: CustomerLikedProduct, CustomerDislikedProduct, CustomerUsedService, CustomerVisitedLocation
-                
-                if let Some(customer_id) = &params.0.customer_id {
-                    // Find what this customer liked/disliked/used
-                    insights["customer_id"] = json!(customer_id);
-                    
-                    if relationship_type == "liked" || relationship_type == "all" {
-                        // Get customer preferences
-                        if let Ok(preferences) = self.helix_client.query(
-                            "get_customer_preferences",
-                            json!({"customer_id": customer_id})
-                        ).await {
-                            insights["liked_products"] = json!({
-                                "note": "Customer preferences found. To get actual product relationships, traverse CustomerLikedProduct edges.",
-                                "preferences": preferences
-                            });
-                        }
-                    }
-
-                    if relationship_type == "disliked" || relationship_type == "all" {
-                        // Get negative feedback
-                        if let Ok(feedback) = self.helix_client.query(
-                            "get_customer_feedback",
-                            json!({"customer_id": customer_id})
-                        ).await {
-                            // Filter for negative sentiment
-                            if let Some(feedback_array) = feedback.as_array() {
-                                let negative: Vec<_> = feedback_array
-                                    .iter()
-                                    .filter(|f| {
-                                        f.get("sentiment")
-                                            .and_then(|s| s.as_str())
-                                            .map(|s| s == "negative")
-                                            .unwrap_or(false)
-                                    })
-                                    .cloned()
-                                    .collect();
-                                
-                                insights["disliked_items"] = json!({
-                                    "count": negative.len(),
-                                    "negative_feedback": negative
-                                });
-                            }
-                        }
-                    }
-
-                    if relationship_type == "used_service" || relationship_type == "all" {
-                        // Get behaviors related to service usage
-                        if let Ok(behaviors) = self.helix_client.query(
-                            "get_customer_behaviors",
-                            json!({"customer_id": customer_id})
-                        ).await {
-                            if let Some(behaviors_array) = behaviors.as_array() {
-                                let service_usage: Vec<_> = behaviors_array
-                                    .iter()
-                                    .filter(|b| {
-                                        b.get("behavior_type")
-                                            .and_then(|bt| bt.as_str())
-                                            .map(|bt| bt == "interaction" || bt == "purchase")
-                                            .unwrap_or(false)
-                                    })
-                                    .cloned()
-                                    .collect();
-                                
-                                insights["service_usage"] = json!({
-                                    "count": service_usage.len(),
-                                    "behaviors": service_usage
-                                });
-                            }
-                        }
-                    }
-
-                } else if let Some(product_id) = &params.0.product_id {
-                    // Find which customers liked/disliked this product
-                    insights["product_id"] = json!(product_id);
-                    insights["note"] = json!("To find customers who liked/disliked this product, you need to traverse incoming CustomerLikedProduct/CustomerDislikedProduct edges. This requires graph traversal queries.");
-                    
-                    // Suggestion: Use low-level tools to traverse
-                    insights["suggestion"] = json!({
-                        "approach": "Use in_step tool to traverse CustomerLikedProduct edges from this product node",
-                        "example": "in_step(connection_id, edge_label='CustomerLikedProduct', edge_type='...')"
-                    });
-
-                } else if let Some(service_id) = &params.0.service_id {
-                    // Find which customers used this service
-                    insights["service_id"] = json!(service_id);
-                    insights["note"] = json!("To find customers who used this service, traverse incoming CustomerUsedService edges.");
-                    
-                    insights["suggestion"] = json!({
-                        "approach": "Use in_step tool to traverse CustomerUsedService edges from this service node"
-                    });
-
-                } else {
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": "Must provide at least one of: customer_id, product_id, or service_id"
-                    })));
-                }
+    #[tool(description = "Adjust product stock - record a sale, delivery or stock take on a product's stock_quantity; availability follows the new quantity (in_stock, low_stock at or below reorder_threshold, out_of_stock at 0). Refuses to go below zero. REQUIRED: business_id, product_id, and delta (e.g. -2 sold, 10 delivered) or set_quantity (absolute count; starts tracking stock). Optional: reorder_threshold, reason.")]
+    async fn adjust_product_stock(&self, params: Parameters<AdjustProductStockParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        info!("adjust_product_stock: business_id={}, product_id={}, delta={:?}, set={:?}, reason={:?}", p.business_id, p.product_id, p.delta, p.set_quantity, p.reason);
 
-                Ok(CallToolResult::structured(insights))
+        let product = match self.helix_client.query("get_business_product_by_id", json!({"product_id": p.product_id})).await {
+            Ok(result) => HelixClient::result_items(&result)
+                .into_iter()
+                .find(|m| m.get("business_id").and_then(|v| v.as_str()) == Some(p.business_id.as_str())),
+            Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to look up product {}: {}", p.product_id, e), json!({}))),
+        };
+        let Some(product) = product else {
+            return Ok(tool_error(ErrorCode::NotFound, format!("Product {} not found for business {}", p.product_id, p.business_id), json!({
+                "product_id": p.product_id,
+                "business_id": p.business_id
+            })));
+        };
+        let previous = product.get("stock_quantity").and_then(|v| v.as_i64()).unwrap_or(inventory::UNTRACKED);
+        let quantity = match inventory::adjust(previous, p.delta, p.set_quantity) {
+            Ok(quantity) => quantity,
+            Err(e) if previous > inventory::UNTRACKED && p.delta.is_some_and(|d| previous + d < 0) => {
+                return Ok(tool_error(ErrorCode::Conflict, e, json!({"product_id": p.product_id, "stock_quantity": previous})));
             }
-            _ => {
-                Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Invalid relationship_type: {}. Valid types: liked, disliked, used_service, visited_location, all", relationship_type)
-                })))
+            Err(e) => return Ok(tool_error(ErrorCode::ValidationFailed, e, json!({"product_id": p.product_id, "stock_quantity": previous}))),
+        };
+        let reorder_threshold = match p.reorder_threshold {
+            Some(threshold) if threshold < 0 => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, format!("reorder_threshold must be 0 or more, got {}", threshold), json!({})));
             }
-        }
-    }
+            Some(threshold) => threshold,
+            None => product.get("reorder_threshold").and_then(|v| v.as_i64()).unwrap_or(0),
+        };
+        let current_availability = product.get("availability").and_then(|v| v.as_str()).unwrap_or_default();
+        let availability = inventory::derive_availability(current_availability, quantity, reorder_threshold);
 
-    // ========================================================================
-    // CREATE TOOLS - Add new memories
-    // ========================================================================
+        // Stock moves too often to re-embed on every change; the node's fields are the source of truth
+        let payload = json!({
+            "business_id": p.business_id,
+            "product_id": p.product_id,
+            "stock_quantity": quantity,
+            "reorder_threshold": reorder_threshold,
+            "availability": availability,
+            "updated_at": self.clock.timestamp()
+        });
+        if let Err(e) = self.helix_client.query("update_product_stock", payload).await {
+            error!("adjust_product_stock failed: {}", e);
+            return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to update stock for {}: {}", p.product_id, e), json!({})));
+        }
 
-    #[tool(description = "Create new business memory - add products, services, locations, hours, social media, policies, events, or information. REQUIRED: business_id, memory_type, text_description (for embedding generation in MCP mode). Type-specific required fields: product_name (for product), service_name (for service), location_name (for location), policy_name (for policy), event_name (for event), platform (for social), title (for information). Provide these at top level or in data. No additional fields required for hours. Use 'information' memory_type for storing documentation, manuals, guides, teachings, product usage instructions, FAQs, tutorials, or any general knowledge content. All other fields will be auto-filled with schema defaults if not provided.")]
-    async fn create_business_memory(&self, params: Parameters<CreateBusinessMemoryParam>) -> Result<CallToolResult, McpError> {
-        let business_id = &params.0.business_id;
-        let memory_type_input = &params.0.memory_type;
-        let text_description = &params.0.text_description;
-        let mut data = params.0.data.clone();
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "business_id": p.business_id,
+            "product_id": p.product_id,
+            "previous_quantity": previous,
+            "stock_quantity": quantity,
+            "reorder_threshold": reorder_threshold,
+            "availability": availability,
+            "low_stock": quantity <= reorder_threshold,
+            "reason": p.reason
+        })))
+    }
 
-        // Add text_description to data
-        data["text_description"] = json!(text_description);
+    #[tool(description = "Get low stock products - products of a business whose stock_quantity is at or below their reorder_threshold, lowest first, with the shortfall to reorder. Only products with tracked stock are considered. REQUIRED: business_id. Optional: include_out_of_stock (default true).")]
+    async fn get_low_stock_products(&self, params: Parameters<GetLowStockProductsParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        info!("get_low_stock_products: business_id={}", p.business_id);
+        let products = match self.helix_client.query("get_stock_tracked_products", json!({"business_id": p.business_id})).await {
+            Ok(result) => HelixClient::result_items(&result),
+            Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get products: {}", e), json!({}))),
+        };
+        let low = inventory::low_stock(&products, p.include_out_of_stock.unwrap_or(true));
+        let out_of_stock = low.iter().filter(|product| product["stock_quantity"] == 0).count();
 
-        // Add type-specific fields to data if provided
-        if let Some(ref product_name) = params.0.product_name {
-            data["product_name"] = json!(product_name);
-        }
-        if let Some(ref service_name) = params.0.service_name {
-            data["service_name"] = json!(service_name);
-        }
-        if let Some(ref location_name) = params.0.location_name {
-            data["location_name"] = json!(location_name);
-        }
-        if let Some(ref policy_name) = params.0.policy_name {
-            data["policy_name"] = json!(policy_name);
-        }
-        if let Some(ref event_name) = params.0.event_name {
-            data["event_name"] = json!(event_name);
-        }
-        if let Some(ref platform) = params.0.platform {
-            data["platform"] = json!(platform);
-        }
-        if let Some(ref title) = params.0.title {
-            data["title"] = json!(title);
+        Ok(CallToolResult::structured(json!({
+            "business_id": p.business_id,
+            "tracked_products": products.len(),
+            "low_stock_count": low.len(),
+            "out_of_stock_count": out_of_stock,
+            "products": low
+        })))
+    }
+
+    #[tool(description = "Get price history - when and how a product's or service's price (or currency) changed, oldest first, with old and new values and the change in percent. Changes are recorded by update_business_memory. REQUIRED: business_id. Optional: entity_id (product_id or service_id; omit for all of the business's price changes), memory_type (product or service), since/until (unix seconds, RFC 3339 or YYYY-MM-DD).")]
+    async fn get_price_history(&self, params: Parameters<GetPriceHistoryParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        info!("get_price_history: business_id={}, entity_id={:?}", p.business_id, p.entity_id);
+        let memory_type = p.memory_type.as_deref().map(memory_types::normalize);
+        if memory_type.is_some_and(|t| !matches!(t, "product" | "service")) {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "memory_type must be product or service", json!({"memory_type": p.memory_type})));
         }
-        
-        // Normalize memory_type (accept both "products" and "product")
-        let memory_type = Self::normalize_memory_type(memory_type_input);
-        
-        info!("create_business_memory: business_id={}, type={} (normalized from: {})", business_id, memory_type, memory_type_input);
+        let since = p.since.as_ref().map(|v| filters::parse_timestamp(v).ok_or(("since", v)));
+        let until = p.until.as_ref().map(|v| filters::parse_timestamp(v).ok_or(("until", v)));
+        let (since, until) = match (since.transpose(), until.transpose()) {
+            (Ok(since), Ok(until)) => (since, until),
+            (Err((field, value)), _) | (_, Err((field, value))) => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid {}: expected unix seconds, RFC 3339 or YYYY-MM-DD", field), json!({ field: value })));
+            }
+        };
 
-        // Validate required fields based on memory_type
-        match memory_type {
-            "product" => {
-                if data.get("product_name").is_none() {
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": "product_name is required for product memory type"
-                    })));
-                }
-            },
-            "service" => {
-                if data.get("service_name").is_none() {
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": "service_name is required for service memory type"
-                    })));
-                }
-            },
-            "location" => {
-                if data.get("location_name").is_none() {
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": "location_name is required for location memory type"
-                    })));
-                }
-            },
-            "policy" => {
-                if data.get("policy_name").is_none() {
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": "policy_name is required for policy memory type"
-                    })));
-                }
-            },
-            "event" => {
-                if data.get("event_name").is_none() {
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": "event_name is required for event memory type"
-                    })));
-                }
-            },
-            "social" => {
-                if data.get("platform").is_none() {
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": "platform is required for social memory type"
-                    })));
-                }
-            },
-            "hours" => {
-                // No specific name field required for hours
-            },
-            "information" => {
-                if data.get("title").is_none() {
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": "title is required for information memory type"
-                    })));
-                }
-            },
-            _ => {}
+        let result = match &p.entity_id {
+            Some(entity_id) => self.helix_client.query("get_price_history", json!({"business_id": p.business_id, "entity_id": entity_id})).await,
+            None => self.helix_client.query("get_business_price_history", json!({"business_id": p.business_id})).await,
+        };
+        let changes: Vec<serde_json::Value> = match result {
+            Ok(result) => HelixClient::result_items(&result)
+                .into_iter()
+                .filter(|c| memory_type.is_none_or(|t| c.get("memory_type").and_then(|v| v.as_str()) == Some(t)))
+                .collect(),
+            Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get price history: {}", e), json!({}))),
+        };
+        let history = price_history::timeline(&changes, since, until);
+
+        Ok(CallToolResult::structured(json!({
+            "business_id": p.business_id,
+            "entity_id": p.entity_id,
+            "change_count": history.len(),
+            "current_price": history.last().map(|c| c["new_price"].clone()),
+            "changes": history
+        })))
+    }
+
+    #[tool(description = "Upsert business memory by external ID - idempotent write for sync jobs (ecommerce platforms, POS, CMS). external_id is the record's key in the source system (SKU, product_id, ...); the memory ID is derived from it, so the same external_id always maps to the same memory. Creates the memory if it doesn't exist, re-embeds it if text_description changed, and does nothing if it is unchanged. REQUIRED: business_id, memory_type, external_id, text_description. Optional: data with type-specific fields (product_name, service_name, ... are required when creating).")]
+    async fn upsert_business_memory(&self, params: Parameters<UpsertBusinessMemoryParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let memory_type = memory_types::normalize(&p.memory_type);
+        let external_id = p.external_id.trim();
+        let Some((id_field, "business_id", lookup_query)) = memory_types::entity(memory_type) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}. Valid: product, service, location, hours, social, policy, event, information", p.memory_type), json!({})));
+        };
+        if external_id.is_empty() {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "external_id cannot be empty", json!({})));
         }
+        let memory_id = upsert::memory_id(memory_type, &p.business_id, external_id);
+        info!("upsert_business_memory: business_id={}, type={}, external_id={}", p.business_id, memory_type, external_id);
 
-        // Add business_id to data (required in schema but provided as parameter)
-        data["business_id"] = json!(business_id);
+        let existing = match self.helix_client.query(lookup_query, json!({ id_field: memory_id })).await {
+            Ok(result) => upsert::existing(HelixClient::result_items(&result), &p.business_id),
+            Err(e) => {
+                error!("upsert_business_memory lookup failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to look up {} {}: {}", memory_type, memory_id, e), json!({})));
+            }
+        };
+
+        let data = if p.data.is_object() { p.data.clone() } else { json!({}) };
+        let action = upsert::action(existing.as_ref(), &p.text_description);
+        let Some(existing) = existing else {
+            let result = self.create_business_memory_with_id(Parameters(CreateBusinessMemoryParam {
+                business_id: p.business_id.clone(),
+                memory_type: memory_type.to_string(),
+                text_description: p.text_description.clone(),
+                product_name: None,
+                service_name: None,
+                location_name: None,
+                policy_name: None,
+                event_name: None,
+                platform: None,
+                title: None,
+                data,
+                // The external key already identifies the record
+                dedup: Some(false),
+            }), Some(memory_id.clone())).await?;
+            if result.is_error == Some(true) {
+                return Ok(result);
+            }
+            return Ok(CallToolResult::structured(json!({
+                "success": true,
+                "action": action.as_str(),
+                "memory_type": memory_type,
+                "external_id": external_id,
+                id_field: memory_id,
+                "result": result.structured_content
+            })));
+        };
 
-        // Add timestamps (auto-filled by MCP server, not LLM - schema has DEFAULT NOW)
-        let timestamp = chrono::Utc::now().timestamp();
-        data["created_at"] = json!(timestamp);
-        data["updated_at"] = json!(timestamp);
+        let stale_fields = upsert::stale_fields(&data, &existing);
 
-        // Auto-generate required IDs based on memory type
-        let id_field_name = match memory_type {
-            "product" => "product_id",
-            "service" => "service_id",
-            "location" => "location_id",
-            "hours" => "hours_id",
-            "social" => "social_id",
-            "policy" => "policy_id",
-            "event" => "event_id",
-            "information" => "info_id",
-            _ => return Ok(CallToolResult::structured_error(json!({
-                "error": format!("Invalid memory_type: {}. Valid types: product, service, location, hours, social, policy, event, information", memory_type)
-            }))),
+        if action == upsert::Action::Unchanged {
+            return Ok(CallToolResult::structured(json!({
+                "success": true,
+                "action": action.as_str(),
+                "memory_type": memory_type,
+                "external_id": external_id,
+                id_field: memory_id,
+                "fields_not_updated": stale_fields
+            })));
+        }
+
+        let internal_id = existing.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let result = self.update_business_memory(Parameters(UpdateBusinessMemoryParam {
+            memory_id: internal_id,
+            memory_type: memory_type.to_string(),
+            updates: json!({
+                "composite_text": p.text_description,
+                "business_id": p.business_id,
+                id_field: memory_id
+            }),
+        })).await?;
+        if result.is_error == Some(true) {
+            return Ok(result);
+        }
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "action": action.as_str(),
+            "memory_type": memory_type,
+            "external_id": external_id,
+            id_field: memory_id,
+            "fields_not_updated": stale_fields,
+            "result": result.structured_content
+        })))
+    }
+
+    #[tool(description = "Sync Shopify catalog - pull every product of the store configured in [shopify] through the Admin API and upsert it as a product memory of business_id, keyed by Shopify product ID: new products are created, changed ones updated and re-embedded (price changes are recorded), unchanged ones left alone. Archived or draft products, and synced products no longer in the store, are marked discontinued. Large catalogs: run it with submit_job. REQUIRED: business_id. Optional: mark_discontinued (default true).")]
+    async fn sync_shopify_catalog(&self, params: Parameters<SyncShopifyCatalogParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let token = self.config.get_shopify_token();
+        let (false, Some(token)) = (self.config.shopify.shop_domain.trim().is_empty(), token) else {
+            return Ok(tool_error(ErrorCode::Disabled, "Shopify sync is not configured", json!({
+                "suggestion": "Set shop_domain and access_token in [shopify] of mcpconfig.toml (or SHOPIFY_ACCESS_TOKEN)"
+            })));
         };
-        let generated_id = format!("{}_{}", memory_type.to_uppercase(), Uuid::new_v4().to_string());
-        data[id_field_name] = json!(generated_id);
+        info!("sync_shopify_catalog: business_id={}, shop={}", p.business_id, self.config.shopify.shop_domain);
 
-        // Auto-fill optional fields based on schema defaults (only if not provided)
-        match memory_type {
-            "product" => {
-                // Optional string fields (DEFAULT "" in schema)
-                if !data.get("product_category").is_some() { data["product_category"] = json!(""); }
-                if !data.get("currency").is_some() { data["currency"] = json!(""); }
-                if !data.get("availability").is_some() { data["availability"] = json!(""); }
-                if !data.get("description").is_some() { data["description"] = json!(""); }
-                if !data.get("competitor_analysis").is_some() { data["competitor_analysis"] = json!(""); }
-                
-                // Optional numeric field (DEFAULT 0.0 in schema)
-                if !data.get("price").is_some() { data["price"] = json!(0.0); }
-                
-                // Optional JSON string fields (DEFAULT "{}" in schema)
-                if !data.get("specifications").is_some() { data["specifications"] = json!("{}"); }
-                if !data.get("seasonal_trends").is_some() { data["seasonal_trends"] = json!("{}"); }
-                
-                // REQUIRED array fields - auto-fill with empty arrays if not provided
-                // Schema says these are REQUIRED but can be empty arrays
-                if !data.get("features").is_some() { data["features"] = json!([]); }
-                if !data.get("tags").is_some() { data["tags"] = json!([]); }
-                if !data.get("seo_keywords").is_some() { data["seo_keywords"] = json!([]); }
-            },
-            "service" => {
-                // Optional string fields (DEFAULT "" in schema)
-                if !data.get("service_category").is_some() { data["service_category"] = json!(""); }
-                if !data.get("currency").is_some() { data["currency"] = json!(""); }
-                if !data.get("availability").is_some() { data["availability"] = json!(""); }
-                if !data.get("description").is_some() { data["description"] = json!(""); }
-                
-                // Optional numeric fields (DEFAULT in schema)
-                if !data.get("price").is_some() { data["price"] = json!(0.0); }
-                if !data.get("duration_minutes").is_some() { data["duration_minutes"] = json!(60); }
-                
-                // REQUIRED array fields - auto-fill with empty arrays if not provided
-                if !data.get("requirements").is_some() { data["requirements"] = json!([]); }
-                if !data.get("deliverables").is_some() { data["deliverables"] = json!([]); }
-                if !data.get("tags").is_some() { data["tags"] = json!([]); }
-            },
-            "location" => {
-                // Optional string fields (DEFAULT "" in schema)
-                if !data.get("location_name").is_some() { data["location_name"] = json!(""); }
-                if !data.get("address").is_some() { data["address"] = json!(""); }
-                if !data.get("city").is_some() { data["city"] = json!(""); }
-                if !data.get("state").is_some() { data["state"] = json!(""); }
-                if !data.get("country").is_some() { data["country"] = json!(""); }
-                if !data.get("postal_code").is_some() { data["postal_code"] = json!(""); }
-                if !data.get("location_type").is_some() { data["location_type"] = json!(""); }
-                if !data.get("parking_info").is_some() { data["parking_info"] = json!(""); }
-                
-                // Optional numeric fields (DEFAULT 0.0 in schema)
-                if !data.get("latitude").is_some() { data["latitude"] = json!(0.0); }
-                if !data.get("longitude").is_some() { data["longitude"] = json!(0.0); }
-                
-                // REQUIRED array field - auto-fill with empty array if not provided
-                if !data.get("accessibility").is_some() { data["accessibility"] = json!([]); }
-            },
-            "hours" => {
-                // Optional string fields (DEFAULT "" in schema) - ONLY business_id and hours_id are REQUIRED
-                if !data.get("schedule_type").is_some() { data["schedule_type"] = json!(""); }
-                if !data.get("monday_open").is_some() { data["monday_open"] = json!(""); }
-                if !data.get("monday_close").is_some() { data["monday_close"] = json!(""); }
-                if !data.get("tuesday_open").is_some() { data["tuesday_open"] = json!(""); }
-                if !data.get("tuesday_close").is_some() { data["tuesday_close"] = json!(""); }
-                if !data.get("wednesday_open").is_some() { data["wednesday_open"] = json!(""); }
-                if !data.get("wednesday_close").is_some() { data["wednesday_close"] = json!(""); }
-                if !data.get("thursday_open").is_some() { data["thursday_open"] = json!(""); }
-                if !data.get("thursday_close").is_some() { data["thursday_close"] = json!(""); }
-                if !data.get("friday_open").is_some() { data["friday_open"] = json!(""); }
-                if !data.get("friday_close").is_some() { data["friday_close"] = json!(""); }
-                if !data.get("saturday_open").is_some() { data["saturday_open"] = json!(""); }
-                if !data.get("saturday_close").is_some() { data["saturday_close"] = json!(""); }
-                if !data.get("sunday_open").is_some() { data["sunday_open"] = json!(""); }
-                if !data.get("sunday_close").is_some() { data["sunday_close"] = json!(""); }
-                if !data.get("timezone").is_some() { data["timezone"] = json!(""); }
-                
-                // Optional JSON field (DEFAULT "{}" in schema)
-                if !data.get("exceptions").is_some() { data["exceptions"] = json!("{}"); }
-            },
-            "social" => {
-                // Optional string fields (DEFAULT "" in schema) - REQUIRED: business_id, social_id, platform
-                if !data.get("handle").is_some() { data["handle"] = json!(""); }
-                if !data.get("profile_url").is_some() { data["profile_url"] = json!(""); }
-                if !data.get("description").is_some() { data["description"] = json!(""); }
-                if !data.get("contact_info").is_some() { data["contact_info"] = json!(""); }
-                
-                // Optional numeric fields (DEFAULT 0 in schema)
-                if !data.get("follower_count").is_some() { data["follower_count"] = json!(0); }
-                if !data.get("post_count").is_some() { data["post_count"] = json!(0); }
-                
-                // last_updated defaults to NOW
-                if !data.get("last_updated").is_some() { data["last_updated"] = json!(timestamp); }
-            },
-            "policy" => {
-                // Optional string fields (DEFAULT "" in schema) - REQUIRED: business_id, policy_id, policy_name
-                if !data.get("policy_type").is_some() { data["policy_type"] = json!(""); }
-                if !data.get("content").is_some() { data["content"] = json!(""); }
-                if !data.get("version").is_some() { data["version"] = json!(""); }
-                
-                // Optional boolean (DEFAULT false in schema)
-                if !data.get("is_active").is_some() { data["is_active"] = json!(false); }
-                
-                // REQUIRED array field
-                if !data.get("tags").is_some() { data["tags"] = json!([]); }
-                
-                // effective_date defaults to NOW but will be handled separately if needed
-                if !data.get("effective_date").is_some() { 
-                    data["effective_date"] = json!(chrono::Utc::now().timestamp()); 
-                }
-            },
-            "event" => {
-                // Optional string fields (DEFAULT "" in schema) - REQUIRED: business_id, event_id, event_name
-                if !data.get("event_type").is_some() { data["event_type"] = json!(""); }
-                if !data.get("description").is_some() { data["description"] = json!(""); }
-                if !data.get("location").is_some() { data["location"] = json!(""); }
-                
-                // Optional numeric field (DEFAULT 0 in schema)
-                if !data.get("capacity").is_some() { data["capacity"] = json!(0); }
-                
-                // Optional boolean (DEFAULT false in schema)
-                if !data.get("registration_required").is_some() { data["registration_required"] = json!(false); }
-                
-                // REQUIRED array field
-                if !data.get("tags").is_some() { data["tags"] = json!([]); }
-                
-                // Date fields default to NOW
-                if !data.get("start_date").is_some() { 
-                    data["start_date"] = json!(chrono::Utc::now().timestamp()); 
+        let client = shopify::Client::new(&self.config.shopify, &token);
+        let (currency, products, existing) = tokio::join!(
+            client.shop_currency(),
+            client.products(),
+            self.helix_client.query("get_business_products", json!({"business_id": p.business_id}))
+        );
+        let products = match products {
+            Ok(products) => products,
+            Err(e) => return Ok(tool_error(ErrorCode::Internal, e, json!({"shop_domain": self.config.shopify.shop_domain}))),
+        };
+        let existing = match existing {
+            Ok(result) => HelixClient::result_items(&result),
+            Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get products of {}: {}", p.business_id, e), json!({}))),
+        };
+        let currency = currency.unwrap_or_else(|e| {
+            warn!("Shopify shop currency unavailable, storing prices without currency: {}", e);
+            String::new()
+        });
+        let stored = |product_id: &str| existing.iter().find(|m| m.get("product_id").and_then(|v| v.as_str()) == Some(product_id));
+
+        let (mut created, mut updated, mut unchanged, mut discontinued) = (0, 0, 0, 0);
+        let mut failures = Vec::new();
+        let mut synced_ids = std::collections::HashSet::new();
+        for product in products.iter().filter_map(|product| shopify::map_product(product, &currency)) {
+            let product_id = upsert::memory_id("product", &p.business_id, &product.external_id);
+            synced_ids.insert(product_id.clone());
+            let result = match stored(&product_id) {
+                None => {
+                    let result = priority::background(self.upsert_business_memory(Parameters(UpsertBusinessMemoryParam {
+                        business_id: p.business_id.clone(),
+                        memory_type: "product".to_string(),
+                        external_id: product.external_id.clone(),
+                        text_description: product.text_description.clone(),
+                        data: product.data.clone(),
+                    }))).await?;
+                    created += usize::from(result.is_error != Some(true));
+                    result
                 }
-                if !data.get("end_date").is_some() { 
-                    data["end_date"] = json!(chrono::Utc::now().timestamp()); 
+                Some(node) => {
+                    // Only the fields Shopify changed are written
+                    let mut updates = product.data.as_object().cloned().unwrap_or_default();
+                    updates.insert("text_description".to_string(), json!(product.text_description));
+                    updates.retain(|field, value| node.get(field.as_str()) != Some(value));
+                    if updates.is_empty() {
+                        unchanged += 1;
+                        continue;
+                    }
+                    updates.insert("business_id".to_string(), json!(p.business_id));
+                    updates.insert("product_id".to_string(), json!(product_id));
+                    let result = priority::background(self.update_business_memory(Parameters(UpdateBusinessMemoryParam {
+                        memory_id: node.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                        memory_type: "product".to_string(),
+                        updates: serde_json::Value::Object(updates),
+                    }))).await?;
+                    updated += usize::from(result.is_error != Some(true));
+                    result
                 }
-            },
-            "information" => {
-                // Optional string fields (DEFAULT "" in schema) - REQUIRED: business_id, info_id, title
-                if !data.get("info_type").is_some() { data["info_type"] = json!(""); }
-                if !data.get("content").is_some() { data["content"] = json!(""); }
-                if !data.get("category").is_some() { data["category"] = json!(""); }
-                
-                // REQUIRED array field
-                if !data.get("tags").is_some() { data["tags"] = json!([]); }
-            },
-            _ => {
-                // Unknown type - text_description already added
+            };
+            if result.is_error == Some(true) {
+                failures.push(json!({
+                    "external_id": product.external_id,
+                    "error": result.structured_content.and_then(|c| c.get("error").cloned())
+                }));
             }
         }
 
-        // Check if embedding needs to be generated (MCP mode)
-        if self.config.is_mcp_embedding_enabled() {
-            // Get text_description from data (now guaranteed to exist, may be empty string)
-            let text_description = data.get("text_description")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            
-            // Only generate embedding if text_description is not empty
-            if text_description.is_empty() {
-                return Ok(CallToolResult::structured_error(json!({
-                    "error": "text_description is required for embedding generation in MCP mode",
-                    "suggestion": "Provide text_description field with descriptive content"
-                })));
+        // Synced products the store no longer has
+        if p.mark_discontinued.unwrap_or(true) {
+            let prefix = upsert::memory_id("product", &p.business_id, shopify::EXTERNAL_PREFIX);
+            let gone = existing.iter().filter(|m| {
+                let product_id = m.get("product_id").and_then(|v| v.as_str()).unwrap_or("");
+                product_id.starts_with(&prefix)
+                    && !synced_ids.contains(product_id)
+                    && m.get("availability").and_then(|v| v.as_str()) != Some(shopify::DISCONTINUED)
+            });
+            for node in gone {
+                let result = priority::background(self.update_business_memory(Parameters(UpdateBusinessMemoryParam {
+                    memory_id: node.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                    memory_type: "product".to_string(),
+                    updates: json!({
+                        "availability": shopify::DISCONTINUED,
+                        "business_id": p.business_id,
+                        "product_id": node.get("product_id")
+                    }),
+                }))).await?;
+                if result.is_error == Some(true) {
+                    failures.push(json!({
+                        "product_id": node.get("product_id"),
+                        "error": result.structured_content.and_then(|c| c.get("error").cloned())
+                    }));
+                } else {
+                    discontinued += 1;
+                }
             }
+        }
 
-            info!("Generating embedding for text_description...");
-            
-            // Get API key (empty for local provider)
-            let api_key = self.config.get_api_key().unwrap_or_default();
-            
-            // Generate embedding
-            match self.generate_embedding(text_description, &api_key).await {
-                Ok(embedding) => {
-                    info!("✓ Generated {} dimensional embedding", embedding.len());
+        info!("✓ Shopify sync for {}: {} created, {} updated, {} unchanged, {} discontinued ({} failures)", p.business_id, created, updated, unchanged, discontinued, failures.len());
+        Ok(CallToolResult::structured(json!({
+            "success": failures.is_empty(),
+            "business_id": p.business_id,
+            "shop_domain": self.config.shopify.shop_domain,
+            "currency": currency,
+            "fetched": products.len(),
+            "created": created,
+            "updated": updated,
+            "unchanged": unchanged,
+            "discontinued": discontinued,
+            "failed": failures.len(),
+            "failures": failures
+        })))
+    }
 
-                    // Add embedding to data (convert f32 to f64 for HelixDB)
-                    data["embedding"] = json!(embedding.iter().map(|&x| x as f64).collect::<Vec<f64>>());
+    #[tool(description = "Import events from iCalendar - read an ICS feed (url: http(s) or webcal://, public hosts or [calendar_import] allowed_hosts only), a file in the server's calendar import directory (path, a file name) or inline text (ics) and create or update an event memory of business_id for every event that hasn't ended yet and starts within horizon_days. Recurring events (RRULE) become one event per occurrence; EXDATEs and moved occurrences are honored. Events are keyed by their UID, so re-importing a feed updates moved or renamed events instead of duplicating them. REQUIRED: business_id and one of url, path, ics. Optional: horizon_days (default 180), utc_offset for TZID/floating times (default UTC).")]
+    async fn import_events_ics(&self, params: Parameters<ImportEventsIcsParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let Some(offset) = hours::parse_offset(p.utc_offset.as_deref().unwrap_or("UTC")) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid utc_offset '{}'", p.utc_offset.unwrap_or_default()), json!({
+                "suggestion": "Use an offset like \"+02:00\" or an abbreviation like \"CET\""
+            })));
+        };
+        let horizon_days = p.horizon_days.unwrap_or(180);
+        if !(1..=1825).contains(&horizon_days) {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "horizon_days must be between 1 and 1825", json!({"horizon_days": horizon_days})));
+        }
 
-                    // Add embedding model info
-                    let model_name = match self.config.embedding.provider {
-                        Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
-                            self.config.embedding.model.clone().unwrap_or_else(|| "unknown".to_string())
-                        }
-                        Some(config::EmbeddingProvider::Local) => "local".to_string(),
-                        Some(config::EmbeddingProvider::Tcp) => "tcp-local".to_string(),
-                        None => "unknown".to_string()
-                    };
-                    data["embedding_model"] = json!(model_name);
+        let ics = match (&p.url, &p.path, &p.ics) {
+            (Some(url), None, None) => {
+                let allowed_hosts = &self.config.calendar_import.allowed_hosts;
+                let url = match ics::check_feed_url(url, allowed_hosts) {
+                    Ok(url) => url,
+                    Err(e) => return Ok(tool_error(ErrorCode::ValidationFailed, e, json!({"url": url}))),
+                };
+                info!("import_events_ics: business_id={}, url={}", p.business_id, url);
+                match ics::fetch_feed(&url, allowed_hosts).await {
+                    Ok(text) => text,
+                    Err(e) => return Ok(tool_error(ErrorCode::ValidationFailed, e, json!({"url": url.as_str()}))),
                 }
-                Err(e) => {
-                    error!("✗ Failed to generate embedding: {}", e);
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to generate embedding: {}", e),
-                        "suggestion": "Check embedding configuration and API connectivity"
-                    })));
+            }
+            (None, Some(path), None) => {
+                let directory = std::path::Path::new(&self.config.calendar_import.directory);
+                let file = match ics::path_for(directory, path) {
+                    Ok(file) => file,
+                    Err(e) => return Ok(tool_error(ErrorCode::ValidationFailed, e, json!({"path": path}))),
+                };
+                info!("import_events_ics: business_id={}, path={}", p.business_id, file.display());
+                match tokio::fs::read_to_string(&file).await {
+                    Ok(text) => text,
+                    Err(e) => return Ok(tool_error(ErrorCode::NotFound, format!("Failed to read {}: {}", path, e), json!({"path": path}))),
                 }
             }
-        } else {
-            // HelixDB mode - embeddings should be in the data already or will be generated by HelixDB
-            info!("Using HelixDB embedding mode - expecting embedding in data or HelixDB will generate it");
-        }
+            (None, None, Some(ics)) => ics.clone(),
+            _ => return Ok(tool_error(ErrorCode::ValidationFailed, "Provide exactly one of url, path or ics", json!({}))),
+        };
+        let events = match ics::parse(&ics, offset) {
+            Ok(events) => events,
+            Err(e) => return Ok(tool_error(ErrorCode::ValidationFailed, e, json!({}))),
+        };
+        let now = self.clock.timestamp();
+        let occurrences = ics::occurrences(&events, now, now + horizon_days * 86_400, offset);
 
-        // Determine which query to execute based on memory_type
-        let query_name = match memory_type {
-            "product" => "add_business_product_memory",
-            "service" => "add_business_service_memory",
-            "location" => "add_business_location_memory",
-            "hours" => "add_business_hours_memory",
-            "social" => "add_business_social_media_memory",
-            "policy" => "add_business_policy_memory",
-            "event" => "add_business_event_memory",
-            "information" => "add_business_information_memory",
-            _ => {
-                return Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Invalid memory_type: {}. Valid types: product, service, location, hours, social, policy, event, information", memory_type)
-                })));
-            }
+        let existing = match self.helix_client.query("get_business_events", json!({"business_id": p.business_id})).await {
+            Ok(result) => HelixClient::result_items(&result),
+            Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get events of {}: {}", p.business_id, e), json!({}))),
         };
+        let stored = |event_id: &str| existing.iter().find(|m| m.get("event_id").and_then(|v| v.as_str()) == Some(event_id));
 
-        // Execute the query
-        match self.helix_client.query(query_name, data).await {
-            Ok(result) => {
-                Ok(CallToolResult::structured(json!({
-                    "success": true,
-                    "memory_type": memory_type,
-                    "business_id": business_id,
-                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
-                    "result": result
-                })))
-            }
-            Err(e) => {
-                error!("create_business_memory failed: {}", e);
-                Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Failed to create {} memory: {}", memory_type, e)
-                })))
+        let (mut created, mut updated, mut unchanged) = (0, 0, 0);
+        let mut failures = Vec::new();
+        for occurrence in &occurrences {
+            let event_id = upsert::memory_id("event", &p.business_id, &occurrence.external_id);
+            let result = match stored(&event_id) {
+                None => {
+                    let result = priority::background(self.upsert_business_memory(Parameters(UpsertBusinessMemoryParam {
+                        business_id: p.business_id.clone(),
+                        memory_type: "event".to_string(),
+                        external_id: occurrence.external_id.clone(),
+                        text_description: occurrence.text_description.clone(),
+                        data: occurrence.data.clone(),
+                    }))).await?;
+                    created += usize::from(result.is_error != Some(true));
+                    result
+                }
+                Some(node) => {
+                    // Only the fields the feed changed are written (times included)
+                    let mut updates = occurrence.data.as_object().cloned().unwrap_or_default();
+                    updates.insert("text_description".to_string(), json!(occurrence.text_description));
+                    updates.retain(|field, value| node.get(field.as_str()) != Some(value));
+                    if updates.is_empty() {
+                        unchanged += 1;
+                        continue;
+                    }
+                    updates.insert("business_id".to_string(), json!(p.business_id));
+                    updates.insert("event_id".to_string(), json!(event_id));
+                    let result = priority::background(self.update_business_fields("event", &serde_json::Value::Object(updates))).await?;
+                    updated += usize::from(result.is_error != Some(true));
+                    result
+                }
+            };
+            if result.is_error == Some(true) {
+                failures.push(json!({
+                    "external_id": occurrence.external_id,
+                    "error": result.structured_content.and_then(|c| c.get("error").cloned())
+                }));
             }
         }
+
+        info!("✓ iCal import for {}: {} created, {} updated, {} unchanged ({} failures)", p.business_id, created, updated, unchanged, failures.len());
+        Ok(CallToolResult::structured(json!({
+            "success": failures.is_empty(),
+            "business_id": p.business_id,
+            "calendar_events": events.len(),
+            "occurrences": occurrences.len(),
+            "horizon_days": horizon_days,
+            "created": created,
+            "updated": updated,
+            "unchanged": unchanged,
+            "failed": failures.len(),
+            "failures": failures
+        })))
     }
 
-    #[tool(description = "Create new customer memory - add behaviors, preferences, desires, rules, or feedback. REQUIRED: customer_id, memory_type, text_description (for embedding generation in MCP mode), data with memory-specific fields. All other optional fields will be auto-filled with schema defaults if not provided.")]
-    async fn create_customer_memory(&self, params: Parameters<CreateCustomerMemoryParam>) -> Result<CallToolResult, McpError> {
-        let customer_id = &params.0.customer_id;
+    #[tool(description = "Update existing customer memory (behaviors, preferences, desires, rules, feedback, communication). REQUIRED: memory_id (internal UUID from database node), memory_type, updates dict with text_description for embedding regeneration and/or importance (0-5) and pinned (not for communications). Get internal ID using query_customer_memory.")]
+    async fn update_customer_memory(&self, params: Parameters<UpdateCustomerMemoryParam>) -> Result<CallToolResult, McpError> {
+        let memory_id = &params.0.memory_id;
         let memory_type_input = &params.0.memory_type;
-        let text_description = &params.0.text_description;
-        let mut data = params.0.data.clone();
-
-        // Add text_description to data
-        data["text_description"] = json!(text_description);
+        let updates = &params.0.updates;
         
         // Normalize memory_type (accept both "behaviors" and "behavior")
-        let memory_type = Self::normalize_memory_type(memory_type_input);
+        let memory_type = memory_types::normalize(memory_type_input);
         
-        info!("create_customer_memory: customer_id={}, type={} (normalized from: {})", customer_id, memory_type, memory_type_input);
-
-        // Check if embedding needs to be generated (MCP mode)
-        if self.config.is_mcp_embedding_enabled() {
-            // Get text_description from data
-            let text_description = data.get("text_description")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| McpError::invalid_params("Missing text_description field", None))?;
+        info!("update_customer_memory: memory_id={}, type={} (normalized from: {})", memory_id, memory_type, memory_type_input);
 
-            info!("Generating embedding for text_description...");
-            
-            // Get API key (empty for local provider)
-            let api_key = self.config.get_api_key().unwrap_or_default();
-            
-            // Generate embedding
-            match self.generate_embedding(text_description, &api_key).await {
-                Ok(embedding) => {
-                    info!("? Generated {} dimensional embedding", embedding.len());
+        // Text changes go through the vector-aware update query; importance/pinned alone don't re-embed
+        let composite_text = updates.get("composite_text")
+            .or(updates.get("text_description"))
+            .or(updates.get("context"))
+            .and_then(|v| v.as_str());
+        let importance = updates.get("importance").and_then(|v| v.as_i64());
+        let pinned = updates.get("pinned").and_then(|v| v.as_bool());
+        let ranking = importance.is_some() || pinned.is_some();
+        if composite_text.is_none() && !ranking {
+            return Err(McpError::invalid_request(
+                "Missing required field: composite_text (or text_description/context), or importance/pinned",
+                None
+            ));
+        }
+        if ranking && !importance::MEMORY_TYPES.contains(&memory_type) {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("importance and pinned are not supported on {} memories", memory_type), json!({})));
+        }
 
-                    // Add embedding to data (convert f32 to f64 for HelixDB)
-                    data["embedding"] = json!(embedding.iter().map(|&x| x as f64).collect::<Vec<f64>>());
+        // Route to appropriate vector-aware update query
+        let query_name = match memory_type {
+            "preference" => "update_customer_preference_memory",
+            "behavior" => "update_customer_behavior_memory",
+            "desire" => "update_customer_desire_memory",
+            "rule" => "update_customer_rule_memory",
+            "feedback" => "update_customer_feedback_memory",
+            "communication" => "update_customer_communication_memory",
+            _ => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}. Valid: preference, behavior, desire, rule, feedback, communication", memory_type), json!({})));
+            }
+        };
 
-                    // Add embedding model info
-                    let model_name = match self.config.embedding.provider {
-                        Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
-                            self.config.embedding.model.clone().unwrap_or_else(|| "unknown".to_string())
-                        }
-                        Some(config::EmbeddingProvider::Local) => "local".to_string(),
-                        Some(config::EmbeddingProvider::Tcp) => "tcp-local".to_string(),
-                        None => "unknown".to_string()
-                    };
-                    data["embedding_model"] = json!(model_name);
+        let timestamp = self.clock.timestamp();
+        let mut result = serde_json::Value::Null;
+        if let Some(composite_text) = composite_text {
+            // Generate embedding based on mode
+            let new_embedding = if self.config.is_mcp_embedding_enabled() {
+                // MCP Mode: Generate embedding via OpenAI/Gemini/Local/TCP
+                info!("MCP mode: Generating new embedding for {} {}", memory_type, memory_id);
+                let api_key = self.config.get_api_key().unwrap_or_default();
+
+                match self.generate_embedding(composite_text, &api_key).await {
+                    Ok(emb) => emb,
+                    Err(e) => {
+                        return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Failed to generate embedding: {}", e), json!({})));
+                    }
                 }
+            } else {
+                // HelixDB Mode: Use empty vector (HelixDB will generate via Embed() function)
+                info!("HelixDB mode: Using empty embedding placeholder for {} {}", memory_type, memory_id);
+                vec![]
+            };
+
+            // Build payload for vector-aware update query
+            let payload = json!({
+                "memory_id": memory_id,
+                "composite_text": composite_text,
+                "new_embedding": new_embedding,
+                "timestamp": timestamp
+            });
+
+            // Execute vector-aware update query (DROP old vector + CREATE new one)
+            match self.helix_client.query(query_name, payload).await {
+                Ok(updated) => result = updated,
                 Err(e) => {
-                    error!("? Failed to generate embedding: {}", e);
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to generate embedding: {}", e),
-                        "suggestion": "Check embedding configuration and API connectivity"
+                    error!("update_customer_memory failed: {}", e);
+                    return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to update {} memory: {}", memory_type, e), json!({
+                        "query_used": query_name
                     })));
                 }
             }
-        } else {
-            info!("Using HelixDB embedding mode - expecting embedding in data or HelixDB will generate it");
         }
 
-        // Add customer_id to data (required by all customer memory queries)
-        data["customer_id"] = json!(customer_id);
-
-        // Auto-fill timestamps (DEFAULT NOW in schema)
-        let current_timestamp = chrono::Utc::now().timestamp();
-        if !data.get("created_at").is_some() {
-            data["created_at"] = json!(current_timestamp);
-        }
-        if !data.get("updated_at").is_some() {
-            data["updated_at"] = json!(current_timestamp);
-        }
-        if !data.get("timestamp").is_some() {
-            data["timestamp"] = json!(current_timestamp);
+        if ranking {
+            match self.set_memory_ranking(memory_type, memory_id, importance, pinned).await {
+                Ok(node) if result.is_null() => result = json!(node),
+                Ok(_) => {}
+                Err(e) => {
+                    error!("update_customer_memory ranking failed: {}", e);
+                    return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to update {} memory importance: {}", memory_type, e), json!({
+                        "text_updated": composite_text.is_some()
+                    })));
+                }
+            }
         }
 
-        // Auto-generate required IDs based on memory type
-        let id_field_name = match memory_type {
-            "behavior" => "behavior_id",
-            "preference" => "preference_id", 
-            "desire" => "desire_id",
-            "rule" => "rule_id",
-            "feedback" => "feedback_id",
-            _ => return Ok(CallToolResult::structured_error(json!({
-                "error": format!("Invalid memory_type: {}. Valid types: behavior, preference, desire, rule, feedback", memory_type)
-            }))),
-        };
-        let generated_id = format!("{}_{}", memory_type.to_uppercase(), Uuid::new_v4().to_string());
-        data[id_field_name] = json!(generated_id);
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "memory_type": memory_type,
+            "memory_id": memory_id,
+            "query_used": composite_text.map(|_| query_name),
+            "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
+            "importance": importance,
+            "pinned": pinned,
+            "updated_at": timestamp,
+            "result": result
+        })))
+    }
 
-        // Auto-fill optional fields based on memory type with schema defaults
-        match memory_type {
-            "behavior" => {
-                if !data.get("behavior_type").is_some() { data["behavior_type"] = json!(""); }
-                if !data.get("action").is_some() { data["action"] = json!(""); }
-                if !data.get("context").is_some() { data["context"] = json!(""); }
-                if !data.get("channel").is_some() { data["channel"] = json!(""); }
-                if !data.get("duration_seconds").is_some() { data["duration_seconds"] = json!(0); }
-                if !data.get("metadata").is_some() { data["metadata"] = json!("{}"); }
-            },
-            "preference" => {
-                if !data.get("preference_type").is_some() { data["preference_type"] = json!(""); }
-                if !data.get("category").is_some() { data["category"] = json!(""); }
-                if !data.get("subject").is_some() { data["subject"] = json!(""); }
-                if !data.get("strength").is_some() { data["strength"] = json!(""); }
-                if !data.get("is_active").is_some() { data["is_active"] = json!(false); }
-                if !data.get("evidence_count").is_some() { data["evidence_count"] = json!(0); }
-                if !data.get("last_evidence").is_some() { data["last_evidence"] = json!(current_timestamp); }
-                if !data.get("confidence_score").is_some() { data["confidence_score"] = json!(0.0); }
-                if !data.get("source_channels").is_some() { data["source_channels"] = json!([]); }
-            },
-            "desire" => {
-                if !data.get("desire_type").is_some() { data["desire_type"] = json!(""); }
-                if !data.get("category").is_some() { data["category"] = json!(""); }
-                if !data.get("description").is_some() { data["description"] = json!(""); }
-                if !data.get("priority").is_some() { data["priority"] = json!(""); }
-                if !data.get("timeframe").is_some() { data["timeframe"] = json!(""); }
-                if !data.get("budget_range").is_some() { data["budget_range"] = json!(""); }
-                if !data.get("is_active").is_some() { data["is_active"] = json!(false); }
-            },
-            "rule" => {
-                if !data.get("rule_type").is_some() { data["rule_type"] = json!(""); }
-                if !data.get("category").is_some() { data["category"] = json!(""); }
-                if !data.get("rule_category").is_some() { data["rule_category"] = json!(""); }
-                if !data.get("rule_description").is_some() { data["rule_description"] = json!(""); }
-                if !data.get("enforcement").is_some() { data["enforcement"] = json!(""); }
-                if !data.get("exceptions").is_some() { data["exceptions"] = json!([]); }
-                if !data.get("condition").is_some() { data["condition"] = json!(""); }
-                if !data.get("action").is_some() { data["action"] = json!(""); }
-                if !data.get("priority").is_some() { data["priority"] = json!(0); }
-                if !data.get("is_active").is_some() { data["is_active"] = json!(true); }
-            },
-            "feedback" => {
-                if !data.get("feedback_type").is_some() { data["feedback_type"] = json!(""); }
-                if !data.get("subject").is_some() { data["subject"] = json!(""); }
-                if !data.get("rating").is_some() { data["rating"] = json!(0); }
-                if !data.get("sentiment").is_some() { data["sentiment"] = json!("neutral"); }
-                if !data.get("channel").is_some() { data["channel"] = json!(""); }
-                if !data.get("response_required").is_some() { data["response_required"] = json!(false); }
-                if !data.get("resolved").is_some() { data["resolved"] = json!(false); }
-                if !data.get("feedback_category").is_some() { data["feedback_category"] = json!(""); }
-                if !data.get("source").is_some() { data["source"] = json!(""); }
-                if !data.get("is_resolved").is_some() { data["is_resolved"] = json!(false); }
-            },
-            _ => {}
+    /// Set importance and/or pinned on a customer memory by node ID; returns the updated node
+    async fn set_memory_ranking(&self, memory_type: &str, memory_id: &str, importance: Option<i64>, pinned: Option<bool>) -> Result<Option<serde_json::Value>, String> {
+        let timestamp = self.clock.timestamp();
+        let mut updated = None;
+        if let Some(importance) = importance {
+            let result = self.helix_client
+                .query(&format!("set_customer_{}_importance", memory_type), json!({"memory_id": memory_id, "importance": importance.clamp(0, 5), "timestamp": timestamp}))
+                .await
+                .map_err(|e| e.to_string())?;
+            updated = HelixClient::result_items(&result).into_iter().next();
         }
+        if let Some(pinned) = pinned {
+            let result = self.helix_client
+                .query(&format!("pin_customer_{}_memory", memory_type), json!({"memory_id": memory_id, "pinned": pinned, "timestamp": timestamp}))
+                .await
+                .map_err(|e| e.to_string())?;
+            updated = HelixClient::result_items(&result).into_iter().next();
+        }
+        Ok(updated)
+    }
 
-        // Determine which query to execute based on memory_type
-        let query_name = match memory_type {
-            "behavior" => "add_customer_behavior_memory",
-            "preference" => "add_customer_preference_memory",
-            "desire" => "add_customer_desire_memory",
-            "rule" => "add_customer_rule_memory",
-            "feedback" => "add_customer_feedback_memory",
-            _ => {
-                return Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Invalid memory_type: {}. Valid types: behavior, preference, desire, rule, feedback", memory_type)
-                })));
-            }
-        };
-
-        // Execute the query
-        match self.helix_client.query(query_name, data).await {
-            Ok(result) => {
-                Ok(CallToolResult::structured(json!({
-                    "success": true,
-                    "memory_type": memory_type,
-                    "customer_id": customer_id,
-                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
-                    "result": result
-                })))
-            }
+    #[tool(description = "Pin memory - pin (or unpin) a customer memory and optionally set its importance (0-5) so critical facts such as allergies or hard constraints rank ahead of routine memories in search_semantic, query_customer_memory and summarize_feedback. Pinned memories always come first. REQUIRED: memory_id (internal UUID from query_customer_memory), memory_type (behavior, preference, desire, rule, feedback). Optional: pinned (default true), importance.")]
+    async fn pin_memory(&self, params: Parameters<PinMemoryParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let memory_type = memory_types::normalize(&p.memory_type);
+        if !importance::MEMORY_TYPES.contains(&memory_type) {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}. Valid: {}", p.memory_type, importance::MEMORY_TYPES.join(", ")), json!({})));
+        }
+        let pinned = p.pinned.unwrap_or(true);
+        info!("pin_memory: memory_id={}, type={}, pinned={}, importance={:?}", p.memory_id, memory_type, pinned, p.importance);
+
+        match self.set_memory_ranking(memory_type, &p.memory_id, p.importance.map(i64::from), Some(pinned)).await {
+            Ok(Some(memory)) => Ok(CallToolResult::structured(json!({
+                "success": true,
+                "memory_type": memory_type,
+                "memory_id": p.memory_id,
+                "pinned": pinned,
+                "importance": memory.get("importance"),
+                "memory": memory
+            }))),
+            Ok(None) => Ok(tool_error(ErrorCode::NotFound, format!("{} memory {} not found", memory_type, p.memory_id), json!({
+                "suggestion": "Use the internal id from query_customer_memory"
+            }))),
             Err(e) => {
-                error!("create_customer_memory failed: {}", e);
-                Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Failed to create {} memory: {}", memory_type, e)
-                })))
+                error!("pin_memory failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to pin {} memory: {}", memory_type, e), json!({})))
             }
         }
     }
 
-    // ========================================================================
-    // CUSTOMER INTERACTION TOOLS - Track detailed customer interactions
-    // ========================================================================
+    #[tool(description = "Update customer interaction (product or service) - change its text and/or fields. Text changes regenerate the embedding; field-only changes keep it. REQUIRED: interaction_id (internal UUID from database node), interaction_type, and at least one of: composite_text (new text), rating (1-5; satisfaction_rating on services), purchase_amount (cost_actual on services), resolution_status (products: resolved, pending, escalated), outcome (services). Get internal ID using query_customer_interactions.")]
+    async fn update_interaction(&self, params: Parameters<UpdateInteractionParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let interaction_id = &p.interaction_id;
 
-    #[tool(description = "Create customer product interaction - track detailed customer-product interactions with reasons (likes, dislikes, purchases, views, reviews). Use query_business_memory to get product_id.")]
-    async fn create_customer_product_interaction(&self, params: Parameters<CreateCustomerProductInteractionParam>) -> Result<CallToolResult, McpError> {
-        let customer_id = &params.0.customer_id;
-        let product_id = &params.0.product_id;
-        let interaction_type = &params.0.interaction_type;
-        let text_reason = &params.0.text_reason;
-        
-        // Always auto-generate interaction_id
-        let interaction_id = format!("INT_{}", Uuid::new_v4().to_string());
-        
-        info!("create_customer_product_interaction: customer_id={}, product_id={}, interaction_id={}, type={}", customer_id, product_id, interaction_id, interaction_type);
+        // Normalize to singular (accept both "products" and "product")
+        let interaction_type = memory_types::normalize(&p.interaction_type);
 
-        // Build the data payload with all fields
-        let timestamp = chrono::Utc::now().timestamp();
-        let mut data = json!({
-            "customer_id": customer_id,
-            "product_id": product_id,
-            "interaction_id": interaction_id,
-            "interaction_type": interaction_type,
-            "rating": params.0.rating.unwrap_or(0),
-            "timestamp": timestamp,
-            "channel": params.0.channel.as_ref().unwrap_or(&String::from("")),
-            "session_duration": params.0.session_duration.unwrap_or(0),
-            "purchase_amount": params.0.purchase_amount.unwrap_or(0.0),
-            "currency": params.0.currency.as_ref().unwrap_or(&String::from("USD")),
-            "issue_category": params.0.issue_category.as_ref().unwrap_or(&String::from("")),
-            "resolution_status": params.0.resolution_status.as_ref().unwrap_or(&String::from("none")),
-            "created_at": timestamp,
-            "updated_at": timestamp,
-            "text_reason": text_reason
-        });
+        info!("update_interaction: interaction_id={}, type={} (normalized from: {})", interaction_id, interaction_type, p.interaction_type);
 
-        // Check if embedding needs to be generated (MCP mode)
-        if self.config.is_mcp_embedding_enabled() {
-            info!("Generating embedding for text_reason...");
-            
-            let api_key = self.config.get_api_key().unwrap_or_default();
-            
-            match self.generate_embedding(text_reason, &api_key).await {
-                Ok(embedding) => {
-                    info!("✓ Generated {} dimensional embedding", embedding.len());
+        let Some(target) = interaction_update::target(interaction_type) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid interaction_type: {}. Valid: product, service", interaction_type), json!({})));
+        };
+        let (text_query, fields_query, text_field) = (target.text_query, target.fields_query, target.text_field);
+        let status = match interaction_update::validate(interaction_type, p.composite_text.is_some(), p.rating, p.purchase_amount, p.resolution_status.as_deref(), p.outcome.as_deref()) {
+            Ok(status) => status,
+            Err((message, details)) => return Ok(tool_error(ErrorCode::ValidationFailed, message, details)),
+        };
 
-                    // Add embedding to data (convert f32 to f64 for HelixDB)
-                    data["embedding"] = json!(embedding.iter().map(|&x| x as f64).collect::<Vec<f64>>());
+        let current = match self.helix_client.query(target.lookup_query, json!({"memory_id": interaction_id})).await {
+            Ok(result) => HelixClient::result_items(&result).into_iter().next(),
+            Err(e) => {
+                error!("update_interaction lookup failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to look up {} interaction: {}", interaction_type, e), json!({})));
+            }
+        };
+        let Some(current) = current else {
+            return Ok(tool_error(ErrorCode::NotFound, format!("{} interaction {} not found", interaction_type, interaction_id), json!({
+                "suggestion": "Use the internal id from query_customer_interactions"
+            })));
+        };
 
-                    let model_name = match self.config.embedding.provider {
-                        Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
-                            self.config.embedding.model.clone().unwrap_or_else(|| "unknown".to_string())
-                        }
-                        Some(config::EmbeddingProvider::Local) => "local".to_string(),
-                        Some(config::EmbeddingProvider::Tcp) => "tcp-local".to_string(),
-                        None => "unknown".to_string()
-                    };
-                    data["embedding_model"] = json!(model_name);
+        let (mut fields, mut changed_fields) = interaction_update::merge_fields(&target, &current, p.rating, p.purchase_amount, status);
+        let new_text = interaction_update::changed_text(&target, &current, p.composite_text.as_deref());
+
+        let timestamp = self.clock.timestamp();
+        let mut result = serde_json::Value::Null;
+        if !changed_fields.is_empty() {
+            fields["memory_id"] = json!(interaction_id);
+            fields["timestamp"] = json!(timestamp);
+            result = match self.helix_client.query(fields_query, fields).await {
+                Ok(result) => result,
+                Err(e) => {
+                    error!("update_interaction failed: {}", e);
+                    return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to update {} interaction: {}", interaction_type, e), json!({"query_used": fields_query})));
+                }
+            };
+        }
+
+        // Only a text change needs a new embedding
+        if let Some(composite_text) = new_text {
+            let new_embedding = if self.config.is_mcp_embedding_enabled() {
+                info!("MCP mode: Generating new embedding for {} interaction {}", interaction_type, interaction_id);
+                let api_key = self.config.get_api_key().unwrap_or_default();
+                match self.generate_embedding(composite_text, &api_key).await {
+                    Ok(emb) => emb,
+                    Err(e) => {
+                        return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Failed to generate embedding: {}", e), json!({
+                            "fields_updated": changed_fields
+                        })));
+                    }
                 }
+            } else {
+                info!("HelixDB mode: Using empty embedding placeholder for {} interaction {}", interaction_type, interaction_id);
+                vec![]
+            };
+            let payload = json!({
+                "memory_id": interaction_id,
+                "composite_text": composite_text,
+                "new_embedding": new_embedding,
+                "timestamp": timestamp
+            });
+            result = match self.helix_client.query(text_query, payload).await {
+                Ok(result) => result,
                 Err(e) => {
-                    error!("✗ Failed to generate embedding: {}", e);
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to generate embedding: {}", e),
-                        "suggestion": "Check embedding configuration and API connectivity"
+                    error!("update_interaction failed: {}", e);
+                    return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to update {} interaction: {}", interaction_type, e), json!({
+                        "query_used": text_query,
+                        "fields_updated": changed_fields
                     })));
                 }
-            }
-        } else {
-            info!("Using HelixDB embedding mode - HelixDB will generate embedding");
+            };
+            changed_fields.push(text_field);
         }
 
-        // Execute the query
-        match self.helix_client.query("add_customer_product_interaction", data).await {
-            Ok(result) => {
-                Ok(CallToolResult::structured(json!({
-                    "success": true,
-                    "interaction_type": "product",
-                    "customer_id": customer_id,
-                    "product_id": product_id,
-                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
-                    "result": result
-                })))
-            }
-            Err(e) => {
-                error!("create_customer_product_interaction failed: {}", e);
-                Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Failed to create product interaction: {}", e)
-                })))
-            }
-        }
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "interaction_type": interaction_type,
+            "interaction_id": interaction_id,
+            "changed_fields": changed_fields,
+            "embedding_regenerated": new_text.is_some(),
+            "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
+            "updated_at": timestamp,
+            "result": result
+        })))
     }
 
-    #[tool(description = "Create customer service interaction - track detailed customer-service interactions with feedback (bookings, completions, reviews, cancellations). Use query_business_memory to get service_id.")]
-    async fn create_customer_service_interaction(&self, params: Parameters<CreateCustomerServiceInteractionParam>) -> Result<CallToolResult, McpError> {
-        let customer_id = &params.0.customer_id;
-        let service_id = &params.0.service_id;
-        let interaction_type = &params.0.interaction_type;
-        let text_feedback = &params.0.text_feedback;
-        
-        // Always auto-generate interaction_id
-        let interaction_id = format!("INT_{}", Uuid::new_v4().to_string());
+    #[tool(description = "Update navigation memory (hub, waypoint, path). REQUIRED: memory_id (internal UUID from database node), navigation_type, composite_text for embedding regeneration. Get internal ID using query_navigation.")]
+    async fn update_navigation(&self, params: Parameters<UpdateNavigationParam>) -> Result<CallToolResult, McpError> {
+        let memory_id = &params.0.memory_id;
+        let navigation_type = &params.0.navigation_type;
+        let composite_text = &params.0.composite_text;
         
-        info!("create_customer_service_interaction: customer_id={}, service_id={}, interaction_id={}, type={}", customer_id, service_id, interaction_id, interaction_type);
+        info!("update_navigation: memory_id={}, type={}", memory_id, navigation_type);
 
-        // Build the data payload with all fields
-        let timestamp = chrono::Utc::now().timestamp();
-        let mut data = json!({
-            "customer_id": customer_id,
-            "service_id": service_id,
-            "interaction_id": interaction_id,
-            "interaction_type": interaction_type,
-            "satisfaction_rating": params.0.satisfaction_rating.unwrap_or(3),
-            "timestamp": timestamp,
-            "duration_actual": params.0.duration_actual.unwrap_or(0),
-            "cost_actual": params.0.cost_actual.unwrap_or(0.0),
-            "currency": params.0.currency.as_ref().unwrap_or(&String::from("USD")),
-            "outcome": params.0.outcome.as_ref().unwrap_or(&String::from("")),
-            "created_at": timestamp,
-            "updated_at": timestamp,
-            "text_feedback": text_feedback
-        });
+        // Route to appropriate vector-aware update query
+        let query_name = match navigation_type.as_str() {
+            "hub" => "update_business_navigation_hub_memory",
+            "waypoint" => "update_navigation_waypoint_memory",
+            "path" => "update_direction_path_memory",
+            _ => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid navigation_type: {}. Valid: hub, waypoint, path", navigation_type), json!({})));
+            }
+        };
 
-        // Check if embedding needs to be generated (MCP mode)
-        if self.config.is_mcp_embedding_enabled() {
-            info!("Generating embedding for text_feedback...");
-            
+        // Generate embedding based on mode
+        let new_embedding = if self.config.is_mcp_embedding_enabled() {
+            info!("MCP mode: Generating new embedding for navigation {} {}", navigation_type, memory_id);
             let api_key = self.config.get_api_key().unwrap_or_default();
             
-            match self.generate_embedding(text_feedback, &api_key).await {
-                Ok(embedding) => {
-                    info!("✓ Generated {} dimensional embedding", embedding.len());
-
-                    // Add embedding to data (convert f32 to f64 for HelixDB)
-                    data["embedding"] = json!(embedding.iter().map(|&x| x as f64).collect::<Vec<f64>>());
-
-                    let model_name = match self.config.embedding.provider {
-                        Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
-                            self.config.embedding.model.clone().unwrap_or_else(|| "unknown".to_string())
-                        }
-                        Some(config::EmbeddingProvider::Local) => "local".to_string(),
-                        Some(config::EmbeddingProvider::Tcp) => "tcp-local".to_string(),
-                        None => "unknown".to_string()
-                    };
-                    data["embedding_model"] = json!(model_name);
-                }
+            match self.generate_embedding(composite_text, &api_key).await {
+                Ok(emb) => emb,
                 Err(e) => {
-                    error!("✗ Failed to generate embedding: {}", e);
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to generate embedding: {}", e),
-                        "suggestion": "Check embedding configuration and API connectivity"
-                    })));
+                    return Ok(tool_error(ErrorCode::EmbeddingUnavailable, format!("Failed to generate embedding: {}", e), json!({})));
                 }
             }
         } else {
-            info!("Using HelixDB embedding mode - HelixDB will generate embedding");
-        }
+            info!("HelixDB mode: Using empty embedding placeholder for navigation {} {}", navigation_type, memory_id);
+            vec![]
+        };
 
-        // Execute the query
-        match self.helix_client.query("add_customer_service_interaction", data).await {
+        // Build payload for vector-aware update query
+        let timestamp = self.clock.timestamp();
+        let payload = json!({
+            "memory_id": memory_id,
+            "composite_text": composite_text,
+            "new_embedding": new_embedding,
+            "timestamp": timestamp
+        });
+
+        // Execute vector-aware update query
+        match self.helix_client.query(query_name, payload).await {
             Ok(result) => {
                 Ok(CallToolResult::structured(json!({
                     "success": true,
-                    "interaction_type": "service",
-                    "customer_id": customer_id,
-                    "service_id": service_id,
+                    "navigation_type": navigation_type,
+                    "memory_id": memory_id,
+                    "query_used": query_name,
                     "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
+                    "updated_at": timestamp,
                     "result": result
                 })))
             }
             Err(e) => {
-                error!("create_customer_service_interaction failed: {}", e);
-                Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Failed to create service interaction: {}", e)
+                error!("update_navigation failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to update navigation {}: {}", navigation_type, e), json!({
+                    "query_used": query_name
                 })))
             }
         }
     }
 
-    #[tool(description = "Query customer interactions - get all product and/or service interactions for a customer")]
-    async fn query_customer_interactions(&self, params: Parameters<QueryCustomerInteractionsParam>) -> Result<CallToolResult, McpError> {
-        let customer_id = &params.0.customer_id;
-        let interaction_type_input = &params.0.interaction_type;
+    // ========================================================================
+    // DELETE TOOLS - Remove memories
+    // ========================================================================
+
+    #[tool(description = "Delete memory (products, services, locations, hours, social, policy, event, information such as documentation/manuals, behaviors, preferences, desires, rules, feedback, interaction, navigation_hub, waypoint, path, business, customer). Deleting a navigation_hub does not delete its waypoints and paths. Run preview_delete before the cascade or complete strategies. REQUIRED: memory_id (internal UUID from database node), memory_type. Get internal ID using appropriate query tool (query_business_memory, query_customer_memory, etc.).")]
+    async fn delete_memory(&self, params: Parameters<DeleteMemoryParam>) -> Result<CallToolResult, McpError> {
+        let memory_id = &params.0.memory_id;
+        let memory_type_input = &params.0.memory_type;
+        let delete_strategy = params.0.delete_strategy.as_deref()
+            .unwrap_or_else(|| {
+                // Backward compatibility: if delete_embedding is specified, use it
+                if params.0.delete_embedding.unwrap_or(true) {
+                    "with_embedding"
+                } else {
+                    "node_only"
+                }
+            });
         
-        // Normalize to singular (accept both "products" and "product")
-        let interaction_type = Self::normalize_memory_type(interaction_type_input);
+        // Normalize memory_type (accept both "products" and "product", "hub" and "navigation_hub")
+        let (memory_type, id_field) = memory_types::delete_target(memory_type_input);
         
-        info!("query_customer_interactions: customer_id={}, type={} (normalized from: {})", customer_id, interaction_type, interaction_type_input);
+        info!("delete_memory: memory_id={}, type={} (normalized from: {}), strategy={}", memory_id, memory_type, memory_type_input, delete_strategy);
 
-        let mut all_interactions = json!({});
+        // Handle cascade and complete deletion strategies
+        match delete_strategy {
+            "cascade" => {
+                // Delete all memories for a business or customer
+                let query_name = match memory_type {
+                    "business" => "delete_all_business_memories",
+                    "customer" => "delete_all_customer_memories",
+                    _ => {
+                        return Ok(tool_error(ErrorCode::ValidationFailed, "Cascade delete strategy only valid for memory_type: business, customer", json!({})));
+                    }
+                };
 
-        match interaction_type {
-            "product" => {
-                // Get product interactions only
-                match self.helix_client.query(
-                    "get_customer_product_interactions",
-                    json!({"customer_id": customer_id})
-                ).await {
-                    Ok(interactions) => {
-                        all_interactions["product_interactions"] = interactions;
+                let payload = json!({
+                    format!("{}_id", memory_type): memory_id
+                });
+
+                match self.helix_client.query(query_name, payload).await {
+                    Ok(result) => {
+                        return Ok(CallToolResult::structured(json!({
+                            "success": true,
+                            "memory_type": memory_type,
+                            "memory_id": memory_id,
+                            "strategy": "cascade",
+                            "message": format!("Deleted all memories for {}", memory_type),
+                            "result": result
+                        })));
                     }
                     Err(e) => {
-                        error!("Failed to get product interactions: {}", e);
-                        return Ok(CallToolResult::structured_error(json!({
-                            "error": format!("Failed to get product interactions: {}", e)
-                        })));
+                        error!("Cascade delete failed: {}", e);
+                        return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to cascade delete: {}", e), json!({})));
                     }
                 }
             }
-            "service" => {
-                // Get service interactions only
-                match self.helix_client.query(
-                    "get_customer_service_interactions",
-                    json!({"customer_id": customer_id})
-                ).await {
-                    Ok(interactions) => {
-                        all_interactions["service_interactions"] = interactions;
+            "complete" => {
+                // Delete entity and all associated memories
+                let query_name = match memory_type {
+                    "business" => "delete_business_complete",
+                    "customer" => "delete_customer_complete",
+                    _ => {
+                        return Ok(tool_error(ErrorCode::ValidationFailed, "Complete delete strategy only valid for memory_type: business, customer", json!({})));
                     }
-                    Err(e) => {
-                        error!("Failed to get service interactions: {}", e);
-                        return Ok(CallToolResult::structured_error(json!({
-                            "error": format!("Failed to get service interactions: {}", e)
+                };
+
+                let payload = json!({
+                    format!("{}_id", memory_type): memory_id
+                });
+
+                match self.helix_client.query(query_name, payload).await {
+                    Ok(result) => {
+                        return Ok(CallToolResult::structured(json!({
+                            "success": true,
+                            "memory_type": memory_type,
+                            "memory_id": memory_id,
+                            "strategy": "complete",
+                            "message": format!("Completely deleted {} and all associated memories", memory_type),
+                            "result": result
                         })));
                     }
+                    Err(e) => {
+                        error!("Complete delete failed: {}", e);
+                        return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to complete delete: {}", e), json!({})));
+                    }
                 }
             }
-            "all" => {
-                // Get both product and service interactions
-                if let Ok(product_interactions) = self.helix_client.query(
-                    "get_customer_product_interactions",
-                    json!({"customer_id": customer_id})
-                ).await {
-                    all_interactions["product_interactions"] = product_interactions;
-                }
-                
-                if let Ok(service_interactions) = self.helix_client.query(
-                    "get_customer_service_interactions",
-                    json!({"customer_id": customer_id})
-                ).await {
-                    all_interactions["service_interactions"] = service_interactions;
-                }
+            _ => {}  // Fall through to normal delete
+        }
+
+        // Normal delete (node_only or with_embedding)
+        let with_embedding = delete_strategy == "with_embedding";
+        
+        // Determine which delete query to use
+        let Some(query_name) = memory_types::delete_query(memory_type, with_embedding) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}. Valid types: product, service, location, hours, social, policy, event, information, behavior, preference, desire, rule, feedback, interaction, navigation_hub, waypoint, path, business, customer", memory_type), json!({})));
+        };
+
+        // Create payload with memory_id
+        let payload = json!({
+            id_field: memory_id
+        });
+
+        // Execute the query
+        match self.helix_client.query(query_name, payload).await {
+            Ok(result) => {
+                Ok(CallToolResult::structured(json!({
+                    "success": true,
+                    "memory_type": memory_type,
+                    "memory_id": memory_id,
+                    "strategy": delete_strategy,
+                    "deleted_embedding": with_embedding,
+                    "result": result
+                })))
             }
-            _ => {
-                return Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Invalid interaction_type: {}. Valid types: product, service, all", interaction_type)
-                })));
+            Err(e) => {
+                error!("delete_memory failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to delete {} memory: {}", memory_type, e), json!({})))
             }
         }
+    }
 
-        // Apply filters if provided
-        if let Some(filters) = &params.0.filters {
-            all_interactions = self.apply_filters(all_interactions, filters);
-        }
+    #[tool(description = "Preview a cascade or complete delete - report exactly which memory nodes (with IDs), edges and embedding vectors delete_memory would remove for a business or customer, without deleting anything. Also lists related nodes that survive (interactions, information, navigation). The cascade deletes leave embedding vectors behind, so they are reported as orphaned. REQUIRED: memory_id (business_id or customer_id), memory_type (business or customer). Optional: delete_strategy (cascade or complete, default complete).")]
+    async fn preview_delete(&self, params: Parameters<PreviewDeleteParam>) -> Result<CallToolResult, McpError> {
+        let memory_id = &params.0.memory_id;
+        let memory_type = memory_types::normalize(&params.0.memory_type);
+        let strategy = params.0.delete_strategy.as_deref().unwrap_or("complete");
 
-        // Count results
-        let product_count = all_interactions.get("product_interactions")
-            .and_then(|v| v.as_array())
-            .map(|a| a.len())
-            .unwrap_or(0);
-        let service_count = all_interactions.get("service_interactions")
-            .and_then(|v| v.as_array())
-            .map(|a| a.len())
-            .unwrap_or(0);
+        info!("preview_delete: memory_id={}, type={}, strategy={}", memory_id, memory_type, strategy);
 
-        Ok(CallToolResult::structured(json!({
-            "customer_id": customer_id,
-            "interaction_type": interaction_type,
-            "product_count": product_count,
-            "service_count": service_count,
-            "total_count": product_count + service_count,
-            "data": all_interactions
-        })))
-    }
+        if strategy != "cascade" && strategy != "complete" {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("preview_delete covers the cascade and complete strategies; {} only removes the one node (and its embedding for with_embedding)", strategy), json!({})));
+        }
+        let Some((query_name, id_field)) = delete_preview::query_for(memory_type) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Cascade and complete deletes only apply to memory_type business or customer, not {}", memory_type), json!({})));
+        };
 
-    #[tool(description = "Search customer interactions semantically - find product and service interactions by meaning using AI embeddings")]
-    async fn search_customer_interactions(&self, params: Parameters<SearchCustomerInteractionsParam>) -> Result<CallToolResult, McpError> {
-        let query = &params.0.query;
-        let interaction_types_input = &params.0.interaction_types;
-        let limit = params.0.limit.unwrap_or(10);
-        
-        // Normalize all interaction types to singular (accept both "products" and "product")
-        let interaction_types: Vec<&str> = interaction_types_input
-            .iter()
-            .map(|t| Self::normalize_memory_type(t.as_str()))
-            .collect();
-        
-        info!("search_customer_interactions: query='{}', types={:?} (normalized from: {:?}), limit={}", query, interaction_types, interaction_types_input, limit);
+        match self.helix_client.query(query_name, json!({ id_field: memory_id })).await {
+            Ok(result) => {
+                let mut preview = delete_preview::report(memory_type, strategy, &result).unwrap_or_default();
+                preview["dry_run"] = json!(true);
+                preview["memory_type"] = json!(memory_type);
+                preview["memory_id"] = json!(memory_id);
+                preview["strategy"] = json!(strategy);
+                preview["next_step"] = json!(format!(
+                    "Call delete_memory with memory_id: {}, memory_type: {}, delete_strategy: {} to remove these",
+                    memory_id, memory_type, strategy
+                ));
+                Ok(CallToolResult::structured(preview))
+            }
+            Err(e) => {
+                error!("preview_delete failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to preview delete: {}", e), json!({})))
+            }
+        }
+    }
 
-        // Check embedding mode
-        if self.config.is_helixdb_embedding_enabled() {
-            info!("Using HelixDB embedding mode (Embed() function in queries)");
-            
-            let mut all_results = Vec::new();
-            
-            // Search across requested interaction types
-            for interaction_type in &interaction_types {
-                let query_name = match *interaction_type {
-                    "product" => "search_customer_product_interactions",
-                    "service" => "search_customer_service_interactions",
-                    _ => {
-                        info!("Skipping unsupported interaction type: {}", interaction_type);
-                        continue;
-                    }
-                };
+    #[tool(description = "Delete memories by filter - delete every memory of one type for a business or customer that matches filters (exact values, {\"gte\"/\"lte\"} ranges, since/until on created_at/timestamp), e.g. {\"availability\": \"discontinued\"} or {\"created_at\": {\"lte\": 1700000000}}. Always preview first: dry_run (default true) returns the matching count and IDs; to delete, call again with dry_run: false and expected_count set to the previewed count. REQUIRED: memory_type, owner_id (business_id or customer_id), filters (non-empty). Optional: dry_run, expected_count, delete_embedding (default true).")]
+    async fn delete_memories_by_filter(&self, params: Parameters<DeleteMemoriesByFilterParam>) -> Result<CallToolResult, McpError> {
+        let memory_type = memory_types::normalize(&params.0.memory_type);
+        let owner_id = &params.0.owner_id;
+        let filters = &params.0.filters;
+        let dry_run = params.0.dry_run.unwrap_or(true);
+        let with_embedding = params.0.delete_embedding.unwrap_or(true);
 
-                let mut payload = json!({
-                    "query_embedding": query,
-                    "limit": limit,
-                });
+        info!("delete_memories_by_filter: type={}, owner_id={}, filters={}, dry_run={}", memory_type, owner_id, filters, dry_run);
 
-                if let Some(customer_id) = &params.0.customer_id {
-                    payload["customer_id"] = json!(customer_id);
-                }
+        let Some((id_field, owner_field, _)) = memory_types::entity(memory_type) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}. Valid types: product, service, location, hours, social, policy, event, information, behavior, preference, desire, rule, feedback", memory_type), json!({})));
+        };
+        if filters.as_object().is_none_or(|f| f.is_empty()) {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "filters must be a non-empty object; use delete_memory(strategy: cascade) to delete everything", json!({})));
+        }
 
-                match self.helix_client.query(query_name, payload).await {
-                    Ok(results) => {
-                        if let Some(array) = results.as_array() {
-                            all_results.extend(array.iter().cloned());
-                        }
-                    }
-                    Err(e) => {
-                        error!("Interaction search failed for {}: {}", interaction_type, e);
-                    }
-                }
+        let list_query = Self::memory_list_query(memory_type, owner_field);
+        let memories = match self.helix_client.query(&list_query, json!({ owner_field: owner_id })).await {
+            Ok(result) => HelixClient::result_items(&result),
+            Err(e) => {
+                error!("delete_memories_by_filter lookup failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to list {} memories: {}", memory_type, e), json!({})));
             }
+        };
+        let matched = bulk_delete::matching_ids(&memories, id_field, filters);
 
+        if dry_run {
             return Ok(CallToolResult::structured(json!({
-                "query": query,
-                "interaction_types": interaction_types,
-                "total_results": all_results.len(),
-                "limit": limit,
-                "embedding_mode": "helixdb",
-                "results": all_results
+                "dry_run": true,
+                "memory_type": memory_type,
+                owner_field: owner_id,
+                "scanned": memories.len(),
+                "matched": matched.len(),
+                "ids": matched,
+                "next_step": format!("Call again with dry_run: false and expected_count: {} to delete these memories", matched.len())
             })));
         }
 
-        // MCP mode: Generate embedding
-        if self.config.is_mcp_embedding_enabled() {
-            info!("Using MCP embedding mode");
-            
-            let api_key = self.config.get_api_key().unwrap_or_default();
-            
-            let query_embedding = match self.generate_embedding(query, &api_key).await {
-                Ok(embedding) => {
-                    info!("✓ Generated embedding vector with {} dimensions", embedding.len());
-                    embedding
+        // The preview count guards against deleting a different set than the one reviewed
+        if let Err((code, message)) = bulk_delete::confirm(params.0.expected_count, matched.len()) {
+            return Ok(tool_error(code, message, json!({
+                "matched": matched.len(),
+                "ids": matched
+            })));
+        }
+
+        let Some(query_name) = memory_types::delete_query(memory_type, with_embedding) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("No delete query for memory_type: {}", memory_type), json!({})));
+        };
+        let mut deleted = Vec::new();
+        let mut failures = Vec::new();
+        for entity_id in matched {
+            match priority::background(self.helix_client.query(query_name, json!({ id_field: entity_id }))).await {
+                Ok(_) => deleted.push(entity_id),
+                Err(e) => {
+                    warn!("delete_memories_by_filter: failed to delete {}: {}", entity_id, e);
+                    failures.push(json!({ id_field: entity_id, "error": e.to_string() }));
                 }
+            }
+        }
+
+        info!("✓ Deleted {} {} memories by filter ({} failed)", deleted.len(), memory_type, failures.len());
+
+        Ok(CallToolResult::structured(json!({
+            "success": failures.is_empty(),
+            "dry_run": false,
+            "memory_type": memory_type,
+            owner_field: owner_id,
+            "deleted": deleted.len(),
+            "deleted_ids": deleted,
+            "deleted_embedding": with_embedding,
+            "failures": failures
+        })))
+    }
+
+    #[tool(description = "Merge duplicate memories - consolidate two or more memories of the same type into one. The first ID in memory_ids survives: its text is replaced with the combined composite_text (caller-provided or merged from all descriptions) and re-embedded, references (interactions, visits, information links) are rewired to it, and the other memories are deleted. REQUIRED: memory_ids (entity IDs such as product_id or preference_id, at least 2), memory_type.")]
+    async fn merge_memories(&self, params: Parameters<MergeMemoriesParam>) -> Result<CallToolResult, McpError> {
+        let memory_ids = &params.0.memory_ids;
+        let memory_type = memory_types::normalize(&params.0.memory_type);
+
+        info!("merge_memories: type={}, ids={:?}", memory_type, memory_ids);
+
+        let (survivor_id, duplicate_ids) = match memory_merge::split_survivor(memory_ids) {
+            Ok(split) => split,
+            Err(e) => return Ok(tool_error(ErrorCode::ValidationFailed, e, json!({}))),
+        };
+
+        let Some((id_field, owner_field, lookup_query)) = memory_types::entity(memory_type) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}. Valid types: product, service, location, hours, social, policy, event, information, behavior, preference, desire, rule, feedback", memory_type), json!({})));
+        };
+
+        // Load every memory so we can verify ownership and combine their text
+        let mut memories = Vec::new();
+        for memory_id in memory_ids {
+            let memory = match self.helix_client.query(lookup_query, json!({ id_field: memory_id })).await {
+                Ok(result) => HelixClient::result_items(&result).into_iter().next(),
                 Err(e) => {
-                    error!("✗ Failed to generate embedding: {}", e);
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Embedding generation failed: {}", e),
-                        "suggestion": "Check API key, network connection, and server status"
-                    })));
+                    error!("merge_memories lookup failed for {}: {}", memory_id, e);
+                    return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to load {} {}: {}", memory_type, memory_id, e), json!({})));
                 }
             };
+            let Some(memory) = memory else {
+                return Ok(tool_error(ErrorCode::NotFound, format!("{} not found: {}", id_field, memory_id), json!({})));
+            };
+            memories.push(memory);
+        }
 
-            let mut all_results = Vec::new();
-            
-            for interaction_type in &interaction_types {
-                let query_name = match *interaction_type {
-                    "product" => "search_customer_product_interactions",
-                    "service" => "search_customer_service_interactions",
-                    _ => {
-                        info!("Skipping unsupported interaction type: {}", interaction_type);
-                        continue;
-                    }
-                };
+        let survivor = &memories[0];
+        let owner_id = survivor.get(owner_field).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        if memories.iter().any(|m| m.get(owner_field).and_then(|v| v.as_str()) != Some(owner_id.as_str())) {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("All memories must belong to the same {}", owner_field), json!({})));
+        }
 
-                let mut payload = json!({
-                    "query_embedding": query_embedding,
-                    "limit": limit,
-                });
+        // Combined text: caller-provided, or the distinct descriptions of all merged memories
+        let composite_text = params.0.composite_text.clone().unwrap_or_else(|| memory_merge::composite_text(&memories));
+        if composite_text.is_empty() {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "No text to merge - provide composite_text", json!({})));
+        }
 
-                if let Some(customer_id) = &params.0.customer_id {
-                    payload["customer_id"] = json!(customer_id);
-                }
+        // Re-embed the survivor with the combined text
+        let internal_id = survivor.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let update_result = if owner_field == "business_id" {
+            priority::background(self.update_business_memory(Parameters(UpdateBusinessMemoryParam {
+                memory_id: internal_id,
+                memory_type: memory_type.to_string(),
+                updates: json!({
+                    "business_id": owner_id,
+                    id_field: survivor_id,
+                    "composite_text": composite_text
+                }),
+            }))).await?
+        } else {
+            priority::background(self.update_customer_memory(Parameters(UpdateCustomerMemoryParam {
+                memory_id: internal_id,
+                memory_type: memory_type.to_string(),
+                updates: json!({ "composite_text": composite_text }),
+            }))).await?
+        };
+        if update_result.is_error == Some(true) {
+            return Ok(tool_error(ErrorCode::Internal, "Failed to update surviving memory - nothing was deleted", json!({
+                "details": update_result.structured_content
+            })));
+        }
 
-                match self.helix_client.query(query_name, payload).await {
-                    Ok(results) => {
-                        if let Some(array) = results.as_array() {
-                            all_results.extend(array.iter().cloned());
-                        }
-                    }
-                    Err(e) => {
-                        error!("Interaction search failed for {}: {}", interaction_type, e);
-                    }
+        // Rewire references from each duplicate to the survivor, then delete it
+        let rewire_query = memory_merge::rewire_query(memory_type);
+        let timestamp = self.clock.timestamp();
+
+        let (merged, failures) = memory_merge::fold_duplicates(
+            duplicate_ids,
+            |duplicate_id| async move {
+                let Some(rewire_query) = rewire_query else {
+                    return Ok(());
+                };
+                let payload = memory_merge::rewire_payload(id_field, &duplicate_id, survivor_id, timestamp);
+                priority::background(self.helix_client.query(rewire_query, payload)).await.map(|_| ()).map_err(|e| {
+                    error!("merge_memories rewire failed for {}: {}", duplicate_id, e);
+                    e.to_string()
+                })
+            },
+            |duplicate_id| async move {
+                let delete_result = priority::background(self.delete_memory(Parameters(DeleteMemoryParam {
+                    memory_id: duplicate_id,
+                    memory_type: memory_type.to_string(),
+                    delete_embedding: Some(true),
+                    delete_strategy: None,
+                }))).await;
+                match delete_result {
+                    Ok(result) if result.is_error == Some(true) => Err(result.structured_content.unwrap_or_default()),
+                    Ok(_) => Ok(()),
+                    Err(e) => Err(json!(e.to_string())),
                 }
-            }
+            },
+        )
+        .await;
 
-            return Ok(CallToolResult::structured(json!({
-                "query": query,
-                "interaction_types": interaction_types,
-                "total_results": all_results.len(),
-                "limit": limit,
-                "embedding_mode": "mcp",
-                "results": all_results
-            })));
+        info!("✓ Merged {} {} memories into {}", merged.len(), memory_type, survivor_id);
+
+        Ok(CallToolResult::structured(json!({
+            "success": failures.is_empty(),
+            "memory_type": memory_type,
+            "survivor_id": survivor_id,
+            owner_field: owner_id,
+            "merged_ids": merged,
+            "failures": failures,
+            "composite_text": composite_text,
+            "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" }
+        })))
+    }
+
+    #[tool(description = "Merge customers - fold a duplicate customer profile (e.g. the same person on WhatsApp and the web) into a canonical one: all memories, interactions, visits, communications, loyalty points and business links move to customer_id, contact fields the canonical profile lacks are copied over, and the duplicate ID is kept as an alias. Preferences both have about the same subject are reconciled: the most recently observed stays active, the other is deactivated. Pass dry_run: true to see the plan. REQUIRED: customer_id (canonical), duplicate_customer_id. Optional: reason.")]
+    async fn merge_customers(&self, params: Parameters<MergeCustomersParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        info!("merge_customers: {} into {}", p.duplicate_customer_id, p.customer_id);
+        if p.customer_id == p.duplicate_customer_id {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "customer_id and duplicate_customer_id must differ", json!({})));
+        }
+
+        let mut profiles = Vec::new();
+        for customer_id in [&p.customer_id, &p.duplicate_customer_id] {
+            let profile = match self.helix_client.query("get_customer_profile", json!({"customer_id": customer_id})).await {
+                Ok(result) => HelixClient::result_items(&json!({"customer": result.get("customer").cloned().unwrap_or_default()})).into_iter().next(),
+                Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to load customer {}: {}", customer_id, e), json!({}))),
+            };
+            let Some(profile) = profile else {
+                // A customer merged earlier only exists as an alias
+                let alias = self.helix_client.query("get_customer_alias", json!({"alias_customer_id": customer_id})).await
+                    .ok()
+                    .and_then(|result| HelixClient::result_items(&result).into_iter().next());
+                return Ok(tool_error(ErrorCode::NotFound, format!("Customer {} not found", customer_id), json!({
+                    "customer_id": customer_id,
+                    "merged_into": alias.and_then(|a| a.get("customer_id").cloned())
+                })));
+            };
+            profiles.push(profile);
+        }
+
+        let (canonical_preferences, duplicate_preferences) = tokio::join!(
+            self.helix_client.query("get_customer_preferences", json!({"customer_id": p.customer_id})),
+            self.helix_client.query("get_customer_preferences", json!({"customer_id": p.duplicate_customer_id}))
+        );
+        let (canonical_preferences, duplicate_preferences) = match (canonical_preferences, duplicate_preferences) {
+            (Ok(ours), Ok(theirs)) => (HelixClient::result_items(&ours), HelixClient::result_items(&theirs)),
+            (Err(e), _) | (_, Err(e)) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to load preferences: {}", e), json!({}))),
+        };
+        let resolutions = customer_merge::resolve_preferences(&canonical_preferences, &duplicate_preferences);
+        let contact = customer_merge::missing_contact(&profiles[0], &profiles[1]);
+
+        // Profile gaps and preference conflicts first; the move itself is one query
+        let timestamp = self.clock.timestamp();
+        let field = |name: &str| {
+            contact.iter().find(|(f, _)| *f == name).map(|(_, v)| v.clone())
+                .unwrap_or_else(|| profiles[0].get(name).and_then(|v| v.as_str()).unwrap_or("").to_string())
+        };
+        let mut writes = Vec::new();
+        if contact.iter().any(|(f, _)| *f != "language") {
+            writes.push(("update_customer_contact", json!({"customer_id": p.customer_id, "phone": field("phone"), "email": field("email")})));
         }
+        if contact.iter().any(|(f, _)| *f == "language") {
+            writes.push(("update_customer_language", json!({"customer_id": p.customer_id, "language": field("language")})));
+        }
+        for resolution in &resolutions {
+            writes.push(("deactivate_preference", json!({"preference_id": resolution.deactivated, "updated_at": timestamp})));
+        }
+        for (query_name, payload) in writes {
+            if let Err(e) = self.helix_client.query(query_name, payload).await {
+                error!("merge_customers {} failed: {}", query_name, e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to prepare merge ({}): {}; nothing was moved", query_name, e), json!({})));
+            }
+        }
+
+        let payload = json!({
+            "from_customer_id": p.duplicate_customer_id,
+            "to_customer_id": p.customer_id,
+            "reason": p.reason.clone().unwrap_or_default(),
+            "timestamp": timestamp
+        });
+        let result = match self.helix_client.query("merge_customers", payload).await {
+            Ok(result) => result,
+            Err(e) => {
+                error!("merge_customers failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to merge customers: {}", e), json!({})));
+            }
+        };
+        let moved: serde_json::Map<String, serde_json::Value> = ["behaviors", "preferences", "desires", "rules", "feedback", "product_interactions", "service_interactions", "location_visits", "communications", "points"]
+            .iter()
+            .map(|key| (key.to_string(), json!(HelixClient::result_items(&json!({ *key: result.get(*key).cloned().unwrap_or_default() })).len())))
+            .collect();
+        info!("✓ Merged customer {} into {}", p.duplicate_customer_id, p.customer_id);
 
-        Ok(CallToolResult::structured_error(json!({
-            "error": "Invalid embedding configuration. Check mcpconfig.toml"
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "customer_id": p.customer_id,
+            "alias_customer_id": p.duplicate_customer_id,
+            "moved": moved,
+            "contact_fields_copied": contact.iter().map(|(f, _)| *f).collect::<Vec<_>>(),
+            "preference_resolutions": resolutions.iter().map(customer_merge::Resolution::to_json).collect::<Vec<_>>()
         })))
     }
 
     // ========================================================================
-    // NAVIGATION SYSTEM TOOLS - Physical location navigation with compass data
+    // MAINTENANCE TOOLS - Keep memory relevance healthy over time
     // ========================================================================
 
-    #[tool(description = "Create navigation hub - set up comprehensive navigation center for a business with address, coordinates, compass bearings, and accessibility information")]
-    async fn create_navigation_hub(&self, params: Parameters<CreateNavigationHubParam>) -> Result<CallToolResult, McpError> {
-        let business_id = &params.0.business_id;
-        let navigation_id = &params.0.navigation_id;
-        let navigation_summary = &params.0.navigation_summary;
-        
-        info!("create_navigation_hub: business_id={}, navigation_id={}", business_id, navigation_id);
+    #[tool(description = "Apply confidence decay - reduce confidence_score of preferences and relevance_score of behaviors based on time since last evidence (exponential half-life), deactivating memories that fall below min_score. Optional: customer_id (default all customers), half_life_days, min_score, dry_run to preview changes.")]
+    async fn apply_decay(&self, params: Parameters<ApplyDecayParam>) -> Result<CallToolResult, McpError> {
+        let policy = decay::DecayPolicy {
+            half_life_days: params.0.half_life_days.unwrap_or(self.config.decay.half_life_days),
+            min_score: params.0.min_score.unwrap_or(self.config.decay.min_score),
+            now: self.clock.timestamp(),
+        };
+        let dry_run = params.0.dry_run.unwrap_or(false);
 
-        // Build the data payload with all fields
-        let timestamp = chrono::Utc::now().timestamp();
-        let mut data = json!({
-            "business_id": business_id,
-            "navigation_id": navigation_id,
-            "primary_address": params.0.primary_address,
-            "secondary_address": params.0.secondary_address.as_ref().unwrap_or(&String::from("")),
-            "building_name": params.0.building_name.as_ref().unwrap_or(&String::from("")),
-            "building_type": params.0.building_type.as_ref().unwrap_or(&String::from("")),
-            "latitude": params.0.latitude,
-            "longitude": params.0.longitude,
-            "what3words_code": params.0.what3words_code.as_ref().unwrap_or(&String::from("")),
-            "plus_code": params.0.plus_code.as_ref().unwrap_or(&String::from("")),
-            "compass_bearing": params.0.compass_bearing.unwrap_or(0.0),
-            "compass_reference": params.0.compass_reference.as_ref().unwrap_or(&String::from("")),
-            "magnetic_declination": params.0.magnetic_declination.unwrap_or(0.0),
-            "building_description": params.0.building_description.as_ref().unwrap_or(&String::from("")),
-            "building_floors": params.0.building_floors.unwrap_or(1),
-            "business_floor": params.0.business_floor.unwrap_or(1),
-            "building_color": params.0.building_color.as_ref().unwrap_or(&String::from("")),
-            "building_size": params.0.building_size.as_ref().unwrap_or(&String::from("")),
-            "main_entrance_description": params.0.main_entrance_description.as_ref().unwrap_or(&String::from("")),
-            "alternative_entrances": params.0.alternative_entrances.as_ref().unwrap_or(&String::from("")),
-            "entrance_restrictions": params.0.entrance_restrictions.as_ref().unwrap_or(&String::from("")),
-            "wheelchair_accessible": params.0.wheelchair_accessible.unwrap_or(false),
-            "elevator_available": params.0.elevator_available.unwrap_or(false),
-            "stairs_required": params.0.stairs_required.unwrap_or(false),
-            "accessibility_notes": params.0.accessibility_notes.as_ref().unwrap_or(&String::from("")),
-            "parking_available": params.0.parking_available.unwrap_or(false),
-            "parking_description": params.0.parking_description.as_ref().unwrap_or(&String::from("")),
-            "public_transport_notes": params.0.public_transport_notes.as_ref().unwrap_or(&String::from("")),
-            "direction_varies_by_hours": params.0.direction_varies_by_hours.unwrap_or(false),
-            "after_hours_instructions": params.0.after_hours_instructions.as_ref().unwrap_or(&String::from("")),
-            "created_at": timestamp,
-            "updated_at": timestamp,
-            "last_verified_at": timestamp,
-            "verification_source": "user",
-            "navigation_summary": navigation_summary
-        });
+        info!("apply_decay: customer_id={:?}, half_life_days={}, min_score={}, dry_run={}", params.0.customer_id, policy.half_life_days, policy.min_score, dry_run);
 
-        // Check if embedding needs to be generated (MCP mode)
-        if self.config.is_mcp_embedding_enabled() {
-            info!("Generating embedding for navigation_summary...");
-            
-            let api_key = self.config.get_api_key().unwrap_or_default();
-            
-            match self.generate_embedding(navigation_summary, &api_key).await {
-                Ok(embedding) => {
-                    info!("✓ Generated {} dimensional embedding", embedding.len());
+        let summary = self.run_decay(params.0.customer_id.as_deref(), &policy, dry_run).await;
+        Ok(CallToolResult::structured(summary))
+    }
 
-                    // Add embedding to data (convert f32 to f64 for HelixDB)
-                    data["embedding"] = json!(embedding.iter().map(|&x| x as f64).collect::<Vec<f64>>());
+    /// Decay preferences and behaviors (shared by the apply_decay tool and the background task)
+    async fn run_decay(&self, customer_id: Option<&str>, policy: &decay::DecayPolicy, dry_run: bool) -> serde_json::Value {
+        // (memory kind, list query for one customer, list query for all, id field, score field, evidence field, update query)
+        let kinds = [
+            ("preference", "get_customer_preferences", "get_all_customer_preferences", "preference_id", "confidence_score", "last_evidence", "apply_preference_decay"),
+            ("behavior", "get_customer_behaviors", "get_all_customer_behaviors", "behavior_id", "relevance_score", "timestamp", "apply_behavior_decay"),
+        ];
 
-                    let model_name = match self.config.embedding.provider {
-                        Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
-                            self.config.embedding.model.clone().unwrap_or_else(|| "unknown".to_string())
-                        }
-                        Some(config::EmbeddingProvider::Local) => "local".to_string(),
-                        Some(config::EmbeddingProvider::Tcp) => "tcp-local".to_string(),
-                        None => "unknown".to_string()
-                    };
-                    data["embedding_model"] = json!(model_name);
-                }
+        let mut report = serde_json::Map::new();
+        for (kind, customer_query, all_query, id_field, score_field, evidence_field, update_query) in kinds {
+            let result = match customer_id {
+                Some(customer_id) => self.helix_client.query(customer_query, json!({"customer_id": customer_id})).await,
+                None => self.helix_client.query(all_query, json!({})).await,
+            };
+            let memories = match result {
+                Ok(result) => HelixClient::result_items(&result),
                 Err(e) => {
-                    error!("✗ Failed to generate embedding: {}", e);
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to generate embedding: {}", e),
-                        "suggestion": "Check embedding configuration and API connectivity"
-                    })));
+                    error!("Decay lookup failed for {}: {}", kind, e);
+                    report.insert(kind.to_string(), json!({"error": e.to_string()}));
+                    continue;
+                }
+            };
+
+            let mut decayed = 0;
+            let mut deactivated = Vec::new();
+            let mut failed = 0;
+            for memory in &memories {
+                let Some(update) = decay::plan_decay(memory, score_field, evidence_field, policy) else {
+                    continue;
+                };
+                let Some(memory_id) = memory.get(id_field).and_then(|v| v.as_str()) else {
+                    continue;
+                };
+
+                if !dry_run {
+                    let payload = json!({
+                        id_field: memory_id,
+                        score_field: update.score,
+                        "is_active": update.is_active,
+                        "decayed_at": policy.now
+                    });
+                    if let Err(e) = self.helix_client.query(update_query, payload).await {
+                        error!("Decay update failed for {} {}: {}", kind, memory_id, e);
+                        failed += 1;
+                        continue;
+                    }
+                }
+
+                decayed += 1;
+                if !update.is_active {
+                    deactivated.push(json!({
+                        id_field: memory_id,
+                        "previous_score": update.previous_score,
+                        "score": update.score
+                    }));
                 }
             }
-        } else {
-            info!("Using HelixDB embedding mode - HelixDB will generate embedding");
+
+            report.insert(kind.to_string(), json!({
+                "examined": memories.len(),
+                "decayed": decayed,
+                "deactivated": deactivated,
+                "failed": failed
+            }));
         }
 
-        // Execute the query
-        match self.helix_client.query("add_business_navigation_hub", data).await {
-            Ok(result) => {
-                Ok(CallToolResult::structured(json!({
-                    "success": true,
-                    "business_id": business_id,
-                    "navigation_id": navigation_id,
-                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
-                    "result": result
-                })))
-            }
-            Err(e) => {
-                error!("create_navigation_hub failed: {}", e);
-                Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Failed to create navigation hub: {}", e)
-                })))
+        info!("✓ Decay run complete (dry_run={})", dry_run);
+
+        json!({
+            "success": true,
+            "dry_run": dry_run,
+            "customer_id": customer_id,
+            "half_life_days": policy.half_life_days,
+            "min_score": policy.min_score,
+            "results": report
+        })
+    }
+
+    #[tool(description = "Maintenance status - the scheduled maintenance tasks (expire_events, decay, prune_orphan_vectors, verify_embeddings) with their cron schedule, next run and the report of their last run. Schedules are set in [maintenance] of mcpconfig.toml.")]
+    async fn maintenance_status(&self) -> Result<CallToolResult, McpError> {
+        let schedules = match maintenance::schedules(&self.config.maintenance) {
+            Ok(schedules) => schedules,
+            Err(e) => return Ok(tool_error(ErrorCode::Internal, format!("Invalid [maintenance] schedule: {}", e), json!({}))),
+        };
+        let enabled = self.config.maintenance.enabled;
+        let now = self.clock.now();
+        let tasks: Vec<serde_json::Value> = maintenance::TASKS.iter().map(|&task| {
+            let schedule = schedules.iter().find(|(t, _)| *t == task).map(|(_, s)| s);
+            json!({
+                "task": task.name(),
+                "schedule": schedule.map(|s| s.expression.as_str()),
+                "next_run": schedule.filter(|_| enabled).and_then(|s| s.next_after(now)).map(|at| at.to_rfc3339()),
+                "last_run": self.maintenance.last_run(task)
+            })
+        }).collect();
+        Ok(CallToolResult::structured(json!({"enabled": enabled, "tasks": tasks})))
+    }
+
+    /// Run one scheduled maintenance task, logging and recording its report
+    async fn run_maintenance(&self, task: maintenance::Task) {
+        let started_at = self.clock.timestamp();
+        info!("Maintenance: running {}", task.name());
+        let outcome = match task {
+            maintenance::Task::ExpireEvents => self.expire_all_events().await,
+            maintenance::Task::Decay => {
+                let policy = decay::DecayPolicy {
+                    half_life_days: self.config.decay.half_life_days,
+                    min_score: self.config.decay.min_score,
+                    now: self.clock.timestamp(),
+                };
+                Ok(self.run_decay(None, &policy, false).await)
             }
+            maintenance::Task::PruneOrphanVectors => self.repair_orphan_vectors(true).await,
+            maintenance::Task::VerifyEmbeddings => self.verify_embedding_dimensions().await,
+        };
+        match &outcome {
+            Ok(report) => info!("Maintenance: {} finished: {}", task.name(), report),
+            Err(e) => warn!("Maintenance: {} failed: {}", task.name(), e),
         }
+        self.maintenance.record(task, started_at, self.clock.timestamp(), &outcome);
     }
 
-    #[tool(description = "Create navigation waypoint - add landmarks, reference points, and visual cues with compass bearings for navigation. Use query_navigation to get navigation_id.")]
-    async fn create_navigation_waypoint(&self, params: Parameters<CreateNavigationWaypointParam>) -> Result<CallToolResult, McpError> {
-        let navigation_id = &params.0.navigation_id;
-        let description = &params.0.description;
-        
-        // Always auto-generate waypoint_id
-        let waypoint_id = format!("WPT_{}", Uuid::new_v4().to_string());
-        
-        info!("create_navigation_waypoint: waypoint_id={}, navigation_id={}", waypoint_id, navigation_id);
+    /// Close past events of every business
+    async fn expire_all_events(&self) -> Result<serde_json::Value, String> {
+        let result = self.helix_client.query("get_all_businesses", json!({})).await
+            .map_err(|e| format!("Failed to list businesses: {}", e))?;
+        let businesses = HelixClient::result_items(&result);
+        let mut closed = 0;
+        let mut failed = Vec::new();
+        for business_id in businesses.iter().filter_map(|b| b.get("business_id").and_then(|v| v.as_str())) {
+            let params = ClosePastEventsParam { business_id: business_id.to_string(), before: None, dry_run: None };
+            match self.close_past_events(Parameters(params)).await {
+                Ok(result) => {
+                    let content = result.structured_content.unwrap_or_default();
+                    if result.is_error == Some(true) {
+                        failed.push(json!({"business_id": business_id, "error": content.get("error")}));
+                        continue;
+                    }
+                    closed += content.get("closed").and_then(|c| c.as_array()).map_or(0, |c| c.len());
+                    failed.extend(content.get("failed").and_then(|f| f.as_array()).cloned().unwrap_or_default());
+                }
+                Err(e) => failed.push(json!({"business_id": business_id, "error": e.message})),
+            }
+        }
+        Ok(json!({"businesses": businesses.len(), "closed": closed, "failed": failed}))
+    }
 
-        // Build the data payload
-        let timestamp = chrono::Utc::now().timestamp();
-        let mut data = json!({
-            "waypoint_id": waypoint_id,
-            "navigation_id": navigation_id,
-            "waypoint_name": params.0.waypoint_name,
-            "waypoint_type": params.0.waypoint_type,
-            "waypoint_category": params.0.waypoint_category.as_ref().unwrap_or(&String::from("")),
-            "description": description,
-            "visual_cues": params.0.visual_cues.as_ref().unwrap_or(&String::from("")),
-            "audio_cues": params.0.audio_cues.as_ref().unwrap_or(&String::from("")),
-            "relative_position": params.0.relative_position.as_ref().unwrap_or(&String::from("")),
-            "distance_from_main": params.0.distance_from_main.unwrap_or(0),
-            "floor_level": params.0.floor_level.unwrap_or(0),
-            "compass_direction": params.0.compass_direction.as_ref().unwrap_or(&String::from("")),
-            "compass_bearing": params.0.compass_bearing.unwrap_or(0.0),
-            "compass_distance": params.0.compass_distance.unwrap_or(0.0),
-            "business_specific_notes": params.0.business_specific_notes.as_ref().unwrap_or(&String::from("")),
-            "accessibility_info": params.0.accessibility_info.as_ref().unwrap_or(&String::from("")),
-            "seasonal_availability": params.0.seasonal_availability.as_ref().unwrap_or(&String::from("")),
-            "time_restrictions": params.0.time_restrictions.as_ref().unwrap_or(&String::from("")),
-            "weather_dependent": params.0.weather_dependent.unwrap_or(false),
-            "created_at": timestamp,
-            "is_active": true,
-            "priority_level": params.0.priority_level.unwrap_or(1)
-        });
+    /// Compare recorded collection dimensions, and a freshly generated
+    /// embedding in MCP mode, with [embedding] dimensions
+    async fn verify_embedding_dimensions(&self) -> Result<serde_json::Value, String> {
+        let model = self.embedding_model_name();
+        let dimensions = self.config.embedding.vector_dimensions();
+        let models = self.corpus_models().await?;
+        let mut mismatches: Vec<serde_json::Value> = models.iter()
+            .filter_map(|(collection, stored)| match embedding_meta::check(Some(stored), &model, dimensions) {
+                embedding_meta::ModelCheck::Mismatch { stored, stored_dimensions } => Some(json!({
+                    "collection": collection,
+                    "embedding_model": stored,
+                    "dimensions": stored_dimensions
+                })),
+                _ => None,
+            })
+            .collect();
 
-        // Check if embedding needs to be generated (MCP mode)
+        let mut generated_dimensions = None;
         if self.config.is_mcp_embedding_enabled() {
-            info!("Generating embedding for waypoint description...");
-            
             let api_key = self.config.get_api_key().unwrap_or_default();
-            
-            match self.generate_embedding(description, &api_key).await {
-                Ok(embedding) => {
-                    info!("✓ Generated {} dimensional embedding", embedding.len());
+            let embedding = self.generate_embedding("embedding dimension check", &api_key).await
+                .map_err(|e| format!("Test embedding failed: {}", e))?;
+            if embedding.len() != dimensions {
+                mismatches.push(json!({
+                    "provider": model,
+                    "dimensions": embedding.len(),
+                    "error": format!("The provider returns {} dimensions but [embedding] dimensions is {}", embedding.len(), dimensions)
+                }));
+            }
+            generated_dimensions = Some(embedding.len());
+        }
+        for mismatch in &mismatches {
+            warn!("Embedding dimension mismatch: {}", mismatch);
+        }
+        Ok(json!({
+            "configured_model": model,
+            "configured_dimensions": dimensions,
+            "generated_dimensions": generated_dimensions,
+            "collections_checked": models.len(),
+            "mismatches": mismatches
+        }))
+    }
 
-                    // Add embedding to data (convert f32 to f64 for HelixDB)
-                    data["embedding"] = json!(embedding.iter().map(|&x| x as f64).collect::<Vec<f64>>());
+    #[tool(description = "Backfill embeddings - re-embed every memory of one type for a business or customer (e.g., after an embedding model change) under the [backfill] rate budget: requests per minute, max concurrency and an optional off-peak window. REQUIRED: memory_type, owner_id (business_id or customer_id). Optional: limit, requests_per_minute, ignore_schedule to run outside the off-peak window.")]
+    async fn backfill_embeddings(&self, params: Parameters<BackfillEmbeddingsParam>) -> Result<CallToolResult, McpError> {
+        let memory_type = memory_types::normalize(&params.0.memory_type);
+        let owner_id = &params.0.owner_id;
+        let backfill = &self.config.backfill;
+
+        let Some((id_field, owner_field, _)) = memory_types::entity(memory_type) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}. Valid types: product, service, location, hours, social, policy, event, information, behavior, preference, desire, rule, feedback", memory_type), json!({})));
+        };
+        let list_query = Self::memory_list_query(memory_type, owner_field);
+
+        // Off-peak window (UTC) - refuse to start outside it unless told otherwise
+        let window = backfill.off_peak_start_hour.zip(backfill.off_peak_end_hour);
+        let ignore_schedule = params.0.ignore_schedule.unwrap_or(false);
+        let clock = self.clock.clone();
+        let outside_window = move || {
+            let hour = chrono::Timelike::hour(&clock.now());
+            window.is_some_and(|(start, end)| !throttle::in_window(hour, start, end))
+        };
+        if !ignore_schedule && outside_window() {
+            let (start, end) = window.unwrap_or_default();
+            return Ok(tool_error(ErrorCode::RateLimited, format!("Outside the off-peak window ({:02}:00-{:02}:00 UTC) - retry later or set ignore_schedule", start, end), json!({})));
+        }
 
-                    let model_name = match self.config.embedding.provider {
-                        Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
-                            self.config.embedding.model.clone().unwrap_or_else(|| "unknown".to_string())
-                        }
-                        Some(config::EmbeddingProvider::Local) => "local".to_string(),
-                        Some(config::EmbeddingProvider::Tcp) => "tcp-local".to_string(),
-                        None => "unknown".to_string()
-                    };
-                    data["embedding_model"] = json!(model_name);
-                }
-                Err(e) => {
-                    error!("✗ Failed to generate embedding: {}", e);
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to generate embedding: {}", e),
-                        "suggestion": "Check embedding configuration and API connectivity"
-                    })));
+        // Mark the collection as migrating to the configured model; repeating this on retries is harmless
+        if self.config.is_mcp_embedding_enabled() && self.config.embedding_metadata.enabled {
+            let collection = Self::normalize_to_plural(memory_type);
+            let model = self.embedding_model_name();
+            let stored = self.corpus_models().await.ok().and_then(|models| models.get(collection).cloned());
+            if let Some(stored) = stored.filter(|s| s.model != model && s.target_model.as_deref() != Some(model.as_str())) {
+                info!("Collection {} migrating from {} to {}", collection, stored.model, model);
+                let migrating = embedding_meta::CollectionModel { target_model: Some(model), ..stored };
+                if let Err(e) = self.store_corpus_model(collection, migrating, true).await {
+                    warn!("Could not mark {} as migrating: {}", collection, e);
                 }
             }
-        } else {
-            info!("Using HelixDB embedding mode - HelixDB will generate embedding");
         }
 
-        // Execute the query
-        match self.helix_client.query("add_navigation_waypoint", data).await {
-            Ok(result) => {
-                Ok(CallToolResult::structured(json!({
-                    "success": true,
-                    "waypoint_id": waypoint_id,
-                    "navigation_id": navigation_id,
-                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
-                    "result": result
-                })))
-            }
+        let memories = match self.helix_client.query(&list_query, json!({ owner_field: owner_id })).await {
+            Ok(result) => HelixClient::result_items(&result),
             Err(e) => {
-                error!("create_navigation_waypoint failed: {}", e);
-                Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Failed to create navigation waypoint: {}", e)
-                })))
+                error!("backfill_embeddings lookup failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, e.to_string(), json!({})));
+            }
+        };
+        let limit = params.0.limit.unwrap_or(usize::MAX);
+        let requests_per_minute = params.0.requests_per_minute.unwrap_or(backfill.requests_per_minute);
+
+        info!("backfill_embeddings: type={}, {}={}, memories={}, rpm={}, concurrency={}",
+            memory_type, owner_field, owner_id, memories.len(), requests_per_minute, backfill.max_concurrency);
+
+        let budget = Arc::new(throttle::RateBudget::new(requests_per_minute, backfill.max_concurrency));
+        let mut tasks = tokio::task::JoinSet::new();
+        let mut skipped = Vec::new();
+
+        for memory in memories.into_iter().take(limit) {
+            let entity_id = memory.get(id_field).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+            let Some(text) = Self::embedding_source_text(&memory) else {
+                skipped.push(json!({id_field: entity_id, "reason": "no text to embed"}));
+                continue;
+            };
+
+            let server = self.clone();
+            let budget = budget.clone();
+            let owner_id = owner_id.clone();
+            let memory_type = memory_type.to_string();
+            let outside_window = outside_window.clone();
+            tasks.spawn(priority::background(async move {
+                let _permit = budget.acquire().await;
+                if !ignore_schedule && outside_window() {
+                    return (entity_id, None);
+                }
+                let result = server.reembed_memory(&memory_type, &owner_id, &memory, text).await;
+                (entity_id, Some(result))
+            }));
+        }
+
+        let mut reembedded = Vec::new();
+        let mut deferred = Vec::new();
+        let mut failures = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((entity_id, None)) => deferred.push(entity_id),
+                Ok((entity_id, Some(Ok(result)))) if result.is_error != Some(true) => reembedded.push(entity_id),
+                Ok((entity_id, Some(Ok(result)))) => failures.push(json!({id_field: entity_id, "error": result.structured_content})),
+                Ok((entity_id, Some(Err(e)))) => failures.push(json!({id_field: entity_id, "error": e.message})),
+                Err(e) => failures.push(json!({"error": format!("Backfill task failed: {}", e)})),
             }
         }
+
+        info!("✓ Backfilled {} {} embeddings ({} deferred, {} failed)", reembedded.len(), memory_type, deferred.len(), failures.len());
+
+        Ok(CallToolResult::structured(json!({
+            "success": failures.is_empty(),
+            "memory_type": memory_type,
+            owner_field: owner_id,
+            "reembedded": reembedded.len(),
+            "reembedded_ids": reembedded,
+            "deferred_ids": deferred,
+            "skipped": skipped,
+            "failures": failures,
+            "requests_per_minute": requests_per_minute,
+            "max_concurrency": backfill.max_concurrency,
+            "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" }
+        })))
     }
 
-    #[tool(description = "Create direction path - add step-by-step directions with compass waypoints, suitability flags, and accessibility information. Use query_navigation to get navigation_id.")]
-    async fn create_direction_path(&self, params: Parameters<CreateDirectionPathParam>) -> Result<CallToolResult, McpError> {
-        let navigation_id = &params.0.navigation_id;
-        let step_by_step_instructions = &params.0.step_by_step_instructions;
-        
-        // Always auto-generate path_id
-        let path_id = format!("PTH_{}", Uuid::new_v4().to_string());
-        
-        info!("create_direction_path: path_id={}, navigation_id={}", path_id, navigation_id);
+    /// Query listing an owner's memories of a singular memory_type
+    fn memory_list_query(memory_type: &str, owner_field: &str) -> String {
+        match memory_type {
+            "social" => "get_business_social_media".to_string(),
+            _ if owner_field == "business_id" => format!("get_business_{}", Self::normalize_to_plural(memory_type)),
+            _ => format!("get_customer_{}", Self::normalize_to_plural(memory_type)),
+        }
+    }
 
-        // Build the data payload
-        let timestamp = chrono::Utc::now().timestamp();
-        let mut data = json!({
-            "path_id": path_id,
-            "navigation_id": navigation_id,
-            "path_name": params.0.path_name,
-            "path_type": params.0.path_type,
-            "transport_mode": params.0.transport_mode.as_ref().unwrap_or(&String::from("walking")),
-            "estimated_duration_minutes": params.0.estimated_duration_minutes.unwrap_or(10),
-            "difficulty_level": params.0.difficulty_level.as_ref().unwrap_or(&String::from("easy")),
-            "distance_meters": params.0.distance_meters.unwrap_or(0),
-            "starting_compass_bearing": params.0.starting_compass_bearing.unwrap_or(0.0),
-            "ending_compass_bearing": params.0.ending_compass_bearing.unwrap_or(0.0),
-            "path_compass_waypoints": params.0.path_compass_waypoints.as_ref().unwrap_or(&String::from("[]")),
-            "suitable_for_mobility_aids": params.0.suitable_for_mobility_aids.unwrap_or(false),
-            "suitable_for_children": params.0.suitable_for_children.unwrap_or(true),
-            "suitable_in_rain": params.0.suitable_in_rain.unwrap_or(true),
-            "suitable_at_night": params.0.suitable_at_night.unwrap_or(true),
-            "requires_appointment": params.0.requires_appointment.unwrap_or(false),
-            "requires_security_clearance": params.0.requires_security_clearance.unwrap_or(false),
-            "visitor_badge_required": params.0.visitor_badge_required.unwrap_or(false),
-            "step_by_step_instructions": step_by_step_instructions,
-            "quick_summary": params.0.quick_summary.as_ref().unwrap_or(&String::from("")),
-            "created_at": timestamp,
-            "is_recommended": params.0.is_recommended.unwrap_or(false),
-            "is_active": true,
-            "last_used_feedback": ""
+    /// Text a stored memory is embedded from
+    fn embedding_source_text(memory: &serde_json::Value) -> Option<String> {
+        ["text_description", "description", "content"]
+            .iter()
+            .find_map(|f| memory.get(*f).and_then(|v| v.as_str()).filter(|t| !t.is_empty()))
+            .map(str::to_string)
+    }
+
+    /// Rebuild a stored memory's embedding from `text` through the update tools
+    async fn reembed_memory(&self, memory_type: &str, owner_id: &str, memory: &serde_json::Value, text: String) -> Result<CallToolResult, McpError> {
+        let Some((id_field, owner_field, _)) = memory_types::entity(memory_type) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}", memory_type), json!({})));
+        };
+        let entity_id = memory.get(id_field).and_then(|v| v.as_str()).unwrap_or_default();
+        let memory_id = memory.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        if owner_field == "business_id" {
+            self.update_business_memory(Parameters(UpdateBusinessMemoryParam {
+                memory_id,
+                memory_type: memory_type.to_string(),
+                updates: json!({
+                    "business_id": owner_id,
+                    id_field: entity_id,
+                    "composite_text": text
+                }),
+            })).await
+        } else {
+            self.update_customer_memory(Parameters(UpdateCustomerMemoryParam {
+                memory_id,
+                memory_type: memory_type.to_string(),
+                updates: json!({ "composite_text": text }),
+            })).await
+        }
+    }
+
+    #[tool(description = "Verify embeddings - sample memories of one type for a business or customer, regenerate embeddings for their text and compare them with the stored vectors by cosine similarity. Flags stale vectors (e.g. text changed via do_query without re-embedding), missing vectors and dimension mismatches. Needs MCP embedding mode. REQUIRED: memory_type, owner_id (business_id or customer_id). Optional: sample_size (default 20), min_similarity (default 0.9).")]
+    async fn verify_embeddings(&self, params: Parameters<VerifyEmbeddingsParam>) -> Result<CallToolResult, McpError> {
+        let memory_type = memory_types::normalize(&params.0.memory_type);
+        let owner_id = &params.0.owner_id;
+        let Some((id_field, owner_field, _)) = memory_types::entity(memory_type) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}. Valid types: product, service, location, hours, social, policy, event, information, behavior, preference, desire, rule, feedback", memory_type), json!({})));
+        };
+        if !self.config.is_mcp_embedding_enabled() {
+            return Ok(tool_error(ErrorCode::Disabled, "verify_embeddings regenerates embeddings in the MCP server, which needs MCP embedding mode", json!({
+                "suggestion": "Set mode = \"mcp\" and a provider in the [embedding] section of mcpconfig.toml"
+            })));
+        }
+        let sample_size = params.0.sample_size.unwrap_or(20).max(1);
+        let min_similarity = params.0.min_similarity.unwrap_or(0.9);
+        info!("verify_embeddings: type={}, {}={}, sample_size={}, min_similarity={}", memory_type, owner_field, owner_id, sample_size, min_similarity);
+
+        let list_query = Self::memory_list_query(memory_type, owner_field);
+        let memories = match self.helix_client.query(&list_query, json!({ owner_field: owner_id })).await {
+            Ok(result) => HelixClient::result_items(&result),
+            Err(e) => {
+                error!("verify_embeddings lookup failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, e.to_string(), json!({})));
+            }
+        };
+
+        // Spread the sample evenly over the collection
+        let sample: Vec<&serde_json::Value> = repair::spread_sample(memories.len(), sample_size).into_iter().map(|i| &memories[i]).collect();
+        let api_key = self.config.get_api_key().unwrap_or_default();
+        let checks = futures::future::join_all(
+            sample.iter().map(|memory| self.check_stored_embedding(memory_type, id_field, memory, &api_key, min_similarity))
+        ).await;
+
+        let mut consistent = 0;
+        let mut flagged = Vec::new();
+        let mut failures = Vec::new();
+        for check in checks {
+            match check {
+                Ok(None) => consistent += 1,
+                Ok(Some(flag)) => flagged.push(flag),
+                Err(failure) => failures.push(failure),
+            }
+        }
+        info!("✓ Verified {} {} embeddings: {} consistent, {} flagged", sample.len(), memory_type, consistent, flagged.len());
+
+        let mut report = json!({
+            "success": failures.is_empty(),
+            "memory_type": memory_type,
+            owner_field: owner_id,
+            "total": memories.len(),
+            "checked": sample.len(),
+            "consistent": consistent,
+            "flagged": flagged,
+            "failures": failures,
+            "min_similarity": min_similarity
         });
+        if !flagged.is_empty() {
+            report["suggestion"] = json!("Re-embed with backfill_embeddings; missing vectors can also be rebuilt with repair_graph (fix: true)");
+        }
+        Ok(CallToolResult::structured(report))
+    }
 
-        // Check if embedding needs to be generated (MCP mode)
-        if self.config.is_mcp_embedding_enabled() {
-            info!("Generating embedding for step-by-step instructions...");
-            
-            let api_key = self.config.get_api_key().unwrap_or_default();
-            
-            match self.generate_embedding(step_by_step_instructions, &api_key).await {
-                Ok(embedding) => {
-                    info!("✓ Generated {} dimensional embedding", embedding.len());
+    /// Compare a memory's stored vector with a fresh embedding of its text;
+    /// `Some` describes the mismatch
+    async fn check_stored_embedding(&self, memory_type: &str, id_field: &str, memory: &serde_json::Value, api_key: &str, min_similarity: f64) -> Result<Option<serde_json::Value>, serde_json::Value> {
+        let entity_id = memory.get(id_field).and_then(|v| v.as_str()).unwrap_or_default();
+        let result = self.helix_client.query(&format!("get_{}_with_embedding", memory_type), json!({ id_field: entity_id })).await
+            .map_err(|e| json!({id_field: entity_id, "error": e.to_string()}))?;
+        let stored = repair::stored_embedding(&result);
+        let flag = |problem: &str, details: serde_json::Value| {
+            let mut flag = json!({id_field: entity_id, "memory_id": memory.get("id"), "problem": problem});
+            if let (Some(fields), serde_json::Value::Object(details)) = (flag.as_object_mut(), details) {
+                fields.extend(details);
+            }
+            Ok(Some(flag))
+        };
+        let Some((stored, vector)) = stored.and_then(|s| similarity::vector_of(&s).map(|v| (s, v))) else {
+            return flag("missing_embedding", json!({}));
+        };
+        let Some(text) = embedding_utils::composite_text(memory_type, memory).or_else(|| Self::embedding_source_text(memory)) else {
+            return flag("no_text", json!({}));
+        };
 
-                    // Add embedding to data (convert f32 to f64 for HelixDB)
-                    data["embedding"] = json!(embedding.iter().map(|&x| x as f64).collect::<Vec<f64>>());
+        let fresh: Vec<f64> = self.generate_embedding(&text, api_key).await
+            .map_err(|e| json!({id_field: entity_id, "error": format!("Failed to generate embedding: {}", e)}))?
+            .into_iter()
+            .map(f64::from)
+            .collect();
+        match repair::embedding_problem(&stored, &vector, &text, &fresh, min_similarity) {
+            Some((problem, details)) => flag(problem, details),
+            None => Ok(None),
+        }
+    }
 
-                    let model_name = match self.config.embedding.provider {
-                        Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
-                            self.config.embedding.model.clone().unwrap_or_else(|| "unknown".to_string())
-                        }
-                        Some(config::EmbeddingProvider::Local) => "local".to_string(),
-                        Some(config::EmbeddingProvider::Tcp) => "tcp-local".to_string(),
-                        None => "unknown".to_string()
-                    };
-                    data["embedding_model"] = json!(model_name);
+    #[tool(description = "Repair graph - find inconsistencies left by partially failed writes: embedding vectors without a memory node (orphan_vectors, across the graph), memories without their embedding (missing_embeddings, for business_id and/or customer_id) and interactions naming a deleted product or service (dangling_references, for customer_id). Reports only unless fix: true, which deletes orphaned vectors, re-embeds memories from their stored text and deletes dangling interactions. Optional: business_id, customer_id, checks (default all), fix.")]
+    async fn repair_graph(&self, params: Parameters<RepairGraphParam>) -> Result<CallToolResult, McpError> {
+        let RepairGraphParam { business_id, customer_id, checks, fix } = params.0;
+        let fix = fix.unwrap_or(false);
+        let checks = match checks {
+            None => repair::CHECKS.to_vec(),
+            Some(names) => match names.iter().map(|n| repair::Check::parse(n).ok_or(n)).collect::<Result<Vec<_>, _>>() {
+                Ok(checks) => checks,
+                Err(name) => {
+                    return Ok(tool_error(ErrorCode::ValidationFailed, format!("Unknown check: {}", name), json!({
+                        "valid_values": repair::CHECKS.map(|c| c.name())
+                    })));
+                }
+            },
+        };
+        info!("repair_graph: business_id={:?}, customer_id={:?}, checks={:?}, fix={}", business_id, customer_id, checks, fix);
+
+        let mut report = serde_json::Map::new();
+        let mut failures = Vec::new();
+        for check in checks {
+            let outcome = match check {
+                repair::Check::OrphanVectors => self.repair_orphan_vectors(fix).await,
+                repair::Check::MissingEmbeddings => self.repair_missing_embeddings(business_id.as_deref(), customer_id.as_deref(), fix).await,
+                repair::Check::DanglingReferences => self.repair_dangling_references(customer_id.as_deref(), fix).await,
+            };
+            match outcome {
+                Ok(found) => {
+                    report.insert(check.name().to_string(), found);
                 }
                 Err(e) => {
-                    error!("✗ Failed to generate embedding: {}", e);
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to generate embedding: {}", e),
-                        "suggestion": "Check embedding configuration and API connectivity"
-                    })));
+                    error!("repair_graph {} failed: {}", check.name(), e);
+                    failures.push(json!({"check": check.name(), "error": e}));
                 }
             }
-        } else {
-            info!("Using HelixDB embedding mode - HelixDB will generate embedding");
         }
 
-        // Execute the query
-        match self.helix_client.query("add_direction_path", data).await {
-            Ok(result) => {
-                Ok(CallToolResult::structured(json!({
-                    "success": true,
-                    "path_id": path_id,
-                    "navigation_id": navigation_id,
-                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
-                    "result": result
-                })))
+        Ok(CallToolResult::structured(json!({
+            "success": failures.is_empty(),
+            "fix": fix,
+            "checks": report,
+            "failures": failures
+        })))
+    }
+
+    /// Embedding vectors whose memory node is gone, deleted when `fix` is set
+    async fn repair_orphan_vectors(&self, fix: bool) -> Result<serde_json::Value, String> {
+        let counts = self.helix_client.query("count_orphan_embeddings", json!({})).await
+            .map_err(|e| format!("Failed to count orphaned embeddings: {}", e))?;
+        let orphaned: serde_json::Map<String, serde_json::Value> = counts.as_object()
+            .map(|counts| counts.iter().filter(|(_, n)| n.as_u64().unwrap_or(0) > 0).map(|(k, n)| (k.clone(), n.clone())).collect())
+            .unwrap_or_default();
+        let found: u64 = orphaned.values().filter_map(|n| n.as_u64()).sum();
+        let fixed = if fix && found > 0 {
+            self.helix_client.query("delete_orphan_embeddings", json!({})).await
+                .map_err(|e| format!("Failed to delete orphaned embeddings: {}", e))?;
+            found
+        } else {
+            0
+        };
+        Ok(json!({"found": found, "orphaned": orphaned, "fixed": fixed}))
+    }
+
+    /// Memories of the business and/or customer without an embedding, re-embedded when `fix` is set
+    async fn repair_missing_embeddings(&self, business_id: Option<&str>, customer_id: Option<&str>, fix: bool) -> Result<serde_json::Value, String> {
+        if business_id.is_none() && customer_id.is_none() {
+            return Ok(json!({"skipped": "Needs business_id or customer_id"}));
+        }
+        let scans = [
+            ("find_business_memories_missing_embeddings", "business_id", business_id),
+            ("find_customer_memories_missing_embeddings", "customer_id", customer_id),
+        ];
+        let mut found = Vec::new();
+        for (query, owner_field, owner_id) in scans {
+            let Some(owner_id) = owner_id else {
+                continue;
+            };
+            let result = self.helix_client.query(query, json!({ owner_field: owner_id })).await
+                .map_err(|e| format!("Failed to scan for missing embeddings: {}", e))?;
+            for (memory_type, nodes) in repair::missing_embeddings(&result) {
+                found.extend(nodes.into_iter().map(|node| (memory_type, owner_id, node)));
             }
-            Err(e) => {
-                error!("create_direction_path failed: {}", e);
-                Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Failed to create direction path: {}", e)
-                })))
+        }
+
+        let mut memories = Vec::new();
+        let mut fixed = 0;
+        let mut failed = Vec::new();
+        for (memory_type, owner_id, node) in &found {
+            let id_field = memory_types::entity(memory_type).map_or("id", |(id_field, _, _)| id_field);
+            let entry = json!({"memory_type": memory_type, id_field: node.get(id_field)});
+            if fix {
+                let outcome = match Self::embedding_source_text(node) {
+                    Some(text) => match self.reembed_memory(memory_type, owner_id, node, text).await {
+                        Ok(result) if result.is_error != Some(true) => Ok(()),
+                        Ok(result) => Err(json!(result.structured_content)),
+                        Err(e) => Err(json!(e.message)),
+                    },
+                    None => Err(json!("no text to embed")),
+                };
+                match outcome {
+                    Ok(()) => fixed += 1,
+                    Err(error) => failed.push(json!({"memory": entry.clone(), "error": error})),
+                }
             }
+            memories.push(entry);
         }
+        Ok(json!({"found": found.len(), "memories": memories, "fixed": fixed, "failed": failed}))
     }
 
-    #[tool(description = "Query navigation - get complete navigation data for a business including hub, waypoints, and paths with optional filtering. PREFERRED: Use business_id parameter to query navigation by business (recommended). ALTERNATIVE: Use navigation_id if you already know it. Returns hub details, waypoints, and direction paths.")]
-    async fn query_navigation(&self, params: Parameters<QueryNavigationParam>) -> Result<CallToolResult, McpError> {
-        info!("query_navigation");
+    /// The customer's interactions naming a product or service that no longer exists, deleted when `fix` is set
+    async fn repair_dangling_references(&self, customer_id: Option<&str>, fix: bool) -> Result<serde_json::Value, String> {
+        let Some(customer_id) = customer_id else {
+            return Ok(json!({"skipped": "Needs customer_id"}));
+        };
+        let (products, services) = tokio::join!(
+            self.helix_client.query("get_customer_product_interactions", json!({"customer_id": customer_id})),
+            self.helix_client.query("get_customer_service_interactions", json!({"customer_id": customer_id})),
+        );
+        let kinds = [(products, "product_id", "get_business_product_by_id"), (services, "service_id", "get_business_service_by_id")];
+
+        let mut interactions = Vec::new();
+        for (result, id_field, lookup) in kinds {
+            let items = HelixClient::result_items(&result.map_err(|e| format!("Failed to get interactions: {}", e))?);
+            let ids = repair::referenced_ids(&items, id_field);
+            let lookups = futures::future::join_all(ids.iter().map(|id| self.helix_client.query(lookup, json!({ id_field: id })))).await;
+            let mut existing = std::collections::HashSet::new();
+            for (id, result) in ids.into_iter().zip(lookups) {
+                match result {
+                    Ok(found) if HelixClient::result_items(&found).is_empty() => {}
+                    Ok(_) => {
+                        existing.insert(id);
+                    }
+                    // A failed lookup isn't evidence the target is gone
+                    Err(e) => {
+                        warn!("repair_graph: lookup of {} {} failed: {}", id_field, id, e);
+                        existing.insert(id);
+                    }
+                }
+            }
+            interactions.extend(repair::dangling(&items, id_field, &existing).into_iter().map(|interaction| json!({
+                "interaction_id": interaction.get("interaction_id"),
+                "interaction_type": interaction.get("interaction_type"),
+                id_field: interaction.get(id_field)
+            })));
+        }
 
-        let mut navigation_data = json!({});
+        let mut fixed = 0;
+        let mut failed = Vec::new();
+        if fix {
+            let deletes = self.helix_client.query_batch(interactions.iter().map(|interaction| {
+                ("delete_interaction_with_embedding", json!({"interaction_id": interaction["interaction_id"]}))
+            }).collect()).await;
+            for (interaction, result) in interactions.iter().zip(deletes) {
+                match result {
+                    Ok(_) => fixed += 1,
+                    Err(e) => failed.push(json!({"interaction_id": interaction["interaction_id"], "error": e.to_string()})),
+                }
+            }
+        }
+        Ok(json!({"customer_id": customer_id, "found": interactions.len(), "interactions": interactions, "fixed": fixed, "failed": failed}))
+    }
 
-        // Determine navigation_id from business_id or use provided navigation_id
-        let nav_id = if let Some(business_id) = &params.0.business_id {
-            // Get navigation hub for business
-            match self.helix_client.query(
-                "get_business_navigation_hub",
-                json!({"business_id": business_id})
-            ).await {
-                Ok(hub) => {
-                    navigation_data["hub"] = hub.clone();
-                    
-                    // Extract navigation_id from hub - try multiple paths as response structure may vary
-                    let nav_id = hub.get("navigation_id")
-                        .and_then(|v| v.as_str())
-                        .or_else(|| {
-                            // Try nav_hub.navigation_id (nested structure)
-                            hub.get("nav_hub")
-                                .and_then(|h| h.get("navigation_id"))
-                                .and_then(|v| v.as_str())
-                        })
-                        .or_else(|| {
-                            // Try if hub is an array, get first item
-                            hub.as_array()
-                                .and_then(|arr| arr.first())
-                                .and_then(|item| item.get("navigation_id"))
-                                .and_then(|v| v.as_str())
-                        })
-                        .or_else(|| {
-                            // Try nav_hub as array
-                            hub.get("nav_hub")
-                                .and_then(|h| h.as_array())
-                                .and_then(|arr| arr.first())
-                                .and_then(|item| item.get("navigation_id"))
-                                .and_then(|v| v.as_str())
-                        })
-                        .map(|s| s.to_string());
-                    
-                    if nav_id.is_none() {
-                        error!("Could not extract navigation_id from hub response. Hub structure: {:?}", hub);
+    #[tool(description = "Get memory stats - overview of the whole memory store: node and vector counts per memory type (with nodes missing their vector), when each type was last written, and a per-business breakdown of memory counts, largest businesses first. Optional: business_id to break down one business, include_businesses (default true), max_businesses (default 50).")]
+    async fn get_memory_stats(&self, params: Parameters<GetMemoryStatsParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        info!("get_memory_stats: business_id={:?}", p.business_id);
+
+        let (nodes, vectors, latest) = futures::future::join3(
+            self.helix_client.query("count_memory_nodes", json!({})),
+            self.helix_client.query("count_memory_vectors", json!({})),
+            self.helix_client.query("get_latest_memory_activity", json!({})),
+        ).await;
+        let (nodes, vectors) = match (nodes, vectors) {
+            (Ok(nodes), Ok(vectors)) => (nodes, vectors),
+            (Err(e), _) | (_, Err(e)) => {
+                error!("get_memory_stats failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to count memories: {}", e), json!({})));
+            }
+        };
+        // Counts are still useful without activity timestamps
+        let latest = latest.unwrap_or_else(|e| {
+            warn!("get_memory_stats: latest activity query failed: {}", e);
+            serde_json::Value::Null
+        });
+        let mut response = stats::overview(&nodes, &vectors, &latest);
+
+        if p.include_businesses.unwrap_or(true) {
+            let businesses = match &p.business_id {
+                Some(business_id) => self.helix_client.query("get_business", json!({"business_id": business_id})).await,
+                None => self.helix_client.query("get_all_businesses", json!({})).await,
+            };
+            let businesses = match businesses {
+                Ok(result) => HelixClient::result_items(&result),
+                Err(e) => return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to list businesses: {}", e), json!({}))),
+            };
+            let slots = tokio::sync::Semaphore::new(self.config.helix.max_parallel_queries.max(1));
+            let mut rows = futures::future::join_all(businesses.iter().map(|business| {
+                let slots = &slots;
+                async move {
+                    let _slot = slots.acquire().await;
+                    let business_id = business.get("business_id").and_then(|v| v.as_str()).unwrap_or_default();
+                    match self.helix_client.query("count_business_memories", json!({"business_id": business_id})).await {
+                        Ok(counts) => stats::business_row(business, &counts),
+                        Err(e) => json!({"business_id": business_id, "error": e.to_string()}),
                     }
-                    
-                    nav_id
-                }
-                Err(e) => {
-                    error!("Failed to get navigation hub: {}", e);
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to get navigation hub for business_id {}: {}", business_id, e),
-                        "suggestion": "Make sure a navigation hub exists for this business_id. Use create_navigation_hub first."
-                    })));
                 }
+            }))
+            .await;
+            rows.sort_by_key(|row| std::cmp::Reverse(row["total_memories"].as_u64().unwrap_or(0)));
+            let listed = p.max_businesses.unwrap_or(50);
+            response["business_count"] = json!(rows.len());
+            response["businesses_truncated"] = json!(rows.len() > listed);
+            rows.truncate(listed);
+            response["businesses"] = json!(rows);
+        }
+
+        Ok(CallToolResult::structured(response))
+    }
+
+    #[tool(description = "Get embedding metadata - the embedding model and dimensions recorded for each collection (memory type), compared with the configured query model. Status is match, mismatch, migrating (backfill to the configured model under way) or unrecorded. Optional: memory_type.")]
+    async fn get_embedding_metadata(&self, params: Parameters<GetEmbeddingMetadataParam>) -> Result<CallToolResult, McpError> {
+        let models = match self.corpus_models().await {
+            Ok(models) => models,
+            Err(e) => {
+                error!("get_embedding_metadata failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to load embedding metadata: {}", e), json!({})));
             }
-        } else if let Some(navigation_id) = &params.0.navigation_id {
-            navigation_data["hub"] = json!({"navigation_id": navigation_id});
-            Some(navigation_id.clone())
-        } else {
-            return Ok(CallToolResult::structured_error(json!({
-                "error": "Must provide either business_id or navigation_id",
-                "suggestion": "Provide business_id to look up navigation by business, or navigation_id if you know it directly"
-            })));
         };
+        let model = self.embedding_model_name();
+        let dimensions = self.config.embedding.vector_dimensions();
+        let filter = params.0.memory_type.as_deref().map(Self::normalize_to_plural);
+
+        let mut collections: Vec<serde_json::Value> = models.iter()
+            .filter(|(collection, _)| filter.is_none_or(|f| f == collection.as_str()))
+            .map(|(collection, stored)| {
+                let status = match embedding_meta::check(Some(stored), &model, dimensions) {
+                    embedding_meta::ModelCheck::Match => "match",
+                    embedding_meta::ModelCheck::Migrating { .. } => "migrating",
+                    embedding_meta::ModelCheck::Mismatch { .. } => "mismatch",
+                    embedding_meta::ModelCheck::Unrecorded => "unrecorded",
+                };
+                json!({
+                    "memory_type": collection,
+                    "embedding_model": stored.model,
+                    "dimensions": stored.dimensions,
+                    "target_model": stored.target_model,
+                    "status": status
+                })
+            })
+            .collect();
+        collections.sort_by(|a, b| a["memory_type"].as_str().cmp(&b["memory_type"].as_str()));
+
+        Ok(CallToolResult::structured(json!({
+            "configured_model": model,
+            "configured_dimensions": dimensions,
+            "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
+            "on_mismatch": self.config.embedding_metadata.on_mismatch,
+            "provider": self.provider_model.get().map(|p| json!({"model": p.model, "dimensions": p.dimensions, "source": p.source})),
+            "collections": collections
+        })))
+    }
+
+    #[tool(description = "Set embedding metadata - record the embedding model of a collection (memory type), e.g. after backfill_embeddings has re-embedded all of it with a new model. Clears any in-progress migration marker. REQUIRED: memory_type. Optional: embedding_model and dimensions (default: the configured model).")]
+    async fn set_embedding_metadata(&self, params: Parameters<SetEmbeddingMetadataParam>) -> Result<CallToolResult, McpError> {
+        let collection = Self::normalize_to_plural(&params.0.memory_type);
+        let models = match self.corpus_models().await {
+            Ok(models) => models,
+            Err(e) => {
+                error!("set_embedding_metadata failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to load embedding metadata: {}", e), json!({})));
+            }
+        };
+        let recorded = embedding_meta::CollectionModel {
+            model: params.0.embedding_model.clone().unwrap_or_else(|| self.embedding_model_name()),
+            dimensions: params.0.dimensions.unwrap_or(self.config.embedding.vector_dimensions()),
+            target_model: None,
+        };
+        info!("set_embedding_metadata: {} -> {} ({} dims)", collection, recorded.model, recorded.dimensions);
 
-        let nav_id = match nav_id {
-            Some(id) => id,
-            None => {
-                return Ok(CallToolResult::structured_error(json!({
-                    "error": "Could not determine navigation_id from the query response",
-                    "suggestion": "The navigation hub may not exist for this business. Use create_navigation_hub first."
-                })));
-            }
+        let previous = models.get(collection).cloned();
+        if let Err(e) = self.store_corpus_model(collection, recorded.clone(), previous.is_some()).await {
+            error!("set_embedding_metadata failed: {}", e);
+            return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to store embedding metadata: {}", e), json!({})));
+        }
+
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "memory_type": collection,
+            "embedding_model": recorded.model,
+            "dimensions": recorded.dimensions,
+            "previous_model": previous.map(|p| p.model)
+        })))
+    }
+
+    // ========================================================================
+    // SESSION WORKING MEMORY - Ephemeral notes, never persisted to HelixDB
+    // ========================================================================
+
+    #[tool(description = "Remember for session - stash an ephemeral key-value note (intermediate findings, shortlists, working state) for the current session. Not persisted to HelixDB; notes expire after the session is idle. REQUIRED: key, value (any JSON). Optional: session_id (needed over HTTP, where each request is otherwise its own session).")]
+    async fn remember_for_session(&self, params: Parameters<RememberForSessionParam>) -> Result<CallToolResult, McpError> {
+        let session_id = params.0.session_id.as_deref().unwrap_or(&self.session_id);
+
+        match self.working_memory.remember(session_id, &params.0.key, params.0.value.clone()) {
+            Ok(note_count) => Ok(CallToolResult::structured(json!({
+                "success": true,
+                "session_id": session_id,
+                "key": params.0.key,
+                "note_count": note_count,
+                "ttl_secs": self.config.session_memory.ttl_secs
+            }))),
+            Err(e) => Ok(tool_error(ErrorCode::Conflict, e, json!({"session_id": session_id}))),
+        }
+    }
+
+    #[tool(description = "Recall session - read ephemeral notes stored with remember_for_session. Optional: key (default: all notes), clear to remove the returned notes, session_id (needed over HTTP).")]
+    async fn recall_session(&self, params: Parameters<RecallSessionParam>) -> Result<CallToolResult, McpError> {
+        let session_id = params.0.session_id.as_deref().unwrap_or(&self.session_id);
+        let notes = self.working_memory.recall(session_id, params.0.key.as_deref(), params.0.clear.unwrap_or(false));
+
+        Ok(CallToolResult::structured(json!({
+            "session_id": session_id,
+            "key": params.0.key,
+            "found": !notes.is_null() && notes != json!({}),
+            "notes": notes
+        })))
+    }
+
+    #[tool(description = "Set sandbox mode - route this session's writes to a shadow tenant. While enabled, business_id/customer_id values in create/update calls are rewritten to sandbox-<tag>-<id>, updates and deletes may only target memories created in the sandbox, and bulk maintenance is refused. Reads still see production data. Sandboxes are deleted automatically after the configured TTL. REQUIRED: enabled. Optional: discard (default true) to delete sandbox memories immediately when disabling.")]
+    async fn set_sandbox_mode(&self, params: Parameters<SetSandboxModeParam>) -> Result<CallToolResult, McpError> {
+        if params.0.enabled {
+            let tag = self.sandbox.enable(&self.session_id);
+            info!("Session {} entered sandbox {}", self.session_id, tag);
+            return Ok(CallToolResult::structured(json!({
+                "sandbox": true,
+                "session_id": &*self.session_id,
+                "namespace_prefix": format!("{}{}-", sandbox::PREFIX, tag),
+                "ttl_secs": self.config.sandbox.ttl_secs
+            })));
+        }
+
+        let discarded = match self.sandbox.disable(&self.session_id) {
+            Some(tenant) if params.0.discard.unwrap_or(true) => Some(self.discard_sandbox(tenant).await),
+            _ => None,
         };
+        Ok(CallToolResult::structured(json!({
+            "sandbox": false,
+            "session_id": &*self.session_id,
+            "discarded": discarded
+        })))
+    }
 
-        // Get waypoints if requested
-        if params.0.include_waypoints.unwrap_or(true) {
-            match self.helix_client.query(
-                "get_navigation_waypoints",
-                json!({"navigation_id": nav_id})
-            ).await {
-                Ok(waypoints) => {
-                    navigation_data["waypoints"] = waypoints;
-                }
+    /// Delete all businesses and customers written by a sandbox
+    async fn discard_sandbox(&self, tenant: sandbox::Tenant) -> serde_json::Value {
+        let mut deleted = 0;
+        let mut failed = Vec::new();
+        let owners = tenant.business_ids.iter().map(|id| ("delete_business_complete", "business_id", id))
+            .chain(tenant.customer_ids.iter().map(|id| ("delete_customer_complete", "customer_id", id)));
+
+        for (query_name, field, id) in owners {
+            match self.helix_client.query(query_name, json!({ field: id })).await {
+                Ok(_) => deleted += 1,
                 Err(e) => {
-                    error!("Failed to get waypoints: {}", e);
+                    warn!("Sandbox {} cleanup: {} {} failed: {}", tenant.tag, query_name, id, e);
+                    failed.push(id.clone());
                 }
             }
         }
 
-        // Get paths if requested
-        if params.0.include_paths.unwrap_or(true) {
-            let query_name = if params.0.filter_accessible_only.unwrap_or(false) {
-                "get_accessible_navigation"
-            } else {
-                "get_direction_paths"
-            };
+        info!("Discarded sandbox {} ({} tenants deleted, {} failed)", tenant.tag, deleted, failed.len());
+        json!({"tag": tenant.tag, "deleted": deleted, "failed": failed})
+    }
 
-            match self.helix_client.query(
-                query_name,
-                json!({"navigation_id": nav_id})
-            ).await {
-                Ok(paths) => {
-                    navigation_data["paths"] = paths;
-                }
-                Err(e) => {
-                    error!("Failed to get paths: {}", e);
-                }
-            }
+    #[tool(description = "Fetch result page - get the next page of a tool result that was too large to send at once (its pagination block has has_more: true). Pages of an array result hold the same fields with the next slice of the array; json_text parts are joined in order and parsed. REQUIRED: cursor (pagination.next_cursor).")]
+    async fn fetch_result_page(&self, params: Parameters<FetchResultPageParam>) -> Result<CallToolResult, McpError> {
+        match self.result_pages.fetch(&params.0.cursor) {
+            Ok(page) => Ok(CallToolResult::structured(page)),
+            Err(e) => Ok(tool_error(ErrorCode::NotFound, e, json!({
+                "suggestion": "Pages expire after result_page_ttl_secs; call the original tool again"
+            }))),
         }
+    }
 
-        // Count results
-        let waypoint_count = navigation_data.get("waypoints")
-            .and_then(|v| v.as_array())
-            .map(|a| a.len())
-            .unwrap_or(0);
-        let path_count = navigation_data.get("paths")
-            .and_then(|v| v.as_array())
-            .map(|a| a.len())
-            .unwrap_or(0);
+    /// Send a result over `[server] max_response_bytes` as its first page, keeping the rest for fetch_result_page
+    fn spill_oversized(&self, result: CallToolResult) -> CallToolResult {
+        let max_bytes = self.config.server.max_response_bytes;
+        let Some(content) = result.structured_content.as_ref().filter(|_| max_bytes > 0 && result.is_error != Some(true)) else {
+            return result;
+        };
+        let size = content.to_string().len();
+        if size <= max_bytes {
+            return result;
+        }
+        let pages = result_pages::paginate(content, max_bytes);
+        if pages.len() < 2 {
+            return result;
+        }
+        info!("Result of {} bytes over max_response_bytes; sending it in {} pages", size, pages.len());
+        CallToolResult::structured(self.result_pages.spill(&self.ids.next_id().to_string(), pages))
+    }
+
+    // ========================================================================
+    // AUDIT - Which memories were read, for whom
+    // ========================================================================
+
+    #[tool(description = "Get access log - list which memories read tools returned, per tool call (MCP request ID), session and customer, newest first. Use to answer \"what data did the agent use when it told customer X?\". Optional: customer_id, session_id, tool_call_id, since/until (unix seconds, RFC3339 or YYYY-MM-DD), limit (default 50).")]
+    async fn get_access_log(&self, params: Parameters<GetAccessLogParam>) -> Result<CallToolResult, McpError> {
+        let p = &params.0;
+        let filter = access_log::AccessQuery {
+            customer_id: p.customer_id.as_deref(),
+            session_id: p.session_id.as_deref(),
+            tool_call_id: p.tool_call_id.as_deref(),
+            since: p.since.as_ref().and_then(filters::parse_timestamp),
+            until: p.until.as_ref().and_then(filters::parse_timestamp),
+        };
+        let entries = self.access_log.query(&filter, p.limit.unwrap_or(50));
 
         Ok(CallToolResult::structured(json!({
-            "navigation_id": nav_id,
-            "waypoint_count": waypoint_count,
-            "path_count": path_count,
-            "data": navigation_data
+            "enabled": self.config.access_log.enabled,
+            "persistent": !self.config.access_log.path.is_empty(),
+            "count": entries.len(),
+            "entries": entries
         })))
     }
 
-    #[tool(description = "Search navigation semantically - find navigation hubs, waypoints, and paths by meaning using AI embeddings")]
-    async fn search_navigation(&self, params: Parameters<SearchNavigationParam>) -> Result<CallToolResult, McpError> {
-        let query = &params.0.query;
-        let search_types = &params.0.search_types;
-        let limit = params.0.limit.unwrap_or(10);
-        
-        info!("search_navigation: query='{}', types={:?}, limit={}", query, search_types, limit);
+    /// Live document behind a `memory://` resource, built from the read tools
+    async fn render_dynamic_resource(&self, resource: &resources::Dynamic) -> Result<serde_json::Value, McpError> {
+        let content = |result: CallToolResult| match result.structured_content {
+            Some(value) if result.is_error != Some(true) => Ok(value),
+            other => Err(McpError::internal_error("Failed to read live memory", other)),
+        };
+        let now = self.clock.timestamp();
+        match resource {
+            resources::Dynamic::BusinessSummary(business_id) => {
+                let memories = content(self.query_business_memory(Parameters(QueryBusinessMemoryParam {
+                    business_id: business_id.clone(),
+                    memory_type: "all".to_string(),
+                    filters: None,
+                    sort_by: None,
+                    order: None,
+                    limit: None,
+                    offset: None,
+                })).await?)?;
+                Ok(resources::business_summary(business_id, &memories, now))
+            }
+            resources::Dynamic::BusinessCollection(business_id, collection) => {
+                let mut listing = content(self.query_business_memory(Parameters(QueryBusinessMemoryParam {
+                    business_id: business_id.clone(),
+                    memory_type: collection.clone(),
+                    filters: None,
+                    sort_by: None,
+                    order: None,
+                    limit: None,
+                    offset: None,
+                })).await?)?;
+                listing["generated_at"] = json!(now);
+                Ok(listing)
+            }
+            resources::Dynamic::CustomerProfile(customer_id) => {
+                let memories = content(self.query_customer_memory(Parameters(QueryCustomerMemoryParam {
+                    customer_id: customer_id.clone(),
+                    memory_type: "all".to_string(),
+                    filters: None,
+                    channel: None,
+                    sort_by: None,
+                    order: None,
+                    limit: None,
+                    offset: None,
+                })).await?)?;
+                // Totals and sentiment are best effort; the memories are the profile
+                let interactions = self.analyze_interactions(Parameters(AnalyzeInteractionsParam {
+                    customer_id: Some(customer_id.clone()),
+                    business_id: None,
+                    interaction_type: None,
+                    bucket: None,
+                    top_n: None,
+                    filters: None,
+                    differential_privacy: None,
+                    reporting_currency: None,
+                    format: None,
+                })).await.ok().and_then(|r| r.structured_content).unwrap_or(serde_json::Value::Null);
+                let feedback = self.summarize_feedback(Parameters(SummarizeFeedbackParam {
+                    customer_id: Some(customer_id.clone()),
+                    business_id: None,
+                    top_n: None,
+                    cluster_themes: None,
+                    theme_threshold: None,
+                    filters: None,
+                    differential_privacy: None,
+                })).await.ok().and_then(|r| r.structured_content).unwrap_or(serde_json::Value::Null);
+                Ok(resources::customer_profile(customer_id, &memories, interactions, feedback, now))
+            }
+        }
+    }
 
-        // Check embedding mode
-        if self.config.is_helixdb_embedding_enabled() {
-            info!("Using HelixDB embedding mode");
-            
-            let mut all_results = Vec::new();
-            
-            for search_type in search_types {
-                let query_name = match search_type.as_str() {
-                    "hubs" => "search_navigation_hubs",
-                    "waypoints" => "search_navigation_waypoints",
-                    "paths" => "search_direction_paths",
-                    _ => {
-                        info!("Skipping unsupported search type: {}", search_type);
-                        continue;
-                    }
-                };
+    // ========================================================================
+    // INFORMATION RELATIONSHIP TOOLS - Create Network of Knowledge
+    // ========================================================================
 
-                let mut payload = json!({
-                    "query_embedding": query,
-                    "limit": limit,
+    #[tool(description = "Create information relationships - create links between information documents and business assets to build a knowledge network. Specify the operation and provide the required parameters: create_related requires from_info_id and to_info_id, create_prerequisite requires from_info_id and to_info_id, create_series requires from_info_id, to_info_id, and series_name, create_reference requires from_info_id and to_info_id, link_to_product requires from_info_id and target_business_id, link_to_service requires from_info_id and target_business_id, link_to_location requires from_info_id and target_business_id, link_to_event requires from_info_id and target_business_id.")]
+    async fn create_information_relationships(&self, params: Parameters<ManageInformationRelationshipsParam>) -> Result<CallToolResult, McpError> {
+        let p = &params.0;
+        match p.operation.as_str() {
+            "create_related" => {
+                let payload = json!({
+                    "from_info_id": p.from_info_id,
+                    "to_info_id": p.to_info_id,
+                    "relationship_type": p.relationship_type.as_ref().unwrap_or(&"related".to_string()),
+                    "strength": p.strength,
+                    "notes": p.notes.as_ref().unwrap_or(&"".to_string())
                 });
-
-                if let Some(business_id) = &params.0.business_id {
-                    payload["business_id"] = json!(business_id);
+                match self.helix_client.query("link_related_information", payload).await {
+                    Ok(result) => Ok(CallToolResult::structured(json!({
+                        "success": true,
+                        "operation": "create_related",
+                        "result": result
+                    }))),
+                    Err(e) => Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to create related relationship: {}", e), json!({})))
                 }
-
-                match self.helix_client.query(query_name, payload).await {
-                    Ok(results) => {
-                        if let Some(array) = results.as_array() {
-                            all_results.extend(array.iter().cloned());
-                        }
-                    }
-                    Err(e) => {
-                        error!("Navigation search failed for {}: {}", search_type, e);
-                    }
+            },
+            "create_prerequisite" => {
+                let payload = json!({
+                    "prerequisite_info_id": p.from_info_id,
+                    "dependent_info_id": p.to_info_id,
+                    "notes": p.notes.as_ref().unwrap_or(&"".to_string())
+                });
+                match self.helix_client.query("link_prerequisite_information", payload).await {
+                    Ok(result) => Ok(CallToolResult::structured(json!({
+                        "success": true,
+                        "operation": "create_prerequisite",
+                        "result": result
+                    }))),
+                    Err(e) => Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to create prerequisite relationship: {}", e), json!({})))
+                }
+            },
+            "create_series" => {
+                let series_name = p.series_name.as_ref().ok_or_else(|| McpError::invalid_params("series_name is required for create_series".to_string(), None))?;
+                let payload = json!({
+                    "from_info_id": p.from_info_id,
+                    "to_info_id": p.to_info_id,
+                    "series_name": series_name,
+                    "order": p.order.unwrap_or(1)
+                });
+                match self.helix_client.query("link_series_information", payload).await {
+                    Ok(result) => Ok(CallToolResult::structured(json!({
+                        "success": true,
+                        "operation": "create_series",
+                        "result": result
+                    }))),
+                    Err(e) => Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to create series relationship: {}", e), json!({})))
+                }
+            },
+            "create_reference" => {
+                let payload = json!({
+                    "referencing_info_id": p.from_info_id,
+                    "referenced_info_id": p.to_info_id,
+                    "reference_type": p.reference_type.as_ref().unwrap_or(&"citation".to_string()),
+                    "page_section": p.page_section.as_ref().unwrap_or(&"".to_string()),
+                    "notes": p.notes.as_ref().unwrap_or(&"".to_string())
+                });
+                match self.helix_client.query("link_reference_information", payload).await {
+                    Ok(result) => Ok(CallToolResult::structured(json!({
+                        "success": true,
+                        "operation": "create_reference",
+                        "result": result
+                    }))),
+                    Err(e) => Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to create reference relationship: {}", e), json!({})))
+                }
+            },
+            "link_to_product" => {
+                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for link_to_product".to_string(), None))?;
+                let payload = json!({
+                    "info_id": p.from_info_id,
+                    "product_id": target_business_id,
+                    "info_type": p.info_type.as_ref().unwrap_or(&"documentation".to_string()),
+                    "notes": p.notes.as_ref().unwrap_or(&"".to_string())
+                });
+                match self.helix_client.query("link_information_to_product", payload).await {
+                    Ok(result) => Ok(CallToolResult::structured(json!({
+                        "success": true,
+                        "operation": "link_to_product",
+                        "result": result
+                    }))),
+                    Err(e) => Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to link information to product: {}", e), json!({})))
+                }
+            },
+            "link_to_service" => {
+                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for link_to_service".to_string(), None))?;
+                let payload = json!({
+                    "info_id": p.from_info_id,
+                    "service_id": target_business_id,
+                    "info_type": p.info_type.as_ref().unwrap_or(&"documentation".to_string()),
+                    "notes": p.notes.as_ref().unwrap_or(&"".to_string())
+                });
+                match self.helix_client.query("link_information_to_service", payload).await {
+                    Ok(result) => Ok(CallToolResult::structured(json!({
+                        "success": true,
+                        "operation": "link_to_service",
+                        "result": result
+                    }))),
+                    Err(e) => Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to link information to service: {}", e), json!({})))
+                }
+            },
+            "link_to_location" => {
+                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for link_to_location".to_string(), None))?;
+                let payload = json!({
+                    "info_id": p.from_info_id,
+                    "location_id": target_business_id,
+                    "info_type": p.info_type.as_ref().unwrap_or(&"guide".to_string()),
+                    "notes": p.notes.as_ref().unwrap_or(&"".to_string())
+                });
+                match self.helix_client.query("link_information_to_location", payload).await {
+                    Ok(result) => Ok(CallToolResult::structured(json!({
+                        "success": true,
+                        "operation": "link_to_location",
+                        "result": result
+                    }))),
+                    Err(e) => Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to link information to location: {}", e), json!({})))
+                }
+            },
+            "link_to_event" => {
+                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for link_to_event".to_string(), None))?;
+                let payload = json!({
+                    "info_id": p.from_info_id,
+                    "event_id": target_business_id,
+                    "info_type": p.info_type.as_ref().unwrap_or(&"details".to_string()),
+                    "notes": p.notes.as_ref().unwrap_or(&"".to_string())
+                });
+                match self.helix_client.query("link_information_to_event", payload).await {
+                    Ok(result) => Ok(CallToolResult::structured(json!({
+                        "success": true,
+                        "operation": "link_to_event",
+                        "result": result
+                    }))),
+                    Err(e) => Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to link information to event: {}", e), json!({})))
                 }
-            }
-
-            return Ok(CallToolResult::structured(json!({
-                "query": query,
-                "search_types": search_types,
-                "total_results": all_results.len(),
-                "limit": limit,
-                "embedding_mode": "helixdb",
-                "results": all_results
-            })));
+            },
+            _ => Err(McpError::invalid_params(format!("Unknown operation: {}", p.operation), None)),
         }
+    }
 
-        // MCP mode: Generate embedding
-        if self.config.is_mcp_embedding_enabled() {
-            info!("Using MCP embedding mode");
-            
-            let api_key = self.config.get_api_key().unwrap_or_default();
-            
-            let query_embedding = match self.generate_embedding(query, &api_key).await {
-                Ok(embedding) => {
-                    info!("✓ Generated embedding vector with {} dimensions", embedding.len());
-                    embedding
+    // ========================================================================
+    // QUERY INFORMATION RELATIONSHIPS - Retrieve Network of Knowledge
+    // ========================================================================
+
+    #[tool(description = "Query information relationships - retrieve links between information documents and business assets. Specify the operation and provide the required parameters: get_related requires from_info_id, get_prerequisites requires from_info_id, get_series requires series_name, get_product_info requires target_business_id, get_service_info requires target_business_id, get_location_info requires target_business_id, get_event_info requires target_business_id.")]
+    async fn query_information_relationships(&self, params: Parameters<QueryInformationRelationshipsParam>) -> Result<CallToolResult, McpError> {
+        let p = &params.0;
+        match p.operation.as_str() {
+            "get_related" => {
+                let from_info_id = p.from_info_id.as_ref().ok_or_else(|| McpError::invalid_params("from_info_id is required for get_related".to_string(), None))?;
+                let payload = json!({"info_id": from_info_id});
+                match self.helix_client.query("get_related_information", payload).await {
+                    Ok(result) => Ok(CallToolResult::structured(json!({
+                        "operation": "get_related",
+                        "data": result
+                    }))),
+                    Err(e) => Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get related information: {}", e), json!({})))
                 }
-                Err(e) => {
-                    error!("✗ Failed to generate embedding: {}", e);
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Embedding generation failed: {}", e),
-                        "suggestion": "Check API key, network connection, and server status"
-                    })));
+            },
+            "get_prerequisites" => {
+                let from_info_id = p.from_info_id.as_ref().ok_or_else(|| McpError::invalid_params("from_info_id is required for get_prerequisites".to_string(), None))?;
+                let payload = json!({"info_id": from_info_id});
+                match self.helix_client.query("get_prerequisites_for_info", payload).await {
+                    Ok(result) => Ok(CallToolResult::structured(json!({
+                        "operation": "get_prerequisites",
+                        "data": result
+                    }))),
+                    Err(e) => Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get prerequisites: {}", e), json!({})))
                 }
-            };
-
-            let mut all_results = Vec::new();
-            
-            for search_type in search_types {
-                let query_name = match search_type.as_str() {
-                    "hubs" => "search_navigation_hubs",
-                    "waypoints" => "search_navigation_waypoints",
-                    "paths" => "search_direction_paths",
-                    _ => {
-                        info!("Skipping unsupported search type: {}", search_type);
-                        continue;
-                    }
-                };
-
-                let mut payload = json!({
-                    "query_embedding": query_embedding,
-                    "limit": limit,
-                });
-
-                if let Some(business_id) = &params.0.business_id {
-                    payload["business_id"] = json!(business_id);
+            },
+            "get_series" => {
+                let series_name = p.series_name.as_ref().ok_or_else(|| McpError::invalid_params("series_name is required for get_series".to_string(), None))?;
+                let payload = json!({"series_name": series_name});
+                match self.helix_client.query("get_series_information", payload).await {
+                    Ok(result) => Ok(CallToolResult::structured(json!({
+                        "operation": "get_series",
+                        "data": result
+                    }))),
+                    Err(e) => Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get series information: {}", e), json!({})))
                 }
-
-                match self.helix_client.query(query_name, payload).await {
-                    Ok(results) => {
-                        if let Some(array) = results.as_array() {
-                            all_results.extend(array.iter().cloned());
-                        }
-                    }
-                    Err(e) => {
-                        error!("Navigation search failed for {}: {}", search_type, e);
-                    }
+            },
+            "get_product_info" => {
+                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for get_product_info".to_string(), None))?;
+                let payload = json!({"product_id": target_business_id});
+                match self.helix_client.query("get_product_information", payload).await {
+                    Ok(result) => Ok(CallToolResult::structured(json!({
+                        "operation": "get_product_info",
+                        "data": result
+                    }))),
+                    Err(e) => Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get product information: {}", e), json!({})))
                 }
-            }
-
-            return Ok(CallToolResult::structured(json!({
-                "query": query,
-                "search_types": search_types,
-                "total_results": all_results.len(),
-                "limit": limit,
-                "embedding_mode": "mcp",
-                "results": all_results
-            })));
+            },
+            "get_service_info" => {
+                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for get_service_info".to_string(), None))?;
+                let payload = json!({"service_id": target_business_id});
+                match self.helix_client.query("get_service_information", payload).await {
+                    Ok(result) => Ok(CallToolResult::structured(json!({
+                        "operation": "get_service_info",
+                        "data": result
+                    }))),
+                    Err(e) => Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get service information: {}", e), json!({})))
+                }
+            },
+            "get_location_info" => {
+                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for get_location_info".to_string(), None))?;
+                let payload = json!({"location_id": target_business_id});
+                match self.helix_client.query("get_location_information", payload).await {
+                    Ok(result) => Ok(CallToolResult::structured(json!({
+                        "operation": "get_location_info",
+                        "data": result
+                    }))),
+                    Err(e) => Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get location information: {}", e), json!({})))
+                }
+            },
+            "get_event_info" => {
+                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for get_event_info".to_string(), None))?;
+                let payload = json!({"event_id": target_business_id});
+                match self.helix_client.query("get_event_information", payload).await {
+                    Ok(result) => Ok(CallToolResult::structured(json!({
+                        "operation": "get_event_info",
+                        "data": result
+                    }))),
+                    Err(e) => Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to get event information: {}", e), json!({})))
+                }
+            },
+            _ => Err(McpError::invalid_params(format!("Unknown operation: {}", p.operation), None)),
         }
-
-        Ok(CallToolResult::structured_error(json!({
-            "error": "Invalid embedding configuration. Check mcpconfig.toml"
-        })))
     }
 
     // ========================================================================
-    // UPDATE TOOLS - Modify existing memories
+    // IMPORT TOOLS - Migrate memories from other agent memory systems
     // ========================================================================
 
-    #[tool(description = "Update existing business memory (products, services, locations, hours, social media, policies, events, information such as documentation/manuals). REQUIRED: memory_id (internal UUID from database node), memory_type, updates dict with: business_id, entity-specific ID (e.g., product_id from query), text_description for embedding regeneration. Get internal ID using query_business_memory.")]
-    async fn update_business_memory(&self, params: Parameters<UpdateBusinessMemoryParam>) -> Result<CallToolResult, McpError> {
-        let memory_id = &params.0.memory_id;
-        let memory_type_input = &params.0.memory_type;
-        let updates = &params.0.updates;
-        
-        // Normalize memory_type (accept both "products" and "product")
-        let memory_type = Self::normalize_memory_type(memory_type_input);
-        
-        info!("update_business_memory: memory_id={}, type={} (normalized from: {})", memory_id, memory_type, memory_type_input);
-
-        // Note: If product doesn't exist, query_business_memory returns empty array, so no special error handling needed here
-        // The update query will fail naturally if memory_id doesn't exist
-
-        // Extract composite_text from updates (required for vector-aware update queries)
-        let composite_text = updates.get("composite_text")
-            .or(updates.get("text_description"))
-            .or(updates.get("description"))
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| McpError::invalid_request(
-                "Missing required field: composite_text (or text_description/description)", 
-                None
-            ))?;
-
-        // Extract business_id (required for all business memory types)
-        let business_id = updates.get("business_id")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| McpError::invalid_request(
-                "Missing required field: business_id in updates", 
-                None
-            ))?;
-
-        // Extract entity-specific ID based on memory type
-        let (entity_id_field, query_name) = match memory_type {
-            "product" => ("product_id", "update_business_product_memory"),
-            "service" => ("service_id", "update_business_service_memory"),
-            "location" => ("location_id", "update_business_location_memory"),
-            "hours" => ("hours_id", "update_business_hours_memory"),
-            "social" => ("social_id", "update_business_social_memory"),
-            "policy" => ("policy_id", "update_business_policy_memory"),
-            "event" => ("event_id", "update_business_event_memory"),
-            "information" => ("info_id", "update_business_information_memory"),
-            _ => {
-                return Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Invalid memory_type: {}. Valid: product, service, location, hours, social, policy, event, information", memory_type)
-                })));
-            }
+    #[tool(description = "Import memories from other agent memory systems - map mem0, Zep, or LangChain memory exports onto customer memories (facts -> behavior/preference/desire/rule/feedback) and communication memories (chat histories). REQUIRED: source ('mem0', 'zep', 'langchain'), customer_id, export (the exported JSON document). Optional: business_id (required to import chat histories), default_memory_type (default 'preference').")]
+    async fn import_memories(&self, params: Parameters<ImportMemoriesParam>) -> Result<CallToolResult, McpError> {
+        let customer_id = &params.0.customer_id;
+        let Some(source) = importers::ImportSource::parse(&params.0.source) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Unsupported import source: {}", params.0.source), json!({
+                "supported_sources": ["mem0", "zep", "langchain"]
+            })));
         };
+        let default_memory_type = params.0.default_memory_type.as_deref().unwrap_or("preference");
 
-        let entity_id = updates.get(entity_id_field)
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                let msg = format!("Missing required field: {} in updates", entity_id_field);
-                McpError::invalid_request(msg, None)
-            })?;
+        let records = importers::parse_export(source, &params.0.export, default_memory_type);
+        info!("import_memories: source={:?}, customer_id={}, records={}", source, customer_id, records.len());
 
-        info!("Updating {} with business_id={}, {}={}", memory_type, business_id, entity_id_field, entity_id);
+        if records.is_empty() {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "No importable records found in export", json!({
+                "source": params.0.source,
+                "suggestion": "Check that the export matches the source system's JSON format"
+            })));
+        }
 
-        // Generate embedding based on mode
-        let new_embedding = if self.config.is_mcp_embedding_enabled() {
-            // MCP Mode: Generate embedding via OpenAI/Gemini/Local/TCP
-            info!("MCP mode: Generating new embedding for {} {}", memory_type, entity_id);
-            let api_key = self.config.get_api_key().unwrap_or_default();
-            
-            match self.generate_embedding(composite_text, &api_key).await {
-                Ok(emb) => emb,
-                Err(e) => {
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to generate embedding: {}", e)
-                    })));
+        let mut memories_created = 0;
+        let mut conversations_stored = 0;
+        let mut failures = Vec::new();
+
+        for record in records {
+            let (label, result) = match record {
+                importers::ImportedRecord::Memory { memory_type, text, external_id, created_at } => {
+                    let mut data = json!({});
+                    if let Some(created_at) = created_at {
+                        data["created_at"] = json!(created_at);
+                    }
+                    let result = priority::background(self.create_customer_memory(Parameters(CreateCustomerMemoryParam {
+                        customer_id: customer_id.clone(),
+                        memory_type,
+                        text_description: text,
+                        data,
+                        dedup: None,
+                    }))).await?;
+                    if result.is_error != Some(true) {
+                        memories_created += 1;
+                    }
+                    (external_id.unwrap_or_else(|| "memory".to_string()), result)
+                }
+                importers::ImportedRecord::Conversation { session_id, transcript } => {
+                    let Some(business_id) = &params.0.business_id else {
+                        failures.push(json!({
+                            "record": session_id.unwrap_or_else(|| "conversation".to_string()),
+                            "error": "business_id is required to import chat histories"
+                        }));
+                        continue;
+                    };
+                    let result = priority::background(self.store_conversation_summary(Parameters(StoreConversationSummaryParam {
+                        customer_id: customer_id.clone(),
+                        business_id: business_id.clone(),
+                        transcript,
+                        session_id: session_id.clone(),
+                        contact_method: Some("chat".to_string()),
+                        contact_reason: None,
+                        agent_id: None,
+                        max_chunk_chars: None,
+                    }))).await?;
+                    if result.is_error != Some(true) {
+                        conversations_stored += 1;
+                    }
+                    (session_id.unwrap_or_else(|| "conversation".to_string()), result)
                 }
+            };
+
+            if result.is_error == Some(true) {
+                failures.push(json!({
+                    "record": label,
+                    "error": result.structured_content.and_then(|c| c.get("error").cloned())
+                }));
             }
-        } else {
-            // HelixDB Mode: Use empty vector (HelixDB will generate via Embed() function)
-            info!("HelixDB mode: Using empty embedding placeholder for {} {}", memory_type, entity_id);
-            vec![]
-        };
+        }
 
-        // Build payload for vector-aware update query
-        let timestamp = chrono::Utc::now().timestamp();
-        let payload = json!({
-            "memory_id": memory_id,
-            "business_id": business_id,
-            entity_id_field: entity_id,
-            "composite_text": composite_text,
-            "new_embedding": new_embedding,
-            "timestamp": timestamp
-        });
+        info!("✓ Imported {} memories and {} conversations ({} failures)", memories_created, conversations_stored, failures.len());
 
-        // Execute vector-aware update query (DROP old vector + CREATE new one)
-        match self.helix_client.query(query_name, payload).await {
-            Ok(result) => {
-                Ok(CallToolResult::structured(json!({
-                    "success": true,
-                    "memory_type": memory_type,
-                    "business_id": business_id,
-                    entity_id_field: entity_id,
-                    "query_used": query_name,
-                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
-                    "updated_at": timestamp,
-                    "result": result
-                })))
+        Ok(CallToolResult::structured(json!({
+            "success": failures.is_empty(),
+            "source": params.0.source,
+            "customer_id": customer_id,
+            "memories_created": memories_created,
+            "conversations_stored": conversations_stored,
+            "failed": failures.len(),
+            "failures": failures
+        })))
+    }
+
+    /// Store one event from `POST /ingest` through the create tool for its record
+    pub async fn ingest_event(&self, event: &ingest::Event, record: ingest::Record) -> Result<CallToolResult, McpError> {
+        let customer_id = event.customer_id.clone();
+        let text = event.summary();
+        match record {
+            ingest::Record::ProductInteraction { product_id, interaction_type } => {
+                self.create_customer_product_interaction(Parameters(CreateCustomerProductInteractionParam {
+                    customer_id,
+                    product_id,
+                    interaction_type: interaction_type.to_string(),
+                    rating: event.rating,
+                    channel: event.channel.clone(),
+                    session_duration: None,
+                    purchase_amount: event.amount,
+                    currency: event.currency.clone(),
+                    issue_category: None,
+                    resolution_status: None,
+                    text_reason: text,
+                })).await
             }
-            Err(e) => {
-                error!("update_business_memory failed: {}", e);
-                Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Failed to update {} memory: {}", memory_type, e),
-                    "query_used": query_name
-                })))
+            ingest::Record::ServiceInteraction { service_id, interaction_type } => {
+                self.create_customer_service_interaction(Parameters(CreateCustomerServiceInteractionParam {
+                    customer_id,
+                    service_id,
+                    interaction_type: interaction_type.to_string(),
+                    satisfaction_rating: event.rating,
+                    duration_actual: None,
+                    cost_actual: event.amount,
+                    currency: event.currency.clone(),
+                    outcome: None,
+                    text_feedback: text,
+                })).await
+            }
+            ingest::Record::Behavior { behavior_type, action } => {
+                self.create_customer_memory(Parameters(CreateCustomerMemoryParam {
+                    customer_id,
+                    memory_type: "behavior".to_string(),
+                    text_description: text,
+                    data: json!({
+                        "behavior_type": behavior_type,
+                        "action": action,
+                        "channel": event.channel.as_deref().unwrap_or("website"),
+                        "metadata": json!({"url": event.url, "event_id": event.event_id}).to_string()
+                    }),
+                    dedup: Some(false),
+                })).await
             }
         }
     }
 
-    #[tool(description = "Update existing customer memory (behaviors, preferences, desires, rules, feedback, communication). REQUIRED: memory_id (internal UUID from database node), memory_type, updates dict with text_description for embedding regeneration. Get internal ID using query_customer_memory.")]
-    async fn update_customer_memory(&self, params: Parameters<UpdateCustomerMemoryParam>) -> Result<CallToolResult, McpError> {
-        let memory_id = &params.0.memory_id;
-        let memory_type_input = &params.0.memory_type;
-        let updates = &params.0.updates;
-        
-        // Normalize memory_type (accept both "behaviors" and "behavior")
-        let memory_type = Self::normalize_memory_type(memory_type_input);
-        
-        info!("update_customer_memory: memory_id={}, type={} (normalized from: {})", memory_id, memory_type, memory_type_input);
+    // ========================================================================
+    // EXPORT TOOLS - Vector-store-agnostic dataset export
+    // ========================================================================
 
-        // Extract composite_text from updates (required for vector-aware update queries)
-        let composite_text = updates.get("composite_text")
-            .or(updates.get("text_description"))
-            .or(updates.get("context"))
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| McpError::invalid_request(
-                "Missing required field: composite_text (or text_description/context)", 
-                None
-            ))?;
+    /// Write an export tool's output to `file_name` inside the configured export directory
+    async fn write_export(&self, file_name: &str, contents: &[u8]) -> Result<std::path::PathBuf, String> {
+        let directory = std::path::Path::new(&self.config.export.directory);
+        let path = export::path_for(directory, file_name)?;
+        tokio::fs::create_dir_all(directory).await.map_err(|e| e.to_string())?;
+        tokio::fs::write(&path, contents).await.map_err(|e| e.to_string())?;
+        Ok(path)
+    }
 
-        // Route to appropriate vector-aware update query
-        let query_name = match memory_type {
-            "preference" => "update_customer_preference_memory",
-            "behavior" => "update_customer_behavior_memory",
-            "desire" => "update_customer_desire_memory",
-            "rule" => "update_customer_rule_memory",
-            "feedback" => "update_customer_feedback_memory",
-            "communication" => "update_customer_communication_memory",
-            _ => {
-                return Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Invalid memory_type: {}. Valid: preference, behavior, desire, rule, feedback, communication", memory_type)
-                })));
-            }
-        };
+    #[tool(description = "Export embedding dataset - export stored embeddings as a generic vector dataset (JSON Lines records with id, text, vector, metadata) for evaluation or migration to other vector stores. REQUIRED: memory_types (products, services, locations, hours, social, policies, events, information, behaviors, preferences, desires, rules, feedback, communications), business_id for business types, customer_id for customer types. Optional: format ('jsonl' or 'csv'), output_path (a file name in the server's export directory) to write a file instead of returning the dataset inline, encrypt to AES-256-GCM encrypt the archive with the configured export key.")]
+    async fn export_embedding_dataset(&self, params: Parameters<ExportEmbeddingDatasetParam>) -> Result<CallToolResult, McpError> {
+        let format = params.0.format.as_deref().unwrap_or("jsonl");
+        if !matches!(format, "jsonl" | "csv") {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Unsupported export format: {}", format), json!({
+                "supported_formats": ["jsonl", "csv"]
+            })));
+        }
 
-        // Generate embedding based on mode
-        let new_embedding = if self.config.is_mcp_embedding_enabled() {
-            // MCP Mode: Generate embedding via OpenAI/Gemini/Local/TCP
-            info!("MCP mode: Generating new embedding for {} {}", memory_type, memory_id);
-            let api_key = self.config.get_api_key().unwrap_or_default();
-            
-            match self.generate_embedding(composite_text, &api_key).await {
-                Ok(emb) => emb,
-                Err(e) => {
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to generate embedding: {}", e)
-                    })));
-                }
-            }
-        } else {
-            // HelixDB Mode: Use empty vector (HelixDB will generate via Embed() function)
-            info!("HelixDB mode: Using empty embedding placeholder for {} {}", memory_type, memory_id);
-            vec![]
-        };
+        let memory_types: Vec<&str> = params.0.memory_types
+            .iter()
+            .map(|t| Self::normalize_to_plural(t.as_str()))
+            .collect();
 
-        // Build payload for vector-aware update query
-        let timestamp = chrono::Utc::now().timestamp();
-        let payload = json!({
-            "memory_id": memory_id,
-            "composite_text": composite_text,
-            "new_embedding": new_embedding,
-            "timestamp": timestamp
-        });
+        info!("export_embedding_dataset: types={:?}, business_id={:?}, customer_id={:?}", memory_types, params.0.business_id, params.0.customer_id);
 
-        // Execute vector-aware update query (DROP old vector + CREATE new one)
-        match self.helix_client.query(query_name, payload).await {
-            Ok(result) => {
-                Ok(CallToolResult::structured(json!({
-                    "success": true,
-                    "memory_type": memory_type,
-                    "memory_id": memory_id,
-                    "query_used": query_name,
-                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
-                    "updated_at": timestamp,
-                    "result": result
-                })))
-            }
-            Err(e) => {
-                error!("update_customer_memory failed: {}", e);
-                Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Failed to update {} memory: {}", memory_type, e),
-                    "query_used": query_name
-                })))
+        let mut records = Vec::new();
+        let mut counts = serde_json::Map::new();
+        let mut skipped = Vec::new();
+
+        for memory_type in &memory_types {
+            let Some((query_name, owner)) = export::export_query(memory_type) else {
+                skipped.push(json!({"memory_type": memory_type, "reason": "unsupported memory type"}));
+                continue;
+            };
+
+            let (owner_field, owner_id) = match owner {
+                export::ExportOwner::Business => ("business_id", params.0.business_id.as_ref()),
+                export::ExportOwner::Customer => ("customer_id", params.0.customer_id.as_ref()),
+            };
+            let Some(owner_id) = owner_id else {
+                skipped.push(json!({"memory_type": memory_type, "reason": format!("{} is required", owner_field)}));
+                continue;
+            };
+
+            match self.helix_client.query(query_name, json!({ owner_field: owner_id })).await {
+                Ok(result) => {
+                    let type_records: Vec<_> = HelixClient::result_items(&result)
+                        .iter()
+                        .filter_map(|item| export::record_from_vector(item, memory_type, owner_field, owner_id))
+                        .collect();
+                    counts.insert(memory_type.to_string(), json!(type_records.len()));
+                    records.extend(type_records);
+                }
+                Err(e) => {
+                    error!("Embedding export failed for {}: {}", memory_type, e);
+                    skipped.push(json!({"memory_type": memory_type, "reason": e.to_string()}));
+                }
             }
         }
-    }
 
-    #[tool(description = "Update customer interaction (product or service). REQUIRED: interaction_id (internal UUID from database node), interaction_type, composite_text for embedding regeneration. Get internal ID using query_customer_interactions.")]
-    async fn update_interaction(&self, params: Parameters<UpdateInteractionParam>) -> Result<CallToolResult, McpError> {
-        let interaction_id = &params.0.interaction_id;
-        let interaction_type_input = &params.0.interaction_type;
-        let composite_text = &params.0.composite_text;
-        
-        // Normalize to singular (accept both "products" and "product")
-        let interaction_type = Self::normalize_memory_type(interaction_type_input);
-        
-        info!("update_interaction: interaction_id={}, type={} (normalized from: {})", interaction_id, interaction_type, interaction_type_input);
+        let dataset = if format == "csv" { export::records_to_csv(&records) } else { export::to_jsonl(&records) };
 
-        // Route to appropriate vector-aware update query
-        let query_name = match interaction_type {
-            "product" => "update_customer_product_interaction_memory",
-            "service" => "update_customer_service_interaction_memory",
-            _ => {
-                return Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Invalid interaction_type: {}. Valid: product, service", interaction_type)
+        // Optionally encrypt so archives with customer PII never hit disk in plaintext
+        let encrypted = params.0.encrypt.unwrap_or(self.config.export.encrypt_by_default);
+        let archive = if encrypted {
+            let Some(key) = self.config.get_export_key() else {
+                return Ok(tool_error(ErrorCode::Disabled, "Encryption requested but no export key is configured", json!({
+                    "suggestion": "Set encryption_key in the [export] section of mcpconfig.toml or the HELIX_EXPORT_KEY environment variable"
                 })));
+            };
+            match crypto::encrypt_archive(dataset.as_bytes(), &key) {
+                Ok(archive) => archive,
+                Err(e) => {
+                    error!("Failed to encrypt export: {}", e);
+                    return Ok(tool_error(ErrorCode::Internal, e, json!({})));
+                }
             }
+        } else {
+            dataset.into_bytes()
         };
 
-        // Generate embedding based on mode
-        let new_embedding = if self.config.is_mcp_embedding_enabled() {
-            info!("MCP mode: Generating new embedding for {} interaction {}", interaction_type, interaction_id);
-            let api_key = self.config.get_api_key().unwrap_or_default();
-            
-            match self.generate_embedding(composite_text, &api_key).await {
-                Ok(emb) => emb,
+        if let Some(output_path) = &params.0.output_path {
+            let output_path = match self.write_export(output_path, &archive).await {
+                Ok(path) => path.display().to_string(),
                 Err(e) => {
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to generate embedding: {}", e)
+                    error!("Failed to write export file {}: {}", output_path, e);
+                    return Ok(tool_error(ErrorCode::Internal, format!("Failed to write export file: {}", e), json!({
+                        "output_path": output_path
                     })));
                 }
-            }
+            };
+            info!("✓ Exported {} embedding records to {}", records.len(), output_path);
+
+            return Ok(CallToolResult::structured(json!({
+                "success": true,
+                "format": format,
+                "encrypted": encrypted,
+                "record_count": records.len(),
+                "counts": counts,
+                "skipped": skipped,
+                "output_path": output_path
+            })));
+        }
+
+        // Encrypted archives are returned base64-encoded
+        let dataset = if encrypted {
+            crypto::to_base64(&archive)
         } else {
-            info!("HelixDB mode: Using empty embedding placeholder for {} interaction {}", interaction_type, interaction_id);
-            vec![]
+            String::from_utf8_lossy(&archive).into_owned()
         };
 
-        // Build payload for vector-aware update query
-        let timestamp = chrono::Utc::now().timestamp();
-        let payload = json!({
-            "memory_id": interaction_id,
-            "composite_text": composite_text,
-            "new_embedding": new_embedding,
-            "timestamp": timestamp
-        });
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "format": format,
+            "encrypted": encrypted,
+            "record_count": records.len(),
+            "counts": counts,
+            "skipped": skipped,
+            "dataset": dataset
+        })))
+    }
 
-        // Execute vector-aware update query
-        match self.helix_client.query(query_name, payload).await {
-            Ok(result) => {
-                Ok(CallToolResult::structured(json!({
-                    "success": true,
-                    "interaction_type": interaction_type,
-                    "interaction_id": interaction_id,
-                    "query_used": query_name,
-                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
-                    "updated_at": timestamp,
-                    "result": result
-                })))
-            }
+    #[tool(description = "Export customer data - compile everything stored about one customer (profile, linked businesses, preferences, behaviors, desires, rules, feedback, communications, product/service interactions, location visits and derived insights) for subject-access requests, as a human-readable Markdown report, one structured JSON document or a CSV table with one row per record (embeddings excluded). REQUIRED: customer_id. Optional: format (markdown, json or csv, default markdown), output_path (a file name in the server's export directory), encrypt.")]
+    async fn export_customer_data(&self, params: Parameters<ExportCustomerDataParam>) -> Result<CallToolResult, McpError> {
+        let customer_id = &params.0.customer_id;
+        let format = params.0.format.as_deref().unwrap_or("markdown");
+        let Some(report_format) = subject_access::Format::parse(format) else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Unsupported report format: {}", format), json!({
+                "supported_formats": ["markdown", "json", "csv"]
+            })));
+        };
+        info!("export_customer_data: customer_id={}", customer_id);
+
+        let profile = match self.helix_client.query("get_customer_profile", json!({"customer_id": customer_id})).await {
+            Ok(result) => result,
             Err(e) => {
-                error!("update_interaction failed: {}", e);
-                Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Failed to update {} interaction: {}", interaction_type, e),
-                    "query_used": query_name
-                })))
+                error!("export_customer_data profile lookup failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to load customer profile: {}", e), json!({})));
+            }
+        };
+
+        let sources = [
+            ("Preferences", "get_customer_preferences"),
+            ("Behaviors", "get_customer_behaviors"),
+            ("Desires", "get_customer_desires"),
+            ("Rules", "get_customer_rules"),
+            ("Feedback", "get_customer_feedback"),
+            ("Communications", "get_customer_communications"),
+            ("Product interactions", "get_customer_product_interactions"),
+            ("Service interactions", "get_customer_service_interactions"),
+            ("Location visits", "get_customer_location_visits"),
+        ];
+        let mut sections = Vec::new();
+        let mut failed = Vec::new();
+        for (title, query_name) in sources {
+            match self.helix_client.query(query_name, json!({"customer_id": customer_id})).await {
+                Ok(result) => sections.push(subject_access::Section { title, records: HelixClient::result_items(&result) }),
+                Err(e) => {
+                    // An incomplete report must say so rather than silently omit data
+                    error!("export_customer_data {} failed: {}", query_name, e);
+                    failed.push(query_name);
+                }
             }
         }
-    }
+        if !failed.is_empty() {
+            return Ok(tool_error(ErrorCode::HelixUnreachable, "Could not load all customer data; refusing to produce an incomplete report", json!({
+                "failed_queries": failed
+            })));
+        }
 
-    #[tool(description = "Update navigation memory (hub, waypoint, path). REQUIRED: memory_id (internal UUID from database node), navigation_type, composite_text for embedding regeneration. Get internal ID using query_navigation.")]
-    async fn update_navigation(&self, params: Parameters<UpdateNavigationParam>) -> Result<CallToolResult, McpError> {
-        let memory_id = &params.0.memory_id;
-        let navigation_type = &params.0.navigation_type;
-        let composite_text = &params.0.composite_text;
-        
-        info!("update_navigation: memory_id={}, type={}", memory_id, navigation_type);
+        let records = |title: &str| sections.iter().find(|s| s.title == title).map(|s| s.records.as_slice()).unwrap_or_default();
+        let insights = json!({
+            "interactions": analytics::summarize_interactions(records("Product interactions"), records("Service interactions"), analytics::TimeBucket::Month, 5),
+            "feedback": analytics::summarize_feedback(records("Feedback"), 0)
+        });
 
-        // Route to appropriate vector-aware update query
-        let query_name = match navigation_type.as_str() {
-            "hub" => "update_business_navigation_hub_memory",
-            "waypoint" => "update_navigation_waypoint_memory",
-            "path" => "update_direction_path_memory",
-            _ => {
-                return Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Invalid navigation_type: {}. Valid: hub, waypoint, path", navigation_type)
+        let report = subject_access::CustomerReport {
+            customer_id: customer_id.clone(),
+            generated_at: self.clock.timestamp(),
+            profile: HelixClient::result_items(&profile["customer"]).into_iter().next(),
+            businesses: HelixClient::result_items(&profile["businesses"]),
+            sections,
+            insights,
+        };
+        let record_count = report.record_count();
+        let (body, document) = report.render(report_format);
+
+        let encrypted = params.0.encrypt.unwrap_or(self.config.export.encrypt_by_default);
+        let archive = if encrypted {
+            let Some(key) = self.config.get_export_key() else {
+                return Ok(tool_error(ErrorCode::Disabled, "Encryption requested but no export key is configured", json!({
+                    "suggestion": "Set encryption_key in the [export] section of mcpconfig.toml or the HELIX_EXPORT_KEY environment variable"
                 })));
+            };
+            match crypto::encrypt_archive(body.as_bytes(), &key) {
+                Ok(archive) => archive,
+                Err(e) => return Ok(tool_error(ErrorCode::Internal, e, json!({}))),
             }
+        } else {
+            body.into_bytes()
         };
 
-        // Generate embedding based on mode
-        let new_embedding = if self.config.is_mcp_embedding_enabled() {
-            info!("MCP mode: Generating new embedding for navigation {} {}", navigation_type, memory_id);
-            let api_key = self.config.get_api_key().unwrap_or_default();
-            
-            match self.generate_embedding(composite_text, &api_key).await {
-                Ok(emb) => emb,
+        if let Some(output_path) = &params.0.output_path {
+            let output_path = match self.write_export(output_path, &archive).await {
+                Ok(path) => path.display().to_string(),
                 Err(e) => {
-                    return Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to generate embedding: {}", e)
+                    error!("Failed to write report {}: {}", output_path, e);
+                    return Ok(tool_error(ErrorCode::Internal, format!("Failed to write report: {}", e), json!({
+                        "output_path": output_path
                     })));
                 }
-            }
+            };
+            info!("✓ Exported subject-access report for {} ({} records) to {}", customer_id, record_count, output_path);
+            return Ok(CallToolResult::structured(json!({
+                "success": true,
+                "customer_id": customer_id,
+                "format": format,
+                "encrypted": encrypted,
+                "record_count": record_count,
+                "output_path": output_path
+            })));
+        }
+
+        // Unencrypted JSON goes inline as a document rather than a string
+        let report = if encrypted {
+            json!(crypto::to_base64(&archive))
         } else {
-            info!("HelixDB mode: Using empty embedding placeholder for navigation {} {}", navigation_type, memory_id);
-            vec![]
+            document.unwrap_or_else(|| json!(String::from_utf8_lossy(&archive)))
         };
 
-        // Build payload for vector-aware update query
-        let timestamp = chrono::Utc::now().timestamp();
-        let payload = json!({
-            "memory_id": memory_id,
-            "composite_text": composite_text,
-            "new_embedding": new_embedding,
-            "timestamp": timestamp
-        });
-
-        // Execute vector-aware update query
-        match self.helix_client.query(query_name, payload).await {
-            Ok(result) => {
-                Ok(CallToolResult::structured(json!({
-                    "success": true,
-                    "navigation_type": navigation_type,
-                    "memory_id": memory_id,
-                    "query_used": query_name,
-                    "embedding_mode": if self.config.is_mcp_embedding_enabled() { "mcp" } else { "helixdb" },
-                    "updated_at": timestamp,
-                    "result": result
-                })))
-            }
-            Err(e) => {
-                error!("update_navigation failed: {}", e);
-                Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Failed to update navigation {}: {}", navigation_type, e),
-                    "query_used": query_name
-                })))
-            }
-        }
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "customer_id": customer_id,
+            "format": format,
+            "encrypted": encrypted,
+            "record_count": record_count,
+            "report": report
+        })))
     }
 
-    // ========================================================================
-    // DELETE TOOLS - Remove memories
-    // ========================================================================
-
-    #[tool(description = "Delete memory (products, services, locations, hours, social, policy, event, information such as documentation/manuals, behaviors, preferences, desires, rules, feedback, business, customer). REQUIRED: memory_id (internal UUID from database node), memory_type. Get internal ID using appropriate query tool (query_business_memory, query_customer_memory, etc.).")]
-    async fn delete_memory(&self, params: Parameters<DeleteMemoryParam>) -> Result<CallToolResult, McpError> {
-        let memory_id = &params.0.memory_id;
-        let memory_type_input = &params.0.memory_type;
-        let delete_strategy = params.0.delete_strategy.as_ref()
-            .map(|s| s.as_str())
-            .unwrap_or_else(|| {
-                // Backward compatibility: if delete_embedding is specified, use it
-                if params.0.delete_embedding.unwrap_or(true) {
-                    "with_embedding"
-                } else {
-                    "node_only"
-                }
-            });
-        
-        // Normalize memory_type (accept both "products" and "product")
-        let memory_type = Self::normalize_memory_type(memory_type_input);
-        
-        info!("delete_memory: memory_id={}, type={} (normalized from: {}), strategy={}", memory_id, memory_type, memory_type_input, delete_strategy);
-
-        // Handle cascade and complete deletion strategies
-        match delete_strategy {
-            "cascade" => {
-                // Delete all memories for a business or customer
-                let query_name = match memory_type {
-                    "business" => "delete_all_business_memories",
-                    "customer" => "delete_all_customer_memories",
-                    _ => {
-                        return Ok(CallToolResult::structured_error(json!({
-                            "error": "Cascade delete strategy only valid for memory_type: business, customer"
-                        })));
-                    }
-                };
-
-                let payload = json!({
-                    format!("{}_id", memory_type): memory_id
-                });
+    #[tool(description = "Export graph visualization - write a business's or customer's subgraph (the root node, its memories, linked customers/businesses, interactions and the products/services/locations they point at) as GraphViz DOT or Cytoscape.js JSON for rendering in external tools, with nodes labeled by memory type and name and edges by relationship. REQUIRED: business_id or customer_id. Optional: format (dot or cytoscape, default dot), memory_types (node types to include), max_nodes (default 500), output_path (a file name in the server's export directory).")]
+    async fn export_graph_viz(&self, params: Parameters<ExportGraphVizParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let (root_type, query_name, root_id) = match (&p.business_id, &p.customer_id) {
+            (Some(business_id), None) => ("business", "graph_business", business_id),
+            (None, Some(customer_id)) => ("customer", "graph_customer", customer_id),
+            _ => return Ok(tool_error(ErrorCode::ValidationFailed, "Provide exactly one of business_id or customer_id", json!({}))),
+        };
+        let format = p.format.as_deref().unwrap_or("dot");
+        if !matches!(format, "dot" | "cytoscape") {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Unsupported graph format: {}", format), json!({
+                "supported_formats": ["dot", "cytoscape"]
+            })));
+        }
+        let valid_types = graph_viz::memory_types();
+        let memory_types: Option<Vec<&str>> = p.memory_types.as_ref().map(|types| {
+            let mut types: Vec<&str> = types.iter().map(|t| memory_types::normalize(t)).collect();
+            types.push(root_type);
+            types
+        });
+        if let Some(unknown) = memory_types.iter().flatten().find(|t| !valid_types.contains(t)) {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid memory_type: {}", unknown), json!({
+                "valid_values": valid_types
+            })));
+        }
+        let id_field = format!("{}_id", root_type);
+        info!("export_graph_viz: {}={}, format={}", id_field, root_id, format);
 
-                match self.helix_client.query(query_name, payload).await {
-                    Ok(result) => {
-                        return Ok(CallToolResult::structured(json!({
-                            "success": true,
-                            "memory_type": memory_type,
-                            "memory_id": memory_id,
-                            "strategy": "cascade",
-                            "message": format!("Deleted all memories for {}", memory_type),
-                            "result": result
-                        })));
-                    }
-                    Err(e) => {
-                        error!("Cascade delete failed: {}", e);
-                        return Ok(CallToolResult::structured_error(json!({
-                            "error": format!("Failed to cascade delete: {}", e)
-                        })));
-                    }
-                }
+        let result = match self.helix_client.query(query_name, json!({ id_field.as_str(): root_id })).await {
+            Ok(result) => result,
+            Err(e) => {
+                error!("export_graph_viz failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to load {} graph: {}", root_type, e), json!({})));
             }
-            "complete" => {
-                // Delete entity and all associated memories
-                let query_name = match memory_type {
-                    "business" => "delete_business_complete",
-                    "customer" => "delete_customer_complete",
-                    _ => {
-                        return Ok(CallToolResult::structured_error(json!({
-                            "error": "Complete delete strategy only valid for memory_type: business, customer"
-                        })));
-                    }
-                };
+        };
+        if HelixClient::result_items(&result[root_type]).is_empty() {
+            return Ok(tool_error(ErrorCode::NotFound, format!("{} {} does not exist", root_type, root_id), json!({ id_field.as_str(): root_id })));
+        }
 
-                let payload = json!({
-                    format!("{}_id", memory_type): memory_id
-                });
+        let graph = graph_viz::Graph::from_result(&result, memory_types.as_deref(), p.max_nodes.unwrap_or(500).max(1));
+        let (graph_document, body) = match format {
+            "cytoscape" => {
+                let document = graph.to_cytoscape();
+                let body = serde_json::to_string_pretty(&document).unwrap_or_default();
+                (document, body)
+            }
+            _ => {
+                let dot = graph.to_dot(&format!("{} {}", root_type, root_id));
+                (json!(dot), dot)
+            }
+        };
+        let mut response = json!({
+            "success": true,
+            id_field.as_str(): root_id,
+            "format": format,
+            "node_count": graph.nodes.len(),
+            "edge_count": graph.edges.len(),
+            "truncated_nodes": graph.truncated_nodes,
+            "omitted_edges": graph.omitted_edges,
+            "summary": graph.summary()
+        });
 
-                match self.helix_client.query(query_name, payload).await {
-                    Ok(result) => {
-                        return Ok(CallToolResult::structured(json!({
-                            "success": true,
-                            "memory_type": memory_type,
-                            "memory_id": memory_id,
-                            "strategy": "complete",
-                            "message": format!("Completely deleted {} and all associated memories", memory_type),
-                            "result": result
-                        })));
-                    }
-                    Err(e) => {
-                        error!("Complete delete failed: {}", e);
-                        return Ok(CallToolResult::structured_error(json!({
-                            "error": format!("Failed to complete delete: {}", e)
-                        })));
-                    }
+        if let Some(output_path) = &p.output_path {
+            let output_path = match self.write_export(output_path, body.as_bytes()).await {
+                Ok(path) => path.display().to_string(),
+                Err(e) => {
+                    error!("Failed to write graph {}: {}", output_path, e);
+                    return Ok(tool_error(ErrorCode::Internal, format!("Failed to write graph: {}", e), json!({"output_path": output_path})));
                 }
-            }
-            _ => {}  // Fall through to normal delete
+            };
+            info!("✓ Exported {} graph for {} ({} nodes, {} edges) to {}", format, root_id, graph.nodes.len(), graph.edges.len(), output_path);
+            response["output_path"] = json!(output_path);
+        } else {
+            response["graph"] = graph_document;
         }
+        Ok(CallToolResult::structured(response))
+    }
 
-        // Normal delete (node_only or with_embedding)
-        let with_embedding = delete_strategy == "with_embedding";
-        
-        // Determine which delete query to use
-        let query_name = match memory_type {
-            "product" => if with_embedding { "delete_product_with_embedding" } else { "delete_product" },
-            "service" => if with_embedding { "delete_service_with_embedding" } else { "delete_service" },
-            "location" => if with_embedding { "delete_location_with_embedding" } else { "delete_location" },
-            "hours" => if with_embedding { "delete_hours_with_embedding" } else { "delete_hours" },
-            "social" => if with_embedding { "delete_social_with_embedding" } else { "delete_social" },
-            "policy" => if with_embedding { "delete_policy_with_embedding" } else { "delete_policy" },
-            "event" => if with_embedding { "delete_event_with_embedding" } else { "delete_event" },
-            "information" => if with_embedding { "delete_information_with_embedding" } else { "delete_information" },
-            "behavior" => if with_embedding { "delete_behavior_with_embedding" } else { "delete_behavior" },
-            "preference" => if with_embedding { "delete_preference_with_embedding" } else { "delete_preference" },
-            "desire" => if with_embedding { "delete_desire_with_embedding" } else { "delete_desire" },
-            "rule" => if with_embedding { "delete_rule_with_embedding" } else { "delete_rule" },
-            "feedback" => if with_embedding { "delete_feedback_with_embedding" } else { "delete_feedback" },
-            _ => {
-                return Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Invalid memory_type: {}. Valid types: product, service, location, hours, social, policy, event, information, behavior, preference, desire, rule, feedback, business, customer", memory_type)
+    #[tool(description = "Forget a customer (right to erasure) - permanently purge a customer and everything stored about them: all memories (behaviors, preferences, desires, rules, feedback), product/service interactions, location visits, communications and conversation summaries, loyalty points transactions, aliases of customer IDs merged into them, their embedding vectors and edges. Returns a deletion manifest (IDs and counts per record type, vectors, edges), verifies nothing is left and appends the manifest to the erasure audit trail. Use dry_run: true to see the manifest first. REQUIRED: customer_id. Optional: reason (request reference for the audit trail).")]
+    async fn forget_customer(&self, params: Parameters<ForgetCustomerParam>) -> Result<CallToolResult, McpError> {
+
+        let customer_id = &params.0.customer_id;
+        info!("forget_customer: customer_id={}", customer_id);
+
+        let inventory = match self.helix_client.query("preview_forget_customer", json!({"customer_id": customer_id})).await {
+            Ok(result) => result,
+            Err(e) => {
+                error!("forget_customer inventory failed: {}", e);
+                return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Failed to inventory customer data: {}", e), json!({
+                    "customer_id": customer_id
                 })));
             }
         };
+        let manifest = erasure::manifest(&inventory);
+        if manifest["totals"]["nodes"] == 0 {
+            return Ok(tool_error(ErrorCode::NotFound, format!("No data stored for customer {}", customer_id), json!({"customer_id": customer_id})));
+        }
 
-        // Create payload with memory_id
-        let payload = json!({
-            format!("{}_id", memory_type): memory_id
-        });
+        if let Err(e) = self.helix_client.query("forget_customer", json!({"customer_id": customer_id})).await {
+            error!("forget_customer purge failed: {}", e);
+            return Ok(tool_error(ErrorCode::HelixUnreachable, format!("Erasure failed and may be partial; retry to finish it: {}", e), json!({
+                "customer_id": customer_id,
+                "manifest": manifest
+            })));
+        }
+        if dry_run::active() {
+            return Ok(CallToolResult::structured(json!({"customer_id": customer_id, "manifest": manifest})));
+        }
 
-        // Execute the query
-        match self.helix_client.query(query_name, payload).await {
-            Ok(result) => {
-                Ok(CallToolResult::structured(json!({
-                    "success": true,
-                    "memory_type": memory_type,
-                    "memory_id": memory_id,
-                    "strategy": delete_strategy,
-                    "deleted_embedding": with_embedding,
-                    "result": result
-                })))
-            }
+        // Take a fresh inventory so the audit record states what was verified, not assumed
+        let remaining = match self.helix_client.query("preview_forget_customer", json!({"customer_id": customer_id})).await {
+            Ok(result) => Some(erasure::remaining(&result)),
             Err(e) => {
-                error!("delete_memory failed: {}", e);
-                Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Failed to delete {} memory: {}", memory_type, e)
-                })))
+                warn!("forget_customer verification failed: {}", e);
+                None
             }
+        };
+        let erasure_id = self.ids.next_id().to_string();
+        let erased_at = self.clock.timestamp();
+        let record = json!({
+            "erasure_id": erasure_id,
+            "customer_id": customer_id,
+            "erased_at": erased_at,
+            "session_id": self.session_id.as_ref(),
+            "reason": params.0.reason,
+            "verified": remaining == Some(0),
+            "remaining": remaining,
+            "manifest": manifest
+        });
+        let audit_path = &self.config.erasure.audit_path;
+        let audit_error = if audit_path.is_empty() {
+            None
+        } else {
+            erasure::append_audit(std::path::Path::new(audit_path), &record).err().map(|e| {
+                error!("Failed to append erasure audit {}: {}", audit_path, e);
+                e.to_string()
+            })
+        };
+        info!("✓ Erased customer {} ({} nodes, {} vectors)", customer_id, manifest["totals"]["nodes"], manifest["totals"]["vectors"]);
+
+        let mut result = record;
+        result["success"] = json!(remaining == Some(0));
+        if remaining.is_some_and(|n| n > 0) {
+            result["warning"] = json!("Some records were still present after the purge; run forget_customer again");
+        }
+        if !audit_path.is_empty() {
+            result["audit_path"] = json!(audit_path);
         }
+        if let Some(e) = audit_error {
+            result["audit_error"] = json!(e);
+        }
+        Ok(CallToolResult::structured(result))
     }
 
     // ========================================================================
-    // INFORMATION RELATIONSHIP TOOLS - Create Network of Knowledge
+    // BACKGROUND JOBS
     // ========================================================================
 
-    #[tool(description = "Create information relationships - create links between information documents and business assets to build a knowledge network. Specify the operation and provide the required parameters: create_related requires from_info_id and to_info_id, create_prerequisite requires from_info_id and to_info_id, create_series requires from_info_id, to_info_id, and series_name, create_reference requires from_info_id and to_info_id, link_to_product requires from_info_id and target_business_id, link_to_service requires from_info_id and target_business_id, link_to_location requires from_info_id and target_business_id, link_to_event requires from_info_id and target_business_id.")]
-    async fn create_information_relationships(&self, params: Parameters<ManageInformationRelationshipsParam>) -> Result<CallToolResult, McpError> {
-        let p = &params.0;
-        match p.operation.as_str() {
-            "create_related" => {
-                let payload = json!({
-                    "from_info_id": p.from_info_id,
-                    "to_info_id": p.to_info_id,
-                    "relationship_type": p.relationship_type.as_ref().unwrap_or(&"related".to_string()),
-                    "strength": p.strength,
-                    "notes": p.notes.as_ref().unwrap_or(&"".to_string())
-                });
-                match self.helix_client.query("link_related_information", payload).await {
-                    Ok(result) => Ok(CallToolResult::structured(json!({
-                        "success": true,
-                        "operation": "create_related",
-                        "result": result
-                    }))),
-                    Err(e) => Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to create related relationship: {}", e)
-                    })))
-                }
-            },
-            "create_prerequisite" => {
-                let payload = json!({
-                    "prerequisite_info_id": p.from_info_id,
-                    "dependent_info_id": p.to_info_id,
-                    "notes": p.notes.as_ref().unwrap_or(&"".to_string())
-                });
-                match self.helix_client.query("link_prerequisite_information", payload).await {
-                    Ok(result) => Ok(CallToolResult::structured(json!({
-                        "success": true,
-                        "operation": "create_prerequisite",
-                        "result": result
-                    }))),
-                    Err(e) => Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to create prerequisite relationship: {}", e)
-                    })))
-                }
-            },
-            "create_series" => {
-                let series_name = p.series_name.as_ref().ok_or_else(|| McpError::invalid_params("series_name is required for create_series".to_string(), None))?;
-                let payload = json!({
-                    "from_info_id": p.from_info_id,
-                    "to_info_id": p.to_info_id,
-                    "series_name": series_name,
-                    "order": p.order.unwrap_or(1)
-                });
-                match self.helix_client.query("link_series_information", payload).await {
-                    Ok(result) => Ok(CallToolResult::structured(json!({
-                        "success": true,
-                        "operation": "create_series",
-                        "result": result
-                    }))),
-                    Err(e) => Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to create series relationship: {}", e)
-                    })))
-                }
-            },
-            "create_reference" => {
-                let payload = json!({
-                    "referencing_info_id": p.from_info_id,
-                    "referenced_info_id": p.to_info_id,
-                    "reference_type": p.reference_type.as_ref().unwrap_or(&"citation".to_string()),
-                    "page_section": p.page_section.as_ref().unwrap_or(&"".to_string()),
-                    "notes": p.notes.as_ref().unwrap_or(&"".to_string())
-                });
-                match self.helix_client.query("link_reference_information", payload).await {
-                    Ok(result) => Ok(CallToolResult::structured(json!({
-                        "success": true,
-                        "operation": "create_reference",
-                        "result": result
-                    }))),
-                    Err(e) => Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to create reference relationship: {}", e)
-                    })))
-                }
-            },
-            "link_to_product" => {
-                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for link_to_product".to_string(), None))?;
-                let payload = json!({
-                    "info_id": p.from_info_id,
-                    "product_id": target_business_id,
-                    "info_type": p.info_type.as_ref().unwrap_or(&"documentation".to_string()),
-                    "notes": p.notes.as_ref().unwrap_or(&"".to_string())
-                });
-                match self.helix_client.query("link_information_to_product", payload).await {
-                    Ok(result) => Ok(CallToolResult::structured(json!({
-                        "success": true,
-                        "operation": "link_to_product",
-                        "result": result
-                    }))),
-                    Err(e) => Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to link information to product: {}", e)
-                    })))
-                }
-            },
-            "link_to_service" => {
-                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for link_to_service".to_string(), None))?;
-                let payload = json!({
-                    "info_id": p.from_info_id,
-                    "service_id": target_business_id,
-                    "info_type": p.info_type.as_ref().unwrap_or(&"documentation".to_string()),
-                    "notes": p.notes.as_ref().unwrap_or(&"".to_string())
-                });
-                match self.helix_client.query("link_information_to_service", payload).await {
-                    Ok(result) => Ok(CallToolResult::structured(json!({
-                        "success": true,
-                        "operation": "link_to_service",
-                        "result": result
-                    }))),
-                    Err(e) => Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to link information to service: {}", e)
-                    })))
-                }
-            },
-            "link_to_location" => {
-                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for link_to_location".to_string(), None))?;
-                let payload = json!({
-                    "info_id": p.from_info_id,
-                    "location_id": target_business_id,
-                    "info_type": p.info_type.as_ref().unwrap_or(&"guide".to_string()),
-                    "notes": p.notes.as_ref().unwrap_or(&"".to_string())
-                });
-                match self.helix_client.query("link_information_to_location", payload).await {
-                    Ok(result) => Ok(CallToolResult::structured(json!({
-                        "success": true,
-                        "operation": "link_to_location",
-                        "result": result
-                    }))),
-                    Err(e) => Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to link information to location: {}", e)
-                    })))
-                }
-            },
-            "link_to_event" => {
-                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for link_to_event".to_string(), None))?;
-                let payload = json!({
-                    "info_id": p.from_info_id,
-                    "event_id": target_business_id,
-                    "info_type": p.info_type.as_ref().unwrap_or(&"details".to_string()),
-                    "notes": p.notes.as_ref().unwrap_or(&"".to_string())
-                });
-                match self.helix_client.query("link_information_to_event", payload).await {
-                    Ok(result) => Ok(CallToolResult::structured(json!({
-                        "success": true,
-                        "operation": "link_to_event",
-                        "result": result
-                    }))),
-                    Err(e) => Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to link information to event: {}", e)
-                    })))
-                }
-            },
-            _ => Err(McpError::invalid_params(format!("Unknown operation: {}", p.operation), None)),
+    #[tool(description = "Submit a background job - run a long operation without holding the tool call open: import_memories, backfill_embeddings, delete_memory (cascade/complete deletes of a business or customer), delete_memories_by_filter or sync_shopify_catalog. Returns a job_id immediately; poll get_job_status for the result. REQUIRED: job_type, arguments (the arguments that tool takes).")]
+    async fn submit_job(&self, params: Parameters<SubmitJobParam>) -> Result<CallToolResult, McpError> {
+        type JobFuture = futures::future::BoxFuture<'static, Result<CallToolResult, McpError>>;
+
+        let SubmitJobParam { job_type, arguments } = params.0;
+        let serde_json::Value::Object(arguments) = arguments else {
+            return Ok(tool_error(ErrorCode::ValidationFailed, "arguments must be an object holding the job tool's arguments", json!({"job_type": job_type})));
+        };
+        let violations = validation::validate(&job_type, &arguments);
+        if !violations.is_empty() {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid arguments for {}", job_type), json!({
+                "job_type": job_type,
+                "validation_errors": violations,
+                "suggestion": "Fix the listed fields and submit again"
+            })));
+        }
+
+        let server = self.clone();
+        let arguments = serde_json::Value::Object(arguments);
+        let work: Result<JobFuture, serde_json::Error> = match job_type.as_str() {
+            "import_memories" => serde_json::from_value(arguments)
+                .map(|p| -> JobFuture { Box::pin(async move { server.import_memories(Parameters(p)).await }) }),
+            "backfill_embeddings" => serde_json::from_value(arguments)
+                .map(|p| -> JobFuture { Box::pin(async move { server.backfill_embeddings(Parameters(p)).await }) }),
+            "delete_memory" => serde_json::from_value(arguments)
+                .map(|p| -> JobFuture { Box::pin(async move { server.delete_memory(Parameters(p)).await }) }),
+            "delete_memories_by_filter" => serde_json::from_value(arguments)
+                .map(|p| -> JobFuture { Box::pin(async move { server.delete_memories_by_filter(Parameters(p)).await }) }),
+            "sync_shopify_catalog" => serde_json::from_value(arguments)
+                .map(|p| -> JobFuture { Box::pin(async move { server.sync_shopify_catalog(Parameters(p)).await }) }),
+            _ => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, format!("Unsupported job_type: {}", job_type), json!({
+                    "valid_values": jobs::JOB_TYPES
+                })));
+            }
+        };
+        let work = match work {
+            Ok(work) => work,
+            Err(e) => {
+                return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid arguments for {}: {}", job_type, e), json!({
+                    "job_type": job_type
+                })));
+            }
+        };
+        let work = budget::scope(self.budget.clone(), async move {
+            match work.await {
+                Ok(result) if result.is_error != Some(true) => Ok(result.structured_content.unwrap_or_default()),
+                Ok(result) => Err(errors::annotate(result).structured_content.unwrap_or_default()),
+                Err(e) => Err(errors::annotate_protocol(e).data.unwrap_or_default()),
+            }
+        });
+
+        // A dry run executes inline so its writes are recorded and reported
+        if dry_run::active() {
+            return Ok(match work.await {
+                Ok(content) => CallToolResult::structured(content),
+                Err(content) => CallToolResult::structured_error(content),
+            });
+        }
+
+        let job_id = self.ids.next_id().to_string();
+        let job = self.jobs.submit(&job_id, &job_type, &self.session_id, work);
+        info!("Submitted {} job {} (session {})", job_type, job_id, self.session_id);
+        Ok(CallToolResult::structured(json!({
+            "success": true,
+            "job": job,
+            "message": "Poll get_job_status with this job_id for the result"
+        })))
+    }
+
+    #[tool(description = "Get background job status - state (queued, running, completed, failed, cancelled) and, once finished, the tool's result or error. Optional: job_id (omit to list this session's jobs).")]
+    async fn get_job_status(&self, params: Parameters<GetJobStatusParam>) -> Result<CallToolResult, McpError> {
+        let Some(job_id) = params.0.job_id else {
+            let jobs = self.jobs.list(&self.session_id);
+            return Ok(CallToolResult::structured(json!({"count": jobs.len(), "jobs": jobs})));
+        };
+        match self.jobs.status(&job_id, &self.session_id) {
+            Some(job) => Ok(CallToolResult::structured(job)),
+            None => Ok(tool_error(ErrorCode::NotFound, format!("Job not found: {}", job_id), json!({
+                "suggestion": "Finished jobs are kept for [jobs] retention_secs"
+            }))),
+        }
+    }
+
+    #[tool(description = "Cancel a background job - a queued job never starts; a running job stops at its next await point and keeps the writes it already made. REQUIRED: job_id.")]
+    async fn cancel_job(&self, params: Parameters<CancelJobParam>) -> Result<CallToolResult, McpError> {
+        let job_id = params.0.job_id;
+        if self.jobs.status(&job_id, &self.session_id).is_none() {
+            return Ok(tool_error(ErrorCode::NotFound, format!("Job not found: {}", job_id), json!({"job_id": job_id})));
+        }
+        match self.jobs.cancel(&job_id, &self.session_id) {
+            Ok(previous) => {
+                info!("Cancelled job {} ({:?})", job_id, previous);
+                Ok(CallToolResult::structured(json!({
+                    "success": true,
+                    "job_id": job_id,
+                    "previous_status": previous,
+                    "partial": previous == jobs::JobStatus::Running
+                })))
+            }
+            // Only a job that finished in the meantime gets here
+            Err(e) => Ok(tool_error(ErrorCode::Conflict, e, json!({"job_id": job_id}))),
         }
     }
 
     // ========================================================================
-    // QUERY INFORMATION RELATIONSHIPS - Retrieve Network of Knowledge
+    // ADVANCED TOOL - Direct query execution (last resort)
     // ========================================================================
 
-    #[tool(description = "Query information relationships - retrieve links between information documents and business assets. Specify the operation and provide the required parameters: get_related requires from_info_id, get_prerequisites requires from_info_id, get_series requires series_name, get_product_info requires target_business_id, get_service_info requires target_business_id, get_location_info requires target_business_id, get_event_info requires target_business_id.")]
-    async fn query_information_relationships(&self, params: Parameters<QueryInformationRelationshipsParam>) -> Result<CallToolResult, McpError> {
-        let p = &params.0;
-        match p.operation.as_str() {
-            "get_related" => {
-                let from_info_id = p.from_info_id.as_ref().ok_or_else(|| McpError::invalid_params("from_info_id is required for get_related".to_string(), None))?;
-                let payload = json!({"info_id": from_info_id});
-                match self.helix_client.query("get_related_information", payload).await {
-                    Ok(result) => Ok(CallToolResult::structured(json!({
-                        "operation": "get_related",
-                        "data": result
-                    }))),
-                    Err(e) => Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to get related information: {}", e)
-                    })))
+    #[tool(description = "ADVANCED: Execute any HelixDB query directly - USE ONLY when high-level tools (query_business_memory, create_customer_memory, etc.) cannot accomplish the task. This is a last resort for complex queries not covered by standard tools.")]
+    async fn do_query(&self, params: Parameters<DoQueryParam>) -> Result<CallToolResult, McpError> {
+        let endpoint = &params.0.endpoint;
+        let payload = &params.0.payload;
+        
+        info!("do_query: endpoint={}", endpoint);
+
+
+        // Validate endpoint is allowed
+        if !DO_QUERY_ENDPOINTS.contains(&endpoint.as_str()) {
+            return Ok(tool_error(ErrorCode::Unauthorized, format!("Query '{}' is not allowed", endpoint), json!({
+                "allowed_queries": DO_QUERY_ENDPOINTS,
+                "suggestion": "Use high-level tools (query_business_memory, create_customer_memory, etc.) instead"
+            })));
+        }
+
+        // Check the payload against the endpoint's parameters before HelixDB sees it
+        if let Some(manifest) = self.query_manifest.as_ref() {
+            let violations = manifest.validate(endpoint, payload);
+            if !violations.is_empty() {
+                return Ok(errors::tool_error(
+                    errors::ErrorCode::ValidationFailed,
+                    format!("Invalid payload for '{}'", endpoint),
+                    json!({
+                        "endpoint": endpoint,
+                        "validation_errors": violations,
+                        "expected": manifest.schema(endpoint)
+                    }),
+                ));
+            }
+        }
+
+        // Execute query directly
+        match self.helix_client.query(endpoint, payload.clone()).await {
+            Ok(result) => {
+                Ok(CallToolResult::structured(json!({
+                    "endpoint": endpoint,
+                    "result": result
+                })))
+            }
+            Err(e) => {
+                error!("do_query failed: {}", e);
+                Ok(tool_error(ErrorCode::HelixUnreachable, format!("Query execution failed: {}", e), json!({
+                    "endpoint": endpoint
+                })))
+            }
+        }
+    }
+
+    // ========================================================================
+    // GRAPH TRAVERSAL (HelixDB MCP endpoints, [traversal] enabled)
+    // ========================================================================
+
+    /// Tool result for a traversal endpoint call
+    fn traversal_result(step: &str, result: anyhow::Result<serde_json::Value>) -> CallToolResult {
+        match result {
+            Ok(value) => CallToolResult::structured(value),
+            Err(e) => {
+                error!("Traversal {} failed: {}", step, e);
+                tool_error(ErrorCode::HelixUnreachable, e.to_string(), json!({"step": step}))
+            }
+        }
+    }
+
+    /// Close traversal connections on HelixDB in the background
+    fn close_traversal_connections(&self, connection_ids: Vec<String>) {
+        for connection_id in connection_ids {
+            let helix_client = self.helix_client.clone();
+            tokio::spawn(async move {
+                if let Err(e) = helix_client.collect(&connection_id, Some((0, 0)), true).await {
+                    warn!("Failed to close traversal connection {}: {}", connection_id, e);
                 }
-            },
-            "get_prerequisites" => {
-                let from_info_id = p.from_info_id.as_ref().ok_or_else(|| McpError::invalid_params("from_info_id is required for get_prerequisites".to_string(), None))?;
-                let payload = json!({"info_id": from_info_id});
-                match self.helix_client.query("get_prerequisites_for_info", payload).await {
-                    Ok(result) => Ok(CallToolResult::structured(json!({
-                        "operation": "get_prerequisites",
-                        "data": result
-                    }))),
-                    Err(e) => Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to get prerequisites: {}", e)
-                    })))
+            });
+        }
+    }
+
+    /// This MCP session's traversal connection, opened with `mcp/init` on first use
+    async fn traversal_connection(&self) -> anyhow::Result<String> {
+        self.close_traversal_connections(self.traversal_sessions.expire());
+        if let Some(connection_id) = self.traversal_sessions.get(&self.session_id) {
+            return Ok(connection_id);
+        }
+        let opened = self.helix_client.init().await?;
+        match self.traversal_sessions.insert(&self.session_id, opened.clone()) {
+            Ok(connection_id) => {
+                if connection_id != opened {
+                    self.close_traversal_connections(vec![opened]);
                 }
+                Ok(connection_id)
+            }
+            Err(e) => {
+                self.close_traversal_connections(vec![opened]);
+                Err(anyhow::anyhow!(e))
+            }
+        }
+    }
+
+    /// Run one traversal step on `connection_id`, or on the session's managed connection
+    async fn traverse<F, Fut>(&self, step: &str, connection_id: Option<String>, run: F) -> CallToolResult
+    where
+        F: FnOnce(String) -> Fut,
+        Fut: std::future::Future<Output = anyhow::Result<serde_json::Value>>,
+    {
+        let connection_id = match connection_id {
+            Some(connection_id) => connection_id,
+            None => match self.traversal_connection().await {
+                Ok(connection_id) => connection_id,
+                Err(e) => return Self::traversal_result(step, Err(e)),
             },
-            "get_series" => {
-                let series_name = p.series_name.as_ref().ok_or_else(|| McpError::invalid_params("series_name is required for get_series".to_string(), None))?;
-                let payload = json!({"series_name": series_name});
-                match self.helix_client.query("get_series_information", payload).await {
-                    Ok(result) => Ok(CallToolResult::structured(json!({
-                        "operation": "get_series",
-                        "data": result
-                    }))),
-                    Err(e) => Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to get series information: {}", e)
-                    })))
+        };
+        Self::traversal_result(step, run(connection_id).await)
+    }
+
+    #[tool(description = "ADVANCED graph traversal (requires [traversal] enabled): the traversal connection for this session, opened on first use and reused by every traversal tool, so calling this is optional. Start with n_from_type/e_from_type/search_keyword, walk with out_step/in_step/out_e_step/in_e_step, narrow with filter_items, read with traversal_next/traversal_collect, and traversal_reset to start over. Idle connections are closed after [traversal] session_ttl_secs.")]
+    async fn traversal_init(&self) -> Result<CallToolResult, McpError> {
+        let result = self.traversal_connection().await.map(|connection_id| json!({
+            "connection_id": connection_id,
+            "managed": true,
+            "idle_ttl_secs": self.traversal_sessions.ttl().as_secs()
+        }));
+        Ok(Self::traversal_result("init", result))
+    }
+
+    #[tool(description = "ADVANCED graph traversal: the next item of the current traversal results. Optional: connection_id (default: this session's connection).")]
+    async fn traversal_next(&self, params: Parameters<TraversalConnectionParam>) -> Result<CallToolResult, McpError> {
+        let client = &self.helix_client;
+        Ok(self.traverse("next", params.0.connection_id, |conn| async move { client.next(&conn).await }).await)
+    }
+
+    #[tool(description = "ADVANCED graph traversal: collect the current traversal results. Optional: connection_id, range {start, end}, drop (close the connection afterwards; the next traversal call opens a new one).")]
+    async fn traversal_collect(&self, params: Parameters<TraversalCollectParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let range = p.range.map(|r| (r.start, r.end));
+        let drop = p.drop.unwrap_or(false);
+        let client = &self.helix_client;
+        let sessions = &self.traversal_sessions;
+        let session_id = &self.session_id;
+        Ok(self.traverse("collect", p.connection_id, |conn| async move {
+            let result = client.collect(&conn, range, drop).await;
+            if drop && result.is_ok() {
+                sessions.forget(session_id, &conn);
+            }
+            result
+        }).await)
+    }
+
+    #[tool(description = "ADVANCED graph traversal: reset the traversal to start a new one on the same connection. Optional: connection_id.")]
+    async fn traversal_reset(&self, params: Parameters<TraversalConnectionParam>) -> Result<CallToolResult, McpError> {
+        let client = &self.helix_client;
+        Ok(self.traverse("reset", params.0.connection_id, |conn| async move {
+            client.reset(&conn).await.map(|message| json!({"message": message}))
+        }).await)
+    }
+
+    #[tool(description = "ADVANCED graph traversal: the graph schema (node, edge and vector types with their fields) as seen by the traversal connection. Optional: connection_id.")]
+    async fn traversal_schema(&self, params: Parameters<TraversalConnectionParam>) -> Result<CallToolResult, McpError> {
+        let client = &self.helix_client;
+        Ok(self.traverse("schema_resource", params.0.connection_id, |conn| async move { client.schema_resource(&conn).await }).await)
+    }
+
+    #[tool(description = "ADVANCED graph traversal: start from all nodes of a type (e.g. BusinessProductMemory). REQUIRED: node_type. Optional: connection_id.")]
+    async fn n_from_type(&self, params: Parameters<NFromTypeParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let client = &self.helix_client;
+        Ok(self.traverse("n_from_type", p.connection_id, |conn| async move { client.n_from_type(&conn, &p.node_type).await }).await)
+    }
+
+    #[tool(description = "ADVANCED graph traversal: start from all edges of a type (e.g. AboutCustomer). REQUIRED: edge_type. Optional: connection_id.")]
+    async fn e_from_type(&self, params: Parameters<EFromTypeParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let client = &self.helix_client;
+        Ok(self.traverse("e_from_type", p.connection_id, |conn| async move { client.e_from_type(&conn, &p.edge_type).await }).await)
+    }
+
+    #[tool(description = "ADVANCED graph traversal: follow outgoing edges with edge_label to their target nodes (edge_type \"node\") or vectors (\"vec\"). REQUIRED: edge_label, edge_type. Optional: connection_id.")]
+    async fn out_step(&self, params: Parameters<TraversalStepParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let client = &self.helix_client;
+        Ok(self.traverse("out_step", p.connection_id, |conn| async move { client.out_step(&conn, &p.edge_label, &p.edge_type).await }).await)
+    }
+
+    #[tool(description = "ADVANCED graph traversal: move to the outgoing edges with edge_label. REQUIRED: edge_label. Optional: connection_id.")]
+    async fn out_e_step(&self, params: Parameters<TraversalEdgeStepParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let client = &self.helix_client;
+        Ok(self.traverse("out_e_step", p.connection_id, |conn| async move { client.out_e_step(&conn, &p.edge_label).await }).await)
+    }
+
+    #[tool(description = "ADVANCED graph traversal: follow incoming edges with edge_label back to their source nodes (edge_type \"node\") or vectors (\"vec\"). REQUIRED: edge_label, edge_type. Optional: connection_id.")]
+    async fn in_step(&self, params: Parameters<TraversalStepParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let client = &self.helix_client;
+        Ok(self.traverse("in_step", p.connection_id, |conn| async move { client.in_step(&conn, &p.edge_label, &p.edge_type).await }).await)
+    }
+
+    #[tool(description = "ADVANCED graph traversal: move to the incoming edges with edge_label. REQUIRED: edge_label. Optional: connection_id.")]
+    async fn in_e_step(&self, params: Parameters<TraversalEdgeStepParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let client = &self.helix_client;
+        Ok(self.traverse("in_e_step", p.connection_id, |conn| async move { client.in_e_step(&conn, &p.edge_label).await }).await)
+    }
+
+    #[tool(description = "ADVANCED graph traversal: keep only current items matching a HelixDB filter object (property conditions). REQUIRED: filter. Optional: connection_id.")]
+    async fn filter_items(&self, params: Parameters<FilterItemsParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let client = &self.helix_client;
+        Ok(self.traverse("filter_items", p.connection_id, |conn| async move { client.filter_items(&conn, p.filter).await }).await)
+    }
+
+    #[tool(description = "ADVANCED graph traversal: start from a vector search of vectors with label, the query text embedded by HelixDB. REQUIRED: query, label. Optional: connection_id.")]
+    async fn search_vector_text(&self, params: Parameters<TraversalSearchParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let client = &self.helix_client;
+        Ok(self.traverse("search_vector_text", p.connection_id, |conn| async move { client.search_vector_text(&conn, &p.query, &p.label).await }).await)
+    }
+
+    #[tool(description = "ADVANCED graph traversal: start from a BM25 keyword search of nodes with label. REQUIRED: query, label. Optional: connection_id, limit (default 10).")]
+    async fn search_keyword(&self, params: Parameters<TraversalSearchParam>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let client = &self.helix_client;
+        Ok(self.traverse("search_keyword", p.connection_id, |conn| async move {
+            client.search_keyword(&conn, &p.query, &p.label, p.limit.unwrap_or(10)).await
+        }).await)
+    }
+
+    // ========================================================================
+    // EMBEDDING GENERATION (MCP Mode)
+    // ========================================================================
+
+    /// Find an existing memory of the same type whose embedding is within the
+    /// configured dedup threshold of `embedding`
+    async fn find_duplicate_memory(&self, memory_type: &str, owner_field: &str, owner_id: &str, embedding: &[f64]) -> Option<(serde_json::Value, f64)> {
+        let query_name = match memory_type {
+            "product" => "find_similar_business_products",
+            "service" => "find_similar_business_services",
+            "location" => "find_similar_business_locations",
+            "hours" => "find_similar_business_hours",
+            "social" => "find_similar_business_social",
+            "policy" => "find_similar_business_policies",
+            "event" => "find_similar_business_events",
+            "information" => "find_similar_business_information",
+            "behavior" => "find_similar_customer_behaviors",
+            "preference" => "find_similar_customer_preferences",
+            "desire" => "find_similar_customer_desires",
+            "rule" => "find_similar_customer_rules",
+            "feedback" => "find_similar_customer_feedback",
+            _ => return None,
+        };
+
+        let payload = json!({
+            "query_embedding": embedding,
+            "limit": self.config.dedup.candidate_limit
+        });
+
+        let result = match self.helix_client.query(query_name, payload).await {
+            Ok(result) => result,
+            Err(e) => {
+                // Dedup is best-effort - never block creation on a failed lookup
+                warn!("Dedup lookup failed for {}: {}", memory_type, e);
+                return None;
+            }
+        };
+
+        let items = |key: &str| result.get(key).and_then(|v| v.as_array()).cloned().unwrap_or_default();
+
+        similarity::best_match(embedding, (owner_field, owner_id), &items("memories"), &items("links"), &items("embeddings"))
+            .filter(|(_, score)| *score >= self.config.dedup.similarity_threshold)
+    }
+
+    /// Name of the embedding model recorded alongside stored vectors
+    fn embedding_model_name(&self) -> String {
+        match self.config.embedding.provider {
+            Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) => {
+                self.config.embedding.model.clone().unwrap_or_else(|| "unknown".to_string())
+            }
+            Some(config::EmbeddingProvider::Local) => "local".to_string(),
+            Some(config::EmbeddingProvider::Tcp) => "tcp-local".to_string(),
+            None => "unknown".to_string()
+        }
+    }
+
+    /// Collection models recorded in HelixDB, cached after the first load
+    async fn corpus_models(&self) -> Result<std::collections::HashMap<String, embedding_meta::CollectionModel>, String> {
+        if let Some(models) = self.corpus_models.get_all() {
+            return Ok(models);
+        }
+        let result = self.helix_client.query("get_embedding_metadata", json!({})).await.map_err(|e| e.to_string())?;
+        Ok(self.corpus_models.load(&HelixClient::result_items(&result)))
+    }
+
+    /// Write a collection's metadata node (insert or update) and refresh the cache
+    async fn store_corpus_model(&self, collection: &str, model: embedding_meta::CollectionModel, exists: bool) -> Result<(), String> {
+        let payload = json!({
+            "memory_type": collection,
+            "embedding_model": model.model,
+            "dimensions": model.dimensions,
+            "target_model": model.target_model.clone().unwrap_or_default(),
+            "updated_at": self.clock.timestamp()
+        });
+        let query = if exists { "update_embedding_metadata" } else { "add_embedding_metadata" };
+        self.helix_client.query(query, payload).await.map_err(|e| e.to_string())?;
+        self.corpus_models.set(collection, model);
+        Ok(())
+    }
+
+    /// Discover the embedding provider's model and dimensions (TCP info
+    /// handshake, otherwise a probe embedding), check them against the config
+    /// and the recorded collections, and keep them for get_embedding_metadata
+    async fn negotiate_embedding_provider(&self) -> Result<embedding_meta::ProviderModel, embedding_meta::NegotiationError> {
+        use embedding_meta::NegotiationError;
+        let provider = match &self.tcp_embedder {
+            Some(client) if self.config.embedding.provider == Some(config::EmbeddingProvider::Tcp) => {
+                let info = client.handshake().await.map_err(|e| NegotiationError::Unreachable(format!("Embedding handshake failed: {}", e)))?;
+                embedding_meta::ProviderModel {
+                    source: if info.model.is_some() { "handshake" } else { "probe" },
+                    model: info.model.unwrap_or_else(|| self.embedding_model_name()),
+                    dimensions: info.dimensions,
                 }
-            },
-            "get_product_info" => {
-                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for get_product_info".to_string(), None))?;
-                let payload = json!({"product_id": target_business_id});
-                match self.helix_client.query("get_product_information", payload).await {
-                    Ok(result) => Ok(CallToolResult::structured(json!({
-                        "operation": "get_product_info",
-                        "data": result
-                    }))),
-                    Err(e) => Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to get product information: {}", e)
-                    })))
+            }
+            _ => {
+                let api_key = self.config.get_api_key().unwrap_or_default();
+                let embedding = self.generate_embedding("embedding capability probe", &api_key).await
+                    .map_err(|e| NegotiationError::Unreachable(format!("Probe embedding failed: {}", e)))?;
+                embedding_meta::ProviderModel { model: self.embedding_model_name(), dimensions: embedding.len(), source: "probe" }
+            }
+        };
+        let collections = if self.config.embedding_metadata.enabled {
+            self.corpus_models().await.unwrap_or_else(|e| {
+                warn!("Could not load embedding metadata: {}", e);
+                Default::default()
+            })
+        } else {
+            Default::default()
+        };
+        let refuse = self.config.embedding_metadata.on_mismatch == config::ModelMismatchAction::Refuse;
+        let collection_problems = embedding_meta::check_provider(&provider, self.config.embedding.vector_dimensions(), &collections, refuse)?;
+        for problem in &collection_problems {
+            warn!("{}", problem);
+        }
+        let _ = self.provider_model.set(provider.clone());
+        Ok(provider)
+    }
+
+    /// Record the model of a collection's first vectors; later writes only warn on a different model
+    async fn note_corpus_model(&self, collection: &str, dimensions: usize) {
+        if !self.config.embedding_metadata.enabled {
+            return;
+        }
+        let model = self.embedding_model_name();
+        let models = match self.corpus_models().await {
+            Ok(models) => models,
+            Err(e) => {
+                warn!("Could not load embedding metadata: {}", e);
+                return;
+            }
+        };
+        match models.get(collection) {
+            None => {
+                let recorded = embedding_meta::CollectionModel { model, dimensions, target_model: None };
+                if let Err(e) = self.store_corpus_model(collection, recorded, false).await {
+                    warn!("Could not record embedding model for {}: {}", collection, e);
                 }
-            },
-            "get_service_info" => {
-                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for get_service_info".to_string(), None))?;
-                let payload = json!({"service_id": target_business_id});
-                match self.helix_client.query("get_service_information", payload).await {
-                    Ok(result) => Ok(CallToolResult::structured(json!({
-                        "operation": "get_service_info",
-                        "data": result
-                    }))),
-                    Err(e) => Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to get service information: {}", e)
-                    })))
+            }
+            Some(stored) if stored.model != model && stored.target_model.as_deref() != Some(model.as_str()) => {
+                warn!("Writing {} vectors to {}, whose corpus model is {} - re-embed with backfill_embeddings", model, collection, stored.model);
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// Compare the query model with each collection's recorded model.
+    /// Returns the collections to skip (refuse mode) and warnings for the response.
+    async fn check_corpus_models(&self, collections: &[&str], dimensions: usize) -> (Vec<String>, Vec<serde_json::Value>) {
+        let mut refused = Vec::new();
+        let mut warnings = Vec::new();
+        if !self.config.embedding_metadata.enabled {
+            return (refused, warnings);
+        }
+        let models = match self.corpus_models().await {
+            Ok(models) => models,
+            Err(e) => {
+                warn!("Could not load embedding metadata: {}", e);
+                return (refused, warnings);
+            }
+        };
+        let model = self.embedding_model_name();
+        let refuse = self.config.embedding_metadata.on_mismatch == config::ModelMismatchAction::Refuse;
+        for collection in collections {
+            match embedding_meta::check(models.get(*collection), &model, dimensions) {
+                embedding_meta::ModelCheck::Match | embedding_meta::ModelCheck::Unrecorded => {}
+                embedding_meta::ModelCheck::Migrating { from } => warnings.push(json!({
+                    "collection": collection,
+                    "warning": format!("Re-embedding from {} to {} is in progress; similarity scores of not-yet-migrated memories are unreliable", from, model)
+                })),
+                embedding_meta::ModelCheck::Mismatch { stored, stored_dimensions } => {
+                    warnings.push(json!({
+                        "collection": collection,
+                        "warning": format!("Collection was embedded with {} ({} dims) but the query model is {} ({} dims)", stored, stored_dimensions, model, dimensions),
+                        "skipped": refuse,
+                        "suggestion": "Re-embed with backfill_embeddings, then mark the collection with set_embedding_metadata"
+                    }));
+                    if refuse {
+                        refused.push(collection.to_string());
+                    }
                 }
-            },
-            "get_location_info" => {
-                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for get_location_info".to_string(), None))?;
-                let payload = json!({"location_id": target_business_id});
-                match self.helix_client.query("get_location_information", payload).await {
-                    Ok(result) => Ok(CallToolResult::structured(json!({
-                        "operation": "get_location_info",
-                        "data": result
-                    }))),
-                    Err(e) => Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to get location information: {}", e)
-                    })))
+            }
+        }
+        (refused, warnings)
+    }
+
+    /// Reorder search results with the rerank provider and cut them to `limit`;
+    /// on provider failure the vector order is kept and the error reported
+    async fn maybe_rerank(&self, rerank: bool, query: &str, results: Vec<serde_json::Value>, limit: i32) -> (Vec<serde_json::Value>, serde_json::Value) {
+        if !rerank || results.is_empty() {
+            return (results, serde_json::Value::Null);
+        }
+        let candidates = results.len();
+        let documents = results.iter().map(rerank::candidate_text).collect();
+        match rerank::scores(&self.config.rerank, self.config.get_rerank_key(), query, documents).await {
+            Ok(scores) => (
+                rerank::apply(results, &scores, limit.max(0) as usize),
+                json!({"provider": self.config.rerank.provider, "model": self.config.rerank.model, "candidates": candidates}),
+            ),
+            Err(e) => {
+                warn!("Rerank failed, keeping vector order: {}", e);
+                (results, json!({"error": e, "candidates": candidates}))
+            }
+        }
+    }
+
+    /// Drop scored search results below `min_score`; results HelixDB gave no score for are kept
+    fn above_min_score(results: Vec<serde_json::Value>, min_score: Option<f64>) -> Vec<serde_json::Value> {
+        let Some(min_score) = min_score else {
+            return results;
+        };
+        results
+            .into_iter()
+            .filter(|r| r.get("score").and_then(|s| s.as_f64()).is_none_or(|score| score >= min_score))
+            .collect()
+    }
+
+    /// HelixDB-embedded search across memory types (plural): the query text is embedded by Embed() in the query
+    async fn text_vector_search(&self, memory_types: &[&str], query: &str, business_id: Option<&str>, customer_id: Option<&str>, limit: i32, min_score: Option<f64>) -> Vec<serde_json::Value> {
+        let mut searches = Vec::new();
+        for memory_type in memory_types {
+            let query_name = match *memory_type {
+                // Business memory types
+                "products" => "search_business_products_semantic",
+                "services" => "search_business_services_semantic",
+                "locations" => "search_business_locations_semantic",
+                "hours" => "search_business_hours_semantic",
+                "social" => "search_business_social_semantic",
+                "policies" => "search_business_policies_semantic",
+                "events" => "search_business_events_semantic",
+                "information" => "search_business_information_semantic",
+                // Customer memory types
+                "behaviors" => "search_customer_behaviors_semantic",
+                "preferences" => "search_customer_preferences_semantic",
+                "desires" => "search_customer_desires_semantic",
+                "rules" => "search_customer_rules_semantic",
+                "feedback" => "search_customer_feedback_semantic",
+                "communications" => "search_customer_communication_semantic",
+                // Customer interaction types
+                "product_interactions" => "search_customer_product_interactions_semantic",
+                "service_interactions" => "search_customer_service_interactions_semantic",
+                // Navigation types
+                "navigation_hubs" => "search_navigation_hubs_semantic",
+                "waypoints" => "search_navigation_waypoints_semantic",
+                "direction_paths" => "search_direction_paths_semantic",
+                _ => {
+                    info!("Skipping unsupported memory type for semantic search: {}", memory_type);
+                    continue;
                 }
-            },
-            "get_event_info" => {
-                let target_business_id = p.target_business_id.as_ref().ok_or_else(|| McpError::invalid_params("target_business_id is required for get_event_info".to_string(), None))?;
-                let payload = json!({"event_id": target_business_id});
-                match self.helix_client.query("get_event_information", payload).await {
-                    Ok(result) => Ok(CallToolResult::structured(json!({
-                        "operation": "get_event_info",
-                        "data": result
-                    }))),
-                    Err(e) => Ok(CallToolResult::structured_error(json!({
-                        "error": format!("Failed to get event information: {}", e)
-                    })))
+            };
+
+            // Build search payload - just text, HelixDB will call Embed()
+            let mut payload = json!({
+                "query_text": query,
+                "k": limit,
+            });
+
+            // Add filters based on optional parameters
+            if let Some(business_id) = business_id {
+                payload["business_id"] = json!(business_id);
+            }
+
+            if let Some(customer_id) = customer_id {
+                payload["customer_id"] = json!(customer_id);
+            }
+
+            searches.push((*memory_type, query_name, payload));
+        }
+        // Scores come from the distances HelixDB reports
+        self.run_searches(searches, None, min_score).await
+    }
+
+    /// Run per-type search queries, at most `[helix] max_parallel_queries` at
+    /// once, and concatenate the scored, tagged results in memory type order
+    async fn run_searches(&self, searches: Vec<(&str, &str, serde_json::Value)>, query_vector: Option<&[f64]>, min_score: Option<f64>) -> Vec<serde_json::Value> {
+        let outcomes = fan_out::bounded(
+            searches.into_iter().map(|(memory_type, query_name, payload)| async move {
+                (memory_type, self.helix_client.query(query_name, payload).await)
+            }),
+            self.config.helix.max_parallel_queries,
+        )
+        .await;
+        let mut all_results = Vec::new();
+        for (memory_type, outcome) in outcomes {
+            match outcome {
+                Ok(results) => {
+                    all_results.extend(search_results::tag(importance::rank(Self::above_min_score(similarity::scored_memories(&results, query_vector), min_score)), memory_type));
                 }
-            },
-            _ => Err(McpError::invalid_params(format!("Unknown operation: {}", p.operation), None)),
+                Err(e) => {
+                    error!("Semantic search failed for {}: {}", memory_type, e);
+                }
+            }
         }
+        all_results
     }
 
-    // ========================================================================
-    // ADVANCED TOOL - Direct query execution (last resort)
-    // ========================================================================
+    /// Vector search across memory types (plural) with a query embedding, results concatenated
+    async fn vector_search(&self, memory_types: &[&str], query_embedding: &[f32], business_id: Option<&str>, customer_id: Option<&str>, limit: i32, min_score: Option<f64>) -> Vec<serde_json::Value> {
+        let query_vector: Vec<f64> = query_embedding.iter().map(|&x| f64::from(x)).collect();
+        let mut searches = Vec::new();
+        for memory_type in memory_types {
+            let query_name = match *memory_type {
+                // Business memory types
+                "products" => "search_business_products_hybrid",
+                "services" => "search_business_services_hybrid",
+                "locations" => "search_business_locations_hybrid",
+                "hours" => "search_business_hours_hybrid",
+                "social" => "search_business_social_hybrid",
+                "policies" => "search_business_policies_hybrid",
+                "events" => "search_business_events_hybrid",
+                "information" => "search_business_information_hybrid",
+                // Customer memory types
+                "behaviors" => "search_customer_behaviors_hybrid",
+                "preferences" => "search_customer_preferences_hybrid",
+                "desires" => "search_customer_desires_hybrid",
+                "rules" => "search_customer_rules_hybrid",
+                "feedback" => "search_customer_feedback_hybrid",
+                "communications" => "search_customer_communication_hybrid",
+                // Customer interaction types
+                "product_interactions" => "search_customer_product_interactions_hybrid",
+                "service_interactions" => "search_customer_service_interactions_hybrid",
+                // Navigation types
+                "navigation_hubs" => "search_navigation_hubs_hybrid",
+                "waypoints" => "search_navigation_waypoints_hybrid",
+                "direction_paths" => "search_direction_paths_hybrid",
+                _ => {
+                    info!("Skipping unsupported memory type for semantic search: {}", memory_type);
+                    continue;
+                }
+            };
+
+            // Build search payload with embedding vector
+            let mut payload = json!({
+                "query_embedding": query_embedding,
+                "limit": limit,
+            });
+
+            // Add filters based on optional parameters
+            if let Some(business_id) = business_id {
+                // Apply business_id filter to business memory types
+                match *memory_type {
+                    "products" => {
+                        payload["business_id"] = json!(business_id);
+                        payload["min_price"] = json!(0.0);
+                        payload["max_price"] = json!(1000000.0);
+                    }
+                    "services" | "locations" | "hours" | "social" | "policies" | "events" | "information" => {
+                        payload["business_id"] = json!(business_id);
+                    }
+                    _ => {}
+                }
+            }
 
-    #[tool(description = "ADVANCED: Execute any HelixDB query directly - USE ONLY when high-level tools (query_business_memory, create_customer_memory, etc.) cannot accomplish the task. This is a last resort for complex queries not covered by standard tools.")]
-    async fn do_query(&self, params: Parameters<DoQueryParam>) -> Result<CallToolResult, McpError> {
-        let endpoint = &params.0.endpoint;
-        let payload = &params.0.payload;
-        
-        info!("do_query: endpoint={}", endpoint);
+            if let Some(customer_id) = customer_id {
+                // Apply customer_id filter to customer memory types
+                match *memory_type {
+                    "behaviors" | "preferences" | "desires" | "rules" | "feedback" | "communications"
+                    | "product_interactions" | "service_interactions" => {
+                        payload["customer_id"] = json!(customer_id);
+                    }
+                    _ => {}
+                }
+            }
 
-        // Whitelist of allowed queries (security - prevent dangerous operations)
-        let allowed_queries = vec![
-            // Business product queries
-            "add_business_product_memory",
-            "get_business_products",
-            "search_business_products",
-            "search_business_products_hybrid",
-            "update_product_price",
-            "update_product_availability",
-            "update_product_full",
-            "delete_product",
-            "delete_product_with_embedding",
-            
-            // Business service queries
-            "add_business_service_memory",
-            "get_business_services",
-            "search_business_services",
-            "update_service_price",
-            "update_service_availability",
-            "update_service_full",
-            "delete_service",
-            "delete_service_with_embedding",
-            
-            // Business location queries
-            "add_business_location_memory",
-            "get_business_locations",
-            "update_location_address",
-            "delete_location",
-            "delete_location_with_embedding",
-            
-            // Business hours queries
-            "add_business_hours_memory",
-            "get_business_hours",
-            "update_business_hours_monday",
-            "delete_hours",
-            "delete_hours_with_embedding",
-            
-            // Business social media queries
-            "add_business_social_media_memory",
-            "get_business_social_media",
-            "update_social_stats",
-            "delete_social",
-            "delete_social_with_embedding",
-            
-            // Business policy queries
-            "add_business_policy_memory",
-            "get_business_policies",
-            "update_policy_content",
-            "delete_policy",
-            "delete_policy_with_embedding",
-            
-            // Business event queries
-            "add_business_event_memory",
-            "get_business_events",
-            "update_event_dates",
-            "delete_event",
-            "delete_event_with_embedding",
-
-            // Business information queries
-            "add_business_information_memory",
-            "get_business_information",
-            "update_business_information_memory",
-            "delete_information",
-            "delete_information_with_embedding",
-            "delete_all_business_information",
-
-            // Information relationship queries
-            "link_related_information",
-            "link_prerequisite_information",
-            "link_series_information",
-            "link_reference_information",
-            "link_information_to_product",
-            "link_information_to_service",
-            "link_information_to_location",
-            "link_information_to_event",
-            "get_related_information",
-            "get_prerequisites_for_info",
-            "get_dependent_information",
-            "get_series_information",
-            "get_information_references",
-            "get_referenced_by_info",
-            "get_product_information",
-            "get_service_information",
-            "get_location_information",
-            "get_event_information",
-
-            // Customer behavior queries
-            "add_customer_behavior_memory",
-            "get_customer_behaviors",
-            "update_behavior_context",
-            "delete_behavior",
-            "delete_behavior_with_embedding",
-            
-            // Customer preference queries
-            "add_customer_preference_memory",
-            "get_customer_preferences",
-            "search_customer_preferences",
-            "search_customer_preferences_hybrid",
-            "update_preference_strength",
-            "delete_preference",
-            "delete_preference_with_embedding",
-            
-            // Customer desire queries
-            "add_customer_desire_memory",
-            "get_customer_desires",
-            "update_desire_priority",
-            "delete_desire",
-            "delete_desire_with_embedding",
-            
-            // Customer rule queries
-            "add_customer_rule_memory",
-            "get_customer_rules",
-            "update_rule_enforcement",
-            "delete_rule",
-            "delete_rule_with_embedding",
-            
-            // Customer feedback queries
-            "add_customer_feedback_memory",
-            "get_customer_feedback",
-            "update_feedback_rating",
-            "delete_feedback",
-            "delete_feedback_with_embedding",
-        ];
+            searches.push((*memory_type, query_name, payload));
+        }
+        // Score each hit against the query vector
+        self.run_searches(searches, Some(&query_vector), min_score).await
+    }
 
-        // Validate endpoint is allowed
-        if !allowed_queries.contains(&endpoint.as_str()) {
-            return Ok(CallToolResult::structured_error(json!({
-                "error": format!("Query '{}' is not allowed", endpoint),
-                "allowed_queries": allowed_queries,
-                "suggestion": "Use high-level tools (query_business_memory, create_customer_memory, etc.) instead"
-            })));
+    /// Generate embedding vector from text using configured provider
+    /// Generate an embedding for search query text, served from the query cache when possible
+    async fn generate_query_embedding(&self, query: &str, api_key: &str) -> Result<Vec<f32>, String> {
+        if let Some(embedding) = self.query_cache.get(query) {
+            info!("Query embedding cache hit: {}", query);
+            return Ok(embedding);
         }
+        let embedding = self.generate_embedding(query, api_key).await?;
+        self.query_cache.insert(query, embedding.clone());
+        Ok(embedding)
+    }
 
-        // Execute query directly
-        match self.helix_client.query(endpoint, payload.clone()).await {
-            Ok(result) => {
-                Ok(CallToolResult::structured(json!({
-                    "endpoint": endpoint,
-                    "result": result
-                })))
+    /// Embeddings for several search queries: cached ones are reused, the rest
+    /// go to OpenAI-compatible providers in one request, are pipelined to the
+    /// TCP embedding server, and go to others concurrently
+    async fn generate_query_embeddings(&self, queries: &[String], api_key: &str) -> Result<Vec<Vec<f32>>, String> {
+        let mut embeddings: Vec<Option<Vec<f32>>> = queries.iter().map(|q| self.query_cache.get(q)).collect();
+        let missing: Vec<usize> = (0..queries.len()).filter(|&i| embeddings[i].is_none()).collect();
+        if missing.is_empty() {
+            return Ok(embeddings.into_iter().flatten().collect());
+        }
+        let texts: Vec<&str> = missing.iter().map(|&i| queries[i].as_str()).collect();
+        let batched = matches!(
+            self.config.embedding.provider,
+            Some(config::EmbeddingProvider::OpenAI) | Some(config::EmbeddingProvider::Gemini) | Some(config::EmbeddingProvider::Tcp)
+        );
+        let generated = if batched && texts.len() > 1 {
+            if self.config.embedding.mode != config::EmbeddingMode::Mcp {
+                return Err("generate_embedding called in non-MCP mode".to_string());
             }
-            Err(e) => {
-                error!("do_query failed: {}", e);
-                Ok(CallToolResult::structured_error(json!({
-                    "error": format!("Query execution failed: {}", e),
-                    "endpoint": endpoint
-                })))
+            for _ in &texts {
+                budget::charge_current_embedding().map_err(|e| e.to_string())?;
+            }
+            let _ticket = self.embedding_gate.enter().await;
+            if matches!(self.config.embedding.provider, Some(config::EmbeddingProvider::Tcp)) {
+                self.generate_tcp_embeddings(&texts).await?
+            } else {
+                self.generate_openai_embeddings(&texts, api_key).await?
             }
+        } else {
+            futures::future::join_all(texts.iter().map(|text| self.generate_embedding(text, api_key)))
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        for (i, embedding) in missing.into_iter().zip(generated) {
+            self.query_cache.insert(&queries[i], embedding.clone());
+            embeddings[i] = Some(embedding);
         }
+        Ok(embeddings.into_iter().flatten().collect())
     }
 
-    // ========================================================================
-    // EMBEDDING GENERATION (MCP Mode)
-    // ========================================================================
-
-    /// Generate embedding vector from text using configured provider
     async fn generate_embedding(&self, text: &str, api_key: &str) -> Result<Vec<f32>, String> {
         use config::{EmbeddingProvider, EmbeddingMode};
 
@@ -3613,6 +10268,9 @@ impl HelixMcpServer {
         let provider = self.config.embedding.provider.as_ref()
             .ok_or("No embedding provider configured")?;
 
+        budget::charge_current_embedding().map_err(|e| e.to_string())?;
+
+        let _ticket = self.embedding_gate.enter().await;
         match provider {
             EmbeddingProvider::OpenAI => {
                 self.generate_openai_embedding(text, api_key).await
@@ -3631,26 +10289,50 @@ impl HelixMcpServer {
     }
 
     /// Generate embedding using OpenAI-compatible API
-    /// Works with: OpenAI, Novita AI, Together AI, OpenRouter, Gemini (via OpenAI proxy), etc.
+    /// Works with: OpenAI, Azure OpenAI, Novita AI, Together AI, OpenRouter, Gemini (via OpenAI proxy), etc.
     async fn generate_openai_embedding(&self, text: &str, api_key: &str) -> Result<Vec<f32>, String> {
-        let model = self.config.embedding.model.as_ref()
-            .ok_or("Embedding model not configured")?;
+        let json_response = self.post_openai_embeddings(json!(text), api_key).await?;
 
-        let api_url = self.config.embedding.openai_api_url.as_ref()
-            .ok_or("OpenAI API URL not configured in mcpconfig.toml")?;
+        // Extract embedding from OpenAI-compatible response
+        let embedding = json_response["data"][0]["embedding"]
+            .as_array()
+            .ok_or("Invalid response: missing data[0].embedding array")?
+            .iter()
+            .map(|v| v.as_f64().unwrap_or(0.0) as f32)
+            .collect::<Vec<f32>>();
+
+        if embedding.is_empty() {
+            return Err("API returned empty embedding".to_string());
+        }
 
-        info!("Generating embedding with model: {} at {}", model, api_url);
+        Ok(embedding)
+    }
+
+    /// POST `input` (a text or an array of texts) to the OpenAI-compatible embeddings
+    /// endpoint. Azure OpenAI deployments take an `api-key` header and pick the model
+    /// from the deployment, so no model is sent.
+    async fn post_openai_embeddings(&self, input: serde_json::Value, api_key: &str) -> Result<serde_json::Value, String> {
+        let api_url = self.config.openai_embeddings_url()
+            .ok_or("OpenAI API URL not configured in mcpconfig.toml")?;
 
         let client = reqwest::Client::new();
-        let response = client
-            .post(api_url)
-            .header("Authorization", format!("Bearer {}", api_key))
+        let request = if self.config.is_azure_openai() {
+            info!("Generating embedding with Azure OpenAI deployment at {}", api_url);
+            client
+                .post(&api_url)
+                .header("api-key", api_key)
+                .json(&json!({"input": input, "encoding_format": "float"}))
+        } else {
+            let model = self.config.embedding.model.as_ref()
+                .ok_or("Embedding model not configured")?;
+            info!("Generating embedding with model: {} at {}", model, api_url);
+            client
+                .post(&api_url)
+                .header("Authorization", format!("Bearer {}", api_key))
+                .json(&json!({"model": model, "input": input, "encoding_format": "float"}))
+        };
+        let response = request
             .header("Content-Type", "application/json")
-            .json(&json!({
-                "model": model,
-                "input": text,
-                "encoding_format": "float"
-            }))
             .send()
             .await
             .map_err(|e| format!("API request failed: {}", e))?;
@@ -3661,22 +10343,32 @@ impl HelixMcpServer {
             return Err(format!("API error {}: {}", status, error_text));
         }
 
-        let json_response: serde_json::Value = response.json().await
-            .map_err(|e| format!("Failed to parse API response: {}", e))?;
-
-        // Extract embedding from OpenAI-compatible response
-        let embedding = json_response["data"][0]["embedding"]
-            .as_array()
-            .ok_or("Invalid response: missing data[0].embedding array")?
-            .iter()
-            .map(|v| v.as_f64().unwrap_or(0.0) as f32)
-            .collect::<Vec<f32>>();
+        response.json().await
+            .map_err(|e| format!("Failed to parse API response: {}", e))
+    }
 
-        if embedding.is_empty() {
-            return Err("API returned empty embedding".to_string());
+    /// Batch variant of `generate_openai_embedding`: one request with an input array
+    async fn generate_openai_embeddings(&self, texts: &[&str], api_key: &str) -> Result<Vec<Vec<f32>>, String> {
+        let json_response = self.post_openai_embeddings(json!(texts), api_key).await?;
+
+        // Entries carry their input position in "index"
+        let mut embeddings: Vec<Option<Vec<f32>>> = vec![None; texts.len()];
+        for (position, item) in json_response["data"].as_array().ok_or("Invalid response: missing data array")?.iter().enumerate() {
+            let index = item["index"].as_u64().map(|i| i as usize).unwrap_or(position);
+            let embedding: Vec<f32> = item["embedding"]
+                .as_array()
+                .ok_or("Invalid response: missing embedding array")?
+                .iter()
+                .map(|v| v.as_f64().unwrap_or(0.0) as f32)
+                .collect();
+            if let Some(slot) = embeddings.get_mut(index) {
+                *slot = Some(embedding);
+            }
         }
-
-        Ok(embedding)
+        embeddings
+            .into_iter()
+            .map(|e| e.filter(|e| !e.is_empty()).ok_or_else(|| "API returned fewer embeddings than inputs".to_string()))
+            .collect()
     }
 
     /// Generate embedding using local embedding model (simple mode)
@@ -3756,57 +10448,391 @@ impl HelixMcpServer {
     }
 
     async fn generate_tcp_embedding(&self, text: &str) -> Result<Vec<f32>, String> {
-        let tcp_addr = self.config.embedding.tcp_address.as_ref()
+        let client = self.tcp_embedder.as_ref()
             .ok_or("TCP address not configured in mcpconfig.toml")?;
 
-        info!("Generating TCP embedding at {}", tcp_addr);
-
-        // Use the embedding_client module
-        let client = embedding_client::EmbeddingClient::new(
-            tcp_addr.clone(), 
-            self.config.embedding.tcp_timeout_secs
-        );
-
-        // Connect and generate embedding
+        // Pooled connection to the embedding server
         let embedding = client.embed_text(text).await
             .map_err(|e| format!("TCP embedding request failed: {}", e))?;
 
         info!("TCP embedding generated: {} dimensions", embedding.len());
         Ok(embedding)
     }
+
+    /// Embeddings for several texts, pipelined to the TCP embedding server
+    async fn generate_tcp_embeddings(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String> {
+        let client = self.tcp_embedder.as_ref()
+            .ok_or("TCP address not configured in mcpconfig.toml")?;
+        let embeddings = client.embed_texts(texts).await
+            .map_err(|e| format!("TCP embedding request failed: {}", e))?;
+        info!("TCP embeddings generated for {} texts", embeddings.len());
+        Ok(embeddings)
+    }
 }
 
 
-#[tool_handler]
 impl ServerHandler for HelixMcpServer {
+    /// Tool dispatch: charges the session budget, then routes to the tool
+    async fn call_tool(
+        &self,
+        mut request: rmcp::model::CallToolRequestParam,
+        context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+
+        if !self.config.traversal.exposes(&request.name) {
+            return Ok(tool_error(ErrorCode::Disabled, format!("{} is a graph traversal tool, which is disabled", request.name), json!({
+                "tool": request.name,
+                "suggestion": "Set enabled = true in the [traversal] section of mcpconfig.toml"
+            })));
+        }
+
+        if let Err(e) = self.budget.charge_tool_call() {
+            warn!("Session {} over budget: {}", self.session_id, e);
+            return Ok(tool_error(ErrorCode::RateLimited, e.to_string(), json!({
+                "tool": request.name,
+                "budget": self.budget.usage(),
+                "suggestion": "Stop and summarize progress, or start a new session. Limits are set in [budget] of mcpconfig.toml"
+            })));
+        }
+
+        let violations = request.arguments.as_ref().map(|a| validation::validate(&request.name, a)).unwrap_or_default();
+        if !violations.is_empty() {
+            return Ok(tool_error(ErrorCode::ValidationFailed, format!("Invalid arguments for {}", request.name), json!({
+                "tool": request.name,
+                "validation_errors": violations,
+                "suggestion": "Fix the listed fields and call again; nothing was written"
+            })));
+        }
+
+        let access = sandbox::access(&request.name, request.arguments.as_ref());
+        let arguments = request.arguments.get_or_insert_with(Default::default);
+        if let Err(e) = self.sandbox.prepare(&self.session_id, access, arguments) {
+            return Ok(tool_error(ErrorCode::Unauthorized, e, json!({
+                "tool": request.name,
+                "sandbox": true,
+                "suggestion": "Create the memory inside the sandbox first, or call set_sandbox_mode(enabled: false)"
+            })));
+        }
+
+        // Mutating tools without their own dry_run get one through the recording client
+        let native_dry_run = self.tool_router.map.get(request.name.as_ref()).is_some_and(|route| dry_run::native(&route.attr));
+        let dry_run_requested = arguments.get("dry_run").and_then(|v| v.as_bool()) == Some(true);
+        let dry_run = access != sandbox::Access::Read && (self.config.dry_run.enforce || (dry_run_requested && !native_dry_run));
+        if dry_run {
+            if native_dry_run {
+                arguments.insert("dry_run".to_string(), json!(true));
+            } else {
+                arguments.remove("dry_run");
+            }
+        }
+
+        // Read tools given a locale add a `display` section with formatted values
+        let display = if access == sandbox::Access::Read {
+            let timezone = arguments.remove("timezone").and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default();
+            match arguments.remove("locale").and_then(|v| v.as_str().map(str::to_string)) {
+                Some(tag) => {
+                    let Some(display_locale) = locale::parse(&tag) else {
+                        return Ok(tool_error(ErrorCode::ValidationFailed, format!("Unsupported locale: {}", tag), json!({
+                            "tool": request.name,
+                            "valid_values": locale::supported()
+                        })));
+                    };
+                    let Some(offset) = hours::parse_offset(&timezone) else {
+                        return Ok(tool_error(ErrorCode::ValidationFailed, format!("Unsupported timezone: {}", timezone), json!({
+                            "tool": request.name,
+                            "suggestion": "Use a UTC offset such as +02:00 or an abbreviation such as CET"
+                        })));
+                    };
+                    Some((display_locale, timezone, offset))
+                }
+                None => None,
+            }
+        } else {
+            None
+        };
+
+        // Create tools run once per idempotency_key; repeats get the first result back
+        let idempotency_key = arguments.remove("idempotency_key")
+            .and_then(|k| k.as_str().map(str::to_string))
+            .filter(|_| access == sandbox::Access::Create && !dry_run);
+        if let Some(key) = &idempotency_key {
+            match self.idempotency.claim(&request.name, key, arguments) {
+                idempotency::Claim::New => {}
+                idempotency::Claim::Replay(mut content) => {
+                    if let Some(fields) = content.as_object_mut() {
+                        fields.insert("idempotent_replay".to_string(), json!(true));
+                    }
+                    return Ok(CallToolResult::structured(content));
+                }
+                idempotency::Claim::InFlight => {
+                    return Ok(tool_error(ErrorCode::Conflict, "A call with this idempotency_key is still running", json!({
+                        "tool": request.name,
+                        "idempotency_key": key,
+                        "retryable": true,
+                        "retry_after_secs": 1
+                    })));
+                }
+                idempotency::Claim::Mismatch => {
+                    return Ok(tool_error(ErrorCode::Conflict, "idempotency_key was already used with different arguments", json!({
+                        "tool": request.name,
+                        "idempotency_key": key,
+                        "suggestion": "Use a new idempotency_key for a different call"
+                    })));
+                }
+            }
+        }
+
+        let tool = request.name.to_string();
+        let tool_call_id = context.id.to_string();
+        let argument = |field: &str| arguments.get(field).and_then(|v| v.as_str()).map(str::to_string);
+        let (customer_id, business_id) = (argument("customer_id"), argument("business_id"));
+        let memory_type = argument("memory_type").or_else(|| argument("navigation_type"));
+        let sandboxed = self.sandbox.is_sandboxed(&self.session_id);
+        let spillable = tool != "fetch_result_page";
+        let alert_record = (!sandboxed && !self.alerts.is_empty()).then(|| alerts::record(arguments));
+
+        let tcc = rmcp::handler::server::tool::ToolCallContext::new(self, request, context);
+        let call = budget::scope(self.budget.clone(), self.tool_router.call(tcc));
+        if dry_run {
+            let recorder = Arc::new(dry_run::Recorder::default());
+            let result = dry_run::scope(recorder.clone(), call).await;
+            let (content, is_error) = match result {
+                Ok(result) => (result.structured_content, result.is_error == Some(true)),
+                Err(e) => (Some(json!({"error": e.message})), true),
+            };
+            info!("Dry run of {} (session {})", tool, self.session_id);
+            return Ok(CallToolResult::structured(dry_run::report(&tool, &recorder, content, is_error)));
+        }
+        let result = match (call.await, &display) {
+            (Ok(CallToolResult { structured_content: Some(mut content), is_error, .. }), Some((display_locale, timezone, offset)))
+                if is_error != Some(true) && content.is_object() =>
+            {
+                content["display"] = locale::display(&content, display_locale, timezone, *offset);
+                Ok(CallToolResult::structured(content))
+            }
+            (result, _) => result,
+        };
+
+        if let Some(key) = &idempotency_key {
+            match &result {
+                Ok(CallToolResult { structured_content: Some(content), is_error, .. }) if *is_error != Some(true) => {
+                    self.idempotency.complete(&tool, key, content.clone())
+                }
+                _ => self.idempotency.release(&tool, key),
+            }
+        }
+        if let Ok(CallToolResult { structured_content: Some(content), is_error, .. }) = &result {
+            if access == sandbox::Access::Create {
+                self.sandbox.record(&self.session_id, content);
+            }
+            if let Some(action) = webhooks::action(&tool, content).filter(|_| !sandboxed && *is_error != Some(true)) {
+                let memory_type = memory_type.as_deref()
+                    .map(|t| memory_types::normalize(t).to_string())
+                    .unwrap_or_else(|| webhooks::tool_memory_type(&tool).to_string());
+                let event = format!("{}.{}", memory_type, action);
+                let notify_webhook = self.webhooks.wants(&event);
+                if notify_webhook || self.changes.has_subscribers() {
+                    let (id, timestamp) = (self.ids.next_id().to_string(), self.clock.timestamp());
+                    self.changes.publish(change_feed::ChangeEvent {
+                        id: id.clone(),
+                        memory_type: memory_type.clone(),
+                        operation: action.to_string(),
+                        memory_ids: access_log::memory_ids(content),
+                        business_id: business_id.clone(),
+                        customer_id: customer_id.clone(),
+                        tool: tool.clone(),
+                        timestamp,
+                        alert: None,
+                    });
+                    if notify_webhook {
+                        self.webhooks.dispatch(webhooks::WebhookEvent {
+                            id,
+                            event,
+                            timestamp,
+                            tool: tool.clone(),
+                            memory_type: memory_type.clone(),
+                            action: action.to_string(),
+                            business_id: business_id.clone(),
+                            customer_id: customer_id.clone(),
+                            data: content.clone(),
+                        });
+                    }
+                }
+                if let Some(record) = alert_record.as_ref().filter(|_| action != "deleted") {
+                    for alert in self.alerts.triggered(&memory_type, business_id.as_deref(), record) {
+                        info!("Alert '{}' triggered by {} ({})", alert.rule, tool, memory_type);
+                        let (id, timestamp) = (self.ids.next_id().to_string(), self.clock.timestamp());
+                        self.changes.publish(change_feed::ChangeEvent {
+                            id: id.clone(),
+                            memory_type: memory_type.clone(),
+                            operation: "alert".to_string(),
+                            memory_ids: access_log::memory_ids(content),
+                            business_id: business_id.clone(),
+                            customer_id: customer_id.clone(),
+                            tool: tool.clone(),
+                            timestamp,
+                            alert: Some(alert.rule.clone()),
+                        });
+                        if self.webhooks.wants("alert.triggered") {
+                            self.webhooks.dispatch(webhooks::WebhookEvent {
+                                id,
+                                event: "alert.triggered".to_string(),
+                                timestamp,
+                                tool: tool.clone(),
+                                memory_type: memory_type.clone(),
+                                action: "triggered".to_string(),
+                                business_id: business_id.clone(),
+                                customer_id: customer_id.clone(),
+                                data: json!({"rule": alert.rule, "matched": alert.matched, "result": content}),
+                            });
+                        }
+                    }
+                }
+            }
+            let logged = access == sandbox::Access::Read && tool != "get_access_log" && *is_error != Some(true);
+            if logged && self.config.access_log.enabled {
+                let memory_ids = access_log::memory_ids(content);
+                if !memory_ids.is_empty() {
+                    self.access_log.record(access_log::AccessEntry {
+                        tool_call_id,
+                        timestamp: self.clock.timestamp(),
+                        session_id: self.session_id.to_string(),
+                        tool,
+                        customer_id,
+                        business_id,
+                        memory_ids,
+                    });
+                }
+            }
+        }
+        result
+            .map(errors::annotate)
+            .map(|result| if spillable { self.spill_oversized(result) } else { result })
+            .map_err(errors::annotate_protocol)
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<rmcp::model::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<rmcp::model::ListToolsResult, McpError> {
+        let mut tools = self.tool_router.list_all();
+        tools.retain(|t| self.config.traversal.exposes(&t.name));
+        for tool in tools.iter_mut().filter(|t| sandbox::access(&t.name, None) != sandbox::Access::Read) {
+            dry_run::advertise(tool);
+        }
+        for tool in tools.iter_mut().filter(|t| sandbox::access(&t.name, None) == sandbox::Access::Create) {
+            idempotency::advertise(tool);
+        }
+        for tool in tools.iter_mut().filter(|t| sandbox::access(&t.name, None) == sandbox::Access::Read) {
+            locale::advertise(tool);
+        }
+        if let (Some(manifest), Some(tool)) = (self.query_manifest.as_ref(), tools.iter_mut().find(|t| t.name == "do_query")) {
+            let signatures: Vec<String> = DO_QUERY_ENDPOINTS.iter().filter_map(|e| manifest.signature(e)).collect();
+            let description = tool.description.as_deref().unwrap_or_default();
+            tool.description = Some(format!("{}\n\nEndpoints and their required payload fields:\n{}", description, signatures.join("\n")).into());
+        }
+        Ok(rmcp::model::ListToolsResult::with_all_items(tools))
+    }
+
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             capabilities: ServerCapabilities::builder()
                 .enable_tools()
                 .enable_resources()
+                .enable_resources_subscribe()
+                .enable_prompts()
                 .build(),
             instructions: Some(
                 "AI Memory Layer - Business & customer intelligence system.\n\n\
                 SEARCH STRATEGY: Use search_bm25 for exact terms/IDs/numbers, search_semantic for concepts.\n\n\
+                DRY RUN: Any create/update/delete tool accepts dry_run: true to report the writes it would make without making them.\n\n\
+                VALIDATION: Invalid enum values or out-of-range numbers are rejected up front with validation_errors listing each field and its valid values.\n\n\
+                ERRORS: Every error has a code (NOT_FOUND, VALIDATION_FAILED, EMBEDDING_UNAVAILABLE, HELIX_UNREACHABLE, UNAUTHORIZED, RATE_LIMITED, CONFLICT, DISABLED, INTERNAL) and retryable; only retry when retryable is true, after retry_after_secs if given.\n\n\
+                IDEMPOTENCY: Create tools accept idempotency_key; repeating a call with the same key returns the original result (idempotent_replay: true) instead of a duplicate.\n\n\
+                LOCALE: Read tools accept locale (e.g. de-DE) and timezone (e.g. +01:00); the result then has a display section with prices, dates and opening hours already formatted - quote those instead of converting yourself.\n\n\
+                CHANNELS: query_customer_memory, query_customer_interactions and the search tools accept channel (whatsapp, website, store, phone, email, ...; comma-separated) to keep only memories from those channels. Location visits count as store; memories without a channel never match.\n\n\
                 CORE TOOLS:\n\
                 • query_business_memory / query_customer_memory - Filter by criteria\n\
                 • search_semantic - Find keywords (exact matches, IDs, phone numbers)\n\
+                • search_semantic_batch - Several semantic searches in one call, results per query\n\
+                • onboard_business - Create a business with its initial products, services, locations and hours\n\
+                • clone_business_memories - Copy a template business's memories to another business\n\
+                • adjust_product_stock - Record sales, deliveries or stock takes; availability follows stock\n\
+                • get_low_stock_products - Tracked products at or below their reorder threshold\n\
+                • get_price_history - When and how a product's or service's price changed\n\
+                • diff_memories - Field and embedding diff of two memories or of two businesses' memories\n\
+                • create_snapshot - Save a business record and its business memories to a versioned snapshot file\n\
+                • restore_snapshot - Roll a business back to a snapshot after a bad bulk operation\n\
                 • create_business_memory / create_customer_memory - Add new memories\n\
+                • store_conversation_summary - Store chunked conversation transcripts for recall\n\
                 • update_business_memory / update_customer_memory - Modify existing\n\
-                • delete_memory - Remove memories\n\n\
+                • pin_memory - Pin a customer memory / set its importance so critical facts rank first\n\
+                • upsert_business_memory - Create or update by source-system ID (idempotent sync)\n\
+                • sync_shopify_catalog - Pull the Shopify store's products into product memories\n\
+                • import_events_ics - Create/update event memories from an iCalendar feed or file\n\
+                • delete_memory - Remove memories\n\
+                • preview_delete - What a cascade/complete delete would remove (nodes, edges, vectors)\n\
+                • delete_memories_by_filter - Bulk delete matching memories (dry-run preview first)\n\
+                • merge_memories - Consolidate duplicate memories into one\n\
+                • merge_customers - Fold a duplicate customer profile into the canonical one\n\
+                • submit_job / get_job_status / cancel_job - Run imports, re-embeddings and cascade deletes in the background\n\
+                • apply_decay - Age out stale preferences and behaviors\n\
+                • verify_embeddings - Flag stored vectors that no longer match their memory's text\n\
+                • repair_graph - Find and fix orphaned vectors, missing embeddings and dangling interactions\n\
+                • maintenance_status - Schedules and last-run reports of scheduled maintenance\n\
+                • backfill_embeddings - Re-embed memories under a rate budget\n\
+                • get_memory_stats - Node/vector counts per memory type, last activity and per-business breakdown\n\
+                • get_embedding_metadata - Embedding model recorded per collection vs the configured model\n\
+                • set_embedding_metadata - Record a collection's model after re-embedding\n\
+                • remember_for_session / recall_session - Ephemeral session notes (not persisted)\n\
+                • fetch_result_page - Next page of a result too large to send at once\n\
+                • set_sandbox_mode - Send this session's writes to a disposable shadow tenant\n\
+                • get_access_log - Which memories were read per tool call and customer\n\n\
                 INTERACTIONS:\n\
                 • create_customer_product_interaction - Track product engagement\n\
                 • create_customer_service_interaction - Track service usage\n\
                 • query_customer_interactions / search_customer_interactions - Find interactions\n\
-                • update_interaction - Modify interactions\n\n\
+                • analyze_interactions - Counts, ratings, spend, top products and trends\n\
+                • get_product_affinities - Products frequently liked together\n\
+                • explain_relationship - Graph evidence linking a customer to a product or service\n\
+                • summarize_feedback - Sentiment rollup, unresolved counts, negative items, themes\n\
+                • list_open_issues - Unresolved dislikes, low ratings and complaints, oldest first\n\
+                • resolve_issue - Close an issue, stamp resolved_at and store the resolution note\n\
+                • create_alert_rule / list_alert_rules - Webhook/SSE alerts when writes match conditions (e.g. rating <= 2)\n\
+                • evaluate_customer_rules - Allow/deny a proposed action against the customer's active rules\n\
+                • is_business_open - Open/closed now (or at a time), next opening, holiday exceptions\n\
+                • get_service_availability - Open booking slots for a service on a day, with a check of a requested time\n\
+                • set_loyalty_rule - How a business's customers earn points on purchases\n\
+                • get_customer_points / redeem_points - Loyalty points balance and redemption\n\
+                • update_business_hours_exceptions - Add/change/remove holiday and special-hours exceptions with conflict checks\n\
+                • get_effective_policy - Policy version in effect for a type and date\n\
+                • supersede_policy - Publish a new policy version and retire the old one\n\
+                • list_upcoming_events - Scheduled events in a date window with places remaining\n\
+                • close_past_events - Mark ended events as closed\n\
+                • update_interaction - Modify interaction text, rating, amount, resolution/outcome\n\n\
                 NAVIGATION:\n\
                 • create_navigation_hub / create_navigation_waypoint / create_direction_path\n\
                 • query_navigation / search_navigation - Get directions\n\
+                • search_nearby - Locations and hubs within a radius, sorted by distance\n\
+                • compute_bearing - Compass bearing, distance and directions to a navigation hub\n\
+                • get_route - Ordered route steps with bearings and timing for a direction path\n\
+                • import_navigation_geojson - Create waypoints and paths from a GeoJSON FeatureCollection\n\
+                • create_navigation_floor / create_floor_connector - Floors and elevator/stair links for indoor routes\n\
+                • locate_customer - Match \"what I can see\" to the nearest waypoint for lost customers\n\
+                • start_location_sharing / get_live_guidance / stop_location_sharing - Real-time guidance from pushed GPS fixes\n\
                 • update_navigation - Modify navigation\n\n\
                 INSIGHTS:\n\
                 • find_customer_insights - Discover customer relationships\n\n\
+                IMPORT / EXPORT:\n\
+                • import_memories - Import mem0, Zep, or LangChain memory exports\n\
+                • export_embedding_dataset - Export embeddings as a generic JSONL vector dataset\n\
+                • export_customer_data - Everything stored about a customer, as a Markdown report or a JSON document\n\
+                • export_graph_viz - A business or customer subgraph as GraphViz DOT or Cytoscape JSON\n\
+                • forget_customer - Right-to-erasure purge of a customer with a deletion manifest and audit record\n\n\
                 ADVANCED:\n\
-                • do_query - Direct database queries (last resort)".to_string()
+                • do_query - Direct database queries (last resort); its description lists each endpoint's payload fields\n\n\
+                PROMPTS: onboard_business, summarize_customer, log_interaction - guided multi-tool workflows".to_string()
             ),
             ..Default::default()
         }
@@ -3832,12 +10858,33 @@ impl ServerHandler for HelixMcpServer {
         schema.description = Some("Complete schema documentation for business and customer memories".to_string());
         schema.mime_type = Some("text/plain".to_string());
         
+        let mut cache = RawResource::new("meta://embedding-cache", "Query Embedding Cache");
+        cache.description = Some("Hit/miss metrics for the search query embedding cache".to_string());
+        cache.mime_type = Some("application/json".to_string());
+
+        let mut resources = vec![
+            about.no_annotation(),
+            instructions.no_annotation(),
+            schema.no_annotation(),
+            cache.no_annotation(),
+        ];
+
+        // Live views for the businesses and customers agents have been working with
+        let recent = self.access_log.query(&access_log::AccessQuery::default(), 200);
+        for subject in resources::recent_subjects(&recent, 20) {
+            let (name, description) = match &subject {
+                resources::Dynamic::BusinessSummary(id) => (format!("Business {} summary", id), "Stored memories per type for this business"),
+                resources::Dynamic::CustomerProfile(id) => (format!("Customer {} profile", id), "Memories, interaction totals and feedback for this customer"),
+                resources::Dynamic::BusinessCollection(id, collection) => (format!("Business {} {}", id, collection), "One memory type for this business"),
+            };
+            let mut resource = RawResource::new(subject.uri(), name);
+            resource.description = Some(description.to_string());
+            resource.mime_type = Some("application/json".to_string());
+            resources.push(resource.no_annotation());
+        }
+
         Ok(rmcp::model::ListResourcesResult {
-            resources: vec![
-                about.no_annotation(),
-                instructions.no_annotation(),
-                schema.no_annotation(),
-            ],
+            resources,
             next_cursor: None,
         })
     }
@@ -3850,6 +10897,22 @@ impl ServerHandler for HelixMcpServer {
         use rmcp::model::ResourceContents;
         
         let uri = &request.uri;
+        if uri == "meta://embedding-cache" {
+            return Ok(rmcp::model::ReadResourceResult {
+                contents: vec![ResourceContents::text(self.query_cache.stats().to_string(), uri.clone())],
+            });
+        }
+        if let Some(dynamic) = resources::Dynamic::parse(uri) {
+            let document = self.render_dynamic_resource(&dynamic).await?;
+            return Ok(rmcp::model::ReadResourceResult {
+                contents: vec![ResourceContents::TextResourceContents {
+                    uri: uri.clone(),
+                    mime_type: Some("application/json".to_string()),
+                    text: serde_json::to_string_pretty(&document).unwrap_or_default(),
+                    meta: None,
+                }],
+            });
+        }
         let content = match uri.as_str() {
             "meta://about" => {
                 "# AI Memory Layer MCP Server\n\n\
@@ -3869,7 +10932,12 @@ impl ServerHandler for HelixMcpServer {
                 Navigation: Hubs, Waypoints, Paths (with compass bearings)\n\n\
                 ## Search\n\
                 - search_bm25: Keywords (exact, fast)\n\
-                - search_semantic: Meaning (concepts, similar)"
+                - search_semantic: Meaning (concepts, similar)\n\n\
+                ## Live Resources\n\
+                - memory://business/{id}/summary: Stored memories per type\n\
+                - memory://business/{id}/{collection}: products, services, locations, hours, social, policies, events, information\n\
+                - memory://customer/{id}/profile: Memories, interaction totals, feedback\n\
+                Subscribe to any of these for update notifications when their memories change."
             },
             "meta://instructions" => {
                 "# AI Memory Layer - Usage Instructions\n\n\
@@ -3933,7 +11001,8 @@ impl ServerHandler for HelixMcpServer {
                 ## Navigation (3 types)\n\
                 1. NavigationHub: navigation_id, business_id, address, lat/lng, what3words, plus_code, compass_bearing, building details, accessibility\n\
                 2. NavigationWaypoint: waypoint_id, navigation_id, name, type, description, visual/audio cues, compass_bearing, floor_level\n\
-                3. DirectionPath: path_id, navigation_id, name, type, transport_mode, duration, distance, compass waypoints, accessibility flags\n\n\
+                3. DirectionPath: path_id, navigation_id, name, type, transport_mode, duration, distance, compass waypoints, accessibility flags\n\
+                Floors: NavigationFloor (floor_id, floor_level, floor_name) groups waypoints; FloorConnector edges (elevator/escalator/ramp/stairs) join waypoints across floors\n\n\
                 All types have vector embeddings for semantic search."
             },
             _ => {
@@ -3948,9 +11017,66 @@ impl ServerHandler for HelixMcpServer {
             contents: vec![ResourceContents::text(content, uri.clone())],
         })
     }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<rmcp::model::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<rmcp::model::ListResourceTemplatesResult, McpError> {
+        let templates = resources::templates().into_iter().map(|t| t.no_annotation()).collect();
+        Ok(rmcp::model::ListResourceTemplatesResult::with_all_items(templates))
+    }
+
+    async fn subscribe(
+        &self,
+        request: rmcp::model::SubscribeRequestParam,
+        context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<(), McpError> {
+        // Static meta:// documents never change
+        let Some(resource) = resources::Dynamic::parse(&request.uri) else {
+            return Err(McpError::invalid_params("Only memory:// resources support subscriptions", Some(json!({"uri": request.uri}))));
+        };
+        info!("Session {} subscribed to {}", self.session_id, request.uri);
+        self.subscriptions.subscribe(resource, &self.changes, context.peer);
+        Ok(())
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: rmcp::model::UnsubscribeRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<(), McpError> {
+        if !self.subscriptions.unsubscribe(&request.uri) {
+            return Err(McpError::invalid_params("Not subscribed to this resource", Some(json!({"uri": request.uri}))));
+        }
+        Ok(())
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<rmcp::model::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<rmcp::model::ListPromptsResult, McpError> {
+        Ok(rmcp::model::ListPromptsResult::with_all_items(prompts::list()))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: rmcp::model::GetPromptRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<rmcp::model::GetPromptResult, McpError> {
+        prompts::get(&request.name, request.arguments.as_ref())
+            .map_err(|e| McpError::invalid_params(e.to_string(), Some(json!({"prompt": request.name}))))
+    }
 }
 
 fn main() -> Result<()> {
+    // Offline utility: helix-mcp-server decrypt-archive <input> <output>
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("decrypt-archive") {
+        return decrypt_archive_command(&args[2..]);
+    }
+
     tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
@@ -3958,6 +11084,21 @@ fn main() -> Result<()> {
         .block_on(async_main())
 }
 
+/// Decrypt an encrypted export archive using the configured export key
+fn decrypt_archive_command(args: &[String]) -> Result<()> {
+    let [input, output] = args else {
+        anyhow::bail!("Usage: helix-mcp-server decrypt-archive <input> <output>");
+    };
+    let config = Config::load()?;
+    let key = config.get_export_key()
+        .ok_or_else(|| anyhow::anyhow!("No export key configured (set [export] encryption_key or HELIX_EXPORT_KEY)"))?;
+    let archive = std::fs::read(input)?;
+    let plaintext = crypto::decrypt_archive(&archive, &key).map_err(|e| anyhow::anyhow!(e))?;
+    std::fs::write(output, plaintext)?;
+    eprintln!("Decrypted {} -> {}", input, output);
+    Ok(())
+}
+
 /// Test local embedding server connection
 async fn test_local_embedding_connection(url: &str) -> Result<()> {
     let client = reqwest::Client::builder()
@@ -3981,15 +11122,14 @@ async fn test_local_embedding_connection(url: &str) -> Result<()> {
     }
 }
 
-async fn test_tcp_embedding_connection(addr: &str, timeout_secs: u64) -> Result<()> {
+async fn test_tcp_embedding_connection(addr: &str, timeout_secs: u64, dimensions: usize) -> Result<(), Box<dyn std::error::Error>> {
     let timeout = timeout_secs.min(10); // Cap at 10 seconds for connection test
     
     // Try to connect to TCP server and send a test request
-    let client = embedding_client::EmbeddingClient::new(addr.to_string(), timeout);
+    let client = embedding_client::EmbeddingClient::new(addr.to_string(), timeout).with_dimensions(dimensions);
     
     // Try a simple test embedding
-    let _result = client.embed_text("connection test").await
-        .map_err(|e| anyhow::anyhow!("TCP test request failed: {}", e))?;
+    client.embed_text("connection test").await?;
     
     Ok(())
 }
@@ -4035,7 +11175,7 @@ async fn async_main() -> Result<()> {
                     }
                 } else {
                     error!("   API Key: ? MISSING!");
-                    error!("   Please set OPENAI_API_KEY or GEMINI_API_KEY environment variable");
+                    error!("   Please set OPENAI_API_KEY, AZURE_OPENAI_API_KEY or GEMINI_API_KEY environment variable");
                     error!("   Or add 'api_key' to mcpconfig.toml [embedding] section");
                     anyhow::bail!("API key required for cloud embedding providers");
                 }
@@ -4073,12 +11213,16 @@ async fn async_main() -> Result<()> {
                     info!("   Testing TCP embedding server connection...");
                     
                     // Test connection to TCP server
-                    match test_tcp_embedding_connection(addr, config.embedding.tcp_timeout_secs).await {
+                    match test_tcp_embedding_connection(addr, config.embedding.tcp_timeout_secs, config.embedding.vector_dimensions()).await {
                         Ok(()) => {
                             info!("   ? TCP embedding server is reachable");
                         }
+                        Err(e) if e.is::<embedding_client::DimensionMismatch>() => {
+                            error!("   ? Configuration error: {}", e);
+                            anyhow::bail!("TCP embedding dimensions do not match [embedding] dimensions");
+                        }
                         Err(e) => {
-                            error!("   ? Cannot connect to TCP embedding server: {}", e);
+                            error!("   ? Cannot connect to TCP embedding server: TCP test request failed: {}", e);
                             error!("   Make sure EmbeddingServer is running at {}", addr);
                             error!("   Example: cd EmbeddingServer && cargo run");
                             anyhow::bail!("TCP embedding server not reachable");
@@ -4109,7 +11253,42 @@ async fn async_main() -> Result<()> {
         .unwrap_or(config.helix.port);
 
     info!(" Connecting to HelixDB at {}:{}", endpoint, port);
-    let helix_client = Arc::new(HelixClient::new(&endpoint, port));
+    let mut helix_client = HelixClient::new(&endpoint, port)
+        .with_background_concurrency(config.priority.background_concurrency)
+        .with_payload_format(config.helix.payload_format.clone());
+    if config.helix.payload_format != config::PayloadFormat::Json {
+        info!("   Query payloads encoded as {:?}", config.helix.payload_format);
+    }
+    let read_port_override = std::env::var("HELIX_READ_PORT").ok();
+    if let Some((read_endpoint, read_port)) = config.helix.read_replica(std::env::var("HELIX_READ_ENDPOINT").ok(), read_port_override.as_deref(), port) {
+        info!("   Read-only queries go to replica {}:{} (primary as fallback)", read_endpoint, read_port);
+        helix_client = helix_client.with_read_replica(&read_endpoint, read_port);
+    }
+    if !config.field_encryption.fields.is_empty() {
+        let key = config.get_field_key();
+        let business_keys = config.get_business_field_keys().map_err(anyhow::Error::msg)?;
+        if key.is_none() && business_keys.is_empty() {
+            error!(" Field encryption is configured but no key is set");
+            error!("   Set key or business_keys in [field_encryption] of mcpconfig.toml or the HELIX_FIELD_KEY environment variable");
+            anyhow::bail!("Missing field encryption key");
+        }
+        info!(" Field-level encryption enabled for: {}", config.field_encryption.fields.join(", "));
+        let mut cipher = crypto::FieldCipher::new(key.as_deref(), &config.field_encryption.fields).map_err(anyhow::Error::msg)?;
+        for (business_id, business_key) in &business_keys {
+            cipher = cipher.with_business_key(business_id, business_key).map_err(anyhow::Error::msg)?;
+        }
+        if !business_keys.is_empty() {
+            info!("   Own keys for {} businesses{}", business_keys.len(), if key.is_none() { " (no default key: other data is refused)" } else { "" });
+        }
+        helix_client = helix_client.with_field_encryption(cipher);
+    }
+    if let Some(key) = config.get_export_key() {
+        if let Err(e) = crypto::parse_key(&key) {
+            error!(" Invalid export encryption key: {}", e);
+            anyhow::bail!("Invalid export encryption key");
+        }
+    }
+    let helix_client = Arc::new(helix_client);
     
     match helix_client.test_connection().await {
         Ok(_) => info!(" Connected to HelixDB"),
@@ -4119,8 +11298,121 @@ async fn async_main() -> Result<()> {
         }
     }
 
+    // Ingest signature verification
+    if config.get_ingest_secret().is_some() {
+        match signature::SignatureScheme::parse(&config.ingest.signature_scheme) {
+            Some(scheme) => info!(" Ingest signature verification: {} ({} header)", config.ingest.signature_scheme, scheme.header_name()),
+            None => {
+                error!(" Unknown ingest signature_scheme '{}'", config.ingest.signature_scheme);
+                error!("   Use one of: shopify, stripe, hmac-sha256");
+                anyhow::bail!("Invalid ingest signature scheme");
+            }
+        }
+    } else if config.server.enable_http {
+        warn!(" No ingest signing secret: POST /ingest and GET /events answer 503 until [ingest] signing_secret is set");
+    }
+
     let server = HelixMcpServer::new(helix_client, Arc::new(config.clone()));
+
+    // Embedding provider handshake: trust what the provider reports, not just the config
+    if config.is_mcp_embedding_enabled() {
+        match server.negotiate_embedding_provider().await {
+            Ok(provider) => info!(" Embedding provider: {} ({} dimensions, from {})", provider.model, provider.dimensions, provider.source),
+            Err(embedding_meta::NegotiationError::Unreachable(e)) => {
+                warn!(" {} - continuing without checking the provider's model and dimensions", e);
+            }
+            Err(embedding_meta::NegotiationError::Mismatch(e)) => {
+                error!(" {}", e);
+                anyhow::bail!("Embedding provider does not match the configuration");
+            }
+        }
+    }
+
+    // Background confidence decay
+    if config.decay.enabled {
+        let decay_server = server.clone();
+        let interval_hours = config.decay.interval_hours.max(1);
+        info!("⏳ Confidence decay enabled (every {}h)", interval_hours);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_hours * 3600));
+            loop {
+                interval.tick().await;
+                let policy = decay::DecayPolicy {
+                    half_life_days: decay_server.config.decay.half_life_days,
+                    min_score: decay_server.config.decay.min_score,
+                    now: decay_server.clock.timestamp(),
+                };
+                priority::background(decay_server.run_decay(None, &policy, false)).await;
+            }
+        });
+    }
     
+    // Live exchange rates replace the static [currency] table once fetched
+    if !config.currency.rates_url.is_empty() {
+        let rates_server = server.clone();
+        let provider = currency::HttpRateProvider::new(&config.currency.rates_url);
+        let refresh_secs = config.currency.refresh_secs.max(60);
+        info!("💱 Live exchange rates from {} (every {}s)", config.currency.rates_url, refresh_secs);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(refresh_secs));
+            loop {
+                interval.tick().await;
+                match currency::RateProvider::fetch(&provider).await {
+                    Ok(mut rates) => {
+                        rates.updated_at = Some(rates_server.clock.timestamp());
+                        rates_server.exchange_rates.update(rates);
+                    }
+                    Err(e) => warn!("Keeping previous exchange rates: {}", e),
+                }
+            }
+        });
+    }
+
+    // Scheduled maintenance
+    if config.maintenance.enabled {
+        let schedules = match maintenance::schedules(&config.maintenance) {
+            Ok(schedules) => schedules,
+            Err(e) => {
+                error!(" Invalid [maintenance] schedule: {}", e);
+                anyhow::bail!("Invalid maintenance schedule");
+            }
+        };
+        let summary: Vec<String> = schedules.iter().map(|(task, s)| format!("{} ({})", task.name(), s.expression)).collect();
+        info!("🧹 Scheduled maintenance enabled: {}", summary.join(", "));
+        let maintenance_server = server.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            let mut last_minute: Option<i64> = None;
+            loop {
+                interval.tick().await;
+                let minute = maintenance_server.clock.timestamp() / 60;
+                // Minutes skipped while a long task ran still count
+                let from = last_minute.map_or(minute, |m| m + 1);
+                last_minute = Some(minute);
+                for (task, schedule) in &schedules {
+                    let due = (from..=minute)
+                        .filter_map(|m| chrono::DateTime::from_timestamp(m * 60, 0))
+                        .any(|at| schedule.matches(at));
+                    if due {
+                        priority::background(maintenance_server.run_maintenance(*task)).await;
+                    }
+                }
+            }
+        });
+    }
+
+    // Sandbox cleanup
+    let sandbox_server = server.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            for tenant in sandbox_server.sandbox.take_expired() {
+                priority::background(sandbox_server.discard_sandbox(tenant)).await;
+            }
+        }
+    });
+
     // Check which transports are enabled
     let tcp_enabled = config.server.enable_tcp;
     let http_enabled = config.server.enable_http;
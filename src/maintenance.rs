@@ -0,0 +1,176 @@
+//! Scheduled memory maintenance
+//!
+//! `[maintenance]` gives each housekeeping task a cron-style schedule
+//! (`minute hour day month weekday`, UTC, with `*`, lists, ranges and
+//! `/step`). The scheduler wakes once a minute and runs the tasks that are
+//! due: closing past events, confidence decay, pruning embedding vectors
+//! whose memory node is gone and checking embedding dimensions. Each run's
+//! report is logged and kept for `maintenance_status`.
+
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::config::MaintenanceConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Task {
+    ExpireEvents,
+    Decay,
+    PruneOrphanVectors,
+    VerifyEmbeddings,
+}
+
+pub const TASKS: [Task; 4] = [Task::ExpireEvents, Task::Decay, Task::PruneOrphanVectors, Task::VerifyEmbeddings];
+
+impl Task {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ExpireEvents => "expire_events",
+            Self::Decay => "decay",
+            Self::PruneOrphanVectors => "prune_orphan_vectors",
+            Self::VerifyEmbeddings => "verify_embeddings",
+        }
+    }
+
+    fn schedule(self, config: &MaintenanceConfig) -> &str {
+        match self {
+            Self::ExpireEvents => &config.expire_events,
+            Self::Decay => &config.decay,
+            Self::PruneOrphanVectors => &config.prune_orphan_vectors,
+            Self::VerifyEmbeddings => &config.verify_embeddings,
+        }
+    }
+}
+
+/// A parsed cron expression; each field is a bit mask of allowed values
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    pub expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+}
+
+fn parse_field(text: &str, min: u32, max: u32) -> Result<u64, String> {
+    let number = |n: &str| n.parse::<u32>().map_err(|_| format!("'{}' is not a number", n));
+    let mut mask = 0u64;
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, number(step)?.max(1)),
+            None => (part, 1),
+        };
+        let (low, high) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((low, high)) => (number(low)?, number(high)?),
+            // "5/15" means from 5 to the end in steps of 15
+            None if step > 1 => (number(range)?, max),
+            None => (number(range)?, number(range)?),
+        };
+        if low < min || high > max || low > high {
+            return Err(format!("'{}' is outside {}-{}", part, min, max));
+        }
+        for value in (low..=high).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+impl Schedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minutes, hours, days, months, weekdays] = fields[..] else {
+            return Err(format!("'{}' needs 5 fields: minute hour day month weekday", expression));
+        };
+        let invalid = |e: String| format!("Invalid schedule '{}': {}", expression, e);
+        let mut weekdays = parse_field(weekdays, 0, 7).map_err(invalid)?;
+        // 7 is Sunday too
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+        Ok(Self {
+            expression: expression.to_string(),
+            minutes: parse_field(minutes, 0, 59).map_err(invalid)?,
+            hours: parse_field(hours, 0, 23).map_err(invalid)?,
+            days: parse_field(days, 1, 31).map_err(invalid)?,
+            months: parse_field(months, 1, 12).map_err(invalid)?,
+            weekdays,
+        })
+    }
+
+    pub fn matches(&self, at: DateTime<Utc>) -> bool {
+        let has = |mask: u64, value: u32| mask & (1 << value) != 0;
+        has(self.minutes, at.minute())
+            && has(self.hours, at.hour())
+            && has(self.days, at.day())
+            && has(self.months, at.month())
+            && has(self.weekdays, at.weekday().num_days_from_sunday())
+    }
+
+    /// The first matching minute after `after`, within a year
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut at = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        for _ in 0..366 * 24 * 60 {
+            if self.matches(at) {
+                return Some(at);
+            }
+            at += Duration::minutes(1);
+        }
+        None
+    }
+}
+
+/// Enabled tasks with their schedules; empty schedules switch a task off
+pub fn schedules(config: &MaintenanceConfig) -> Result<Vec<(Task, Schedule)>, String> {
+    TASKS
+        .iter()
+        .filter(|task| !task.schedule(config).trim().is_empty())
+        .map(|&task| Schedule::parse(task.schedule(config)).map(|s| (task, s)).map_err(|e| format!("{}: {}", task.name(), e)))
+        .collect()
+}
+
+/// The last run of each task
+#[derive(Default)]
+pub struct MaintenanceLog {
+    runs: Mutex<HashMap<Task, Value>>,
+}
+
+impl MaintenanceLog {
+    pub fn record(&self, task: Task, started_at: i64, finished_at: i64, outcome: &Result<Value, String>) {
+        let run = match outcome {
+            Ok(report) => json!({"started_at": started_at, "finished_at": finished_at, "success": true, "report": report}),
+            Err(e) => json!({"started_at": started_at, "finished_at": finished_at, "success": false, "error": e}),
+        };
+        self.runs.lock().unwrap().insert(task, run);
+    }
+
+    pub fn last_run(&self, task: Task) -> Option<Value> {
+        self.runs.lock().unwrap().get(&task).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_schedule_parse_and_next() {
+        // Sundays at 04:00
+        let weekly = Schedule::parse("0 4 * * 7").unwrap();
+        let monday = Utc.with_ymd_and_hms(2024, 1, 1, 12, 30, 0).unwrap();
+        assert!(!weekly.matches(monday));
+        assert_eq!(weekly.next_after(monday), Some(Utc.with_ymd_and_hms(2024, 1, 7, 4, 0, 0).unwrap()));
+
+        let quarter_hours = Schedule::parse("*/15 9-17 * * 1-5").unwrap();
+        assert_eq!(quarter_hours.next_after(monday), Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 45, 0).unwrap()));
+
+        assert!(Schedule::parse("0 25 * * *").is_err());
+        assert!(Schedule::parse("0 4 * *").is_err());
+        assert!(Schedule::parse("x 4 * * *").is_err());
+    }
+}
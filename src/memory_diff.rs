@@ -0,0 +1,102 @@
+//! Field-by-field comparison of memories for `diff_memories`
+//!
+//! Two memories are compared on their content fields; identity, ownership,
+//! timestamps and embeddings are left out since they always differ between
+//! copies. Across two businesses, memories are paired on a natural key
+//! (product name, policy name, ...) so catalog drift shows up as changed
+//! fields, plus memories only one side has.
+
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashMap};
+
+/// Fields that differ between any two copies of a memory
+const IGNORED: &[&str] = &["id", "label", "business_id", "customer_id", "created_at", "updated_at", "embedding", "embedding_model"];
+
+/// Field pairing memories of a type across two businesses
+pub fn natural_key(memory_type: &str) -> Option<&'static str> {
+    let key = match memory_type {
+        "product" => "product_name",
+        "service" => "service_name",
+        "location" => "location_name",
+        "hours" => "schedule_type",
+        "social" => "platform",
+        "policy" => "policy_name",
+        "event" => "event_name",
+        "information" => "title",
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// `{field, a, b}` for each content field whose values differ, by field name
+pub fn field_diff(a: &Value, b: &Value, id_field: &str) -> Vec<Value> {
+    let fields: BTreeSet<&String> = a
+        .as_object()
+        .into_iter()
+        .chain(b.as_object())
+        .flat_map(|map| map.keys())
+        .filter(|field| field.as_str() != id_field && !IGNORED.contains(&field.as_str()))
+        .collect();
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let (left, right) = (a.get(field).unwrap_or(&Value::Null), b.get(field).unwrap_or(&Value::Null));
+            (left != right).then(|| json!({"field": field, "a": left, "b": right}))
+        })
+        .collect()
+}
+
+/// Key value of a memory, compared case-insensitively
+fn key_of(memory: &Value, key: &str) -> Option<String> {
+    memory.get(key).and_then(|v| v.as_str()).map(|k| k.trim().to_lowercase()).filter(|k| !k.is_empty())
+}
+
+/// Memories paired on `key`, then those only in `a` and only in `b`.
+/// Memories without a key value, and repeats of a key, count as unpaired.
+pub fn pair_by(a: Vec<Value>, b: Vec<Value>, key: &str) -> (Vec<(Value, Value)>, Vec<Value>, Vec<Value>) {
+    let mut by_key: HashMap<String, Value> = HashMap::new();
+    let mut only_b = Vec::new();
+    for memory in b {
+        match key_of(&memory, key) {
+            Some(k) if !by_key.contains_key(&k) => {
+                by_key.insert(k, memory);
+            }
+            _ => only_b.push(memory),
+        }
+    }
+    let mut pairs = Vec::new();
+    let mut only_a = Vec::new();
+    for memory in a {
+        match key_of(&memory, key).and_then(|k| by_key.remove(&k)) {
+            Some(other) => pairs.push((memory, other)),
+            None => only_a.push(memory),
+        }
+    }
+    only_b.extend(by_key.into_values());
+    (pairs, only_a, only_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_and_pairing() {
+        let a = json!({"id": "n1", "product_id": "P1", "business_id": "B1", "product_name": "Latte", "price": 4.5, "tags": ["hot"]});
+        let b = json!({"id": "n2", "product_id": "P9", "business_id": "B2", "product_name": "Latte", "price": 5.0, "tags": ["hot"], "currency": "EUR"});
+        assert_eq!(field_diff(&a, &b, "product_id"), vec![
+            json!({"field": "currency", "a": null, "b": "EUR"}),
+            json!({"field": "price", "a": 4.5, "b": 5.0}),
+        ]);
+
+        let (pairs, only_a, only_b) = pair_by(
+            vec![a.clone(), json!({"product_name": "Mocha"})],
+            vec![json!({"product_name": "latte "}), json!({"product_name": "Chai"}), json!({})],
+            natural_key("product").unwrap(),
+        );
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].0["product_id"], "P1");
+        assert_eq!(only_a, vec![json!({"product_name": "Mocha"})]);
+        assert_eq!(only_b.len(), 2);
+    }
+}
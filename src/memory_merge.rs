@@ -0,0 +1,146 @@
+//! Merging duplicate memories of one type
+//!
+//! `merge_memories` keeps the first listed memory, replaces its text with
+//! the combined text of all of them, moves references (interactions,
+//! visits, information links) from each duplicate onto it and then deletes
+//! the duplicate. A duplicate whose references could not be moved is kept,
+//! so a failed rewire never leaves dangling edges behind.
+
+use serde_json::{json, Value};
+use std::future::Future;
+
+/// Survivor and duplicates of a merge: the first ID survives. At least two
+/// distinct IDs are required - a repeated ID would delete the survivor.
+pub fn split_survivor(memory_ids: &[String]) -> Result<(&String, &[String]), String> {
+    if memory_ids.len() < 2 {
+        return Err("At least two memory_ids are required to merge".to_string());
+    }
+    if let Some((_, repeated)) = memory_ids.iter().enumerate().find(|(i, id)| memory_ids[..*i].contains(id)) {
+        return Err(format!("memory_ids lists {} more than once", repeated));
+    }
+    Ok((&memory_ids[0], &memory_ids[1..]))
+}
+
+/// Distinct descriptions of the merged memories, survivor first
+pub fn composite_text(memories: &[Value]) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for memory in memories {
+        let text = ["text_description", "description", "content"]
+            .iter()
+            .find_map(|f| memory.get(*f).and_then(|v| v.as_str()).filter(|t| !t.is_empty()));
+        if let Some(text) = text {
+            if !parts.contains(&text) {
+                parts.push(text);
+            }
+        }
+    }
+    parts.join("\n")
+}
+
+/// Query moving references between memories of a type; types nothing points at have none
+pub fn rewire_query(memory_type: &str) -> Option<&'static str> {
+    match memory_type {
+        "product" => Some("merge_rewire_product"),
+        "service" => Some("merge_rewire_service"),
+        "location" => Some("merge_rewire_location"),
+        "event" => Some("merge_rewire_event"),
+        "behavior" => Some("merge_rewire_behavior"),
+        "preference" => Some("merge_rewire_preference"),
+        _ => None,
+    }
+}
+
+/// Payload of a `merge_rewire_*` query, e.g. `from_product_id` / `to_product_id`
+pub fn rewire_payload(id_field: &str, duplicate_id: &str, survivor_id: &str, timestamp: i64) -> Value {
+    json!({
+        format!("from_{}", id_field): duplicate_id,
+        format!("to_{}", id_field): survivor_id,
+        "timestamp": timestamp
+    })
+}
+
+/// Rewire then delete each duplicate in order. Returns the merged IDs and
+/// one failure per duplicate that is (partly) still there; a duplicate is
+/// only deleted after its references moved.
+pub async fn fold_duplicates<R, RF, D, DF>(duplicates: &[String], mut rewire: R, mut delete: D) -> (Vec<String>, Vec<Value>)
+where
+    R: FnMut(String) -> RF,
+    RF: Future<Output = Result<(), String>>,
+    D: FnMut(String) -> DF,
+    DF: Future<Output = Result<(), Value>>,
+{
+    let mut merged = Vec::new();
+    let mut failures = Vec::new();
+    for duplicate_id in duplicates {
+        if let Err(e) = rewire(duplicate_id.clone()).await {
+            failures.push(json!({"memory_id": duplicate_id, "error": format!("Rewire failed, memory kept: {}", e)}));
+            continue;
+        }
+        match delete(duplicate_id.clone()).await {
+            Ok(()) => merged.push(duplicate_id.clone()),
+            Err(error) => failures.push(json!({"memory_id": duplicate_id, "error": error})),
+        }
+    }
+    (merged, failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_split_survivor() {
+        let memory_ids = ids(&["P2", "P1", "P3"]);
+        let (survivor, duplicates) = split_survivor(&memory_ids).unwrap();
+        assert_eq!(survivor, "P2");
+        assert_eq!(duplicates, ["P1", "P3"]);
+
+        assert!(split_survivor(&ids(&["P1"])).is_err());
+        assert_eq!(split_survivor(&ids(&["P1", "P2", "P1"])).unwrap_err(), "memory_ids lists P1 more than once");
+    }
+
+    #[test]
+    fn test_composite_text_and_rewire_payload() {
+        let memories = vec![
+            json!({"text_description": "Oat latte"}),
+            json!({"description": "Oat latte"}),
+            json!({"text_description": "", "content": "Oat milk latte, 12oz"}),
+        ];
+        assert_eq!(composite_text(&memories), "Oat latte\nOat milk latte, 12oz");
+
+        assert_eq!(rewire_query("product"), Some("merge_rewire_product"));
+        assert_eq!(rewire_query("rule"), None);
+        assert_eq!(
+            rewire_payload("product_id", "P1", "P2", 1_700_000_000),
+            json!({"from_product_id": "P1", "to_product_id": "P2", "timestamp": 1_700_000_000})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fold_duplicates_partial_failure() {
+        let deleted = Mutex::new(Vec::new());
+        let (merged, failures) = fold_duplicates(
+            &ids(&["P1", "P3", "P4"]),
+            // P4's references can't be moved
+            |id| async move { if id == "P4" { Err("timeout".to_string()) } else { Ok(()) } },
+            |id| {
+                deleted.lock().unwrap().push(id.clone());
+                // P3 is rewired but its delete fails
+                async move { if id == "P3" { Err(json!("delete failed")) } else { Ok(()) } }
+            },
+        )
+        .await;
+
+        assert_eq!(merged, ["P1"]);
+        assert_eq!(*deleted.lock().unwrap(), ["P1", "P3"], "a duplicate whose rewire failed must not be deleted");
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0], json!({"memory_id": "P3", "error": "delete failed"}));
+        assert_eq!(failures[1]["memory_id"], "P4");
+        assert_eq!(failures[1]["error"], "Rewire failed, memory kept: timeout");
+    }
+}
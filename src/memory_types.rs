@@ -0,0 +1,114 @@
+//! Memory type names and the queries behind them
+//!
+//! Tools accept memory types in singular or plural ("product" / "products")
+//! and a few aliases. Create, update and delete work on the singular name,
+//! which maps to the entity ID field, the owner field and the per-type
+//! lookup and delete queries.
+
+/// Normalize memory_type to SINGULAR (for create/update/delete operations)
+pub fn normalize(memory_type: &str) -> &str {
+    match memory_type {
+        // Plural to singular for create/update/delete operations
+        "products" => "product",
+        "services" => "service",
+        "locations" => "location",
+        "policies" => "policy",
+        "events" => "event",
+        // Plural to singular for customer operations
+        "behaviors" => "behavior",
+        "preferences" => "preference",
+        "desires" => "desire",
+        "rules" => "rule",
+        // Already singular or special cases
+        _ => memory_type
+    }
+}
+
+/// (entity ID field, owner field, lookup query) of a SINGULAR memory_type
+pub fn entity(memory_type: &str) -> Option<(&'static str, &'static str, &'static str)> {
+    let entity = match memory_type {
+        "product" => ("product_id", "business_id", "get_business_product_by_id"),
+        "service" => ("service_id", "business_id", "get_business_service_by_id"),
+        "location" => ("location_id", "business_id", "get_business_location_by_id"),
+        "hours" => ("hours_id", "business_id", "get_business_hours_by_id"),
+        "social" => ("social_id", "business_id", "get_business_social_by_id"),
+        "policy" => ("policy_id", "business_id", "get_business_policy_by_id"),
+        "event" => ("event_id", "business_id", "get_business_event_by_id"),
+        "information" => ("info_id", "business_id", "get_business_information_by_id"),
+        "behavior" => ("behavior_id", "customer_id", "get_customer_behavior_by_id"),
+        "preference" => ("preference_id", "customer_id", "get_customer_preference_by_id"),
+        "desire" => ("desire_id", "customer_id", "get_customer_desire_by_id"),
+        "rule" => ("rule_id", "customer_id", "get_customer_rule_by_id"),
+        "feedback" => ("feedback_id", "customer_id", "get_customer_feedback_by_id"),
+        _ => return None,
+    };
+    Some(entity)
+}
+
+/// Delete query of a singular memory_type
+pub fn delete_query(memory_type: &str, with_embedding: bool) -> Option<&'static str> {
+    let query_name = match memory_type {
+        "product" => if with_embedding { "delete_product_with_embedding" } else { "delete_product" },
+        "service" => if with_embedding { "delete_service_with_embedding" } else { "delete_service" },
+        "location" => if with_embedding { "delete_location_with_embedding" } else { "delete_location" },
+        "hours" => if with_embedding { "delete_hours_with_embedding" } else { "delete_hours" },
+        "social" => if with_embedding { "delete_social_with_embedding" } else { "delete_social" },
+        "policy" => if with_embedding { "delete_policy_with_embedding" } else { "delete_policy" },
+        "event" => if with_embedding { "delete_event_with_embedding" } else { "delete_event" },
+        "information" => if with_embedding { "delete_information_with_embedding" } else { "delete_information" },
+        "behavior" => if with_embedding { "delete_behavior_with_embedding" } else { "delete_behavior" },
+        "preference" => if with_embedding { "delete_preference_with_embedding" } else { "delete_preference" },
+        "desire" => if with_embedding { "delete_desire_with_embedding" } else { "delete_desire" },
+        "rule" => if with_embedding { "delete_rule_with_embedding" } else { "delete_rule" },
+        "feedback" => if with_embedding { "delete_feedback_with_embedding" } else { "delete_feedback" },
+        "interaction" => if with_embedding { "delete_interaction_with_embedding" } else { "delete_interaction" },
+        "navigation_hub" => if with_embedding { "delete_navigation_hub_with_embedding" } else { "delete_navigation_hub" },
+        "waypoint" => if with_embedding { "delete_waypoint_with_embedding" } else { "delete_waypoint" },
+        "path" => if with_embedding { "delete_path_with_embedding" } else { "delete_path" },
+        _ => return None,
+    };
+    Some(query_name)
+}
+
+/// Canonicalize delete_memory types beyond [`normalize`] (interaction and
+/// navigation aliases) and resolve the ID field the delete query takes
+pub fn delete_target(memory_type: &str) -> (&str, String) {
+    let memory_type = match normalize(memory_type) {
+        "interactions" | "product_interaction" | "service_interaction" => "interaction",
+        "hub" | "hubs" | "navigation" | "navigation_hubs" => "navigation_hub",
+        "waypoints" => "waypoint",
+        "paths" | "direction_path" => "path",
+        other => other,
+    };
+    let id_field = match memory_type {
+        "interaction" => "interaction_id".to_string(),
+        "navigation_hub" => "navigation_id".to_string(),
+        other => entity(other).map_or_else(|| format!("{}_id", other), |(id_field, _, _)| id_field.to_string()),
+    };
+    (memory_type, id_field)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delete_target() {
+        assert_eq!(delete_target("products"), ("product", "product_id".to_string()));
+        assert_eq!(delete_target("information"), ("information", "info_id".to_string()));
+        assert_eq!(delete_target("service_interaction"), ("interaction", "interaction_id".to_string()));
+        assert_eq!(delete_target("hubs"), ("navigation_hub", "navigation_id".to_string()));
+        assert_eq!(delete_target("waypoints"), ("waypoint", "waypoint_id".to_string()));
+        assert_eq!(delete_target("direction_path"), ("path", "path_id".to_string()));
+        // Unknown types keep their name; delete_query rejects them
+        assert_eq!(delete_target("business"), ("business", "business_id".to_string()));
+    }
+
+    #[test]
+    fn test_delete_query() {
+        assert_eq!(delete_query("interaction", true), Some("delete_interaction_with_embedding"));
+        assert_eq!(delete_query("navigation_hub", false), Some("delete_navigation_hub"));
+        assert_eq!(delete_query("path", true), Some("delete_path_with_embedding"));
+        assert_eq!(delete_query("preference", false), Some("delete_preference"));
+        assert_eq!(delete_query("business", true), None);
+    }
+}
@@ -0,0 +1,719 @@
+//! Typed memory models
+//!
+//! One struct per business and customer memory type, mirroring the
+//! `add_*_memory` query parameters in `db/queries.hx` with the schema
+//! defaults. Create tools deserialize their `data` into these instead of
+//! patching JSON field by field, so a missing required field or a wrong
+//! value type is reported before the query runs. Fields outside the model
+//! (`text_description`, `embedding`, ...) are carried through unchanged.
+
+use rmcp::schemars::{self, JsonSchema};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ModelError {
+    #[error("Invalid memory_type: {0}")]
+    UnknownType(String),
+    #[error("Invalid {memory_type} memory data: {source}")]
+    Invalid {
+        memory_type: String,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+/// A memory type's model
+pub trait Memory: DeserializeOwned + Serialize + JsonSchema {
+    /// Fill defaults that depend on the current time
+    fn stamp(&mut self, now: i64);
+}
+
+fn empty_json() -> String {
+    "{}".to_string()
+}
+
+fn default_duration_minutes() -> i32 {
+    60
+}
+
+fn untracked_stock() -> i64 {
+    crate::inventory::UNTRACKED
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_sentiment() -> String {
+    "neutral".to_string()
+}
+
+fn default_event_status() -> String {
+    crate::events::STATUS_SCHEDULED.to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Product {
+    #[serde(default)]
+    pub business_id: String,
+    #[serde(default)]
+    pub product_id: String,
+    pub product_name: String,
+    #[serde(default)]
+    pub product_category: String,
+    #[serde(default)]
+    pub price: f64,
+    #[serde(default)]
+    pub currency: String,
+    #[serde(default)]
+    pub availability: String,
+    /// Units in stock; -1 while stock isn't tracked
+    #[serde(default = "untracked_stock")]
+    pub stock_quantity: i64,
+    #[serde(default)]
+    pub reorder_threshold: i64,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// JSON object encoded as a string
+    #[serde(default = "empty_json")]
+    pub specifications: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub seo_keywords: Vec<String>,
+    #[serde(default)]
+    pub competitor_analysis: String,
+    /// JSON object encoded as a string
+    #[serde(default = "empty_json")]
+    pub seasonal_trends: String,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Service {
+    #[serde(default)]
+    pub business_id: String,
+    #[serde(default)]
+    pub service_id: String,
+    pub service_name: String,
+    #[serde(default)]
+    pub service_category: String,
+    #[serde(default)]
+    pub price: f64,
+    #[serde(default)]
+    pub currency: String,
+    #[serde(default = "default_duration_minutes")]
+    pub duration_minutes: i32,
+    #[serde(default)]
+    pub availability: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub requirements: Vec<String>,
+    #[serde(default)]
+    pub deliverables: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Location {
+    #[serde(default)]
+    pub business_id: String,
+    #[serde(default)]
+    pub location_id: String,
+    pub location_name: String,
+    #[serde(default)]
+    pub address: String,
+    #[serde(default)]
+    pub city: String,
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub country: String,
+    #[serde(default)]
+    pub postal_code: String,
+    #[serde(default)]
+    pub latitude: f64,
+    #[serde(default)]
+    pub longitude: f64,
+    #[serde(default)]
+    pub location_type: String,
+    #[serde(default)]
+    pub accessibility: Vec<String>,
+    #[serde(default)]
+    pub parking_info: String,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Opening and closing times are "HH:MM", empty when closed or unknown
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Hours {
+    #[serde(default)]
+    pub business_id: String,
+    #[serde(default)]
+    pub hours_id: String,
+    #[serde(default)]
+    pub schedule_type: String,
+    #[serde(default)]
+    pub monday_open: String,
+    #[serde(default)]
+    pub monday_close: String,
+    #[serde(default)]
+    pub tuesday_open: String,
+    #[serde(default)]
+    pub tuesday_close: String,
+    #[serde(default)]
+    pub wednesday_open: String,
+    #[serde(default)]
+    pub wednesday_close: String,
+    #[serde(default)]
+    pub thursday_open: String,
+    #[serde(default)]
+    pub thursday_close: String,
+    #[serde(default)]
+    pub friday_open: String,
+    #[serde(default)]
+    pub friday_close: String,
+    #[serde(default)]
+    pub saturday_open: String,
+    #[serde(default)]
+    pub saturday_close: String,
+    #[serde(default)]
+    pub sunday_open: String,
+    #[serde(default)]
+    pub sunday_close: String,
+    #[serde(default)]
+    pub timezone: String,
+    /// JSON object encoded as a string
+    #[serde(default = "empty_json")]
+    pub exceptions: String,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Social {
+    #[serde(default)]
+    pub business_id: String,
+    #[serde(default)]
+    pub social_id: String,
+    pub platform: String,
+    #[serde(default)]
+    pub handle: String,
+    #[serde(default)]
+    pub profile_url: String,
+    #[serde(default)]
+    pub follower_count: i64,
+    #[serde(default)]
+    pub post_count: i64,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub contact_info: String,
+    /// Defaults to now
+    pub last_updated: Option<i64>,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Policy {
+    #[serde(default)]
+    pub business_id: String,
+    #[serde(default)]
+    pub policy_id: String,
+    #[serde(default)]
+    pub policy_type: String,
+    pub policy_name: String,
+    #[serde(default)]
+    pub content: String,
+    /// Defaults to now
+    pub effective_date: Option<i64>,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub is_active: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Event {
+    #[serde(default)]
+    pub business_id: String,
+    #[serde(default)]
+    pub event_id: String,
+    pub event_name: String,
+    #[serde(default)]
+    pub event_type: String,
+    /// Defaults to now
+    pub start_date: Option<i64>,
+    /// Defaults to now
+    pub end_date: Option<i64>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub location: String,
+    /// 0 for unlimited
+    #[serde(default)]
+    pub capacity: i32,
+    #[serde(default)]
+    pub registration_required: bool,
+    #[serde(default)]
+    pub registered_count: i32,
+    #[serde(default = "default_event_status")]
+    pub status: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Information {
+    #[serde(default)]
+    pub business_id: String,
+    #[serde(default)]
+    pub info_id: String,
+    #[serde(default)]
+    pub info_type: String,
+    pub title: String,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Behavior {
+    #[serde(default)]
+    pub customer_id: String,
+    #[serde(default)]
+    pub behavior_id: String,
+    #[serde(default)]
+    pub behavior_type: String,
+    #[serde(default)]
+    pub action: String,
+    #[serde(default)]
+    pub context: String,
+    /// When the behavior happened; defaults to now
+    pub timestamp: Option<i64>,
+    #[serde(default)]
+    pub channel: String,
+    #[serde(default)]
+    pub duration_seconds: i32,
+    /// JSON object encoded as a string
+    #[serde(default = "empty_json")]
+    pub metadata: String,
+    /// 0-5; higher ranks first in search and listings
+    #[serde(default)]
+    pub importance: i32,
+    #[serde(default)]
+    pub pinned: bool,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Preference {
+    #[serde(default)]
+    pub customer_id: String,
+    #[serde(default)]
+    pub preference_id: String,
+    #[serde(default)]
+    pub preference_type: String,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub subject: String,
+    #[serde(default)]
+    pub strength: String,
+    #[serde(default)]
+    pub is_active: bool,
+    #[serde(default)]
+    pub evidence_count: i32,
+    /// Defaults to now
+    pub last_evidence: Option<i64>,
+    #[serde(default)]
+    pub confidence_score: f64,
+    #[serde(default)]
+    pub source_channels: Vec<String>,
+    /// 0-5; higher ranks first in search and listings
+    #[serde(default)]
+    pub importance: i32,
+    #[serde(default)]
+    pub pinned: bool,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Desire {
+    #[serde(default)]
+    pub customer_id: String,
+    #[serde(default)]
+    pub desire_id: String,
+    #[serde(default)]
+    pub desire_type: String,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub priority: String,
+    #[serde(default)]
+    pub timeframe: String,
+    #[serde(default)]
+    pub budget_range: String,
+    #[serde(default)]
+    pub is_active: bool,
+    /// 0-5; higher ranks first in search and listings
+    #[serde(default)]
+    pub importance: i32,
+    #[serde(default)]
+    pub pinned: bool,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Rule {
+    #[serde(default)]
+    pub customer_id: String,
+    #[serde(default)]
+    pub rule_id: String,
+    #[serde(default)]
+    pub rule_type: String,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub rule_description: String,
+    #[serde(default)]
+    pub enforcement: String,
+    #[serde(default)]
+    pub exceptions: Vec<String>,
+    /// Rules are stored because they apply, so they start active
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+    /// 0-5; higher ranks first in search and listings
+    #[serde(default)]
+    pub importance: i32,
+    #[serde(default)]
+    pub pinned: bool,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Feedback {
+    #[serde(default)]
+    pub customer_id: String,
+    #[serde(default)]
+    pub feedback_id: String,
+    #[serde(default)]
+    pub feedback_type: String,
+    #[serde(default)]
+    pub subject: String,
+    /// 1-5, 0 when not given
+    #[serde(default)]
+    pub rating: i32,
+    #[serde(default = "default_sentiment")]
+    pub sentiment: String,
+    #[serde(default)]
+    pub channel: String,
+    #[serde(default)]
+    pub response_required: bool,
+    #[serde(default)]
+    pub resolved: bool,
+    /// 0-5; higher ranks first in search and listings
+    #[serde(default)]
+    pub importance: i32,
+    #[serde(default)]
+    pub pinned: bool,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// `created_at` / `updated_at` default to now
+fn stamp_created(created_at: &mut Option<i64>, updated_at: &mut Option<i64>, now: i64) {
+    created_at.get_or_insert(now);
+    updated_at.get_or_insert(now);
+}
+
+impl Memory for Product {
+    fn stamp(&mut self, now: i64) {
+        stamp_created(&mut self.created_at, &mut self.updated_at, now);
+        self.availability = crate::inventory::derive_availability(&self.availability, self.stock_quantity, self.reorder_threshold);
+    }
+}
+
+impl Memory for Service {
+    fn stamp(&mut self, now: i64) {
+        stamp_created(&mut self.created_at, &mut self.updated_at, now);
+    }
+}
+
+impl Memory for Location {
+    fn stamp(&mut self, now: i64) {
+        stamp_created(&mut self.created_at, &mut self.updated_at, now);
+    }
+}
+
+impl Memory for Hours {
+    fn stamp(&mut self, now: i64) {
+        stamp_created(&mut self.created_at, &mut self.updated_at, now);
+    }
+}
+
+impl Memory for Social {
+    fn stamp(&mut self, now: i64) {
+        stamp_created(&mut self.created_at, &mut self.updated_at, now);
+        self.last_updated.get_or_insert(now);
+    }
+}
+
+impl Memory for Policy {
+    fn stamp(&mut self, now: i64) {
+        stamp_created(&mut self.created_at, &mut self.updated_at, now);
+        self.effective_date.get_or_insert(now);
+    }
+}
+
+impl Memory for Event {
+    fn stamp(&mut self, now: i64) {
+        stamp_created(&mut self.created_at, &mut self.updated_at, now);
+        self.start_date.get_or_insert(now);
+        self.end_date.get_or_insert(now);
+    }
+}
+
+impl Memory for Information {
+    fn stamp(&mut self, now: i64) {
+        stamp_created(&mut self.created_at, &mut self.updated_at, now);
+    }
+}
+
+impl Memory for Behavior {
+    fn stamp(&mut self, now: i64) {
+        stamp_created(&mut self.created_at, &mut self.updated_at, now);
+        self.timestamp.get_or_insert(now);
+    }
+}
+
+impl Memory for Preference {
+    fn stamp(&mut self, now: i64) {
+        stamp_created(&mut self.created_at, &mut self.updated_at, now);
+        self.last_evidence.get_or_insert(now);
+    }
+}
+
+impl Memory for Desire {
+    fn stamp(&mut self, now: i64) {
+        stamp_created(&mut self.created_at, &mut self.updated_at, now);
+    }
+}
+
+impl Memory for Rule {
+    fn stamp(&mut self, now: i64) {
+        stamp_created(&mut self.created_at, &mut self.updated_at, now);
+    }
+}
+
+impl Memory for Feedback {
+    fn stamp(&mut self, now: i64) {
+        stamp_created(&mut self.created_at, &mut self.updated_at, now);
+    }
+}
+
+fn complete<T: Memory>(memory_type: &str, data: Value, now: i64) -> Result<Value, ModelError> {
+    let invalid = |source| ModelError::Invalid { memory_type: memory_type.to_string(), source };
+    let mut memory: T = serde_json::from_value(data).map_err(invalid)?;
+    memory.stamp(now);
+    serde_json::to_value(memory).map_err(invalid)
+}
+
+/// Business memory `data` for the `add_business_*_memory` query, with
+/// schema defaults filled in; `memory_type` is singular
+pub fn business_memory(memory_type: &str, data: Value, now: i64) -> Result<Value, ModelError> {
+    match memory_type {
+        "product" => complete::<Product>(memory_type, data, now),
+        "service" => complete::<Service>(memory_type, data, now),
+        "location" => complete::<Location>(memory_type, data, now),
+        "hours" => complete::<Hours>(memory_type, data, now),
+        "social" => complete::<Social>(memory_type, data, now),
+        "policy" => complete::<Policy>(memory_type, data, now),
+        "event" => complete::<Event>(memory_type, data, now),
+        "information" => complete::<Information>(memory_type, data, now),
+        _ => Err(ModelError::UnknownType(memory_type.to_string())),
+    }
+}
+
+/// Customer memory `data` for the `add_customer_*_memory` query, with
+/// schema defaults filled in; `memory_type` is singular
+pub fn customer_memory(memory_type: &str, data: Value, now: i64) -> Result<Value, ModelError> {
+    match memory_type {
+        "behavior" => complete::<Behavior>(memory_type, data, now),
+        "preference" => complete::<Preference>(memory_type, data, now),
+        "desire" => complete::<Desire>(memory_type, data, now),
+        "rule" => complete::<Rule>(memory_type, data, now),
+        "feedback" => complete::<Feedback>(memory_type, data, now),
+        _ => Err(ModelError::UnknownType(memory_type.to_string())),
+    }
+}
+
+/// JSON Schema of a memory type's fields, for error responses
+pub fn schema(memory_type: &str) -> Option<Value> {
+    let schema = match memory_type {
+        "product" => schemars::schema_for!(Product),
+        "service" => schemars::schema_for!(Service),
+        "location" => schemars::schema_for!(Location),
+        "hours" => schemars::schema_for!(Hours),
+        "social" => schemars::schema_for!(Social),
+        "policy" => schemars::schema_for!(Policy),
+        "event" => schemars::schema_for!(Event),
+        "information" => schemars::schema_for!(Information),
+        "behavior" => schemars::schema_for!(Behavior),
+        "preference" => schemars::schema_for!(Preference),
+        "desire" => schemars::schema_for!(Desire),
+        "rule" => schemars::schema_for!(Rule),
+        "feedback" => schemars::schema_for!(Feedback),
+        _ => return None,
+    };
+    serde_json::to_value(schema).ok()
+}
+
+/// The fields of `data` that belong to the memory type's model, without
+/// pass-through extras
+pub fn model_fields(memory_type: &str, data: &Value) -> Map<String, Value> {
+    let schema = schema(memory_type).unwrap_or_default();
+    let known = schema.get("properties").and_then(|p| p.as_object());
+    data.as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| known.is_some_and(|k| k.contains_key(*key)))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// Apply a partial update to a stored memory; returns the fields that changed
+pub fn merge_updates(stored: &mut Value, updates: &Value) -> Vec<String> {
+    let mut changed = Vec::new();
+    for (field, value) in updates.as_object().into_iter().flatten() {
+        if stored.get(field) != Some(value) {
+            changed.push(field.clone());
+            stored[field] = value.clone();
+        }
+    }
+    changed
+}
+
+/// Payload of the `update_business_*_fields` queries: every model field of
+/// the merged memory, its text, the rebuilt composite text and embedding
+pub fn field_update_payload(memory_type: &str, full: &Value, composite_text: &str, embedding: &[f32], timestamp: i64) -> Value {
+    let mut payload = model_fields(memory_type, full);
+    payload.insert("text_description".to_string(), json!(full.get("text_description").and_then(|v| v.as_str()).unwrap_or("")));
+    payload.insert("composite_text".to_string(), json!(composite_text));
+    payload.insert("new_embedding".to_string(), json!(embedding));
+    payload.insert("timestamp".to_string(), json!(timestamp));
+    Value::Object(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_and_passthrough() {
+        let data = json!({"service_name": "Haircut", "price": 25, "text_description": "A haircut", "created_at": 5});
+        let service = business_memory("service", data, 100).unwrap();
+        assert_eq!(service["duration_minutes"], 60);
+        assert_eq!(service["price"], 25.0);
+        assert_eq!(service["tags"], json!([]));
+        assert_eq!(service["created_at"], 5);
+        assert_eq!(service["updated_at"], 100);
+        assert_eq!(service["text_description"], "A haircut");
+
+        let event = business_memory("event", json!({"event_name": "Launch"}), 100).unwrap();
+        assert_eq!(event["start_date"], 100);
+        assert_eq!(event["status"], crate::events::STATUS_SCHEDULED);
+
+        let rule = customer_memory("rule", json!({}), 100).unwrap();
+        assert_eq!(rule["is_active"], true);
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        let missing = business_memory("product", json!({"price": 3.5}), 0).unwrap_err();
+        assert!(missing.to_string().contains("missing field `product_name`"));
+        let wrong_type = customer_memory("feedback", json!({"rating": "great"}), 0).unwrap_err();
+        assert!(wrong_type.to_string().starts_with("Invalid feedback memory data"));
+        assert!(matches!(business_memory("widget", json!({}), 0), Err(ModelError::UnknownType(_))));
+        assert!(schema("product").unwrap()["required"].as_array().unwrap().contains(&json!("product_name")));
+
+        let fields = model_fields("service", &json!({"service_name": "Haircut", "id": "uuid", "label": "BusinessServiceMemory"}));
+        assert_eq!(fields.keys().collect::<Vec<_>>(), vec!["service_name"]);
+    }
+
+    #[test]
+    fn test_partial_update() {
+        let mut stored = json!({
+            "id": "uuid", "label": "BusinessProductMemory", "business_id": "B1", "product_id": "P1",
+            "product_name": "Oat latte", "price": 4.5, "text_description": "Creamy", "created_at": 10
+        });
+        let changed = merge_updates(&mut stored, &json!({"business_id": "B1", "product_id": "P1", "price": 5.0}));
+        assert_eq!(changed, ["price"]);
+        assert_eq!(stored["product_name"], "Oat latte");
+
+        let full = business_memory("product", stored, 100).unwrap();
+        let payload = field_update_payload("product", &full, "Oat latte 5.00", &[0.5], 100);
+        assert_eq!(payload["price"], 5.0);
+        assert_eq!(payload["product_name"], "Oat latte");
+        assert_eq!(payload["text_description"], "Creamy");
+        assert_eq!(payload["composite_text"], "Oat latte 5.00");
+        assert_eq!(payload["new_embedding"], json!([0.5]));
+        assert_eq!(payload["updated_at"], 100);
+        assert!(payload.get("id").is_none() && payload.get("label").is_none());
+    }
+}
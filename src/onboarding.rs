@@ -0,0 +1,118 @@
+//! Setting up a business in one call
+//!
+//! `onboard_business` creates the business node and its first products,
+//! services, locations and hours. Every entry is checked against the memory
+//! type's model before anything is written, so a bad entry can't leave a
+//! half-created business behind. `clone_business_memories` then copies a
+//! template business's memories to a new one (e.g. a franchise location)
+//! under new IDs, with overrides applied to every copy.
+
+use crate::models;
+use serde_json::{json, Value};
+
+/// Memory types `clone_business_memories` copies, with the query listing each
+pub const CLONE_SOURCES: [(&str, &str); 8] = [
+    ("product", "get_business_products"),
+    ("service", "get_business_services"),
+    ("location", "get_business_locations"),
+    ("hours", "get_business_hours"),
+    ("social", "get_business_social_media"),
+    ("policy", "get_business_policies"),
+    ("event", "get_business_events"),
+    ("information", "get_business_information"),
+];
+
+/// Why an initial memory can't be created, if it can't
+pub fn entry_problem(memory_type: &str, id_field: &str, business_id: &str, item: &Value, now: i64) -> Option<String> {
+    match item.get("text_description").and_then(|v| v.as_str()) {
+        _ if !item.is_object() => Some("must be an object".to_string()),
+        None | Some("") => Some("text_description is required".to_string()),
+        Some(_) => {
+            let mut data = item.clone();
+            data["business_id"] = json!(business_id);
+            data[id_field] = json!("validation");
+            models::business_memory(memory_type, data, now).err().map(|e| e.to_string())
+        }
+    }
+}
+
+/// Payload of `create_business`; metadata is stored as a JSON string
+pub fn business_node(business_id: &str, business_name: &str, business_type: &str, allow_collaboration: Option<bool>, metadata: Option<Value>) -> Value {
+    let metadata = match metadata {
+        Some(Value::String(text)) => text,
+        Some(value) => value.to_string(),
+        None => "{}".to_string(),
+    };
+    json!({
+        "business_id": business_id,
+        "business_name": business_name,
+        "business_type": business_type,
+        "status": "active",
+        "allow_collaboration": allow_collaboration.unwrap_or(false),
+        "metadata": metadata
+    })
+}
+
+/// Fields of the copy of `source`: identity and bookkeeping fields are
+/// dropped to be regenerated, then top-level overrides are applied, then the
+/// ones under the memory type's key (`{"product": {...}}`)
+pub fn clone_fields(mut source: Value, memory_type: &str, id_field: &str, overrides: &Value) -> Value {
+    if let Some(fields) = source.as_object_mut() {
+        for field in ["id", "label", id_field, "business_id", "created_at", "updated_at", "embedding", "embedding_model"] {
+            fields.remove(field);
+        }
+        for (field, value) in overrides.as_object().into_iter().flatten() {
+            if CLONE_SOURCES.iter().all(|(t, _)| t != field) {
+                fields.insert(field.clone(), value.clone());
+            }
+        }
+        for (field, value) in overrides.get(memory_type).and_then(|o| o.as_object()).into_iter().flatten() {
+            fields.insert(field.clone(), value.clone());
+        }
+    }
+    source
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_problem() {
+        let product = json!({"text_description": "Oat latte", "product_name": "Oat latte", "price": 4.5});
+        assert_eq!(entry_problem("product", "product_id", "BIZ_1", &product, 1_700_000_000), None);
+        assert_eq!(entry_problem("product", "product_id", "BIZ_1", &json!("Oat latte"), 0).unwrap(), "must be an object");
+        assert_eq!(entry_problem("service", "service_id", "BIZ_1", &json!({"text_description": ""}), 0).unwrap(), "text_description is required");
+        // Field types are checked by the memory type's model
+        assert!(entry_problem("product", "product_id", "BIZ_1", &json!({"text_description": "Latte", "price": "cheap"}), 0).is_some());
+    }
+
+    #[test]
+    fn test_business_node() {
+        let node = business_node("BIZ_1", "Corner Cafe", "restaurant", None, Some(json!({"website": "https://example.com"})));
+        assert_eq!(node["status"], "active");
+        assert_eq!(node["allow_collaboration"], false);
+        assert_eq!(node["metadata"], r#"{"website":"https://example.com"}"#);
+        // String metadata is kept as given
+        assert_eq!(business_node("BIZ_1", "Cafe", "retail", Some(true), Some(json!("{}")))["metadata"], "{}");
+        assert_eq!(business_node("BIZ_1", "Cafe", "retail", Some(true), None)["allow_collaboration"], true);
+    }
+
+    #[test]
+    fn test_clone_fields() {
+        let source = json!({
+            "id": "node-1", "label": "Product", "product_id": "P1", "business_id": "BIZ_1",
+            "created_at": 100, "embedding_model": "local",
+            "product_name": "Latte", "currency": "USD", "availability": "in_stock"
+        });
+        let overrides = json!({"currency": "EUR", "product": {"availability": "preorder"}, "service": {"duration_minutes": 30}});
+        assert_eq!(
+            clone_fields(source.clone(), "product", "product_id", &overrides),
+            json!({"product_name": "Latte", "currency": "EUR", "availability": "preorder"})
+        );
+        // Another type's overrides don't leak into the copy
+        let copy = clone_fields(json!({"service_id": "S1", "service_name": "Cut"}), "service", "service_id", &overrides);
+        assert_eq!(copy, json!({"service_name": "Cut", "currency": "EUR", "duration_minutes": 30}));
+        assert_eq!(clone_fields(source, "product", "product_id", &json!({}))["currency"], "USD");
+    }
+}
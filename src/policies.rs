@@ -0,0 +1,100 @@
+//! Policy versions
+//!
+//! Several policy memories of one type form a version history. The version in
+//! effect at a date is the one with the latest `effective_date` on or before
+//! it among versions that are active or were retired by a successor
+//! (`superseded_by`); inactive versions that were never superseded are drafts
+//! or withdrawn and never resolve.
+
+use serde_json::Value;
+use std::cmp::Ordering;
+
+fn text<'a>(policy: &'a Value, field: &str) -> &'a str {
+    policy.get(field).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+fn int(policy: &Value, field: &str) -> i64 {
+    policy.get(field).and_then(|v| v.as_i64()).unwrap_or(0)
+}
+
+pub fn is_active(policy: &Value) -> bool {
+    policy.get("is_active").and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+pub fn superseded_by(policy: &Value) -> Option<&str> {
+    Some(text(policy, "superseded_by")).filter(|s| !s.is_empty())
+}
+
+/// Compare version strings component-wise ("1.10" > "1.9"), falling back to text order
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |v: &str| v.trim_start_matches(['v', 'V']).split('.').map(|p| p.parse::<u64>()).collect::<Result<Vec<_>, _>>();
+    match (parse(a), parse(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+/// Versions of `policy_type` (case-insensitive), oldest effective first
+pub fn versions<'a>(policies: &'a [Value], policy_type: &str) -> Vec<&'a Value> {
+    let mut versions: Vec<&Value> = policies
+        .iter()
+        .filter(|p| text(p, "policy_type").eq_ignore_ascii_case(policy_type))
+        .collect();
+    versions.sort_by(|a, b| {
+        int(a, "effective_date")
+            .cmp(&int(b, "effective_date"))
+            .then_with(|| compare_versions(text(a, "version"), text(b, "version")))
+            .then_with(|| int(a, "created_at").cmp(&int(b, "created_at")))
+    });
+    versions
+}
+
+/// The version of `policy_type` in effect at `at`
+pub fn effective<'a>(policies: &'a [Value], policy_type: &str, at: i64) -> Option<&'a Value> {
+    versions(policies, policy_type)
+        .into_iter()
+        .rfind(|p| int(p, "effective_date") <= at && (is_active(p) || superseded_by(p).is_some()))
+}
+
+/// Default version label for a successor: bump the last numeric component
+pub fn next_version(current: &str) -> String {
+    let current = current.trim();
+    if current.is_empty() {
+        return "2".to_string();
+    }
+    match current.rsplit_once('.') {
+        Some((head, last)) => match last.parse::<u64>() {
+            Ok(n) => format!("{}.{}", head, n + 1),
+            Err(_) => format!("{}.1", current),
+        },
+        None => match current.trim_start_matches(['v', 'V']).parse::<u64>() {
+            Ok(n) => format!("{}{}", &current[..current.len() - n.to_string().len()], n + 1),
+            Err(_) => format!("{}.1", current),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_effective_version() {
+        let policies = vec![
+            json!({"policy_id": "v1", "policy_type": "return", "effective_date": 100, "version": "1", "is_active": false, "superseded_by": "v2"}),
+            json!({"policy_id": "v2", "policy_type": "Return", "effective_date": 200, "version": "2", "is_active": true}),
+            json!({"policy_id": "draft", "policy_type": "return", "effective_date": 150, "is_active": false}),
+            json!({"policy_id": "ship", "policy_type": "shipping", "effective_date": 50, "is_active": true}),
+        ];
+        let id = |p: Option<&Value>| p.map(|p| p["policy_id"].as_str().unwrap().to_string());
+        assert_eq!(id(effective(&policies, "return", 50)), None);
+        assert_eq!(id(effective(&policies, "return", 160)), Some("v1".to_string()));
+        assert_eq!(id(effective(&policies, "RETURN", 250)), Some("v2".to_string()));
+
+        assert_eq!(next_version(""), "2");
+        assert_eq!(next_version("v3"), "v4");
+        assert_eq!(next_version("1.9"), "1.10");
+        assert_eq!(next_version("beta"), "beta.1");
+    }
+}
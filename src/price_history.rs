@@ -0,0 +1,88 @@
+//! Price history of products and services
+//!
+//! Updates overwrite `price` and `currency` on the memory node, so each
+//! change is also written as a `PriceChange` node (old and new values, when,
+//! and which tool). `get_price_history` reads them back as a timeline, oldest
+//! first, with the change in percent where the currency stayed the same.
+
+use serde_json::{json, Value};
+
+/// `add_price_change` payload when `before` → `after` changes the price or currency
+pub fn change(memory_type: &str, id_field: &str, before: &Value, after: &Value, source: &str, changed_at: i64) -> Option<Value> {
+    let price = |memory: &Value| memory.get("price").and_then(|v| v.as_f64()).unwrap_or(0.0);
+    let currency = |memory: &Value| memory.get("currency").and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let (old_price, new_price) = (price(before), price(after));
+    let (old_currency, new_currency) = (currency(before), currency(after));
+    if old_price == new_price && old_currency == new_currency {
+        return None;
+    }
+    let name_field = format!("{}_name", memory_type);
+    Some(json!({
+        "business_id": after.get("business_id").and_then(|v| v.as_str()).unwrap_or(""),
+        "entity_id": after.get(id_field).and_then(|v| v.as_str()).unwrap_or(""),
+        "memory_type": memory_type,
+        "entity_name": after.get(&name_field).and_then(|v| v.as_str()).unwrap_or(""),
+        "old_price": old_price,
+        "new_price": new_price,
+        "old_currency": old_currency,
+        "new_currency": new_currency,
+        "source": source,
+        "changed_at": changed_at
+    }))
+}
+
+/// Stored changes within `since..=until` (Unix seconds), oldest first
+pub fn timeline(changes: &[Value], since: Option<i64>, until: Option<i64>) -> Vec<Value> {
+    let mut entries: Vec<(i64, Value)> = changes
+        .iter()
+        .filter_map(|change| {
+            let at = change.get("changed_at").and_then(|v| v.as_i64())?;
+            if since.is_some_and(|s| at < s) || until.is_some_and(|u| at > u) {
+                return None;
+            }
+            let old_price = change.get("old_price").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            let new_price = change.get("new_price").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            let same_currency = change.get("old_currency") == change.get("new_currency");
+            let percent = (same_currency && old_price > 0.0).then(|| ((new_price - old_price) / old_price * 10_000.0).round() / 100.0);
+            Some((at, json!({
+                "memory_type": change.get("memory_type"),
+                "entity_id": change.get("entity_id"),
+                "entity_name": change.get("entity_name"),
+                "old_price": old_price,
+                "new_price": new_price,
+                "old_currency": change.get("old_currency"),
+                "new_currency": change.get("new_currency"),
+                "change_percent": percent,
+                "source": change.get("source"),
+                "changed_at": at
+            })))
+        })
+        .collect();
+    entries.sort_by_key(|(at, _)| *at);
+    entries.into_iter().map(|(_, entry)| entry).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_and_timeline() {
+        let before = json!({"business_id": "B1", "product_id": "P1", "product_name": "Latte", "price": 4.0, "currency": "EUR"});
+        let after = json!({"business_id": "B1", "product_id": "P1", "product_name": "Latte", "price": 4.5, "currency": "EUR"});
+        assert!(change("product", "product_id", &before, &before, "update_business_memory", 1).is_none());
+        let entry = change("product", "product_id", &before, &after, "update_business_memory", 200).unwrap();
+        assert_eq!(entry["entity_id"], "P1");
+        assert_eq!(entry["entity_name"], "Latte");
+        assert_eq!(entry["old_price"], 4.0);
+
+        let earlier = json!({"entity_id": "P1", "old_price": 5.0, "new_price": 4.0, "old_currency": "EUR", "new_currency": "EUR", "changed_at": 100});
+        let switched = json!({"entity_id": "P1", "old_price": 4.5, "new_price": 5.0, "old_currency": "EUR", "new_currency": "USD", "changed_at": 300});
+        let history = timeline(&[switched, entry, earlier], None, None);
+        assert_eq!(history.iter().map(|e| e["changed_at"].as_i64().unwrap()).collect::<Vec<_>>(), vec![100, 200, 300]);
+        assert_eq!(history[0]["change_percent"], -20.0);
+        assert_eq!(history[1]["change_percent"], 12.5);
+        assert_eq!(history[2]["change_percent"], Value::Null);
+        assert_eq!(timeline(&history, Some(150), Some(250)).len(), 1);
+    }
+}
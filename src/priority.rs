@@ -0,0 +1,117 @@
+//! Work classes for shared HelixDB and embedding capacity
+//!
+//! Tool calls run as `Interactive` work. Imports, re-embedding and
+//! consolidation run inside [`background`], and their requests wait at a
+//! [`PriorityGate`] while any interactive request is in flight, so agent
+//! latency stays stable during maintenance.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::{Notify, Semaphore, SemaphorePermit};
+
+/// Default number of background requests allowed in flight at once
+pub const DEFAULT_BACKGROUND_CONCURRENCY: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkClass {
+    Interactive,
+    Background,
+}
+
+tokio::task_local! {
+    static WORK_CLASS: WorkClass;
+}
+
+/// Work class of the current task (interactive unless inside [`background`])
+pub fn current() -> WorkClass {
+    WORK_CLASS.try_with(|class| *class).unwrap_or(WorkClass::Interactive)
+}
+
+/// Run `future` as background work
+pub async fn background<F: Future>(future: F) -> F::Output {
+    WORK_CLASS.scope(WorkClass::Background, future).await
+}
+
+/// Admission gate giving interactive work precedence over background work
+pub struct PriorityGate {
+    interactive: AtomicUsize,
+    idle: Notify,
+    background: Semaphore,
+}
+
+/// Held for the duration of one request
+pub struct Ticket<'a> {
+    interactive: Option<&'a PriorityGate>,
+    _permit: Option<SemaphorePermit<'a>>,
+}
+
+impl PriorityGate {
+    pub fn new(background_concurrency: usize) -> Self {
+        PriorityGate {
+            interactive: AtomicUsize::new(0),
+            idle: Notify::new(),
+            background: Semaphore::new(background_concurrency.max(1)),
+        }
+    }
+
+    /// Admit a request of the current task's work class.
+    ///
+    /// Interactive requests are admitted immediately; background requests
+    /// wait until no interactive request is in flight and a background slot is free.
+    pub async fn enter(&self) -> Ticket<'_> {
+        if current() == WorkClass::Interactive {
+            self.interactive.fetch_add(1, Ordering::SeqCst);
+            return Ticket { interactive: Some(self), _permit: None };
+        }
+
+        loop {
+            // Register for the wake-up before checking, so a release in between isn't missed
+            let idle = self.idle.notified();
+            if self.interactive.load(Ordering::SeqCst) == 0 {
+                let permit = self.background.acquire().await.expect("priority gate semaphore is never closed");
+                if self.interactive.load(Ordering::SeqCst) == 0 {
+                    return Ticket { interactive: None, _permit: Some(permit) };
+                }
+                drop(permit);
+            }
+            idle.await;
+        }
+    }
+}
+
+impl Drop for Ticket<'_> {
+    fn drop(&mut self) {
+        if let Some(gate) = self.interactive {
+            if gate.interactive.fetch_sub(1, Ordering::SeqCst) == 1 {
+                gate.idle.notify_waiters();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_background_waits_for_interactive() {
+        let gate = Arc::new(PriorityGate::new(1));
+        let interactive = gate.enter().await;
+        assert_eq!(gate.interactive.load(Ordering::SeqCst), 1);
+
+        let waiting = tokio::spawn({
+            let gate = gate.clone();
+            background(async move {
+                let _ticket = gate.enter().await;
+            })
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiting.is_finished());
+
+        drop(interactive);
+        tokio::time::timeout(Duration::from_secs(1), waiting).await.unwrap().unwrap();
+        assert_eq!(gate.interactive.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,160 @@
+//! Differential privacy for cross-customer aggregates
+//!
+//! Laplace mechanism over the rollups produced by [`crate::analytics`]. The
+//! configured epsilon is the budget for one response and is split evenly
+//! across the statistic groups it releases (sequential composition);
+//! histograms (counts by type, trend buckets) are disjoint and share their
+//! group's share (parallel composition). Amounts are clamped per record so
+//! sums have bounded sensitivity, and free-text items that could identify a
+//! single customer are withheld.
+
+use rand::Rng;
+use serde_json::{json, Value};
+
+/// Rating scale assumed for average sensitivity (1-5 stars)
+const RATING_RANGE: f64 = 4.0;
+
+/// Sample Laplace(0, scale) noise
+pub fn laplace<R: Rng + ?Sized>(rng: &mut R, scale: f64) -> f64 {
+    let u: f64 = rng.random_range(-0.5..0.5);
+    -scale * u.signum() * (1.0 - 2.0 * u.abs()).ln()
+}
+
+/// Clamp `field` on every record to at most `max`, bounding a sum's sensitivity
+pub fn clamp_amounts(records: &mut [Value], field: &str, max: f64) {
+    for record in records {
+        if let Some(amount) = record.get(field).and_then(|v| v.as_f64()) {
+            record[field] = json!(amount.clamp(0.0, max));
+        }
+    }
+}
+
+struct Noise<'a, R: Rng + ?Sized> {
+    rng: &'a mut R,
+    epsilon: f64,
+}
+
+impl<R: Rng + ?Sized> Noise<'_, R> {
+    /// Noisy non-negative count (sensitivity 1)
+    fn count(&mut self, value: &mut Value) {
+        if let Some(count) = value.as_f64() {
+            let noisy = (count + laplace(self.rng, 1.0 / self.epsilon)).round().max(0.0);
+            *value = json!(noisy as u64);
+        }
+    }
+
+    /// Noisy sum with per-record sensitivity `max`
+    fn sum(&mut self, value: &mut Value, max: f64) {
+        if let Some(sum) = value.as_f64() {
+            let noisy = (sum + laplace(self.rng, max / self.epsilon)).max(0.0);
+            *value = json!((noisy * 100.0).round() / 100.0);
+        }
+    }
+
+    /// Noisy mean of `n` ratings on the 1-5 scale
+    fn rating(&mut self, value: &mut Value, n: f64) {
+        if let Some(mean) = value.as_f64() {
+            let noisy = mean + laplace(self.rng, RATING_RANGE / n.max(1.0) / self.epsilon);
+            *value = json!((noisy.clamp(1.0, 5.0) * 100.0).round() / 100.0);
+        }
+    }
+
+    fn histogram(&mut self, value: &mut Value) {
+        if let Some(map) = value.as_object_mut() {
+            map.values_mut().for_each(|v| self.count(v));
+        }
+    }
+}
+
+/// Apply noise to an [`crate::analytics::summarize_interactions`] rollup
+pub fn privatize_interactions<R: Rng + ?Sized>(summary: &mut Value, epsilon: f64, max_amount: f64, rng: &mut R) {
+    // counts, counts_by_type, ratings, amounts, top_products, trend
+    let mut noise = Noise { rng, epsilon: epsilon / 6.0 };
+
+    noise.count(&mut summary["product_interactions"]);
+    noise.count(&mut summary["service_interactions"]);
+    let total = summary["product_interactions"].as_u64().unwrap_or(0) + summary["service_interactions"].as_u64().unwrap_or(0);
+    summary["total_interactions"] = json!(total);
+
+    noise.histogram(&mut summary["counts_by_type"]);
+
+    let product_n = summary["product_interactions"].as_f64().unwrap_or(0.0);
+    let service_n = summary["service_interactions"].as_f64().unwrap_or(0.0);
+    noise.rating(&mut summary["average_product_rating"], product_n);
+    noise.rating(&mut summary["average_service_rating"], service_n);
+
+    if let Some(totals) = summary["total_amount_by_currency"].as_object_mut() {
+        totals.values_mut().for_each(|v| noise.sum(v, max_amount));
+    }
+
+    if let Some(top) = summary["top_products"].as_array_mut() {
+        top.iter_mut().for_each(|p| noise.count(&mut p["positive_interactions"]));
+        top.sort_by_key(|p| std::cmp::Reverse(p["positive_interactions"].as_u64().unwrap_or(0)));
+    }
+
+    if let Some(trend) = summary["trend"].as_array_mut() {
+        trend.iter_mut().for_each(|bucket| noise.count(&mut bucket["count"]));
+    }
+}
+
+/// Apply noise to an [`crate::analytics::summarize_feedback`] rollup.
+/// Individual negative items and theme texts are withheld.
+pub fn privatize_feedback<R: Rng + ?Sized>(summary: &mut Value, epsilon: f64, rng: &mut R) {
+    // total, sentiment, rating, unresolved, awaiting_response, themes
+    let mut noise = Noise { rng, epsilon: epsilon / 6.0 };
+
+    noise.count(&mut summary["total_feedback"]);
+    noise.histogram(&mut summary["sentiment_distribution"]);
+    let n = summary["total_feedback"].as_f64().unwrap_or(0.0);
+    noise.rating(&mut summary["average_rating"], n);
+    noise.count(&mut summary["unresolved"]);
+    noise.count(&mut summary["awaiting_response"]);
+
+    if let Some(map) = summary.as_object_mut() {
+        map.remove("top_negative");
+    }
+    if let Some(themes) = summary.get_mut("themes").and_then(|t| t.as_array_mut()) {
+        for theme in themes.iter_mut() {
+            let mut size = theme["size"].take();
+            noise.count(&mut size);
+            *theme = json!({"size": size});
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_laplace_is_centered() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let samples: Vec<f64> = (0..20_000).map(|_| laplace(&mut rng, 2.0)).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let mean_abs = samples.iter().map(|s| s.abs()).sum::<f64>() / samples.len() as f64;
+        assert!(mean.abs() < 0.1, "{}", mean);
+        // E|X| = scale for Laplace
+        assert!((mean_abs - 2.0).abs() < 0.1, "{}", mean_abs);
+    }
+
+    #[test]
+    fn test_privatize_feedback_withholds_items() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut summary = json!({
+            "total_feedback": 10,
+            "sentiment_distribution": {"negative": 4, "positive": 6},
+            "average_rating": 3.2,
+            "unresolved": 2,
+            "awaiting_response": 1,
+            "top_negative": [{"feedback_id": "F1"}],
+            "themes": [{"size": 3, "representative": "slow delivery", "examples": []}]
+        });
+        privatize_feedback(&mut summary, 1.0, &mut rng);
+        assert!(summary.get("top_negative").is_none());
+        assert!(summary["themes"][0].get("representative").is_none());
+        assert!(summary["total_feedback"].is_u64());
+        let rating = summary["average_rating"].as_f64().unwrap();
+        assert!((1.0..=5.0).contains(&rating));
+    }
+}
@@ -0,0 +1,197 @@
+//! MCP prompts for common memory workflows
+//!
+//! Each prompt is a parameterized user message that walks the model through
+//! a multi-tool workflow (onboarding a business, summarizing a customer,
+//! logging an interaction) so clients can offer them as one-click actions
+//! instead of relying on the model to discover the right tool sequence.
+
+use rmcp::model::{GetPromptResult, Prompt, PromptArgument, PromptMessage, PromptMessageRole};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+struct Arg {
+    name: &'static str,
+    description: &'static str,
+    required: bool,
+}
+
+struct Spec {
+    name: &'static str,
+    title: &'static str,
+    description: &'static str,
+    arguments: &'static [Arg],
+    render: fn(&Args) -> String,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PromptError {
+    #[error("unknown prompt: {0}")]
+    Unknown(String),
+    #[error("missing required argument: {0}")]
+    MissingArgument(&'static str),
+}
+
+/// Prompt arguments as strings; absent optional arguments read as ""
+struct Args<'a>(&'a Map<String, Value>);
+
+impl Args<'_> {
+    fn get(&self, name: &str) -> String {
+        match self.0.get(name) {
+            Some(Value::String(s)) => s.trim().to_string(),
+            Some(Value::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        }
+    }
+
+    /// `template` with `{}` replaced by the argument, or "" when it is absent
+    fn optional(&self, name: &str, template: &str) -> String {
+        match self.get(name) {
+            value if value.is_empty() => String::new(),
+            value => template.replace("{}", &value),
+        }
+    }
+}
+
+const PROMPTS: &[Spec] = &[
+    Spec {
+        name: "onboard_business",
+        title: "Onboard a business",
+        description: "Collect and store a new business's locations, hours, products, services and policies",
+        arguments: &[
+            Arg { name: "business_id", description: "ID to store the business's memories under", required: true },
+            Arg { name: "business_name", description: "Business name", required: true },
+            Arg { name: "business_type", description: "Kind of business (restaurant, clinic, retail store, ...)", required: false },
+            Arg { name: "source", description: "Where to take the details from (website text, menu, notes)", required: false },
+        ],
+        render: onboard_business,
+    },
+    Spec {
+        name: "summarize_customer",
+        title: "Summarize a customer",
+        description: "Build a short profile of a customer from their memories, interactions and feedback",
+        arguments: &[
+            Arg { name: "customer_id", description: "Customer to summarize", required: true },
+            Arg { name: "business_id", description: "Limit interactions to this business", required: false },
+            Arg { name: "focus", description: "What the summary is for (upsell, support follow-up, ...)", required: false },
+        ],
+        render: summarize_customer,
+    },
+    Spec {
+        name: "log_interaction",
+        title: "Log an interaction",
+        description: "Record a customer's product or service interaction and any preference it reveals",
+        arguments: &[
+            Arg { name: "customer_id", description: "Customer who interacted", required: true },
+            Arg { name: "item", description: "Product or service name or ID", required: true },
+            Arg { name: "interaction_type", description: "purchased, viewed, liked, booked, completed, ...", required: false },
+            Arg { name: "details", description: "What happened, in the customer's or agent's words", required: false },
+        ],
+        render: log_interaction,
+    },
+];
+
+fn onboard_business(args: &Args) -> String {
+    format!(
+        "Onboard the business \"{name}\"{kind} into memory under business_id \"{id}\".{source}\n\n\
+        1. Check nothing is stored yet: query_business_memory with business_id \"{id}\" and memory_type \"all\".\n\
+        2. Store each location with create_business_memory (memory_type \"location\"), then its opening hours (memory_type \"hours\").\n\
+        3. Store products and services (memory_type \"product\" / \"service\"), one call per item, with prices and currency.\n\
+        4. Store policies (returns, cancellations, privacy) with memory_type \"policy\" and upcoming events with memory_type \"event\".\n\
+        5. If customers need directions, add a navigation hub with create_navigation_hub and its main waypoints.\n\
+        6. Finish with is_business_open to confirm the hours were understood, and list anything you could not find.\n\n\
+        Ask for missing essentials (address, hours) rather than inventing them.",
+        name = args.get("business_name"),
+        kind = args.optional("business_type", " ({})"),
+        id = args.get("business_id"),
+        source = args.optional("source", "\n\nUse these details:\n{}"),
+    )
+}
+
+fn summarize_customer(args: &Args) -> String {
+    format!(
+        "Summarize customer \"{id}\"{focus}.\n\n\
+        1. Read their memories with query_customer_memory (memory_type \"all\").\n\
+        2. Read their recent interactions with query_customer_interactions{business}, and totals with analyze_interactions.\n\
+        3. Check sentiment and open complaints with summarize_feedback.\n\
+        4. Use find_customer_insights for related products or customers if it helps.\n\n\
+        Write a short profile: who they are, what they like and avoid, recent activity, spend, open issues and any rules \
+        (communication or dietary) to respect. Cite memory IDs for each claim and say when data is missing.",
+        id = args.get("customer_id"),
+        focus = args.optional("focus", " for {}"),
+        business = args.optional("business_id", " (business_id \"{}\")"),
+    )
+}
+
+fn log_interaction(args: &Args) -> String {
+    format!(
+        "Log that customer \"{id}\" {kind} \"{item}\".{details}\n\n\
+        1. Resolve \"{item}\" to a product or service ID with search_bm25 (or search_semantic if the name is vague).\n\
+        2. Record it with create_customer_product_interaction or create_customer_service_interaction, keeping the \
+        customer's reason in text_reason / text_feedback.\n\
+        3. If it reveals a lasting preference or complaint, store it with create_customer_memory \
+        (memory_type \"preference\" or \"feedback\"), after checking query_customer_memory for an existing one to update instead.\n\n\
+        Confirm what was stored, with the new IDs.",
+        id = args.get("customer_id"),
+        kind = match args.get("interaction_type") {
+            kind if kind.is_empty() => "interacted with".to_string(),
+            kind => kind,
+        },
+        item = args.get("item"),
+        details = args.optional("details", "\n\nDetails: {}"),
+    )
+}
+
+pub fn list() -> Vec<Prompt> {
+    PROMPTS
+        .iter()
+        .map(|spec| {
+            let arguments = spec
+                .arguments
+                .iter()
+                .map(|arg| PromptArgument {
+                    name: arg.name.to_string(),
+                    title: None,
+                    description: Some(arg.description.to_string()),
+                    required: Some(arg.required),
+                })
+                .collect();
+            let mut prompt = Prompt::new(spec.name, Some(spec.description), Some(arguments));
+            prompt.title = Some(spec.title.to_string());
+            prompt
+        })
+        .collect()
+}
+
+pub fn get(name: &str, arguments: Option<&Map<String, Value>>) -> Result<GetPromptResult, PromptError> {
+    let spec = PROMPTS.iter().find(|p| p.name == name).ok_or_else(|| PromptError::Unknown(name.to_string()))?;
+    let empty = Map::new();
+    let args = Args(arguments.unwrap_or(&empty));
+    if let Some(missing) = spec.arguments.iter().find(|a| a.required && args.get(a.name).is_empty()) {
+        return Err(PromptError::MissingArgument(missing.name));
+    }
+    Ok(GetPromptResult {
+        description: Some(spec.description.to_string()),
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, (spec.render)(&args))],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::PromptMessageContent;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_prompts() {
+        assert_eq!(list().len(), PROMPTS.len());
+        assert_eq!(get("nope", None), Err(PromptError::Unknown("nope".to_string())));
+        let partial = json!({"customer_id": "C1"});
+        assert_eq!(get("log_interaction", partial.as_object()), Err(PromptError::MissingArgument("item")));
+
+        let args = json!({"customer_id": "C1", "business_id": "B9"});
+        let result = get("summarize_customer", args.as_object()).unwrap();
+        let PromptMessageContent::Text { text } = &result.messages[0].content else { panic!("expected text") };
+        assert!(text.starts_with("Summarize customer \"C1\".\n"));
+        assert!(text.contains("query_customer_interactions (business_id \"B9\")"));
+    }
+}
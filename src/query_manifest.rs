@@ -0,0 +1,184 @@
+//! Payload schemas for `do_query` endpoints
+//!
+//! Each HelixQL query declares its parameters (`QUERY name(a: String, b: [F64])`),
+//! so `db/queries.hx` doubles as the manifest: its signatures become one JSON
+//! schema per endpoint. A `.json` manifest mapping endpoint names to schemas
+//! works too. `do_query` payloads are checked against the schema (missing,
+//! unknown and mistyped fields) before they reach HelixDB, and the tool
+//! description lists each endpoint's fields.
+
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+use crate::validation::Violation;
+
+pub struct QueryManifest {
+    schemas: BTreeMap<String, Value>,
+}
+
+/// JSON schema for a HelixQL parameter type
+fn type_schema(helix_type: &str) -> Value {
+    if let Some(inner) = helix_type.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        return json!({"type": "array", "items": type_schema(inner.trim())});
+    }
+    match helix_type {
+        "I8" | "I16" | "I32" | "I64" | "U8" | "U16" | "U32" | "U64" | "U128" => json!({"type": "integer"}),
+        "F32" | "F64" => json!({"type": "number"}),
+        "Boolean" => json!({"type": "boolean"}),
+        _ => json!({"type": "string"}),
+    }
+}
+
+fn matches_schema(value: &Value, schema: &Value) -> bool {
+    match schema.get("type").and_then(|t| t.as_str()) {
+        Some("integer") => value.is_i64() || value.is_u64(),
+        Some("number") => value.is_number(),
+        Some("boolean") => value.is_boolean(),
+        Some("string") => value.is_string(),
+        Some("array") => value.as_array().is_some_and(|items| {
+            let item_schema = schema.get("items").cloned().unwrap_or(Value::Null);
+            items.iter().all(|item| matches_schema(item, &item_schema))
+        }),
+        Some("object") => value.is_object(),
+        _ => true,
+    }
+}
+
+fn type_name(schema: &Value) -> String {
+    match schema.get("type").and_then(|t| t.as_str()) {
+        Some("array") => format!("{}[]", type_name(schema.get("items").unwrap_or(&Value::Null))),
+        Some(name) => name.to_string(),
+        None => "any".to_string(),
+    }
+}
+
+impl QueryManifest {
+    /// Schemas from the `QUERY` signatures of a HelixQL source file
+    pub fn from_helixql(source: &str) -> Self {
+        let mut schemas = BTreeMap::new();
+        for declaration in source.split("QUERY ").skip(1) {
+            let Some((name, rest)) = declaration.split_once('(') else {
+                continue;
+            };
+            let Some((params, _)) = rest.split_once(')') else {
+                continue;
+            };
+            let mut properties = Map::new();
+            let mut required = Vec::new();
+            for param in params.split(',').filter(|p| !p.trim().is_empty()) {
+                let Some((field, helix_type)) = param.split_once(':') else {
+                    continue;
+                };
+                let field = field.trim();
+                properties.insert(field.to_string(), type_schema(helix_type.trim()));
+                required.push(json!(field));
+            }
+            schemas.insert(
+                name.trim().to_string(),
+                json!({"type": "object", "properties": properties, "required": required, "additionalProperties": false}),
+            );
+        }
+        Self { schemas }
+    }
+
+    /// Load a `.json` manifest (`{endpoint: schema}`) or a HelixQL query file
+    pub fn load(path: &str) -> Result<Self, String> {
+        let source = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        if path.ends_with(".json") {
+            let schemas = serde_json::from_str(&source).map_err(|e| format!("Invalid manifest {}: {}", path, e))?;
+            return Ok(Self { schemas });
+        }
+        Ok(Self::from_helixql(&source))
+    }
+
+    pub fn len(&self) -> usize {
+        self.schemas.len()
+    }
+
+    pub fn schema(&self, endpoint: &str) -> Option<&Value> {
+        self.schemas.get(endpoint)
+    }
+
+    /// Problems with `payload` for `endpoint`; endpoints without a schema pass
+    pub fn validate(&self, endpoint: &str, payload: &Value) -> Vec<Violation> {
+        let Some(schema) = self.schemas.get(endpoint) else {
+            return Vec::new();
+        };
+        let Some(fields) = payload.as_object() else {
+            return vec![Violation {
+                field: "payload".to_string(),
+                value: payload.clone(),
+                problem: "must be an object".to_string(),
+                valid_values: None,
+            }];
+        };
+        let properties = schema.get("properties").and_then(|p| p.as_object()).cloned().unwrap_or_default();
+        let mut violations = Vec::new();
+        for field in schema.get("required").and_then(|r| r.as_array()).into_iter().flatten().filter_map(|f| f.as_str()) {
+            if fields.get(field).is_none_or(|v| v.is_null()) {
+                let expected = properties.get(field).map(type_name).unwrap_or_else(|| "any".to_string());
+                violations.push(Violation {
+                    field: format!("payload.{}", field),
+                    value: Value::Null,
+                    problem: format!("is required ({})", expected),
+                    valid_values: None,
+                });
+            }
+        }
+        let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+        for (field, value) in fields {
+            let problem = match properties.get(field) {
+                None if closed => format!("is not a parameter of {}", endpoint),
+                Some(expected) if !value.is_null() && !matches_schema(value, expected) => format!("must be {}", type_name(expected)),
+                _ => continue,
+            };
+            violations.push(Violation { field: format!("payload.{}", field), value: value.clone(), problem, valid_values: None });
+        }
+        violations
+    }
+
+    /// `endpoint(field: type, ...)` for each endpoint, for tool descriptions
+    pub fn signature(&self, endpoint: &str) -> Option<String> {
+        let schema = self.schemas.get(endpoint)?;
+        let properties = schema.get("properties").and_then(|p| p.as_object());
+        let fields: Vec<String> = properties
+            .into_iter()
+            .flatten()
+            .map(|(field, field_schema)| format!("{}: {}", field, type_name(field_schema)))
+            .collect();
+        Some(format!("{}({})", endpoint, fields.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_helixql_manifest_validation() {
+        let manifest = QueryManifest::from_helixql(
+            "QUERY get_business_products(business_id: String) =>\n    RETURN products\n\n\
+             QUERY update_product_price(\n    product_id: String,\n    price: F64,\n    tags: [String],\n    stock: I64\n) =>\n    RETURN product\n\n\
+             QUERY get_all_businesses() =>\n    RETURN businesses\n",
+        );
+        assert_eq!(manifest.len(), 3);
+        assert_eq!(manifest.signature("get_all_businesses").unwrap(), "get_all_businesses()");
+
+        let ok = json!({"product_id": "P1", "price": 9.5, "tags": ["a"], "stock": 3});
+        assert!(manifest.validate("update_product_price", &ok).is_empty());
+
+        let bad = json!({"product_id": "P1", "price": "cheap", "tags": [1], "colour": "red"});
+        let problems: Vec<(String, String)> = manifest
+            .validate("update_product_price", &bad)
+            .into_iter()
+            .map(|v| (v.field, v.problem))
+            .collect();
+        assert_eq!(problems, vec![
+            ("payload.stock".to_string(), "is required (integer)".to_string()),
+            ("payload.colour".to_string(), "is not a parameter of update_product_price".to_string()),
+            ("payload.price".to_string(), "must be number".to_string()),
+            ("payload.tags".to_string(), "must be string[]".to_string()),
+        ]);
+        assert!(manifest.validate("unknown_endpoint", &json!({"x": 1})).is_empty());
+    }
+}
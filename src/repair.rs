@@ -0,0 +1,172 @@
+//! Graph consistency checks for `repair_graph`
+//!
+//! Partial failures in create/update flows leave the graph inconsistent: an
+//! embedding vector without its memory node (also left behind by cascade
+//! deletes), a memory node whose embedding step failed, or an interaction
+//! naming a product or service that has since been deleted. HelixDB drops a
+//! node's edges together with the node, so the dangling links that survive
+//! are these ID references.
+//!
+//! `verify_embeddings` looks for the subtler case of a vector that exists but
+//! no longer matches its memory's text (or was made by another model).
+
+use crate::similarity;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    OrphanVectors,
+    MissingEmbeddings,
+    DanglingReferences,
+}
+
+pub const CHECKS: [Check; 3] = [Check::OrphanVectors, Check::MissingEmbeddings, Check::DanglingReferences];
+
+impl Check {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::OrphanVectors => "orphan_vectors",
+            Self::MissingEmbeddings => "missing_embeddings",
+            Self::DanglingReferences => "dangling_references",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        CHECKS.into_iter().find(|c| c.name() == name)
+    }
+}
+
+/// Return names of the `find_*_missing_embeddings` queries and the memory type each holds
+const COLLECTIONS: &[(&str, &str)] = &[
+    ("products", "product"),
+    ("services", "service"),
+    ("locations", "location"),
+    ("hours", "hours"),
+    ("social", "social"),
+    ("policies", "policy"),
+    ("events", "event"),
+    ("information", "information"),
+    ("behaviors", "behavior"),
+    ("preferences", "preference"),
+    ("desires", "desire"),
+    ("rules", "rule"),
+    ("feedback", "feedback"),
+];
+
+/// Memories without an embedding, per memory type
+pub fn missing_embeddings(result: &Value) -> Vec<(&'static str, Vec<Value>)> {
+    COLLECTIONS
+        .iter()
+        .filter_map(|(key, memory_type)| {
+            let nodes = match result.get(*key) {
+                Some(Value::Array(nodes)) => nodes.clone(),
+                Some(node @ Value::Object(_)) => vec![node.clone()],
+                _ => Vec::new(),
+            };
+            (!nodes.is_empty()).then_some((*memory_type, nodes))
+        })
+        .collect()
+}
+
+/// Distinct non-empty values of `id_field` across `items`
+pub fn referenced_ids(items: &[Value], id_field: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    items
+        .iter()
+        .filter_map(|item| item.get(id_field).and_then(|v| v.as_str()))
+        .filter(|id| !id.is_empty() && seen.insert(id.to_string()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Items whose `id_field` names something not in `existing`
+pub fn dangling<'a>(items: &'a [Value], id_field: &str, existing: &HashSet<String>) -> Vec<&'a Value> {
+    items
+        .iter()
+        .filter(|item| {
+            item.get(id_field)
+                .and_then(|v| v.as_str())
+                .is_some_and(|id| !id.is_empty() && !existing.contains(id))
+        })
+        .collect()
+}
+
+/// Indices of at most `sample_size` of `len` items, spread evenly over them
+pub fn spread_sample(len: usize, sample_size: usize) -> Vec<usize> {
+    let sample_size = sample_size.max(1);
+    let step = (len as f64 / sample_size as f64).max(1.0);
+    (0..sample_size.min(len)).map(|i| (i as f64 * step) as usize).collect()
+}
+
+/// The embedding node of a `get_*_with_embedding` result
+pub fn stored_embedding(result: &Value) -> Option<Value> {
+    match result.get("embedding") {
+        Some(Value::Array(items)) => items.first().cloned(),
+        Some(item @ Value::Object(_)) => Some(item.clone()),
+        _ => None,
+    }
+}
+
+/// How a fresh embedding of `text` disagrees with the stored one, as
+/// (problem, details); `None` when they are at least `min_similarity` alike
+pub fn embedding_problem(stored: &Value, stored_vector: &[f64], text: &str, fresh: &[f64], min_similarity: f64) -> Option<(&'static str, Value)> {
+    if fresh.len() != stored_vector.len() {
+        return Some(("dimension_mismatch", json!({"stored_dimensions": stored_vector.len(), "generated_dimensions": fresh.len()})));
+    }
+    let similarity = similarity::cosine_similarity(fresh, stored_vector);
+    if similarity >= min_similarity {
+        return None;
+    }
+    let stored_text = stored.get("composite_embedding_text").and_then(|t| t.as_str());
+    Some(("stale_embedding", json!({
+        "similarity": similarity,
+        "text_changed": stored_text.is_some_and(|t| t != text)
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_and_dangling() {
+        let result = json!({"products": [{"product_id": "P1"}], "services": [], "feedback": {"feedback_id": "F1"}});
+        let missing = missing_embeddings(&result);
+        assert_eq!(missing.len(), 2);
+        assert_eq!(missing[0].0, "product");
+        assert_eq!(missing[1], ("feedback", vec![json!({"feedback_id": "F1"})]));
+
+        let interactions = vec![
+            json!({"interaction_id": "I1", "product_id": "P1"}),
+            json!({"interaction_id": "I2", "product_id": "P2"}),
+            json!({"interaction_id": "I3", "product_id": "P2"}),
+            json!({"interaction_id": "I4"}),
+        ];
+        assert_eq!(referenced_ids(&interactions, "product_id"), vec!["P1", "P2"]);
+        let existing: HashSet<String> = ["P1".to_string()].into();
+        let found: Vec<&str> = dangling(&interactions, "product_id", &existing).iter().map(|i| i["interaction_id"].as_str().unwrap()).collect();
+        assert_eq!(found, vec!["I2", "I3"]);
+        assert_eq!(Check::parse("orphan_vectors"), Some(Check::OrphanVectors));
+    }
+
+    #[test]
+    fn test_verify_embeddings_helpers() {
+        assert_eq!(spread_sample(10, 3), vec![0, 3, 6]);
+        assert_eq!(spread_sample(2, 20), vec![0, 1]);
+        assert!(spread_sample(0, 5).is_empty());
+
+        assert_eq!(stored_embedding(&json!({"embedding": [{"id": "v1"}]})).unwrap()["id"], "v1");
+        assert_eq!(stored_embedding(&json!({"embedding": {"id": "v1"}})).unwrap()["id"], "v1");
+        assert!(stored_embedding(&json!({"embedding": []})).is_none());
+
+        let stored = json!({"composite_embedding_text": "Oat latte"});
+        assert!(embedding_problem(&stored, &[1.0, 0.0], "Oat latte", &[0.99, 0.05], 0.9).is_none());
+        let (problem, details) = embedding_problem(&stored, &[1.0, 0.0], "Oat milk latte", &[0.0, 1.0], 0.9).unwrap();
+        assert_eq!(problem, "stale_embedding");
+        assert_eq!(details["text_changed"], true);
+        let (problem, details) = embedding_problem(&stored, &[1.0, 0.0], "Oat latte", &[1.0, 0.0, 0.0], 0.9).unwrap();
+        assert_eq!(problem, "dimension_mismatch");
+        assert_eq!(details["generated_dimensions"], 3);
+    }
+}
@@ -0,0 +1,129 @@
+//! Relevance reranking of semantic search results
+//!
+//! Vector order is only as good as the embedding; a cross-encoder scoring
+//! each candidate against the query puts strong matches first. Candidates
+//! are scored by the configured provider (Cohere-compatible `/rerank` API or
+//! a local TCP service), ordered by score and cut to the caller's limit.
+
+use crate::config::{RerankConfig, RerankProvider};
+use crate::embedding_client::EmbeddingClient;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Fields tried, in order, for the text a candidate is scored on
+const TEXT_FIELDS: &[&str] = &[
+    "text_description",
+    "composite_embedding_text",
+    "text_interaction",
+    "text_reason",
+    "text_feedback",
+    "description",
+    "content",
+];
+
+/// The text a search result is scored on: its description, else its name fields
+pub fn candidate_text(item: &Value) -> String {
+    let text = |field: &str| item.get(field).and_then(|v| v.as_str()).map(str::trim).filter(|t| !t.is_empty());
+    if let Some(found) = TEXT_FIELDS.iter().find_map(|f| text(f)) {
+        return found.to_string();
+    }
+    item.as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| key.ends_with("_name") || key.as_str() == "title" || key.as_str() == "subject")
+        .filter_map(|(_, value)| value.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Relevance score of each document, in document order
+pub async fn scores(config: &RerankConfig, api_key: Option<String>, query: &str, documents: Vec<String>) -> Result<Vec<f64>, String> {
+    match config.provider {
+        RerankProvider::Cohere => {
+            let api_key = api_key.ok_or("Rerank API key not configured (set COHERE_API_KEY or rerank.api_key)")?;
+            let count = documents.len();
+            let response = reqwest::Client::new()
+                .post(&config.api_url)
+                .bearer_auth(api_key)
+                .timeout(Duration::from_secs(config.timeout_secs))
+                .json(&json!({
+                    "model": config.model,
+                    "query": query,
+                    "documents": documents,
+                    "top_n": count
+                }))
+                .send()
+                .await
+                .map_err(|e| format!("Rerank request failed: {}", e))?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(format!("Rerank API error {}: {}", status, error_text));
+            }
+            let body: Value = response.json().await.map_err(|e| format!("Failed to parse rerank response: {}", e))?;
+            cohere_scores(&body, count)
+        }
+        RerankProvider::Tcp => {
+            let address = config.tcp_address.clone().ok_or("rerank.tcp_address not configured")?;
+            let client = EmbeddingClient::new(address, config.timeout_secs);
+            let scores = client
+                .rerank(query, documents, Some(config.model.clone()))
+                .await
+                .map_err(|e| format!("TCP rerank failed: {}", e))?;
+            Ok(scores.into_iter().map(f64::from).collect())
+        }
+    }
+}
+
+/// Scores from a Cohere `/rerank` response (`results: [{index, relevance_score}]`)
+fn cohere_scores(body: &Value, count: usize) -> Result<Vec<f64>, String> {
+    let mut scores = vec![None; count];
+    for result in body["results"].as_array().ok_or("Invalid rerank response: missing results")? {
+        let index = result["index"].as_u64().ok_or("Invalid rerank response: result without index")? as usize;
+        if let Some(slot) = scores.get_mut(index) {
+            *slot = result["relevance_score"].as_f64();
+        }
+    }
+    // Documents the API left out rank last
+    Ok(scores.into_iter().map(|s| s.unwrap_or(f64::MIN)).collect())
+}
+
+/// `results` ordered by score (highest first), each with `rerank_score`, cut to `limit`
+pub fn apply(results: Vec<Value>, scores: &[f64], limit: usize) -> Vec<Value> {
+    let mut scored: Vec<(f64, Value)> = scores.iter().copied().zip(results).collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(score, mut item)| {
+            if let Some(fields) = item.as_object_mut() {
+                fields.insert("rerank_score".to_string(), json!(score));
+            }
+            item
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rerank_order() {
+        let results = vec![
+            json!({"product_name": "Tea", "text_description": "Green tea"}),
+            json!({"product_name": "Espresso"}),
+            json!({"title": "Opening hours"}),
+        ];
+        assert_eq!(candidate_text(&results[0]), "Green tea");
+        assert_eq!(candidate_text(&results[1]), "Espresso");
+
+        let body = json!({"results": [{"index": 1, "relevance_score": 0.9}, {"index": 0, "relevance_score": 0.2}]});
+        let scores = cohere_scores(&body, 3).unwrap();
+        let ranked = apply(results, &scores, 2);
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0]["product_name"], "Espresso");
+        assert_eq!(ranked[0]["rerank_score"], 0.9);
+        assert_eq!(ranked[1]["product_name"], "Tea");
+    }
+}
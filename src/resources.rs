@@ -0,0 +1,290 @@
+//! Dynamic MCP resources rendered from live memory
+//!
+//! Besides the static `meta://` documents, clients can read
+//! `memory://business/{id}/summary` (what is stored for a business, counted
+//! and named per memory type) and `memory://customer/{id}/profile` (a
+//! customer's memories, interaction totals and feedback sentiment) to pin
+//! current context without tool round-trips. `list_resources` advertises
+//! these for the businesses and customers recently seen in the access log.
+//!
+//! `memory://business/{id}/{collection}` (products, services, ...) lists one
+//! memory type. Clients can subscribe to any of these; a subscription follows
+//! the change feed and sends `notifications/resources/updated` whenever a
+//! change may have touched the resource.
+
+use crate::access_log::AccessEntry;
+use crate::change_feed::{ChangeEvent, ChangeFeed};
+use crate::helix_client::HelixClient;
+use rmcp::model::{RawResourceTemplate, ResourceUpdatedNotificationParam};
+use rmcp::service::Peer;
+use rmcp::RoleServer;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+use tokio::task::AbortHandle;
+
+/// Items named per memory type in a summary
+const MAX_NAMED_ITEMS: usize = 10;
+
+/// Text fields tried, in order, to label an item that has no `*_name`
+const LABEL_FIELDS: &[&str] = &["subject", "goal", "action", "text_description", "composite_text", "message"];
+const MAX_LABEL_CHARS: usize = 120;
+
+/// Business collections readable as resources, with the memory type their changes carry
+pub const BUSINESS_COLLECTIONS: &[(&str, &str)] = &[
+    ("products", "product"),
+    ("services", "service"),
+    ("locations", "location"),
+    ("hours", "hours"),
+    ("social", "social"),
+    ("policies", "policy"),
+    ("events", "event"),
+    ("information", "information"),
+];
+
+/// Memory types whose changes can touch a customer profile
+const CUSTOMER_TYPES: &[&str] = &["behavior", "preference", "desire", "rule", "feedback", "communication", "interaction"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dynamic {
+    BusinessSummary(String),
+    /// Business ID and collection (plural memory type)
+    BusinessCollection(String, String),
+    CustomerProfile(String),
+}
+
+impl Dynamic {
+    pub fn parse(uri: &str) -> Option<Self> {
+        let rest = uri.strip_prefix("memory://")?;
+        let (kind, rest) = rest.split_once('/')?;
+        let (id, view) = rest.rsplit_once('/')?;
+        if id.is_empty() {
+            return None;
+        }
+        match (kind, view) {
+            ("business", "summary") => Some(Dynamic::BusinessSummary(id.to_string())),
+            ("business", collection) if BUSINESS_COLLECTIONS.iter().any(|(c, _)| *c == collection) => {
+                Some(Dynamic::BusinessCollection(id.to_string(), collection.to_string()))
+            }
+            ("customer", "profile") => Some(Dynamic::CustomerProfile(id.to_string())),
+            _ => None,
+        }
+    }
+
+    pub fn uri(&self) -> String {
+        match self {
+            Dynamic::BusinessSummary(id) => format!("memory://business/{}/summary", id),
+            Dynamic::BusinessCollection(id, collection) => format!("memory://business/{}/{}", id, collection),
+            Dynamic::CustomerProfile(id) => format!("memory://customer/{}/profile", id),
+        }
+    }
+
+    /// Whether `event` may have changed this resource; events missing the
+    /// owner ID (deletes by memory ID) count when their memory type fits.
+    /// Alerts change nothing.
+    pub fn affected_by(&self, event: &ChangeEvent) -> bool {
+        if event.alert.is_some() {
+            return false;
+        }
+        let business_type = BUSINESS_COLLECTIONS.iter().any(|(_, t)| *t == event.memory_type);
+        let owned_by_business = |id: &str| event.business_id.as_deref().map_or(business_type, |b| b == id);
+        match self {
+            Dynamic::BusinessSummary(id) => owned_by_business(id),
+            Dynamic::BusinessCollection(id, collection) => {
+                BUSINESS_COLLECTIONS.iter().any(|(c, t)| c == collection && *t == event.memory_type) && owned_by_business(id)
+            }
+            Dynamic::CustomerProfile(id) => match event.customer_id.as_deref() {
+                Some(customer) => customer == id,
+                None => CUSTOMER_TYPES.contains(&event.memory_type.as_str()),
+            },
+        }
+    }
+}
+
+/// URI templates for `resources/templates/list`
+pub fn templates() -> Vec<RawResourceTemplate> {
+    let template = |uri_template: &str, name: &str, description: &str| RawResourceTemplate {
+        uri_template: uri_template.to_string(),
+        name: name.to_string(),
+        title: None,
+        description: Some(description.to_string()),
+        mime_type: Some("application/json".to_string()),
+    };
+    let collections: Vec<&str> = BUSINESS_COLLECTIONS.iter().map(|(c, _)| *c).collect();
+    vec![
+        template("memory://business/{business_id}/summary", "Business summary", "Stored memories per type for a business"),
+        template(
+            "memory://business/{business_id}/{collection}",
+            "Business collection",
+            &format!("One memory type for a business; collection is one of: {}", collections.join(", ")),
+        ),
+        template("memory://customer/{customer_id}/profile", "Customer profile", "Memories, interaction totals and feedback for a customer"),
+    ]
+}
+
+/// A session's resource subscriptions; each follows the change feed until
+/// unsubscribed, the session ends or the client stops accepting notifications
+#[derive(Default)]
+pub struct Subscriptions {
+    watchers: Mutex<HashMap<String, AbortHandle>>,
+}
+
+impl Subscriptions {
+    pub fn subscribe(&self, resource: Dynamic, feed: &ChangeFeed, peer: Peer<RoleServer>) {
+        let uri = resource.uri();
+        let mut events = feed.subscribe();
+        let notified_uri = uri.clone();
+        let watcher = tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) if resource.affected_by(&event) => {}
+                    Ok(_) => continue,
+                    // Missed events may have touched the resource
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+                let update = ResourceUpdatedNotificationParam { uri: notified_uri.clone() };
+                if peer.notify_resource_updated(update).await.is_err() {
+                    break;
+                }
+            }
+        });
+        if let Some(previous) = self.watchers.lock().unwrap().insert(uri, watcher.abort_handle()) {
+            previous.abort();
+        }
+    }
+
+    pub fn unsubscribe(&self, uri: &str) -> bool {
+        let removed = Dynamic::parse(uri).and_then(|r| self.watchers.lock().unwrap().remove(&r.uri()));
+        removed.map(|watcher| watcher.abort()).is_some()
+    }
+}
+
+impl Drop for Subscriptions {
+    fn drop(&mut self) {
+        for watcher in self.watchers.lock().unwrap().values() {
+            watcher.abort();
+        }
+    }
+}
+
+/// Businesses and customers from recent access log entries (newest first), without repeats
+pub fn recent_subjects(entries: &[AccessEntry], limit: usize) -> Vec<Dynamic> {
+    let mut subjects: Vec<Dynamic> = Vec::new();
+    for entry in entries {
+        let candidates = [
+            entry.business_id.clone().map(Dynamic::BusinessSummary),
+            entry.customer_id.clone().map(Dynamic::CustomerProfile),
+        ];
+        for subject in candidates.into_iter().flatten() {
+            if subjects.len() < limit && !subjects.contains(&subject) {
+                subjects.push(subject);
+            }
+        }
+    }
+    subjects
+}
+
+/// Short human-readable label for a memory item
+fn label(item: &Value) -> Option<String> {
+    let object = item.as_object()?;
+    let named = object
+        .iter()
+        .find(|(key, value)| key.ends_with("_name") && value.as_str().is_some_and(|v| !v.is_empty()))
+        .and_then(|(_, value)| value.as_str());
+    let text = named.or_else(|| {
+        LABEL_FIELDS
+            .iter()
+            .find_map(|field| object.get(*field).and_then(|v| v.as_str()).filter(|v| !v.is_empty()))
+    })?;
+    Some(match text.char_indices().nth(MAX_LABEL_CHARS) {
+        Some((cut, _)) => format!("{}...", &text[..cut]),
+        None => text.to_string(),
+    })
+}
+
+/// Count and name the items of each memory type in a `{type: query_result}` object
+pub fn summarize_types(memories: &Value) -> Value {
+    let mut types = Map::new();
+    let mut total = 0;
+    for (memory_type, result) in memories.as_object().into_iter().flatten() {
+        let items = HelixClient::result_items(result);
+        total += items.len();
+        let names: Vec<String> = items.iter().filter_map(label).take(MAX_NAMED_ITEMS).collect();
+        types.insert(memory_type.clone(), json!({"count": items.len(), "items": names}));
+    }
+    json!({"total": total, "types": types})
+}
+
+pub fn business_summary(business_id: &str, memories: &Value, generated_at: i64) -> Value {
+    json!({
+        "business_id": business_id,
+        "generated_at": generated_at,
+        "memories": summarize_types(memories)
+    })
+}
+
+/// `interactions` and `feedback` are the `analyze_interactions` and `summarize_feedback` results
+pub fn customer_profile(customer_id: &str, memories: &Value, interactions: Value, feedback: Value, generated_at: i64) -> Value {
+    json!({
+        "customer_id": customer_id,
+        "generated_at": generated_at,
+        "memories": summarize_types(memories),
+        "interactions": interactions,
+        "feedback": feedback
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_summarize() {
+        let summary = Dynamic::parse("memory://business/B1/summary").unwrap();
+        assert_eq!(summary, Dynamic::BusinessSummary("B1".to_string()));
+        assert_eq!(summary.uri(), "memory://business/B1/summary");
+        assert_eq!(Dynamic::parse("memory://customer/C 7/profile"), Some(Dynamic::CustomerProfile("C 7".to_string())));
+        assert_eq!(Dynamic::parse("memory://customer//profile"), None);
+        assert_eq!(Dynamic::parse("meta://about"), None);
+        assert_eq!(Dynamic::parse("memory://business/B1/widgets"), None);
+
+        let memories = json!({
+            "products": {"products": [{"product_name": "Espresso"}, {"product_name": ""}]},
+            "preferences": {"preferences": [{"subject": "oat milk"}]},
+            "events": {"events": []}
+        });
+        let summary = summarize_types(&memories);
+        assert_eq!(summary["total"], 3);
+        assert_eq!(summary["types"]["products"]["items"], json!(["Espresso"]));
+        assert_eq!(summary["types"]["preferences"]["items"], json!(["oat milk"]));
+        assert_eq!(summary["types"]["events"]["count"], 0);
+    }
+
+    #[test]
+    fn test_affected_by() {
+        let event = |memory_type: &str, business_id: Option<&str>, customer_id: Option<&str>| ChangeEvent {
+            id: "e".to_string(),
+            memory_type: memory_type.to_string(),
+            operation: "updated".to_string(),
+            memory_ids: Vec::new(),
+            business_id: business_id.map(str::to_string),
+            customer_id: customer_id.map(str::to_string),
+            tool: "update_business_memory".to_string(),
+            timestamp: 0,
+            alert: None,
+        };
+        let products = Dynamic::parse("memory://business/B1/products").unwrap();
+        assert!(products.affected_by(&event("product", Some("B1"), None)));
+        assert!(!products.affected_by(&event("product", Some("B2"), None)));
+        assert!(!products.affected_by(&event("service", Some("B1"), None)));
+        assert!(products.affected_by(&event("product", None, None)));
+
+        let profile = Dynamic::CustomerProfile("C1".to_string());
+        assert!(profile.affected_by(&event("interaction", Some("B1"), Some("C1"))));
+        assert!(!profile.affected_by(&event("preference", None, Some("C2"))));
+        assert!(profile.affected_by(&event("preference", None, None)));
+        assert!(!profile.affected_by(&event("product", None, None)));
+    }
+}
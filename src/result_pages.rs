@@ -0,0 +1,192 @@
+//! Paginated spill of oversized tool results
+//!
+//! A tool result larger than `[server] max_response_bytes` is not sent
+//! whole. Its largest array (e.g. `results`, `memories`) is split into
+//! pages that fit the limit; the first page goes out in place of the full
+//! result with a `pagination` cursor, and `fetch_result_page` returns the
+//! rest. Results without a large array are split as JSON text instead.
+//! Cursors carry the spilled result's random ID, so they work across HTTP
+//! requests (each its own session); pages expire after a TTL.
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Room left for the `pagination` block and the page's own framing
+const PAGE_OVERHEAD: usize = 512;
+
+/// Path (object keys) to the array holding the most serialized bytes
+fn largest_array(value: &Value) -> Option<(Vec<String>, usize)> {
+    let Value::Object(map) = value else {
+        return None;
+    };
+    map.iter()
+        .filter_map(|(key, field)| match field {
+            Value::Array(items) if !items.is_empty() => Some((vec![key.clone()], field.to_string().len().max(items.len()))),
+            Value::Object(_) => largest_array(field).map(|(mut path, size)| {
+                path.insert(0, key.clone());
+                (path, size)
+            }),
+            _ => None,
+        })
+        .max_by_key(|(_, size)| *size)
+}
+
+fn array_at<'a>(value: &'a mut Value, path: &[String]) -> Option<&'a mut Vec<Value>> {
+    path.iter().try_fold(value, |v, key| v.get_mut(key))?.as_array_mut()
+}
+
+/// Split `result` into pages of at most about `max_bytes` serialized each
+pub fn paginate(result: &Value, max_bytes: usize) -> Vec<Value> {
+    if let Some((path, _)) = largest_array(result) {
+        let mut skeleton = result.clone();
+        let items = std::mem::take(array_at(&mut skeleton, &path).unwrap());
+        let budget = max_bytes.saturating_sub(skeleton.to_string().len() + PAGE_OVERHEAD);
+        // Only worth it if the rest of the result leaves room for items
+        if budget > max_bytes / 4 {
+            let mut pages = Vec::new();
+            let mut current: Vec<Value> = Vec::new();
+            let mut size = 0;
+            for item in items {
+                let item_size = item.to_string().len() + 1;
+                if !current.is_empty() && size + item_size > budget {
+                    pages.push(std::mem::take(&mut current));
+                    size = 0;
+                }
+                size += item_size;
+                current.push(item);
+            }
+            pages.push(current);
+            let total_items: usize = pages.iter().map(Vec::len).sum();
+            let array_path = path.join(".");
+            let mut offset = 0;
+            return pages
+                .into_iter()
+                .map(|page_items| {
+                    let mut page = skeleton.clone();
+                    let count = page_items.len();
+                    *array_at(&mut page, &path).unwrap() = page_items;
+                    page["pagination"] = json!({"array": array_path, "offset": offset, "count": count, "total_items": total_items});
+                    offset += count;
+                    page
+                })
+                .collect();
+        }
+    }
+
+    // No array to split: send the JSON text in parts to be joined by the client
+    let text = result.to_string();
+    let chunk = max_bytes.saturating_sub(PAGE_OVERHEAD).max(1);
+    let mut parts = Vec::new();
+    let mut rest = text.as_str();
+    while !rest.is_empty() {
+        let mut end = chunk.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (part, tail) = rest.split_at(end);
+        parts.push(part.to_string());
+        rest = tail;
+    }
+    let total = parts.len();
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(i, part)| json!({"json_text": part, "pagination": {"part": i + 1, "parts": total, "format": "json_text"}}))
+        .collect()
+}
+
+struct Spilled {
+    pages: Vec<Value>,
+    created: Instant,
+}
+
+/// Spilled results by result ID
+pub struct ResultPages {
+    results: Mutex<HashMap<String, Spilled>>,
+    ttl: Duration,
+}
+
+fn cursor(result_id: &str, page: usize) -> String {
+    format!("{}:{}", result_id, page)
+}
+
+impl ResultPages {
+    pub fn new(ttl: Duration) -> Self {
+        ResultPages { results: Mutex::new(HashMap::new()), ttl }
+    }
+
+    fn with_cursor(mut page: Value, result_id: &str, index: usize, total: usize) -> Value {
+        page["pagination"]["page"] = json!(index + 1);
+        page["pagination"]["pages"] = json!(total);
+        page["pagination"]["has_more"] = json!(index + 1 < total);
+        if index + 1 < total {
+            page["pagination"]["next_cursor"] = json!(cursor(result_id, index + 1));
+            page["pagination"]["next_step"] = json!("Call fetch_result_page with next_cursor for the rest of this result");
+        }
+        page
+    }
+
+    /// Keep `pages` and return the first one
+    pub fn spill(&self, result_id: &str, mut pages: Vec<Value>) -> Value {
+        let total = pages.len();
+        let first = pages.remove(0);
+        let mut results = self.results.lock().unwrap();
+        results.retain(|_, r| r.created.elapsed() < self.ttl);
+        // Page 1 is already delivered; keep a placeholder so indexes stay aligned
+        pages.insert(0, Value::Null);
+        results.insert(result_id.to_string(), Spilled { pages, created: Instant::now() });
+        Self::with_cursor(first, result_id, 0, total)
+    }
+
+    /// The page a cursor points at
+    pub fn fetch(&self, page_cursor: &str) -> Result<Value, String> {
+        let (result_id, index) = page_cursor
+            .rsplit_once(':')
+            .and_then(|(id, page)| Some((id, page.parse::<usize>().ok()?)))
+            .ok_or_else(|| format!("Invalid cursor: {}", page_cursor))?;
+        let results = self.results.lock().unwrap();
+        let spilled = results
+            .get(result_id)
+            .filter(|r| r.created.elapsed() < self.ttl)
+            .ok_or_else(|| format!("Result pages not found or expired for cursor {}", page_cursor))?;
+        match spilled.pages.get(index) {
+            Some(page) if index > 0 => Ok(Self::with_cursor(page.clone(), result_id, index, spilled.pages.len())),
+            _ => Err(format!("Invalid cursor: {} (page out of range)", page_cursor)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate_and_fetch() {
+        let items: Vec<Value> = (0..100).map(|i| json!({"id": i, "text": "x".repeat(50)})).collect();
+        let result = json!({"count": 100, "data": {"results": items}});
+        let pages = paginate(&result, 2000);
+        assert!(pages.len() > 2);
+        assert!(pages.iter().all(|p| p.to_string().len() <= 2000));
+        assert_eq!(pages[0]["count"], 100);
+        assert_eq!(pages[0]["pagination"]["array"], "data.results");
+        let joined: Vec<Value> = pages.iter().flat_map(|p| p["data"]["results"].as_array().unwrap().clone()).collect();
+        assert_eq!(joined.len(), 100);
+        assert_eq!(joined[99]["id"], 99);
+
+        let store = ResultPages::new(Duration::from_secs(60));
+        let first = store.spill("r1", pages.clone());
+        assert_eq!(first["pagination"]["next_cursor"], "r1:1");
+        let second = store.fetch("r1:1").unwrap();
+        assert_eq!(second["data"]["results"], pages[1]["data"]["results"]);
+        assert!(store.fetch("r2:1").is_err());
+        assert!(store.fetch("r1:0").is_err());
+
+        // A long string is split as JSON text
+        let text = json!({"report": "y".repeat(5000)});
+        let parts = paginate(&text, 2000);
+        let joined: String = parts.iter().map(|p| p["json_text"].as_str().unwrap()).collect();
+        assert_eq!(serde_json::from_str::<Value>(&joined).unwrap(), text);
+    }
+}
@@ -0,0 +1,136 @@
+//! Turning direction paths into step lists
+//!
+//! A `DirectionPath` reaches its waypoints through `PathThroughWaypoint`
+//! edges carrying `sequence_order` and `is_optional`. `get_route` orders the
+//! waypoints by those edges and describes each leg from the waypoint's
+//! compass bearing/direction and distance, splitting the path's estimated
+//! duration across legs by distance when the path has one.
+
+use crate::geo;
+use crate::helix_client::HelixClient;
+use serde_json::{json, Value};
+
+/// Waypoints of a `get_path_route` result in path order, as (sequence_order, is_optional, waypoint);
+/// waypoints without an edge go last
+pub fn waypoints(route: &Value, include_optional: bool) -> Vec<(i64, bool, Value)> {
+    let edges = HelixClient::result_items(&route["edges"]);
+    let mut waypoints: Vec<(i64, bool, Value)> = HelixClient::result_items(&route["waypoints"])
+        .into_iter()
+        .map(|waypoint| {
+            let edge = edges.iter().find(|e| e.get("to_node").is_some() && e.get("to_node") == waypoint.get("id"));
+            let order = edge.and_then(|e| e.get("sequence_order")).and_then(|v| v.as_i64()).unwrap_or(i64::MAX);
+            let optional = edge.and_then(|e| e.get("is_optional")).and_then(|v| v.as_bool()).unwrap_or(false);
+            (order, optional, waypoint)
+        })
+        .filter(|(_, optional, _)| !optional || include_optional)
+        .collect();
+    waypoints.sort_by_key(|(order, _, _)| *order);
+    waypoints
+}
+
+/// Best active path for the request: recommended first, then the shortest estimated duration
+pub fn best_path<'a>(paths: &'a [Value], transport_mode: Option<&str>, accessible_only: bool) -> Option<&'a Value> {
+    paths
+        .iter()
+        .filter(|path| path.get("is_active").and_then(|v| v.as_bool()).unwrap_or(true))
+        .filter(|path| !accessible_only || path.get("suitable_for_mobility_aids").and_then(|v| v.as_bool()) == Some(true))
+        .filter(|path| transport_mode.is_none_or(|mode| path.get("transport_mode").and_then(|v| v.as_str()) == Some(mode)))
+        .min_by_key(|path| {
+            (
+                path.get("is_recommended").and_then(|v| v.as_bool()) != Some(true),
+                path.get("estimated_duration_minutes").and_then(|v| v.as_i64()).filter(|m| *m > 0).unwrap_or(i64::MAX),
+            )
+        })
+}
+
+/// Minutes for one leg: its share of the path's duration, else distance at `speed` m/s
+pub fn leg_minutes(distance: f64, total_distance: f64, path_minutes: Option<f64>, speed: f64) -> f64 {
+    match path_minutes {
+        Some(total) if total_distance > 0.0 => total * distance / total_distance,
+        _ => distance / speed / 60.0,
+    }
+}
+
+/// Step `index` (0-based) walking `distance` meters to `waypoint`
+pub fn step(index: usize, waypoint: &Value, optional: bool, distance: f64, minutes: f64) -> Value {
+    let bearing = waypoint.get("compass_bearing").and_then(|v| v.as_f64()).filter(|b| *b > 0.0);
+    let direction = waypoint
+        .get("compass_direction")
+        .and_then(|v| v.as_str())
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+        .or_else(|| bearing.map(|b| geo::compass_point(b).to_string()));
+    let name = waypoint.get("waypoint_name").and_then(|v| v.as_str()).unwrap_or("next waypoint");
+    let instruction = match (&direction, distance > 0.0) {
+        (Some(direction), true) => format!("Go {} for about {} to {}", direction, geo::format_distance(distance), name),
+        (Some(direction), false) => format!("Head {} to {}", direction, name),
+        (None, _) => format!("Continue to {}", name),
+    };
+
+    json!({
+        "step": index + 1,
+        "waypoint_id": waypoint.get("waypoint_id"),
+        "waypoint_name": name,
+        "instruction": instruction,
+        "compass_direction": direction,
+        "compass_bearing": bearing,
+        "distance_meters": distance,
+        "estimated_minutes": (minutes * 10.0).round() / 10.0,
+        "floor_level": waypoint.get("floor_level"),
+        "visual_cues": waypoint.get("visual_cues"),
+        "accessibility_info": waypoint.get("accessibility_info"),
+        "optional": optional
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_waypoints_in_path_order() {
+        let route = json!({
+            "waypoints": [{"id": "n3", "waypoint_id": "W3"}, {"id": "n1", "waypoint_id": "W1"}, {"id": "n2", "waypoint_id": "W2"}],
+            "edges": [
+                {"to_node": "n1", "sequence_order": 1},
+                {"to_node": "n2", "sequence_order": 2, "is_optional": true},
+                {"to_node": "n3", "sequence_order": 3}
+            ]
+        });
+        let ids = |include_optional| waypoints(&route, include_optional).into_iter().map(|(_, _, w)| w["waypoint_id"].clone()).collect::<Vec<_>>();
+        assert_eq!(ids(true), ["W1", "W2", "W3"]);
+        assert_eq!(ids(false), ["W1", "W3"]);
+    }
+
+    #[test]
+    fn test_best_path() {
+        let paths = vec![
+            json!({"path_id": "slow", "transport_mode": "walking", "estimated_duration_minutes": 12}),
+            json!({"path_id": "fast", "transport_mode": "walking", "estimated_duration_minutes": 5}),
+            json!({"path_id": "closed", "transport_mode": "walking", "is_active": false, "is_recommended": true}),
+            json!({"path_id": "ramp", "transport_mode": "walking", "estimated_duration_minutes": 9, "suitable_for_mobility_aids": true}),
+            json!({"path_id": "drive", "transport_mode": "driving", "is_recommended": true}),
+        ];
+        let best = |mode, accessible| best_path(&paths, mode, accessible).map(|p| p["path_id"].clone());
+        assert_eq!(best(None, false).unwrap(), "drive");
+        assert_eq!(best(Some("walking"), false).unwrap(), "fast");
+        assert_eq!(best(Some("walking"), true).unwrap(), "ramp");
+        assert!(best(Some("cycling"), false).is_none());
+    }
+
+    #[test]
+    fn test_step_and_leg_minutes() {
+        assert_eq!(leg_minutes(100.0, 400.0, Some(8.0), 1.4), 2.0);
+        assert!((leg_minutes(84.0, 0.0, None, 1.4) - 1.0).abs() < 1e-9);
+
+        let waypoint = json!({"waypoint_id": "W1", "waypoint_name": "Food court", "compass_direction": "north", "floor_level": 1});
+        let step = step(0, &waypoint, false, 120.0, 1.44);
+        assert_eq!(step["step"], 1);
+        assert_eq!(step["estimated_minutes"], 1.4);
+        assert!(step["instruction"].as_str().unwrap().starts_with("Go north for about"));
+
+        let unnamed = super::step(1, &json!({"waypoint_id": "W2"}), true, 0.0, 0.0);
+        assert_eq!(unnamed["instruction"], "Continue to next waypoint");
+        assert_eq!(unnamed["optional"], true);
+    }
+}
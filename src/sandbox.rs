@@ -0,0 +1,254 @@
+//! Sandbox (shadow tenant) sessions
+//!
+//! While a session is sandboxed, every business_id/customer_id written by a
+//! mutating tool is rewritten into a per-session namespace
+//! (`sandbox-<tag>-<id>`), mutations may only target nodes the sandbox
+//! created, and bulk maintenance is refused. Production data stays readable.
+//! Sandboxes are discarded (their tenants deleted) once their TTL elapses.
+
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub const PREFIX: &str = "sandbox-";
+
+/// Owner ID fields rewritten into the sandbox namespace
+const OWNER_FIELDS: &[&str] = &["business_id", "customer_id", "target_business_id"];
+
+/// Arguments naming existing nodes that a sandboxed mutation must own
+const TARGET_FIELDS: &[&str] = &["memory_id", "memory_ids", "interaction_id", "from_info_id", "to_info_id"];
+
+/// How a tool is treated inside a sandbox
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// Reads run unchanged against production data
+    Read,
+    /// Creates are namespaced and their IDs recorded as sandbox-owned
+    Create,
+    /// Mutations of existing nodes must target sandbox-owned IDs
+    Targeted,
+    /// Bulk or raw mutations are refused
+    Blocked,
+}
+
+pub fn access(tool: &str, arguments: Option<&Map<String, Value>>) -> Access {
+    match tool {
+        "create_business_memory" | "create_customer_memory" | "store_conversation_summary"
+        | "create_customer_product_interaction" | "create_customer_service_interaction"
+        | "create_navigation_hub" | "create_navigation_waypoint" | "create_direction_path"
+        | "import_navigation_geojson" | "create_navigation_floor" | "create_floor_connector"
+        | "supersede_policy" | "upsert_business_memory" | "import_memories" | "onboard_business"
+        | "clone_business_memories" | "set_loyalty_rule" | "redeem_points" | "import_events_ics" => Access::Create,
+        "update_business_memory" | "update_customer_memory" | "update_interaction" | "adjust_product_stock"
+        | "update_navigation" | "update_business_hours_exceptions" | "close_past_events" | "delete_memory" | "merge_memories"
+        | "create_information_relationships" | "forget_customer" | "resolve_issue" | "pin_memory" => Access::Targeted,
+        "backfill_embeddings" | "apply_decay" | "set_embedding_metadata"
+        | "delete_memories_by_filter" | "submit_job" | "repair_graph" | "restore_snapshot"
+        | "merge_customers" | "sync_shopify_catalog" | "create_alert_rule" => Access::Blocked,
+        "do_query" => {
+            let endpoint = arguments
+                .and_then(|a| a.get("endpoint"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            if endpoint.starts_with("get_") || endpoint.starts_with("search_") || endpoint.starts_with("preview_") {
+                Access::Read
+            } else {
+                Access::Blocked
+            }
+        }
+        _ => Access::Read,
+    }
+}
+
+/// Shadow tenant state for one session
+pub struct Tenant {
+    pub tag: String,
+    created: Instant,
+    owned: HashSet<String>,
+    pub business_ids: HashSet<String>,
+    pub customer_ids: HashSet<String>,
+}
+
+impl Tenant {
+    fn new() -> Self {
+        Tenant {
+            tag: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            created: Instant::now(),
+            owned: HashSet::new(),
+            business_ids: HashSet::new(),
+            customer_ids: HashSet::new(),
+        }
+    }
+
+    fn namespaced(&self, id: &str) -> String {
+        if id.starts_with(PREFIX) {
+            id.to_string()
+        } else {
+            format!("{}{}-{}", PREFIX, self.tag, id)
+        }
+    }
+
+    /// Rewrite owner IDs in `value` (recursively) into this tenant's namespace
+    fn namespace(&mut self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, field) in map.iter_mut() {
+                    match field {
+                        Value::String(id) if OWNER_FIELDS.contains(&key.as_str()) && !id.is_empty() => {
+                            *id = self.namespaced(id);
+                            if key == "customer_id" {
+                                self.customer_ids.insert(id.clone());
+                            } else {
+                                self.business_ids.insert(id.clone());
+                            }
+                        }
+                        _ => self.namespace(field),
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.namespace(item)),
+            _ => {}
+        }
+    }
+
+    /// Record every `id`/`*_id` string in a create result as sandbox-owned
+    fn record(&mut self, value: &Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, field) in map {
+                    match field {
+                        Value::String(id) if key == "id" || key.ends_with("_id") => {
+                            self.owned.insert(id.clone());
+                        }
+                        _ => self.record(field),
+                    }
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|item| self.record(item)),
+            _ => {}
+        }
+    }
+
+    /// First targeted ID in `arguments` that this sandbox did not create
+    fn foreign_target(&self, arguments: &Map<String, Value>) -> Option<String> {
+        TARGET_FIELDS
+            .iter()
+            .filter_map(|field| arguments.get(*field))
+            .flat_map(|v| match v {
+                Value::Array(items) => items.clone(),
+                other => vec![other.clone()],
+            })
+            .filter_map(|v| v.as_str().map(String::from))
+            .find(|id| !id.starts_with(PREFIX) && !self.owned.contains(id))
+    }
+}
+
+/// Sandboxed sessions by session ID
+pub struct SandboxRegistry {
+    tenants: Mutex<HashMap<String, Tenant>>,
+    ttl: Duration,
+}
+
+impl SandboxRegistry {
+    pub fn new(ttl: Duration) -> Self {
+        SandboxRegistry { tenants: Mutex::new(HashMap::new()), ttl }
+    }
+
+    /// Put a session in sandbox mode; returns its namespace tag
+    pub fn enable(&self, session_id: &str) -> String {
+        let mut tenants = self.tenants.lock().unwrap();
+        tenants.entry(session_id.to_string()).or_insert_with(Tenant::new).tag.clone()
+    }
+
+    /// Leave sandbox mode, returning the tenant so its data can be discarded
+    pub fn disable(&self, session_id: &str) -> Option<Tenant> {
+        self.tenants.lock().unwrap().remove(session_id)
+    }
+
+    pub fn is_sandboxed(&self, session_id: &str) -> bool {
+        self.tenants.lock().unwrap().contains_key(session_id)
+    }
+
+    /// Prepare a sandboxed call: namespace owner IDs, then check targets.
+    /// `Err` carries the reason the call is refused.
+    pub fn prepare(&self, session_id: &str, access: Access, arguments: &mut Map<String, Value>) -> Result<(), String> {
+        let mut tenants = self.tenants.lock().unwrap();
+        let Some(tenant) = tenants.get_mut(session_id) else {
+            return Ok(());
+        };
+
+        match access {
+            Access::Read => Ok(()),
+            Access::Blocked => Err("This tool can modify production data and is disabled in sandbox mode".to_string()),
+            Access::Create | Access::Targeted => {
+                let mut value = Value::Object(std::mem::take(arguments));
+                tenant.namespace(&mut value);
+                if let Value::Object(map) = value {
+                    *arguments = map;
+                }
+                match tenant.foreign_target(arguments) {
+                    Some(id) if access == Access::Targeted => Err(format!(
+                        "'{}' was not created in this sandbox; sandboxed sessions can only modify their own memories",
+                        id
+                    )),
+                    _ => Ok(()),
+                }
+            }
+        }
+    }
+
+    pub fn record(&self, session_id: &str, result: &Value) {
+        if let Some(tenant) = self.tenants.lock().unwrap().get_mut(session_id) {
+            tenant.record(result);
+        }
+    }
+
+    /// Remove and return sandboxes older than the TTL
+    pub fn take_expired(&self) -> Vec<Tenant> {
+        let mut tenants = self.tenants.lock().unwrap();
+        let expired: Vec<String> = tenants
+            .iter()
+            .filter(|(_, t)| t.created.elapsed() >= self.ttl)
+            .map(|(session, _)| session.clone())
+            .collect();
+        expired.iter().filter_map(|session| tenants.remove(session)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_namespacing_and_ownership() {
+        let registry = SandboxRegistry::new(Duration::from_secs(60));
+        let tag = registry.enable("s1");
+
+        let mut create = json!({"business_id": "B1", "data": {"customer_id": "C1"}}).as_object().unwrap().clone();
+        registry.prepare("s1", Access::Create, &mut create).unwrap();
+        assert_eq!(create["business_id"], format!("sandbox-{}-B1", tag));
+        assert_eq!(create["data"]["customer_id"], format!("sandbox-{}-C1", tag));
+        registry.record("s1", &json!({"result": {"product": {"id": "node-1", "product_id": "P1"}}}));
+
+        let mut update = json!({"memory_id": "node-1"}).as_object().unwrap().clone();
+        assert!(registry.prepare("s1", Access::Targeted, &mut update).is_ok());
+        let mut foreign = json!({"memory_ids": ["P1", "P-prod"]}).as_object().unwrap().clone();
+        assert!(registry.prepare("s1", Access::Targeted, &mut foreign).is_err());
+        assert!(registry.prepare("s1", Access::Blocked, &mut Map::new()).is_err());
+
+        // Sessions without a sandbox are untouched
+        let mut other = json!({"business_id": "B1"}).as_object().unwrap().clone();
+        registry.prepare("s2", Access::Create, &mut other).unwrap();
+        assert_eq!(other["business_id"], "B1");
+    }
+
+    #[test]
+    fn test_expiry() {
+        let registry = SandboxRegistry::new(Duration::ZERO);
+        registry.enable("s1");
+        assert_eq!(registry.take_expired().len(), 1);
+        assert!(registry.disable("s1").is_none());
+    }
+}
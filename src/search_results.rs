@@ -0,0 +1,111 @@
+//! Merging semantic search results from several memory types
+//!
+//! Each memory type is searched by its own query route, so one node can come
+//! back more than once (e.g. `["product", "products"]`). Results are tagged
+//! with the memory type they were found under, deduplicated by node ID and
+//! returned grouped per type with counts, in ranking order within each group.
+//! `search_semantic_batch` returns one such grouping per query.
+
+use serde_json::{json, Map, Value};
+
+/// Tag each result with the memory type whose query route returned it
+pub fn tag(results: Vec<Value>, memory_type: &str) -> Vec<Value> {
+    results
+        .into_iter()
+        .map(|mut result| {
+            if let Some(fields) = result.as_object_mut() {
+                fields.entry("memory_type").or_insert_with(|| json!(memory_type));
+            }
+            result
+        })
+        .collect()
+}
+
+/// Drop repeated nodes (by HelixDB `id`, else by content), keeping the
+/// best-scored copy in its first position; returns the number dropped
+pub fn dedup(results: &mut Vec<Value>) -> usize {
+    let key = |result: &Value| match result.get("id") {
+        Some(id) => id.to_string(),
+        None => result.to_string(),
+    };
+    let score = |result: &Value| result.get("score").and_then(|s| s.as_f64()).unwrap_or(f64::MIN);
+    let before = results.len();
+    let mut unique: Vec<Value> = Vec::with_capacity(before);
+    for result in results.drain(..) {
+        match unique.iter_mut().find(|kept| key(kept) == key(&result)) {
+            Some(kept) if score(&result) > score(kept) => *kept = result,
+            Some(_) => {}
+            None => unique.push(result),
+        }
+    }
+    *results = unique;
+    before - results.len()
+}
+
+/// Results grouped by `memory_type`: `{type: {count, results}}`
+pub fn group(results: Vec<Value>) -> Value {
+    let mut groups: Map<String, Value> = Map::new();
+    for result in results {
+        let memory_type = result.get("memory_type").and_then(|t| t.as_str()).unwrap_or("unknown").to_string();
+        let entry = groups.entry(memory_type).or_insert_with(|| json!({"count": 0, "results": []}));
+        entry["count"] = json!(entry["count"].as_u64().unwrap_or(0) + 1);
+        if let Some(members) = entry["results"].as_array_mut() {
+            members.push(result);
+        }
+    }
+    Value::Object(groups)
+}
+
+/// Queries of a batch search, trimmed and without blanks; between 1 and `max`
+pub fn batch_queries(queries: &[String], max: usize) -> Result<Vec<String>, String> {
+    let queries: Vec<String> = queries.iter().map(|q| q.trim().to_string()).filter(|q| !q.is_empty()).collect();
+    if queries.is_empty() || queries.len() > max {
+        return Err(format!("queries must contain between 1 and {} non-empty queries, got {}", max, queries.len()));
+    }
+    Ok(queries)
+}
+
+/// One query's entry in a batch search response
+pub fn batch_entry(query: &str, results: Vec<Value>, rerank: Value) -> Value {
+    json!({
+        "query": query,
+        "total_results": results.len(),
+        "rerank": rerank,
+        "results": group(results)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup_and_group() {
+        let mut results = tag(vec![json!({"id": "p1", "score": 0.7}), json!({"id": "p2", "score": 0.6})], "products");
+        results.extend(tag(vec![json!({"id": "p1", "score": 0.9})], "products"));
+        results.extend(tag(vec![json!({"id": "r1", "score": 0.8})], "rules"));
+
+        assert_eq!(dedup(&mut results), 1);
+        assert_eq!(results[0]["score"], 0.9);
+
+        let grouped = group(results);
+        assert_eq!(grouped["products"]["count"], 2);
+        assert_eq!(grouped["products"]["results"][0]["id"], "p1");
+        assert_eq!(grouped["rules"]["results"][0]["id"], "r1");
+    }
+
+    #[test]
+    fn test_batch_queries_and_entry() {
+        let queries = vec![" oat latte ".to_string(), "  ".to_string(), "cold brew".to_string()];
+        assert_eq!(batch_queries(&queries, 20).unwrap(), ["oat latte", "cold brew"]);
+        assert_eq!(batch_queries(&queries, 1).unwrap_err(), "queries must contain between 1 and 1 non-empty queries, got 2");
+        assert!(batch_queries(&[" ".to_string()], 20).is_err());
+
+        let results = tag(vec![json!({"id": "p1"}), json!({"id": "p2"})], "products");
+        let entry = batch_entry("oat latte", results, json!({"applied": false}));
+        assert_eq!(entry["query"], "oat latte");
+        assert_eq!(entry["total_results"], 2);
+        assert_eq!(entry["results"]["products"]["count"], 2);
+        assert_eq!(entry["rerank"]["applied"], false);
+    }
+}
@@ -1,29 +1,269 @@
-/// HTTP Transport Server for MCP using RMCP's StreamableHttpService
-/// 
-/// This module provides an HTTP server that uses RMCP's built-in streamable HTTP
-/// transport, which provides full MCP protocol support including all tools and resources.
-/// 
-/// The server uses Hyper + Tower for HTTP handling and RMCP's StreamableHttpService
-/// for the MCP protocol layer. This provides:
-/// - Full JSON-RPC 2.0 protocol over HTTP
-/// - Server-Sent Events (SSE) for streaming responses
-/// - Complete tool and resource access (same as TCP/STDIO)
-/// - Session management for stateful connections
-/// - CORS headers for browser access
+//! HTTP Transport Server for MCP using RMCP's StreamableHttpService
+//! 
+//! This module provides an HTTP server that uses RMCP's built-in streamable HTTP
+//! transport, which provides full MCP protocol support including all tools and resources.
+//! 
+//! The server uses Hyper + Tower for HTTP handling and RMCP's StreamableHttpService
+//! for the MCP protocol layer. This provides:
+//! - Full JSON-RPC 2.0 protocol over HTTP
+//! - Server-Sent Events (SSE) for streaming responses
+//! - Complete tool and resource access (same as TCP/STDIO)
+//! - Session management for stateful connections
+//! - CORS headers for browser access
+//!
+//! It also accepts live location fixes for location sharing sessions on
+//! `POST /location/<share_id>` (see `start_location_sharing`), streams
+//! memory change events as Server-Sent Events on `GET /events`
+//! (`?business_id=` to filter), and takes signed external events on
+//! `POST /ingest` (see `ingest`). MCP and ingest request bodies over
+//! `[server] max_request_bytes` are refused with 413.
+//!
+//! Both `/ingest` and `/events` need `[ingest] signing_secret`: ingest
+//! requests must be signed with it and `/events` clients send it as a
+//! bearer token. Until it is set both answer 503.
 
 use anyhow::Result;
+use http_body_util::{BodyExt, Full, Limited, combinators::BoxBody};
+use hyper::body::{Body, Bytes, Frame, Incoming};
 use hyper::server::conn::http1;
+use hyper::{Method, Request, Response, StatusCode};
 use hyper::service::service_fn;
 use hyper_util::rt::TokioIo;
 use rmcp::transport::streamable_http_server::{StreamableHttpService, StreamableHttpServerConfig};
 use rmcp::transport::streamable_http_server::session::never::NeverSessionManager;
+use std::convert::Infallible;
 use std::net::SocketAddr;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
 use tokio::net::TcpListener;
+use tokio::sync::{broadcast, mpsc};
 use tower_service::Service;
-use tracing::{info, error};
+use tracing::{info, error, warn};
+
+use crate::{HelixMcpServer, change_feed, clock::Clock, config::ServerConfig, idempotency, ingest, live, priority, signature};
+
+/// Largest accepted location fix body
+const MAX_LOCATION_BODY: usize = 4096;
+
+fn json_response(status: StatusCode, body: serde_json::Value) -> Response<BoxBody<Bytes, Infallible>> {
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(body.to_string())).boxed())
+        .unwrap_or_default()
+}
+
+/// Handle `POST /location/<share_id>` with a bearer push token and a JSON fix
+async fn push_location(
+    sessions: &live::LiveSessions,
+    clock: &dyn Clock,
+    share_id: &str,
+    request: Request<Incoming>,
+) -> Response<BoxBody<Bytes, Infallible>> {
+    let token = request
+        .headers()
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or("")
+        .trim()
+        .to_string();
+    let body = match Limited::new(request.into_body(), MAX_LOCATION_BODY).collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(_) => return json_response(StatusCode::PAYLOAD_TOO_LARGE, serde_json::json!({"error": "body too large"})),
+    };
+    let fix: live::Fix = match serde_json::from_slice(&body) {
+        Ok(fix) => fix,
+        Err(e) => return json_response(StatusCode::BAD_REQUEST, serde_json::json!({"error": format!("invalid location: {}", e)})),
+    };
+    match sessions.push(share_id, &token, fix, clock.timestamp()) {
+        Ok(expires_at) => json_response(StatusCode::OK, serde_json::json!({"ok": true, "expires_at": expires_at})),
+        Err(e) => {
+            let status = match e {
+                live::PushError::NotFound => StatusCode::NOT_FOUND,
+                live::PushError::Unauthorized => StatusCode::UNAUTHORIZED,
+                live::PushError::Invalid(_) => StatusCode::BAD_REQUEST,
+            };
+            json_response(status, serde_json::json!({"error": e.to_string()}))
+        }
+    }
+}
+
+/// Signature check for `POST /ingest`: 503 while no secret is configured,
+/// 401 for a missing or wrong signature
+fn verify_ingest(
+    scheme: signature::SignatureScheme,
+    secret: Option<&str>,
+    body: &[u8],
+    signature_header: Option<&str>,
+    now: i64,
+    tolerance: i64,
+) -> Result<(), (StatusCode, String)> {
+    signature::verify(scheme, secret, body, signature_header, now, tolerance).map_err(|e| match e {
+        signature::SignatureError::NotConfigured => (StatusCode::SERVICE_UNAVAILABLE, "ingest is disabled until [ingest] signing_secret is set".to_string()),
+        e => (StatusCode::UNAUTHORIZED, e.to_string()),
+    })
+}
+
+/// Bearer check for `GET /events`, against the same secret as `/ingest`
+fn authorize_events(secret: Option<&str>, authorization: Option<&str>) -> Result<(), (StatusCode, String)> {
+    if secret.is_none_or(str::is_empty) {
+        return Err((StatusCode::SERVICE_UNAVAILABLE, "the change feed is disabled until [ingest] signing_secret is set".to_string()));
+    }
+    let token = authorization.and_then(|v| v.strip_prefix("Bearer "));
+    if signature::token_matches(secret, token) {
+        Ok(())
+    } else {
+        Err((StatusCode::UNAUTHORIZED, "missing or invalid bearer token".to_string()))
+    }
+}
 
-use crate::{HelixMcpServer, config::ServerConfig};
+/// Handle `POST /ingest`: verify the signature, check each event, answer 202
+/// and store the accepted events in the background
+async fn ingest_events(server: &HelixMcpServer, request: Request<Incoming>, max_bytes: usize) -> Response<BoxBody<Bytes, Infallible>> {
+    let header = |name: &str| request.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+    let scheme = signature::SignatureScheme::parse(&server.config.ingest.signature_scheme).unwrap_or(signature::SignatureScheme::HmacSha256);
+    let signature_header = header(scheme.header_name());
+    let body = match Limited::new(request.into_body(), max_bytes).collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(_) => return json_response(StatusCode::PAYLOAD_TOO_LARGE, serde_json::json!({"error": "body too large"})),
+    };
+    let secret = server.config.get_ingest_secret();
+    let tolerance = server.config.ingest.timestamp_tolerance_secs;
+    if let Err((status, e)) = verify_ingest(scheme, secret.as_deref(), &body, signature_header.as_deref(), server.clock.timestamp(), tolerance) {
+        warn!("Rejected ingest request: {}", e);
+        return json_response(status, serde_json::json!({"error": e}));
+    }
+    let events = match ingest::parse(&body) {
+        Ok(events) => events,
+        Err(e) => return json_response(StatusCode::BAD_REQUEST, serde_json::json!({"error": e})),
+    };
+
+    let mut accepted = Vec::new();
+    let mut duplicates = Vec::new();
+    let mut rejected = Vec::new();
+    for (index, event) in events.into_iter().enumerate() {
+        let record = match event.record() {
+            Ok(record) => record,
+            Err(e) => {
+                rejected.push(serde_json::json!({"index": index, "event_id": event.event_id, "error": e}));
+                continue;
+            }
+        };
+        if let Some(event_id) = &event.event_id {
+            let arguments = serde_json::to_value(&event).ok().and_then(|v| v.as_object().cloned()).unwrap_or_default();
+            match server.idempotency.claim("ingest", event_id, &arguments) {
+                idempotency::Claim::New => {}
+                idempotency::Claim::Mismatch => {
+                    rejected.push(serde_json::json!({"index": index, "event_id": event_id, "error": "event_id was already used for a different event"}));
+                    continue;
+                }
+                _ => {
+                    duplicates.push(event_id.clone());
+                    continue;
+                }
+            }
+        }
+        accepted.push((event, record));
+    }
+
+    let count = accepted.len();
+    let server = server.clone();
+    tokio::spawn(async move {
+        for (event, record) in accepted {
+            let result = priority::background(server.ingest_event(&event, record)).await;
+            let stored = result.as_ref().is_ok_and(|r| r.is_error != Some(true));
+            if !stored {
+                let error = match result {
+                    Ok(r) => r.structured_content.and_then(|c| c.get("error").cloned()).unwrap_or_default().to_string(),
+                    Err(e) => e.to_string(),
+                };
+                error!("Failed to store {} event for {}: {}", event.event_type, event.customer_id, error);
+            }
+            if let Some(event_id) = &event.event_id {
+                if stored {
+                    server.idempotency.complete("ingest", event_id, serde_json::json!({"stored": true}));
+                } else {
+                    // Let the producer's redelivery try again
+                    server.idempotency.release("ingest", event_id);
+                }
+            }
+        }
+    });
+
+    json_response(StatusCode::ACCEPTED, serde_json::json!({
+        "accepted": count,
+        "duplicates": duplicates,
+        "rejected": rejected
+    }))
+}
+
+/// Streaming body fed by a channel of pre-formatted SSE frames
+struct SseBody {
+    frames: mpsc::Receiver<Bytes>,
+}
+
+impl Body for SseBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        self.frames.poll_recv(cx).map(|frame| frame.map(|bytes| Ok(Frame::data(bytes))))
+    }
+}
+
+/// Value of `name` in a query string, with `+` and `%XX` decoded
+fn query_param(query: &str, name: &str) -> Option<String> {
+    let raw = query.split('&').find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))?;
+    let bytes = raw.replace('+', " ").into_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = if bytes[i] == b'%' { bytes.get(i + 1..i + 3) } else { None };
+        match hex.and_then(|h| u8::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok()) {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok().filter(|v| !v.is_empty())
+}
+
+/// Handle `GET /events`: forward matching change events until the client disconnects
+fn change_stream(feed: &change_feed::ChangeFeed, business_id: Option<String>) -> Response<BoxBody<Bytes, Infallible>> {
+    let mut events = feed.subscribe();
+    let (tx, frames) = mpsc::channel::<Bytes>(64);
+    tokio::spawn(async move {
+        let mut keep_alive = tokio::time::interval(std::time::Duration::from_secs(15));
+        loop {
+            let frame = tokio::select! {
+                event = events.recv() => match event {
+                    Ok(event) if event.matches(business_id.as_deref()) => event.to_sse(),
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(missed)) => format!("event: lagged\ndata: {{\"missed\":{}}}\n\n", missed),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = keep_alive.tick() => ": keep-alive\n\n".to_string(),
+            };
+            if tx.send(Bytes::from(frame)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("content-type", "text/event-stream")
+        .header("cache-control", "no-cache")
+        .body(SseBody { frames }.boxed())
+        .unwrap_or_default()
+}
 
 /// Start the HTTP MCP server using RMCP's StreamableHttpService
 /// 
@@ -34,7 +274,7 @@ use crate::{HelixMcpServer, config::ServerConfig};
 /// * `server` - The MCP server instance to use for handling requests
 /// * `addr` - The address to bind to (e.g., "127.0.0.1:8080")
 /// * `config` - Server configuration including HTTP settings
-pub async fn start_http_server(server: HelixMcpServer, addr: &str, _config: Arc<ServerConfig>) -> Result<()> {
+pub async fn start_http_server(server: HelixMcpServer, addr: &str, config: Arc<ServerConfig>) -> Result<()> {
     let addr: SocketAddr = addr.parse()?;
     
     info!("🌐 HTTP MCP Server starting on http://{}", addr);
@@ -43,6 +283,18 @@ pub async fn start_http_server(server: HelixMcpServer, addr: &str, _config: Arc<
     info!("📡 Endpoints:");
     info!("   POST / - MCP JSON-RPC requests");
     info!("   GET  / - Health check");
+    info!("   POST /location/<share_id> - Live location fixes for location sharing");
+    info!("   GET  /events - Memory change feed (Server-Sent Events, ?business_id= to filter, bearer token)");
+    info!("   POST /ingest - Signed purchase, page view and review events from external systems");
+
+    let live_sessions = server.live.clone();
+    let clock = server.clock.clone();
+    let changes = server.changes.clone();
+    let ingest_server = server.clone();
+    let max_request_bytes = match config.max_request_bytes {
+        0 => usize::MAX,
+        limit => limit,
+    };
     
     // Create RMCP StreamableHttpService configuration
     let http_config = StreamableHttpServerConfig {
@@ -51,8 +303,9 @@ pub async fn start_http_server(server: HelixMcpServer, addr: &str, _config: Arc<
     };
     
     // Create the service factory - RMCP will call this for each request
+    // (stateless: each request is its own session unless tools are given a session_id)
     let service_factory = move || {
-        Ok::<_, std::io::Error>(server.clone())
+        Ok::<_, std::io::Error>(server.new_session())
     };
     
     // Create the StreamableHttpService
@@ -75,6 +328,10 @@ pub async fn start_http_server(server: HelixMcpServer, addr: &str, _config: Arc<
                 info!("🔌 New HTTP connection from {}", peer_addr);
                 
                 let service = mcp_http_service.clone();
+                let live_sessions = live_sessions.clone();
+                let clock = clock.clone();
+                let changes = changes.clone();
+                let ingest_server = ingest_server.clone();
                 
                 // Spawn a task to handle this connection
                 tokio::spawn(async move {
@@ -82,10 +339,46 @@ pub async fn start_http_server(server: HelixMcpServer, addr: &str, _config: Arc<
                     let io = TokioIo::new(stream);
                     
                     // Convert the service to tower::Service
-                    let service = service_fn(move |req| {
+                    let service = service_fn(move |req: Request<Incoming>| {
                         let mut svc = service.clone();
+                        let live_sessions = live_sessions.clone();
+                        let clock = clock.clone();
+                        let changes = changes.clone();
+                        let ingest_server = ingest_server.clone();
                         async move {
-                            svc.call(req).await
+                            if req.method() == Method::GET && req.uri().path() == "/events" {
+                                let authorization = req.headers().get("authorization").and_then(|v| v.to_str().ok());
+                                if let Err((status, e)) = authorize_events(ingest_server.config.get_ingest_secret().as_deref(), authorization) {
+                                    warn!("Rejected change feed request: {}", e);
+                                    return Ok(json_response(status, serde_json::json!({"error": e})));
+                                }
+                                let business_id = req.uri().query().and_then(|q| query_param(q, "business_id"));
+                                return Ok(change_stream(&changes, business_id));
+                            }
+                            if req.method() == Method::POST && req.uri().path() == "/ingest" {
+                                return Ok(ingest_events(&ingest_server, req, max_request_bytes).await);
+                            }
+                            let share_id = (req.method() == Method::POST)
+                                .then(|| req.uri().path().strip_prefix("/location/"))
+                                .flatten()
+                                .map(str::to_string);
+                            match share_id {
+                                Some(share_id) => Ok(push_location(&live_sessions, clock.as_ref(), &share_id, req).await),
+                                None => {
+                                    let declared = req
+                                        .headers()
+                                        .get("content-length")
+                                        .and_then(|v| v.to_str().ok())
+                                        .and_then(|v| v.parse::<usize>().ok());
+                                    if declared.is_some_and(|length| length > max_request_bytes) {
+                                        return Ok(json_response(
+                                            StatusCode::PAYLOAD_TOO_LARGE,
+                                            serde_json::json!({"error": format!("request body over max_request_bytes ({})", max_request_bytes)}),
+                                        ));
+                                    }
+                                    svc.call(req.map(|body| Limited::new(body, max_request_bytes))).await
+                                }
+                            }
                         }
                     });
                     
@@ -107,3 +400,32 @@ pub async fn start_http_server(server: HelixMcpServer, addr: &str, _config: Arc<
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature::SignatureScheme;
+
+    #[test]
+    fn test_unsigned_ingest_is_rejected() {
+        let body = br#"{"event_type":"purchase"}"#;
+        // No secret configured: refused outright, signed or not
+        let (status, _) = verify_ingest(SignatureScheme::HmacSha256, None, body, None, 0, 300).unwrap_err();
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        let signed = signature::sign_hex("secret", body);
+        let (status, _) = verify_ingest(SignatureScheme::HmacSha256, None, body, Some(&signed), 0, 300).unwrap_err();
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+        let (status, _) = verify_ingest(SignatureScheme::HmacSha256, Some("secret"), body, None, 0, 300).unwrap_err();
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(verify_ingest(SignatureScheme::HmacSha256, Some("secret"), body, Some(&signed), 0, 300).is_ok());
+    }
+
+    #[test]
+    fn test_events_need_bearer_token() {
+        assert_eq!(authorize_events(None, Some("Bearer secret")).unwrap_err().0, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(authorize_events(Some("secret"), None).unwrap_err().0, StatusCode::UNAUTHORIZED);
+        assert_eq!(authorize_events(Some("secret"), Some("Bearer wrong")).unwrap_err().0, StatusCode::UNAUTHORIZED);
+        assert!(authorize_events(Some("secret"), Some("Bearer secret")).is_ok());
+    }
+}
@@ -1,8 +1,8 @@
-/// Server transport modules for MCP
-/// 
-/// This module provides multiple transport options:
-/// - TCP: Direct TCP socket connections
-/// - HTTP: Hyper-based HTTP server for REST-like access
+//! Server transport modules for MCP
+//! 
+//! This module provides multiple transport options:
+//! - TCP: Direct TCP socket connections
+//! - HTTP: Hyper-based HTTP server for REST-like access
 
 pub mod tcp_server;
 pub mod http_server;
@@ -1,23 +1,77 @@
-/// TCP Transport Server for MCP
-/// 
-/// This module provides a simple TCP server that accepts connections
-/// and serves the MCP protocol over TCP without any authentication.
-/// 
-/// Each connection is handled independently, making it easy for clients
-/// to connect and start using MCP tools immediately.
-/// 
-/// Performance optimizations are configurable via mcpconfig.toml:
-/// - tcp_nodelay: Disable Nagle's algorithm for low latency
-/// - tcp_keepalive: Detect broken connections automatically
+//! TCP Transport Server for MCP
+//! 
+//! This module provides a simple TCP server that accepts connections
+//! and serves the MCP protocol over TCP without any authentication.
+//! 
+//! Each connection is handled independently, making it easy for clients
+//! to connect and start using MCP tools immediately.
+//! 
+//! Performance optimizations are configurable via mcpconfig.toml:
+//! - tcp_nodelay: Disable Nagle's algorithm for low latency
+//! - tcp_keepalive: Detect broken connections automatically
+//! - max_request_bytes: Close connections sending a larger JSON-RPC message
 
 use anyhow::Result;
 use rmcp::{serve_server};
+use std::io;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use tokio::net::{TcpListener, TcpStream};
 use tracing::{info, error};
 
 use crate::{HelixMcpServer, config::ServerConfig};
 
+/// Stream wrapper failing reads once a line (one JSON-RPC message) exceeds `limit` bytes
+struct RequestLimit<S> {
+    inner: S,
+    limit: usize,
+    line_bytes: usize,
+}
+
+impl<S> RequestLimit<S> {
+    fn new(inner: S, limit: usize) -> Self {
+        Self { inner, limit: if limit == 0 { usize::MAX } else { limit }, line_bytes: 0 }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for RequestLimit<S> {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let start = buf.filled().len();
+        let this = &mut *self;
+        match Pin::new(&mut this.inner).poll_read(cx, buf) {
+            Poll::Ready(Ok(())) => {
+                for &byte in &buf.filled()[start..] {
+                    this.line_bytes = if byte == b'\n' { 0 } else { this.line_bytes + 1 };
+                    if this.line_bytes > this.limit {
+                        return Poll::Ready(Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("request over max_request_bytes ({})", this.limit),
+                        )));
+                    }
+                }
+                Poll::Ready(Ok(()))
+            }
+            other => other,
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for RequestLimit<S> {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
 /// Start the TCP MCP server
 /// 
 /// This function binds to the specified address and accepts connections.
@@ -120,11 +174,11 @@ async fn handle_connection(
             let stream = TcpStream::from_std(socket.into())?;
             
             // 3. Serve the MCP protocol
-            let running_service = serve_server((*server).clone(), stream).await?;
+            let running_service = serve_server(server.new_session(), RequestLimit::new(stream, config.max_request_bytes)).await?;
             running_service.waiting().await?;
         } else {
             // Serve without keepalive
-            let running_service = serve_server((*server).clone(), stream).await?;
+            let running_service = serve_server(server.new_session(), RequestLimit::new(stream, config.max_request_bytes)).await?;
             running_service.waiting().await?;
         }
     }
@@ -138,7 +192,7 @@ async fn handle_connection(
         }
         
         // Serve the MCP protocol
-        let running_service = serve_server((*server).clone(), stream).await?;
+        let running_service = serve_server(server.new_session(), RequestLimit::new(stream, config.max_request_bytes)).await?;
         running_service.waiting().await?;
     }
     
@@ -1,100 +1,108 @@
-use serde::{Deserialize, Serialize};
+//! HelixDB traversal connections owned by the server
+//!
+//! Traversal steps run on a HelixDB connection opened with `mcp/init`.
+//! Rather than having agents carry connection IDs between calls, each MCP
+//! session gets one connection on first use and reuses it for every step.
+//! Connections idle past the TTL are expired (the caller closes them on
+//! HelixDB) and the number open at once is capped.
+
 use std::collections::HashMap;
-use uuid::Uuid;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+struct Connection {
+    connection_id: String,
+    last_used: Instant,
+}
 
-/// Session state for managing query results pagination
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct QuerySession {
-    pub session_id: String,
-    pub query: String,
-    pub results: Vec<serde_json::Value>,
-    pub cursor: usize,
-    pub total_count: usize,
-    pub created_at: std::time::SystemTime,
+/// Traversal connection per MCP session
+pub struct TraversalSessions {
+    connections: Mutex<HashMap<String, Connection>>,
+    ttl: Duration,
+    max_sessions: usize,
 }
 
-impl QuerySession {
-    pub fn new(query: String, results: Vec<serde_json::Value>) -> Self {
-        let total_count = results.len();
-        Self {
-            session_id: Uuid::new_v4().to_string(),
-            query,
-            results,
-            cursor: 0,
-            total_count,
-            created_at: std::time::SystemTime::now(),
+impl TraversalSessions {
+    pub fn new(ttl: Duration, max_sessions: usize) -> Self {
+        TraversalSessions {
+            connections: Mutex::new(HashMap::new()),
+            ttl,
+            max_sessions,
         }
     }
-    
-    /// Get next batch of results
-    pub fn next(&mut self, limit: usize) -> Vec<serde_json::Value> {
-        let start = self.cursor;
-        let end = std::cmp::min(start + limit, self.results.len());
-        
-        let batch = self.results[start..end].to_vec();
-        self.cursor = end;
-        
-        batch
-    }
-    
-    /// Collect all remaining results
-    pub fn collect_all(&mut self) -> Vec<serde_json::Value> {
-        let remaining = self.results[self.cursor..].to_vec();
-        self.cursor = self.results.len();
-        remaining
-    }
-    
-    /// Check if more results available
-    pub fn has_more(&self) -> bool {
-        self.cursor < self.results.len()
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
     }
-}
 
-/// Manages multiple query sessions
-pub struct SessionManager {
-    sessions: HashMap<String, QuerySession>,
-}
+    /// The session's live connection, marking it used
+    pub fn get(&self, session_id: &str) -> Option<String> {
+        let mut connections = self.connections.lock().unwrap();
+        let connection = connections.get_mut(session_id).filter(|c| c.last_used.elapsed() < self.ttl)?;
+        connection.last_used = Instant::now();
+        Some(connection.connection_id.clone())
+    }
 
-impl SessionManager {
-    pub fn new() -> Self {
-        Self {
-            sessions: HashMap::new(),
+    /// Register a newly opened connection for the session. Returns the
+    /// connection to use: an existing live one wins over `connection_id`.
+    pub fn insert(&self, session_id: &str, connection_id: String) -> Result<String, String> {
+        let mut connections = self.connections.lock().unwrap();
+        if let Some(existing) = connections.get_mut(session_id).filter(|c| c.last_used.elapsed() < self.ttl) {
+            existing.last_used = Instant::now();
+            return Ok(existing.connection_id.clone());
         }
-    }
-    
-    /// Create new session
-    pub fn create_session(&mut self, query: String, results: Vec<serde_json::Value>) -> String {
-        let session = QuerySession::new(query, results);
-        let session_id = session.session_id.clone();
-        self.sessions.insert(session_id.clone(), session);
-        
-        // Cleanup old sessions (keep last 100)
-        if self.sessions.len() > 100 {
-            self.cleanup_old_sessions();
+        let open = connections.values().filter(|c| c.last_used.elapsed() < self.ttl).count();
+        if open >= self.max_sessions {
+            return Err(format!(
+                "Too many open traversal sessions ({}); idle ones expire after {}s",
+                self.max_sessions,
+                self.ttl.as_secs()
+            ));
         }
-        
-        session_id
+        connections.insert(session_id.to_string(), Connection { connection_id: connection_id.clone(), last_used: Instant::now() });
+        Ok(connection_id)
     }
-    
-    /// Get session by ID
-    pub fn get_session(&mut self, session_id: &str) -> Option<&mut QuerySession> {
-        self.sessions.get_mut(session_id)
+
+    /// Stop managing the session's connection if it is `connection_id` (it was dropped)
+    pub fn forget(&self, session_id: &str, connection_id: &str) {
+        let mut connections = self.connections.lock().unwrap();
+        if connections.get(session_id).is_some_and(|c| c.connection_id == connection_id) {
+            connections.remove(session_id);
+        }
     }
-    
-    /// Remove session
-    pub fn remove_session(&mut self, session_id: &str) -> bool {
-        self.sessions.remove(session_id).is_some()
+
+    /// Remove connections idle past the TTL, returning their IDs to close
+    pub fn expire(&self) -> Vec<String> {
+        let mut connections = self.connections.lock().unwrap();
+        let expired: Vec<String> = connections
+            .iter()
+            .filter(|(_, c)| c.last_used.elapsed() >= self.ttl)
+            .map(|(session_id, _)| session_id.clone())
+            .collect();
+        expired.iter().filter_map(|session_id| connections.remove(session_id)).map(|c| c.connection_id).collect()
     }
-    
-    /// Cleanup sessions older than 1 hour
-    fn cleanup_old_sessions(&mut self) {
-        let now = std::time::SystemTime::now();
-        let one_hour = std::time::Duration::from_secs(3600);
-        
-        self.sessions.retain(|_, session| {
-            now.duration_since(session.created_at)
-                .map(|duration| duration < one_hour)
-                .unwrap_or(false)
-        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reuse_cap_and_expiry() {
+        let sessions = TraversalSessions::new(Duration::from_secs(60), 1);
+        assert_eq!(sessions.get("s1"), None);
+        assert_eq!(sessions.insert("s1", "conn-1".to_string()).unwrap(), "conn-1");
+        // A concurrently opened second connection loses to the registered one
+        assert_eq!(sessions.insert("s1", "conn-2".to_string()).unwrap(), "conn-1");
+        assert_eq!(sessions.get("s1").as_deref(), Some("conn-1"));
+        assert!(sessions.insert("s2", "conn-3".to_string()).is_err());
+
+        sessions.forget("s1", "conn-1");
+        assert!(sessions.insert("s2", "conn-3".to_string()).is_ok());
+
+        let expiring = TraversalSessions::new(Duration::ZERO, 4);
+        expiring.insert("s1", "conn-1".to_string()).unwrap();
+        assert_eq!(expiring.get("s1"), None);
+        assert_eq!(expiring.expire(), vec!["conn-1".to_string()]);
     }
 }
@@ -0,0 +1,197 @@
+//! Shopify catalog sync
+//!
+//! `sync_shopify_catalog` pages through the Admin API `products.json` of the
+//! store in `[shopify]` and maps each product onto a product memory keyed by
+//! external ID `shopify_<product id>`: lowest variant price, summed tracked
+//! inventory, variant titles as features and the cleaned body HTML as the
+//! description. Products that are archived or drafts in Shopify, or missing
+//! from it, are marked `discontinued` rather than deleted.
+
+use reqwest::header::LINK;
+use serde_json::{json, Value};
+
+use crate::config::ShopifyConfig;
+
+/// External ID prefix of products synced from Shopify
+pub const EXTERNAL_PREFIX: &str = "shopify_";
+
+pub const DISCONTINUED: &str = "discontinued";
+
+/// A Shopify product as a product memory
+#[derive(Debug, Clone, PartialEq)]
+pub struct Product {
+    pub external_id: String,
+    pub text_description: String,
+    pub data: Value,
+}
+
+pub struct Client {
+    base: String,
+    token: String,
+    page_size: u32,
+    client: reqwest::Client,
+}
+
+impl Client {
+    pub fn new(config: &ShopifyConfig, token: &str) -> Self {
+        let domain = config.shop_domain.trim().trim_start_matches("https://").trim_end_matches('/');
+        Client {
+            base: format!("https://{}/admin/api/{}", domain, config.api_version),
+            token: token.to_string(),
+            page_size: config.page_size.clamp(1, 250),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn get(&self, url: &str) -> Result<reqwest::Response, String> {
+        self.client
+            .get(url)
+            .header("X-Shopify-Access-Token", &self.token)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Shopify request failed: {}", e))
+    }
+
+    /// The store's currency, used for every product price
+    pub async fn shop_currency(&self) -> Result<String, String> {
+        let body: Value = self.get(&format!("{}/shop.json", self.base)).await?.json().await.map_err(|e| format!("Invalid shop response: {}", e))?;
+        Ok(body["shop"]["currency"].as_str().unwrap_or("").to_string())
+    }
+
+    /// Every product of the store, following the `Link` header's next pages
+    pub async fn products(&self) -> Result<Vec<Value>, String> {
+        let mut products = Vec::new();
+        let mut url = Some(format!("{}/products.json?limit={}", self.base, self.page_size));
+        while let Some(page_url) = url {
+            let response = self.get(&page_url).await?;
+            url = response.headers().get(LINK).and_then(|v| v.to_str().ok()).and_then(next_page);
+            let body: Value = response.json().await.map_err(|e| format!("Invalid products response: {}", e))?;
+            products.extend(body["products"].as_array().cloned().unwrap_or_default());
+        }
+        Ok(products)
+    }
+}
+
+/// URL of the `rel="next"` page in a `Link` header
+pub fn next_page(link: &str) -> Option<String> {
+    link.split(',').find_map(|part| {
+        let (url, rel) = part.split_once(';')?;
+        rel.contains("rel=\"next\"").then(|| url.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+    })
+}
+
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                text.push(' ');
+            }
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = text.replace("&amp;", "&").replace("&nbsp;", " ").replace("&quot;", "\"").replace("&#39;", "'");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Map an Admin API product onto product memory fields
+pub fn map_product(product: &Value, currency: &str) -> Option<Product> {
+    let id = product.get("id").map(|id| id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string()))?;
+    let title = product.get("title").and_then(|v| v.as_str()).unwrap_or("").trim();
+    if title.is_empty() {
+        return None;
+    }
+    let text = |field: &str| product.get(field).and_then(|v| v.as_str()).unwrap_or("").trim().to_string();
+    let variants = product.get("variants").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+
+    let price = variants
+        .iter()
+        .filter_map(|v| v.get("price").and_then(|p| p.as_str().and_then(|p| p.parse::<f64>().ok()).or_else(|| p.as_f64())))
+        .fold(None, |min: Option<f64>, p| Some(min.map_or(p, |m| m.min(p))))
+        .unwrap_or(0.0);
+    let tracked: Vec<i64> = variants
+        .iter()
+        .filter(|v| v.get("inventory_management").and_then(|m| m.as_str()) == Some("shopify"))
+        .map(|v| v.get("inventory_quantity").and_then(|q| q.as_i64()).unwrap_or(0).max(0))
+        .collect();
+    let stock_quantity = if tracked.is_empty() { -1 } else { tracked.iter().sum() };
+    let availability = match text("status").as_str() {
+        "archived" | "draft" => DISCONTINUED,
+        _ if stock_quantity == 0 => "out_of_stock",
+        _ => "in_stock",
+    };
+    let features: Vec<String> = variants
+        .iter()
+        .filter_map(|v| v.get("title").and_then(|t| t.as_str()))
+        .filter(|t| !t.is_empty() && *t != "Default Title")
+        .map(str::to_string)
+        .collect();
+    let tags: Vec<String> = text("tags").split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+    let description = strip_html(&text("body_html"));
+    let category = text("product_type");
+
+    let text_description = format!(
+        "Product: {}. Category: {}. Price: {:.2} {}. Description: {}. Features: {}. Tags: {}. Availability: {}",
+        title, category, price, currency, description, features.join(", "), tags.join(", "), availability
+    );
+    Some(Product {
+        external_id: format!("{}{}", EXTERNAL_PREFIX, id),
+        text_description,
+        data: json!({
+            "product_name": title,
+            "product_category": category,
+            "price": price,
+            "currency": currency,
+            "availability": availability,
+            "stock_quantity": stock_quantity,
+            "description": description,
+            "features": features,
+            "tags": tags,
+            "specifications": json!({"vendor": text("vendor"), "handle": text("handle"), "shopify_id": id}).to_string()
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_product_and_pagination() {
+        let product = json!({
+            "id": 632910392,
+            "title": "Oat Latte",
+            "body_html": "<p>Creamy <b>oat</b> milk &amp; espresso</p>",
+            "vendor": "Cafe",
+            "product_type": "Drinks",
+            "status": "active",
+            "tags": "vegan, hot",
+            "variants": [
+                {"title": "Small", "price": "3.50", "inventory_management": "shopify", "inventory_quantity": 4},
+                {"title": "Large", "price": "4.20", "inventory_management": "shopify", "inventory_quantity": -2}
+            ]
+        });
+        let mapped = map_product(&product, "EUR").unwrap();
+        assert_eq!(mapped.external_id, "shopify_632910392");
+        assert_eq!(mapped.data["price"], 3.5);
+        assert_eq!(mapped.data["stock_quantity"], 4);
+        assert_eq!(mapped.data["availability"], "in_stock");
+        assert_eq!(mapped.data["description"], "Creamy oat milk & espresso");
+        assert_eq!(mapped.data["features"], json!(["Small", "Large"]));
+        assert_eq!(mapped.data["tags"], json!(["vegan", "hot"]));
+
+        let archived = json!({"id": 1, "title": "Old mug", "status": "archived", "variants": [{"title": "Default Title", "price": "9.00"}]});
+        let mapped = map_product(&archived, "EUR").unwrap();
+        assert_eq!((mapped.data["availability"].as_str(), mapped.data["stock_quantity"].as_i64()), (Some(DISCONTINUED), Some(-1)));
+        assert!(map_product(&json!({"id": 2, "title": " "}), "EUR").is_none());
+
+        let link = r#"<https://s.myshopify.com/admin/api/2024-10/products.json?page_info=abc>; rel="previous", <https://s.myshopify.com/admin/api/2024-10/products.json?page_info=def>; rel="next""#;
+        assert_eq!(next_page(link).as_deref(), Some("https://s.myshopify.com/admin/api/2024-10/products.json?page_info=def"));
+        assert_eq!(next_page(r#"<https://x>; rel="previous""#), None);
+    }
+}
@@ -0,0 +1,169 @@
+//! HMAC signature verification for externally delivered events
+//!
+//! Supported schemes:
+//! - `shopify`: base64 HMAC-SHA256 of the raw body (`X-Shopify-Hmac-Sha256`)
+//! - `stripe`: `t=<unix>,v1=<hex>` over `"<t>.<body>"` with timestamp tolerance (`Stripe-Signature`)
+//! - `hmac-sha256`: hex HMAC-SHA256 of the raw body, optional `sha256=` prefix (`X-Signature`)
+//!
+//! Verification fails closed: without a configured secret every payload is
+//! rejected rather than accepted unsigned.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use thiserror::Error;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Signature scheme used by an event producer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureScheme {
+    Shopify,
+    Stripe,
+    HmacSha256,
+}
+
+impl SignatureScheme {
+    pub fn parse(scheme: &str) -> Option<Self> {
+        match scheme.to_lowercase().as_str() {
+            "shopify" => Some(Self::Shopify),
+            "stripe" => Some(Self::Stripe),
+            "hmac-sha256" | "hmac" => Some(Self::HmacSha256),
+            _ => None,
+        }
+    }
+
+    /// HTTP header carrying the signature for this scheme
+    pub fn header_name(&self) -> &'static str {
+        match self {
+            Self::Shopify => "x-shopify-hmac-sha256",
+            Self::Stripe => "stripe-signature",
+            Self::HmacSha256 => "x-signature",
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SignatureError {
+    #[error("no signing secret configured")]
+    NotConfigured,
+    #[error("missing signature header")]
+    Missing,
+    #[error("malformed signature header")]
+    Malformed,
+    #[error("signature timestamp outside tolerance")]
+    Expired,
+    #[error("signature mismatch")]
+    Mismatch,
+}
+
+/// Hex HMAC-SHA256 of `payload` (also used to sign outbound events)
+pub fn sign_hex(secret: &str, payload: &[u8]) -> String {
+    hex::encode(mac(secret, payload).finalize().into_bytes())
+}
+
+/// Verify `signature` (the raw header value) for `payload`; `secret` is
+/// `None` when none is configured, which rejects everything
+pub fn verify(
+    scheme: SignatureScheme,
+    secret: Option<&str>,
+    payload: &[u8],
+    signature: Option<&str>,
+    now: i64,
+    tolerance_secs: i64,
+) -> Result<(), SignatureError> {
+    let secret = secret.filter(|s| !s.is_empty()).ok_or(SignatureError::NotConfigured)?;
+    let signature = signature.map(str::trim).filter(|s| !s.is_empty()).ok_or(SignatureError::Missing)?;
+
+    match scheme {
+        SignatureScheme::Shopify => {
+            let expected = BASE64.decode(signature).map_err(|_| SignatureError::Malformed)?;
+            mac(secret, payload).verify_slice(&expected).map_err(|_| SignatureError::Mismatch)
+        }
+        SignatureScheme::HmacSha256 => {
+            let hex_sig = signature.strip_prefix("sha256=").unwrap_or(signature);
+            let expected = hex::decode(hex_sig).map_err(|_| SignatureError::Malformed)?;
+            mac(secret, payload).verify_slice(&expected).map_err(|_| SignatureError::Mismatch)
+        }
+        SignatureScheme::Stripe => {
+            let mut timestamp = None;
+            let mut candidates = Vec::new();
+            for part in signature.split(',') {
+                match part.trim().split_once('=') {
+                    Some(("t", t)) => timestamp = t.parse::<i64>().ok(),
+                    Some(("v1", v)) => candidates.push(v),
+                    _ => {}
+                }
+            }
+            let timestamp = timestamp.ok_or(SignatureError::Malformed)?;
+            if candidates.is_empty() {
+                return Err(SignatureError::Malformed);
+            }
+            if tolerance_secs > 0 && (now - timestamp).abs() > tolerance_secs {
+                return Err(SignatureError::Expired);
+            }
+
+            let mut signed = format!("{}.", timestamp).into_bytes();
+            signed.extend_from_slice(payload);
+            let matched = candidates.iter().any(|candidate| {
+                hex::decode(candidate)
+                    .map(|expected| mac(secret, &signed).verify_slice(&expected).is_ok())
+                    .unwrap_or(false)
+            });
+            if matched { Ok(()) } else { Err(SignatureError::Mismatch) }
+        }
+    }
+}
+
+/// Constant-time check of a bearer token against the configured secret
+pub fn token_matches(secret: Option<&str>, token: Option<&str>) -> bool {
+    let (Some(secret), Some(token)) = (secret.filter(|s| !s.is_empty()), token) else {
+        return false;
+    };
+    let expected = mac(secret, secret.as_bytes()).finalize().into_bytes();
+    mac(secret, token.trim().as_bytes()).verify_slice(&expected).is_ok()
+}
+
+fn mac(secret: &str, payload: &[u8]) -> HmacSha256 {
+    // HMAC accepts keys of any length
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(payload);
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_schemes() {
+        let body = br#"{"event":"purchase"}"#;
+        let hex_sig = sign_hex("secret", body);
+        assert_eq!(verify(SignatureScheme::HmacSha256, Some("secret"), body, Some(&format!("sha256={}", hex_sig)), 0, 0), Ok(()));
+        assert_eq!(verify(SignatureScheme::HmacSha256, Some("other"), body, Some(&hex_sig), 0, 0), Err(SignatureError::Mismatch));
+        assert_eq!(verify(SignatureScheme::HmacSha256, Some("secret"), body, None, 0, 0), Err(SignatureError::Missing));
+
+        let shopify_sig = BASE64.encode(hex::decode(&hex_sig).unwrap());
+        assert_eq!(verify(SignatureScheme::Shopify, Some("secret"), body, Some(&shopify_sig), 0, 0), Ok(()));
+
+        let mut signed = b"1700000000.".to_vec();
+        signed.extend_from_slice(body);
+        let stripe_sig = format!("t=1700000000,v1={}", sign_hex("secret", &signed));
+        assert_eq!(verify(SignatureScheme::Stripe, Some("secret"), body, Some(&stripe_sig), 1_700_000_100, 300), Ok(()));
+        assert_eq!(verify(SignatureScheme::Stripe, Some("secret"), body, Some(&stripe_sig), 1_700_001_000, 300), Err(SignatureError::Expired));
+
+        // No secret: even a correctly signed payload is refused
+        assert_eq!(verify(SignatureScheme::HmacSha256, None, body, Some(&hex_sig), 0, 0), Err(SignatureError::NotConfigured));
+        assert_eq!(verify(SignatureScheme::HmacSha256, Some(""), body, None, 0, 0), Err(SignatureError::NotConfigured));
+    }
+
+    #[test]
+    fn test_token_matches() {
+        assert!(token_matches(Some("secret"), Some("secret")));
+        assert!(!token_matches(Some("secret"), Some("secreT")));
+        assert!(!token_matches(Some("secret"), None));
+        assert!(!token_matches(None, Some("secret")));
+        assert!(!token_matches(Some(""), Some("")));
+    }
+}
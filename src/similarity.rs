@@ -0,0 +1,229 @@
+//! Vector similarity helpers
+//!
+//! Used for near-duplicate detection, theme clustering and client-side scoring of
+//! HelixDB vector search results.
+
+use crate::helix_client::HelixClient;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+
+/// Cosine similarity between two vectors (0.0 for mismatched or zero vectors)
+pub fn cosine_similarity(a: &[f64], b: &[f64]) -> f64 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// Read the vector stored on a HelixDB vector item (`data`, `vector` or `embedding`)
+pub fn vector_of(item: &Value) -> Option<Vec<f64>> {
+    let values = ["data", "vector", "embedding"]
+        .iter()
+        .find_map(|key| item.get(*key).and_then(|v| v.as_array()))?;
+    let vector: Vec<f64> = values.iter().filter_map(|x| x.as_f64()).collect();
+    (!vector.is_empty()).then_some(vector)
+}
+
+/// Pick the most similar memory of one owner from the dedup candidates.
+///
+/// `memories`, `links` and `embeddings` are the outputs of a `find_similar_*`
+/// query: the nearest vectors, their `Has*Embedding` edges and the memories.
+/// Vectors are paired with memories through the edges' `from_node` / `to_node`,
+/// so vectors without a memory (or memories without a vector) are skipped
+/// rather than shifting the pairing. Memories whose `owner` field (e.g.
+/// `business_id`) differs are ignored. Returns the memory and its similarity.
+pub fn best_match(query: &[f64], owner: (&str, &str), memories: &[Value], links: &[Value], embeddings: &[Value]) -> Option<(Value, f64)> {
+    let id = |item: &Value, field: &str| item.get(field).and_then(|v| v.as_str()).map(str::to_string);
+    let vectors: HashMap<String, Vec<f64>> = embeddings
+        .iter()
+        .filter_map(|embedding| Some((id(embedding, "id")?, vector_of(embedding)?)))
+        .collect();
+    let (owner_field, owner_id) = owner;
+    let memories: HashMap<String, &Value> = memories
+        .iter()
+        .filter(|memory| id(memory, owner_field).as_deref() == Some(owner_id))
+        .filter_map(|memory| Some((id(memory, "id")?, memory)))
+        .collect();
+
+    links
+        .iter()
+        .filter_map(|link| {
+            let memory = memories.get(&id(link, "from_node")?)?;
+            let vector = vectors.get(&id(link, "to_node")?)?;
+            Some(((*memory).clone(), cosine_similarity(query, vector)))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Similarity of a vector search hit: cosine similarity to `query` when the hit
+/// carries its vector, else the `score` HelixDB reports, else `1 - distance`
+pub fn hit_score(hit: &Value, query: Option<&[f64]>) -> Option<f64> {
+    if let (Some(query), Some(vector)) = (query, vector_of(hit)) {
+        return Some(cosine_similarity(query, &vector));
+    }
+    hit.get("score")
+        .and_then(|v| v.as_f64())
+        .or_else(|| hit.get("distance").and_then(|v| v.as_f64()).map(|d| 1.0 - d))
+}
+
+/// Memories of a `SearchV` + `In<Has*Embedding>` response, each with its `score`.
+///
+/// The search queries return the vector hits (`embeddings` or `results`), the
+/// `Has*Embedding` edges leading to them as `links`, the memories and, for
+/// filtered searches, the surviving memories as `filtered`. Each hit is joined
+/// to its memory through the edge's `to_node` / `from_node`, so a hit without
+/// an edge (or an edge without a memory) is dropped instead of shifting the
+/// scores onto the wrong memories. Memories are returned in hit order; without
+/// edges they are returned unscored, and responses without vector hits are
+/// flattened unscored.
+pub fn scored_memories(result: &Value, query: Option<&[f64]>) -> Vec<Value> {
+    let Some(fields) = result.as_object() else {
+        return HelixClient::result_items(result);
+    };
+    let items = |key: &str| fields.get(key).and_then(|v| v.as_array()).cloned();
+    let Some(hits) = items("embeddings").or_else(|| items("results")) else {
+        return HelixClient::result_items(result);
+    };
+    let memories = fields
+        .iter()
+        .find(|(key, _)| !matches!(key.as_str(), "embeddings" | "results" | "links" | "filtered"))
+        .and_then(|(_, v)| v.as_array().cloned())
+        .unwrap_or_default();
+
+    let id = |item: &Value, field: &str| item.get(field).and_then(|v| v.as_str()).map(str::to_string);
+    let kept: Option<HashSet<String>> = items("filtered").map(|kept| kept.iter().filter_map(|memory| id(memory, "id")).collect());
+    let is_kept = |memory: &Value| kept.as_ref().is_none_or(|kept| id(memory, "id").is_some_and(|id| kept.contains(&id)));
+
+    let Some(links) = items("links") else {
+        return memories.into_iter().filter(|memory| is_kept(memory)).collect();
+    };
+    let memory_of: HashMap<String, String> = links.iter().filter_map(|link| Some((id(link, "to_node")?, id(link, "from_node")?))).collect();
+    let by_id: HashMap<String, &Value> = memories.iter().filter_map(|memory| Some((id(memory, "id")?, memory))).collect();
+
+    hits.iter()
+        .filter_map(|hit| {
+            let memory = *by_id.get(memory_of.get(&id(hit, "id")?)?)?;
+            is_kept(memory).then(|| {
+                let mut memory = memory.clone();
+                if let (Some(score), Some(fields)) = (hit_score(hit, query), memory.as_object_mut()) {
+                    fields.insert("score".to_string(), json!(score));
+                }
+                memory
+            })
+        })
+        .collect()
+}
+
+/// Greedy single-pass clustering: each vector joins the first cluster whose
+/// leader is at least `threshold` similar, otherwise it starts a new cluster.
+/// Returns clusters of indices, largest first.
+pub fn cluster(vectors: &[Vec<f64>], threshold: f64) -> Vec<Vec<usize>> {
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+    for (index, vector) in vectors.iter().enumerate() {
+        match clusters.iter_mut().find(|c| cosine_similarity(&vectors[c[0]], vector) >= threshold) {
+            Some(cluster) => cluster.push(index),
+            None => clusters.push(vec![index]),
+        }
+    }
+    clusters.sort_by_key(|c| std::cmp::Reverse(c.len()));
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[1.0, 0.0]) - 1.0).abs() < 1e-9);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-9);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 2.0]), 0.0);
+    }
+
+    #[test]
+    fn test_best_match_pairs_by_edge() {
+        let memories = vec![
+            json!({"id": "a", "business_id": "b1"}),
+            json!({"id": "b", "business_id": "b1"}),
+            json!({"id": "c", "business_id": "b1"}),
+            json!({"id": "d", "business_id": "b2"}),
+        ];
+        // "v0" is an orphan vector and "c" has none; neither may shift the pairing
+        let embeddings = vec![
+            json!({"id": "v0", "data": [1.0, 0.0]}),
+            json!({"id": "va", "data": [0.0, 1.0]}),
+            json!({"id": "vb", "data": [0.9, 0.1]}),
+            json!({"id": "vd", "data": [1.0, 0.0]}),
+        ];
+        let links = vec![
+            json!({"from_node": "a", "to_node": "va"}),
+            json!({"from_node": "b", "to_node": "vb"}),
+            json!({"from_node": "d", "to_node": "vd"}),
+        ];
+        // "d" is the closest hit but belongs to another business
+        let (memory, score) = best_match(&[1.0, 0.0], ("business_id", "b1"), &memories, &links, &embeddings).unwrap();
+        assert_eq!(memory["id"], "b");
+        assert!(score > 0.9 && score < 1.0);
+        assert_eq!(best_match(&[1.0, 0.0], ("business_id", "b2"), &memories, &links, &embeddings).unwrap().0["id"], "d");
+        assert!(best_match(&[1.0, 0.0], ("business_id", "b1"), &memories, &[], &embeddings).is_none());
+    }
+
+    #[test]
+    fn test_scored_memories() {
+        let result = json!({
+            "embeddings": [
+                {"id": "va", "data": [1.0, 0.0]},
+                {"id": "vb", "data": [0.0, 1.0]},
+                {"id": "vc", "data": [0.6, 0.8]}
+            ],
+            "links": [{"from_node": "c", "to_node": "vc"}, {"from_node": "a", "to_node": "va"}, {"from_node": "b", "to_node": "vb"}],
+            "products": [{"id": "a", "business_id": "B1"}, {"id": "b", "business_id": "B2"}, {"id": "c", "business_id": "B1"}],
+            "filtered": [{"id": "a", "business_id": "B1"}, {"id": "c", "business_id": "B1"}]
+        });
+        let scored = scored_memories(&result, Some(&[1.0, 0.0]));
+        assert_eq!(scored.len(), 2);
+        assert_eq!(scored[0]["id"], "a");
+        assert_eq!(scored[1]["id"], "c");
+        assert!((scored[1]["score"].as_f64().unwrap() - 0.6).abs() < 1e-9);
+
+        // HelixDB-reported distances when the vectors aren't returned
+        let text_search = json!({
+            "results": [{"id": "v1", "distance": 0.25}],
+            "links": [{"from_node": "r1", "to_node": "v1"}],
+            "rules": [{"id": "r1"}]
+        });
+        assert_eq!(scored_memories(&text_search, None)[0]["score"], 0.75);
+        assert_eq!(scored_memories(&json!({"rules": [{"id": "r1"}]}), None), vec![json!({"id": "r1"})]);
+    }
+
+    #[test]
+    fn test_scored_memories_missing_edge() {
+        // "v1" has no edge, so its distance must not be credited to "r2"
+        let result = json!({
+            "results": [{"id": "v1", "distance": 0.1}, {"id": "v2", "distance": 0.4}],
+            "links": [{"from_node": "r2", "to_node": "v2"}],
+            "rules": [{"id": "r2"}]
+        });
+        let scored = scored_memories(&result, None);
+        assert_eq!(scored.len(), 1);
+        assert_eq!(scored[0]["id"], "r2");
+        assert!((scored[0]["score"].as_f64().unwrap() - 0.6).abs() < 1e-9);
+
+        // Without edges nothing can be paired, so the memories come back unscored
+        let unlinked = json!({"results": [{"id": "v1", "distance": 0.1}], "rules": [{"id": "r1"}]});
+        assert_eq!(scored_memories(&unlinked, None), vec![json!({"id": "r1"})]);
+    }
+
+    #[test]
+    fn test_cluster() {
+        let vectors = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.99, 0.05], vec![0.98, 0.1]];
+        let clusters = cluster(&vectors, 0.9);
+        assert_eq!(clusters, vec![vec![0, 2, 3], vec![1]]);
+    }
+}
@@ -0,0 +1,159 @@
+//! Versioned snapshots of a business's memory layer
+//!
+//! `create_snapshot` writes the business record and its business memories
+//! to a JSON file in `[snapshots] directory` (encrypted like exports when
+//! asked). Customers, interactions, communications, information links and
+//! loyalty rules are not part of a snapshot. Embeddings are left out;
+//! restore regenerates them. `restore_snapshot` rolls the business back to
+//! the file: memories deleted since are recreated under their original IDs,
+//! changed ones replaced (new copy first, old node dropped after) and ones
+//! added since removed.
+
+use crate::memory_diff;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+
+/// Version written into new snapshots; restore refuses newer files
+pub const FORMAT_VERSION: u64 = 1;
+
+/// Business memory types a snapshot covers
+pub const MEMORY_TYPES: [&str; 8] = ["product", "service", "location", "hours", "social", "policy", "event", "information"];
+
+/// Fields regenerated when a memory is recreated from a snapshot
+const REGENERATED: &[&str] = &["id", "label", "business_id", "created_at", "updated_at", "embedding", "embedding_model"];
+
+/// `{business_id}_{timestamp}`, also the snapshot's file stem
+pub fn snapshot_id(business_id: &str, created_at: DateTime<Utc>) -> String {
+    let safe: String = business_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("{}_{}", safe, created_at.format("%Y%m%dT%H%M%S%3fZ"))
+}
+
+/// File of a snapshot inside `directory`; IDs can't name paths outside it
+pub fn path_for(directory: &Path, snapshot_id: &str) -> Result<PathBuf, String> {
+    if snapshot_id.is_empty() || snapshot_id.contains(['/', '\\']) || snapshot_id.contains("..") {
+        return Err(format!("Invalid snapshot_id: {}", snapshot_id));
+    }
+    Ok(directory.join(format!("{}.json", snapshot_id)))
+}
+
+/// Snapshot document for `business` and its memories by memory type
+pub fn document(snapshot_id: &str, business_id: &str, created_at: DateTime<Utc>, business: Value, memories: Map<String, Value>) -> Value {
+    let counts: Map<String, Value> = memories
+        .iter()
+        .map(|(memory_type, items)| (memory_type.clone(), json!(items.as_array().map_or(0, Vec::len))))
+        .collect();
+    json!({
+        "format": "helix-mcp-snapshot",
+        "version": FORMAT_VERSION,
+        "snapshot_id": snapshot_id,
+        "business_id": business_id,
+        "created_at": created_at.to_rfc3339(),
+        "counts": counts,
+        "business": business,
+        "memories": memories
+    })
+}
+
+/// Reject files that aren't snapshots of `business_id` this version can read
+pub fn check(document: &Value, business_id: &str) -> Result<(), String> {
+    if document.get("format").and_then(|v| v.as_str()) != Some("helix-mcp-snapshot") {
+        return Err("Not a snapshot file".to_string());
+    }
+    let version = document.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version == 0 || version > FORMAT_VERSION {
+        return Err(format!("Unsupported snapshot version {} (this server reads up to {})", version, FORMAT_VERSION));
+    }
+    match document.get("business_id").and_then(|v| v.as_str()) {
+        Some(id) if id == business_id => Ok(()),
+        other => Err(format!("Snapshot belongs to business {}, not {}", other.unwrap_or("?"), business_id)),
+    }
+}
+
+/// A snapshot memory as create data: bookkeeping fields dropped
+pub fn creation_data(memory: &Value, id_field: &str) -> Value {
+    let mut data = memory.clone();
+    if let Some(fields) = data.as_object_mut() {
+        for field in REGENERATED.iter().chain(std::iter::once(&id_field)) {
+            fields.remove(*field);
+        }
+    }
+    data
+}
+
+/// What restoring one memory type changes
+#[derive(Debug, Default)]
+pub struct RestorePlan {
+    /// Snapshot memories missing now
+    pub create: Vec<Value>,
+    /// Snapshot memories whose current copy differs
+    pub replace: Vec<Value>,
+    /// IDs of current memories the snapshot doesn't have
+    pub delete: Vec<String>,
+    pub unchanged: usize,
+}
+
+/// Compare snapshot and current memories by entity ID (`id_field`)
+pub fn plan(snapshot: &[Value], current: &[Value], id_field: &str) -> RestorePlan {
+    let id_of = |memory: &Value| memory.get(id_field).and_then(|v| v.as_str()).map(str::to_string);
+    let mut plan = RestorePlan::default();
+    for memory in snapshot {
+        let Some(id) = id_of(memory) else {
+            continue;
+        };
+        match current.iter().find(|c| id_of(c).as_deref() == Some(id.as_str())) {
+            None => plan.create.push(memory.clone()),
+            Some(existing) if !memory_diff::field_diff(memory, existing, id_field).is_empty() => plan.replace.push(memory.clone()),
+            Some(_) => plan.unchanged += 1,
+        }
+    }
+    plan.delete = current
+        .iter()
+        .filter_map(id_of)
+        .filter(|id| !snapshot.iter().any(|m| id_of(m).as_deref() == Some(id.as_str())))
+        .collect();
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_snapshot_document_and_plan() {
+        let at = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let id = snapshot_id("biz/1", at);
+        assert_eq!(id, "biz_1_20260301T120000000Z");
+        assert!(path_for(Path::new("snapshots"), "../etc/passwd").is_err());
+        assert_eq!(path_for(Path::new("snapshots"), &id).unwrap(), Path::new("snapshots").join(format!("{}.json", id)));
+
+        let mut memories = Map::new();
+        memories.insert("product".to_string(), json!([{"product_id": "P1"}]));
+        let doc = document(&id, "biz/1", at, json!({"business_id": "biz/1"}), memories);
+        assert_eq!(doc["counts"]["product"], 1);
+        assert!(check(&doc, "biz/1").is_ok());
+        assert!(check(&doc, "other").is_err());
+        assert!(check(&json!({"format": "helix-mcp-snapshot", "version": 99, "business_id": "biz/1"}), "biz/1").is_err());
+
+        let snapshot = vec![
+            json!({"id": "n1", "product_id": "P1", "price": 4.5}),
+            json!({"id": "n2", "product_id": "P2", "price": 3.0}),
+            json!({"id": "n3", "product_id": "P3", "price": 2.0}),
+        ];
+        let current = vec![
+            json!({"id": "n1", "product_id": "P1", "price": 4.5, "updated_at": 99}),
+            json!({"id": "n9", "product_id": "P2", "price": 0.0}),
+            json!({"id": "n4", "product_id": "P4", "price": 1.0}),
+        ];
+        let plan = plan(&snapshot, &current, "product_id");
+        assert_eq!(plan.unchanged, 1);
+        assert_eq!(plan.replace[0]["product_id"], "P2");
+        assert_eq!(plan.create[0]["product_id"], "P3");
+        assert_eq!(plan.delete, vec!["P4"]);
+        assert_eq!(creation_data(&snapshot[2], "product_id"), json!({"price": 2.0}));
+    }
+}
@@ -0,0 +1,127 @@
+//! Memory store overview for `get_memory_stats`
+//!
+//! Node and vector counts per collection come from `::COUNT` queries, so
+//! the overview costs a few round trips however large the store is. Each
+//! collection's newest node gives its last activity; nodes without a
+//! vector show up as `missing_vectors` (usually a failed embedding step).
+
+use chrono::DateTime;
+use serde_json::{json, Map, Value};
+
+use crate::helix_client::HelixClient;
+
+/// Collections in report order, by their return name in the count queries
+pub const COLLECTIONS: &[&str] = &[
+    "businesses",
+    "customers",
+    "products",
+    "services",
+    "locations",
+    "hours",
+    "social",
+    "policies",
+    "events",
+    "information",
+    "behaviors",
+    "preferences",
+    "desires",
+    "rules",
+    "feedback",
+    "product_interactions",
+    "service_interactions",
+    "location_visits",
+    "communications",
+    "navigation_hubs",
+    "waypoints",
+    "paths",
+];
+
+/// Collections `count_business_memories` counts for one business
+pub const BUSINESS_COLLECTIONS: &[&str] = &["products", "services", "locations", "hours", "social", "policies", "events", "information", "communications", "customers"];
+
+fn count(result: &Value, key: &str) -> Option<u64> {
+    match result.get(key)? {
+        Value::Number(n) => n.as_u64(),
+        Value::Object(o) => o.get("count").and_then(|n| n.as_u64()),
+        _ => None,
+    }
+}
+
+/// Newest `updated_at` / `created_at` (Unix seconds) of a collection's latest node
+fn latest_timestamp(latest: &Value, key: &str) -> Option<i64> {
+    let items = HelixClient::result_items(&json!({ key: latest.get(key).cloned().unwrap_or(Value::Null) }));
+    items
+        .iter()
+        .filter_map(|node| ["updated_at", "created_at"].iter().find_map(|f| node.get(*f).and_then(|v| v.as_i64())))
+        .max()
+}
+
+fn iso(timestamp: i64) -> Value {
+    DateTime::from_timestamp(timestamp, 0).map_or(Value::Null, |t| json!(t.to_rfc3339()))
+}
+
+/// Per-collection counts and last activity, plus store totals
+pub fn overview(nodes: &Value, vectors: &Value, latest: &Value) -> Value {
+    let mut collections = Map::new();
+    let (mut node_total, mut vector_total) = (0, 0);
+    let mut last_activity: Option<i64> = None;
+    for key in COLLECTIONS {
+        let node_count = count(nodes, key).unwrap_or(0);
+        let vector_count = count(vectors, key);
+        let last = latest_timestamp(latest, key);
+        node_total += node_count;
+        vector_total += vector_count.unwrap_or(0);
+        last_activity = last_activity.max(last);
+        let mut entry = json!({"nodes": node_count, "last_activity": last.map_or(Value::Null, iso)});
+        if let Some(vector_count) = vector_count {
+            entry["vectors"] = json!(vector_count);
+            entry["missing_vectors"] = json!(node_count.saturating_sub(vector_count));
+        }
+        collections.insert(key.to_string(), entry);
+    }
+    json!({
+        "totals": {"nodes": node_total, "vectors": vector_total},
+        "last_activity": last_activity.map_or(Value::Null, iso),
+        "collections": collections
+    })
+}
+
+/// One business's row of the per-business breakdown
+pub fn business_row(business: &Value, counts: &Value) -> Value {
+    let memories: Map<String, Value> = BUSINESS_COLLECTIONS
+        .iter()
+        .map(|key| (key.to_string(), json!(count(counts, key).unwrap_or(0))))
+        .collect();
+    let total: u64 = memories.iter().filter(|(k, _)| *k != "customers").filter_map(|(_, n)| n.as_u64()).sum();
+    json!({
+        "business_id": business.get("business_id"),
+        "business_name": business.get("business_name"),
+        "total_memories": total,
+        "counts": memories
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overview_and_business_row() {
+        let nodes = json!({"businesses": 2, "customers": 5, "products": 10, "preferences": 4});
+        let vectors = json!({"products": 9, "preferences": {"count": 4}});
+        let latest = json!({
+            "products": [{"product_id": "P1", "updated_at": 1_700_000_000}],
+            "customers": {"customer_id": "C1", "created_at": 1_700_000_500}
+        });
+        let stats = overview(&nodes, &vectors, &latest);
+        assert_eq!(stats["totals"], json!({"nodes": 21, "vectors": 13}));
+        assert_eq!(stats["collections"]["products"]["missing_vectors"], 1);
+        assert!(stats["collections"]["businesses"].get("vectors").is_none());
+        assert_eq!(stats["collections"]["services"]["last_activity"], Value::Null);
+        assert_eq!(stats["last_activity"], "2023-11-14T22:21:40+00:00");
+
+        let row = business_row(&json!({"business_id": "B1", "business_name": "Cafe"}), &json!({"products": 3, "policies": 1, "customers": 7}));
+        assert_eq!(row["total_memories"], 4);
+        assert_eq!(row["counts"]["customers"], 7);
+    }
+}
@@ -0,0 +1,254 @@
+//! Subject-access reports
+//!
+//! Compiles everything stored about one customer into a human-readable
+//! Markdown report, or a structured JSON document, for data subject access
+//! requests (GDPR Art. 15). Vectors and internal graph fields are left out;
+//! every stored property is shown.
+
+use serde_json::{json, Map, Value};
+
+/// Properties that are storage internals rather than personal data
+const INTERNAL_FIELDS: &[&str] = &["id", "label", "data", "vector", "embedding", "from_node", "to_node"];
+
+/// Formats `export_customer_data` can produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Markdown,
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "markdown" => Some(Format::Markdown),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// One titled group of stored records
+pub struct Section {
+    pub title: &'static str,
+    pub records: Vec<Value>,
+}
+
+pub struct CustomerReport {
+    pub customer_id: String,
+    pub generated_at: i64,
+    pub profile: Option<Value>,
+    pub businesses: Vec<Value>,
+    pub sections: Vec<Section>,
+    pub insights: Value,
+}
+
+impl CustomerReport {
+    pub fn record_count(&self) -> usize {
+        self.profile.iter().count() + self.businesses.len() + self.sections.iter().map(|s| s.records.len()).sum::<usize>()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let generated = chrono::DateTime::from_timestamp(self.generated_at, 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_default();
+        let mut out = format!(
+            "# Personal data report for customer {}\n\nGenerated {}. This report lists every record stored about this customer in the memory layer.\n\n",
+            self.customer_id, generated
+        );
+
+        out.push_str("## Profile\n\n");
+        match &self.profile {
+            Some(profile) => out.push_str(&render_record(profile)),
+            None => out.push_str("No customer profile is stored.\n"),
+        }
+        out.push('\n');
+
+        if !self.businesses.is_empty() {
+            out.push_str("## Businesses you have a relationship with\n\n");
+            for business in &self.businesses {
+                let name = field_text(business, "business_name").or_else(|| field_text(business, "business_id")).unwrap_or_default();
+                out.push_str(&format!("- {}\n", name));
+            }
+            out.push('\n');
+        }
+
+        for section in &self.sections {
+            out.push_str(&format!("## {} ({})\n\n", section.title, section.records.len()));
+            if section.records.is_empty() {
+                out.push_str("None stored.\n\n");
+                continue;
+            }
+            for (i, record) in section.records.iter().enumerate() {
+                out.push_str(&format!("### {} {}\n\n", section.title.trim_end_matches('s'), i + 1));
+                out.push_str(&render_record(record));
+                out.push('\n');
+            }
+        }
+
+        out.push_str("## Derived insights\n\nSummaries computed from the records above.\n\n```json\n");
+        out.push_str(&serde_json::to_string_pretty(&self.insights).unwrap_or_default());
+        out.push_str("\n```\n");
+        out
+    }
+
+    /// The report body in `format`; for JSON also the document itself, which
+    /// goes inline when the report is returned unencrypted
+    pub fn render(&self, format: Format) -> (String, Option<Value>) {
+        match format {
+            Format::Markdown => (self.to_markdown(), None),
+            Format::Csv => (self.to_csv(), None),
+            Format::Json => {
+                let document = self.to_json();
+                (serde_json::to_string_pretty(&document).unwrap_or_default(), Some(document))
+            }
+        }
+    }
+
+    /// The report as one JSON document, records grouped by section
+    pub fn to_json(&self) -> Value {
+        let mut records = Map::new();
+        let mut counts = Map::new();
+        for section in &self.sections {
+            let key = section.title.to_lowercase().replace(' ', "_");
+            counts.insert(key.clone(), json!(section.records.len()));
+            records.insert(key, Value::Array(section.records.iter().map(stored_fields).collect()));
+        }
+        json!({
+            "customer_id": self.customer_id,
+            "generated_at": self.generated_at,
+            "profile": self.profile.as_ref().map(stored_fields),
+            "businesses": self.businesses.iter().map(stored_fields).collect::<Vec<_>>(),
+            "records": records,
+            "record_counts": counts,
+            "derived_insights": self.insights
+        })
+    }
+    /// Every record as one CSV row with its section (derived insights left out)
+    pub fn to_csv(&self) -> String {
+        let tagged = |section: &str, record: &Value| {
+            let mut row = stored_fields(record);
+            if let Some(map) = row.as_object_mut() {
+                map.insert("section".to_string(), json!(section));
+            }
+            row
+        };
+        let mut rows: Vec<Value> = self.profile.iter().map(|p| tagged("profile", p)).collect();
+        rows.extend(self.businesses.iter().map(|b| tagged("businesses", b)));
+        for section in &self.sections {
+            let key = section.title.to_lowercase().replace(' ', "_");
+            rows.extend(section.records.iter().map(|r| tagged(&key, r)));
+        }
+        crate::export::to_csv(&rows, &["section"])
+    }
+}
+
+/// A record's stored properties without storage internals
+fn stored_fields(record: &Value) -> Value {
+    match record.as_object() {
+        Some(map) => Value::Object(map.iter().filter(|(k, _)| !INTERNAL_FIELDS.contains(&k.as_str())).map(|(k, v)| (k.clone(), v.clone())).collect()),
+        None => record.clone(),
+    }
+}
+
+fn field_text(record: &Value, field: &str) -> Option<String> {
+    record.get(field).and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(String::from)
+}
+
+/// Bullet list of a record's non-empty properties; `*_at` timestamps shown as dates
+fn render_record(record: &Value) -> String {
+    let Some(map) = record.as_object() else {
+        return format!("- {}\n", record);
+    };
+    let mut out = String::new();
+    for (key, value) in map {
+        if INTERNAL_FIELDS.contains(&key.as_str()) {
+            continue;
+        }
+        let text = match value {
+            Value::Null => continue,
+            Value::String(s) if s.is_empty() || s == "[]" || s == "{}" => continue,
+            Value::String(s) => s.clone(),
+            Value::Number(n) if key.ends_with("_at") || key == "timestamp" => n
+                .as_i64()
+                .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+                .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_else(|| n.to_string()),
+            Value::Array(items) if items.is_empty() => continue,
+            other => other.to_string(),
+        };
+        out.push_str(&format!("- **{}**: {}\n", key.replace('_', " "), text.replace('\n', " ")));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_report() {
+        let report = CustomerReport {
+            customer_id: "CUST_1".to_string(),
+            generated_at: 1_700_000_000,
+            profile: Some(json!({"id": "node-1", "customer_id": "CUST_1", "email": "a@example.com", "phone": ""})),
+            businesses: vec![json!({"business_id": "BIZ_1", "business_name": "Corner Cafe"})],
+            sections: vec![
+                Section { title: "Preferences", records: vec![json!({"preference_id": "P1", "created_at": 1_700_000_000, "data": [0.1]})] },
+                Section { title: "Feedback", records: vec![] },
+            ],
+            insights: json!({"total_feedback": 0}),
+        };
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("- **email**: a@example.com"));
+        assert!(!markdown.contains("phone"));
+        assert!(!markdown.contains("node-1"));
+        assert!(markdown.contains("- Corner Cafe"));
+        assert!(markdown.contains("### Preference 1"));
+        assert!(markdown.contains("- **created at**: 2023-11-14 22:13 UTC"));
+        assert!(markdown.contains("## Feedback (0)\n\nNone stored."));
+        assert_eq!(report.record_count(), 3);
+
+        let document = report.to_json();
+        assert_eq!(document["profile"], json!({"customer_id": "CUST_1", "email": "a@example.com", "phone": ""}));
+        assert_eq!(document["records"]["preferences"], json!([{"preference_id": "P1", "created_at": 1_700_000_000}]));
+        assert_eq!(document["record_counts"]["feedback"], 0);
+
+        let csv = report.to_csv();
+        assert_eq!(csv.lines().next(), Some("section,customer_id,email,phone,business_id,business_name,created_at,preference_id"));
+        assert!(csv.contains("\r\npreferences,,,,,,1700000000,P1\r\n"));
+    }
+
+    #[test]
+    fn test_json_report() {
+        let report = CustomerReport {
+            customer_id: "CUST_1".to_string(),
+            generated_at: 1_700_000_000,
+            profile: None,
+            businesses: vec![json!({"id": "node-2", "business_id": "BIZ_1"})],
+            sections: vec![Section {
+                title: "Location visits",
+                records: vec![json!({"visit_id": "V1", "location_id": "LOC_1", "vector": [0.2], "from_node": "n1"})],
+            }],
+            insights: json!({"interactions": {"total": 0}}),
+        };
+        assert_eq!(Format::parse("json"), Some(Format::Json));
+        assert_eq!(Format::parse("pdf"), None);
+
+        let (body, document) = report.render(Format::Json);
+        let document = document.unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&body).unwrap(), document);
+        // Multi-word section titles become snake_case keys
+        assert_eq!(document["records"]["location_visits"], json!([{"visit_id": "V1", "location_id": "LOC_1"}]));
+        assert_eq!(document["record_counts"], json!({"location_visits": 1}));
+        assert_eq!(document["profile"], Value::Null);
+        assert_eq!(document["businesses"], json!([{"business_id": "BIZ_1"}]));
+        assert_eq!(document["derived_insights"]["interactions"]["total"], 0);
+
+        let (markdown, document) = report.render(Format::Markdown);
+        assert!(document.is_none());
+        assert!(markdown.contains("## Location visits (1)"));
+        assert!(markdown.contains("No customer profile is stored."));
+    }
+}
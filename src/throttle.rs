@@ -0,0 +1,83 @@
+//! Rate budgets for background embedding jobs
+//!
+//! Backfill and re-embed jobs share the embedding provider with live
+//! traffic, so they run under a requests-per-minute budget, a concurrency
+//! cap and an optional off-peak window (UTC hours).
+
+use std::time::Duration;
+use tokio::sync::{Mutex, Semaphore, SemaphorePermit};
+use tokio::time::Instant;
+
+/// Requests-per-minute and concurrency budget
+pub struct RateBudget {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+    permits: Semaphore,
+}
+
+impl RateBudget {
+    /// `requests_per_minute` of 0 disables pacing; concurrency is at least 1
+    pub fn new(requests_per_minute: u32, max_concurrency: usize) -> Self {
+        RateBudget {
+            interval: request_interval(requests_per_minute),
+            next_slot: Mutex::new(Instant::now()),
+            permits: Semaphore::new(max_concurrency.max(1)),
+        }
+    }
+
+    /// Wait for a concurrency permit and the next free rate slot
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        let permit = self.permits.acquire().await.expect("rate budget semaphore is never closed");
+
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+
+        permit
+    }
+}
+
+/// Spacing between requests for a per-minute budget
+pub fn request_interval(requests_per_minute: u32) -> Duration {
+    if requests_per_minute == 0 {
+        Duration::ZERO
+    } else {
+        Duration::from_secs(60) / requests_per_minute
+    }
+}
+
+/// Whether `hour` (0-23) falls in the window `[start, end)`; wraps past midnight
+pub fn in_window(hour: u32, start: u32, end: u32) -> bool {
+    if start == end {
+        true
+    } else if start < end {
+        hour >= start && hour < end
+    } else {
+        hour >= start || hour < end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_interval() {
+        assert_eq!(request_interval(60), Duration::from_secs(1));
+        assert_eq!(request_interval(120), Duration::from_millis(500));
+        assert_eq!(request_interval(0), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_in_window_wraps_midnight() {
+        assert!(in_window(23, 22, 6));
+        assert!(in_window(3, 22, 6));
+        assert!(!in_window(12, 22, 6));
+        assert!(in_window(9, 9, 17));
+        assert!(!in_window(17, 9, 17));
+    }
+}
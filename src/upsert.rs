@@ -0,0 +1,86 @@
+//! Upserting business memories keyed by an external ID
+//!
+//! Sync jobs (Shopify, POS, CMS exports) identify records by their key in
+//! the source system. The memory ID is derived from business, memory type
+//! and that key, so replaying a sync finds the memory it wrote last time:
+//! a missing memory is created, a changed text is re-embedded and an
+//! unchanged one is left alone.
+
+use serde_json::Value;
+
+/// What an upsert does with the memory stored under the derived ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Create,
+    Update,
+    Unchanged,
+}
+
+impl Action {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Action::Create => "created",
+            Action::Update => "updated",
+            Action::Unchanged => "unchanged",
+        }
+    }
+}
+
+/// Stable memory ID for a record keyed by `external_id` in a source system
+pub fn memory_id(memory_type: &str, business_id: &str, external_id: &str) -> String {
+    format!("{}_EXT_{}_{}", memory_type.to_uppercase(), business_id, external_id)
+}
+
+/// The looked-up memory, if it belongs to `business_id`; another business's
+/// memory under the same ID is treated as missing
+pub fn existing(memories: Vec<Value>, business_id: &str) -> Option<Value> {
+    memories
+        .into_iter()
+        .find(|m| m.get("business_id").and_then(|v| v.as_str()) == Some(business_id))
+}
+
+/// Create when nothing is stored, update when the text changed
+pub fn action(existing: Option<&Value>, text_description: &str) -> Action {
+    match existing {
+        None => Action::Create,
+        Some(memory) if memory.get("text_description").and_then(|v| v.as_str()) == Some(text_description) => Action::Unchanged,
+        Some(_) => Action::Update,
+    }
+}
+
+/// Fields of `data` that differ from the stored memory; the text update
+/// can't change them, so they are reported for sync jobs to see drift
+pub fn stale_fields(data: &Value, existing: &Value) -> Vec<String> {
+    data.as_object()
+        .map(|fields| fields.iter().filter(|(k, v)| existing.get(k.as_str()) != Some(*v)).map(|(k, _)| k.clone()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_memory_id_is_the_conflict_key() {
+        assert_eq!(memory_id("product", "B1", "SKU-9"), "PRODUCT_EXT_B1_SKU-9");
+        assert_eq!(memory_id("product", "B1", "SKU-9"), memory_id("product", "B1", "SKU-9"));
+        assert_ne!(memory_id("product", "B1", "SKU-9"), memory_id("product", "B2", "SKU-9"));
+        assert_ne!(memory_id("product", "B1", "SKU-9"), memory_id("service", "B1", "SKU-9"));
+    }
+
+    #[test]
+    fn test_match_or_create() {
+        let stored = vec![json!({"business_id": "B1", "text_description": "Oat latte", "price": 4.5})];
+        assert!(existing(stored.clone(), "B2").is_none());
+        let memory = existing(stored, "B1").unwrap();
+
+        assert_eq!(action(None, "Oat latte"), Action::Create);
+        assert_eq!(action(Some(&memory), "Oat latte"), Action::Unchanged);
+        assert_eq!(action(Some(&memory), "Oat milk latte"), Action::Update);
+        assert_eq!(Action::Update.as_str(), "updated");
+
+        assert_eq!(stale_fields(&json!({"price": 5.0, "text_description": "Oat latte"}), &memory), ["price"]);
+        assert!(stale_fields(&json!(null), &memory).is_empty());
+    }
+}
@@ -0,0 +1,224 @@
+//! Tool argument validation
+//!
+//! Checks enum-like string arguments (memory_type, interaction_type,
+//! path_type, sentiment, ...) against the values the tools and schema
+//! understand, and numeric arguments against their ranges (ratings 1-5,
+//! latitude/longitude bounds, bearings 0-360) and ID lists against repeats,
+//! before anything reaches HelixDB. Fields inside `data` and `updates`
+//! objects are checked too.
+//! Every problem is reported at once, with the valid values, so the caller
+//! can fix the call in one go.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+const BUSINESS_TYPES: &[&str] = &[
+    "product", "service", "location", "hours", "social", "policy", "event", "information",
+    "products", "services", "locations", "policies", "events",
+];
+const BUSINESS_QUERY_TYPES: &[&str] = &[
+    "products", "services", "locations", "hours", "social", "policies", "events", "information", "all",
+    "product", "service", "location", "policy", "event",
+];
+const CUSTOMER_TYPES: &[&str] = &[
+    "behavior", "preference", "desire", "rule", "feedback",
+    "behaviors", "preferences", "desires", "rules",
+];
+const CUSTOMER_UPDATE_TYPES: &[&str] = &[
+    "behavior", "preference", "desire", "rule", "feedback", "communication",
+    "behaviors", "preferences", "desires", "rules", "communications",
+];
+const CUSTOMER_QUERY_TYPES: &[&str] = &[
+    "behaviors", "preferences", "desires", "rules", "feedback", "all",
+    "behavior", "preference", "desire", "rule",
+];
+
+enum Rule {
+    OneOf(&'static [&'static str]),
+    Range(f64, f64),
+    /// A list without repeated entries
+    Distinct,
+}
+
+struct FieldRule {
+    /// Tools the rule applies to; empty for every tool
+    tools: &'static [&'static str],
+    field: &'static str,
+    rule: Rule,
+}
+
+const RULES: &[FieldRule] = &[
+    FieldRule { tools: &["query_business_memory"], field: "memory_type", rule: Rule::OneOf(BUSINESS_QUERY_TYPES) },
+    FieldRule {
+        tools: &["create_business_memory", "update_business_memory", "upsert_business_memory"],
+        field: "memory_type",
+        rule: Rule::OneOf(BUSINESS_TYPES),
+    },
+    FieldRule { tools: &["query_customer_memory"], field: "memory_type", rule: Rule::OneOf(CUSTOMER_QUERY_TYPES) },
+    FieldRule { tools: &["create_customer_memory"], field: "memory_type", rule: Rule::OneOf(CUSTOMER_TYPES) },
+    FieldRule { tools: &["update_customer_memory"], field: "memory_type", rule: Rule::OneOf(CUSTOMER_UPDATE_TYPES) },
+    FieldRule {
+        tools: &["create_customer_product_interaction"],
+        field: "interaction_type",
+        rule: Rule::OneOf(&["liked", "disliked", "purchased", "viewed", "favorited", "reviewed"]),
+    },
+    FieldRule {
+        tools: &["create_customer_service_interaction"],
+        field: "interaction_type",
+        rule: Rule::OneOf(&["booked", "completed", "reviewed", "canceled"]),
+    },
+    FieldRule {
+        tools: &["query_customer_interactions"],
+        field: "interaction_type",
+        rule: Rule::OneOf(&["product", "service", "visit", "all"]),
+    },
+    FieldRule { tools: &["analyze_interactions"], field: "interaction_type", rule: Rule::OneOf(&["product", "service", "all"]) },
+    FieldRule { tools: &["update_interaction"], field: "interaction_type", rule: Rule::OneOf(&["product", "service"]) },
+    FieldRule { tools: &["update_interaction"], field: "resolution_status", rule: Rule::OneOf(&["resolved", "pending", "escalated"]) },
+    FieldRule { tools: &["update_navigation"], field: "navigation_type", rule: Rule::OneOf(&["hub", "waypoint", "path"]) },
+    FieldRule {
+        tools: &["create_direction_path"],
+        field: "path_type",
+        rule: Rule::OneOf(&["primary", "alternative", "accessible", "emergency"]),
+    },
+    FieldRule {
+        tools: &["create_direction_path", "get_route", "compute_bearing", "import_navigation_geojson"],
+        field: "transport_mode",
+        rule: Rule::OneOf(&["walking", "driving", "cycling", "wheelchair", "public_transport"]),
+    },
+    FieldRule {
+        tools: &["delete_memory"],
+        field: "delete_strategy",
+        rule: Rule::OneOf(&["node_only", "with_embedding", "cascade", "complete"]),
+    },
+    FieldRule {
+        tools: &["find_customer_insights"],
+        field: "relationship_type",
+        rule: Rule::OneOf(&["liked", "disliked", "used_service", "visited_location", "all"]),
+    },
+    FieldRule { tools: &["analyze_interactions"], field: "bucket", rule: Rule::OneOf(&["day", "week", "month"]) },
+    FieldRule { tools: &["submit_job"], field: "job_type", rule: Rule::OneOf(crate::jobs::JOB_TYPES) },
+    FieldRule { tools: &["import_memories"], field: "source", rule: Rule::OneOf(&["mem0", "zep", "langchain"]) },
+    FieldRule { tools: &["verify_embeddings"], field: "min_similarity", rule: Rule::Range(0.0, 1.0) },
+    FieldRule { tools: &["export_customer_data"], field: "format", rule: Rule::OneOf(&["markdown", "json", "csv"]) },
+    FieldRule { tools: &["export_graph_viz"], field: "format", rule: Rule::OneOf(&["dot", "cytoscape"]) },
+    FieldRule { tools: &["merge_memories"], field: "memory_ids", rule: Rule::Distinct },
+    FieldRule { tools: &[], field: "sentiment", rule: Rule::OneOf(&["positive", "negative", "neutral"]) },
+    FieldRule { tools: &[], field: "rating", rule: Rule::Range(1.0, 5.0) },
+    FieldRule { tools: &[], field: "satisfaction_rating", rule: Rule::Range(1.0, 5.0) },
+    FieldRule { tools: &[], field: "importance", rule: Rule::Range(0.0, 5.0) },
+    FieldRule { tools: &[], field: "latitude", rule: Rule::Range(-90.0, 90.0) },
+    FieldRule { tools: &[], field: "longitude", rule: Rule::Range(-180.0, 180.0) },
+    FieldRule { tools: &[], field: "compass_bearing", rule: Rule::Range(0.0, 360.0) },
+    FieldRule { tools: &[], field: "starting_compass_bearing", rule: Rule::Range(0.0, 360.0) },
+    FieldRule { tools: &[], field: "ending_compass_bearing", rule: Rule::Range(0.0, 360.0) },
+];
+
+/// Objects whose fields are memory fields and get the same checks
+const NESTED: &[&str] = &["data", "updates"];
+
+#[derive(Debug, Serialize)]
+pub struct Violation {
+    /// Argument path, e.g. `data.rating`
+    pub field: String,
+    pub value: Value,
+    pub problem: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_values: Option<&'static [&'static str]>,
+}
+
+fn check(rule: &Rule, path: String, value: &Value) -> Option<Violation> {
+    // Explicit nulls mean "not given"
+    if value.is_null() {
+        return None;
+    }
+    match rule {
+        Rule::OneOf(allowed) => {
+            let valid = value.as_str().is_some_and(|v| allowed.contains(&v));
+            (!valid).then(|| Violation {
+                problem: format!("must be one of: {}", allowed.join(", ")),
+                field: path,
+                value: value.clone(),
+                valid_values: Some(allowed),
+            })
+        }
+        Rule::Range(min, max) => {
+            let problem = match value.as_f64() {
+                Some(n) if (*min..=*max).contains(&n) => return None,
+                Some(_) => format!("must be between {} and {}", min, max),
+                None => format!("must be a number between {} and {}", min, max),
+            };
+            Some(Violation { field: path, value: value.clone(), problem, valid_values: None })
+        }
+        Rule::Distinct => {
+            let items = value.as_array()?;
+            let repeated = items.iter().enumerate().find(|(i, item)| items[..*i].contains(item))?.1;
+            Some(Violation {
+                problem: format!("must not repeat an entry ({} is listed more than once)", repeated),
+                field: path,
+                value: value.clone(),
+                valid_values: None,
+            })
+        }
+    }
+}
+
+/// Every rule violation in `arguments` for `tool`
+pub fn validate(tool: &str, arguments: &Map<String, Value>) -> Vec<Violation> {
+    let applicable = RULES.iter().filter(|r| r.tools.is_empty() || r.tools.contains(&tool));
+    let mut violations = Vec::new();
+    for rule in applicable {
+        if let Some(value) = arguments.get(rule.field) {
+            violations.extend(check(&rule.rule, rule.field.to_string(), value));
+        }
+        // Only tool-independent rules describe memory fields
+        if !rule.tools.is_empty() {
+            continue;
+        }
+        for nested in NESTED {
+            if let Some(value) = arguments.get(*nested).and_then(|o| o.get(rule.field)) {
+                violations.extend(check(&rule.rule, format!("{}.{}", nested, rule.field), value));
+            }
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate() {
+        let ok = json!({"customer_id": "C1", "memory_type": "preferences", "data": {"sentiment": "positive", "rating": 4}});
+        assert!(validate("create_customer_memory", ok.as_object().unwrap()).is_empty());
+
+        let bad = json!({
+            "memory_type": "wishlist",
+            "rating": 7,
+            "data": {"sentiment": "angry", "latitude": "north"},
+            "updates": {"compass_bearing": null}
+        });
+        let violations = validate("create_customer_memory", bad.as_object().unwrap());
+        let fields: Vec<&str> = violations.iter().map(|v| v.field.as_str()).collect();
+        assert_eq!(fields, vec!["memory_type", "data.sentiment", "rating", "data.latitude"]);
+        assert!(violations[0].valid_values.unwrap().contains(&"preference"));
+        assert_eq!(violations[2].problem, "must be between 1 and 5");
+
+        // Tool-specific rules don't leak into other tools
+        let interaction = json!({"interaction_type": "booked"});
+        assert!(validate("create_customer_service_interaction", interaction.as_object().unwrap()).is_empty());
+        assert_eq!(validate("create_customer_product_interaction", interaction.as_object().unwrap()).len(), 1);
+
+        // A merge can't list the survivor (or any memory) twice
+        let merge = json!({"memory_type": "product", "memory_ids": ["p1", "p2", "p1"]});
+        let violations = validate("merge_memories", merge.as_object().unwrap());
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].field, "memory_ids");
+        assert!(validate("merge_memories", json!({"memory_ids": ["p1", "p2"]}).as_object().unwrap()).is_empty());
+
+        assert!(validate("export_customer_data", json!({"format": "csv"}).as_object().unwrap()).is_empty());
+        assert_eq!(validate("export_customer_data", json!({"format": "xlsx"}).as_object().unwrap()).len(), 1);
+    }
+}
@@ -0,0 +1,177 @@
+//! Outbound webhook notifications
+//!
+//! Successful memory-changing tool calls become `<memory_type>.<action>`
+//! events (`product.created`, `preference.updated`, `interaction.deleted`)
+//! that are POSTed as JSON to the configured URL when they match the event
+//! filters. Bodies are signed like inbound `hmac-sha256` events
+//! (`X-Signature: sha256=<hex>`). Delivery runs in the background with
+//! exponential backoff; events still failing after the last retry are
+//! appended to a JSONL dead-letter log.
+
+use crate::config::WebhooksConfig;
+use crate::signature;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    pub id: String,
+    /// `<memory_type>.<action>`
+    pub event: String,
+    pub timestamp: i64,
+    pub tool: String,
+    pub memory_type: String,
+    pub action: String,
+    pub business_id: Option<String>,
+    pub customer_id: Option<String>,
+    /// Tool result
+    pub data: Value,
+}
+
+/// What a successful call of `tool` did, judged from its result
+pub fn action(tool: &str, result: &Value) -> Option<&'static str> {
+    // Previews change nothing
+    if result.get("dry_run").and_then(|v| v.as_bool()) == Some(true) {
+        return None;
+    }
+    match tool {
+        "upsert_business_memory" => match result.get("action").and_then(|v| v.as_str()) {
+            Some("created") => Some("created"),
+            Some("updated") => Some("updated"),
+            _ => None,
+        },
+        "delete_memory" | "delete_memories_by_filter" => Some("deleted"),
+        "update_business_memory" | "update_customer_memory" | "update_interaction" | "update_navigation"
+        | "update_business_hours_exceptions" | "close_past_events" | "merge_memories" | "pin_memory" => Some("updated"),
+        "create_business_memory" | "create_customer_memory" | "store_conversation_summary"
+        | "create_customer_product_interaction" | "create_customer_service_interaction"
+        | "create_navigation_hub" | "create_navigation_waypoint" | "create_direction_path"
+        | "create_navigation_floor" | "create_floor_connector" | "import_navigation_geojson"
+        | "supersede_policy" | "import_memories" => Some("created"),
+        _ => None,
+    }
+}
+
+/// Memory type for tools that don't take a `memory_type` argument
+pub fn tool_memory_type(tool: &str) -> &'static str {
+    match tool {
+        "store_conversation_summary" => "communication",
+        "create_customer_product_interaction" | "create_customer_service_interaction" | "update_interaction" => "interaction",
+        "update_business_hours_exceptions" => "hours",
+        "close_past_events" => "event",
+        "supersede_policy" => "policy",
+        t if t.contains("navigation") || t.contains("floor") || t == "create_direction_path" => "navigation",
+        _ => "memory",
+    }
+}
+
+/// Whether `event` matches any filter (`*` matches either part); no filters match everything
+pub fn matches(filters: &[String], event: &str) -> bool {
+    let (memory_type, action) = event.split_once('.').unwrap_or((event, ""));
+    filters.is_empty()
+        || filters.iter().any(|filter| {
+            let (f_type, f_action) = filter.split_once('.').unwrap_or((filter.as_str(), "*"));
+            (f_type == "*" || f_type == memory_type) && (f_action == "*" || f_action == action)
+        })
+}
+
+pub struct Webhooks {
+    config: WebhooksConfig,
+    client: reqwest::Client,
+}
+
+impl Webhooks {
+    pub fn new(config: WebhooksConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .build()
+            .unwrap_or_default();
+        Webhooks { config, client }
+    }
+
+    pub fn wants(&self, event: &str) -> bool {
+        self.config.enabled && !self.config.url.is_empty() && matches(&self.config.events, event)
+    }
+
+    /// Deliver in the background; never blocks the tool call
+    pub fn dispatch(self: &Arc<Self>, event: WebhookEvent) {
+        let webhooks = self.clone();
+        tokio::spawn(async move { webhooks.deliver(event).await });
+    }
+
+    async fn deliver(&self, event: WebhookEvent) {
+        let body = serde_json::to_vec(&event).unwrap_or_default();
+        let mut backoff = Duration::from_millis(self.config.initial_backoff_ms);
+        let mut last_error = String::new();
+
+        for attempt in 0..=self.config.max_retries {
+            if attempt > 0 {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            let mut request = self.client
+                .post(&self.config.url)
+                .header("content-type", "application/json")
+                .header("x-webhook-id", &event.id)
+                .header("x-webhook-event", &event.event)
+                .header("x-webhook-timestamp", event.timestamp.to_string())
+                .body(body.clone());
+            if !self.config.secret.is_empty() {
+                request = request.header("x-signature", format!("sha256={}", signature::sign_hex(&self.config.secret, &body)));
+            }
+            match request.send().await {
+                Ok(response) if response.status().is_success() => {
+                    tracing::debug!("Webhook {} delivered ({} attempts)", event.id, attempt + 1);
+                    return;
+                }
+                Ok(response) => last_error = format!("HTTP {}", response.status()),
+                Err(e) => last_error = e.to_string(),
+            }
+            tracing::warn!("Webhook {} attempt {} failed: {}", event.id, attempt + 1, last_error);
+        }
+
+        self.dead_letter(&event, self.config.max_retries + 1, &last_error);
+    }
+
+    fn dead_letter(&self, event: &WebhookEvent, attempts: u32, error: &str) {
+        tracing::error!("Webhook {} ({}) dead-lettered after {} attempts: {}", event.id, event.event, attempts, error);
+        if self.config.dead_letter_path.is_empty() {
+            return;
+        }
+        let line = serde_json::json!({ "attempts": attempts, "error": error, "event": event }).to_string();
+        let written = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.config.dead_letter_path)
+            .and_then(|mut file| writeln!(file, "{}", line));
+        if let Err(e) = written {
+            tracing::warn!("Failed to append webhook dead letter {}: {}", self.config.dead_letter_path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_event_filters() {
+        let filters = vec!["product.*".to_string(), "*.deleted".to_string(), "preference.updated".to_string()];
+        assert!(matches(&filters, "product.created"));
+        assert!(matches(&filters, "event.deleted"));
+        assert!(matches(&filters, "preference.updated"));
+        assert!(!matches(&filters, "preference.created"));
+        assert!(matches(&[], "anything.created"));
+        assert!(matches(&["navigation".to_string()], "navigation.updated"));
+
+        assert_eq!(action("delete_memories_by_filter", &json!({"dry_run": true})), None);
+        assert_eq!(action("upsert_business_memory", &json!({"action": "unchanged"})), None);
+        assert_eq!(action("create_business_memory", &json!({"success": true})), Some("created"));
+        assert_eq!(action("search_semantic", &json!({})), None);
+    }
+}
@@ -0,0 +1,101 @@
+//! Ephemeral per-session working memory
+//!
+//! Key-value notes agents can stash between tool calls without creating
+//! permanent memory nodes. Notes live only in this process and expire with
+//! their session after an idle TTL.
+
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+struct SessionNotes {
+    notes: Map<String, Value>,
+    last_access: Instant,
+}
+
+/// Notes for all active sessions
+pub struct WorkingMemory {
+    sessions: Mutex<HashMap<String, SessionNotes>>,
+    ttl: Duration,
+    max_notes: usize,
+}
+
+impl WorkingMemory {
+    pub fn new(ttl: Duration, max_notes: usize) -> Self {
+        WorkingMemory {
+            sessions: Mutex::new(HashMap::new()),
+            ttl,
+            max_notes,
+        }
+    }
+
+    /// Store `value` under `key`, replacing any previous note
+    pub fn remember(&self, session_id: &str, key: &str, value: Value) -> Result<usize, String> {
+        let mut sessions = self.lock_live();
+        let session = sessions.entry(session_id.to_string()).or_insert_with(|| SessionNotes {
+            notes: Map::new(),
+            last_access: Instant::now(),
+        });
+        session.last_access = Instant::now();
+
+        if !session.notes.contains_key(key) && session.notes.len() >= self.max_notes {
+            return Err(format!("Session note limit reached ({} notes) - clear notes with recall_session(clear: true)", self.max_notes));
+        }
+        session.notes.insert(key.to_string(), value);
+        Ok(session.notes.len())
+    }
+
+    /// Read one note, or all notes when `key` is `None`; `clear` removes what was read
+    pub fn recall(&self, session_id: &str, key: Option<&str>, clear: bool) -> Value {
+        let mut sessions = self.lock_live();
+        let Some(session) = sessions.get_mut(session_id) else {
+            return match key {
+                Some(_) => Value::Null,
+                None => json!({}),
+            };
+        };
+        session.last_access = Instant::now();
+
+        match key {
+            Some(key) if clear => session.notes.remove(key).unwrap_or(Value::Null),
+            Some(key) => session.notes.get(key).cloned().unwrap_or(Value::Null),
+            None if clear => Value::Object(std::mem::take(&mut session.notes)),
+            None => Value::Object(session.notes.clone()),
+        }
+    }
+
+    /// Lock the store, dropping sessions idle longer than the TTL
+    fn lock_live(&self) -> std::sync::MutexGuard<'_, HashMap<String, SessionNotes>> {
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, s| s.last_access.elapsed() < self.ttl);
+        sessions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notes_are_session_scoped() {
+        let memory = WorkingMemory::new(Duration::from_secs(60), 2);
+        memory.remember("a", "budget", json!(500)).unwrap();
+        memory.remember("a", "style", json!("minimal")).unwrap();
+        assert!(memory.remember("a", "third", json!(1)).is_err());
+
+        assert_eq!(memory.recall("a", Some("budget"), false), json!(500));
+        assert_eq!(memory.recall("b", Some("budget"), false), Value::Null);
+
+        let all = memory.recall("a", None, true);
+        assert_eq!(all.as_object().unwrap().len(), 2);
+        assert_eq!(memory.recall("a", None, false), json!({}));
+    }
+
+    #[test]
+    fn test_idle_sessions_expire() {
+        let memory = WorkingMemory::new(Duration::ZERO, 10);
+        memory.remember("a", "k", json!(1)).unwrap();
+        assert_eq!(memory.recall("a", Some("k"), false), Value::Null);
+    }
+}